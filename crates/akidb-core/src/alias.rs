@@ -0,0 +1,43 @@
+//! Collection aliases: stable names that point at a physical collection.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{CollectionId, DatabaseId};
+
+/// A name clients use in place of a collection ID.
+///
+/// Operators repoint an alias from one collection to another in a single
+/// write, so clients querying the alias move to a freshly built collection
+/// (blue/green reindexing) without a deploy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionAlias {
+    /// Database the alias and its collection belong to.
+    pub database_id: DatabaseId,
+    /// Alias name, unique within the database.
+    pub name: String,
+    /// Collection the alias currently resolves to.
+    pub collection_id: CollectionId,
+    pub created_at: DateTime<Utc>,
+    /// When the alias was last pointed at a collection.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CollectionAlias {
+    /// Creates an alias pointing at `collection_id`.
+    #[must_use]
+    pub fn new(
+        database_id: DatabaseId,
+        name: impl Into<String>,
+        collection_id: CollectionId,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            database_id,
+            name: name.into(),
+            collection_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
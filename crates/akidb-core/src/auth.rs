@@ -5,7 +5,7 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-use crate::{ApiKeyId, TenantId, UserId};
+use crate::{ApiKeyId, CollectionId, TenantId, UserId};
 
 /// API key descriptor with permissions and metadata.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -33,6 +33,10 @@ pub struct ApiKeyDescriptor {
 
     /// User who created this API key (None = system-created).
     pub created_by: Option<UserId>,
+
+    /// Collections this API key may access (None = every collection of the tenant).
+    #[serde(default)]
+    pub allowed_collections: Option<Vec<CollectionId>>,
 }
 
 impl ApiKeyDescriptor {
@@ -54,9 +58,17 @@ impl ApiKeyDescriptor {
             expires_at,
             last_used_at: None,
             created_by,
+            allowed_collections: None,
         }
     }
 
+    /// Restricts this API key to the given collections.
+    #[must_use]
+    pub fn with_allowed_collections(mut self, collections: Vec<CollectionId>) -> Self {
+        self.allowed_collections = Some(collections);
+        self
+    }
+
     /// Checks if this API key has expired.
     #[must_use]
     pub fn is_expired(&self) -> bool {
@@ -72,6 +84,14 @@ impl ApiKeyDescriptor {
     pub fn has_permission(&self, permission: &str) -> bool {
         self.permissions.iter().any(|p| p == permission)
     }
+
+    /// Checks if this API key may access a collection.
+    #[must_use]
+    pub fn can_access_collection(&self, collection_id: CollectionId) -> bool {
+        self.allowed_collections
+            .as_ref()
+            .map_or(true, |allowed| allowed.contains(&collection_id))
+    }
 }
 
 /// Request to create a new API key.
@@ -86,6 +106,10 @@ pub struct CreateApiKeyRequest {
     /// Optional expiration time (None = never expires).
     #[serde(default)]
     pub expires_at: Option<DateTime<Utc>>,
+
+    /// Optional collection allowlist (None = every collection of the tenant).
+    #[serde(default)]
+    pub allowed_collections: Option<Vec<CollectionId>>,
 }
 
 /// Response containing the newly created API key.
@@ -177,6 +201,18 @@ mod tests {
         assert!(!descriptor.is_expired());
     }
 
+    #[test]
+    fn test_api_key_collection_allowlist() {
+        let descriptor =
+            ApiKeyDescriptor::new(TenantId::new(), "test-key".to_string(), vec![], None, None);
+        let allowed = CollectionId::new();
+        assert!(descriptor.can_access_collection(allowed));
+
+        let descriptor = descriptor.with_allowed_collections(vec![allowed]);
+        assert!(descriptor.can_access_collection(allowed));
+        assert!(!descriptor.can_access_collection(CollectionId::new()));
+    }
+
     #[test]
     fn test_api_key_expired() {
         let descriptor = ApiKeyDescriptor::new(
@@ -47,6 +47,94 @@ impl FromStr for DistanceMetric {
     }
 }
 
+/// Storage precision for the vectors held in a collection's index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Quantization {
+    /// Full-precision f32 vectors
+    #[default]
+    None,
+    /// One signed byte per component with a per-vector scale
+    Int8,
+    /// IEEE 754 half-precision floats
+    Fp16,
+}
+
+impl Quantization {
+    /// Returns the canonical lowercase string stored in SQLite.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Int8 => "int8",
+            Self::Fp16 => "fp16",
+        }
+    }
+}
+
+impl FromStr for Quantization {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "int8" => Ok(Self::Int8),
+            "fp16" => Ok(Self::Fp16),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Kinds of vectors the documents of a collection carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorType {
+    /// A dense vector of the collection's dimension
+    #[default]
+    Dense,
+    /// A sparse vector only; the collection's dimension is 0
+    Sparse,
+    /// A dense vector and, optionally, a sparse vector
+    Both,
+}
+
+impl VectorType {
+    /// Returns the canonical lowercase string stored in SQLite.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dense => "dense",
+            Self::Sparse => "sparse",
+            Self::Both => "both",
+        }
+    }
+
+    /// Whether documents carry a dense vector.
+    #[must_use]
+    pub const fn has_dense(&self) -> bool {
+        !matches!(self, Self::Sparse)
+    }
+
+    /// Whether documents may carry a sparse vector.
+    #[must_use]
+    pub const fn has_sparse(&self) -> bool {
+        !matches!(self, Self::Dense)
+    }
+}
+
+impl FromStr for VectorType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dense" => Ok(Self::Dense),
+            "sparse" => Ok(Self::Sparse),
+            "both" => Ok(Self::Both),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Configuration parameters for a vector collection.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CollectionDescriptor {
@@ -56,10 +144,19 @@ pub struct CollectionDescriptor {
     pub database_id: DatabaseId,
     /// Human-readable name for the collection.
     pub name: String,
-    /// Vector dimension (16-4096).
+    /// Vector dimension (16-4096, or 0 for sparse-only collections).
     pub dimension: u32,
     /// Distance metric for similarity search.
     pub metric: DistanceMetric,
+    /// L2-normalize vectors at insert and query time.
+    #[serde(default)]
+    pub normalize: bool,
+    /// Precision of the vectors stored in the index.
+    #[serde(default)]
+    pub quantization: Quantization,
+    /// Dense, sparse, or both kinds of vectors per document.
+    #[serde(default)]
+    pub vector_type: VectorType,
     /// Embedding model identifier (e.g., "qwen3-embed-8b").
     pub embedding_model: String,
     /// HNSW graph degree (M parameter).
@@ -102,6 +199,9 @@ impl CollectionDescriptor {
             name: name.into(),
             dimension,
             metric: DistanceMetric::default(),
+            normalize: false,
+            quantization: Quantization::None,
+            vector_type: VectorType::Dense,
             embedding_model: embedding_model.into(),
             hnsw_m: Self::DEFAULT_HNSW_M,
             hnsw_ef_construction: Self::DEFAULT_HNSW_EF_CONSTRUCTION,
@@ -115,8 +215,18 @@ impl CollectionDescriptor {
     ///
     /// # Errors
     ///
-    /// Returns an error if dimension is outside [MIN_DIMENSION, MAX_DIMENSION].
+    /// Returns an error if dimension is outside [MIN_DIMENSION, MAX_DIMENSION],
+    /// or is not 0 for a sparse-only collection.
     pub fn validate_dimension(&self) -> Result<(), String> {
+        if self.vector_type == VectorType::Sparse {
+            if self.dimension != 0 {
+                return Err(format!(
+                    "sparse collections have dimension 0, got {}",
+                    self.dimension
+                ));
+            }
+            return Ok(());
+        }
         if self.dimension < Self::MIN_DIMENSION || self.dimension > Self::MAX_DIMENSION {
             return Err(format!(
                 "dimension {} is outside valid range [{}, {}]",
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Canonical error type for core metadata operations.
@@ -99,6 +100,109 @@ impl CoreError {
     }
 }
 
+/// Stable, machine-readable error code exposed by the REST and gRPC APIs.
+///
+/// Clients branch on the code rather than on messages, which may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// Request is malformed or a parameter is out of range.
+    InvalidArgument,
+    /// Vector length does not match the collection's dimension.
+    DimensionMismatch,
+    /// Request body or document payload exceeds a configured limit.
+    PayloadTooLarge,
+    /// Collection does not exist.
+    CollectionNotFound,
+    /// Document does not exist in the collection.
+    DocumentNotFound,
+    /// Some other entity (tenant, job, snapshot, ...) does not exist.
+    NotFound,
+    /// Entity already exists.
+    AlreadyExists,
+    /// Tenant quota prohibits the operation.
+    QuotaExceeded,
+    /// Request rate exceeds the caller's limit.
+    RateLimited,
+    /// Conflicts with a concurrent request.
+    Conflict,
+    /// Operation is not allowed in the current state.
+    FailedPrecondition,
+    /// Caller is not allowed to perform the operation.
+    PermissionDenied,
+    /// Storage or a downstream dependency is temporarily unavailable.
+    Unavailable,
+    /// Unexpected server-side failure.
+    Internal,
+}
+
+impl ErrorCode {
+    /// Returns the wire representation (e.g. `"COLLECTION_NOT_FOUND"`).
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::InvalidArgument => "INVALID_ARGUMENT",
+            Self::DimensionMismatch => "DIMENSION_MISMATCH",
+            Self::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            Self::CollectionNotFound => "COLLECTION_NOT_FOUND",
+            Self::DocumentNotFound => "DOCUMENT_NOT_FOUND",
+            Self::NotFound => "NOT_FOUND",
+            Self::AlreadyExists => "ALREADY_EXISTS",
+            Self::QuotaExceeded => "QUOTA_EXCEEDED",
+            Self::RateLimited => "RATE_LIMITED",
+            Self::Conflict => "CONFLICT",
+            Self::FailedPrecondition => "FAILED_PRECONDITION",
+            Self::PermissionDenied => "PERMISSION_DENIED",
+            Self::Unavailable => "UNAVAILABLE",
+            Self::Internal => "INTERNAL",
+        }
+    }
+
+    /// Returns `true` when retrying the same request later may succeed.
+    #[must_use]
+    pub const fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited | Self::Conflict | Self::Unavailable | Self::Internal
+        )
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl CoreError {
+    /// Returns the API error code for this error.
+    #[must_use]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::NotFound { entity, .. } if entity.eq_ignore_ascii_case("collection") => {
+                ErrorCode::CollectionNotFound
+            }
+            Self::NotFound { entity, .. } if entity.eq_ignore_ascii_case("document") => {
+                ErrorCode::DocumentNotFound
+            }
+            Self::NotFound { .. } => ErrorCode::NotFound,
+            Self::AlreadyExists { .. } => ErrorCode::AlreadyExists,
+            Self::QuotaExceeded { .. } => ErrorCode::QuotaExceeded,
+            Self::ValidationError(message) | Self::InvalidState { message }
+                if message.to_ascii_lowercase().contains("dimension mismatch") =>
+            {
+                ErrorCode::DimensionMismatch
+            }
+            Self::ValidationError(_) | Self::DeserializationError(_) => ErrorCode::InvalidArgument,
+            Self::InvalidState { .. } => ErrorCode::FailedPrecondition,
+            Self::StorageError(_) => ErrorCode::Unavailable,
+            Self::Internal { .. } | Self::IoError(_) | Self::SerializationError(_) => {
+                ErrorCode::Internal
+            }
+        }
+    }
+}
+
 impl From<serde_json::Error> for CoreError {
     fn from(err: serde_json::Error) -> Self {
         if err.is_eof() || err.is_syntax() {
@@ -111,3 +215,37 @@ impl From<serde_json::Error> for CoreError {
 
 /// Convenient result alias for core operations.
 pub type CoreResult<T> = Result<T, CoreError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(
+            CoreError::not_found("Collection", "c1").code(),
+            ErrorCode::CollectionNotFound
+        );
+        assert_eq!(
+            CoreError::not_found("tenant", "t1").code(),
+            ErrorCode::NotFound
+        );
+        assert_eq!(
+            CoreError::ValidationError("Vector dimension mismatch: expected 16, got 8".into())
+                .code(),
+            ErrorCode::DimensionMismatch
+        );
+        assert_eq!(
+            CoreError::ValidationError("top_k must be > 0".into()).code(),
+            ErrorCode::InvalidArgument
+        );
+        assert!(CoreError::StorageError("s3 down".into())
+            .code()
+            .is_retryable());
+        assert!(!ErrorCode::QuotaExceeded.is_retryable());
+        assert_eq!(
+            serde_json::to_string(&ErrorCode::DimensionMismatch).unwrap(),
+            "\"DIMENSION_MISMATCH\""
+        );
+    }
+}
@@ -97,3 +97,7 @@ define_id!(
     ApiKeyId,
     "Unique identifier for an API key used for authentication."
 );
+define_id!(
+    JobId,
+    "Unique identifier for a background job (reindex, export, bulk delete, ...)."
+);
@@ -0,0 +1,152 @@
+//! Background job domain model for long-running operations.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+
+use crate::ids::{CollectionId, JobId};
+
+/// Lifecycle state of a background job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Accepted but not started yet.
+    Pending,
+    /// Currently executing.
+    Running,
+    /// Finished successfully.
+    Succeeded,
+    /// Finished with an error.
+    Failed,
+    /// Stopped early at a client's request.
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Convert status to string representation.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether the job has finished (successfully or not).
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Succeeded | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(JobStatus::Pending),
+            "running" => Ok(JobStatus::Running),
+            "succeeded" => Ok(JobStatus::Succeeded),
+            "failed" => Ok(JobStatus::Failed),
+            "cancelled" => Ok(JobStatus::Cancelled),
+            _ => Err(format!("invalid job status: {s}")),
+        }
+    }
+}
+
+/// A long-running operation tracked outside the request that started it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobDescriptor {
+    pub job_id: JobId,
+    /// Operation name (e.g. `bulk_delete`, `reindex`, `export`, `restore`).
+    pub kind: String,
+    /// Collection the job operates on, if any.
+    pub collection_id: Option<CollectionId>,
+    pub status: JobStatus,
+    /// Units of work completed so far.
+    pub progress: u64,
+    /// Total units of work, when known up front.
+    pub total: Option<u64>,
+    /// Failure reason for `Failed` jobs, or why a `Cancelled` job stopped.
+    pub error: Option<String>,
+    /// Operation-specific result for `Succeeded` jobs.
+    pub result: Option<JsonValue>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl JobDescriptor {
+    /// Create a new pending job.
+    #[must_use]
+    pub fn new(kind: impl Into<String>, collection_id: Option<CollectionId>) -> Self {
+        let now = Utc::now();
+        Self {
+            job_id: JobId::new(),
+            kind: kind.into(),
+            collection_id,
+            status: JobStatus::Pending,
+            progress: 0,
+            total: None,
+            error: None,
+            result: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Set the total amount of work.
+    #[must_use]
+    pub fn with_total(mut self, total: u64) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Completion percentage (0-100), when the total is known.
+    #[must_use]
+    pub fn percent_complete(&self) -> Option<f64> {
+        match (self.status, self.total) {
+            (JobStatus::Succeeded, _) => Some(100.0),
+            (_, Some(0)) => Some(0.0),
+            (_, Some(total)) => Some((self.progress.min(total) as f64 / total as f64) * 100.0),
+            (_, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_status_round_trip() {
+        for status in [
+            JobStatus::Pending,
+            JobStatus::Running,
+            JobStatus::Succeeded,
+            JobStatus::Failed,
+            JobStatus::Cancelled,
+        ] {
+            assert_eq!(JobStatus::from_str(status.as_str()).unwrap(), status);
+        }
+        assert!(JobStatus::from_str("canceled").is_err());
+    }
+
+    #[test]
+    fn percent_complete() {
+        let mut job = JobDescriptor::new("bulk_delete", None).with_total(4);
+        job.progress = 1;
+        assert_eq!(job.percent_complete(), Some(25.0));
+
+        job.status = JobStatus::Succeeded;
+        assert_eq!(job.percent_complete(), Some(100.0));
+
+        assert_eq!(JobDescriptor::new("export", None).percent_complete(), None);
+    }
+}
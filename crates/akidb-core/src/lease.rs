@@ -0,0 +1,51 @@
+//! Time-bounded leases for coordinating work between server processes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Exclusive, expiring claim on a named resource (e.g. a leader role).
+///
+/// The holder must renew the lease before `expires_at`; afterwards any other
+/// process may take it over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lease {
+    /// Resource the lease guards.
+    pub name: String,
+    /// Identifier of the process holding the lease.
+    pub holder: String,
+    /// Incremented every time the lease changes hands, never on renewal.
+    /// Writers can pass it along as a fencing token.
+    pub token: u64,
+    /// When the current holder first acquired the lease.
+    pub acquired_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Lease {
+    /// Whether the lease has lapsed at `now`.
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn lease_expiry() {
+        let now = Utc::now();
+        let lease = Lease {
+            name: "gc".to_string(),
+            holder: "node-a".to_string(),
+            token: 1,
+            acquired_at: now,
+            expires_at: now + Duration::seconds(10),
+        };
+
+        assert!(!lease.is_expired(now));
+        assert!(lease.is_expired(now + Duration::seconds(10)));
+    }
+}
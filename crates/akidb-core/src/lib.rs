@@ -1,29 +1,42 @@
 //! Core domain types and traits for AkiDB 2.0 metadata services.
 
+pub mod alias;
 pub mod audit;
 pub mod auth;
 pub mod collection;
 pub mod database;
 pub mod error;
+pub mod feature_flag;
 pub mod ids;
+pub mod job;
+pub mod lease;
 pub mod tenant;
 pub mod traits;
+pub mod usage;
 pub mod user;
 pub mod vector;
 
+pub use alias::CollectionAlias;
 pub use audit::{AuditLogEntry, AuditResult};
 pub use auth::{
     generate_api_key, hash_api_key, is_valid_api_key_format, ApiKeyDescriptor, CreateApiKeyRequest,
     CreateApiKeyResponse, ListApiKeysResponse,
 };
-pub use collection::{CollectionDescriptor, DistanceMetric};
+pub use collection::{CollectionDescriptor, DistanceMetric, Quantization, VectorType};
 pub use database::{DatabaseDescriptor, DatabaseState};
-pub use error::{CoreError, CoreResult};
-pub use ids::{ApiKeyId, AuditLogId, CollectionId, DatabaseId, DocumentId, TenantId, UserId};
+pub use error::{CoreError, CoreResult, ErrorCode};
+pub use feature_flag::FeatureFlag;
+pub use ids::{
+    ApiKeyId, AuditLogId, CollectionId, DatabaseId, DocumentId, JobId, TenantId, UserId,
+};
+pub use job::{JobDescriptor, JobStatus};
+pub use lease::Lease;
 pub use tenant::{TenantDescriptor, TenantQuota, TenantStatus};
 pub use traits::{
-    ApiKeyRepository, AuditLogRepository, CollectionRepository, DatabaseRepository, TenantCatalog,
-    UserRepository, VectorIndex,
+    AliasRepository, ApiKeyRepository, AuditLogRepository, CollectionRepository,
+    DatabaseRepository, FeatureFlagRepository, JobRepository, LeaseRepository, SearchFilter,
+    TenantCatalog, UsageRepository, UserRepository, VectorIndex,
 };
+pub use usage::{hour_of, UsageRollup};
 pub use user::{Action, Role, UserDescriptor, UserStatus};
-pub use vector::{SearchResult, VectorDocument};
+pub use vector::{SearchResult, SparseVector, VectorDocument};
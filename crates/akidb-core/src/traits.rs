@@ -1,14 +1,20 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
+use crate::alias::CollectionAlias;
 use crate::audit::AuditLogEntry;
 use crate::auth::ApiKeyDescriptor;
 use crate::collection::CollectionDescriptor;
 use crate::database::DatabaseDescriptor;
 use crate::error::CoreResult;
-use crate::ids::{ApiKeyId, CollectionId, DatabaseId, DocumentId, TenantId, UserId};
+use crate::feature_flag::FeatureFlag;
+use crate::ids::{ApiKeyId, CollectionId, DatabaseId, DocumentId, JobId, TenantId, UserId};
+use crate::job::{JobDescriptor, JobStatus};
+use crate::lease::Lease;
 use crate::tenant::TenantDescriptor;
+use crate::usage::UsageRollup;
 use crate::user::UserDescriptor;
-use crate::vector::{SearchResult, VectorDocument};
+use crate::vector::{SearchResult, SparseVector, VectorDocument};
 
 /// Catalog interface for managing tenant metadata.
 #[async_trait]
@@ -147,6 +153,112 @@ pub trait ApiKeyRepository: Send + Sync {
     async fn update_last_used(&self, key_id: ApiKeyId) -> CoreResult<()>;
 }
 
+/// Repository interface for background job state.
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    /// Persists a new job.
+    async fn create(&self, job: &JobDescriptor) -> CoreResult<()>;
+
+    /// Updates status, progress, error, and result of an existing job.
+    async fn update(&self, job: &JobDescriptor) -> CoreResult<()>;
+
+    /// Fetches a job by its identifier.
+    async fn get(&self, job_id: JobId) -> CoreResult<Option<JobDescriptor>>;
+
+    /// Lists jobs, newest first, optionally only those with `status` or on
+    /// `collection_id`.
+    async fn list(
+        &self,
+        status: Option<JobStatus>,
+        collection_id: Option<CollectionId>,
+        limit: usize,
+        offset: usize,
+    ) -> CoreResult<Vec<JobDescriptor>>;
+
+    /// Marks all pending/running jobs as failed with `reason`.
+    ///
+    /// Called on startup: jobs run in-process, so unfinished jobs from a
+    /// previous run can never complete. Returns the number of jobs updated.
+    async fn fail_unfinished(&self, reason: &str) -> CoreResult<u64>;
+}
+
+/// Repository interface for leases shared by server processes.
+///
+/// Implementations must make `acquire` atomic: of two processes racing for
+/// the same free lease, exactly one gets it.
+#[async_trait]
+pub trait LeaseRepository: Send + Sync {
+    /// Acquires `name` for `holder`, or renews it if `holder` already has it.
+    ///
+    /// Returns the lease, valid for `ttl` from now, or `None` if another
+    /// holder's lease has not expired yet.
+    async fn acquire(
+        &self,
+        name: &str,
+        holder: &str,
+        ttl: std::time::Duration,
+    ) -> CoreResult<Option<Lease>>;
+
+    /// Gives up `name` if `holder` still has it. Returns whether it did.
+    async fn release(&self, name: &str, holder: &str) -> CoreResult<bool>;
+
+    /// Fetches the current lease on `name`, expired or not.
+    async fn get(&self, name: &str) -> CoreResult<Option<Lease>>;
+}
+
+/// Repository interface for hourly per-tenant usage rollups.
+#[async_trait]
+pub trait UsageRepository: Send + Sync {
+    /// Adds `rollup` to the stored rollup for its tenant and hour, creating
+    /// it if needed. Counters are summed; stored bytes keep the maximum.
+    async fn record(&self, rollup: &UsageRollup) -> CoreResult<()>;
+
+    /// Lists rollups with `from <= hour < to`, for one tenant or all of
+    /// them, ordered by hour then tenant.
+    async fn list(
+        &self,
+        tenant_id: Option<TenantId>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> CoreResult<Vec<UsageRollup>>;
+}
+
+/// Repository interface for runtime feature flags.
+#[async_trait]
+pub trait FeatureFlagRepository: Send + Sync {
+    /// Lists every stored flag ordered by name.
+    async fn list(&self) -> CoreResult<Vec<FeatureFlag>>;
+
+    /// Stores `flag`, replacing any flag of the same name and its overrides.
+    async fn upsert(&self, flag: &FeatureFlag) -> CoreResult<()>;
+
+    /// Deletes a flag; returns `false` if it did not exist.
+    async fn delete(&self, name: &str) -> CoreResult<bool>;
+}
+
+/// Repository interface for collection aliases.
+#[async_trait]
+pub trait AliasRepository: Send + Sync {
+    /// Lists the aliases of a database ordered by name.
+    async fn list(&self, database_id: DatabaseId) -> CoreResult<Vec<CollectionAlias>>;
+
+    /// Fetches an alias by database and name.
+    async fn get(&self, database_id: DatabaseId, name: &str)
+        -> CoreResult<Option<CollectionAlias>>;
+
+    /// Creates `alias`, or atomically repoints an existing alias of the same
+    /// name to `alias.collection_id`, keeping its `created_at`.
+    async fn upsert(&self, alias: &CollectionAlias) -> CoreResult<()>;
+
+    /// Deletes an alias; returns `false` if it did not exist.
+    async fn delete(&self, database_id: DatabaseId, name: &str) -> CoreResult<bool>;
+}
+
+/// Predicate restricting a filtered search, given each candidate's id and
+/// metadata.
+pub type SearchFilter<'a> =
+    &'a (dyn Fn(DocumentId, Option<&serde_json::Value>) -> bool + Send + Sync);
+
 /// Vector index trait for insert, search, and delete operations.
 #[async_trait]
 pub trait VectorIndex: Send + Sync {
@@ -182,6 +294,32 @@ pub trait VectorIndex: Send + Sync {
         ef_search: Option<usize>,
     ) -> CoreResult<Vec<SearchResult>>;
 
+    /// Searches for the k nearest neighbors accepted by `filter`.
+    ///
+    /// Unlike filtering the output of [`search`](Self::search), rejected
+    /// documents never take a result slot, so a selective filter does not
+    /// cost recall. Results are sorted as for `search`.
+    ///
+    /// The default implementation ranks every document and filters the
+    /// ranking; indexes override it to skip rejected documents while
+    /// searching.
+    async fn search_filtered(
+        &self,
+        query: &[f32],
+        k: usize,
+        ef_search: Option<usize>,
+        filter: SearchFilter<'_>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        let count = self.count().await?;
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let mut results = self.search(query, count, ef_search).await?;
+        results.retain(|result| filter(result.doc_id, result.metadata.as_ref()));
+        results.truncate(k);
+        Ok(results)
+    }
+
     /// Deletes a document by ID.
     ///
     /// HNSW implementations may use soft deletion with tombstone marking.
@@ -195,4 +333,41 @@ pub trait VectorIndex: Send + Sync {
 
     /// Clears the entire index (for testing).
     async fn clear(&self) -> CoreResult<()>;
+
+    /// Serializes the index graph so it can be restored without a rebuild.
+    ///
+    /// Returns `None` for indexes without a persistable graph (the default).
+    fn serialize_graph(&self) -> CoreResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// Ranks documents by BM25 relevance of their payload text to `query`,
+    /// best first, returning at most `k` matches.
+    ///
+    /// `fields` limits matching to those top-level metadata keys (all keys
+    /// when `None`). Returns `None` for indexes without a text index (the
+    /// default).
+    fn text_search(
+        &self,
+        query: &str,
+        fields: Option<&[String]>,
+        k: usize,
+    ) -> CoreResult<Option<Vec<(DocumentId, f32)>>> {
+        let _ = (query, fields, k);
+        Ok(None)
+    }
+
+    /// Ranks documents by the dot product of their sparse vector with
+    /// `query`, best first, returning at most `k` documents with a positive
+    /// score.
+    ///
+    /// Returns `None` for indexes without a sparse index (the default).
+    fn sparse_search(
+        &self,
+        query: &SparseVector,
+        k: usize,
+    ) -> CoreResult<Option<Vec<(DocumentId, f32)>>> {
+        let _ = (query, k);
+        Ok(None)
+    }
 }
@@ -0,0 +1,51 @@
+//! Per-tenant usage rollups for metering and billing.
+
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ids::TenantId;
+
+/// Usage of one tenant during one hour.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageRollup {
+    pub tenant_id: TenantId,
+    /// Start of the hour (UTC).
+    pub hour: DateTime<Utc>,
+    /// Search and query requests.
+    pub queries: u64,
+    /// Vectors inserted or replaced.
+    pub vectors_inserted: u64,
+    /// Largest vector payload stored at any sample during the hour, in bytes.
+    pub stored_bytes: u64,
+    /// Tokens embedded (each image counts as one).
+    pub embedding_tokens: u64,
+}
+
+impl UsageRollup {
+    /// Creates an empty rollup for the hour containing `at`.
+    #[must_use]
+    pub fn new(tenant_id: TenantId, at: DateTime<Utc>) -> Self {
+        Self {
+            tenant_id,
+            hour: hour_of(at),
+            queries: 0,
+            vectors_inserted: 0,
+            stored_bytes: 0,
+            embedding_tokens: 0,
+        }
+    }
+
+    /// Adds `other`'s counters to this rollup; stored bytes keep the maximum.
+    pub fn merge(&mut self, other: &UsageRollup) {
+        self.queries += other.queries;
+        self.vectors_inserted += other.vectors_inserted;
+        self.stored_bytes = self.stored_bytes.max(other.stored_bytes);
+        self.embedding_tokens += other.embedding_tokens;
+    }
+}
+
+/// Start of the hour containing `at`.
+#[must_use]
+pub fn hour_of(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.duration_trunc(TimeDelta::hours(1)).unwrap_or(at)
+}
@@ -73,6 +73,34 @@ pub enum Action {
 
     // Audit logs
     AuditRead,
+
+    // Storage administration
+    DlqRetry,
+    CircuitBreakerReset,
+
+    // Runtime diagnostics (`/debug` endpoints)
+    DebugRead,
+
+    // Taking the node out of rotation (`/admin/drain`)
+    NodeDrain,
+
+    // Point-in-time collection restore
+    CollectionRestore,
+
+    // Creating, repointing and deleting collection aliases
+    AliasWrite,
+
+    // Changing runtime feature flags
+    FeatureFlagWrite,
+
+    // Rebuilding a collection's index
+    CollectionReindex,
+
+    // Listing, inspecting and cancelling background jobs of every tenant
+    JobAdmin,
+
+    // Exporting per-tenant usage for billing
+    UsageRead,
 }
 
 impl UserDescriptor {
@@ -217,6 +245,16 @@ impl Action {
             Action::DocumentUpdate => "document::update",
             Action::DocumentDelete => "document::delete",
             Action::AuditRead => "audit::read",
+            Action::DlqRetry => "admin::dlq_retry",
+            Action::CircuitBreakerReset => "admin::circuit_breaker_reset",
+            Action::DebugRead => "admin::debug",
+            Action::NodeDrain => "admin::drain",
+            Action::CollectionRestore => "admin::restore",
+            Action::AliasWrite => "admin::aliases",
+            Action::FeatureFlagWrite => "admin::feature_flags",
+            Action::CollectionReindex => "admin::reindex",
+            Action::JobAdmin => "admin::jobs",
+            Action::UsageRead => "admin::usage",
         }
     }
 }
@@ -243,6 +281,16 @@ impl FromStr for Action {
             "document::update" => Ok(Action::DocumentUpdate),
             "document::delete" => Ok(Action::DocumentDelete),
             "audit::read" => Ok(Action::AuditRead),
+            "admin::dlq_retry" => Ok(Action::DlqRetry),
+            "admin::circuit_breaker_reset" => Ok(Action::CircuitBreakerReset),
+            "admin::debug" => Ok(Action::DebugRead),
+            "admin::drain" => Ok(Action::NodeDrain),
+            "admin::restore" => Ok(Action::CollectionRestore),
+            "admin::aliases" => Ok(Action::AliasWrite),
+            "admin::feature_flags" => Ok(Action::FeatureFlagWrite),
+            "admin::reindex" => Ok(Action::CollectionReindex),
+            "admin::jobs" => Ok(Action::JobAdmin),
+            "admin::usage" => Ok(Action::UsageRead),
             _ => Err(format!("invalid action: {s}")),
         }
     }
@@ -4,9 +4,123 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
+use crate::error::{CoreError, CoreResult};
 use crate::ids::DocumentId;
 use crate::DistanceMetric;
 
+/// A sparse vector: the non-zero components of a vector as `(index, value)`
+/// pairs, held in increasing index order.
+///
+/// Sparse vectors are scored with the dot product, which only visits the
+/// indices both vectors share.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "SparseVectorParts")]
+pub struct SparseVector {
+    indices: Vec<u32>,
+    values: Vec<f32>,
+}
+
+/// Unvalidated wire form of [`SparseVector`].
+#[derive(Deserialize)]
+struct SparseVectorParts {
+    indices: Vec<u32>,
+    values: Vec<f32>,
+}
+
+impl TryFrom<SparseVectorParts> for SparseVector {
+    type Error = CoreError;
+
+    fn try_from(parts: SparseVectorParts) -> CoreResult<Self> {
+        Self::new(parts.indices, parts.values)
+    }
+}
+
+impl SparseVector {
+    /// Creates a sparse vector from parallel index and value lists, sorting
+    /// the pairs by index.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ValidationError` if the lists differ in length, an index
+    /// repeats, or a value is not finite.
+    pub fn new(indices: Vec<u32>, values: Vec<f32>) -> CoreResult<Self> {
+        if indices.len() != values.len() {
+            return Err(CoreError::ValidationError(format!(
+                "sparse vector has {} indices but {} values",
+                indices.len(),
+                values.len()
+            )));
+        }
+        if values.iter().any(|value| !value.is_finite()) {
+            return Err(CoreError::ValidationError(
+                "sparse vector values must be finite".to_string(),
+            ));
+        }
+
+        let mut pairs: Vec<(u32, f32)> = indices.into_iter().zip(values).collect();
+        pairs.sort_unstable_by_key(|&(index, _)| index);
+        if let Some(pair) = pairs.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(CoreError::ValidationError(format!(
+                "sparse vector index {} appears more than once",
+                pair[0].0
+            )));
+        }
+        let (indices, values) = pairs.into_iter().unzip();
+        Ok(Self { indices, values })
+    }
+
+    /// Indices of the stored components, in increasing order.
+    #[must_use]
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Values of the stored components, in index order.
+    #[must_use]
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// `(index, value)` pairs in increasing index order.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, f32)> + '_ {
+        self.indices
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+    }
+
+    /// Number of stored components.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Whether no components are stored (the zero vector).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Dot product with another sparse vector.
+    #[must_use]
+    pub fn dot(&self, other: &Self) -> f32 {
+        let (mut i, mut j) = (0, 0);
+        let mut sum = 0.0;
+        while i < self.indices.len() && j < other.indices.len() {
+            match self.indices[i].cmp(&other.indices[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    sum += self.values[i] * other.values[j];
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        sum
+    }
+}
+
 /// A vector document stored in the index.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorDocument {
@@ -16,9 +130,13 @@ pub struct VectorDocument {
     /// External identifier (user-provided, optional)
     pub external_id: Option<String>,
 
-    /// Dense vector embedding
+    /// Dense vector embedding (empty in sparse-only collections)
     pub vector: Vec<f32>,
 
+    /// Sparse vector (collections with sparse vectors only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse: Option<SparseVector>,
+
     /// JSON metadata payload (user-defined)
     pub metadata: Option<JsonValue>,
 
@@ -34,6 +152,7 @@ impl VectorDocument {
             doc_id,
             external_id: None,
             vector,
+            sparse: None,
             metadata: None,
             inserted_at: Utc::now(),
         }
@@ -53,6 +172,13 @@ impl VectorDocument {
         self
     }
 
+    /// Sets the sparse vector (builder pattern).
+    #[must_use]
+    pub fn with_sparse(mut self, sparse: SparseVector) -> Self {
+        self.sparse = Some(sparse);
+        self
+    }
+
     /// Sets a specific insertion timestamp (builder pattern).
     ///
     /// This is primarily for deserialization/retrieval operations.
@@ -84,6 +210,14 @@ pub struct SearchResult {
 
     /// Document metadata (if requested)
     pub metadata: Option<JsonValue>,
+
+    /// Stored vector (if requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<Vec<f32>>,
+
+    /// Stored sparse vector (if requested and the document has one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse: Option<SparseVector>,
 }
 
 impl SearchResult {
@@ -95,6 +229,8 @@ impl SearchResult {
             external_id: None,
             score,
             metadata: None,
+            vector: None,
+            sparse: None,
         }
     }
 
@@ -111,6 +247,13 @@ impl SearchResult {
         self.metadata = Some(metadata);
         self
     }
+
+    /// Sets the stored vector (builder pattern).
+    #[must_use]
+    pub fn with_vector(mut self, vector: Vec<f32>) -> Self {
+        self.vector = Some(vector);
+        self
+    }
 }
 
 /// Computes the cosine similarity between two vectors.
@@ -169,6 +312,23 @@ pub fn dot_product(a: &[f32], b: &[f32]) -> f32 {
     a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
 }
 
+/// Scales a vector to unit L2 norm in place.
+///
+/// Returns `false`, leaving the vector unchanged, if its norm is zero or not
+/// finite.
+#[must_use]
+pub fn l2_normalize(vector: &mut [f32]) -> bool {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 || !norm.is_finite() {
+        return false;
+    }
+
+    for x in vector.iter_mut() {
+        *x /= norm;
+    }
+    true
+}
+
 impl DistanceMetric {
     /// Computes the distance/similarity score between two vectors.
     ///
@@ -211,6 +371,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_l2_normalize() {
+        let mut v = vec![3.0, 4.0];
+        assert!(l2_normalize(&mut v));
+        assert!((v[0] - 0.6).abs() < 1e-6);
+        assert!((v[1] - 0.8).abs() < 1e-6);
+
+        let mut zero = vec![0.0, 0.0];
+        assert!(!l2_normalize(&mut zero));
+        assert_eq!(zero, vec![0.0, 0.0]);
+    }
+
     #[test]
     fn test_euclidean_distance_identical() {
         let a = vec![1.0, 2.0, 3.0];
@@ -294,6 +466,35 @@ mod tests {
         assert_eq!(doc.dimension(), 3);
     }
 
+    #[test]
+    fn test_sparse_vector_new_sorts_and_validates() {
+        let sparse = SparseVector::new(vec![7, 2, 5], vec![0.7, 0.2, 0.5]).unwrap();
+        assert_eq!(sparse.indices(), &[2, 5, 7]);
+        assert_eq!(sparse.values(), &[0.2, 0.5, 0.7]);
+
+        assert!(SparseVector::new(vec![1, 2], vec![1.0]).is_err());
+        assert!(SparseVector::new(vec![3, 3], vec![1.0, 2.0]).is_err());
+        assert!(SparseVector::new(vec![1], vec![f32::NAN]).is_err());
+
+        // Deserialization goes through the same checks
+        let parsed: SparseVector =
+            serde_json::from_str(r#"{"indices": [9, 1], "values": [2.0, 1.0]}"#).unwrap();
+        assert_eq!(parsed.indices(), &[1, 9]);
+        assert!(serde_json::from_str::<SparseVector>(
+            r#"{"indices": [1, 1], "values": [1.0, 1.0]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_sparse_vector_dot() {
+        let a = SparseVector::new(vec![1, 4, 9], vec![1.0, 2.0, 3.0]).unwrap();
+        let b = SparseVector::new(vec![0, 4, 9, 12], vec![5.0, 0.5, 2.0, 1.0]).unwrap();
+        // 2*0.5 + 3*2
+        assert!((a.dot(&b) - 7.0).abs() < 1e-6);
+        assert_eq!(a.dot(&SparseVector::default()), 0.0);
+    }
+
     #[test]
     fn test_search_result_builder() {
         let doc_id = DocumentId::new();
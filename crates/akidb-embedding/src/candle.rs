@@ -0,0 +1,504 @@
+//! Candle embedding provider.
+//!
+//! Pure-Rust local inference for BERT-family sentence embedding models, for
+//! builds without Python or ONNX Runtime (e.g. slim Linux containers).
+//!
+//! # Model formats
+//!
+//! - **safetensors**: Hugging Face checkpoint plus its `config.json`
+//! - **GGUF**: llama.cpp `bert` checkpoint; the architecture is read from the
+//!   file metadata and quantized weights are dequantized at load time
+//!
+//! Both need the model's `tokenizer.json`. Embeddings are mean-pooled over the
+//! attention mask. Inference runs on the CPU in a blocking task.
+
+use crate::{
+    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingProvider,
+    EmbeddingResult, ModelInfo, Usage,
+};
+use async_trait::async_trait;
+use candle_core::quantized::gguf_file;
+use candle_core::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config as BertConfig, HiddenAct};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+/// Configuration for the Candle embedding provider.
+#[derive(Debug, Clone)]
+pub struct CandleConfig {
+    /// Path to the weights (`.safetensors` or `.gguf`)
+    pub model_path: PathBuf,
+    /// Path to tokenizer.json file
+    pub tokenizer_path: PathBuf,
+    /// Path to config.json (required for safetensors, ignored for GGUF)
+    pub config_path: Option<PathBuf>,
+    /// Model name (for metadata)
+    pub model_name: String,
+    /// Maximum sequence length; longer inputs are truncated
+    pub max_length: usize,
+}
+
+impl Default for CandleConfig {
+    fn default() -> Self {
+        Self {
+            model_path: PathBuf::from("models/model.safetensors"),
+            tokenizer_path: PathBuf::from("models/tokenizer.json"),
+            config_path: Some(PathBuf::from("models/config.json")),
+            model_name: "sentence-transformers/all-MiniLM-L6-v2".to_string(),
+            max_length: 512,
+        }
+    }
+}
+
+impl CandleConfig {
+    /// Config for a model directory laid out like a Hugging Face snapshot:
+    /// `tokenizer.json` plus either a `.gguf` file or `model.safetensors`
+    /// with `config.json`.
+    pub fn from_dir(dir: impl AsRef<Path>, model_name: impl Into<String>) -> Self {
+        let dir = dir.as_ref();
+        let gguf = std::fs::read_dir(dir).ok().and_then(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .find(|path| path.extension().is_some_and(|ext| ext == "gguf"))
+        });
+
+        Self {
+            config_path: gguf.is_none().then(|| dir.join("config.json")),
+            model_path: gguf.unwrap_or_else(|| dir.join("model.safetensors")),
+            tokenizer_path: dir.join("tokenizer.json"),
+            model_name: model_name.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Loaded model and tokenizer, shared with blocking inference tasks.
+struct CandleModel {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+/// Candle (pure Rust) embedding provider.
+pub struct CandleEmbeddingProvider {
+    inner: Arc<CandleModel>,
+    /// Tokenizer without padding or truncation, for counting input tokens
+    counter: Tokenizer,
+    config: CandleConfig,
+    dimension: u32,
+}
+
+fn internal(context: &str) -> impl Fn(candle_core::Error) -> EmbeddingError + '_ {
+    move |e| EmbeddingError::Internal(format!("{}: {}", context, e))
+}
+
+impl CandleEmbeddingProvider {
+    /// Loads the model and tokenizer described by `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ModelNotFound` if a file is missing and `Internal` if the
+    /// model cannot be loaded.
+    pub async fn with_config(config: CandleConfig) -> EmbeddingResult<Self> {
+        for path in [&config.model_path, &config.tokenizer_path]
+            .into_iter()
+            .chain(config.config_path.as_ref())
+        {
+            if !path.exists() {
+                return Err(EmbeddingError::ModelNotFound(format!(
+                    "{} does not exist",
+                    path.display()
+                )));
+            }
+        }
+
+        let load_config = config.clone();
+        let (inner, dimension) = tokio::task::spawn_blocking(move || Self::load(&load_config))
+            .await
+            .map_err(|e| EmbeddingError::Internal(format!("Model loading task failed: {}", e)))??;
+
+        Ok(Self {
+            counter: crate::truncation::counting_tokenizer(&inner.tokenizer),
+            inner: Arc::new(inner),
+            config,
+            dimension,
+        })
+    }
+
+    fn load(config: &CandleConfig) -> EmbeddingResult<(CandleModel, u32)> {
+        let device = Device::Cpu;
+        let is_gguf = config
+            .model_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("gguf"));
+
+        let (bert_config, vb) = if is_gguf {
+            let (bert_config, tensors) = load_gguf(&config.model_path, &device)?;
+            (
+                bert_config,
+                VarBuilder::from_tensors(tensors, DType::F32, &device),
+            )
+        } else {
+            let config_path = config.config_path.as_ref().ok_or_else(|| {
+                EmbeddingError::InvalidInput(
+                    "config_path is required for safetensors models".to_string(),
+                )
+            })?;
+            let raw = std::fs::read_to_string(config_path).map_err(|e| {
+                EmbeddingError::Internal(format!("Failed to read {}: {}", config_path.display(), e))
+            })?;
+            let bert_config: BertConfig = serde_json::from_str(&raw)
+                .map_err(|e| EmbeddingError::Internal(format!("Invalid config.json: {}", e)))?;
+            // SAFETY: the file is memory-mapped read-only and not modified while loaded
+            let vb = unsafe {
+                VarBuilder::from_mmaped_safetensors(&[&config.model_path], DType::F32, &device)
+            }
+            .map_err(internal("Failed to load safetensors"))?;
+            (bert_config, vb)
+        };
+
+        let model = BertModel::load(vb, &bert_config).map_err(internal("Failed to build model"))?;
+
+        let mut tokenizer = Tokenizer::from_file(&config.tokenizer_path)
+            .map_err(|e| EmbeddingError::Internal(format!("Failed to load tokenizer: {}", e)))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: config.max_length.min(bert_config.max_position_embeddings),
+                ..Default::default()
+            }))
+            .map_err(|e| EmbeddingError::Internal(format!("Invalid truncation: {}", e)))?;
+
+        Ok((
+            CandleModel {
+                model,
+                tokenizer,
+                device,
+            },
+            bert_config.hidden_size as u32,
+        ))
+    }
+
+    async fn embed(&self, texts: Vec<String>, normalize: bool) -> EmbeddingResult<Vec<Vec<f32>>> {
+        let inner = Arc::clone(&self.inner);
+        tokio::task::spawn_blocking(move || inner.embed(texts, normalize))
+            .await
+            .map_err(|e| EmbeddingError::Internal(format!("Inference task failed: {}", e)))?
+    }
+}
+
+impl CandleModel {
+    /// Tokenizes, runs the encoder and mean-pools over the attention mask.
+    fn embed(&self, texts: Vec<String>, normalize: bool) -> EmbeddingResult<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts, true)
+            .map_err(|e| EmbeddingError::Internal(format!("Tokenization failed: {}", e)))?;
+        let batch_size = encodings.len();
+        let seq_len = encodings.first().map_or(0, |e| e.get_ids().len());
+
+        let mut ids = Vec::with_capacity(batch_size * seq_len);
+        let mut mask = Vec::with_capacity(batch_size * seq_len);
+        for encoding in &encodings {
+            ids.extend_from_slice(encoding.get_ids());
+            mask.extend_from_slice(encoding.get_attention_mask());
+        }
+
+        let input_ids = Tensor::from_vec(ids, (batch_size, seq_len), &self.device)
+            .map_err(internal("Failed to create input tensor"))?;
+        let attention_mask = Tensor::from_vec(mask, (batch_size, seq_len), &self.device)
+            .map_err(internal("Failed to create mask tensor"))?;
+        let token_type_ids = input_ids
+            .zeros_like()
+            .map_err(internal("Failed to create token_type_ids tensor"))?;
+
+        let hidden = self
+            .model
+            .forward(&input_ids, &token_type_ids, Some(&attention_mask))
+            .map_err(internal("Inference failed"))?;
+
+        let pooled = mean_pool(&hidden, &attention_mask).map_err(internal("Pooling failed"))?;
+        let pooled = if normalize {
+            l2_normalize(&pooled).map_err(internal("Normalization failed"))?
+        } else {
+            pooled
+        };
+        pooled
+            .to_vec2::<f32>()
+            .map_err(internal("Failed to read embeddings"))
+    }
+}
+
+/// Averages `hidden` (`[batch, seq, dim]`) over unmasked positions.
+fn mean_pool(hidden: &Tensor, attention_mask: &Tensor) -> candle_core::Result<Tensor> {
+    let mask = attention_mask.to_dtype(DType::F32)?.unsqueeze(2)?;
+    let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+    let counts = mask.sum(1)?.maximum(1e-9)?;
+    summed.broadcast_div(&counts)
+}
+
+fn l2_normalize(embeddings: &Tensor) -> candle_core::Result<Tensor> {
+    let norms = embeddings.sqr()?.sum_keepdim(1)?.sqrt()?.maximum(1e-12)?;
+    embeddings.broadcast_div(&norms)
+}
+
+/// Reads a llama.cpp `bert` GGUF file into a BERT config and dequantized
+/// tensors named as in the Hugging Face checkpoint.
+fn load_gguf(
+    path: &Path,
+    device: &Device,
+) -> EmbeddingResult<(BertConfig, HashMap<String, Tensor>)> {
+    let mut file = std::fs::File::open(path).map_err(|e| {
+        EmbeddingError::Internal(format!("Failed to open {}: {}", path.display(), e))
+    })?;
+    let content = gguf_file::Content::read(&mut file).map_err(internal("Invalid GGUF file"))?;
+
+    let mut tensors = HashMap::new();
+    for name in content.tensor_infos.keys() {
+        let Some(hf_name) = hf_tensor_name(name) else {
+            continue;
+        };
+        let tensor = content
+            .tensor(&mut file, name, device)
+            .and_then(|qtensor| qtensor.dequantize(device))
+            .map_err(internal("Failed to read GGUF tensor"))?;
+        tensors.insert(hf_name, tensor);
+    }
+
+    let vocab_size = tensors
+        .get("embeddings.word_embeddings.weight")
+        .map(|t| t.dims()[0])
+        .ok_or_else(|| EmbeddingError::Internal("GGUF file has no token embeddings".to_string()))?;
+    let type_vocab_size = tensors
+        .get("embeddings.token_type_embeddings.weight")
+        .map_or(2, |t| t.dims()[0]);
+    let mut config = bert_config_from_gguf(&content.metadata)?;
+    config.vocab_size = vocab_size;
+    config.type_vocab_size = type_vocab_size;
+
+    Ok((config, tensors))
+}
+
+/// Builds a BERT config from GGUF metadata (`bert.*` keys).
+fn bert_config_from_gguf(
+    metadata: &HashMap<String, gguf_file::Value>,
+) -> EmbeddingResult<BertConfig> {
+    let get = |key: &str| {
+        metadata
+            .get(key)
+            .ok_or_else(|| EmbeddingError::Internal(format!("GGUF metadata is missing {}", key)))
+    };
+    let get_usize = |key: &str| {
+        get(key)?
+            .to_u32()
+            .map(|v| v as usize)
+            .map_err(internal("Invalid GGUF metadata"))
+    };
+
+    let architecture = get("general.architecture")?
+        .to_string()
+        .map_err(internal("Invalid GGUF metadata"))?;
+    if architecture != "bert" {
+        return Err(EmbeddingError::InvalidInput(format!(
+            "Unsupported GGUF architecture '{}' (expected 'bert')",
+            architecture
+        )));
+    }
+
+    Ok(BertConfig {
+        hidden_size: get_usize("bert.embedding_length")?,
+        num_hidden_layers: get_usize("bert.block_count")?,
+        num_attention_heads: get_usize("bert.attention.head_count")?,
+        intermediate_size: get_usize("bert.feed_forward_length")?,
+        max_position_embeddings: get_usize("bert.context_length")?,
+        layer_norm_eps: get("bert.attention.layer_norm_epsilon")?
+            .to_f32()
+            .map_err(internal("Invalid GGUF metadata"))? as f64,
+        hidden_act: HiddenAct::Gelu,
+        ..BertConfig::default()
+    })
+}
+
+/// Maps a llama.cpp BERT tensor name to its Hugging Face name.
+///
+/// Returns `None` for tensors the encoder does not use (e.g. the pooler).
+fn hf_tensor_name(gguf_name: &str) -> Option<String> {
+    let (base, param) = gguf_name.rsplit_once('.')?;
+    let mapped = match base {
+        "token_embd" => "embeddings.word_embeddings".to_string(),
+        "position_embd" => "embeddings.position_embeddings".to_string(),
+        "token_types" => "embeddings.token_type_embeddings".to_string(),
+        "token_embd_norm" => "embeddings.LayerNorm".to_string(),
+        _ => {
+            let (layer, block) = base.strip_prefix("blk.")?.split_once('.')?;
+            let layer: usize = layer.parse().ok()?;
+            let suffix = match block {
+                "attn_q" => "attention.self.query",
+                "attn_k" => "attention.self.key",
+                "attn_v" => "attention.self.value",
+                "attn_output" => "attention.output.dense",
+                "attn_output_norm" => "attention.output.LayerNorm",
+                "ffn_up" => "intermediate.dense",
+                "ffn_down" => "output.dense",
+                "layer_output_norm" => "output.LayerNorm",
+                _ => return None,
+            };
+            format!("encoder.layer.{}.{}", layer, suffix)
+        }
+    };
+    Some(format!("{}.{}", mapped, param))
+}
+
+#[async_trait]
+impl EmbeddingProvider for CandleEmbeddingProvider {
+    async fn embed_batch(
+        &self,
+        request: BatchEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        if request.inputs.is_empty() {
+            return Err(EmbeddingError::InvalidInput("Empty input list".to_string()));
+        }
+
+        for (i, input) in request.inputs.iter().enumerate() {
+            if input.trim().is_empty() {
+                return Err(EmbeddingError::InvalidInput(format!(
+                    "Input at index {} is empty or whitespace",
+                    i
+                )));
+            }
+        }
+
+        let start = Instant::now();
+        // Approximate: 0.75 tokens per word
+        let total_tokens: usize = request
+            .inputs
+            .iter()
+            .map(|text| ((text.split_whitespace().count() as f32) * 0.75) as usize)
+            .sum();
+        let embeddings = self.embed(request.inputs, request.normalize).await?;
+
+        Ok(BatchEmbeddingResponse {
+            model: request.model,
+            embeddings,
+            usage: Usage {
+                total_tokens,
+                duration_ms: start.elapsed().as_millis() as u64,
+            },
+        })
+    }
+
+    async fn model_info(&self) -> EmbeddingResult<ModelInfo> {
+        Ok(ModelInfo {
+            model: self.config.model_name.clone(),
+            dimension: self.dimension,
+            max_tokens: self.config.max_length,
+            execution_provider: Some("cpu".to_string()),
+        })
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<Range<usize>> {
+        crate::truncation::tokenizer_spans(&self.counter, text)
+    }
+
+    async fn health_check(&self) -> EmbeddingResult<()> {
+        let embeddings = self.embed(vec!["health check".to_string()], true).await?;
+        match embeddings.first() {
+            Some(embedding) if embedding.len() == self.dimension as usize => Ok(()),
+            _ => Err(EmbeddingError::ServiceUnavailable(
+                "Health check failed: unexpected embedding output".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hf_tensor_name() {
+        assert_eq!(
+            hf_tensor_name("token_embd.weight").as_deref(),
+            Some("embeddings.word_embeddings.weight")
+        );
+        assert_eq!(
+            hf_tensor_name("token_embd_norm.bias").as_deref(),
+            Some("embeddings.LayerNorm.bias")
+        );
+        assert_eq!(
+            hf_tensor_name("blk.3.attn_q.weight").as_deref(),
+            Some("encoder.layer.3.attention.self.query.weight")
+        );
+        assert_eq!(
+            hf_tensor_name("blk.11.layer_output_norm.bias").as_deref(),
+            Some("encoder.layer.11.output.LayerNorm.bias")
+        );
+        assert_eq!(hf_tensor_name("pooler.weight"), None);
+        assert_eq!(hf_tensor_name("blk.x.attn_q.weight"), None);
+    }
+
+    #[test]
+    fn test_bert_config_from_gguf() {
+        use gguf_file::Value;
+
+        let mut metadata = HashMap::from([
+            (
+                "general.architecture".to_string(),
+                Value::String("bert".to_string()),
+            ),
+            ("bert.embedding_length".to_string(), Value::U32(384)),
+            ("bert.block_count".to_string(), Value::U32(6)),
+            ("bert.attention.head_count".to_string(), Value::U32(12)),
+            ("bert.feed_forward_length".to_string(), Value::U32(1536)),
+            ("bert.context_length".to_string(), Value::U32(512)),
+            (
+                "bert.attention.layer_norm_epsilon".to_string(),
+                Value::F32(1e-12),
+            ),
+        ]);
+        let config = bert_config_from_gguf(&metadata).unwrap();
+        assert_eq!(config.hidden_size, 384);
+        assert_eq!(config.num_hidden_layers, 6);
+        assert_eq!(config.intermediate_size, 1536);
+
+        metadata.insert(
+            "general.architecture".to_string(),
+            Value::String("llama".to_string()),
+        );
+        assert!(matches!(
+            bert_config_from_gguf(&metadata),
+            Err(EmbeddingError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_mean_pool_ignores_padding() {
+        let device = Device::Cpu;
+        let hidden = Tensor::new(&[[[1.0f32, 2.0], [3.0, 4.0], [100.0, 100.0]]], &device).unwrap();
+        let mask = Tensor::new(&[[1u32, 1, 0]], &device).unwrap();
+
+        let pooled = mean_pool(&hidden, &mask).unwrap();
+        assert_eq!(pooled.to_vec2::<f32>().unwrap(), vec![vec![2.0, 3.0]]);
+
+        let normalized = l2_normalize(&Tensor::new(&[[3.0f32, 4.0]], &device).unwrap()).unwrap();
+        assert_eq!(normalized.to_vec2::<f32>().unwrap(), vec![vec![0.6, 0.8]]);
+    }
+
+    #[tokio::test]
+    async fn test_missing_model_file() {
+        let config = CandleConfig {
+            model_path: PathBuf::from("/nonexistent/model.gguf"),
+            ..CandleConfig::default()
+        };
+        let result = CandleEmbeddingProvider::with_config(config).await;
+        assert!(matches!(result, Err(EmbeddingError::ModelNotFound(_))));
+    }
+}
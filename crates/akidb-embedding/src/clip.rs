@@ -0,0 +1,609 @@
+//! CLIP-style multimodal embedding provider.
+//!
+//! Runs the text and vision towers of a CLIP model (e.g.
+//! `openai/clip-vit-base-patch32`) exported to ONNX as two models:
+//!
+//! - `text_model.onnx`: `input_ids`, `attention_mask` → `text_embeds`
+//! - `vision_model.onnx`: `pixel_values` → `image_embeds`
+//!
+//! Both project into the same space, so text and image vectors can live in
+//! one collection and a text query retrieves matching images. Images are
+//! resized (shortest side), center-cropped and normalized the way CLIP was
+//! trained. URL inputs are downloaded before preprocessing.
+//!
+//! Image URLs are fetched from the server, so they must not reach internal
+//! services: hosts resolving to private, loopback or link-local addresses
+//! are refused (unless listed in [`ClipConfig::allowed_image_hosts`]), the
+//! connection goes to the address that was checked, and redirects are not
+//! followed. Downloads and decoded images are size-capped.
+//!
+//! Sessions use the same execution providers and CPU fallback as
+//! [`OnnxEmbeddingProvider`](crate::OnnxEmbeddingProvider).
+
+use crate::onnx::{ActiveSession, ExecutionProviderConfig, OnnxEmbeddingProvider};
+use crate::{
+    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingInput,
+    EmbeddingProvider, EmbeddingResult, ImageInput, ModelInfo, MultimodalEmbeddingRequest, Usage,
+};
+use async_trait::async_trait;
+use image::imageops::FilterType;
+use ndarray::{Array2, Array4};
+use ort::value::Value;
+use parking_lot::Mutex;
+use std::io::Cursor;
+use std::net::{IpAddr, SocketAddr};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokenizers::Tokenizer;
+
+/// Per-channel mean of the CLIP training images (RGB).
+const CLIP_MEAN: [f32; 3] = [0.481_454_66, 0.457_827_5, 0.408_210_73];
+
+/// Per-channel standard deviation of the CLIP training images (RGB).
+const CLIP_STD: [f32; 3] = [0.268_629_54, 0.261_302_6, 0.275_777_1];
+
+/// Configuration for [`ClipEmbeddingProvider`].
+#[derive(Debug, Clone)]
+pub struct ClipConfig {
+    /// Path to the text tower ONNX model
+    pub text_model_path: PathBuf,
+    /// Path to the vision tower ONNX model
+    pub vision_model_path: PathBuf,
+    /// Path to tokenizer.json file
+    pub tokenizer_path: PathBuf,
+    /// Model name (for metadata)
+    pub model_name: String,
+    /// Output embedding dimension
+    pub dimension: u32,
+    /// Maximum text length in tokens (CLIP: 77)
+    pub max_length: usize,
+    /// Side of the square image the vision tower expects (CLIP: 224)
+    pub image_size: u32,
+    /// Largest image accepted, in bytes (downloaded or inline)
+    pub max_image_bytes: usize,
+    /// Largest width or height of a decoded image, in pixels
+    pub max_image_dimension: u32,
+    /// Timeout for downloading URL images
+    pub download_timeout: Duration,
+    /// Hosts image URLs may name; empty allows any host that resolves to
+    /// public addresses. Listed hosts may also resolve to private ones.
+    pub allowed_image_hosts: Vec<String>,
+    /// Execution provider
+    pub execution_provider: ExecutionProviderConfig,
+    /// Use the CPU if the execution provider fails to initialize
+    pub fallback_to_cpu: bool,
+}
+
+impl ClipConfig {
+    /// Config for a model directory containing `text_model.onnx`,
+    /// `vision_model.onnx` and `tokenizer.json`.
+    pub fn from_dir(dir: impl AsRef<Path>, model_name: impl Into<String>) -> Self {
+        let dir = dir.as_ref();
+        Self {
+            text_model_path: dir.join("text_model.onnx"),
+            vision_model_path: dir.join("vision_model.onnx"),
+            tokenizer_path: dir.join("tokenizer.json"),
+            model_name: model_name.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for ClipConfig {
+    fn default() -> Self {
+        Self {
+            text_model_path: PathBuf::from("models/clip/text_model.onnx"),
+            vision_model_path: PathBuf::from("models/clip/vision_model.onnx"),
+            tokenizer_path: PathBuf::from("models/clip/tokenizer.json"),
+            model_name: "openai/clip-vit-base-patch32".to_string(),
+            dimension: 512,
+            max_length: 77,
+            image_size: 224,
+            max_image_bytes: 20 * 1024 * 1024,
+            max_image_dimension: 8192,
+            download_timeout: Duration::from_secs(30),
+            allowed_image_hosts: Vec::new(),
+            execution_provider: ExecutionProviderConfig::CPU,
+            fallback_to_cpu: true,
+        }
+    }
+}
+
+/// Multimodal embedding provider running CLIP text and vision towers.
+pub struct ClipEmbeddingProvider {
+    text_session: Mutex<ActiveSession>,
+    vision_session: Mutex<ActiveSession>,
+    tokenizer: Tokenizer,
+    /// Tokenizer without padding or truncation, for counting input tokens
+    counter: Tokenizer,
+    config: ClipConfig,
+}
+
+impl ClipEmbeddingProvider {
+    /// Loads both towers and the tokenizer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a model or the tokenizer cannot be loaded.
+    pub fn with_config(config: ClipConfig) -> EmbeddingResult<Self> {
+        let text_session = OnnxEmbeddingProvider::create_session(
+            &config.text_model_path,
+            &config.execution_provider,
+            config.fallback_to_cpu,
+        )?;
+        let vision_session = OnnxEmbeddingProvider::create_session(
+            &config.vision_model_path,
+            &config.execution_provider,
+            config.fallback_to_cpu,
+        )?;
+        let tokenizer = Tokenizer::from_file(&config.tokenizer_path)
+            .map_err(|e| EmbeddingError::Internal(format!("Failed to load tokenizer: {}", e)))?;
+        eprintln!(
+            "✅ ClipEmbeddingProvider initialized\n   Model: {}\n   Dimension: {}\n   Execution provider: {}",
+            config.model_name,
+            config.dimension,
+            text_session.provider.name()
+        );
+
+        Ok(Self {
+            text_session: Mutex::new(text_session),
+            vision_session: Mutex::new(vision_session),
+            counter: crate::truncation::counting_tokenizer(&tokenizer),
+            tokenizer,
+            config,
+        })
+    }
+
+    fn embed_texts(&self, texts: &[String]) -> EmbeddingResult<(Vec<Vec<f32>>, usize)> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| EmbeddingError::Internal(format!("Tokenization failed: {}", e)))?;
+
+        let max_length = self.config.max_length;
+        let batch_size = encodings.len();
+        let mut input_ids = Vec::with_capacity(batch_size * max_length);
+        let mut attention_mask = Vec::with_capacity(batch_size * max_length);
+        let mut tokens = 0;
+
+        for encoding in &encodings {
+            let mut ids = encoding.get_ids().to_vec();
+            let mut mask = encoding.get_attention_mask().to_vec();
+            ids.truncate(max_length);
+            mask.truncate(max_length);
+            tokens += ids.len();
+            ids.resize(max_length, 0);
+            mask.resize(max_length, 0);
+            input_ids.extend(ids.iter().map(|&x| x as i64));
+            attention_mask.extend(mask.iter().map(|&x| x as i64));
+        }
+
+        let to_value = |data: Vec<i64>, name: &str| {
+            Array2::from_shape_vec((batch_size, max_length), data)
+                .map_err(|e| e.to_string())
+                .and_then(|array| Value::from_array(array).map_err(|e| e.to_string()))
+                .map_err(|e| {
+                    EmbeddingError::Internal(format!("Failed to create {} tensor: {}", name, e))
+                })
+        };
+        let input_ids = to_value(input_ids, "input_ids")?;
+        let attention_mask = to_value(attention_mask, "attention_mask")?;
+
+        let mut active = self.text_session.lock();
+        let outputs = active
+            .session
+            .run(ort::inputs![
+                "input_ids" => input_ids,
+                "attention_mask" => attention_mask
+            ])
+            .map_err(|e| EmbeddingError::Internal(format!("ONNX inference failed: {}", e)))?;
+        let embeddings = extract_rows(&outputs["text_embeds"], batch_size)?;
+        Ok((embeddings, tokens))
+    }
+
+    fn embed_pixels(&self, pixels: Vec<f32>, batch_size: usize) -> EmbeddingResult<Vec<Vec<f32>>> {
+        let size = self.config.image_size as usize;
+        let pixel_values = Array4::from_shape_vec((batch_size, 3, size, size), pixels)
+            .map_err(|e| e.to_string())
+            .and_then(|array| Value::from_array(array).map_err(|e| e.to_string()))
+            .map_err(|e| {
+                EmbeddingError::Internal(format!("Failed to create pixel_values tensor: {}", e))
+            })?;
+
+        let mut active = self.vision_session.lock();
+        let outputs = active
+            .session
+            .run(ort::inputs!["pixel_values" => pixel_values])
+            .map_err(|e| EmbeddingError::Internal(format!("ONNX inference failed: {}", e)))?;
+        extract_rows(&outputs["image_embeds"], batch_size)
+    }
+
+    /// Returns the encoded image, downloading it if given by URL.
+    async fn load_image(&self, image: &ImageInput) -> EmbeddingResult<Vec<u8>> {
+        let bytes = match image {
+            ImageInput::Bytes(bytes) => bytes.clone(),
+            ImageInput::Url(url) => self.download_image(url).await?,
+        };
+
+        if bytes.len() > self.config.max_image_bytes {
+            return Err(self.too_large());
+        }
+        Ok(bytes)
+    }
+
+    /// Downloads an image URL, at most `max_image_bytes` of it.
+    ///
+    /// Errors do not repeat the URL, which may carry credentials.
+    async fn download_image(&self, url: &str) -> EmbeddingResult<Vec<u8>> {
+        let url = reqwest::Url::parse(url)
+            .map_err(|_| EmbeddingError::InvalidInput("Invalid image URL".to_string()))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(EmbeddingError::InvalidInput(
+                "Unsupported image URL (expected http or https)".to_string(),
+            ));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| EmbeddingError::InvalidInput("Image URL has no host".to_string()))?
+            .to_string();
+        let port = url.port_or_known_default().unwrap_or(80);
+
+        let allowlisted = self
+            .config
+            .allowed_image_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&host));
+        if !self.config.allowed_image_hosts.is_empty() && !allowlisted {
+            return Err(EmbeddingError::InvalidInput(
+                "Image URL host is not allowed".to_string(),
+            ));
+        }
+
+        // Resolve once and connect to the checked addresses, so a second
+        // lookup cannot return a different (internal) one
+        let literal = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .ok();
+        let addrs: Vec<SocketAddr> = match literal {
+            Some(ip) => vec![SocketAddr::new(ip, port)],
+            None => tokio::net::lookup_host((host.as_str(), port))
+                .await
+                .map_err(|_| {
+                    EmbeddingError::InvalidInput("Failed to resolve image URL host".to_string())
+                })?
+                .collect(),
+        };
+        if addrs.is_empty() || !(allowlisted || addrs.iter().all(|addr| is_public(addr.ip()))) {
+            return Err(EmbeddingError::InvalidInput(
+                "Image URL resolves to a non-public address".to_string(),
+            ));
+        }
+
+        let mut client = reqwest::Client::builder()
+            .timeout(self.config.download_timeout)
+            .redirect(reqwest::redirect::Policy::none());
+        if literal.is_none() {
+            client = client.resolve_to_addrs(&host, &addrs);
+        }
+        let client = client
+            .build()
+            .map_err(|e| EmbeddingError::Internal(format!("Failed to build HTTP client: {}", e)))?;
+
+        let download_error = |e: reqwest::Error| {
+            EmbeddingError::InvalidInput(format!("Failed to download image: {}", e.without_url()))
+        };
+        let mut response = client.get(url).send().await.map_err(download_error)?;
+        if !response.status().is_success() {
+            return Err(EmbeddingError::InvalidInput(format!(
+                "Failed to download image: HTTP {}",
+                response.status()
+            )));
+        }
+        let max = self.config.max_image_bytes;
+        if response
+            .content_length()
+            .is_some_and(|length| length > max as u64)
+        {
+            return Err(self.too_large());
+        }
+
+        // Content-Length may be absent or wrong; count as the body arrives
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(download_error)? {
+            if bytes.len() + chunk.len() > max {
+                return Err(self.too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
+    fn too_large(&self) -> EmbeddingError {
+        EmbeddingError::InvalidInput(format!(
+            "Image exceeds the maximum of {} bytes",
+            self.config.max_image_bytes
+        ))
+    }
+}
+
+/// Whether `ip` is a public unicast address, not one of a private network,
+/// the host itself, or link-local (e.g. cloud metadata services).
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                // Carrier-grade NAT (100.64.0.0/10)
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    // Unique local (fc00::/7) and link-local (fe80::/10)
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// Decodes an image of at most `max_dimension` pixels per side and
+/// converts it to normalized CHW pixels of `size` x `size`.
+fn preprocess_image(bytes: &[u8], size: u32, max_dimension: u32) -> EmbeddingResult<Vec<f32>> {
+    let decode_error = |e: image::ImageError| {
+        EmbeddingError::InvalidInput(format!("Failed to decode image: {}", e))
+    };
+    let mut reader = image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| EmbeddingError::InvalidInput(format!("Failed to decode image: {}", e)))?;
+    // A small compressed file can declare huge dimensions
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(max_dimension);
+    limits.max_image_height = Some(max_dimension);
+    limits.max_alloc = Some(u64::from(max_dimension) * u64::from(max_dimension) * 4);
+    reader.limits(limits);
+    let image = reader.decode().map_err(decode_error)?;
+
+    // Resize the shortest side to `size`, then center-crop
+    let (width, height) = (image.width().max(1), image.height().max(1));
+    let scale = size as f32 / width.min(height) as f32;
+    let resized_width = ((width as f32 * scale).round() as u32).max(size);
+    let resized_height = ((height as f32 * scale).round() as u32).max(size);
+    let rgb = image
+        .resize_exact(resized_width, resized_height, FilterType::CatmullRom)
+        .crop_imm(
+            (resized_width - size) / 2,
+            (resized_height - size) / 2,
+            size,
+            size,
+        )
+        .to_rgb8();
+
+    let plane = (size * size) as usize;
+    let mut pixels = vec![0.0f32; 3 * plane];
+    for (i, pixel) in rgb.pixels().enumerate() {
+        for channel in 0..3 {
+            let value = pixel[channel] as f32 / 255.0;
+            pixels[channel * plane + i] = (value - CLIP_MEAN[channel]) / CLIP_STD[channel];
+        }
+    }
+    Ok(pixels)
+}
+
+/// Splits a `[batch, dim]` output tensor into rows.
+fn extract_rows(value: &ort::value::DynValue, batch_size: usize) -> EmbeddingResult<Vec<Vec<f32>>> {
+    let (shape, data) = value
+        .try_extract_tensor::<f32>()
+        .map_err(|e| EmbeddingError::Internal(format!("Failed to extract output: {}", e)))?;
+    if shape.len() != 2 || shape[0] as usize != batch_size {
+        return Err(EmbeddingError::Internal(format!(
+            "Unexpected output shape: {:?}",
+            shape
+        )));
+    }
+    Ok(data
+        .chunks(shape[1] as usize)
+        .map(<[f32]>::to_vec)
+        .collect())
+}
+
+fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt().max(1e-12);
+    for value in vector {
+        *value /= norm;
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for ClipEmbeddingProvider {
+    async fn embed_batch(
+        &self,
+        request: BatchEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        self.embed_multimodal(MultimodalEmbeddingRequest {
+            model: request.model,
+            inputs: request
+                .inputs
+                .into_iter()
+                .map(EmbeddingInput::Text)
+                .collect(),
+            normalize: request.normalize,
+        })
+        .await
+    }
+
+    async fn model_info(&self) -> EmbeddingResult<ModelInfo> {
+        Ok(ModelInfo {
+            model: self.config.model_name.clone(),
+            dimension: self.config.dimension,
+            max_tokens: self.config.max_length,
+            execution_provider: Some(self.text_session.lock().provider.name().to_string()),
+        })
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<Range<usize>> {
+        crate::truncation::tokenizer_spans(&self.counter, text)
+    }
+
+    async fn health_check(&self) -> EmbeddingResult<()> {
+        let (embeddings, _) = self.embed_texts(&["health check".to_string()])?;
+        if embeddings.first().map(Vec::len) == Some(self.config.dimension as usize) {
+            Ok(())
+        } else {
+            Err(EmbeddingError::Internal(
+                "CLIP text tower returned an unexpected dimension".to_string(),
+            ))
+        }
+    }
+
+    async fn embed_multimodal(
+        &self,
+        request: MultimodalEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        if request.inputs.is_empty() {
+            return Err(EmbeddingError::InvalidInput("Empty input list".to_string()));
+        }
+        let start = Instant::now();
+
+        // Split by modality, remembering each input's position
+        let mut texts = Vec::new();
+        let mut pixels = Vec::new();
+        let mut order = Vec::with_capacity(request.inputs.len());
+        for input in &request.inputs {
+            match input {
+                EmbeddingInput::Text(text) => {
+                    order.push((true, texts.len()));
+                    texts.push(text.clone());
+                }
+                EmbeddingInput::Image(image) => {
+                    let bytes = self.load_image(image).await?;
+                    let size = self.config.image_size;
+                    let max_dimension = self.config.max_image_dimension;
+                    let image_pixels = tokio::task::spawn_blocking(move || {
+                        preprocess_image(&bytes, size, max_dimension)
+                    })
+                    .await
+                    .map_err(|e| {
+                        EmbeddingError::Internal(format!("Preprocessing failed: {}", e))
+                    })??;
+                    order.push((false, pixels.len()));
+                    pixels.push(image_pixels);
+                }
+            }
+        }
+
+        let (text_embeddings, total_tokens) = if texts.is_empty() {
+            (Vec::new(), 0)
+        } else {
+            self.embed_texts(&texts)?
+        };
+        let image_embeddings = if pixels.is_empty() {
+            Vec::new()
+        } else {
+            let count = pixels.len();
+            self.embed_pixels(pixels.concat(), count)?
+        };
+
+        let embeddings = order
+            .into_iter()
+            .map(|(is_text, index)| {
+                let mut embedding = if is_text {
+                    text_embeddings[index].clone()
+                } else {
+                    image_embeddings[index].clone()
+                };
+                if request.normalize {
+                    l2_normalize(&mut embedding);
+                }
+                embedding
+            })
+            .collect();
+
+        Ok(BatchEmbeddingResponse {
+            model: self.config.model_name.clone(),
+            embeddings,
+            usage: Usage {
+                total_tokens,
+                duration_ms: start.elapsed().as_millis() as u64,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgb, RgbImage};
+
+    fn png(width: u32, height: u32, color: [u8; 3]) -> Vec<u8> {
+        let image = RgbImage::from_pixel(width, height, Rgb(color));
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_preprocess_resizes_crops_and_normalizes() {
+        let pixels = preprocess_image(&png(64, 32, [255, 0, 0]), 16, 8192).unwrap();
+        assert_eq!(pixels.len(), 3 * 16 * 16);
+
+        // Solid red: every pixel of a channel plane has the same value
+        let plane = 16 * 16;
+        let red = (1.0 - CLIP_MEAN[0]) / CLIP_STD[0];
+        let green = (0.0 - CLIP_MEAN[1]) / CLIP_STD[1];
+        assert!(pixels[..plane].iter().all(|v| (v - red).abs() < 1e-3));
+        assert!(pixels[plane..2 * plane]
+            .iter()
+            .all(|v| (v - green).abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_preprocess_rejects_invalid_images() {
+        let result = preprocess_image(b"not an image", 16, 8192);
+        assert!(matches!(result, Err(EmbeddingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_preprocess_rejects_oversized_dimensions() {
+        let result = preprocess_image(&png(64, 32, [255, 0, 0]), 16, 48);
+        assert!(matches!(result, Err(EmbeddingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_only_public_addresses_are_fetched() {
+        for ip in ["93.184.216.34", "2606:2800:220:1::248"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.5.4",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+}
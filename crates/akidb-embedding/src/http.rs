@@ -0,0 +1,810 @@
+//! Generic HTTP embedding provider.
+//!
+//! Calls any JSON-over-HTTP embedding service described by a request template
+//! and a response path, so in-house services can be plugged in through
+//! configuration alone. The defaults match the OpenAI embeddings API:
+//!
+//! ```json
+//! {"model": "{{model}}", "input": "{{inputs}}"}
+//! ```
+//!
+//! with embeddings read from `/data/*/embedding`.
+//!
+//! Transient failures (connection errors, timeouts, `429` and `5xx`) are
+//! retried with exponential backoff; other `4xx` responses fail immediately.
+//!
+//! [`HttpRerankProvider`] calls remote rerank APIs the same way; its defaults
+//! match the Cohere/Jina rerank API.
+
+use crate::provider::{EmbeddingProvider, RerankProvider};
+use crate::types::{
+    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingResult, ModelInfo,
+    RerankRequest, RerankResponse, Usage,
+};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Placeholder replaced by the input texts (as a JSON array).
+pub const INPUTS_PLACEHOLDER: &str = "{{inputs}}";
+
+/// Placeholder replaced by the model name.
+pub const MODEL_PLACEHOLDER: &str = "{{model}}";
+
+/// Placeholder replaced by the rerank query.
+pub const QUERY_PLACEHOLDER: &str = "{{query}}";
+
+/// Placeholder replaced by the documents to rerank (as a JSON array).
+pub const DOCUMENTS_PLACEHOLDER: &str = "{{documents}}";
+
+/// Configuration for [`HttpEmbeddingProvider`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpProviderConfig {
+    /// Endpoint receiving the POST request
+    pub url: String,
+    /// Model name sent as `{{model}}` and reported by `model_info`
+    #[serde(default)]
+    pub model: String,
+    /// Output dimension; probed with a test request when absent
+    #[serde(default)]
+    pub dimension: Option<u32>,
+    /// Maximum input tokens reported by `model_info` (default: 512)
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: usize,
+    /// Request body; `"{{inputs}}"` and `"{{model}}"` are substituted
+    #[serde(default = "default_request_template")]
+    pub request_template: Value,
+    /// JSON pointer to the embeddings; one `*` segment maps over an array
+    #[serde(default = "default_response_path")]
+    pub response_path: String,
+    /// Header carrying credentials (default: `Authorization`)
+    #[serde(default = "default_auth_header")]
+    pub auth_header: String,
+    /// Value of the auth header, e.g. `Bearer sk-...`; omitted when absent
+    #[serde(default)]
+    pub auth_value: Option<String>,
+    /// Extra request headers
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Per-attempt timeout in milliseconds (default: 30000)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Retries after the first attempt for transient failures (default: 3)
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled for each further retry (default: 200)
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+}
+
+fn default_max_tokens() -> usize {
+    512
+}
+
+fn default_request_template() -> Value {
+    serde_json::json!({"model": MODEL_PLACEHOLDER, "input": INPUTS_PLACEHOLDER})
+}
+
+fn default_response_path() -> String {
+    "/data/*/embedding".to_string()
+}
+
+fn default_auth_header() -> String {
+    "Authorization".to_string()
+}
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    200
+}
+
+impl HttpProviderConfig {
+    /// Creates a config with OpenAI-compatible defaults.
+    pub fn new(url: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            model: model.into(),
+            dimension: None,
+            max_tokens: default_max_tokens(),
+            request_template: default_request_template(),
+            response_path: default_response_path(),
+            auth_header: default_auth_header(),
+            auth_value: None,
+            headers: HashMap::new(),
+            timeout_ms: default_timeout_ms(),
+            max_retries: default_max_retries(),
+            retry_backoff_ms: default_retry_backoff_ms(),
+        }
+    }
+}
+
+/// Embedding provider calling a configurable HTTP endpoint.
+pub struct HttpEmbeddingProvider {
+    client: reqwest::Client,
+    config: HttpProviderConfig,
+    /// Configured or probed output dimension
+    dimension: Mutex<Option<u32>>,
+}
+
+/// Outcome of one HTTP attempt.
+enum AttemptError {
+    /// Worth retrying (connection error, timeout, 429, 5xx)
+    Transient(String),
+    /// Retrying will not help
+    Permanent(EmbeddingError),
+}
+
+impl HttpEmbeddingProvider {
+    /// Creates a provider; no request is made until the first embedding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the auth or extra headers are invalid.
+    pub fn new(config: HttpProviderConfig) -> EmbeddingResult<Self> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let auth = config
+            .auth_value
+            .as_ref()
+            .map(|value| (&config.auth_header, value));
+        for (name, value) in config.headers.iter().chain(auth) {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| EmbeddingError::InvalidInput(format!("Invalid header name: {}", e)))?;
+            let value = reqwest::header::HeaderValue::from_str(value).map_err(|e| {
+                EmbeddingError::InvalidInput(format!("Invalid value for header {}: {}", name, e))
+            })?;
+            headers.insert(name, value);
+        }
+
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .map_err(|e| EmbeddingError::Internal(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            dimension: Mutex::new(config.dimension),
+            config,
+        })
+    }
+
+    /// Builds the request body for `inputs`.
+    fn render_request(&self, inputs: &[String]) -> Value {
+        render_template(&self.config.request_template, &self.config.model, inputs)
+    }
+
+    async fn attempt(&self, body: &Value) -> Result<Value, AttemptError> {
+        let response = self
+            .client
+            .post(&self.config.url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| AttemptError::Transient(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return response
+                .json::<Value>()
+                .await
+                .map_err(|e| AttemptError::Transient(format!("Invalid response body: {}", e)));
+        }
+
+        let text = response.text().await.unwrap_or_default();
+        let message = format!("{} returned {}: {}", self.config.url, status, text);
+        if status.as_u16() == 429 || status.is_server_error() {
+            Err(AttemptError::Transient(message))
+        } else {
+            Err(AttemptError::Permanent(EmbeddingError::InvalidInput(
+                message,
+            )))
+        }
+    }
+
+    /// Sends `body`, retrying transient failures.
+    async fn send(&self, body: &Value) -> EmbeddingResult<Value> {
+        let mut backoff = Duration::from_millis(self.config.retry_backoff_ms);
+        let mut attempt = 0;
+        loop {
+            match self.attempt(body).await {
+                Ok(value) => return Ok(value),
+                Err(AttemptError::Permanent(e)) => return Err(e),
+                Err(AttemptError::Transient(message)) if attempt < self.config.max_retries => {
+                    attempt += 1;
+                    tracing::warn!(
+                        url = %self.config.url,
+                        attempt,
+                        "Embedding request failed, retrying: {}",
+                        message
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = backoff.saturating_mul(2);
+                }
+                Err(AttemptError::Transient(message)) => {
+                    return Err(EmbeddingError::ServiceUnavailable(message))
+                }
+            }
+        }
+    }
+
+    async fn embed(&self, inputs: &[String]) -> EmbeddingResult<Vec<Vec<f32>>> {
+        let response = self.send(&self.render_request(inputs)).await?;
+        let embeddings = extract_embeddings(&response, &self.config.response_path)?;
+        if embeddings.len() != inputs.len() {
+            return Err(EmbeddingError::Internal(format!(
+                "Expected {} embeddings, got {}",
+                inputs.len(),
+                embeddings.len()
+            )));
+        }
+        Ok(embeddings)
+    }
+}
+
+/// Substitutes the placeholders in `template`.
+fn render_template(template: &Value, model: &str, inputs: &[String]) -> Value {
+    substitute(template, model, &|placeholder| {
+        (placeholder == INPUTS_PLACEHOLDER).then(|| string_array(inputs))
+    })
+}
+
+/// Replaces strings for which `value_for` returns a value, and `{{model}}`
+/// inside any other string.
+fn substitute(template: &Value, model: &str, value_for: &dyn Fn(&str) -> Option<Value>) -> Value {
+    match template {
+        Value::String(s) => {
+            value_for(s).unwrap_or_else(|| Value::String(s.replace(MODEL_PLACEHOLDER, model)))
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| substitute(item, model, value_for))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), substitute(v, model, value_for)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn string_array(items: &[String]) -> Value {
+    Value::Array(items.iter().cloned().map(Value::String).collect())
+}
+
+/// Reads the embeddings at `path` (a JSON pointer with at most one `*`).
+fn extract_embeddings(response: &Value, path: &str) -> EmbeddingResult<Vec<Vec<f32>>> {
+    let missing = || EmbeddingError::Internal(format!("Response has no embeddings at {}", path));
+    let vectors: Vec<&Value> = match path.split_once("/*") {
+        Some((array_path, item_path)) => response
+            .pointer(array_path)
+            .and_then(Value::as_array)
+            .ok_or_else(missing)?
+            .iter()
+            .map(|item| item.pointer(item_path).ok_or_else(missing))
+            .collect::<EmbeddingResult<_>>()?,
+        None => response
+            .pointer(path)
+            .and_then(Value::as_array)
+            .ok_or_else(missing)?
+            .iter()
+            .collect(),
+    };
+
+    vectors
+        .into_iter()
+        .map(|vector| {
+            vector
+                .as_array()
+                .and_then(|values| {
+                    values
+                        .iter()
+                        .map(|v| v.as_f64().map(|f| f as f32))
+                        .collect::<Option<Vec<f32>>>()
+                })
+                .ok_or_else(|| {
+                    EmbeddingError::Internal("Embedding is not an array of numbers".to_string())
+                })
+        })
+        .collect()
+}
+
+fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for HttpEmbeddingProvider {
+    async fn embed_batch(
+        &self,
+        request: BatchEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        if request.inputs.is_empty() {
+            return Err(EmbeddingError::InvalidInput(
+                "Input list cannot be empty".to_string(),
+            ));
+        }
+
+        let start = Instant::now();
+        let mut embeddings = self.embed(&request.inputs).await?;
+        if request.normalize {
+            embeddings.iter_mut().for_each(|e| l2_normalize(e));
+        }
+        if let Some(first) = embeddings.first() {
+            self.dimension.lock().get_or_insert(first.len() as u32);
+        }
+
+        // Rough estimate: 1 token ~= 4 characters
+        let total_tokens = request.inputs.iter().map(|s| s.len().div_ceil(4)).sum();
+
+        Ok(BatchEmbeddingResponse {
+            model: self.config.model.clone(),
+            embeddings,
+            usage: Usage {
+                total_tokens,
+                duration_ms: start.elapsed().as_millis() as u64,
+            },
+        })
+    }
+
+    async fn model_info(&self) -> EmbeddingResult<ModelInfo> {
+        let known = *self.dimension.lock();
+        let dimension = match known {
+            Some(dimension) => dimension,
+            None => {
+                let probe = self.embed(&["dimension probe".to_string()]).await?;
+                let dimension = probe.first().map_or(0, |e| e.len() as u32);
+                *self.dimension.lock() = Some(dimension);
+                dimension
+            }
+        };
+
+        Ok(ModelInfo {
+            model: self.config.model.clone(),
+            dimension,
+            max_tokens: self.config.max_tokens,
+            execution_provider: None,
+        })
+    }
+
+    async fn health_check(&self) -> EmbeddingResult<()> {
+        self.embed(&["health check".to_string()]).await.map(|_| ())
+    }
+}
+
+/// Configuration for [`HttpRerankProvider`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRerankConfig {
+    /// Endpoint receiving the POST request
+    pub url: String,
+    /// Model name sent as `{{model}}`
+    #[serde(default)]
+    pub model: String,
+    /// Request body; `"{{query}}"`, `"{{documents}}"` and `"{{model}}"` are substituted
+    #[serde(default = "default_rerank_template")]
+    pub request_template: Value,
+    /// JSON pointer to the array of results
+    #[serde(default = "default_results_path")]
+    pub results_path: String,
+    /// JSON pointer, within a result, to the document index; results are
+    /// taken in request order when empty (default: `/index`)
+    #[serde(default = "default_index_pointer")]
+    pub index_pointer: String,
+    /// JSON pointer, within a result, to the score (default: `/relevance_score`)
+    #[serde(default = "default_score_pointer")]
+    pub score_pointer: String,
+    /// Header carrying credentials (default: `Authorization`)
+    #[serde(default = "default_auth_header")]
+    pub auth_header: String,
+    /// Value of the auth header, e.g. `Bearer sk-...`; omitted when absent
+    #[serde(default)]
+    pub auth_value: Option<String>,
+    /// Extra request headers
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Per-attempt timeout in milliseconds (default: 30000)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Retries after the first attempt for transient failures (default: 3)
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled for each further retry (default: 200)
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+}
+
+fn default_rerank_template() -> Value {
+    serde_json::json!({
+        "model": MODEL_PLACEHOLDER,
+        "query": QUERY_PLACEHOLDER,
+        "documents": DOCUMENTS_PLACEHOLDER
+    })
+}
+
+fn default_results_path() -> String {
+    "/results".to_string()
+}
+
+fn default_index_pointer() -> String {
+    "/index".to_string()
+}
+
+fn default_score_pointer() -> String {
+    "/relevance_score".to_string()
+}
+
+impl HttpRerankConfig {
+    /// Creates a config with Cohere-compatible defaults.
+    pub fn new(url: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            model: model.into(),
+            request_template: default_rerank_template(),
+            results_path: default_results_path(),
+            index_pointer: default_index_pointer(),
+            score_pointer: default_score_pointer(),
+            auth_header: default_auth_header(),
+            auth_value: None,
+            headers: HashMap::new(),
+            timeout_ms: default_timeout_ms(),
+            max_retries: default_max_retries(),
+            retry_backoff_ms: default_retry_backoff_ms(),
+        }
+    }
+}
+
+/// Reranker calling a configurable HTTP endpoint.
+pub struct HttpRerankProvider {
+    /// Shares connection handling, auth and retries with the embedding provider
+    transport: HttpEmbeddingProvider,
+    config: HttpRerankConfig,
+}
+
+impl HttpRerankProvider {
+    /// Creates a provider; no request is made until the first rerank.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the auth or extra headers are invalid.
+    pub fn new(config: HttpRerankConfig) -> EmbeddingResult<Self> {
+        let transport = HttpEmbeddingProvider::new(HttpProviderConfig {
+            auth_header: config.auth_header.clone(),
+            auth_value: config.auth_value.clone(),
+            headers: config.headers.clone(),
+            timeout_ms: config.timeout_ms,
+            max_retries: config.max_retries,
+            retry_backoff_ms: config.retry_backoff_ms,
+            ..HttpProviderConfig::new(config.url.clone(), config.model.clone())
+        })?;
+        Ok(Self { transport, config })
+    }
+
+    fn render_request(&self, query: &str, documents: &[String]) -> Value {
+        substitute(
+            &self.config.request_template,
+            &self.config.model,
+            &|placeholder| match placeholder {
+                QUERY_PLACEHOLDER => Some(Value::String(query.to_string())),
+                DOCUMENTS_PLACEHOLDER => Some(string_array(documents)),
+                _ => None,
+            },
+        )
+    }
+}
+
+/// Reads one score per document from a rerank response.
+fn extract_scores(
+    response: &Value,
+    config: &HttpRerankConfig,
+    documents: usize,
+) -> EmbeddingResult<Vec<f32>> {
+    let invalid = |message: String| EmbeddingError::Internal(message);
+    let results = response
+        .pointer(&config.results_path)
+        .and_then(Value::as_array)
+        .ok_or_else(|| {
+            invalid(format!(
+                "Response has no results at {}",
+                config.results_path
+            ))
+        })?;
+
+    let mut scores: Vec<Option<f32>> = vec![None; documents];
+    for (position, result) in results.iter().enumerate() {
+        let index = if config.index_pointer.is_empty() {
+            position
+        } else {
+            result
+                .pointer(&config.index_pointer)
+                .and_then(Value::as_u64)
+                .ok_or_else(|| {
+                    invalid(format!("Result has no index at {}", config.index_pointer))
+                })? as usize
+        };
+        let score = result
+            .pointer(&config.score_pointer)
+            .and_then(Value::as_f64)
+            .ok_or_else(|| invalid(format!("Result has no score at {}", config.score_pointer)))?;
+        let slot = scores
+            .get_mut(index)
+            .ok_or_else(|| invalid(format!("Result index {} out of range", index)))?;
+        *slot = Some(score as f32);
+    }
+
+    scores
+        .into_iter()
+        .enumerate()
+        .map(|(i, score)| score.ok_or_else(|| invalid(format!("No score for document {}", i))))
+        .collect()
+}
+
+#[async_trait]
+impl RerankProvider for HttpRerankProvider {
+    async fn rerank(&self, request: RerankRequest) -> EmbeddingResult<RerankResponse> {
+        if request.query.trim().is_empty() {
+            return Err(EmbeddingError::InvalidInput(
+                "Query cannot be empty".to_string(),
+            ));
+        }
+        if request.documents.is_empty() {
+            return Ok(RerankResponse {
+                model: self.config.model.clone(),
+                scores: Vec::new(),
+                usage: Usage {
+                    total_tokens: 0,
+                    duration_ms: 0,
+                },
+            });
+        }
+
+        let start = Instant::now();
+        let body = self.render_request(&request.query, &request.documents);
+        let response = self.transport.send(&body).await?;
+        let scores = extract_scores(&response, &self.config, request.documents.len())?;
+
+        // Rough estimate: 1 token ~= 4 characters, query repeated per document
+        let query_tokens = request.query.len().div_ceil(4);
+        let total_tokens = request
+            .documents
+            .iter()
+            .map(|d| query_tokens + d.len().div_ceil(4))
+            .sum();
+
+        Ok(RerankResponse {
+            model: self.config.model.clone(),
+            scores,
+            usage: Usage {
+                total_tokens,
+                duration_ms: start.elapsed().as_millis() as u64,
+            },
+        })
+    }
+
+    async fn health_check(&self) -> EmbeddingResult<()> {
+        self.rerank(RerankRequest {
+            model: self.config.model.clone(),
+            query: "health check".to_string(),
+            documents: vec!["health check".to_string()],
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TruncationStrategy;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves `responses` (status, body) in order, one per connection.
+    async fn serve(responses: Vec<(u16, String)>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/embed", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        tokio::spawn(async move {
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 64 * 1024];
+                let _ = socket.read(&mut buf).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                let response = format!(
+                    "HTTP/1.1 {} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, hits)
+    }
+
+    fn config(url: String) -> HttpProviderConfig {
+        HttpProviderConfig {
+            retry_backoff_ms: 1,
+            ..HttpProviderConfig::new(url, "test-model")
+        }
+    }
+
+    #[test]
+    fn test_render_template() {
+        let template = serde_json::json!({
+            "model": "{{model}}",
+            "payload": {"texts": "{{inputs}}", "tag": "m={{model}}"},
+            "truncate": true
+        });
+        let rendered = render_template(&template, "m1", &["a".to_string(), "b".to_string()]);
+        assert_eq!(
+            rendered,
+            serde_json::json!({
+                "model": "m1",
+                "payload": {"texts": ["a", "b"], "tag": "m=m1"},
+                "truncate": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_extract_embeddings() {
+        let openai =
+            serde_json::json!({"data": [{"embedding": [1.0, 2.0]}, {"embedding": [3.0, 4.0]}]});
+        assert_eq!(
+            extract_embeddings(&openai, "/data/*/embedding").unwrap(),
+            vec![vec![1.0, 2.0], vec![3.0, 4.0]]
+        );
+
+        let flat = serde_json::json!({"vectors": [[0.5], [0.25]]});
+        assert_eq!(
+            extract_embeddings(&flat, "/vectors").unwrap(),
+            vec![vec![0.5], vec![0.25]]
+        );
+
+        assert!(extract_embeddings(&flat, "/missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let (url, hits) = serve(vec![
+            (503, "{}".to_string()),
+            (200, r#"{"data": [{"embedding": [3.0, 4.0]}]}"#.to_string()),
+        ])
+        .await;
+        let provider = HttpEmbeddingProvider::new(config(url)).unwrap();
+
+        let response = provider
+            .embed_batch(BatchEmbeddingRequest {
+                model: "test-model".to_string(),
+                inputs: vec!["hello".to_string()],
+                normalize: true,
+                truncation: TruncationStrategy::default(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.embeddings, vec![vec![0.6, 0.8]]);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        assert_eq!(provider.model_info().await.unwrap().dimension, 2);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let (url, hits) = serve(vec![(400, r#"{"error": "bad"}"#.to_string())]).await;
+        let provider = HttpEmbeddingProvider::new(config(url)).unwrap();
+
+        let result = provider
+            .embed_batch(BatchEmbeddingRequest {
+                model: "test-model".to_string(),
+                inputs: vec!["hello".to_string()],
+                normalize: false,
+                truncation: TruncationStrategy::default(),
+            })
+            .await;
+        assert!(matches!(result, Err(EmbeddingError::InvalidInput(_))));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_text_only_provider_rejects_images() {
+        let (url, hits) = serve(vec![(
+            200,
+            r#"{"data": [{"embedding": [1.0, 0.0]}]}"#.to_string(),
+        )])
+        .await;
+        let provider = HttpEmbeddingProvider::new(config(url)).unwrap();
+        let request = |inputs| crate::MultimodalEmbeddingRequest {
+            model: "test-model".to_string(),
+            inputs,
+            normalize: false,
+        };
+
+        let image = crate::EmbeddingInput::Image(crate::ImageInput::Url(
+            "https://example.com/cat.png".to_string(),
+        ));
+        let result = provider.embed_multimodal(request(vec![image])).await;
+        assert!(matches!(result, Err(EmbeddingError::InvalidInput(_))));
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+
+        // Text-only requests go through embed_batch
+        let text = crate::EmbeddingInput::Text("hello".to_string());
+        let response = provider
+            .embed_multimodal(request(vec![text]))
+            .await
+            .unwrap();
+        assert_eq!(response.embeddings, vec![vec![1.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_extract_rerank_scores() {
+        let config = HttpRerankConfig::new("http://localhost/rerank", "rerank-model");
+        let response = serde_json::json!({
+            "results": [
+                {"index": 1, "relevance_score": 0.9},
+                {"index": 0, "relevance_score": 0.2}
+            ]
+        });
+        assert_eq!(
+            extract_scores(&response, &config, 2).unwrap(),
+            vec![0.2, 0.9]
+        );
+
+        // A missing document is an error
+        assert!(extract_scores(&response, &config, 3).is_err());
+
+        // Bare arrays in request order, e.g. [{"score": ...}]
+        let config = HttpRerankConfig {
+            results_path: String::new(),
+            index_pointer: String::new(),
+            score_pointer: "/score".to_string(),
+            ..config
+        };
+        let response = serde_json::json!([{"score": 0.5}, {"score": 0.1}]);
+        assert_eq!(
+            extract_scores(&response, &config, 2).unwrap(),
+            vec![0.5, 0.1]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_reranker_round_trip() {
+        let body =
+            r#"{"results":[{"index":0,"relevance_score":0.1},{"index":1,"relevance_score":0.8}]}"#;
+        let (url, hits) = serve(vec![(200, body.to_string())]).await;
+        let provider = HttpRerankProvider::new(HttpRerankConfig::new(url, "rerank-model")).unwrap();
+
+        let rendered = provider.render_request("q", &["a".to_string()]);
+        assert_eq!(
+            rendered,
+            serde_json::json!({"model": "rerank-model", "query": "q", "documents": ["a"]})
+        );
+
+        let response = provider
+            .rerank(RerankRequest {
+                model: "rerank-model".to_string(),
+                query: "what is akidb".to_string(),
+                documents: vec!["a cat".to_string(), "a vector database".to_string()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.scores, vec![0.1, 0.8]);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+}
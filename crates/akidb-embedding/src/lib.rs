@@ -1,7 +1,7 @@
 //! Embedding service infrastructure for AkiDB 2.0.
 //!
 //! This crate provides trait definitions and implementations for text embedding generation.
-//! The architecture supports multiple backends (MLX, ONNX, etc.) through the `EmbeddingProvider` trait.
+//! The architecture supports multiple backends (MLX, ONNX, Candle, etc.) through the `EmbeddingProvider` trait.
 //!
 //! # Bug Fix #5: Feature-gated MLX
 //!
@@ -9,25 +9,44 @@
 //! Enable with: `cargo build --features mlx` (enabled by default)
 //! Disable for Python-free builds: `cargo build --no-default-features`
 
+#[cfg(feature = "candle")]
+mod candle;
+#[cfg(feature = "clip")]
+mod clip;
+#[cfg(feature = "http")]
+mod http;
 #[cfg(feature = "mlx")]
 mod mlx;
 #[cfg(feature = "onnx")]
 mod onnx;
+#[cfg(feature = "onnx")]
+mod onnx_rerank;
 #[cfg(feature = "python-bridge")]
 mod python_bridge;
 mod mock;
 mod provider;
+mod truncation;
 mod types;
 
+#[cfg(feature = "candle")]
+pub use candle::{CandleConfig, CandleEmbeddingProvider};
+#[cfg(feature = "clip")]
+pub use clip::{ClipConfig, ClipEmbeddingProvider};
+#[cfg(feature = "http")]
+pub use http::{HttpEmbeddingProvider, HttpProviderConfig, HttpRerankConfig, HttpRerankProvider};
 #[cfg(feature = "mlx")]
 pub use mlx::MlxEmbeddingProvider;
 #[cfg(feature = "onnx")]
 pub use onnx::{ExecutionProviderConfig, OnnxConfig, OnnxEmbeddingProvider};
+#[cfg(feature = "onnx")]
+pub use onnx_rerank::{OnnxRerankConfig, OnnxRerankProvider};
 #[cfg(feature = "python-bridge")]
 pub use python_bridge::PythonBridgeProvider;
-pub use mock::MockEmbeddingProvider;
-pub use provider::EmbeddingProvider;
+pub use mock::{MockEmbeddingProvider, MockRerankProvider};
+pub use provider::{EmbeddingProvider, RerankProvider};
+pub use truncation::{estimate_token_spans, prepare_inputs, PreparedInputs, TruncatingProvider};
 pub use types::{
-    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingResult, ModelInfo,
-    Usage,
+    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingInput,
+    EmbeddingResult, ImageInput, ModelInfo, MultimodalEmbeddingRequest, RerankRequest,
+    RerankResponse, TruncationStrategy, Usage,
 };
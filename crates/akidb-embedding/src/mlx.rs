@@ -202,6 +202,7 @@ impl EmbeddingProvider for MlxEmbeddingProvider {
             model: self.model_name.clone(),
             dimension: self.dimension,
             max_tokens: 512, // Default for now, will be configurable later
+            execution_provider: None,
         })
     }
 
@@ -244,6 +245,7 @@ impl MlxEmbeddingProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::TruncationStrategy;
 
     #[tokio::test]
     async fn test_mlx_provider_initialization() {
@@ -300,6 +302,7 @@ mod tests {
             model: "qwen3-0.6b-4bit".to_string(),
             inputs: vec!["hello world".to_string(), "test embedding".to_string()],
             normalize: true,
+            truncation: TruncationStrategy::default(),
         };
 
         let response = provider.embed_batch(request).await.unwrap();
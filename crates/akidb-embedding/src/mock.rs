@@ -4,16 +4,17 @@ use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 
-use crate::provider::EmbeddingProvider;
+use crate::provider::{EmbeddingProvider, RerankProvider};
 use crate::types::{
-    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingResult, ModelInfo,
-    Usage,
+    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingInput, EmbeddingResult,
+    ModelInfo, MultimodalEmbeddingRequest, RerankRequest, RerankResponse, Usage,
 };
 
 /// Mock embedding provider for testing.
 ///
 /// Generates deterministic embeddings based on input hash, allowing
-/// integration tests to run without ML dependencies.
+/// integration tests to run without ML dependencies. Images are embedded the
+/// same way (hashing their bytes or URL), so multimodal paths can be tested too.
 pub struct MockEmbeddingProvider {
     model: String,
     dimension: u32,
@@ -65,14 +66,14 @@ impl MockEmbeddingProvider {
         self
     }
 
-    /// Generate a deterministic embedding for a given text input.
+    /// Generate a deterministic embedding for a given input.
     ///
-    /// Uses the hash of the input string to seed a deterministic vector.
+    /// Uses the hash of the input to seed a deterministic vector.
     /// The vector is L2 normalized if requested.
-    fn generate_embedding(&self, text: &str, normalize: bool) -> Vec<f32> {
+    fn generate_embedding<T: Hash + ?Sized>(&self, input: &T, normalize: bool) -> Vec<f32> {
         // Hash the input to get a deterministic seed
         let mut hasher = DefaultHasher::new();
-        text.hash(&mut hasher);
+        input.hash(&mut hasher);
         let seed = hasher.finish();
 
         // Generate deterministic vector based on seed
@@ -146,7 +147,7 @@ impl EmbeddingProvider for MockEmbeddingProvider {
         let embeddings: Vec<Vec<f32>> = request
             .inputs
             .iter()
-            .map(|text| self.generate_embedding(text, request.normalize))
+            .map(|text| self.generate_embedding(text.as_str(), request.normalize))
             .collect();
 
         // Calculate usage
@@ -172,6 +173,7 @@ impl EmbeddingProvider for MockEmbeddingProvider {
             model: self.model.clone(),
             dimension: self.dimension,
             max_tokens: 8192, // Mock max tokens
+            execution_provider: None,
         })
     }
 
@@ -179,11 +181,135 @@ impl EmbeddingProvider for MockEmbeddingProvider {
         // Mock provider is always healthy
         Ok(())
     }
+
+    async fn embed_multimodal(
+        &self,
+        request: MultimodalEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        let start = Instant::now();
+
+        if request.inputs.is_empty() {
+            return Err(EmbeddingError::InvalidInput(
+                "empty input batch".to_string(),
+            ));
+        }
+        if request.model != self.model {
+            return Err(EmbeddingError::ModelNotFound(format!(
+                "expected model '{}', got '{}'",
+                self.model, request.model
+            )));
+        }
+
+        let embeddings = request
+            .inputs
+            .iter()
+            .map(|input| match input {
+                // Same vector as embed_batch for the same text
+                EmbeddingInput::Text(text) => {
+                    self.generate_embedding(text.as_str(), request.normalize)
+                }
+                EmbeddingInput::Image(image) => self.generate_embedding(image, request.normalize),
+            })
+            .collect();
+        let total_tokens = request
+            .inputs
+            .iter()
+            .map(|input| match input {
+                EmbeddingInput::Text(text) => Self::estimate_tokens(text),
+                EmbeddingInput::Image(_) => 1,
+            })
+            .sum();
+
+        Ok(BatchEmbeddingResponse {
+            model: self.model.clone(),
+            embeddings,
+            usage: Usage {
+                total_tokens,
+                duration_ms: start.elapsed().as_millis() as u64,
+            },
+        })
+    }
+}
+
+/// Mock reranker for testing.
+///
+/// Scores each document by the fraction of query words it contains
+/// (case-insensitive), so tests get a stable, meaningful ordering.
+#[derive(Debug, Default)]
+pub struct MockRerankProvider;
+
+impl MockRerankProvider {
+    /// Model name reported by the mock reranker.
+    pub const MODEL: &'static str = "mock-rerank";
+
+    /// Creates a new mock reranker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn score(query_words: &[String], document: &str) -> f32 {
+        if query_words.is_empty() {
+            return 0.0;
+        }
+        let document = document.to_lowercase();
+        let words: Vec<&str> = document.split_whitespace().collect();
+        let matched = query_words
+            .iter()
+            .filter(|word| words.contains(&word.as_str()))
+            .count();
+        matched as f32 / query_words.len() as f32
+    }
+}
+
+#[async_trait]
+impl RerankProvider for MockRerankProvider {
+    async fn rerank(&self, request: RerankRequest) -> EmbeddingResult<RerankResponse> {
+        if request.query.trim().is_empty() {
+            return Err(EmbeddingError::InvalidInput(
+                "Query cannot be empty".to_string(),
+            ));
+        }
+        let start = Instant::now();
+        let query_words: Vec<String> = request
+            .query
+            .to_lowercase()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        let scores = request
+            .documents
+            .iter()
+            .map(|document| Self::score(&query_words, document))
+            .collect();
+        let total_tokens = request
+            .documents
+            .iter()
+            .map(|document| {
+                MockEmbeddingProvider::estimate_tokens(&request.query)
+                    + MockEmbeddingProvider::estimate_tokens(document)
+            })
+            .sum();
+
+        Ok(RerankResponse {
+            model: Self::MODEL.to_string(),
+            scores,
+            usage: Usage {
+                total_tokens,
+                duration_ms: start.elapsed().as_millis() as u64,
+            },
+        })
+    }
+
+    async fn health_check(&self) -> EmbeddingResult<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{ImageInput, TruncationStrategy};
 
     #[tokio::test]
     async fn test_mock_provider_deterministic() {
@@ -192,6 +318,7 @@ mod tests {
             model: "mock-embed-512".to_string(),
             inputs: vec!["hello world".to_string()],
             normalize: false,
+            truncation: TruncationStrategy::default(),
         };
 
         let response1 = provider.embed_batch(request.clone()).await.unwrap();
@@ -207,6 +334,7 @@ mod tests {
             model: "mock-embed-128".to_string(),
             inputs: vec!["test".to_string()],
             normalize: false,
+            truncation: TruncationStrategy::default(),
         };
 
         let response = provider.embed_batch(request).await.unwrap();
@@ -220,6 +348,7 @@ mod tests {
             model: "mock-embed-512".to_string(),
             inputs: vec!["normalize me".to_string()],
             normalize: true,
+            truncation: TruncationStrategy::default(),
         };
 
         let response = provider.embed_batch(request).await.unwrap();
@@ -243,4 +372,57 @@ mod tests {
         assert_eq!(info.dimension, 256);
         assert_eq!(info.model, "mock-embed-256");
     }
+
+    #[tokio::test]
+    async fn test_mock_reranker_orders_by_overlap() {
+        let reranker = MockRerankProvider::new();
+        let response = reranker
+            .rerank(RerankRequest {
+                model: MockRerankProvider::MODEL.to_string(),
+                query: "Rust vector database".to_string(),
+                documents: vec![
+                    "a cooking blog".to_string(),
+                    "a vector database written in rust".to_string(),
+                    "rust tutorials".to_string(),
+                ],
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.scores.len(), 3);
+        assert!(response.scores[1] > response.scores[2]);
+        assert!(response.scores[2] > response.scores[0]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_multimodal() {
+        let provider = MockEmbeddingProvider::new();
+        let image = ImageInput::Bytes(vec![0x89, b'P', b'N', b'G']);
+        let request = MultimodalEmbeddingRequest {
+            model: "mock-embed-512".to_string(),
+            inputs: vec![
+                EmbeddingInput::Text("a cat".to_string()),
+                EmbeddingInput::Image(image.clone()),
+                EmbeddingInput::Image(image),
+            ],
+            normalize: true,
+        };
+
+        let response = provider.embed_multimodal(request).await.unwrap();
+        assert_eq!(response.embeddings.len(), 3);
+        assert_eq!(response.embeddings[1], response.embeddings[2]);
+        assert_ne!(response.embeddings[0], response.embeddings[1]);
+
+        // Text embeds the same as through embed_batch
+        let text = provider
+            .embed_batch(BatchEmbeddingRequest {
+                model: "mock-embed-512".to_string(),
+                inputs: vec!["a cat".to_string()],
+                normalize: true,
+                truncation: TruncationStrategy::default(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(text.embeddings[0], response.embeddings[0]);
+    }
 }
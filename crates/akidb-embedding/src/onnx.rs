@@ -5,10 +5,17 @@
 //!
 //! # Execution Providers
 //!
-//! - **CoreML**: Mac ARM GPU acceleration (M1/M2/M3)
+//! - **CoreML**: Mac ARM GPU acceleration (M1/M2/M3), requires the `onnx-coreml` feature
 //! - **TensorRT**: NVIDIA GPU optimization for Jetson Thor (FP8 support)
 //! - **CUDA**: Generic NVIDIA GPU fallback
+//! - **DirectML**: Windows GPUs (any vendor), requires the `onnx-directml` feature
 //! - **CPU**: CPU-only fallback
+//!
+//! If the requested accelerator fails to initialize (missing drivers, EP not
+//! compiled in, unsupported operators) the provider falls back to CPU unless
+//! `OnnxConfig::fallback_to_cpu` is disabled. The active provider is reported
+//! in `ModelInfo::execution_provider` and can be changed at runtime with
+//! [`OnnxEmbeddingProvider::switch_execution_provider`].
 
 use crate::{
     BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingProvider,
@@ -16,13 +23,19 @@ use crate::{
 };
 use async_trait::async_trait;
 use ndarray::Array2;
-use ort::{session::{Session, builder::GraphOptimizationLevel}, value::Value};
+use ort::execution_providers::{
+    CUDAExecutionProvider, CoreMLExecutionProvider, DirectMLExecutionProvider, ExecutionProvider,
+    TensorRTExecutionProvider,
+};
+use ort::session::builder::{GraphOptimizationLevel, SessionBuilder};
+use ort::{session::Session, value::Value};
 use parking_lot::Mutex;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use tokenizers::Tokenizer;
 
 /// Execution provider configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ExecutionProviderConfig {
     /// CoreML (Mac ARM)
     CoreML,
@@ -34,10 +47,25 @@ pub enum ExecutionProviderConfig {
     },
     /// CUDA (generic NVIDIA GPU)
     CUDA { device_id: i32 },
+    /// DirectML (Windows, any DirectX 12 GPU)
+    DirectML { device_id: i32 },
     /// CPU fallback
     CPU,
 }
 
+impl ExecutionProviderConfig {
+    /// Short name reported in `ModelInfo::execution_provider`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CoreML => "coreml",
+            Self::TensorRT { .. } => "tensorrt",
+            Self::CUDA { .. } => "cuda",
+            Self::DirectML { .. } => "directml",
+            Self::CPU => "cpu",
+        }
+    }
+}
+
 /// Configuration for ONNX embedding provider.
 #[derive(Debug, Clone)]
 pub struct OnnxConfig {
@@ -53,6 +81,8 @@ pub struct OnnxConfig {
     pub max_length: usize,
     /// Execution provider
     pub execution_provider: ExecutionProviderConfig,
+    /// Use the CPU if the execution provider fails to initialize or run
+    pub fallback_to_cpu: bool,
 }
 
 impl Default for OnnxConfig {
@@ -64,26 +94,36 @@ impl Default for OnnxConfig {
             dimension: 384,
             max_length: 512,
             execution_provider: ExecutionProviderConfig::CPU,
+            fallback_to_cpu: true,
         }
     }
 }
 
 /// ONNX Runtime embedding provider.
 ///
-/// Uses ONNX Runtime for universal GPU support (CoreML, TensorRT, CUDA) with
-/// transformer models for text embedding generation.
+/// Uses ONNX Runtime for universal GPU support (CoreML, TensorRT, CUDA, DirectML)
+/// with transformer models for text embedding generation.
 pub struct OnnxEmbeddingProvider {
-    /// ONNX Runtime session (contains model)
+    /// ONNX Runtime session (contains model) and the provider it runs on
     /// Wrapped in Mutex for interior mutability (Session::run requires &mut self)
-    session: Mutex<Session>,
+    session: Mutex<ActiveSession>,
 
     /// Tokenizer for text preprocessing
     tokenizer: Tokenizer,
 
+    /// Tokenizer without padding or truncation, for counting input tokens
+    counter: Tokenizer,
+
     /// Configuration
     config: OnnxConfig,
 }
 
+/// Session together with the execution provider it was created for.
+pub(crate) struct ActiveSession {
+    pub(crate) session: Session,
+    pub(crate) provider: ExecutionProviderConfig,
+}
+
 impl OnnxEmbeddingProvider {
     /// Create new ONNX embedding provider with configuration.
     ///
@@ -110,6 +150,7 @@ impl OnnxEmbeddingProvider {
     ///         fp8_enable: true,
     ///         engine_cache_path: Some(PathBuf::from("/tmp/trt_cache")),
     ///     },
+    ///     fallback_to_cpu: true,
     /// };
     ///
     /// let provider = OnnxEmbeddingProvider::with_config(config).await?;
@@ -125,7 +166,102 @@ impl OnnxEmbeddingProvider {
 
         // 1. Create session with execution provider
         eprintln!("📦 Loading ONNX model from: {:?}", config.model_path);
+        let session = Self::create_session(
+            &config.model_path,
+            &config.execution_provider,
+            config.fallback_to_cpu,
+        )?;
+
+        eprintln!("✅ ONNX model loaded successfully");
+
+        // 2. Load tokenizer
+        eprintln!("📝 Loading tokenizer from: {:?}", config.tokenizer_path);
+        let tokenizer = Tokenizer::from_file(&config.tokenizer_path)
+            .map_err(|e| EmbeddingError::Internal(format!("Failed to load tokenizer: {}", e)))?;
+        eprintln!("✅ Tokenizer loaded successfully");
+
+        eprintln!(
+            "✅ OnnxEmbeddingProvider initialized\n   Model: {}\n   Dimension: {}\n   Execution provider: {}",
+            config.model_name,
+            config.dimension,
+            session.provider.name()
+        );
+
+        Ok(Self {
+            session: Mutex::new(session),
+            counter: crate::truncation::counting_tokenizer(&tokenizer),
+            tokenizer,
+            config,
+        })
+    }
+
+    /// Execution provider the current session runs on.
+    ///
+    /// Differs from `OnnxConfig::execution_provider` after a CPU fallback.
+    pub fn execution_provider(&self) -> ExecutionProviderConfig {
+        self.session.lock().provider.clone()
+    }
+
+    /// Rebuilds the session on `provider` and swaps it in.
+    ///
+    /// In-flight inference finishes on the old session. Falls back to CPU
+    /// when the provider fails to initialize and `fallback_to_cpu` is set.
+    ///
+    /// # Returns
+    ///
+    /// The execution provider now in use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (and keeps the current session) if no session could
+    /// be created.
+    pub fn switch_execution_provider(
+        &self,
+        provider: ExecutionProviderConfig,
+    ) -> EmbeddingResult<ExecutionProviderConfig> {
+        let session = Self::create_session(
+            &self.config.model_path,
+            &provider,
+            self.config.fallback_to_cpu,
+        )?;
+        let active = session.provider.clone();
+        *self.session.lock() = session;
+        eprintln!("🔁 Switched ONNX execution provider to {}", active.name());
+        Ok(active)
+    }
 
+    /// Creates a session on `provider`, falling back to CPU if allowed.
+    ///
+    /// Shared with the ONNX cross-encoder reranker.
+    pub(crate) fn create_session(
+        model_path: &Path,
+        provider: &ExecutionProviderConfig,
+        fallback_to_cpu: bool,
+    ) -> EmbeddingResult<ActiveSession> {
+        match Self::build_session(model_path, provider) {
+            Ok(session) => Ok(ActiveSession {
+                session,
+                provider: provider.clone(),
+            }),
+            Err(e) if fallback_to_cpu && *provider != ExecutionProviderConfig::CPU => {
+                eprintln!(
+                    "⚠️  {} execution provider failed ({}), falling back to CPU",
+                    provider.name(),
+                    e
+                );
+                Ok(ActiveSession {
+                    session: Self::build_session(model_path, &ExecutionProviderConfig::CPU)?,
+                    provider: ExecutionProviderConfig::CPU,
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn build_session(
+        model_path: &Path,
+        provider: &ExecutionProviderConfig,
+    ) -> EmbeddingResult<Session> {
         let mut builder = Session::builder()
             .map_err(|e| EmbeddingError::Internal(format!("Failed to create session builder: {}", e)))?
             .with_optimization_level(GraphOptimizationLevel::Level3)
@@ -133,9 +269,19 @@ impl OnnxEmbeddingProvider {
             .with_intra_threads(4)
             .map_err(|e| EmbeddingError::Internal(format!("Failed to set threads: {}", e)))?;
 
-        // Configure execution provider
-        builder = match &config.execution_provider {
-            #[cfg(feature = "tensorrt")]
+        Self::register_execution_provider(&mut builder, provider)?;
+
+        builder
+            .commit_from_file(model_path)
+            .map_err(|e| EmbeddingError::Internal(format!("Failed to load model: {}", e)))
+    }
+
+    /// Registers `provider` on `builder`, failing if it is unavailable.
+    fn register_execution_provider(
+        builder: &mut SessionBuilder,
+        provider: &ExecutionProviderConfig,
+    ) -> EmbeddingResult<()> {
+        let result = match provider {
             ExecutionProviderConfig::TensorRT {
                 device_id,
                 fp8_enable,
@@ -143,12 +289,11 @@ impl OnnxEmbeddingProvider {
             } => {
                 eprintln!("🚀 Configuring TensorRT Execution Provider (FP8: {})", fp8_enable);
 
-                use ort::TensorRTExecutionProvider;
                 let mut trt_options = TensorRTExecutionProvider::default()
                     .with_device_id(*device_id)
-                    .with_fp16_enable(true)  // Enable FP16 for better performance
-                    .with_engine_cache_enable(true)
-                    .with_timing_cache_enable(true);
+                    .with_fp16(true)  // Enable FP16 for better performance
+                    .with_engine_cache(true)
+                    .with_timing_cache(true);
 
                 if *fp8_enable {
                     // FP8 is enabled via TensorRT builder flags
@@ -164,61 +309,40 @@ impl OnnxEmbeddingProvider {
                     eprintln!("   💾 Engine cache: {:?}", cache_path);
                 }
 
-                builder.with_execution_providers([trt_options])
-                    .map_err(|e| EmbeddingError::Internal(format!("Failed to set TensorRT EP: {}", e)))?
+                trt_options.register(builder)
             }
 
-            #[cfg(feature = "cuda")]
             ExecutionProviderConfig::CUDA { device_id } => {
                 eprintln!("🎮 Configuring CUDA Execution Provider");
+                CUDAExecutionProvider::default()
+                    .with_device_id(*device_id)
+                    .register(builder)
+            }
 
-                use ort::CUDAExecutionProvider;
-                let cuda_options = CUDAExecutionProvider::default()
-                    .with_device_id(*device_id);
-
-                builder.with_execution_providers([cuda_options])
-                    .map_err(|e| EmbeddingError::Internal(format!("Failed to set CUDA EP: {}", e)))?
+            ExecutionProviderConfig::DirectML { device_id } => {
+                eprintln!("🪟 Configuring DirectML Execution Provider");
+                DirectMLExecutionProvider::default()
+                    .with_device_id(*device_id)
+                    .register(builder)
             }
 
             ExecutionProviderConfig::CoreML => {
                 eprintln!("🍎 Configuring CoreML Execution Provider");
-                // CoreML is automatically detected on macOS
-                builder
+                CoreMLExecutionProvider::default().register(builder)
             }
 
             ExecutionProviderConfig::CPU => {
                 eprintln!("💻 Using CPU Execution Provider");
-                builder
-            }
-
-            #[allow(unreachable_patterns)]
-            _ => {
-                eprintln!("⚠️  Execution provider not available, falling back to CPU");
-                builder
+                Ok(())
             }
         };
 
-        let session = builder
-            .commit_from_file(&config.model_path)
-            .map_err(|e| EmbeddingError::Internal(format!("Failed to load model: {}", e)))?;
-
-        eprintln!("✅ ONNX model loaded successfully");
-
-        // 2. Load tokenizer
-        eprintln!("📝 Loading tokenizer from: {:?}", config.tokenizer_path);
-        let tokenizer = Tokenizer::from_file(&config.tokenizer_path)
-            .map_err(|e| EmbeddingError::Internal(format!("Failed to load tokenizer: {}", e)))?;
-        eprintln!("✅ Tokenizer loaded successfully");
-
-        eprintln!(
-            "✅ OnnxEmbeddingProvider initialized\n   Model: {}\n   Dimension: {}",
-            config.model_name, config.dimension
-        );
-
-        Ok(Self {
-            session: Mutex::new(session),
-            tokenizer,
-            config,
+        result.map_err(|e| {
+            EmbeddingError::ServiceUnavailable(format!(
+                "Failed to register {} execution provider: {}",
+                provider.name(),
+                e
+            ))
         })
     }
 
@@ -241,6 +365,7 @@ impl OnnxEmbeddingProvider {
             dimension: 384, // Default for MiniLM
             max_length: 512,
             execution_provider: ExecutionProviderConfig::CPU,
+            fallback_to_cpu: true,
         };
 
         Self::with_config(config).await
@@ -306,7 +431,7 @@ impl OnnxEmbeddingProvider {
 
         let attention_mask_array = Array2::from_shape_vec(
             (batch_size, max_length),
-            attention_mask_vec.clone(),
+            attention_mask_vec,
         )
         .map_err(|e| EmbeddingError::Internal(format!("Failed to create mask tensor: {}", e)))?;
 
@@ -316,17 +441,56 @@ impl OnnxEmbeddingProvider {
         )
         .map_err(|e| EmbeddingError::Internal(format!("Failed to create token_type_ids tensor: {}", e)))?;
 
-        // 5. Run ONNX inference with 3 inputs: input_ids, attention_mask, token_type_ids
-        //    Pass owned arrays directly (OwnedRepr required for OwnedTensorArrayData trait)
-        let input_ids_value = Value::from_array(input_ids_array)
+        // 5. Run inference, retrying once on CPU if the accelerator fails
+        let (result, provider) = {
+            let mut active = self.session.lock();
+            let result = Self::run_inference(
+                &mut active.session,
+                &input_ids_array,
+                &attention_mask_array,
+                &token_type_ids_array,
+            );
+            (result, active.provider.clone())
+        };
+
+        match result {
+            Err(e) if self.config.fallback_to_cpu && provider != ExecutionProviderConfig::CPU => {
+                eprintln!(
+                    "⚠️  Inference on {} failed ({}), falling back to CPU",
+                    provider.name(),
+                    e
+                );
+                self.switch_execution_provider(ExecutionProviderConfig::CPU)?;
+                Self::run_inference(
+                    &mut self.session.lock().session,
+                    &input_ids_array,
+                    &attention_mask_array,
+                    &token_type_ids_array,
+                )
+            }
+            result => result,
+        }
+    }
+
+    /// Runs the model on one tokenized batch and mean-pools the output.
+    fn run_inference(
+        session: &mut Session,
+        input_ids_array: &Array2<i64>,
+        attention_mask_array: &Array2<i64>,
+        token_type_ids_array: &Array2<i64>,
+    ) -> EmbeddingResult<Vec<Vec<f32>>> {
+        let (batch_size, max_length) = attention_mask_array.dim();
+        let attention_mask_vec: Vec<i64> = attention_mask_array.iter().copied().collect();
+
+        // Run ONNX inference with 3 inputs: input_ids, attention_mask, token_type_ids
+        //    Pass owned arrays (OwnedRepr required for OwnedTensorArrayData trait)
+        let input_ids_value = Value::from_array(input_ids_array.clone())
             .map_err(|e| EmbeddingError::Internal(format!("Failed to create input_ids value: {}", e)))?;
-        let attention_mask_value = Value::from_array(attention_mask_array)
+        let attention_mask_value = Value::from_array(attention_mask_array.clone())
             .map_err(|e| EmbeddingError::Internal(format!("Failed to create attention_mask value: {}", e)))?;
-        let token_type_ids_value = Value::from_array(token_type_ids_array)
+        let token_type_ids_value = Value::from_array(token_type_ids_array.clone())
             .map_err(|e| EmbeddingError::Internal(format!("Failed to create token_type_ids value: {}", e)))?;
 
-        // Lock session for inference (Session::run requires &mut self)
-        let mut session = self.session.lock();
         let outputs = session
             .run(ort::inputs![
                 "input_ids" => input_ids_value,
@@ -335,7 +499,7 @@ impl OnnxEmbeddingProvider {
             ])
             .map_err(|e| EmbeddingError::Internal(format!("ONNX inference failed: {}", e)))?;
 
-        // 6. Extract last_hidden_state output (first output)
+        // Extract last_hidden_state output (first output)
         let (hidden_shape, hidden_data) = outputs["last_hidden_state"]
             .try_extract_tensor::<f32>()
             .map_err(|e| EmbeddingError::Internal(format!("Failed to extract output: {}", e)))?;
@@ -350,7 +514,7 @@ impl OnnxEmbeddingProvider {
 
         let hidden_size = hidden_shape[2] as usize;
 
-        // 7. Mean pooling with attention mask
+        // Mean pooling with attention mask
         let mut embeddings = Vec::with_capacity(batch_size);
 
         for i in 0..batch_size {
@@ -376,7 +540,7 @@ impl OnnxEmbeddingProvider {
                 }
             }
 
-            // 8. L2 normalization
+            // L2 normalization
             let norm: f32 = pooled.iter().map(|x| x * x).sum::<f32>().sqrt();
             let norm = norm.max(1e-12); // Prevent division by zero
 
@@ -455,9 +619,14 @@ impl EmbeddingProvider for OnnxEmbeddingProvider {
             model: self.config.model_name.clone(),
             dimension: self.config.dimension,
             max_tokens: self.config.max_length,
+            execution_provider: Some(self.execution_provider().name().to_string()),
         })
     }
 
+    fn token_spans(&self, text: &str) -> Vec<Range<usize>> {
+        crate::truncation::tokenizer_spans(&self.counter, text)
+    }
+
     async fn health_check(&self) -> EmbeddingResult<()> {
         // Generate a test embedding to verify the provider is functional
         let test_embedding = self
@@ -0,0 +1,263 @@
+//! ONNX Runtime cross-encoder reranker.
+//!
+//! Runs sequence-classification cross-encoders (e.g.
+//! `cross-encoder/ms-marco-MiniLM-L-6-v2`, `BAAI/bge-reranker-base`) exported
+//! to ONNX. Each (query, document) pair is tokenized as one sequence and the
+//! model's single relevance logit is squashed into `0.0..=1.0` with a sigmoid.
+//!
+//! Sessions are created the same way as for [`OnnxEmbeddingProvider`], so the
+//! same execution providers and CPU fallback apply.
+//!
+//! [`OnnxEmbeddingProvider`]: crate::OnnxEmbeddingProvider
+
+use crate::onnx::{ActiveSession, ExecutionProviderConfig, OnnxEmbeddingProvider};
+use crate::{
+    EmbeddingError, EmbeddingResult, RerankProvider, RerankRequest, RerankResponse, Usage,
+};
+use async_trait::async_trait;
+use ndarray::Array2;
+use ort::{session::Session, value::Value};
+use parking_lot::Mutex;
+use std::path::PathBuf;
+use std::time::Instant;
+use tokenizers::Tokenizer;
+
+/// Configuration for [`OnnxRerankProvider`].
+#[derive(Debug, Clone)]
+pub struct OnnxRerankConfig {
+    /// Path to ONNX model file
+    pub model_path: PathBuf,
+    /// Path to tokenizer.json file
+    pub tokenizer_path: PathBuf,
+    /// Model name (for metadata)
+    pub model_name: String,
+    /// Maximum length of a (query, document) pair in tokens
+    pub max_length: usize,
+    /// Pairs scored per inference call
+    pub batch_size: usize,
+    /// Execution provider
+    pub execution_provider: ExecutionProviderConfig,
+    /// Use the CPU if the execution provider fails to initialize
+    pub fallback_to_cpu: bool,
+}
+
+impl Default for OnnxRerankConfig {
+    fn default() -> Self {
+        Self {
+            model_path: PathBuf::from("models/reranker.onnx"),
+            tokenizer_path: PathBuf::from("models/tokenizer.json"),
+            model_name: "cross-encoder/ms-marco-MiniLM-L-6-v2".to_string(),
+            max_length: 512,
+            batch_size: 32,
+            execution_provider: ExecutionProviderConfig::CPU,
+            fallback_to_cpu: true,
+        }
+    }
+}
+
+/// Cross-encoder reranker backed by ONNX Runtime.
+pub struct OnnxRerankProvider {
+    session: Mutex<ActiveSession>,
+    tokenizer: Tokenizer,
+    config: OnnxRerankConfig,
+}
+
+impl OnnxRerankProvider {
+    /// Loads the model and tokenizer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the model or tokenizer cannot be loaded.
+    pub fn with_config(config: OnnxRerankConfig) -> EmbeddingResult<Self> {
+        let session = OnnxEmbeddingProvider::create_session(
+            &config.model_path,
+            &config.execution_provider,
+            config.fallback_to_cpu,
+        )?;
+        let tokenizer = Tokenizer::from_file(&config.tokenizer_path)
+            .map_err(|e| EmbeddingError::Internal(format!("Failed to load tokenizer: {}", e)))?;
+
+        eprintln!(
+            "✅ OnnxRerankProvider initialized\n   Model: {}\n   Execution provider: {}",
+            config.model_name,
+            session.provider.name()
+        );
+
+        Ok(Self {
+            session: Mutex::new(session),
+            tokenizer,
+            config,
+        })
+    }
+
+    /// Execution provider the current session runs on.
+    pub fn execution_provider(&self) -> ExecutionProviderConfig {
+        self.session.lock().provider.clone()
+    }
+
+    /// Scores one batch of pairs, returning the count of tokens used too.
+    fn score_batch(&self, query: &str, documents: &[String]) -> EmbeddingResult<(Vec<f32>, usize)> {
+        let encodings = documents
+            .iter()
+            .map(|document| self.tokenizer.encode((query, document.as_str()), true))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| EmbeddingError::Internal(format!("Tokenization failed: {}", e)))?;
+
+        // Pad to the longest pair in the batch rather than max_length
+        let seq_len = encodings
+            .iter()
+            .map(|e| e.get_ids().len().min(self.config.max_length))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let batch_size = encodings.len();
+
+        let mut input_ids = Vec::with_capacity(batch_size * seq_len);
+        let mut attention_mask = Vec::with_capacity(batch_size * seq_len);
+        let mut token_type_ids = Vec::with_capacity(batch_size * seq_len);
+        let mut tokens = 0;
+
+        for encoding in &encodings {
+            let mut ids = encoding.get_ids().to_vec();
+            let mut mask = encoding.get_attention_mask().to_vec();
+            let mut type_ids = encoding.get_type_ids().to_vec();
+            ids.truncate(seq_len);
+            mask.truncate(seq_len);
+            type_ids.truncate(seq_len);
+            tokens += ids.len();
+            ids.resize(seq_len, 0);
+            mask.resize(seq_len, 0);
+            type_ids.resize(seq_len, 0);
+
+            input_ids.extend(ids.iter().map(|&x| x as i64));
+            attention_mask.extend(mask.iter().map(|&x| x as i64));
+            token_type_ids.extend(type_ids.iter().map(|&x| x as i64));
+        }
+
+        let shape = (batch_size, seq_len);
+        let to_array = |data: Vec<i64>, name: &str| {
+            Array2::from_shape_vec(shape, data).map_err(|e| {
+                EmbeddingError::Internal(format!("Failed to create {} tensor: {}", name, e))
+            })
+        };
+        let input_ids = to_array(input_ids, "input_ids")?;
+        let attention_mask = to_array(attention_mask, "attention_mask")?;
+        let token_type_ids = to_array(token_type_ids, "token_type_ids")?;
+
+        let logits = Self::run_inference(
+            &mut self.session.lock().session,
+            input_ids,
+            attention_mask,
+            token_type_ids,
+        )?;
+        if logits.len() != batch_size {
+            return Err(EmbeddingError::Internal(format!(
+                "Reranker returned {} scores for {} documents",
+                logits.len(),
+                batch_size
+            )));
+        }
+
+        Ok((logits.into_iter().map(sigmoid).collect(), tokens))
+    }
+
+    /// Runs the model and returns the first logit of each row.
+    fn run_inference(
+        session: &mut Session,
+        input_ids: Array2<i64>,
+        attention_mask: Array2<i64>,
+        token_type_ids: Array2<i64>,
+    ) -> EmbeddingResult<Vec<f32>> {
+        let value = |array: Array2<i64>, name: &str| {
+            Value::from_array(array).map_err(|e| {
+                EmbeddingError::Internal(format!("Failed to create {} value: {}", name, e))
+            })
+        };
+        let input_ids = value(input_ids, "input_ids")?;
+        let attention_mask = value(attention_mask, "attention_mask")?;
+        let token_type_ids = value(token_type_ids, "token_type_ids")?;
+
+        let outputs = session
+            .run(ort::inputs![
+                "input_ids" => input_ids,
+                "attention_mask" => attention_mask,
+                "token_type_ids" => token_type_ids
+            ])
+            .map_err(|e| EmbeddingError::Internal(format!("ONNX inference failed: {}", e)))?;
+
+        // Shape is [batch_size, num_labels]; relevance is the first label
+        let (shape, data) = outputs["logits"]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| EmbeddingError::Internal(format!("Failed to extract logits: {}", e)))?;
+        let labels = match shape.len() {
+            1 => 1,
+            2 => shape[1].max(1) as usize,
+            _ => {
+                return Err(EmbeddingError::Internal(format!(
+                    "Unexpected logits shape: {:?}",
+                    shape
+                )))
+            }
+        };
+
+        Ok(data.chunks(labels).map(|row| row[0]).collect())
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[async_trait]
+impl RerankProvider for OnnxRerankProvider {
+    async fn rerank(&self, request: RerankRequest) -> EmbeddingResult<RerankResponse> {
+        if request.query.trim().is_empty() {
+            return Err(EmbeddingError::InvalidInput(
+                "Query cannot be empty".to_string(),
+            ));
+        }
+
+        let start = Instant::now();
+        let mut scores = Vec::with_capacity(request.documents.len());
+        let mut total_tokens = 0;
+        for batch in request.documents.chunks(self.config.batch_size.max(1)) {
+            let (batch_scores, tokens) = self.score_batch(&request.query, batch)?;
+            scores.extend(batch_scores);
+            total_tokens += tokens;
+        }
+
+        Ok(RerankResponse {
+            model: self.config.model_name.clone(),
+            scores,
+            usage: Usage {
+                total_tokens,
+                duration_ms: start.elapsed().as_millis() as u64,
+            },
+        })
+    }
+
+    async fn health_check(&self) -> EmbeddingResult<()> {
+        let scores = self
+            .score_batch("health check", &["health check".to_string()])?
+            .0;
+        if scores.iter().all(|s| s.is_finite()) {
+            Ok(())
+        } else {
+            Err(EmbeddingError::Internal(
+                "Reranker produced non-finite scores".to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sigmoid_maps_logits_to_unit_interval() {
+        assert!((sigmoid(0.0) - 0.5).abs() < 1e-6);
+        assert!(sigmoid(8.0) > 0.99);
+        assert!(sigmoid(-8.0) < 0.01);
+    }
+}
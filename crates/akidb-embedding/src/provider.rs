@@ -1,6 +1,10 @@
 use async_trait::async_trait;
 
-use crate::types::{BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingResult, ModelInfo};
+use crate::types::{
+    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingInput, EmbeddingResult,
+    ModelInfo, MultimodalEmbeddingRequest, RerankRequest, RerankResponse, TruncationStrategy,
+};
+use std::ops::Range;
 
 /// Trait for embedding model providers.
 ///
@@ -34,4 +38,73 @@ pub trait EmbeddingProvider: Send + Sync {
     ///
     /// Returns an error if the service is unhealthy or models are not loaded.
     async fn health_check(&self) -> EmbeddingResult<()>;
+
+    /// Generate embeddings for a mix of text and image inputs.
+    ///
+    /// Embeddings are returned in input order. The default implementation
+    /// serves text-only requests through [`embed_batch`](Self::embed_batch)
+    /// and rejects images; multimodal providers override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if the request contains images and the
+    /// provider does not support them, plus any error of `embed_batch`.
+    async fn embed_multimodal(
+        &self,
+        request: MultimodalEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        let inputs = request
+            .inputs
+            .into_iter()
+            .map(|input| match input {
+                EmbeddingInput::Text(text) => Ok(text),
+                EmbeddingInput::Image(_) => Err(EmbeddingError::InvalidInput(format!(
+                    "Model '{}' does not support image inputs",
+                    request.model
+                ))),
+            })
+            .collect::<EmbeddingResult<Vec<_>>>()?;
+
+        self.embed_batch(BatchEmbeddingRequest {
+            model: request.model,
+            inputs,
+            normalize: request.normalize,
+            truncation: TruncationStrategy::default(),
+        })
+        .await
+    }
+
+    /// Byte ranges of the tokens in `text`, excluding special tokens.
+    ///
+    /// Used to count and truncate inputs to the model's token limit (see
+    /// [`TruncatingProvider`](crate::TruncatingProvider)). The default
+    /// estimates them with [`estimate_token_spans`](crate::estimate_token_spans);
+    /// providers that own a tokenizer override it with exact spans.
+    fn token_spans(&self, text: &str) -> Vec<Range<usize>> {
+        crate::estimate_token_spans(text)
+    }
+}
+
+/// Trait for cross-encoder reranking providers.
+///
+/// Rerankers score each (query, document) pair jointly, which is slower than
+/// comparing embeddings but more accurate, so they are applied to a short
+/// list of candidates from vector search.
+#[async_trait]
+pub trait RerankProvider: Send + Sync {
+    /// Score `request.documents` against `request.query`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Input validation fails (e.g. empty query)
+    /// - Internal scoring fails
+    async fn rerank(&self, request: RerankRequest) -> EmbeddingResult<RerankResponse>;
+
+    /// Health check for the reranking service.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the service is unhealthy or the model is not loaded.
+    async fn health_check(&self) -> EmbeddingResult<()>;
 }
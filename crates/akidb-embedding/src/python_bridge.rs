@@ -8,7 +8,7 @@
 // Protocol: JSON-RPC over stdin/stdout
 
 use crate::provider::EmbeddingProvider;
-use crate::types::{BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingResult, ModelInfo, Usage};
+use crate::types::{BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingResult, ModelInfo, TruncationStrategy, Usage};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader, Write};
@@ -126,6 +126,7 @@ impl PythonBridgeProvider {
             model: model_name.to_string(),
             inputs: vec!["warmup".to_string()],
             normalize: true,
+            truncation: TruncationStrategy::default(),
         };
 
         // Perform warmup embedding (result discarded)
@@ -272,6 +273,7 @@ impl EmbeddingProvider for PythonBridgeProvider {
             model: "python-bridge-onnx-coreml".to_string(),
             dimension,
             max_tokens: 512,
+            execution_provider: None,
         })
     }
 
@@ -340,6 +342,7 @@ mod tests {
             model: "sentence-transformers/all-MiniLM-L6-v2".to_string(),
             inputs: vec!["Hello, world!".to_string()],
             normalize: true,
+            truncation: TruncationStrategy::default(),
         };
 
         let response = provider.embed_batch(request).await.unwrap();
@@ -0,0 +1,369 @@
+//! Input length accounting and truncation.
+//!
+//! Models accept a bounded number of tokens per input. Instead of letting
+//! each backend cut inputs silently, [`TruncatingProvider`] counts tokens
+//! with the provider's own tokenizer (see
+//! [`EmbeddingProvider::token_spans`]) and applies the request's
+//! [`TruncationStrategy`] before the provider sees the text. The counted
+//! tokens are reported in [`Usage::total_tokens`], so usage-based billing
+//! matches what was actually embedded.
+
+use crate::{
+    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingProvider,
+    EmbeddingResult, ModelInfo, MultimodalEmbeddingRequest, TruncationStrategy,
+};
+use async_trait::async_trait;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Tokens kept free for the special tokens models add (e.g. `[CLS]`, `[SEP]`).
+const SPECIAL_TOKENS: usize = 2;
+
+/// Characters per token assumed when no tokenizer is available.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Approximates token spans without a tokenizer.
+///
+/// Each whitespace-separated word is split into pieces of up to four
+/// characters, which is close to subword tokenizers on English text.
+pub fn estimate_token_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    let mut chars = 0;
+    for (offset, ch) in text.char_indices() {
+        if ch.is_whitespace() {
+            if let Some(begin) = start.take() {
+                spans.push(begin..offset);
+            }
+            continue;
+        }
+        match start {
+            Some(_) if chars < CHARS_PER_TOKEN => chars += 1,
+            Some(begin) => {
+                spans.push(begin..offset);
+                start = Some(offset);
+                chars = 1;
+            }
+            None => {
+                start = Some(offset);
+                chars = 1;
+            }
+        }
+    }
+    if let Some(begin) = start {
+        spans.push(begin..text.len());
+    }
+    spans
+}
+
+/// Copy of `tokenizer` that neither pads nor truncates, for counting.
+#[cfg(any(feature = "onnx", feature = "candle"))]
+pub(crate) fn counting_tokenizer(tokenizer: &tokenizers::Tokenizer) -> tokenizers::Tokenizer {
+    let mut tokenizer = tokenizer.clone();
+    tokenizer.with_padding(None);
+    // Only fails for invalid truncation params, and `None` has none
+    let _ = tokenizer.with_truncation(None);
+    tokenizer
+}
+
+/// Token spans of `text` according to `tokenizer` (which must not pad or
+/// truncate), falling back to [`estimate_token_spans`] if encoding fails.
+#[cfg(any(feature = "onnx", feature = "candle"))]
+pub(crate) fn tokenizer_spans(tokenizer: &tokenizers::Tokenizer, text: &str) -> Vec<Range<usize>> {
+    match tokenizer.encode(text, false) {
+        Ok(encoding) => encoding
+            .get_offsets()
+            .iter()
+            .filter(|(start, end)| start < end)
+            .map(|&(start, end)| start..end)
+            .collect(),
+        Err(_) => estimate_token_spans(text),
+    }
+}
+
+/// Inputs cut to a model's token limit.
+#[derive(Debug, Clone)]
+pub struct PreparedInputs {
+    /// Texts to send to the provider; chunked inputs contribute several
+    pub texts: Vec<String>,
+    /// Token count of each text in `texts`
+    pub token_counts: Vec<usize>,
+    /// Number of entries in `texts` belonging to each original input
+    pub chunks: Vec<usize>,
+    /// Inputs that were shortened or chunked
+    pub truncated: usize,
+}
+
+impl PreparedInputs {
+    /// Tokens sent to the provider.
+    pub fn total_tokens(&self) -> usize {
+        self.token_counts.iter().sum()
+    }
+
+    /// Folds the embeddings of `texts` back into one per original input,
+    /// averaging chunks weighted by their token counts.
+    pub fn combine(&self, embeddings: Vec<Vec<f32>>, normalize: bool) -> Vec<Vec<f32>> {
+        let mut embeddings = embeddings.into_iter().zip(&self.token_counts);
+        self.chunks
+            .iter()
+            .map(|&count| {
+                if count == 1 {
+                    return embeddings.next().map(|(e, _)| e).unwrap_or_default();
+                }
+                let mut sum: Vec<f32> = Vec::new();
+                let mut weight = 0.0;
+                for (embedding, &tokens) in embeddings.by_ref().take(count) {
+                    let w = tokens.max(1) as f32;
+                    sum.resize(embedding.len(), 0.0);
+                    for (s, v) in sum.iter_mut().zip(&embedding) {
+                        *s += v * w;
+                    }
+                    weight += w;
+                }
+                for s in &mut sum {
+                    *s /= weight;
+                }
+                if normalize {
+                    let norm = sum.iter().map(|x| x * x).sum::<f32>().sqrt();
+                    if norm > 0.0 {
+                        for s in &mut sum {
+                            *s /= norm;
+                        }
+                    }
+                }
+                sum
+            })
+            .collect()
+    }
+}
+
+/// Applies `strategy` so every input fits in `max_tokens` model tokens
+/// (including special tokens), counting tokens with `provider`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if an input is too long and `strategy` is
+/// [`TruncationStrategy::Error`].
+pub fn prepare_inputs(
+    provider: &dyn EmbeddingProvider,
+    inputs: Vec<String>,
+    max_tokens: usize,
+    strategy: TruncationStrategy,
+) -> EmbeddingResult<PreparedInputs> {
+    let budget = max_tokens.saturating_sub(SPECIAL_TOKENS).max(1);
+    let mut prepared = PreparedInputs {
+        texts: Vec::with_capacity(inputs.len()),
+        token_counts: Vec::with_capacity(inputs.len()),
+        chunks: Vec::with_capacity(inputs.len()),
+        truncated: 0,
+    };
+
+    for (index, text) in inputs.into_iter().enumerate() {
+        let spans = provider.token_spans(&text);
+        if spans.len() <= budget {
+            prepared.token_counts.push(spans.len());
+            prepared.texts.push(text);
+            prepared.chunks.push(1);
+            continue;
+        }
+
+        prepared.truncated += 1;
+        match strategy {
+            TruncationStrategy::Error => {
+                return Err(EmbeddingError::InvalidInput(format!(
+                    "Input {} has {} tokens (maximum {})",
+                    index,
+                    spans.len(),
+                    budget
+                )))
+            }
+            TruncationStrategy::TruncateTail => {
+                prepared
+                    .texts
+                    .push(text[..spans[budget - 1].end].to_string());
+                prepared.token_counts.push(budget);
+                prepared.chunks.push(1);
+            }
+            TruncationStrategy::TruncateHead => {
+                prepared
+                    .texts
+                    .push(text[spans[spans.len() - budget].start..].to_string());
+                prepared.token_counts.push(budget);
+                prepared.chunks.push(1);
+            }
+            TruncationStrategy::ChunkAndAverage => {
+                let chunks = spans.chunks(budget);
+                prepared.chunks.push(chunks.len());
+                for chunk in chunks {
+                    let range = chunk[0].start..chunk[chunk.len() - 1].end;
+                    prepared.texts.push(text[range].to_string());
+                    prepared.token_counts.push(chunk.len());
+                }
+            }
+        }
+    }
+    Ok(prepared)
+}
+
+/// Provider wrapper that fits text inputs to the model's token limit.
+///
+/// Each request's [`truncation`](BatchEmbeddingRequest::truncation) strategy
+/// is applied before the inner provider is called, and the response's
+/// `usage.total_tokens` is replaced with the tokens actually embedded.
+pub struct TruncatingProvider {
+    inner: Arc<dyn EmbeddingProvider + Send + Sync>,
+    max_tokens: usize,
+}
+
+impl TruncatingProvider {
+    /// Wraps `inner`, whose model accepts `max_tokens` tokens per input.
+    pub fn new(inner: Arc<dyn EmbeddingProvider + Send + Sync>, max_tokens: usize) -> Self {
+        Self { inner, max_tokens }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for TruncatingProvider {
+    async fn embed_batch(
+        &self,
+        request: BatchEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        let prepared = prepare_inputs(
+            self.inner.as_ref(),
+            request.inputs,
+            self.max_tokens,
+            request.truncation,
+        )?;
+        let mut response = self
+            .inner
+            .embed_batch(BatchEmbeddingRequest {
+                model: request.model,
+                inputs: prepared.texts.clone(),
+                normalize: request.normalize,
+                truncation: request.truncation,
+            })
+            .await?;
+        if response.embeddings.len() != prepared.texts.len() {
+            return Err(EmbeddingError::Internal(format!(
+                "Provider returned {} embeddings for {} inputs",
+                response.embeddings.len(),
+                prepared.texts.len()
+            )));
+        }
+
+        response.embeddings = prepared.combine(response.embeddings, request.normalize);
+        response.usage.total_tokens = prepared.total_tokens();
+        Ok(response)
+    }
+
+    async fn model_info(&self) -> EmbeddingResult<ModelInfo> {
+        self.inner.model_info().await
+    }
+
+    async fn health_check(&self) -> EmbeddingResult<()> {
+        self.inner.health_check().await
+    }
+
+    async fn embed_multimodal(
+        &self,
+        request: MultimodalEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        self.inner.embed_multimodal(request).await
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<Range<usize>> {
+        self.inner.token_spans(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockEmbeddingProvider;
+
+    fn words(count: usize) -> String {
+        (0..count)
+            .map(|i| format!("w{}", i))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_estimate_token_spans() {
+        let text = "a tokenizer  héllo";
+        let spans: Vec<&str> = estimate_token_spans(text)
+            .into_iter()
+            .map(|span| &text[span])
+            .collect();
+        assert_eq!(spans, vec!["a", "toke", "nize", "r", "héll", "o"]);
+        assert!(estimate_token_spans("   ").is_empty());
+    }
+
+    #[test]
+    fn test_prepare_inputs_strategies() {
+        let provider = MockEmbeddingProvider::new();
+        let long = words(10);
+        let prepare = |strategy| prepare_inputs(&provider, vec![long.clone()], 6, strategy);
+
+        let tail = prepare(TruncationStrategy::TruncateTail).unwrap();
+        assert_eq!(tail.texts, vec!["w0 w1 w2 w3"]);
+        assert_eq!((tail.total_tokens(), tail.truncated), (4, 1));
+
+        let head = prepare(TruncationStrategy::TruncateHead).unwrap();
+        assert_eq!(head.texts, vec!["w6 w7 w8 w9"]);
+
+        let chunked = prepare(TruncationStrategy::ChunkAndAverage).unwrap();
+        assert_eq!(chunked.texts, vec!["w0 w1 w2 w3", "w4 w5 w6 w7", "w8 w9"]);
+        assert_eq!(chunked.chunks, vec![3]);
+        assert_eq!(chunked.total_tokens(), 10);
+
+        let err = prepare(TruncationStrategy::Error).unwrap_err();
+        assert!(matches!(err, EmbeddingError::InvalidInput(_)));
+
+        let short = prepare_inputs(&provider, vec!["w0".to_string()], 6, Default::default());
+        assert_eq!(short.unwrap().truncated, 0);
+    }
+
+    #[test]
+    fn test_combine_weights_chunks_by_tokens() {
+        let prepared = PreparedInputs {
+            texts: vec![String::new(); 3],
+            token_counts: vec![1, 3, 1],
+            chunks: vec![2, 1],
+            truncated: 1,
+        };
+        let combined =
+            prepared.combine(vec![vec![4.0, 0.0], vec![0.0, 4.0], vec![1.0, 1.0]], false);
+        assert_eq!(combined, vec![vec![1.0, 3.0], vec![1.0, 1.0]]);
+    }
+
+    #[tokio::test]
+    async fn test_truncating_provider_reports_tokens() {
+        let provider = TruncatingProvider::new(Arc::new(MockEmbeddingProvider::new()), 6);
+        let request = |truncation| BatchEmbeddingRequest {
+            model: "mock-embed-512".to_string(),
+            inputs: vec![words(10), "short".to_string()],
+            normalize: true,
+            truncation,
+        };
+
+        let response = provider
+            .embed_batch(request(TruncationStrategy::ChunkAndAverage))
+            .await
+            .unwrap();
+        assert_eq!(response.embeddings.len(), 2);
+        assert_eq!(response.usage.total_tokens, 12);
+        let norm: f32 = response.embeddings[0].iter().map(|x| x * x).sum::<f32>();
+        assert!((norm.sqrt() - 1.0).abs() < 1e-4);
+
+        let response = provider
+            .embed_batch(request(TruncationStrategy::TruncateTail))
+            .await
+            .unwrap();
+        assert_eq!(response.usage.total_tokens, 6);
+        assert!(provider
+            .embed_batch(request(TruncationStrategy::Error))
+            .await
+            .is_err());
+    }
+}
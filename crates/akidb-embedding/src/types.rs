@@ -34,6 +34,60 @@ pub struct BatchEmbeddingRequest {
     /// Whether to L2 normalize the output vectors.
     #[serde(default)]
     pub normalize: bool,
+    /// How inputs longer than the model's token limit are handled.
+    #[serde(default)]
+    pub truncation: TruncationStrategy,
+}
+
+/// What to do with inputs longer than the model's token limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Keep the beginning of the input and drop the tokens past the limit.
+    #[default]
+    TruncateTail,
+    /// Keep the end of the input and drop the tokens before it.
+    TruncateHead,
+    /// Embed the input in limit-sized chunks and average the chunk vectors,
+    /// weighted by token count.
+    ChunkAndAverage,
+    /// Reject the request with `InvalidInput`.
+    Error,
+}
+
+/// An image to embed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageInput {
+    /// Encoded image file (PNG, JPEG, ...).
+    Bytes(Vec<u8>),
+    /// HTTP(S) URL the provider downloads the image from.
+    Url(String),
+}
+
+/// A single input to a multimodal embedding model.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingInput {
+    /// Text input.
+    Text(String),
+    /// Image input.
+    Image(ImageInput),
+}
+
+/// Request for embedding a mix of text and image inputs.
+///
+/// Multimodal models (e.g. CLIP) embed both into the same vector space, so
+/// text queries can retrieve images and vice versa.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultimodalEmbeddingRequest {
+    /// Model identifier (e.g., "openai/clip-vit-base-patch32").
+    pub model: String,
+    /// Inputs to embed.
+    pub inputs: Vec<EmbeddingInput>,
+    /// Whether to L2 normalize the output vectors.
+    #[serde(default)]
+    pub normalize: bool,
 }
 
 /// Response from batch embedding generation.
@@ -56,6 +110,28 @@ pub struct Usage {
     pub duration_ms: u64,
 }
 
+/// Request to score documents against a query with a cross-encoder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankRequest {
+    /// Model identifier.
+    pub model: String,
+    /// Query the documents are scored against.
+    pub query: String,
+    /// Candidate documents.
+    pub documents: Vec<String>,
+}
+
+/// Relevance scores from a reranker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankResponse {
+    /// Model identifier that produced the scores.
+    pub model: String,
+    /// One score per document, in request order; higher is more relevant.
+    pub scores: Vec<f32>,
+    /// Usage statistics.
+    pub usage: Usage,
+}
+
 /// Model information and capabilities.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
@@ -65,4 +141,7 @@ pub struct ModelInfo {
     pub dimension: u32,
     /// Maximum input tokens supported.
     pub max_tokens: usize,
+    /// Execution provider running the model, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_provider: Option<String>,
 }
@@ -13,6 +13,8 @@ mod onnx_tests {
         assert_eq!(config.dimension, 384);
         assert_eq!(config.max_length, 512);
         assert_eq!(config.model_name, "sentence-transformers/all-MiniLM-L6-v2");
+        assert_eq!(config.execution_provider, ExecutionProviderConfig::CPU);
+        assert!(config.fallback_to_cpu);
     }
 
     #[test]
@@ -28,6 +30,7 @@ mod onnx_tests {
                 fp8_enable: true,
                 engine_cache_path: Some(PathBuf::from("/tmp/trt_cache")),
             },
+            fallback_to_cpu: false,
         };
 
         assert_eq!(config.dimension, 4096);
@@ -71,4 +74,28 @@ mod onnx_tests {
             _ => panic!("Expected CUDA execution provider"),
         }
     }
+
+    #[test]
+    fn test_onnx_config_directml() {
+        let config = OnnxConfig {
+            execution_provider: ExecutionProviderConfig::DirectML { device_id: 1 },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.execution_provider,
+            ExecutionProviderConfig::DirectML { device_id: 1 }
+        );
+    }
+
+    #[test]
+    fn test_execution_provider_names() {
+        assert_eq!(ExecutionProviderConfig::CoreML.name(), "coreml");
+        assert_eq!(ExecutionProviderConfig::CUDA { device_id: 0 }.name(), "cuda");
+        assert_eq!(
+            ExecutionProviderConfig::DirectML { device_id: 0 }.name(),
+            "directml"
+        );
+        assert_eq!(ExecutionProviderConfig::CPU.name(), "cpu");
+    }
 }
@@ -0,0 +1,81 @@
+//! Per-API-key collection allowlists for the gRPC API.
+//!
+//! Calls addressing a collection with an API key (`x-api-key` metadata or
+//! `authorization: Bearer`) restricted to other collections fail with
+//! `PERMISSION_DENIED`, as do calls without a valid key when
+//! [`CollectionAcl::require_key`] is set. See [`CollectionAcl`] for how keys
+//! are resolved.
+
+use crate::error::{error_status, status_from_core};
+use akidb_core::{CollectionId, ErrorCode};
+use akidb_service::{CollectionAccess, CollectionAcl, CollectionGrants};
+use tonic::metadata::MetadataMap;
+use tonic::Status;
+
+/// Metadata key carrying the API key.
+pub const API_KEY_METADATA: &str = "x-api-key";
+
+/// API key from `x-api-key` or `authorization: Bearer` metadata.
+pub fn api_key(metadata: &MetadataMap) -> Option<String> {
+    if let Some(key) = metadata.get(API_KEY_METADATA).and_then(|v| v.to_str().ok()) {
+        return Some(key.trim().to_string());
+    }
+
+    metadata
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|key| key.trim().to_string())
+}
+
+fn unauthenticated(api_key: Option<&str>) -> Status {
+    let message = match api_key {
+        Some(key) if !key.is_empty() => "Invalid or expired API key",
+        _ => "An API key is required",
+    };
+    error_status(ErrorCode::PermissionDenied, message, [])
+}
+
+/// Collections `api_key` may access; rejects the call if a valid key is
+/// required and missing.
+pub async fn collection_grants(
+    acl: Option<&CollectionAcl>,
+    api_key: Option<&str>,
+) -> Result<CollectionGrants, Status> {
+    let Some(acl) = acl else {
+        return Ok(CollectionGrants::All);
+    };
+
+    acl.grants(api_key)
+        .await
+        .map_err(status_from_core)?
+        .ok_or_else(|| unauthenticated(api_key))
+}
+
+/// Rejects the call if `api_key` may not access `collection_id`.
+pub async fn check_collection_access(
+    acl: Option<&CollectionAcl>,
+    api_key: Option<&str>,
+    collection_id: CollectionId,
+) -> Result<(), Status> {
+    let Some(acl) = acl else {
+        return Ok(());
+    };
+
+    match acl
+        .check(api_key, collection_id)
+        .await
+        .map_err(status_from_core)?
+    {
+        CollectionAccess::Allowed => Ok(()),
+        CollectionAccess::Denied { key_id } => Err(error_status(
+            ErrorCode::PermissionDenied,
+            format!("API key may not access collection {}", collection_id),
+            [
+                ("key_id", key_id.to_string()),
+                ("collection_id", collection_id.to_string()),
+            ],
+        )),
+        CollectionAccess::Unauthenticated => Err(unauthenticated(api_key)),
+    }
+}
@@ -1,56 +1,115 @@
-use akidb_core::{CollectionId, DocumentId, VectorDocument};
+use crate::acl;
+use crate::error::{error_status, invalid_argument, overload_status, status_from_core};
+use akidb_core::{CollectionId, DocumentId, ErrorCode, SparseVector, VectorDocument};
 use akidb_proto::{
-    collection_service_server::CollectionService as GrpcCollectionService, DeleteRequest,
-    DeleteResponse, DescribeRequest, DescribeResponse, GetRequest, GetResponse, InsertRequest,
-    InsertResponse, QueryRequest, QueryResponse, VectorDocument as ProtoVectorDocument,
-    VectorMatch,
+    collection_service_server::CollectionService as GrpcCollectionService, BatchDeleteRequest,
+    BatchDeleteResponse, BatchDeleteResult, DeleteByFilterRequest, DeleteRequest, DeleteResponse,
+    DescribeRequest, DescribeResponse, GetRequest, GetResponse, InsertRequest, InsertResponse,
+    QueryRequest, QueryResponse, QueryStreamRequest, QueryStreamResponse,
+    SparseVector as ProtoSparseVector, UpsertRequest, UpsertResponse,
+    VectorDocument as ProtoVectorDocument, VectorMatch,
+};
+use akidb_service::{
+    validation, BatchDeleteStatus, CollectionAcl, CollectionService, FilterTree, SearchOptions,
 };
-use akidb_service::CollectionService;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 
+/// Matches per streamed message when the client does not specify a batch size.
+const DEFAULT_STREAM_BATCH_SIZE: usize = 100;
+
+/// Upper bound on matches per streamed message (keeps each message well under gRPC limits).
+const MAX_STREAM_BATCH_SIZE: usize = 1_000;
+
 pub struct CollectionHandler {
     service: Arc<CollectionService>,
+    acl: Option<Arc<CollectionAcl>>,
 }
 
 impl CollectionHandler {
     pub fn new(service: Arc<CollectionService>) -> Self {
-        Self { service }
+        Self { service, acl: None }
+    }
+
+    /// Enforces API key collection allowlists on calls addressing a collection.
+    pub fn with_collection_acl(mut self, acl: Arc<CollectionAcl>) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
+    /// Resolves a collection ID or alias named in a request.
+    async fn resolve_collection(&self, id_or_alias: &str) -> Result<CollectionId, Status> {
+        self.service
+            .resolve_collection(id_or_alias)
+            .await
+            .map_err(status_from_core)
+    }
+
+    async fn authorize(
+        &self,
+        api_key: Option<&str>,
+        collection_id: CollectionId,
+    ) -> Result<(), Status> {
+        acl::check_collection_access(self.acl.as_deref(), api_key, collection_id).await
     }
 }
 
 #[tonic::async_trait]
 impl GrpcCollectionService for CollectionHandler {
+    type QueryStreamStream = ReceiverStream<Result<QueryStreamResponse, Status>>;
+
     async fn query(
         &self,
         request: Request<QueryRequest>,
     ) -> Result<Response<QueryResponse>, Status> {
         let start = Instant::now();
+        let api_key = acl::api_key(request.metadata());
         let req = request.into_inner();
 
-        // Parse collection ID
-        let collection_id = CollectionId::from_str(&req.collection_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid collection_id: {}", e)))?;
+        // Resolve collection ID or alias
+        let collection_id = self.resolve_collection(&req.collection_id).await?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
 
         // Validate query vector
-        if req.query_vector.is_empty() {
-            return Err(Status::invalid_argument("query_vector cannot be empty"));
+        if req.query_vector.is_empty() && req.sparse_query.is_none() {
+            return Err(invalid_argument("query_vector cannot be empty"));
         }
+        let sparse_query = req.sparse_query.map(sparse_from_proto).transpose()?;
+
+        let filter = match req.filter.as_deref() {
+            Some(filter) => {
+                let filter: serde_json::Value = serde_json::from_str(filter)
+                    .map_err(|e| invalid_argument(format!("Invalid filter: {}", e)))?;
+                Some(FilterTree::parse(&filter).map_err(|e| invalid_argument(e.to_string()))?)
+            }
+            None => None,
+        };
+        let options = SearchOptions {
+            filter,
+            score_threshold: req.score_threshold,
+            include_payload: req.include_payload,
+            payload_fields: (!req.payload_fields.is_empty()).then_some(req.payload_fields),
+            include_vector: req.include_vector,
+            rerank: None,
+            hybrid: None,
+            sparse: sparse_query,
+        };
 
         // Perform search
         let results = self
             .service
-            .query(collection_id, req.query_vector, req.top_k as usize)
+            .search(
+                collection_id,
+                req.query_vector,
+                req.top_k as usize,
+                &options,
+            )
             .await
-            .map_err(|e| {
-                if e.to_string().contains("not found") {
-                    Status::not_found(e.to_string())
-                } else {
-                    Status::internal(e.to_string())
-                }
-            })?;
+            .map_err(status_from_core)?;
 
         // Convert to protobuf
         let matches = results
@@ -59,6 +118,9 @@ impl GrpcCollectionService for CollectionHandler {
                 doc_id: r.doc_id.to_string(),
                 external_id: r.external_id,
                 distance: r.score,
+                metadata: r.metadata.map(|m| m.to_string()),
+                vector: r.vector.unwrap_or_default(),
+                sparse: r.sparse.map(sparse_to_proto),
             })
             .collect();
 
@@ -68,35 +130,121 @@ impl GrpcCollectionService for CollectionHandler {
         }))
     }
 
+    async fn query_stream(
+        &self,
+        request: Request<QueryStreamRequest>,
+    ) -> Result<Response<Self::QueryStreamStream>, Status> {
+        let api_key = acl::api_key(request.metadata());
+        let req = request.into_inner();
+
+        let collection_id = self.resolve_collection(&req.collection_id).await?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
+
+        if req.query_vector.is_empty() {
+            return Err(invalid_argument("query_vector cannot be empty"));
+        }
+
+        let batch_size = match req.batch_size as usize {
+            0 => DEFAULT_STREAM_BATCH_SIZE,
+            n => n.min(MAX_STREAM_BATCH_SIZE),
+        };
+        let offset =
+            usize::try_from(req.offset).map_err(|_| invalid_argument("offset is out of range"))?;
+
+        // Search for offset + top_k matches, then skip the ones already scrolled past.
+        // Errors (unknown collection, invalid top_k) surface before the stream starts.
+        let search_k = offset
+            .checked_add(req.top_k as usize)
+            .ok_or_else(|| invalid_argument("offset + top_k is out of range"))?;
+        let results = self
+            .service
+            .query(collection_id, req.query_vector, search_k)
+            .await
+            .map_err(status_from_core)?;
+
+        let matches: Vec<VectorMatch> = results
+            .into_iter()
+            .skip(offset)
+            .map(|r| VectorMatch {
+                doc_id: r.doc_id.to_string(),
+                external_id: r.external_id,
+                distance: r.score,
+                ..VectorMatch::default()
+            })
+            .collect();
+
+        let (tx, rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            let mut next_offset = offset;
+            let mut batches = matches.chunks(batch_size).peekable();
+
+            if batches.peek().is_none() {
+                let _ = tx
+                    .send(Ok(QueryStreamResponse {
+                        matches: Vec::new(),
+                        next_offset: next_offset as u64,
+                        done: true,
+                    }))
+                    .await;
+                return;
+            }
+
+            while let Some(batch) = batches.next() {
+                next_offset += batch.len();
+                let response = QueryStreamResponse {
+                    matches: batch.to_vec(),
+                    next_offset: next_offset as u64,
+                    done: batches.peek().is_none(),
+                };
+
+                // Client went away; stop producing batches
+                if tx.send(Ok(response)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
     async fn insert(
         &self,
         request: Request<InsertRequest>,
     ) -> Result<Response<InsertResponse>, Status> {
         let start = Instant::now();
+        let api_key = acl::api_key(request.metadata());
         let req = request.into_inner();
 
-        let collection_id = CollectionId::from_str(&req.collection_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid collection_id: {}", e)))?;
+        let collection_id = self.resolve_collection(&req.collection_id).await?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
 
         let doc_id = DocumentId::from_str(&req.doc_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid doc_id: {}", e)))?;
+            .map_err(|e| invalid_argument(format!("Invalid doc_id: {}", e)))?;
 
-        if req.vector.is_empty() {
-            return Err(Status::invalid_argument("vector cannot be empty"));
+        if req.vector.is_empty() && req.sparse.is_none() {
+            return Err(invalid_argument("vector cannot be empty"));
         }
 
         let mut doc = VectorDocument::new(doc_id, req.vector);
+        if let Some(sparse) = req.sparse {
+            doc = doc.with_sparse(sparse_from_proto(sparse)?);
+        }
         if let Some(external_id) = req.external_id {
             doc = doc.with_external_id(external_id);
         }
 
-        let inserted_id = self.service.insert(collection_id, doc).await.map_err(|e| {
-            if e.to_string().contains("not found") {
-                Status::not_found(e.to_string())
-            } else {
-                Status::internal(e.to_string())
-            }
-        })?;
+        validation::validate_document(&doc, self.service.limits()).map_err(validation_status)?;
+
+        self.service
+            .check_write_backpressure()
+            .await
+            .map_err(|overload| overload_status(&overload))?;
+
+        let inserted_id = self
+            .service
+            .insert(collection_id, doc)
+            .await
+            .map_err(status_from_core)?;
 
         Ok(Response::new(InsertResponse {
             doc_id: inserted_id.to_string(),
@@ -104,28 +252,74 @@ impl GrpcCollectionService for CollectionHandler {
         }))
     }
 
+    async fn upsert(
+        &self,
+        request: Request<UpsertRequest>,
+    ) -> Result<Response<UpsertResponse>, Status> {
+        let start = Instant::now();
+        let api_key = acl::api_key(request.metadata());
+        let req = request.into_inner();
+
+        let collection_id = self.resolve_collection(&req.collection_id).await?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
+
+        if req.vector.is_empty() {
+            return Err(invalid_argument("vector cannot be empty"));
+        }
+        let metadata = match req.metadata.as_deref() {
+            Some(metadata) => Some(
+                serde_json::from_str(metadata)
+                    .map_err(|e| invalid_argument(format!("Invalid metadata: {}", e)))?,
+            ),
+            None => None,
+        };
+
+        // The document ID is assigned by the service; only the payload is checked here
+        let mut doc = VectorDocument::new(DocumentId::new(), Vec::new())
+            .with_external_id(req.external_id.clone());
+        doc.metadata = metadata;
+        validation::validate_document(&doc, self.service.limits()).map_err(validation_status)?;
+
+        self.service
+            .check_write_backpressure()
+            .await
+            .map_err(|overload| overload_status(&overload))?;
+
+        let (doc_id, created) = self
+            .service
+            .upsert_by_external_id(collection_id, req.external_id, req.vector, doc.metadata)
+            .await
+            .map_err(status_from_core)?;
+
+        Ok(Response::new(UpsertResponse {
+            doc_id: doc_id.to_string(),
+            created,
+            latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+        }))
+    }
+
     async fn get(&self, request: Request<GetRequest>) -> Result<Response<GetResponse>, Status> {
+        let api_key = acl::api_key(request.metadata());
         let req = request.into_inner();
 
-        let collection_id = CollectionId::from_str(&req.collection_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid collection_id: {}", e)))?;
+        let collection_id = self.resolve_collection(&req.collection_id).await?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
 
         let doc_id = DocumentId::from_str(&req.doc_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid doc_id: {}", e)))?;
+            .map_err(|e| invalid_argument(format!("Invalid doc_id: {}", e)))?;
 
-        let doc = self.service.get(collection_id, doc_id).await.map_err(|e| {
-            if e.to_string().contains("not found") {
-                Status::not_found(e.to_string())
-            } else {
-                Status::internal(e.to_string())
-            }
-        })?;
+        let doc = self
+            .service
+            .get(collection_id, doc_id)
+            .await
+            .map_err(status_from_core)?;
 
         let document = doc.map(|d| ProtoVectorDocument {
             doc_id: d.doc_id.to_string(),
             external_id: d.external_id,
             vector: d.vector,
             inserted_at: d.inserted_at.to_rfc3339(),
+            sparse: d.sparse.map(sparse_to_proto),
         });
 
         Ok(Response::new(GetResponse { document }))
@@ -136,47 +330,124 @@ impl GrpcCollectionService for CollectionHandler {
         request: Request<DeleteRequest>,
     ) -> Result<Response<DeleteResponse>, Status> {
         let start = Instant::now();
+        let api_key = acl::api_key(request.metadata());
         let req = request.into_inner();
 
-        let collection_id = CollectionId::from_str(&req.collection_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid collection_id: {}", e)))?;
+        let collection_id = self.resolve_collection(&req.collection_id).await?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
 
         let doc_id = DocumentId::from_str(&req.doc_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid doc_id: {}", e)))?;
+            .map_err(|e| invalid_argument(format!("Invalid doc_id: {}", e)))?;
 
         self.service
             .delete(collection_id, doc_id)
             .await
-            .map_err(|e| {
-                if e.to_string().contains("not found") {
-                    Status::not_found(e.to_string())
-                } else {
-                    Status::internal(e.to_string())
-                }
-            })?;
+            .map_err(status_from_core)?;
 
         Ok(Response::new(DeleteResponse {
             latency_ms: start.elapsed().as_secs_f64() * 1000.0,
         }))
     }
 
+    async fn batch_delete(
+        &self,
+        request: Request<BatchDeleteRequest>,
+    ) -> Result<Response<BatchDeleteResponse>, Status> {
+        let start = Instant::now();
+        let api_key = acl::api_key(request.metadata());
+        let req = request.into_inner();
+
+        let collection_id = self.resolve_collection(&req.collection_id).await?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
+
+        if req.doc_ids.is_empty() {
+            return Err(invalid_argument("doc_ids cannot be empty"));
+        }
+        validation::validate_batch_size(req.doc_ids.len(), self.service.limits())
+            .map_err(validation_status)?;
+
+        // Results are filled in request order; unparseable IDs never reach the service
+        let mut results = Vec::with_capacity(req.doc_ids.len());
+        let mut pending = Vec::new();
+        for id in req.doc_ids {
+            let status = match DocumentId::from_str(&id) {
+                Ok(doc_id) => {
+                    pending.push((results.len(), doc_id));
+                    "pending"
+                }
+                Err(_) => "invalid_id",
+            };
+            results.push(BatchDeleteResult {
+                doc_id: id,
+                status: status.to_string(),
+                error: None,
+            });
+        }
+
+        let doc_ids = pending.iter().map(|(_, doc_id)| *doc_id).collect();
+        let statuses = self
+            .service
+            .delete_batch(collection_id, doc_ids)
+            .await
+            .map_err(status_from_core)?;
+        for ((slot, _), (_, status)) in pending.iter().zip(statuses) {
+            set_delete_status(&mut results[*slot], status);
+        }
+
+        Ok(Response::new(batch_delete_response(results, start)))
+    }
+
+    async fn delete_by_filter(
+        &self,
+        request: Request<DeleteByFilterRequest>,
+    ) -> Result<Response<BatchDeleteResponse>, Status> {
+        let start = Instant::now();
+        let api_key = acl::api_key(request.metadata());
+        let req = request.into_inner();
+
+        let collection_id = self.resolve_collection(&req.collection_id).await?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
+
+        let filter: serde_json::Value = serde_json::from_str(&req.filter)
+            .map_err(|e| invalid_argument(format!("Invalid filter: {}", e)))?;
+        let filter = FilterTree::parse(&filter).map_err(|e| invalid_argument(e.to_string()))?;
+
+        let results = self
+            .service
+            .delete_by_filter(collection_id, &filter)
+            .await
+            .map_err(status_from_core)?
+            .into_iter()
+            .map(|(doc_id, status)| {
+                let mut result = BatchDeleteResult {
+                    doc_id: doc_id.to_string(),
+                    status: String::new(),
+                    error: None,
+                };
+                set_delete_status(&mut result, status);
+                result
+            })
+            .collect();
+
+        Ok(Response::new(batch_delete_response(results, start)))
+    }
+
     async fn describe(
         &self,
         request: Request<DescribeRequest>,
     ) -> Result<Response<DescribeResponse>, Status> {
+        let api_key = acl::api_key(request.metadata());
         let req = request.into_inner();
 
-        let collection_id = CollectionId::from_str(&req.collection_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid collection_id: {}", e)))?;
+        let collection_id = self.resolve_collection(&req.collection_id).await?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
 
         // Get document count from service
-        let document_count = self.service.get_count(collection_id).await.map_err(|e| {
-            if e.to_string().contains("not found") {
-                Status::not_found(e.to_string())
-            } else {
-                Status::internal(e.to_string())
-            }
-        })?;
+        let document_count = self
+            .service
+            .get_count(collection_id)
+            .await
+            .map_err(status_from_core)?;
 
         // For now, return basic info (TODO: integrate with metadata layer)
         Ok(Response::new(DescribeResponse {
@@ -188,3 +459,52 @@ impl GrpcCollectionService for CollectionHandler {
         }))
     }
 }
+
+fn validation_status(e: validation::ValidationError) -> Status {
+    let code = if e.is_payload_too_large() {
+        ErrorCode::PayloadTooLarge
+    } else {
+        ErrorCode::InvalidArgument
+    };
+    error_status(
+        code,
+        e.to_string(),
+        [
+            ("reason", e.code().to_string()),
+            ("limit", e.limit().to_string()),
+            ("actual", e.actual().to_string()),
+        ],
+    )
+}
+
+/// Validates a sparse vector from a request.
+pub(crate) fn sparse_from_proto(sparse: ProtoSparseVector) -> Result<SparseVector, Status> {
+    SparseVector::new(sparse.indices, sparse.values).map_err(status_from_core)
+}
+
+pub(crate) fn sparse_to_proto(sparse: SparseVector) -> ProtoSparseVector {
+    ProtoSparseVector {
+        indices: sparse.indices().to_vec(),
+        values: sparse.values().to_vec(),
+    }
+}
+
+fn set_delete_status(result: &mut BatchDeleteResult, status: BatchDeleteStatus) {
+    match status {
+        BatchDeleteStatus::Deleted => result.status = "deleted".to_string(),
+        BatchDeleteStatus::NotFound => result.status = "not_found".to_string(),
+        BatchDeleteStatus::Failed(error) => {
+            result.status = "error".to_string();
+            result.error = Some(error);
+        }
+    }
+}
+
+fn batch_delete_response(results: Vec<BatchDeleteResult>, start: Instant) -> BatchDeleteResponse {
+    let deleted = results.iter().filter(|r| r.status == "deleted").count();
+    BatchDeleteResponse {
+        results,
+        deleted: deleted as u64,
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+    }
+}
@@ -1,19 +1,84 @@
+use crate::acl;
+use crate::error::{error_status, invalid_argument, overload_status, status_from_core};
+use akidb_core::{CollectionId, ErrorCode, TenantId};
 use akidb_proto::embedding::{
-    embedding_service_server::EmbeddingService as GrpcEmbeddingService, EmbedRequest,
+    embedding_service_server::EmbeddingService as GrpcEmbeddingService, image_input, EmbedRequest,
     EmbedResponse, Embedding, GetModelInfoRequest, GetModelInfoResponse, UsageInfo,
 };
-use akidb_service::EmbeddingManager;
+use akidb_service::{
+    CollectionAcl, CollectionService, EmbeddingInput, EmbeddingManager, ImageInput,
+};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
 use tonic::{Request, Response, Status};
 
+/// Metadata key naming the tenant whose embed QPS budget a request uses.
+const TENANT_METADATA_KEY: &str = "x-tenant-id";
+
 pub struct EmbeddingHandler {
     embedding_manager: Arc<EmbeddingManager>,
+    collection_service: Arc<CollectionService>,
+    acl: Option<Arc<CollectionAcl>>,
 }
 
 impl EmbeddingHandler {
-    pub fn new(embedding_manager: Arc<EmbeddingManager>) -> Self {
-        Self { embedding_manager }
+    pub fn new(
+        embedding_manager: Arc<EmbeddingManager>,
+        collection_service: Arc<CollectionService>,
+    ) -> Self {
+        Self {
+            embedding_manager,
+            collection_service,
+            acl: None,
+        }
+    }
+
+    /// Enforces API key collection allowlists on requests naming a collection.
+    pub fn with_collection_acl(mut self, acl: Arc<CollectionAcl>) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
+    /// Model selected by the request's `model` or `collection_id`.
+    async fn resolve_model(
+        &self,
+        api_key: Option<&str>,
+        model: Option<String>,
+        collection_id: Option<String>,
+    ) -> Result<String, Status> {
+        let manager = &self.embedding_manager;
+        match (collection_id, model) {
+            (Some(_), Some(_)) => Err(invalid_argument(
+                "Specify either model or collection_id, not both",
+            )),
+            (Some(collection_id), None) => {
+                let collection_id = CollectionId::from_str(&collection_id)
+                    .map_err(|e| invalid_argument(format!("Invalid collection_id: {}", e)))?;
+                acl::check_collection_access(self.acl.as_deref(), api_key, collection_id).await?;
+                let collection = self
+                    .collection_service
+                    .get_collection(collection_id)
+                    .await
+                    .map_err(status_from_core)?;
+                manager
+                    .model_for_collection(&collection)
+                    .map(str::to_string)
+                    .ok_or_else(|| {
+                        invalid_argument(format!(
+                            "Collection embedding model '{}' is not configured",
+                            collection.embedding_model
+                        ))
+                    })
+            }
+            (None, Some(model)) if !manager.has_model(&model) => Err(invalid_argument(format!(
+                "Unknown embedding model '{}'. Available: {}",
+                model,
+                manager.model_names().join(", ")
+            ))),
+            (None, Some(model)) => Ok(model),
+            (None, None) => Ok(manager.model_name().to_string()),
+        }
     }
 }
 
@@ -24,39 +89,98 @@ impl GrpcEmbeddingService for EmbeddingHandler {
         request: Request<EmbedRequest>,
     ) -> Result<Response<EmbedResponse>, Status> {
         let start = Instant::now();
+        // Requests without a tenant share one budget
+        let tenant = request
+            .metadata()
+            .get(TENANT_METADATA_KEY)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_string())
+            .unwrap_or_else(|| "default".to_string());
+        let api_key = acl::api_key(request.metadata());
         let req = request.into_inner();
 
         // Validate input
-        if req.texts.is_empty() {
-            return Err(Status::invalid_argument("texts cannot be empty"));
+        if req.texts.is_empty() && req.images.is_empty() {
+            return Err(invalid_argument("texts cannot be empty"));
         }
 
-        if req.texts.len() > 32 {
-            return Err(Status::invalid_argument("Maximum 32 texts per request"));
+        if req.texts.len() + req.images.len() > 32 {
+            return Err(invalid_argument("Maximum 32 inputs per request"));
         }
 
+        self.embedding_manager
+            .check_backpressure()
+            .and_then(|()| self.embedding_manager.check_tenant_rate(&tenant))
+            .map_err(|overload| overload_status(&overload))?;
+
+        let model = self
+            .resolve_model(
+                api_key.as_deref(),
+                req.model.clone(),
+                req.collection_id.clone(),
+            )
+            .await?;
+
         tracing::info!(
-            "gRPC Embedding request: {} texts, model: {:?}",
+            "gRPC Embedding request: {} texts, {} images, model: {}",
             req.texts.len(),
-            req.model
+            req.images.len(),
+            model
         );
 
         // Generate embeddings
-        let embedding_vectors = self
+        let embedding_vectors = if req.images.is_empty() {
+            self.embedding_manager
+                .embed_with_model(Some(&model), req.texts.clone())
+                .await
+        } else {
+            let mut inputs: Vec<EmbeddingInput> = req
+                .texts
+                .iter()
+                .cloned()
+                .map(EmbeddingInput::Text)
+                .collect();
+            for image in &req.images {
+                let input = match image.source.clone() {
+                    Some(image_input::Source::Data(data)) => ImageInput::Bytes(data),
+                    Some(image_input::Source::Url(url)) => ImageInput::Url(url),
+                    None => return Err(invalid_argument("Each image needs data or url")),
+                };
+                inputs.push(EmbeddingInput::Image(input));
+            }
+            self.embedding_manager
+                .embed_inputs(Some(&model), inputs)
+                .await
+        }
+        .map_err(|e| {
+            tracing::error!("Embedding generation failed: {}", e);
+            if e.contains("Invalid input:") {
+                invalid_argument(e)
+            } else if e.contains("Service unavailable:") {
+                error_status(ErrorCode::Unavailable, e, [])
+            } else {
+                error_status(
+                    ErrorCode::Internal,
+                    format!("Embedding generation failed: {}", e),
+                    [],
+                )
+            }
+        })?;
+
+        // Get model info for dimension
+        let model_info = self
             .embedding_manager
-            .embed(req.texts.clone())
+            .model_info_for(Some(&model))
             .await
             .map_err(|e| {
-                tracing::error!("Embedding generation failed: {}", e);
-                Status::internal(format!("Embedding generation failed: {}", e))
+                tracing::error!("Failed to get model info: {}", e);
+                error_status(
+                    ErrorCode::Internal,
+                    format!("Failed to get model info: {}", e),
+                    [],
+                )
             })?;
 
-        // Get model info for dimension
-        let model_info = self.embedding_manager.model_info().await.map_err(|e| {
-            tracing::error!("Failed to get model info: {}", e);
-            Status::internal(format!("Failed to get model info: {}", e))
-        })?;
-
         // Convert to protobuf Embedding format
         let embeddings: Vec<Embedding> = embedding_vectors
             .into_iter()
@@ -66,13 +190,24 @@ impl GrpcEmbeddingService for EmbeddingHandler {
         // Calculate duration
         let duration_ms = start.elapsed().as_millis() as u64;
 
-        // Estimate token count (rough: 1 token ~= 4 characters)
-        let total_tokens: u64 = req
-            .texts
-            .iter()
-            .map(|s| (s.len() / 4) as u64)
-            .sum::<u64>()
-            .max(req.texts.len() as u64);
+        // Tokens embedded after truncation, counted with the model's
+        // tokenizer; each image counts as one
+        let text_tokens = self
+            .embedding_manager
+            .count_tokens(Some(&model), &req.texts)
+            .await
+            .map_err(|e| {
+                error_status(
+                    ErrorCode::Internal,
+                    format!("Failed to count tokens: {}", e),
+                    [],
+                )
+            })?;
+        let total_tokens = (text_tokens + req.images.len()) as u64;
+        // Billed to the default database's tenant unless a tenant ID is given
+        self.collection_service
+            .record_embedding_usage(TenantId::from_str(&tenant).ok(), total_tokens)
+            .await;
 
         tracing::info!(
             "gRPC Embedding completed: {} embeddings generated in {}ms (dimension: {})",
@@ -83,7 +218,7 @@ impl GrpcEmbeddingService for EmbeddingHandler {
 
         Ok(Response::new(EmbedResponse {
             embeddings,
-            model: req.model.unwrap_or_else(|| "qwen3-0.6b-4bit".to_string()),
+            model,
             dimension: model_info.dimension,
             usage: Some(UsageInfo {
                 total_tokens,
@@ -94,12 +229,23 @@ impl GrpcEmbeddingService for EmbeddingHandler {
 
     async fn get_model_info(
         &self,
-        _request: Request<GetModelInfoRequest>,
+        request: Request<GetModelInfoRequest>,
     ) -> Result<Response<GetModelInfoResponse>, Status> {
-        let model_info = self.embedding_manager.model_info().await.map_err(|e| {
-            tracing::error!("Failed to get model info: {}", e);
-            Status::internal(format!("Failed to get model info: {}", e))
-        })?;
+        let model = self
+            .resolve_model(None, request.into_inner().model, None)
+            .await?;
+        let model_info = self
+            .embedding_manager
+            .model_info_for(Some(&model))
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get model info: {}", e);
+                error_status(
+                    ErrorCode::Internal,
+                    format!("Failed to get model info: {}", e),
+                    [],
+                )
+            })?;
 
         Ok(Response::new(GetModelInfoResponse {
             model: model_info.model,
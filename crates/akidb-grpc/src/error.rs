@@ -0,0 +1,161 @@
+//! Mapping of service errors to gRPC statuses.
+//!
+//! Every error status carries the same machine-readable information as the
+//! REST error body: a `google.rpc.ErrorInfo` in the status details (`reason`
+//! is the [`ErrorCode`], `domain` is `akidb`, `metadata` holds `retryable`
+//! and any extra details), plus `akidb-error-code` / `akidb-retryable`
+//! metadata entries for clients that do not decode details.
+
+use akidb_core::{CoreError, ErrorCode};
+use akidb_service::Overload;
+use prost::Message;
+use std::collections::HashMap;
+use tonic::codegen::Bytes;
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::{Code, Status};
+
+/// Metadata key carrying the error code.
+pub const ERROR_CODE_METADATA: &str = "akidb-error-code";
+
+/// Metadata key carrying `true`/`false` for whether a retry may succeed.
+pub const RETRYABLE_METADATA: &str = "akidb-retryable";
+
+/// Metadata key carrying the suggested retry delay in seconds.
+pub const RETRY_AFTER_METADATA: &str = "retry-after";
+
+const ERROR_DOMAIN: &str = "akidb";
+const ERROR_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+
+/// `google.rpc.Status`, as carried in `grpc-status-details-bin`.
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<Any>,
+}
+
+/// `google.protobuf.Any`.
+#[derive(Clone, PartialEq, Message)]
+struct Any {
+    #[prost(string, tag = "1")]
+    type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+/// `google.rpc.ErrorInfo`.
+#[derive(Clone, PartialEq, Message)]
+struct ErrorInfo {
+    #[prost(string, tag = "1")]
+    reason: String,
+    #[prost(string, tag = "2")]
+    domain: String,
+    #[prost(map = "string, string", tag = "3")]
+    metadata: HashMap<String, String>,
+}
+
+/// gRPC status code used for each error code.
+pub fn grpc_code(code: ErrorCode) -> Code {
+    match code {
+        ErrorCode::InvalidArgument | ErrorCode::DimensionMismatch => Code::InvalidArgument,
+        ErrorCode::PayloadTooLarge | ErrorCode::QuotaExceeded | ErrorCode::RateLimited => {
+            Code::ResourceExhausted
+        }
+        ErrorCode::CollectionNotFound | ErrorCode::DocumentNotFound | ErrorCode::NotFound => {
+            Code::NotFound
+        }
+        ErrorCode::AlreadyExists => Code::AlreadyExists,
+        ErrorCode::Conflict => Code::Aborted,
+        ErrorCode::FailedPrecondition => Code::FailedPrecondition,
+        ErrorCode::PermissionDenied => Code::PermissionDenied,
+        ErrorCode::Unavailable => Code::Unavailable,
+        ErrorCode::Internal => Code::Internal,
+    }
+}
+
+/// Builds a status carrying `code` in its details and metadata.
+pub fn error_status(
+    code: ErrorCode,
+    message: impl Into<String>,
+    details: impl IntoIterator<Item = (&'static str, String)>,
+) -> Status {
+    let message = message.into();
+    let grpc_code = grpc_code(code);
+
+    let mut info = ErrorInfo {
+        reason: code.as_str().to_string(),
+        domain: ERROR_DOMAIN.to_string(),
+        metadata: details
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect(),
+    };
+    info.metadata
+        .insert("retryable".to_string(), code.is_retryable().to_string());
+    let status = RpcStatus {
+        code: grpc_code as i32,
+        message: message.clone(),
+        details: vec![Any {
+            type_url: ERROR_INFO_TYPE_URL.to_string(),
+            value: info.encode_to_vec(),
+        }],
+    };
+
+    let mut metadata = MetadataMap::new();
+    metadata.insert(
+        ERROR_CODE_METADATA,
+        MetadataValue::from_static(code.as_str()),
+    );
+    metadata.insert(
+        RETRYABLE_METADATA,
+        MetadataValue::from_static(if code.is_retryable() { "true" } else { "false" }),
+    );
+
+    Status::with_details_and_metadata(
+        grpc_code,
+        message,
+        Bytes::from(status.encode_to_vec()),
+        metadata,
+    )
+}
+
+/// `INVALID_ARGUMENT` status for malformed requests.
+pub fn invalid_argument(message: impl Into<String>) -> Status {
+    error_status(ErrorCode::InvalidArgument, message, [])
+}
+
+/// Status for a request rejected by queue-depth backpressure.
+///
+/// gRPC has no `Retry-After`; the suggested delay is carried as
+/// `retry_after_seconds` in the error details and as `retry-after` metadata.
+pub fn overload_status(overload: &Overload) -> Status {
+    let retry_after = overload.retry_after_secs().to_string();
+    let mut status = error_status(
+        overload.code(),
+        overload.to_string(),
+        [
+            ("queue", overload.queue.as_str().to_string()),
+            ("depth", overload.depth.to_string()),
+            ("limit", overload.limit.to_string()),
+            ("retry_after_seconds", retry_after.clone()),
+        ],
+    );
+    if let Ok(value) = MetadataValue::try_from(retry_after) {
+        status.metadata_mut().insert(RETRY_AFTER_METADATA, value);
+    }
+    status
+}
+
+/// Converts a service error into a status (use with `map_err`).
+pub fn status_from_core(err: CoreError) -> Status {
+    let details = match &err {
+        CoreError::NotFound { entity, id } => {
+            vec![("entity", entity.to_string()), ("id", id.clone())]
+        }
+        _ => Vec::new(),
+    };
+    error_status(err.code(), err.to_string(), details)
+}
@@ -0,0 +1,92 @@
+//! Standard gRPC health checking (`grpc.health.v1.Health`).
+//!
+//! Kubernetes gRPC probes and Envoy health checks query this service instead
+//! of the REST `/ready` endpoint. Per-service status is derived from the same
+//! readiness logic (`CollectionService::is_ready`) and refreshed periodically.
+//! The empty service name reports liveness (`CollectionService::is_healthy`),
+//! so a probe without a service name does not restart a pod that is merely
+//! not ready yet.
+
+use crate::{CollectionHandler, CollectionManagementHandler, EmbeddingHandler};
+use akidb_proto::collection_management_service_server::CollectionManagementServiceServer;
+use akidb_proto::collection_service_server::CollectionServiceServer;
+use akidb_proto::embedding::embedding_service_server::EmbeddingServiceServer;
+use akidb_service::CollectionService;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tonic::server::NamedService;
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+
+/// How often readiness is re-evaluated.
+pub const HEALTH_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Keeps the health service's per-service statuses in sync with readiness.
+pub struct HealthMonitor {
+    reporter: HealthReporter,
+    service: Arc<CollectionService>,
+    embedding_available: bool,
+}
+
+impl HealthMonitor {
+    /// Creates a monitor publishing to `reporter`.
+    ///
+    /// `embedding_available` is false when the embedding provider failed to
+    /// initialize; the embedding service is then reported as `NOT_SERVING`.
+    pub fn new(
+        reporter: HealthReporter,
+        service: Arc<CollectionService>,
+        embedding_available: bool,
+    ) -> Self {
+        Self {
+            reporter,
+            service,
+            embedding_available,
+        }
+    }
+
+    /// Re-evaluates readiness and publishes the resulting statuses.
+    pub async fn refresh(&mut self) {
+        let server = serving_status(self.service.is_healthy());
+        let collections = serving_status(self.service.is_ready().await);
+        let embedding = serving_status(self.embedding_available);
+
+        for (name, status) in [
+            ("", server),
+            (
+                <CollectionServiceServer<CollectionHandler> as NamedService>::NAME,
+                collections,
+            ),
+            (
+                <CollectionManagementServiceServer<CollectionManagementHandler> as NamedService>::NAME,
+                collections,
+            ),
+            (
+                <EmbeddingServiceServer<EmbeddingHandler> as NamedService>::NAME,
+                embedding,
+            ),
+        ] {
+            self.reporter.set_service_status(name, status).await;
+        }
+    }
+
+    /// Refreshes statuses every [`HEALTH_REFRESH_INTERVAL`] in the background.
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEALTH_REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                self.refresh().await;
+            }
+        })
+    }
+}
+
+fn serving_status(ready: bool) -> ServingStatus {
+    if ready {
+        ServingStatus::Serving
+    } else {
+        ServingStatus::NotServing
+    }
+}
@@ -1,7 +1,15 @@
+pub mod acl;
 mod collection_handler;
 mod embedding_handler;
+pub mod error;
+pub mod health;
 mod management_handler;
+pub mod rate_limit;
+mod replication_handler;
+pub mod tls;
+pub mod trace;
 
 pub use collection_handler::CollectionHandler;
 pub use embedding_handler::EmbeddingHandler;
 pub use management_handler::CollectionManagementHandler;
+pub use replication_handler::{run_standby, ReplicationHandler};
@@ -1,11 +1,33 @@
-use akidb_grpc::{CollectionHandler, CollectionManagementHandler, EmbeddingHandler};
-use akidb_metadata::{SqliteCollectionRepository, VectorPersistence};
+use akidb_grpc::health::HealthMonitor;
+use akidb_grpc::rate_limit::RateLimitLayer;
+use akidb_grpc::tls::TlsConnection;
+use akidb_grpc::{
+    run_standby, CollectionHandler, CollectionManagementHandler, EmbeddingHandler,
+    ReplicationHandler,
+};
+use akidb_metadata::{
+    SqliteAliasRepository, SqliteApiKeyRepository, SqliteAuditLogRepository,
+    SqliteCollectionRepository, SqliteDatabaseRepository, SqliteFeatureFlagRepository,
+    SqliteLeaseRepository, SqliteTenantCatalog, SqliteUsageRepository, TierStateRepository,
+    VectorPersistence,
+};
 use akidb_proto::collection_management_service_server::CollectionManagementServiceServer;
 use akidb_proto::collection_service_server::CollectionServiceServer;
 use akidb_proto::embedding::embedding_service_server::EmbeddingServiceServer;
-use akidb_service::{CollectionService, Config, EmbeddingManager};
+use akidb_proto::replication::replication_service_server::ReplicationServiceServer;
+use akidb_service::reload::init_logging;
+use akidb_service::tls::ReloadableTlsConfig;
+use akidb_service::{
+    AuditTrail, CdcPublisher, CollectionAcl, CollectionService, Config, ConfigReloader,
+    DrainConfig, EmbeddingManager, FeatureFlags, LeaderElection, OtlpMetricsConfig,
+    OtlpMetricsExporter, RateLimiter, ReplicaApplier, ReplicationRules, SnapshotScheduler,
+    TieringManager, UsageMeter, WalShipper,
+};
 use sqlx::SqlitePool;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
 use tonic::transport::Server;
 
 #[tokio::main]
@@ -19,21 +41,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Validate configuration
     config.validate()?;
 
-    // Initialize logging based on config
-    let subscriber =
-        tracing_subscriber::fmt().with_max_level(match config.logging.level.as_str() {
-            "trace" => tracing::Level::TRACE,
-            "debug" => tracing::Level::DEBUG,
-            "info" => tracing::Level::INFO,
-            "warn" => tracing::Level::WARN,
-            "error" => tracing::Level::ERROR,
-            _ => tracing::Level::INFO,
-        });
-
-    if config.logging.format == "json" {
-        subscriber.json().init();
+    // Initialize logging based on config; ENABLE_TRACING=true exports spans to Jaeger
+    let tracing_enabled = std::env::var("ENABLE_TRACING").is_ok_and(|v| v == "true");
+    let tracing_error = if tracing_enabled {
+        let jaeger_endpoint = std::env::var("JAEGER_ENDPOINT")
+            .unwrap_or_else(|_| "http://jaeger:14268/api/traces".to_string());
+        let service_name =
+            std::env::var("SERVICE_NAME").unwrap_or_else(|_| "akidb-grpc".to_string());
+        akidb_service::trace_context::init_tracing(&service_name, &jaeger_endpoint).err()
     } else {
-        subscriber.init();
+        None
+    };
+
+    // Only basic logging's level can be changed by a config reload
+    let log_level =
+        (!tracing_enabled || tracing_error.is_some()).then(|| init_logging(&config.logging));
+    if let Some(e) = tracing_error {
+        tracing::warn!(
+            "⚠️  Failed to initialize tracing: {}. Falling back to basic logging.",
+            e
+        );
     }
 
     // Initialize SQLite database
@@ -46,13 +73,92 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .run(&pool)
         .await?;
 
+    // Runtime feature flags, stored in the metadata database and evaluated per tenant
+    let feature_flags = Arc::new(FeatureFlags::new(&config.features.flags).with_repository(
+        Arc::new(SqliteFeatureFlagRepository::new(pool.clone())),
+        Arc::new(SqliteDatabaseRepository::new(pool.clone())),
+    ));
+    feature_flags.refresh().await?;
+    let flag_refresh = akidb_service::feature_flags::spawn_refresh(
+        Arc::clone(&feature_flags),
+        Duration::from_secs(config.features.flag_refresh_interval_seconds),
+    );
+
     // Create repository and service with full persistence (collections + vectors + metrics)
     let repository = Arc::new(SqliteCollectionRepository::new(pool.clone()));
     let vector_persistence = Arc::new(VectorPersistence::new(pool.clone()));
-    let service = Arc::new(CollectionService::with_full_persistence(
-        repository,
-        vector_persistence,
-    ));
+    let service = CollectionService::with_full_persistence(repository, vector_persistence)
+        .with_limits(config.limits.clone())
+        .with_index_graph_persistence(&config.hnsw)
+        .with_backpressure(&config.backpressure)
+        .with_alias_repository(Arc::new(SqliteAliasRepository::new(pool.clone())))
+        .with_audit(Arc::new(AuditTrail::new(
+            Arc::new(SqliteAuditLogRepository::new(pool.clone())),
+            Arc::new(SqliteDatabaseRepository::new(pool.clone())),
+            &config.audit,
+        )))
+        .with_feature_flags(Arc::clone(&feature_flags));
+    let service = if config.metering.enabled {
+        tracing::info!(
+            "🧾 Metering per-tenant usage, flushing every {}s",
+            config.metering.flush_interval_seconds
+        );
+        service.with_metering(Arc::new(UsageMeter::new(
+            Arc::new(SqliteUsageRepository::new(pool.clone())),
+            Arc::new(SqliteDatabaseRepository::new(pool.clone())),
+        )))
+    } else {
+        service
+    };
+
+    // Leader election for singleton maintenance across processes sharing the database
+    let leader_election = config.leader_election.enabled.then(|| {
+        let election = Arc::new(LeaderElection::from_config(
+            Arc::new(SqliteLeaseRepository::new(pool.clone())),
+            &config.leader_election,
+        ));
+        tracing::info!(
+            "🗳️  Campaigning for lease '{}' as {}",
+            election.name(),
+            election.holder()
+        );
+        let campaign = election.spawn();
+        (election, campaign)
+    });
+
+    // Hot/warm/cold tiering of collections by access pattern
+    let tiering_manager = if config.tiering.enabled {
+        tracing::info!("🌡️  Enabling collection tiering");
+        let mut manager = TieringManager::new(
+            config.tiering.policy.clone(),
+            Arc::new(TierStateRepository::new(pool.clone())),
+        )?;
+        // Under leader election only the leader sweeps (below)
+        if leader_election.is_none() {
+            manager.start_worker();
+        }
+        Some(Arc::new(manager))
+    } else {
+        None
+    };
+    let tiering_sweep = match (&leader_election, &tiering_manager) {
+        (Some((election, _)), Some(manager)) => {
+            let manager = Arc::clone(manager);
+            Some(election.spawn_singleton(
+                "tiering",
+                manager.policy().worker_interval(),
+                move || {
+                    let manager = Arc::clone(&manager);
+                    async move { manager.run_tiering_cycle().await }
+                },
+            ))
+        }
+        _ => None,
+    };
+    let service = Arc::new(match &tiering_manager {
+        Some(manager) => service.with_tiering_manager(Arc::clone(manager)),
+        None => service,
+    });
 
     // Initialize default database_id for RC1 (single-database mode)
     tracing::info!("🔍 Initializing default tenant and database...");
@@ -106,11 +212,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     service.set_default_database_id(database_id).await;
     tracing::info!("✅ Using default database_id: {}", database_id);
 
-    // Load existing collections from database
+    // Load existing collections in the background; not ready until done (see /statusz)
     tracing::info!("🔄 Loading collections from database...");
-    service.load_all_collections().await?;
-    let collection_count = service.list_collections().await?.len();
-    tracing::info!("✅ Loaded {} collection(s)", collection_count);
+    let bootstrap = {
+        let service = Arc::clone(&service);
+        tokio::spawn(async move {
+            match service.bootstrap().await {
+                Ok(()) => {
+                    let status = service.readiness().status();
+                    tracing::info!(
+                        "✅ Loaded {} collection(s), ready to serve traffic",
+                        status.collections_loaded
+                    );
+                }
+                Err(e) => tracing::error!("❌ Failed to load collections: {}", e),
+            }
+        })
+    };
 
     // Initialize EmbeddingManager from configuration
     let embedding_config = &config.embedding;
@@ -120,13 +238,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         embedding_config.model
     );
 
-    let embedding_manager = match EmbeddingManager::from_config(
-        &embedding_config.provider,
-        &embedding_config.model,
-        embedding_config.python_path.as_deref(),
-    )
-    .await
-    {
+    let embedding_manager = match EmbeddingManager::from_embedding_config(embedding_config).await {
         Ok(manager) => {
             tracing::info!(
                 "✅ EmbeddingManager initialized (provider: {}, model: {}, dimension: {})",
@@ -134,7 +246,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 embedding_config.model,
                 manager.dimension()
             );
-            Some(Arc::new(manager))
+            let manager = Arc::new(manager.with_backpressure(&config.backpressure));
+            if embedding_config.warmup {
+                manager.spawn_warmup();
+            }
+            Some(manager)
         }
         Err(e) => {
             tracing::warn!(
@@ -145,36 +261,309 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    // Create gRPC handlers
-    let collection_handler = CollectionHandler::new(Arc::clone(&service));
-    let management_handler = CollectionManagementHandler::new(Arc::clone(&service));
+    // Create gRPC handlers, keeping API keys restricted to specific collections
+    // out of all others
+    let collection_acl = Arc::new(
+        CollectionAcl::new(Arc::new(SqliteApiKeyRepository::new(pool.clone())))
+            .require_key(config.features.require_api_key),
+    );
+    let collection_handler = CollectionHandler::new(Arc::clone(&service))
+        .with_collection_acl(Arc::clone(&collection_acl));
+    let management_handler = CollectionManagementHandler::new(Arc::clone(&service))
+        .with_collection_acl(Arc::clone(&collection_acl));
+
+    // Rate limiting sized from tenant QPS quotas, by peer IP for anonymous
+    // calls; always layered so a config reload can switch it on or off
+    let rate_limiter = Arc::new(RateLimiter::with_repositories(
+        Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+        Arc::new(SqliteTenantCatalog::new(pool.clone())),
+    ));
+    rate_limiter.set_enabled(config.features.rate_limiting_enabled);
+    if config.features.rate_limiting_enabled {
+        tracing::info!("🚦 Enabling per-tenant rate limiting");
+    }
 
     // Start gRPC server
-    let addr = format!("{}:{}", config.server.host, config.server.grpc_port).parse()?;
-    tracing::info!("🚀 gRPC server listening on {}", addr);
+    let addr: std::net::SocketAddr =
+        format!("{}:{}", config.server.host, config.server.grpc_port).parse()?;
 
+    let max_message_size = config.limits.max_body_bytes;
     let mut server_builder = Server::builder()
-        .add_service(CollectionServiceServer::new(collection_handler))
-        .add_service(CollectionManagementServiceServer::new(management_handler));
+        .trace_fn(akidb_grpc::trace::request_span)
+        .layer(RateLimitLayer::new(Arc::clone(&rate_limiter)))
+        .add_service(
+            CollectionServiceServer::new(collection_handler)
+                .max_decoding_message_size(max_message_size),
+        )
+        .add_service(
+            CollectionManagementServiceServer::new(management_handler)
+                .max_decoding_message_size(max_message_size),
+        );
+
+    // grpc.health.v1.Health for Kubernetes gRPC probes and Envoy health checks
+    let (health_reporter, health_server) = tonic_health::server::health_reporter();
+    let mut health_monitor = HealthMonitor::new(
+        health_reporter,
+        Arc::clone(&service),
+        embedding_manager.is_some(),
+    );
+    health_monitor.refresh().await;
+    health_monitor.spawn();
+    server_builder = server_builder.add_service(health_server);
+
+    // Server reflection lets grpcurl and Postman introspect the API without .proto files
+    let reflection_server = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(akidb_proto::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build()?;
+    server_builder = server_builder.add_service(reflection_server);
 
     // Conditionally add embedding service if manager is available
-    if let Some(manager) = embedding_manager {
+    if let Some(manager) = embedding_manager.clone() {
         tracing::info!("🔌 Adding EmbeddingService to gRPC server");
-        let embedding_handler = EmbeddingHandler::new(manager);
-        server_builder = server_builder.add_service(EmbeddingServiceServer::new(embedding_handler));
+        let embedding_handler = EmbeddingHandler::new(manager, Arc::clone(&service))
+            .with_collection_acl(Arc::clone(&collection_acl));
+        server_builder = server_builder.add_service(
+            EmbeddingServiceServer::new(embedding_handler)
+                .max_decoding_message_size(max_message_size),
+        );
     }
 
-    server_builder
-        .serve_with_shutdown(addr, shutdown_signal())
-        .await?;
+    // WAL shipping: a primary serves its WAL, a standby follows the primary
+    match config.replication.role.as_str() {
+        "primary" => {
+            tracing::info!("🔁 Serving WAL to standbys (replication role: primary)");
+            let shipper = WalShipper::new(
+                Arc::clone(&service),
+                Duration::from_millis(config.replication.poll_interval_ms),
+            )
+            .with_rules(Arc::new(ReplicationRules::new(
+                config.replication.rules.clone(),
+            )));
+            let handler = ReplicationHandler::new(
+                shipper,
+                config.replication.auth_token.clone().unwrap_or_default(),
+            );
+            server_builder = server_builder.add_service(ReplicationServiceServer::new(handler));
+        }
+        "standby" => {
+            let applier = Arc::new(
+                ReplicaApplier::new(Arc::clone(&service))
+                    .with_state_path(&config.replication.state_path)?,
+            );
+            tracing::info!(
+                "🔁 Following primary {} (replication role: standby)",
+                config
+                    .replication
+                    .primary_url
+                    .as_deref()
+                    .unwrap_or_default()
+            );
+            tokio::spawn(run_standby(applier, config.replication.clone()));
+        }
+        _ => {}
+    }
+
+    // Push metrics to an OpenTelemetry collector when OTEL_EXPORTER_OTLP_* is set
+    let otlp_metrics = OtlpMetricsConfig::from_env().map(|otlp_config| {
+        tracing::info!(
+            "📤 Exporting OTLP metrics to {} every {:?}",
+            otlp_config.endpoint,
+            otlp_config.interval
+        );
+        let exporter = Arc::new(OtlpMetricsExporter::new("akidb-grpc", otlp_config));
+        let task = Arc::clone(&exporter).spawn(Arc::clone(&service));
+        (exporter, task)
+    });
+
+    // Persist usage rollups periodically
+    let usage_flush = config.metering.enabled.then(|| {
+        akidb_service::metering::spawn_flush(
+            Arc::clone(&service),
+            Duration::from_secs(config.metering.flush_interval_seconds),
+        )
+    });
+
+    // Publish WAL changes to Kafka or NATS
+    let cdc = if config.cdc.enabled {
+        let sink = akidb_service::cdc::connect_sink(&config.cdc).await?;
+        tracing::info!(
+            "📡 Publishing change events to {} at {}",
+            config.cdc.sink,
+            config.cdc.servers
+        );
+        Some(CdcPublisher::new(Arc::clone(&service), sink, &config.cdc)?.spawn())
+    } else {
+        None
+    };
+
+    // S3 garbage collection deletes shared objects, so only the leader runs it
+    let gc = match &leader_election {
+        Some((election, _)) if service.gc_config().enabled => {
+            let service = Arc::clone(&service);
+            Some(election.spawn_singleton(
+                "gc",
+                Duration::from_secs(service.gc_config().interval_seconds),
+                move || {
+                    let service = Arc::clone(&service);
+                    async move {
+                        let report = service.collect_garbage().await?;
+                        tracing::info!(
+                            "🧹 S3 garbage collection deleted {} of {} objects",
+                            report.objects_deleted,
+                            report.objects_scanned
+                        );
+                        Ok(())
+                    }
+                },
+            ))
+        }
+        None if service.gc_config().enabled => {
+            tracing::warn!("⚠️  S3 garbage collection requires [leader_election]; not running it");
+            None
+        }
+        _ => None,
+    };
+
+    // Snapshot collections on their schedules, as `snapshot` jobs
+    let snapshot_schedule = (!config.snapshots.schedules.is_empty()).then(|| {
+        tracing::info!(
+            "📸 Scheduling snapshots ({} schedule(s))",
+            config.snapshots.schedules.len()
+        );
+        let scheduler = Arc::new(SnapshotScheduler::new(
+            Arc::clone(&service),
+            config.snapshots.schedules.clone(),
+        ));
+        let interval = Duration::from_secs(config.snapshots.check_interval_seconds);
+        match &leader_election {
+            // Only the leader takes scheduled snapshots
+            Some((election, _)) => election.spawn_singleton("snapshots", interval, move || {
+                let scheduler = Arc::clone(&scheduler);
+                async move { scheduler.run_due().await.map(|_| ()) }
+            }),
+            None => scheduler.spawn(interval),
+        }
+    });
+
+    // Apply config file changes on SIGHUP or file change, keeping indexes in memory
+    let config_reload = config.reload.enabled.then(|| {
+        let reloader = Arc::new(ConfigReloader::new(Config::DEFAULT_PATH, config.clone()));
+        if let Some(handle) = log_level {
+            reloader.on_reload(move |config| {
+                if let Err(e) = handle.set_level(&config.logging.level) {
+                    tracing::warn!("⚠️  {}", e);
+                }
+            });
+        }
+        reloader.on_reload(move |config| {
+            rate_limiter.set_enabled(config.features.rate_limiting_enabled)
+        });
+        if let Some(manager) = embedding_manager {
+            reloader.on_reload(move |config| manager.set_tenant_qps(config.embedding.tenant_qps));
+        }
+        if let Some(manager) = tiering_manager.clone() {
+            reloader.on_reload(move |config| {
+                if let Err(e) = manager.set_policy(config.tiering.policy.clone()) {
+                    tracing::warn!("⚠️  Failed to apply tiering policy: {}", e);
+                }
+            });
+        }
+        reloader.spawn(Duration::from_secs(config.reload.watch_interval_seconds))
+    });
+
+    if let Some(tls_config) = &config.server.tls {
+        // gRPC requires HTTP/2, negotiated via ALPN
+        let tls = Arc::new(ReloadableTlsConfig::load(tls_config, vec![b"h2".to_vec()])?);
+        tls.spawn_reloader();
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!(
+            "🚀 gRPC server listening on {} ({})",
+            addr,
+            if tls.is_mutual() { "mTLS" } else { "TLS" }
+        );
+
+        let incoming = ReceiverStream::new(tls.accept(listener))
+            .map(|stream| Ok::<_, std::io::Error>(TlsConnection(stream)));
+        server_builder
+            .serve_with_incoming_shutdown(
+                incoming,
+                shutdown_signal(Arc::clone(&service), config.drain.clone()),
+            )
+            .await?;
+    } else {
+        tracing::info!("🚀 gRPC server listening on {}", addr);
+        server_builder
+            .serve_with_shutdown(
+                addr,
+                shutdown_signal(Arc::clone(&service), config.drain.clone()),
+            )
+            .await?;
+    }
 
     tracing::info!("✅ Server shutdown complete");
 
+    // Stop waiting for the object store if it never became reachable
+    bootstrap.abort();
+    flag_refresh.abort();
+
+    if let Some(task) = config_reload {
+        task.abort();
+    }
+
+    // Unacknowledged events are published again after the restart
+    if let Some(task) = cdc {
+        task.abort();
+    }
+
+    if let Some(task) = snapshot_schedule {
+        task.abort();
+    }
+
+    if let Some(task) = gc {
+        task.abort();
+    }
+
+    if let Some(task) = tiering_sweep {
+        task.abort();
+    }
+
+    // Hand over the lease instead of letting it expire
+    if let Some((election, campaign)) = leader_election {
+        campaign.abort();
+        if let Err(e) = election.resign().await {
+            tracing::warn!("Failed to release lease '{}': {}", election.name(), e);
+        }
+    }
+
+    // Persist usage counted since the last flush
+    if let Some(task) = usage_flush {
+        task.abort();
+        if let Err(e) = service.flush_usage().await {
+            tracing::warn!("Final usage flush failed: {}", e);
+        }
+    }
+
+    // Flush final metric values
+    if let Some((exporter, task)) = otlp_metrics {
+        task.abort();
+        if let Err(e) = exporter.export(&service).await {
+            tracing::warn!("Final OTLP metrics export failed: {}", e);
+        }
+    }
+
+    // Shutdown tracing provider (flushes pending spans)
+    if tracing_enabled {
+        akidb_service::trace_context::shutdown();
+    }
+
     Ok(())
 }
 
-/// Wait for SIGTERM or SIGINT signal for graceful shutdown.
-async fn shutdown_signal() {
+/// Wait for SIGTERM or SIGINT signal for graceful shutdown, then drain so
+/// health checks report NOT_SERVING before the listener closes.
+async fn shutdown_signal(service: Arc<CollectionService>, drain: DrainConfig) {
     use tokio::signal;
 
     let ctrl_c = async {
@@ -202,4 +591,11 @@ async fn shutdown_signal() {
             tracing::info!("🛑 Received SIGTERM, initiating graceful shutdown...");
         },
     }
+
+    service
+        .drain(
+            Duration::from_secs(drain.grace_period_seconds),
+            Duration::from_secs(drain.timeout_seconds),
+        )
+        .await;
 }
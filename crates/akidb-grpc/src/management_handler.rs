@@ -1,22 +1,70 @@
-use akidb_core::{CollectionId, DistanceMetric};
+use crate::acl;
+use crate::error::{invalid_argument, status_from_core};
+use akidb_core::{
+    CollectionAlias, CollectionId, CoreError, DistanceMetric, Quantization, VectorType,
+};
 use akidb_proto::{
     collection_management_service_server::CollectionManagementService as GrpcCollectionManagementService,
-    CollectionInfo, CreateCollectionRequest, CreateCollectionResponse, DeleteCollectionRequest,
-    DeleteCollectionResponse, GetCollectionRequest, GetCollectionResponse, ListCollectionsRequest,
-    ListCollectionsResponse,
+    AliasInfo, CollectionInfo, CreateCollectionRequest, CreateCollectionResponse,
+    DeleteAliasRequest, DeleteAliasResponse, DeleteCollectionRequest, DeleteCollectionResponse,
+    GetCollectionRequest, GetCollectionResponse, ListAliasesRequest, ListAliasesResponse,
+    ListCollectionsRequest, ListCollectionsResponse, SetAliasRequest, SetAliasResponse,
 };
-use akidb_service::CollectionService;
+use akidb_service::{CollectionAcl, CollectionGrants, CollectionOptions, CollectionService};
 use std::str::FromStr;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
 pub struct CollectionManagementHandler {
     service: Arc<CollectionService>,
+    acl: Option<Arc<CollectionAcl>>,
 }
 
 impl CollectionManagementHandler {
     pub fn new(service: Arc<CollectionService>) -> Self {
-        Self { service }
+        Self { service, acl: None }
+    }
+
+    /// Enforces API key collection allowlists on calls addressing a collection.
+    pub fn with_collection_acl(mut self, acl: Arc<CollectionAcl>) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
+    /// Collections `api_key` may access.
+    async fn grants(&self, api_key: Option<&str>) -> Result<CollectionGrants, Status> {
+        acl::collection_grants(self.acl.as_deref(), api_key).await
+    }
+
+    async fn authorize(
+        &self,
+        api_key: Option<&str>,
+        collection_id: CollectionId,
+    ) -> Result<(), Status> {
+        acl::check_collection_access(self.acl.as_deref(), api_key, collection_id).await
+    }
+
+    /// Rejects changes to the alias `name` unless `api_key` may access the
+    /// collection it currently points to.
+    async fn authorize_alias(&self, api_key: Option<&str>, name: &str) -> Result<(), Status> {
+        let current = self
+            .service
+            .get_alias(name)
+            .await
+            .map_err(status_from_core)?;
+        match current {
+            Some(alias) => self.authorize(api_key, alias.collection_id).await,
+            None => Ok(()),
+        }
+    }
+}
+
+fn alias_info(alias: CollectionAlias) -> AliasInfo {
+    AliasInfo {
+        name: alias.name,
+        collection_id: alias.collection_id.to_string(),
+        created_at: alias.created_at.to_rfc3339(),
+        updated_at: alias.updated_at.to_rfc3339(),
     }
 }
 
@@ -26,11 +74,13 @@ impl GrpcCollectionManagementService for CollectionManagementHandler {
         &self,
         request: Request<CreateCollectionRequest>,
     ) -> Result<Response<CreateCollectionResponse>, Status> {
+        let api_key = acl::api_key(request.metadata());
+        self.grants(api_key.as_deref()).await?;
         let req = request.into_inner();
 
         // Validate name
         if req.name.is_empty() {
-            return Err(Status::invalid_argument("name cannot be empty"));
+            return Err(invalid_argument("name cannot be empty"));
         }
 
         // Parse metric
@@ -39,51 +89,90 @@ impl GrpcCollectionManagementService for CollectionManagementHandler {
             "l2" => DistanceMetric::L2,
             "dot" => DistanceMetric::Dot,
             _ => {
-                return Err(Status::invalid_argument(format!(
+                return Err(invalid_argument(format!(
                     "invalid metric: '{}', must be one of: cosine, l2, dot",
                     req.metric
                 )))
             }
         };
 
+        let quantization = match req.quantization.as_deref() {
+            Some(quantization) => {
+                Quantization::from_str(&quantization.to_lowercase()).map_err(|()| {
+                    invalid_argument(format!(
+                        "invalid quantization: '{}', must be one of: none, int8, fp16",
+                        quantization
+                    ))
+                })?
+            }
+            None => Quantization::None,
+        };
+
+        let vector_type = match req.vector_type.as_deref() {
+            Some(vector_type) => {
+                VectorType::from_str(&vector_type.to_lowercase()).map_err(|()| {
+                    invalid_argument(format!(
+                        "invalid vector_type: '{}', must be one of: dense, sparse, both",
+                        vector_type
+                    ))
+                })?
+            }
+            None => VectorType::Dense,
+        };
+
         // Create collection
+        let database_id = self.service.default_database_id().await;
         let collection_id = self
             .service
-            .create_collection(req.name.clone(), req.dimension, metric, req.embedding_model)
+            .create_collection_in(
+                database_id,
+                req.name.clone(),
+                req.dimension,
+                metric,
+                req.embedding_model,
+                CollectionOptions {
+                    normalize: req.normalize,
+                    quantization,
+                    vector_type,
+                },
+            )
             .await
-            .map_err(|e| {
-                if e.to_string().contains("dimension") {
-                    Status::invalid_argument(e.to_string())
-                } else {
-                    Status::internal(e.to_string())
-                }
-            })?;
+            .map_err(status_from_core)?;
 
         Ok(Response::new(CreateCollectionResponse {
             collection_id: collection_id.to_string(),
             name: req.name,
             dimension: req.dimension,
             metric: req.metric,
+            normalize: req.normalize,
+            quantization: quantization.as_str().to_string(),
+            vector_type: vector_type.as_str().to_string(),
         }))
     }
 
     async fn list_collections(
         &self,
-        _request: Request<ListCollectionsRequest>,
+        request: Request<ListCollectionsRequest>,
     ) -> Result<Response<ListCollectionsResponse>, Status> {
+        let api_key = acl::api_key(request.metadata());
+        let grants = self.grants(api_key.as_deref()).await?;
         let collections = self
             .service
             .list_collections()
             .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+            .map_err(status_from_core)?;
 
         let collection_infos = collections
             .into_iter()
+            .filter(|c| grants.allows(c.collection_id))
             .map(|c| CollectionInfo {
                 collection_id: c.collection_id.to_string(),
                 name: c.name,
                 dimension: c.dimension,
                 metric: c.metric.as_str().to_string(),
+                normalize: c.normalize,
+                quantization: c.quantization.as_str().to_string(),
+                vector_type: c.vector_type.as_str().to_string(),
                 document_count: 0, // TODO: Get actual count from service
                 created_at: c.created_at.to_rfc3339(),
             })
@@ -98,22 +187,18 @@ impl GrpcCollectionManagementService for CollectionManagementHandler {
         &self,
         request: Request<GetCollectionRequest>,
     ) -> Result<Response<GetCollectionResponse>, Status> {
+        let api_key = acl::api_key(request.metadata());
         let req = request.into_inner();
 
         let collection_id = CollectionId::from_str(&req.collection_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid collection_id: {}", e)))?;
+            .map_err(|e| invalid_argument(format!("Invalid collection_id: {}", e)))?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
 
         let collection = self
             .service
             .get_collection(collection_id)
             .await
-            .map_err(|e| {
-                if e.to_string().contains("not found") {
-                    Status::not_found(e.to_string())
-                } else {
-                    Status::internal(e.to_string())
-                }
-            })?;
+            .map_err(status_from_core)?;
 
         // Get document count
         let document_count = self.service.get_count(collection_id).await.unwrap_or(0) as u64;
@@ -124,6 +209,9 @@ impl GrpcCollectionManagementService for CollectionManagementHandler {
                 name: collection.name,
                 dimension: collection.dimension,
                 metric: collection.metric.as_str().to_string(),
+                normalize: collection.normalize,
+                quantization: collection.quantization.as_str().to_string(),
+                vector_type: collection.vector_type.as_str().to_string(),
                 document_count,
                 created_at: collection.created_at.to_rfc3339(),
             }),
@@ -134,22 +222,82 @@ impl GrpcCollectionManagementService for CollectionManagementHandler {
         &self,
         request: Request<DeleteCollectionRequest>,
     ) -> Result<Response<DeleteCollectionResponse>, Status> {
+        let api_key = acl::api_key(request.metadata());
         let req = request.into_inner();
 
         let collection_id = CollectionId::from_str(&req.collection_id)
-            .map_err(|e| Status::invalid_argument(format!("Invalid collection_id: {}", e)))?;
+            .map_err(|e| invalid_argument(format!("Invalid collection_id: {}", e)))?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
 
         self.service
             .delete_collection(collection_id)
             .await
-            .map_err(|e| {
-                if e.to_string().contains("not found") {
-                    Status::not_found(e.to_string())
-                } else {
-                    Status::internal(e.to_string())
-                }
-            })?;
+            .map_err(status_from_core)?;
 
         Ok(Response::new(DeleteCollectionResponse { success: true }))
     }
+
+    async fn set_alias(
+        &self,
+        request: Request<SetAliasRequest>,
+    ) -> Result<Response<SetAliasResponse>, Status> {
+        let api_key = acl::api_key(request.metadata());
+        let req = request.into_inner();
+
+        let collection_id = CollectionId::from_str(&req.collection_id)
+            .map_err(|e| invalid_argument(format!("Invalid collection_id: {}", e)))?;
+        self.authorize(api_key.as_deref(), collection_id).await?;
+        self.authorize_alias(api_key.as_deref(), &req.name).await?;
+
+        let alias = self
+            .service
+            .set_alias(&req.name, collection_id)
+            .await
+            .map_err(status_from_core)?;
+
+        Ok(Response::new(SetAliasResponse {
+            alias: Some(alias_info(alias)),
+        }))
+    }
+
+    async fn list_aliases(
+        &self,
+        request: Request<ListAliasesRequest>,
+    ) -> Result<Response<ListAliasesResponse>, Status> {
+        let api_key = acl::api_key(request.metadata());
+        let grants = self.grants(api_key.as_deref()).await?;
+        let aliases = self
+            .service
+            .list_aliases()
+            .await
+            .map_err(status_from_core)?;
+
+        Ok(Response::new(ListAliasesResponse {
+            aliases: aliases
+                .into_iter()
+                .filter(|alias| grants.allows(alias.collection_id))
+                .map(alias_info)
+                .collect(),
+        }))
+    }
+
+    async fn delete_alias(
+        &self,
+        request: Request<DeleteAliasRequest>,
+    ) -> Result<Response<DeleteAliasResponse>, Status> {
+        let api_key = acl::api_key(request.metadata());
+        let req = request.into_inner();
+
+        self.authorize_alias(api_key.as_deref(), &req.name).await?;
+        let removed = self
+            .service
+            .remove_alias(&req.name)
+            .await
+            .map_err(status_from_core)?;
+        if !removed {
+            return Err(status_from_core(CoreError::not_found("Alias", req.name)));
+        }
+
+        Ok(Response::new(DeleteAliasResponse { success: true }))
+    }
 }
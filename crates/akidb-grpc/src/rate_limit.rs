@@ -0,0 +1,104 @@
+//! Rate limiting for the gRPC API.
+//!
+//! Applies the shared [`RateLimiter`] to every call. Calls whose API key
+//! (`x-api-key` or `authorization: Bearer` metadata) resolves to a tenant draw
+//! from the tenant's bucket; all others are limited by peer IP. Throttled calls
+//! fail with `RESOURCE_EXHAUSTED` and a `RATE_LIMITED` error detail carrying
+//! `retry_after_seconds`.
+
+use crate::acl::api_key;
+use crate::error::error_status;
+use akidb_core::ErrorCode;
+use akidb_service::RateLimiter;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tonic::body::BoxBody;
+use tonic::metadata::MetadataMap;
+use tonic::transport::server::TcpConnectInfo;
+use tower::{Layer, Service};
+
+/// Tower layer applying [`RateLimiter`] to every call; add it with
+/// `Server::builder().layer(..)`.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    /// Creates a layer backed by a shared limiter.
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: Arc::clone(&self.limiter),
+        }
+    }
+}
+
+/// Service produced by [`RateLimitLayer`].
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for RateLimitService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        let limiter = Arc::clone(&self.limiter);
+        // Use the service that was driven to readiness; leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            if !limiter.is_enabled() {
+                return inner.call(req).await;
+            }
+
+            // Inserted by tonic for both plain and TLS connections (see `TlsConnection`)
+            let client_ip = req
+                .extensions()
+                .get::<TcpConnectInfo>()
+                .and_then(TcpConnectInfo::remote_addr)
+                .map(|addr| addr.ip());
+            let api_key = api_key(&MetadataMap::from_headers(req.headers().clone()));
+
+            let decision = limiter.check(api_key.as_deref(), client_ip).await;
+            if !decision.allowed {
+                let retry_after = decision.reset_after.as_secs_f64().ceil().max(1.0) as u64;
+                return Ok(error_status(
+                    ErrorCode::RateLimited,
+                    format!(
+                        "Rate limit of {} requests per second exceeded",
+                        decision.limit
+                    ),
+                    [("retry_after_seconds", retry_after.to_string())],
+                )
+                .to_http());
+            }
+
+            inner.call(req).await
+        })
+    }
+}
@@ -0,0 +1,452 @@
+//! WAL shipping over gRPC.
+//!
+//! A primary serves `ReplicationService.StreamWal` from [`ReplicationHandler`];
+//! a standby runs [`run_standby`], which keeps a stream to the primary open
+//! and applies every message to the local collections. `SetRules` changes
+//! which collections the primary ships without reopening the streams.
+//!
+//! A standby receives every document of the collections it follows, whatever
+//! the per-key collection ACLs say, so the primary only serves peers that send
+//! the shared `replication.auth_token` as `x-replication-token` metadata.
+
+use crate::collection_handler::sparse_to_proto;
+use crate::error::{error_status, invalid_argument};
+use akidb_core::{
+    CollectionDescriptor, CollectionId, DatabaseId, DistanceMetric, DocumentId, ErrorCode,
+    Quantization, SparseVector, VectorDocument, VectorType,
+};
+use akidb_proto::replication::{
+    replication_message, replication_service_client::ReplicationServiceClient,
+    replication_service_server::ReplicationService as GrpcReplicationService, wal_record,
+    CollectionState, GetRulesRequest, Heartbeat, ReplicationMessage,
+    ReplicationRule as ProtoReplicationRule, RulesResponse, SetRulesRequest, StreamWalRequest,
+    WalDelete, WalRecord, WalUpsert,
+};
+use akidb_service::{
+    ReplicaApplier, ReplicatedOp, ReplicationConfig, ReplicationEvent, ReplicationRule, WalShipper,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::MetadataValue;
+use tonic::{Request, Response, Status};
+
+/// Metadata key carrying the shared replication token.
+pub const REPLICATION_TOKEN_METADATA: &str = "x-replication-token";
+
+pub struct ReplicationHandler {
+    shipper: WalShipper,
+    auth_token: String,
+}
+
+impl ReplicationHandler {
+    /// Serves `shipper` to peers presenting `auth_token`; an empty token
+    /// rejects every call.
+    pub fn new(shipper: WalShipper, auth_token: impl Into<String>) -> Self {
+        Self {
+            shipper,
+            auth_token: auth_token.into(),
+        }
+    }
+
+    /// Rejects calls without the shared replication token.
+    fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let presented = request
+            .metadata()
+            .get(REPLICATION_TOKEN_METADATA)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if !self.auth_token.is_empty()
+            && constant_time_eq(presented.as_bytes(), self.auth_token.as_bytes())
+        {
+            return Ok(());
+        }
+
+        tracing::warn!(
+            "Rejected unauthenticated replication call from {}",
+            request
+                .remote_addr()
+                .map_or_else(|| "unknown peer".to_string(), |addr| addr.to_string())
+        );
+        Err(error_status(
+            ErrorCode::PermissionDenied,
+            "replication peer is not authenticated",
+            [],
+        ))
+    }
+}
+
+#[tonic::async_trait]
+impl GrpcReplicationService for ReplicationHandler {
+    type StreamWalStream = ReceiverStream<Result<ReplicationMessage, Status>>;
+
+    async fn stream_wal(
+        &self,
+        request: Request<StreamWalRequest>,
+    ) -> Result<Response<Self::StreamWalStream>, Status> {
+        self.authorize(&request)?;
+        let req = request.into_inner();
+        let positions = parse_positions(req.positions)
+            .map_err(|e| invalid_argument(format!("Invalid positions: {}", e)))?;
+
+        tracing::info!(
+            "Standby '{}' connected ({} collection position(s))",
+            req.standby_id,
+            positions.len()
+        );
+
+        let mut events = self.shipper.stream(positions);
+        let (tx, rx) = mpsc::channel(16);
+        let standby_id = req.standby_id;
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                if tx.send(Ok(to_message(event))).await.is_err() {
+                    break;
+                }
+            }
+            // Dropping `events` stops the shipper
+            tracing::info!("Standby '{}' disconnected", standby_id);
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn set_rules(
+        &self,
+        request: Request<SetRulesRequest>,
+    ) -> Result<Response<RulesResponse>, Status> {
+        self.authorize(&request)?;
+        let rules: Vec<ReplicationRule> = request
+            .into_inner()
+            .rules
+            .into_iter()
+            .map(|rule| ReplicationRule {
+                collections: rule.collections,
+                enabled: rule.enabled.unwrap_or(true),
+                priority: rule.priority,
+            })
+            .collect();
+
+        self.shipper
+            .rules()
+            .set(rules)
+            .map_err(|e| invalid_argument(e.to_string()))?;
+        tracing::info!(
+            "Replication rules updated ({} rule(s))",
+            self.shipper.rules().get().len()
+        );
+
+        Ok(Response::new(self.rules_response()))
+    }
+
+    async fn get_rules(
+        &self,
+        request: Request<GetRulesRequest>,
+    ) -> Result<Response<RulesResponse>, Status> {
+        self.authorize(&request)?;
+        Ok(Response::new(self.rules_response()))
+    }
+}
+
+impl ReplicationHandler {
+    fn rules_response(&self) -> RulesResponse {
+        RulesResponse {
+            rules: self
+                .shipper
+                .rules()
+                .get()
+                .into_iter()
+                .map(|rule| ProtoReplicationRule {
+                    collections: rule.collections,
+                    enabled: Some(rule.enabled),
+                    priority: rule.priority,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Follows the primary at `config.primary_url` until the process exits,
+/// reconnecting after `config.reconnect_backoff_ms` whenever the stream
+/// fails.
+pub async fn run_standby(applier: Arc<ReplicaApplier>, config: ReplicationConfig) {
+    let Some(primary_url) = config.primary_url.clone() else {
+        tracing::error!("replication.primary_url is not set; standby replication disabled");
+        return;
+    };
+    let backoff = Duration::from_millis(config.reconnect_backoff_ms);
+
+    let Some(auth_token) = config.auth_token.clone() else {
+        tracing::error!("replication.auth_token is not set; standby replication disabled");
+        return;
+    };
+
+    loop {
+        match follow_primary(&applier, &primary_url, &config.standby_id, &auth_token).await {
+            Ok(()) => tracing::warn!("Replication stream from {} ended", primary_url),
+            Err(e) => tracing::warn!("Replication from {} failed: {}", primary_url, e),
+        }
+        applier.set_connected(false);
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+async fn follow_primary(
+    applier: &ReplicaApplier,
+    primary_url: &str,
+    standby_id: &str,
+    auth_token: &str,
+) -> anyhow::Result<()> {
+    let mut client = ReplicationServiceClient::connect(primary_url.to_string()).await?;
+    let mut request = Request::new(StreamWalRequest {
+        positions: applier
+            .positions()
+            .into_iter()
+            .map(|(id, lsn)| (id.to_string(), lsn))
+            .collect(),
+        standby_id: standby_id.to_string(),
+    });
+    request.metadata_mut().insert(
+        REPLICATION_TOKEN_METADATA,
+        MetadataValue::try_from(auth_token)?,
+    );
+
+    let mut stream = client.stream_wal(request).await?.into_inner();
+    applier.set_connected(true);
+    tracing::info!("Following primary {}", primary_url);
+
+    while let Some(message) = stream.message().await? {
+        let event = from_message(message)?;
+        applier.apply(event).await?;
+    }
+    Ok(())
+}
+
+fn parse_positions(
+    positions: HashMap<String, u64>,
+) -> Result<HashMap<CollectionId, u64>, <CollectionId as FromStr>::Err> {
+    positions
+        .into_iter()
+        .map(|(id, lsn)| Ok((CollectionId::from_str(&id)?, lsn)))
+        .collect()
+}
+
+fn to_message(event: ReplicationEvent) -> ReplicationMessage {
+    let message = match event {
+        ReplicationEvent::Collection { descriptor, reset } => {
+            replication_message::Message::Collection(CollectionState {
+                collection_id: descriptor.collection_id.to_string(),
+                name: descriptor.name,
+                dimension: descriptor.dimension,
+                metric: descriptor.metric.as_str().to_string(),
+                normalize: descriptor.normalize,
+                quantization: descriptor.quantization.as_str().to_string(),
+                vector_type: descriptor.vector_type.as_str().to_string(),
+                embedding_model: descriptor.embedding_model,
+                hnsw_m: descriptor.hnsw_m,
+                hnsw_ef_construction: descriptor.hnsw_ef_construction,
+                max_doc_count: descriptor.max_doc_count,
+                reset,
+            })
+        }
+        ReplicationEvent::Entry {
+            collection_id,
+            lsn,
+            op,
+        } => {
+            let op = match op {
+                ReplicatedOp::Upsert(doc) => wal_record::Op::Upsert(WalUpsert {
+                    doc_id: doc.doc_id.to_string(),
+                    vector: doc.vector,
+                    external_id: doc.external_id,
+                    metadata: doc.metadata.map(|metadata| metadata.to_string()),
+                    inserted_at_ms: doc.inserted_at.timestamp_millis(),
+                    sparse: doc.sparse.map(sparse_to_proto),
+                }),
+                ReplicatedOp::Delete(doc_id) => wal_record::Op::Delete(WalDelete {
+                    doc_id: doc_id.to_string(),
+                }),
+            };
+            replication_message::Message::Record(WalRecord {
+                collection_id: collection_id.to_string(),
+                lsn,
+                op: Some(op),
+            })
+        }
+        ReplicationEvent::CollectionDeleted { collection_id } => {
+            replication_message::Message::CollectionDeleted(collection_id.to_string())
+        }
+        ReplicationEvent::Heartbeat {
+            positions,
+            timestamp,
+        } => replication_message::Message::Heartbeat(Heartbeat {
+            positions: positions
+                .into_iter()
+                .map(|(id, lsn)| (id.to_string(), lsn))
+                .collect(),
+            timestamp_ms: timestamp.timestamp_millis(),
+        }),
+    };
+    ReplicationMessage {
+        message: Some(message),
+    }
+}
+
+fn from_message(message: ReplicationMessage) -> anyhow::Result<ReplicationEvent> {
+    let message = message
+        .message
+        .ok_or_else(|| anyhow::anyhow!("empty replication message"))?;
+
+    Ok(match message {
+        replication_message::Message::Collection(state) => {
+            let now = Utc::now();
+            ReplicationEvent::Collection {
+                descriptor: CollectionDescriptor {
+                    collection_id: CollectionId::from_str(&state.collection_id)?,
+                    // Replaced with the standby's default database
+                    database_id: DatabaseId::new(),
+                    name: state.name,
+                    dimension: state.dimension,
+                    metric: DistanceMetric::from_str(&state.metric)
+                        .map_err(|()| anyhow::anyhow!("invalid metric '{}'", state.metric))?,
+                    normalize: state.normalize,
+                    quantization: if state.quantization.is_empty() {
+                        Quantization::None
+                    } else {
+                        Quantization::from_str(&state.quantization).map_err(|()| {
+                            anyhow::anyhow!("invalid quantization '{}'", state.quantization)
+                        })?
+                    },
+                    vector_type: if state.vector_type.is_empty() {
+                        VectorType::Dense
+                    } else {
+                        VectorType::from_str(&state.vector_type).map_err(|()| {
+                            anyhow::anyhow!("invalid vector type '{}'", state.vector_type)
+                        })?
+                    },
+                    embedding_model: state.embedding_model,
+                    hnsw_m: state.hnsw_m,
+                    hnsw_ef_construction: state.hnsw_ef_construction,
+                    max_doc_count: state.max_doc_count,
+                    created_at: now,
+                    updated_at: now,
+                },
+                reset: state.reset,
+            }
+        }
+        replication_message::Message::Record(record) => {
+            let op = match record.op {
+                Some(wal_record::Op::Upsert(upsert)) => ReplicatedOp::Upsert(VectorDocument {
+                    doc_id: DocumentId::from_str(&upsert.doc_id)?,
+                    external_id: upsert.external_id,
+                    vector: upsert.vector,
+                    sparse: upsert
+                        .sparse
+                        .map(|sparse| SparseVector::new(sparse.indices, sparse.values))
+                        .transpose()?,
+                    metadata: upsert
+                        .metadata
+                        .map(|metadata| serde_json::from_str(&metadata))
+                        .transpose()?,
+                    inserted_at: timestamp(upsert.inserted_at_ms),
+                }),
+                Some(wal_record::Op::Delete(delete)) => {
+                    ReplicatedOp::Delete(DocumentId::from_str(&delete.doc_id)?)
+                }
+                None => anyhow::bail!("WAL record without an operation"),
+            };
+            ReplicationEvent::Entry {
+                collection_id: CollectionId::from_str(&record.collection_id)?,
+                lsn: record.lsn,
+                op,
+            }
+        }
+        replication_message::Message::CollectionDeleted(collection_id) => {
+            ReplicationEvent::CollectionDeleted {
+                collection_id: CollectionId::from_str(&collection_id)?,
+            }
+        }
+        replication_message::Message::Heartbeat(heartbeat) => ReplicationEvent::Heartbeat {
+            positions: parse_positions(heartbeat.positions)?,
+            timestamp: timestamp(heartbeat.timestamp_ms),
+        },
+    })
+}
+
+/// Compares without an early exit, so timing does not reveal the token.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn timestamp(millis: i64) -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .unwrap_or_else(Utc::now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_service::CollectionService;
+
+    fn handler() -> ReplicationHandler {
+        let shipper = WalShipper::new(Arc::new(CollectionService::new()), Duration::from_secs(1));
+        ReplicationHandler::new(shipper, "replication-secret")
+    }
+
+    fn set_rules_request(token: Option<&str>) -> Request<SetRulesRequest> {
+        let mut request = Request::new(SetRulesRequest {
+            rules: vec![ProtoReplicationRule {
+                collections: "orders".to_string(),
+                enabled: Some(true),
+                priority: 1,
+            }],
+        });
+        if let Some(token) = token {
+            request
+                .metadata_mut()
+                .insert(REPLICATION_TOKEN_METADATA, token.parse().unwrap());
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn test_rules_require_replication_token() {
+        let handler = handler();
+
+        for token in [None, Some("wrong-secret")] {
+            let status = handler
+                .set_rules(set_rules_request(token))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), tonic::Code::PermissionDenied);
+            let status = handler
+                .get_rules(Request::new(GetRulesRequest {}))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        }
+        assert!(handler.shipper.rules().get().is_empty());
+
+        let response = handler
+            .set_rules(set_rules_request(Some("replication-secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.into_inner().rules.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_empty_token_rejects_every_call() {
+        let shipper = WalShipper::new(Arc::new(CollectionService::new()), Duration::from_secs(1));
+        let handler = ReplicationHandler::new(shipper, "");
+        let status = handler
+            .set_rules(set_rules_request(Some("")))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+}
@@ -0,0 +1,22 @@
+//! Request spans for the gRPC server.
+//!
+//! Installed with `Server::trace_fn`: every call runs in a `grpc.request` span
+//! whose parent comes from the `traceparent` metadata, so the service,
+//! storage, and embedding spans below it join the client's distributed trace.
+
+use akidb_service::trace_context::set_parent_from_headers;
+use tonic::codegen::http::Request;
+
+/// Builds the span for one gRPC call.
+pub fn request_span(req: &Request<()>) -> tracing::Span {
+    let path = req.uri().path();
+    let span = tracing::info_span!(
+        "grpc.request",
+        otel.name = %path.trim_start_matches('/'),
+        otel.kind = "server",
+        rpc.system = "grpc",
+        rpc.method = %path,
+    );
+    set_parent_from_headers(&span, req.headers());
+    span
+}
@@ -11,12 +11,21 @@ use std::collections::HashMap;
 use async_trait::async_trait;
 
 use akidb_core::{
-    CoreError, CoreResult, DistanceMetric, DocumentId, SearchResult, VectorDocument, VectorIndex,
+    CoreError, CoreResult, DistanceMetric, DocumentId, Quantization, SearchFilter, SearchResult,
+    VectorDocument, VectorIndex,
 };
 
+use crate::quantization::{best_first, Int8Query, QuantizedVector, RESCORE_OVERSAMPLE};
 // Use crate-level sync module for conditional compilation (Loom vs production)
 use crate::{Arc, RwLock};
 
+/// A document with its vector held at the index's precision.
+struct StoredDocument {
+    /// The document, with an empty `vector`
+    doc: VectorDocument,
+    vector: QuantizedVector,
+}
+
 /// Brute-force linear scan index (baseline for correctness).
 ///
 /// Time complexity: O(n·d) per search where n = number of documents, d = dimension
@@ -50,8 +59,11 @@ pub struct BruteForceIndex {
     /// Distance metric
     metric: DistanceMetric,
 
+    /// Precision of stored vectors
+    quantization: Quantization,
+
     /// In-memory document storage
-    documents: Arc<RwLock<HashMap<DocumentId, VectorDocument>>>,
+    documents: Arc<RwLock<HashMap<DocumentId, StoredDocument>>>,
 }
 
 impl BruteForceIndex {
@@ -70,10 +82,21 @@ impl BruteForceIndex {
         Self {
             dim,
             metric,
+            quantization: Quantization::None,
             documents: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Stores vectors at the given precision.
+    ///
+    /// Int8 searches rank every document with integer arithmetic, then
+    /// re-score the best candidates exactly against the f32 query.
+    #[must_use]
+    pub fn with_quantization(mut self, quantization: Quantization) -> Self {
+        self.quantization = quantization;
+        self
+    }
+
     /// Returns the vector dimension.
     #[must_use]
     pub fn dimension(&self) -> usize {
@@ -85,62 +108,19 @@ impl BruteForceIndex {
     pub fn metric(&self) -> DistanceMetric {
         self.metric
     }
-}
 
-#[async_trait]
-impl VectorIndex for BruteForceIndex {
-    async fn insert(&self, doc: VectorDocument) -> CoreResult<()> {
-        if doc.vector.len() != self.dim {
-            return Err(CoreError::invalid_state(format!(
-                "Vector dimension mismatch: expected {}, got {}",
-                self.dim,
-                doc.vector.len()
-            )));
-        }
-
-        // BUG-5 FIX: Validate no NaN or Inf values
-        for (i, &val) in doc.vector.iter().enumerate() {
-            if !val.is_finite() {
-                return Err(CoreError::invalid_state(format!(
-                    "Vector contains invalid value at index {}: {}. \
-                     Only finite numbers are allowed (no NaN or Infinity)",
-                    i, val
-                )));
-            }
-        }
-
-        // BUG-9 FIX: For Cosine metric, reject zero vectors (undefined similarity)
-        if matches!(self.metric, DistanceMetric::Cosine) {
-            let norm_squared: f32 = doc.vector.iter().map(|x| x * x).sum();
-            if norm_squared == 0.0 {
-                return Err(CoreError::invalid_state(
-                    "Cannot insert zero vector with Cosine similarity metric. \
-                     Cosine similarity is mathematically undefined for zero vectors. \
-                     Consider using L2 distance metric instead."
-                        .to_string(),
-                ));
-            }
-        }
-
-        let mut docs = self.documents.write();
-
-        // BUG-1 FIX: Reject duplicate inserts (consistent with InstantDistanceIndex)
-        if docs.contains_key(&doc.doc_id) {
-            return Err(CoreError::invalid_state(format!(
-                "Document {} already exists",
-                doc.doc_id
-            )));
-        }
-
-        docs.insert(doc.doc_id, doc);
-        Ok(())
+    /// Returns the precision of stored vectors.
+    #[must_use]
+    pub fn quantization(&self) -> Quantization {
+        self.quantization
     }
 
-    async fn search(
+    /// Ranks the documents accepted by `filter` (all when `None`).
+    fn search_matching(
         &self,
         query: &[f32],
         k: usize,
-        _ef_search: Option<usize>,
+        filter: Option<SearchFilter<'_>>,
     ) -> CoreResult<Vec<SearchResult>> {
         if query.len() != self.dim {
             return Err(CoreError::invalid_state(format!(
@@ -162,12 +142,40 @@ impl VectorIndex for BruteForceIndex {
         }
 
         let docs = self.documents.read();
-
-        // Compute distances for all documents
-        let mut results: Vec<_> = docs
-            .values()
-            .map(|doc| {
-                let score = self.metric.compute(query, &doc.vector);
+        let accepted = docs.values().filter(|stored| {
+            filter.map_or(true, |filter| {
+                filter(stored.doc.doc_id, stored.doc.metadata.as_ref())
+            })
+        });
+
+        // Compute distances for all documents (Int8: for the best coarse
+        // candidates only)
+        let scored: Vec<(&StoredDocument, f32)> = if self.quantization == Quantization::Int8 {
+            let coarse_query = Int8Query::new(query);
+            let mut candidates: Vec<_> = accepted
+                .filter_map(|stored| {
+                    let score = stored.vector.coarse_score(self.metric, &coarse_query)?;
+                    Some((stored, score))
+                })
+                .collect();
+            let limit = k.saturating_mul(RESCORE_OVERSAMPLE);
+            if candidates.len() > limit {
+                candidates.select_nth_unstable_by(limit, |a, b| best_first(self.metric, a.1, b.1));
+                candidates.truncate(limit);
+            }
+            candidates
+                .into_iter()
+                .map(|(stored, _)| (stored, stored.vector.score(self.metric, query)))
+                .collect()
+        } else {
+            accepted
+                .map(|stored| (stored, stored.vector.score(self.metric, query)))
+                .collect()
+        };
+
+        let mut results: Vec<_> = scored
+            .into_iter()
+            .map(|(StoredDocument { doc, .. }, score)| {
                 let mut result = SearchResult::new(doc.doc_id, score);
 
                 // BUG-2 FIX: Only set external_id/metadata if they exist (don't fabricate empty values)
@@ -199,6 +207,76 @@ impl VectorIndex for BruteForceIndex {
         results.truncate(k);
         Ok(results)
     }
+}
+
+#[async_trait]
+impl VectorIndex for BruteForceIndex {
+    async fn insert(&self, mut doc: VectorDocument) -> CoreResult<()> {
+        if doc.vector.len() != self.dim {
+            return Err(CoreError::invalid_state(format!(
+                "Vector dimension mismatch: expected {}, got {}",
+                self.dim,
+                doc.vector.len()
+            )));
+        }
+
+        // BUG-5 FIX: Validate no NaN or Inf values
+        for (i, &val) in doc.vector.iter().enumerate() {
+            if !val.is_finite() {
+                return Err(CoreError::invalid_state(format!(
+                    "Vector contains invalid value at index {}: {}. \
+                     Only finite numbers are allowed (no NaN or Infinity)",
+                    i, val
+                )));
+            }
+        }
+
+        // BUG-9 FIX: For Cosine metric, reject zero vectors (undefined similarity)
+        if matches!(self.metric, DistanceMetric::Cosine) {
+            let norm_squared: f32 = doc.vector.iter().map(|x| x * x).sum();
+            if norm_squared == 0.0 {
+                return Err(CoreError::invalid_state(
+                    "Cannot insert zero vector with Cosine similarity metric. \
+                     Cosine similarity is mathematically undefined for zero vectors. \
+                     Consider using L2 distance metric instead."
+                        .to_string(),
+                ));
+            }
+        }
+
+        let mut docs = self.documents.write();
+
+        // BUG-1 FIX: Reject duplicate inserts (consistent with InstantDistanceIndex)
+        if docs.contains_key(&doc.doc_id) {
+            return Err(CoreError::invalid_state(format!(
+                "Document {} already exists",
+                doc.doc_id
+            )));
+        }
+
+        let vector = QuantizedVector::new(std::mem::take(&mut doc.vector), self.quantization);
+        docs.insert(doc.doc_id, StoredDocument { doc, vector });
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        _ef_search: Option<usize>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        self.search_matching(query, k, None)
+    }
+
+    async fn search_filtered(
+        &self,
+        query: &[f32],
+        k: usize,
+        _ef_search: Option<usize>,
+        filter: SearchFilter<'_>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        self.search_matching(query, k, Some(filter))
+    }
 
     async fn delete(&self, doc_id: DocumentId) -> CoreResult<()> {
         let mut docs = self.documents.write();
@@ -212,7 +290,11 @@ impl VectorIndex for BruteForceIndex {
 
     async fn get(&self, doc_id: DocumentId) -> CoreResult<Option<VectorDocument>> {
         let docs = self.documents.read();
-        Ok(docs.get(&doc_id).cloned())
+        Ok(docs.get(&doc_id).map(|stored| {
+            let mut doc = stored.doc.clone();
+            doc.vector = stored.vector.dequantize().into_owned();
+            doc
+        }))
     }
 
     async fn count(&self) -> CoreResult<usize> {
@@ -468,4 +550,70 @@ mod tests {
 
         assert!(result.is_ok()); // Should succeed for Dot metric
     }
+
+    #[tokio::test]
+    async fn test_quantized_search_finds_exact_match() {
+        let vectors: Vec<Vec<f32>> = (0..100)
+            .map(|i| {
+                (0..16)
+                    .map(|j| ((i * 16 + j) as f32 * 0.37).sin())
+                    .collect()
+            })
+            .collect();
+
+        for quantization in [Quantization::Int8, Quantization::Fp16] {
+            for metric in [
+                DistanceMetric::Cosine,
+                DistanceMetric::L2,
+                DistanceMetric::Dot,
+            ] {
+                let index = BruteForceIndex::new(16, metric).with_quantization(quantization);
+                let mut ids = Vec::new();
+                for vector in &vectors {
+                    let doc_id = DocumentId::new();
+                    ids.push(doc_id);
+                    index
+                        .insert(VectorDocument::new(doc_id, vector.clone()))
+                        .await
+                        .unwrap();
+                }
+
+                let results = index.search(&vectors[42], 5, None).await.unwrap();
+                assert_eq!(results.len(), 5);
+                if metric != DistanceMetric::Dot {
+                    assert_eq!(results[0].doc_id, ids[42], "{quantization:?} {metric:?}");
+                }
+
+                // get() returns the dequantized vector
+                let stored = index.get(ids[42]).await.unwrap().unwrap().vector;
+                for (original, restored) in vectors[42].iter().zip(&stored) {
+                    assert!((original - restored).abs() < 0.01);
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_filtered_ranks_only_accepted() {
+        let index = BruteForceIndex::new(2, DistanceMetric::L2);
+        for i in 0..50 {
+            let doc = VectorDocument::new(DocumentId::new(), vec![i as f32, 0.0])
+                .with_metadata(serde_json::json!({ "rank": i }));
+            index.insert(doc).await.unwrap();
+        }
+
+        // Only multiples of 10 are accepted, all far from the query
+        let filter = |_: DocumentId, metadata: Option<&serde_json::Value>| {
+            metadata.is_some_and(|m| m["rank"].as_i64().unwrap() % 10 == 0)
+        };
+        let results = index
+            .search_filtered(&[4.0, 0.0], 3, None, &filter)
+            .await
+            .unwrap();
+        let ranks: Vec<_> = results
+            .iter()
+            .map(|r| r.metadata.as_ref().unwrap()["rank"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ranks, vec![0, 10, 20]);
+    }
 }
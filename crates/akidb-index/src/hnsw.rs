@@ -23,14 +23,22 @@ use std::collections::{BinaryHeap, HashMap, HashSet};
 
 use async_trait::async_trait;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use akidb_core::{
-    CoreError, CoreResult, DistanceMetric, DocumentId, SearchResult, VectorDocument, VectorIndex,
+    CoreError, CoreResult, DistanceMetric, DocumentId, SearchFilter, SearchResult, VectorDocument,
+    VectorIndex,
 };
 
 // Use crate-level sync module for conditional compilation (Loom vs production)
 use crate::{Arc, RwLock};
 
+/// Leading bytes of a serialized [`HnswIndex`] graph.
+const GRAPH_MAGIC: &[u8; 8] = b"AKHNSW\0\0";
+
+/// Version of the serialized graph format, bumped on incompatible changes.
+pub const GRAPH_FORMAT_VERSION: u32 = 1;
+
 /// HNSW index configuration parameters.
 #[derive(Debug, Clone)]
 pub struct HnswConfig {
@@ -180,6 +188,35 @@ struct HnswState {
     max_layer: usize,
 }
 
+/// Serialized form of [`HnswIndex`] (graph format version 1).
+///
+/// Metadata is stored as JSON text since the binary encoding cannot
+/// represent self-describing JSON values.
+#[derive(Serialize, Deserialize)]
+struct GraphSnapshot {
+    dim: usize,
+    metric: DistanceMetric,
+    m: usize,
+    m0: usize,
+    ef_construction: usize,
+    ef_search: usize,
+    ml: f64,
+    nodes: Vec<NodeSnapshot>,
+    layers: Vec<Vec<(DocumentId, Vec<DocumentId>)>>,
+    entry_point: Option<DocumentId>,
+    max_layer: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NodeSnapshot {
+    doc_id: DocumentId,
+    external_id: Option<String>,
+    vector: Vec<f32>,
+    metadata: Option<String>,
+    max_layer: usize,
+    deleted: bool,
+}
+
 impl HnswIndex {
     /// Creates a new HNSW index with the given configuration.
     pub fn new(config: HnswConfig) -> Self {
@@ -194,6 +231,166 @@ impl HnswIndex {
         }
     }
 
+    /// Serializes the graph, nodes and configuration.
+    ///
+    /// The output starts with a magic number and [`GRAPH_FORMAT_VERSION`], so
+    /// [`from_bytes`](Self::from_bytes) can reject graphs it cannot read.
+    /// Tombstoned nodes are kept, as they may still route searches.
+    pub fn to_bytes(&self) -> CoreResult<Vec<u8>> {
+        let state = self.state.read();
+        let nodes = state
+            .nodes
+            .values()
+            .map(|node| {
+                Ok(NodeSnapshot {
+                    doc_id: node.doc_id,
+                    external_id: node.external_id.clone(),
+                    vector: node.vector.clone(),
+                    metadata: node
+                        .metadata
+                        .as_ref()
+                        .map(serde_json::to_string)
+                        .transpose()
+                        .map_err(|e| {
+                            CoreError::internal(format!("Failed to encode metadata: {}", e))
+                        })?,
+                    max_layer: node.max_layer,
+                    deleted: node.deleted,
+                })
+            })
+            .collect::<CoreResult<Vec<_>>>()?;
+        let snapshot = GraphSnapshot {
+            dim: self.config.dim,
+            metric: self.config.metric,
+            m: self.config.m,
+            m0: self.config.m0,
+            ef_construction: self.config.ef_construction,
+            ef_search: self.config.ef_search,
+            ml: self.config.ml,
+            nodes,
+            layers: state
+                .layers
+                .iter()
+                .map(|layer| {
+                    layer
+                        .iter()
+                        .map(|(id, neighbors)| (*id, neighbors.clone()))
+                        .collect()
+                })
+                .collect(),
+            entry_point: state.entry_point,
+            max_layer: state.max_layer,
+        };
+        drop(state);
+
+        let mut bytes = Vec::with_capacity(GRAPH_MAGIC.len() + 4);
+        bytes.extend_from_slice(GRAPH_MAGIC);
+        bytes.extend_from_slice(&GRAPH_FORMAT_VERSION.to_le_bytes());
+        bincode::serialize_into(&mut bytes, &snapshot)
+            .map_err(|e| CoreError::internal(format!("Failed to serialize HNSW graph: {}", e)))?;
+        Ok(bytes)
+    }
+
+    /// Restores an index serialized by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not an HNSW graph, were written by
+    /// an unsupported format version, or are corrupted.
+    pub fn from_bytes(bytes: &[u8]) -> CoreResult<Self> {
+        let header_len = GRAPH_MAGIC.len() + 4;
+        if bytes.len() < header_len || &bytes[..GRAPH_MAGIC.len()] != GRAPH_MAGIC {
+            return Err(CoreError::invalid_state("Not a serialized HNSW graph"));
+        }
+        let mut version = [0u8; 4];
+        version.copy_from_slice(&bytes[GRAPH_MAGIC.len()..header_len]);
+        let version = u32::from_le_bytes(version);
+        if version != GRAPH_FORMAT_VERSION {
+            return Err(CoreError::invalid_state(format!(
+                "Unsupported HNSW graph format version {} (expected {})",
+                version, GRAPH_FORMAT_VERSION
+            )));
+        }
+
+        let snapshot: GraphSnapshot = bincode::deserialize(&bytes[header_len..])
+            .map_err(|e| CoreError::invalid_state(format!("Corrupted HNSW graph: {}", e)))?;
+        let entry_point_known = snapshot.entry_point.map_or(true, |ep| {
+            snapshot.nodes.iter().any(|node| node.doc_id == ep)
+        });
+        if snapshot.layers.is_empty() || !entry_point_known {
+            return Err(CoreError::invalid_state(
+                "Corrupted HNSW graph: inconsistent entry point",
+            ));
+        }
+
+        let mut nodes = HashMap::with_capacity(snapshot.nodes.len());
+        for node in snapshot.nodes {
+            if node.vector.len() != snapshot.dim {
+                return Err(CoreError::invalid_state(format!(
+                    "Corrupted HNSW graph: node {} has dimension {}, expected {}",
+                    node.doc_id,
+                    node.vector.len(),
+                    snapshot.dim
+                )));
+            }
+            let metadata = node
+                .metadata
+                .as_deref()
+                .map(serde_json::from_str)
+                .transpose()
+                .map_err(|e| CoreError::invalid_state(format!("Corrupted HNSW graph: {}", e)))?;
+            nodes.insert(
+                node.doc_id,
+                Node {
+                    doc_id: node.doc_id,
+                    external_id: node.external_id,
+                    vector: node.vector,
+                    metadata,
+                    max_layer: node.max_layer,
+                    deleted: node.deleted,
+                },
+            );
+        }
+
+        Ok(Self {
+            config: HnswConfig {
+                dim: snapshot.dim,
+                metric: snapshot.metric,
+                m: snapshot.m,
+                m0: snapshot.m0,
+                ef_construction: snapshot.ef_construction,
+                ef_search: snapshot.ef_search,
+                ml: snapshot.ml,
+            },
+            state: Arc::new(RwLock::new(HnswState {
+                nodes,
+                layers: snapshot
+                    .layers
+                    .into_iter()
+                    .map(|layer| layer.into_iter().collect())
+                    .collect(),
+                entry_point: snapshot.entry_point,
+                max_layer: snapshot.max_layer,
+            })),
+        })
+    }
+
+    /// IDs of the documents in the index, excluding deleted ones.
+    pub fn doc_ids(&self) -> Vec<DocumentId> {
+        let state = self.state.read();
+        state
+            .nodes
+            .values()
+            .filter(|node| !node.deleted)
+            .map(|node| node.doc_id)
+            .collect()
+    }
+
+    /// Configuration the index was created (or restored) with.
+    pub fn config(&self) -> &HnswConfig {
+        &self.config
+    }
+
     /// Assigns a random layer to a new node using exponential distribution.
     fn assign_layer(&self) -> usize {
         let mut rng = rand::thread_rng();
@@ -354,6 +551,214 @@ impl HnswIndex {
             .collect()
     }
 
+    /// k-NN search over the documents accepted by `filter` (all when `None`).
+    fn search_matching(
+        &self,
+        query: &[f32],
+        k: usize,
+        ef_search: Option<usize>,
+        filter: Option<SearchFilter<'_>>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        if query.len() != self.config.dim {
+            return Err(CoreError::invalid_state(format!(
+                "Query dimension mismatch: expected {}, got {}",
+                self.config.dim,
+                query.len()
+            )));
+        }
+
+        let state = self.state.read();
+
+        let ef = ef_search.unwrap_or(self.config.ef_search).max(k);
+
+        // Handle empty index
+        let entry_point = match state.entry_point {
+            Some(ep) => ep,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut entry_points = vec![entry_point];
+
+        // Search from top layer down to layer 1
+        for layer in (1..=state.max_layer).rev() {
+            let nearest = self.search_layer(&state, query, &entry_points, 1, layer);
+            if !nearest.is_empty() {
+                entry_points = vec![nearest[0].1];
+            }
+        }
+
+        // Search layer 0 with ef parameter
+        let candidates = match filter {
+            Some(filter) => {
+                let found = self.search_layer_filtered(&state, query, &entry_points, ef, filter);
+                if found.len() < k {
+                    // The traversal reached too few accepted nodes; rank
+                    // every accepted node instead
+                    self.scan_filtered(&state, query, k, filter)
+                } else {
+                    found
+                }
+            }
+            None => self.search_layer(&state, query, &entry_points, ef, 0),
+        };
+
+        // FIX BUG #21: Filter out deleted nodes before building results
+        // Without this, deleted vectors continue to appear in search results (GDPR violation!)
+        // Mirror the deleted check used in get() and count() methods
+        let results: Vec<SearchResult> = candidates
+            .into_iter()
+            .take(k)
+            .filter_map(|(score, doc_id)| {
+                state.nodes.get(&doc_id).and_then(|node| {
+                    // Skip deleted nodes (soft delete with tombstone)
+                    if node.deleted {
+                        return None;
+                    }
+
+                    let mut result = SearchResult::new(doc_id, score);
+                    if let Some(ref ext_id) = node.external_id {
+                        result = result.with_external_id(ext_id.clone());
+                    }
+                    if let Some(ref meta) = node.metadata {
+                        result = result.with_metadata(meta.clone());
+                    }
+                    Some(result)
+                })
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Searches layer 0 for the `ef` nearest nodes accepted by `filter`.
+    ///
+    /// Rejected nodes are still expanded, so they keep the graph connected,
+    /// but only accepted nodes enter the result set that bounds the search.
+    /// A selective filter therefore visits more of the graph.
+    fn search_layer_filtered(
+        &self,
+        state: &HnswState,
+        query: &[f32],
+        entry_points: &[DocumentId],
+        ef: usize,
+        filter: SearchFilter<'_>,
+    ) -> Vec<(f32, DocumentId)> {
+        let metric = self.config.metric;
+        // Min-heap keys: negate similarities so the best node pops first
+        let heap_dist = |dist: f32| match metric {
+            DistanceMetric::L2 => dist,
+            DistanceMetric::Cosine | DistanceMetric::Dot => -dist,
+        };
+        let accepts = |node: &Node| filter(node.doc_id, node.metadata.as_ref());
+        let worst_of = |results: &[OrderedDist]| {
+            results
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| {
+                    if a.is_better_than(b, metric) {
+                        Ordering::Less
+                    } else {
+                        Ordering::Greater
+                    }
+                })
+                .map(|(idx, od)| (idx, *od))
+        };
+
+        let mut visited = HashSet::new();
+        let mut candidates: BinaryHeap<std::cmp::Reverse<OrderedDist>> = BinaryHeap::new();
+        let mut results: Vec<OrderedDist> = Vec::new();
+
+        for &ep in entry_points {
+            if let Some(node) = state.nodes.get(&ep) {
+                if node.deleted || !visited.insert(ep) {
+                    continue;
+                }
+                let dist = self.compute_distance(query, node);
+                candidates.push(std::cmp::Reverse(OrderedDist(heap_dist(dist), ep)));
+                if accepts(node) {
+                    results.push(OrderedDist(dist, ep));
+                }
+            }
+        }
+
+        while let Some(std::cmp::Reverse(OrderedDist(curr_heap, curr_id))) = candidates.pop() {
+            let curr = OrderedDist(heap_dist(curr_heap), curr_id);
+            if results.len() >= ef {
+                if let Some((_, worst)) = worst_of(&results) {
+                    if !curr.is_better_than(&worst, metric) {
+                        break;
+                    }
+                }
+            }
+
+            let Some(neighbors) = state.layers.first().and_then(|l| l.get(&curr_id)) else {
+                continue;
+            };
+            for &neighbor_id in neighbors {
+                if !visited.insert(neighbor_id) {
+                    continue;
+                }
+                let Some(neighbor) = state.nodes.get(&neighbor_id) else {
+                    continue;
+                };
+                if neighbor.deleted {
+                    continue;
+                }
+                let dist = self.compute_distance(query, neighbor);
+                let od = OrderedDist(dist, neighbor_id);
+                let worst = worst_of(&results);
+                let promising = results.len() < ef
+                    || worst.map_or(true, |(_, worst)| od.is_better_than(&worst, metric));
+                if !promising {
+                    continue;
+                }
+
+                candidates.push(std::cmp::Reverse(OrderedDist(heap_dist(dist), neighbor_id)));
+                if accepts(neighbor) {
+                    results.push(od);
+                    if results.len() > ef {
+                        if let Some((worst_idx, _)) = worst_of(&results) {
+                            results.swap_remove(worst_idx);
+                        }
+                    }
+                }
+            }
+        }
+
+        results.sort_by(|a, b| {
+            if a.is_better_than(b, metric) {
+                Ordering::Less
+            } else if b.is_better_than(a, metric) {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        });
+        results
+            .into_iter()
+            .map(|OrderedDist(dist, id)| (dist, id))
+            .collect()
+    }
+
+    /// Exact k-NN over every live node accepted by `filter`.
+    fn scan_filtered(
+        &self,
+        state: &HnswState,
+        query: &[f32],
+        k: usize,
+        filter: SearchFilter<'_>,
+    ) -> Vec<(f32, DocumentId)> {
+        let mut scored: Vec<(f32, DocumentId)> = state
+            .nodes
+            .values()
+            .filter(|node| !node.deleted && filter(node.doc_id, node.metadata.as_ref()))
+            .map(|node| (self.compute_distance(query, node), node.doc_id))
+            .collect();
+        scored.sort_by(|a, b| self.compare_distances(a.0, b.0));
+        scored.truncate(k);
+        scored
+    }
+
     /// Compares two distances according to the metric convention.
     fn compare_distances(&self, a: f32, b: f32) -> Ordering {
         match self.config.metric {
@@ -631,63 +1036,17 @@ impl VectorIndex for HnswIndex {
         k: usize,
         ef_search: Option<usize>,
     ) -> CoreResult<Vec<SearchResult>> {
-        if query.len() != self.config.dim {
-            return Err(CoreError::invalid_state(format!(
-                "Query dimension mismatch: expected {}, got {}",
-                self.config.dim,
-                query.len()
-            )));
-        }
-
-        let state = self.state.read();
-
-        let ef = ef_search.unwrap_or(self.config.ef_search).max(k);
-
-        // Handle empty index
-        let entry_point = match state.entry_point {
-            Some(ep) => ep,
-            None => return Ok(Vec::new()),
-        };
-
-        let mut entry_points = vec![entry_point];
-
-        // Search from top layer down to layer 1
-        for layer in (1..=state.max_layer).rev() {
-            let nearest = self.search_layer(&state, query, &entry_points, 1, layer);
-            if !nearest.is_empty() {
-                entry_points = vec![nearest[0].1];
-            }
-        }
-
-        // Search layer 0 with ef parameter
-        let candidates = self.search_layer(&state, query, &entry_points, ef, 0);
-
-        // FIX BUG #21: Filter out deleted nodes before building results
-        // Without this, deleted vectors continue to appear in search results (GDPR violation!)
-        // Mirror the deleted check used in get() and count() methods
-        let results: Vec<SearchResult> = candidates
-            .into_iter()
-            .take(k)
-            .filter_map(|(score, doc_id)| {
-                state.nodes.get(&doc_id).and_then(|node| {
-                    // Skip deleted nodes (soft delete with tombstone)
-                    if node.deleted {
-                        return None;
-                    }
-
-                    let mut result = SearchResult::new(doc_id, score);
-                    if let Some(ref ext_id) = node.external_id {
-                        result = result.with_external_id(ext_id.clone());
-                    }
-                    if let Some(ref meta) = node.metadata {
-                        result = result.with_metadata(meta.clone());
-                    }
-                    Some(result)
-                })
-            })
-            .collect();
+        self.search_matching(query, k, ef_search, None)
+    }
 
-        Ok(results)
+    async fn search_filtered(
+        &self,
+        query: &[f32],
+        k: usize,
+        ef_search: Option<usize>,
+        filter: SearchFilter<'_>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        self.search_matching(query, k, ef_search, Some(filter))
     }
 
     async fn delete(&self, doc_id: DocumentId) -> CoreResult<()> {
@@ -698,6 +1057,27 @@ impl VectorIndex for HnswIndex {
             node.deleted = true;
         }
 
+        // Searches start at the entry point and skip tombstones, so move it
+        // to the highest remaining live node
+        if state.entry_point == Some(doc_id) {
+            let next = state
+                .nodes
+                .values()
+                .filter(|node| !node.deleted)
+                .max_by_key(|node| node.max_layer)
+                .map(|node| (node.doc_id, node.max_layer));
+            match next {
+                Some((entry_point, max_layer)) => {
+                    state.entry_point = Some(entry_point);
+                    state.max_layer = max_layer;
+                }
+                None => {
+                    state.entry_point = None;
+                    state.max_layer = 0;
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -712,6 +1092,7 @@ impl VectorIndex for HnswIndex {
                     doc_id: node.doc_id,
                     external_id: node.external_id.clone(),
                     vector: node.vector.clone(),
+                    sparse: None,
                     metadata: node.metadata.clone(),
                     inserted_at: chrono::Utc::now(), // Note: We don't store inserted_at in HNSW node
                 })
@@ -734,6 +1115,10 @@ impl VectorIndex for HnswIndex {
         state.max_layer = 0;
         Ok(())
     }
+
+    fn serialize_graph(&self) -> CoreResult<Option<Vec<u8>>> {
+        self.to_bytes().map(Some)
+    }
 }
 
 /// Wrapper for f32 to implement Ord for use in BinaryHeap.
@@ -899,4 +1284,85 @@ mod tests {
         assert_eq!(high.m, 48);
         assert_eq!(high.ef_construction, 320);
     }
+
+    #[tokio::test]
+    async fn test_hnsw_graph_round_trip() {
+        let config = HnswConfig::edge_cache(4, DistanceMetric::L2);
+        let index = HnswIndex::new(config);
+
+        let mut doc_ids = Vec::new();
+        for i in 0..50 {
+            let doc = VectorDocument::new(DocumentId::new(), vec![i as f32, 1.0, 0.5, -1.0])
+                .with_metadata(serde_json::json!({"i": i}));
+            doc_ids.push(doc.doc_id);
+            index.insert(doc).await.unwrap();
+        }
+        index.delete(doc_ids[0]).await.unwrap();
+
+        let restored = HnswIndex::from_bytes(&index.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.config().m, 16);
+        assert_eq!(restored.count().await.unwrap(), 49);
+        assert!(restored.get(doc_ids[0]).await.unwrap().is_none());
+        let doc = restored.get(doc_ids[7]).await.unwrap().unwrap();
+        assert_eq!(doc.metadata, Some(serde_json::json!({"i": 7})));
+
+        let query = vec![20.0, 1.0, 0.5, -1.0];
+        let expected = index.search(&query, 5, None).await.unwrap();
+        let actual = restored.search(&query, 5, None).await.unwrap();
+        assert_eq!(
+            expected.iter().map(|r| r.doc_id).collect::<Vec<_>>(),
+            actual.iter().map(|r| r.doc_id).collect::<Vec<_>>()
+        );
+        assert_eq!(actual[0].doc_id, doc_ids[20]);
+    }
+
+    #[tokio::test]
+    async fn test_hnsw_graph_rejects_unknown_version() {
+        let index = HnswIndex::new(HnswConfig::balanced(2, DistanceMetric::Cosine));
+        let mut bytes = index.to_bytes().unwrap();
+        assert!(HnswIndex::from_bytes(&bytes).is_ok());
+
+        bytes[GRAPH_MAGIC.len()..GRAPH_MAGIC.len() + 4]
+            .copy_from_slice(&(GRAPH_FORMAT_VERSION + 1).to_le_bytes());
+        let err = HnswIndex::from_bytes(&bytes).err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("Unsupported HNSW graph format version"));
+        assert!(HnswIndex::from_bytes(b"not a graph").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hnsw_search_filtered() {
+        let config = HnswConfig::edge_cache(2, DistanceMetric::L2);
+        let index = HnswIndex::new(config);
+        for i in 0..200 {
+            let doc = VectorDocument::new(DocumentId::new(), vec![i as f32, (i % 7) as f32])
+                .with_metadata(serde_json::json!({ "group": i % 20 }));
+            index.insert(doc).await.unwrap();
+        }
+
+        // 10 of 200 documents match; a post-filtered top 5 would be empty
+        let filter = |_: DocumentId, metadata: Option<&serde_json::Value>| {
+            metadata.is_some_and(|m| m["group"] == 3)
+        };
+        let results = index
+            .search_filtered(&[100.0, 0.0], 5, None, &filter)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 5);
+        assert!(results
+            .iter()
+            .all(|r| r.metadata.as_ref().unwrap()["group"] == 3));
+        assert!(results
+            .windows(2)
+            .all(|pair| pair[0].score <= pair[1].score));
+
+        // Nothing matches
+        let none = |_: DocumentId, _: Option<&serde_json::Value>| false;
+        let results = index
+            .search_filtered(&[100.0, 0.0], 5, None, &none)
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+    }
 }
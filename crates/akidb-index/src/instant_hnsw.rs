@@ -6,12 +6,14 @@
 //! Use this for collections with >10k vectors where high recall is critical.
 
 use akidb_core::{
-    CoreError, CoreResult, DistanceMetric, DocumentId, SearchResult, VectorDocument, VectorIndex,
+    CoreError, CoreResult, DistanceMetric, DocumentId, Quantization, SearchFilter, SearchResult,
+    VectorDocument, VectorIndex,
 };
 use async_trait::async_trait;
 use instant_distance::{Builder, HnswMap, Point, Search};
 use std::collections::HashMap;
 
+use crate::quantization::{best_first, QuantizedVector, RESCORE_OVERSAMPLE};
 // Use crate-level sync module for conditional compilation (Loom vs production)
 use crate::{Arc, RwLock};
 
@@ -66,19 +68,17 @@ impl InstantDistanceConfig {
 }
 
 /// Point wrapper for instant-distance compatibility.
+///
+/// Graph points are stored at the index's precision; query points are
+/// always full precision.
 #[derive(Clone, Debug)]
-struct VectorPoint(Vec<f32>);
+struct VectorPoint(QuantizedVector);
 
 impl Point for VectorPoint {
     fn distance(&self, other: &Self) -> f32 {
         // Default to Euclidean L2 distance for instant-distance
         // The actual metric conversion happens in compute_score()
-        let mut sum: f32 = 0.0;
-        for (a, b) in self.0.iter().zip(other.0.iter()) {
-            let diff = a - b;
-            sum += diff * diff;
-        }
-        sum.sqrt()
+        self.0.l2_distance(&other.0)
     }
 }
 
@@ -88,10 +88,23 @@ struct DocMetadata {
     doc_id: DocumentId,
     external_id: Option<String>,
     metadata: Option<serde_json::Value>,
-    vector: Vec<f32>,
+    vector: QuantizedVector,
     inserted_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl DocMetadata {
+    fn to_result(&self, score: f32) -> SearchResult {
+        let mut result = SearchResult::new(self.doc_id, score);
+        if let Some(ref ext_id) = self.external_id {
+            result = result.with_external_id(ext_id.clone());
+        }
+        if let Some(ref meta_data) = self.metadata {
+            result = result.with_metadata(meta_data.clone());
+        }
+        result
+    }
+}
+
 /// State for InstantDistanceIndex.
 struct InstantDistanceState {
     /// The HNSW index from instant-distance
@@ -143,6 +156,7 @@ struct InstantDistanceState {
 /// ```
 pub struct InstantDistanceIndex {
     config: InstantDistanceConfig,
+    quantization: Quantization,
     state: Arc<RwLock<InstantDistanceState>>,
 }
 
@@ -168,6 +182,7 @@ impl InstantDistanceIndex {
 
         Ok(Self {
             config,
+            quantization: Quantization::None,
             state: Arc::new(RwLock::new(InstantDistanceState {
                 index: None,
                 doc_map: HashMap::new(),
@@ -178,6 +193,22 @@ impl InstantDistanceIndex {
         })
     }
 
+    /// Stores vectors at the given precision.
+    ///
+    /// Quantized searches take extra candidates from the graph and re-score
+    /// them exactly against the f32 query.
+    #[must_use]
+    pub fn with_quantization(mut self, quantization: Quantization) -> Self {
+        self.quantization = quantization;
+        self
+    }
+
+    /// Returns the precision of stored vectors.
+    #[must_use]
+    pub fn quantization(&self) -> Quantization {
+        self.quantization
+    }
+
     /// Normalizes a vector for Cosine similarity (unit length).
     fn normalize_vector(&self, vector: &[f32]) -> Vec<f32> {
         if !matches!(self.config.metric, DistanceMetric::Cosine) {
@@ -244,8 +275,11 @@ impl InstantDistanceIndex {
         let mut points = Vec::new();
         let mut values = Vec::new();
         for (id, meta) in state.doc_map.iter() {
-            let normalized = self.normalize_vector(&meta.vector);
-            points.push(VectorPoint(normalized));
+            let normalized = self.normalize_vector(&meta.vector.dequantize());
+            points.push(VectorPoint(QuantizedVector::new(
+                normalized,
+                self.quantization,
+            )));
             values.push(*id);
         }
 
@@ -292,6 +326,122 @@ impl InstantDistanceIndex {
             }
         }
     }
+
+    /// k-NN search over the documents accepted by `filter` (all when `None`).
+    async fn search_matching(
+        &self,
+        query: &[f32],
+        k: usize,
+        filter: Option<SearchFilter<'_>>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        if query.len() != self.config.dim {
+            return Err(CoreError::invalid_state(format!(
+                "Query dimension mismatch: expected {}, got {}",
+                self.config.dim,
+                query.len()
+            )));
+        }
+
+        // BUG-8 FIX: Validate query contains no NaN/Inf
+        for (i, &val) in query.iter().enumerate() {
+            if !val.is_finite() {
+                return Err(CoreError::invalid_state(format!(
+                    "Query vector contains invalid value at index {}: {}. \
+                     Only finite numbers are allowed (no NaN or Infinity)",
+                    i, val
+                )));
+            }
+        }
+
+        // FIX BUG #20: Validate query vector is not zero for Cosine metric
+        // Mirror the validation in insert() to prevent NaN scores and unstable ranking
+        if self.config.metric == DistanceMetric::Cosine {
+            let norm: f32 = query.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm == 0.0 {
+                return Err(CoreError::ValidationError(
+                    "Cannot search with zero vector using Cosine metric".to_string(),
+                ));
+            }
+        }
+
+        // CONCURRENCY FIX: Auto-rebuild if dirty (better UX for concurrent workloads)
+        // Check if rebuild needed with read lock, then rebuild if necessary.
+        // This avoids "dirty index" errors during concurrent write+search operations.
+        //
+        // ⚠️ BRITTLENESS WARNING (Bob, 2025-11-07):
+        // This dirty flag check is THREAD-SAFE because it's done INSIDE the read lock.
+        // DO NOT optimize by checking dirty outside the lock (e.g., AtomicBool) without
+        // implementing epoch counter pattern. See ARCHITECTURE-CONCURRENCY.md for details.
+        let is_dirty = {
+            let state = self.state.read();
+            state.dirty
+        }; // Read guard dropped here before await
+
+        if is_dirty {
+            // IMPORTANT: Lock released before calling force_rebuild() to avoid:
+            // 1. Deadlock (parking_lot::RwLock cannot upgrade read → write)
+            // 2. Send trait issues (cannot hold guard across await)
+            //
+            // If multiple threads see dirty and call rebuild concurrently,
+            // the first one to acquire the write lock will rebuild, and subsequent ones
+            // will see !dirty and return early (idempotent operation).
+            self.force_rebuild().await?;
+        }
+
+        // Use read lock for concurrent searches
+        let state = self.state.read();
+
+        let index = match &state.index {
+            Some(idx) => idx,
+            None => return Ok(Vec::new()),
+        };
+        let accepts = |meta: &DocMetadata| {
+            filter.map_or(true, |filter| filter(meta.doc_id, meta.metadata.as_ref()))
+        };
+
+        // Perform search (normalize query for Cosine)
+        let normalized_query = self.normalize_vector(query);
+        let query_point = VectorPoint(QuantizedVector::F32(normalized_query));
+        let mut search = Search::default();
+
+        let results = index
+            .search(&query_point, &mut search)
+            .filter_map(|item| Some((state.doc_map.get(item.value)?, item.distance)))
+            .filter(|(meta, _)| accepts(meta));
+
+        let mut search_results: Vec<SearchResult> = if self.quantization != Quantization::None {
+            // Re-score extra candidates against the stored vectors, since the
+            // graph distances are only approximate
+            let mut search_results: Vec<SearchResult> = results
+                .take(k.saturating_mul(RESCORE_OVERSAMPLE))
+                .map(|(meta, _)| meta.to_result(meta.vector.score(self.config.metric, query)))
+                .collect();
+            search_results.sort_by(|a, b| best_first(self.config.metric, a.score, b.score));
+            search_results.truncate(k);
+            search_results
+        } else {
+            // Convert results to SearchResult
+            results
+                .take(k)
+                .map(|(meta, distance)| meta.to_result(self.compute_score(distance)))
+                .collect()
+        };
+
+        // The graph only yields its ef_search nearest points; when the filter
+        // rejected too many of them, rank the accepted documents exactly
+        if filter.is_some() && search_results.len() < k {
+            search_results = state
+                .doc_map
+                .values()
+                .filter(|meta| accepts(meta))
+                .map(|meta| meta.to_result(meta.vector.score(self.config.metric, query)))
+                .collect();
+            search_results.sort_by(|a, b| best_first(self.config.metric, a.score, b.score));
+            search_results.truncate(k);
+        }
+
+        Ok(search_results)
+    }
 }
 
 #[async_trait]
@@ -347,7 +497,7 @@ impl VectorIndex for InstantDistanceIndex {
             doc_id: doc.doc_id,
             external_id: doc.external_id,
             metadata: doc.metadata,
-            vector: doc.vector,
+            vector: QuantizedVector::new(doc.vector, self.quantization),
             inserted_at: doc.inserted_at,
         };
 
@@ -366,97 +516,19 @@ impl VectorIndex for InstantDistanceIndex {
         &self,
         query: &[f32],
         k: usize,
-        _filter: Option<usize>,
+        _ef_search: Option<usize>,
     ) -> CoreResult<Vec<SearchResult>> {
-        if query.len() != self.config.dim {
-            return Err(CoreError::invalid_state(format!(
-                "Query dimension mismatch: expected {}, got {}",
-                self.config.dim,
-                query.len()
-            )));
-        }
-
-        // BUG-8 FIX: Validate query contains no NaN/Inf
-        for (i, &val) in query.iter().enumerate() {
-            if !val.is_finite() {
-                return Err(CoreError::invalid_state(format!(
-                    "Query vector contains invalid value at index {}: {}. \
-                     Only finite numbers are allowed (no NaN or Infinity)",
-                    i, val
-                )));
-            }
-        }
-
-        // FIX BUG #20: Validate query vector is not zero for Cosine metric
-        // Mirror the validation in insert() to prevent NaN scores and unstable ranking
-        if self.config.metric == DistanceMetric::Cosine {
-            let norm: f32 = query.iter().map(|x| x * x).sum::<f32>().sqrt();
-            if norm == 0.0 {
-                return Err(CoreError::ValidationError(
-                    "Cannot search with zero vector using Cosine metric".to_string(),
-                ));
-            }
-        }
-
-        // CONCURRENCY FIX: Auto-rebuild if dirty (better UX for concurrent workloads)
-        // Check if rebuild needed with read lock, then rebuild if necessary.
-        // This avoids "dirty index" errors during concurrent write+search operations.
-        //
-        // ⚠️ BRITTLENESS WARNING (Bob, 2025-11-07):
-        // This dirty flag check is THREAD-SAFE because it's done INSIDE the read lock.
-        // DO NOT optimize by checking dirty outside the lock (e.g., AtomicBool) without
-        // implementing epoch counter pattern. See ARCHITECTURE-CONCURRENCY.md for details.
-        let is_dirty = {
-            let state = self.state.read();
-            state.dirty
-        }; // Read guard dropped here before await
-
-        if is_dirty {
-            // IMPORTANT: Lock released before calling force_rebuild() to avoid:
-            // 1. Deadlock (parking_lot::RwLock cannot upgrade read → write)
-            // 2. Send trait issues (cannot hold guard across await)
-            //
-            // If multiple threads see dirty and call rebuild concurrently,
-            // the first one to acquire the write lock will rebuild, and subsequent ones
-            // will see !dirty and return early (idempotent operation).
-            self.force_rebuild().await?;
-        }
-
-        // Use read lock for concurrent searches
-        let state = self.state.read();
-
-        let index = match &state.index {
-            Some(idx) => idx,
-            None => return Ok(Vec::new()),
-        };
-
-        // Perform search (normalize query for Cosine)
-        let normalized_query = self.normalize_vector(query);
-        let query_point = VectorPoint(normalized_query);
-        let mut search = Search::default();
-
-        let results = index.search(&query_point, &mut search);
-
-        // Convert results to SearchResult
-        let search_results: Vec<SearchResult> = results
-            .into_iter()
-            .take(k)
-            .filter_map(|item| {
-                state.doc_map.get(item.value).map(|meta| {
-                    let score = self.compute_score(item.distance);
-                    let mut result = SearchResult::new(meta.doc_id, score);
-                    if let Some(ref ext_id) = meta.external_id {
-                        result = result.with_external_id(ext_id.clone());
-                    }
-                    if let Some(ref meta_data) = meta.metadata {
-                        result = result.with_metadata(meta_data.clone());
-                    }
-                    result
-                })
-            })
-            .collect();
+        self.search_matching(query, k, None).await
+    }
 
-        Ok(search_results)
+    async fn search_filtered(
+        &self,
+        query: &[f32],
+        k: usize,
+        _ef_search: Option<usize>,
+        filter: SearchFilter<'_>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        self.search_matching(query, k, Some(filter)).await
     }
 
     async fn delete(&self, doc_id: DocumentId) -> CoreResult<()> {
@@ -489,8 +561,8 @@ impl VectorIndex for InstantDistanceIndex {
             None => return Ok(None),
         };
 
-        let mut doc =
-            VectorDocument::new(doc_id, meta.vector.clone()).with_timestamp(meta.inserted_at);
+        let mut doc = VectorDocument::new(doc_id, meta.vector.dequantize().into_owned())
+            .with_timestamp(meta.inserted_at);
         if let Some(ref ext_id) = meta.external_id {
             doc = doc.with_external_id(ext_id.clone());
         }
@@ -790,4 +862,71 @@ mod tests {
 
         assert!(result.is_ok()); // Should succeed for L2 metric
     }
+
+    #[tokio::test]
+    async fn test_instant_quantized_search_rescoring() {
+        for quantization in [Quantization::Int8, Quantization::Fp16] {
+            let config = InstantDistanceConfig::balanced(16, DistanceMetric::Cosine);
+            let index = InstantDistanceIndex::new(config)
+                .unwrap()
+                .with_quantization(quantization);
+
+            let vectors: Vec<Vec<f32>> = (0..100)
+                .map(|i| {
+                    (0..16)
+                        .map(|j| ((i * 16 + j) as f32 * 0.37).sin())
+                        .collect()
+                })
+                .collect();
+            let mut ids = Vec::new();
+            for vector in &vectors {
+                let doc_id = DocumentId::new();
+                ids.push(doc_id);
+                index
+                    .insert(VectorDocument::new(doc_id, vector.clone()))
+                    .await
+                    .unwrap();
+            }
+
+            let results = index.search(&vectors[7], 5, None).await.unwrap();
+            assert_eq!(results.len(), 5);
+            assert_eq!(results[0].doc_id, ids[7], "{quantization:?}");
+            // Re-scored exactly against the dequantized vector
+            let stored = index.get(ids[7]).await.unwrap().unwrap().vector;
+            let exact = DistanceMetric::Cosine.compute(&vectors[7], &stored);
+            assert!((results[0].score - exact).abs() < 1e-6);
+            assert!(results
+                .windows(2)
+                .all(|pair| pair[0].score >= pair[1].score));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_instant_search_filtered() {
+        let config = InstantDistanceConfig::balanced(2, DistanceMetric::L2);
+        let index = InstantDistanceIndex::new(config).unwrap();
+        for i in 0..200 {
+            let doc = VectorDocument::new(DocumentId::new(), vec![i as f32, (i % 7) as f32])
+                .with_metadata(serde_json::json!({ "group": i % 20 }));
+            index.insert(doc).await.unwrap();
+        }
+
+        let filter = |_: DocumentId, metadata: Option<&serde_json::Value>| {
+            metadata.is_some_and(|m| m["group"] == 3)
+        };
+        let results = index
+            .search_filtered(&[100.0, 0.0], 5, None, &filter)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 5);
+        assert!(results
+            .iter()
+            .all(|r| r.metadata.as_ref().unwrap()["group"] == 3));
+        // The closest matching documents are 103 and 83
+        let first: Vec<f32> = index.get(results[0].doc_id).await.unwrap().unwrap().vector;
+        assert_eq!(first[0], 103.0);
+        assert!(results
+            .windows(2)
+            .all(|pair| pair[0].score <= pair[1].score));
+    }
 }
@@ -3,6 +3,13 @@
 //! This crate provides vector index implementations:
 //! - `BruteForceIndex`: Simple linear scan (baseline for correctness)
 //! - `HnswIndex`: HNSW graph-based ANN for approximate nearest neighbor search
+//! - `TextIndex`: BM25 keyword index over payload text, paired with a vector
+//!   index by `HybridIndex`
+//! - `SparseIndex`: inverted dot-product index over sparse vectors, also held
+//!   by `HybridIndex`
+//!
+//! `BruteForceIndex` and `InstantDistanceIndex` can store vectors as int8 or
+//! fp16 (see `akidb_core::Quantization`).
 
 // Conditional compilation for Loom testing vs production
 // This allows us to swap std::sync/parking_lot types with Loom's instrumented versions
@@ -29,7 +36,12 @@ pub(crate) use sync::{Arc, RwLock};
 mod brute_force;
 mod hnsw;
 mod instant_hnsw;
+mod quantization;
+mod sparse;
+mod text;
 
 pub use brute_force::BruteForceIndex;
-pub use hnsw::{HnswConfig, HnswIndex};
+pub use hnsw::{HnswConfig, HnswIndex, GRAPH_FORMAT_VERSION};
 pub use instant_hnsw::{InstantDistanceConfig, InstantDistanceIndex};
+pub use sparse::SparseIndex;
+pub use text::{AnalyzedText, HybridIndex, TextIndex};
@@ -0,0 +1,236 @@
+//! Quantized vector storage shared by the index implementations.
+//!
+//! Indexes keep one `QuantizedVector` per document. Int8 vectors are ranked
+//! with integer arithmetic against a quantized query, then the best
+//! candidates are re-scored exactly against the f32 query by dequantizing
+//! them on the fly. Fp16 vectors are dequantized while scoring.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use akidb_core::{DistanceMetric, Quantization};
+use half::f16;
+
+/// How many candidates per requested result an Int8 coarse pass keeps for
+/// exact re-scoring.
+pub(crate) const RESCORE_OVERSAMPLE: usize = 4;
+
+/// Largest magnitude of an int8 code (symmetric range, -127 is the minimum).
+const INT8_MAX: f32 = 127.0;
+
+/// A vector stored at the precision selected for its collection.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum QuantizedVector {
+    /// Full-precision components
+    F32(Vec<f32>),
+    /// Codes in [-127, 127]; component `i` is `codes[i] * scale`
+    Int8 { scale: f32, codes: Vec<i8> },
+    /// Raw IEEE 754 half-precision bits
+    Fp16(Vec<u16>),
+}
+
+impl QuantizedVector {
+    /// Encodes a vector at the given precision.
+    pub(crate) fn new(vector: Vec<f32>, quantization: Quantization) -> Self {
+        match quantization {
+            Quantization::None => Self::F32(vector),
+            Quantization::Int8 => {
+                let (scale, codes) = quantize_int8(&vector);
+                Self::Int8 { scale, codes }
+            }
+            Quantization::Fp16 => {
+                Self::Fp16(vector.iter().map(|&x| f16::from_f32(x).to_bits()).collect())
+            }
+        }
+    }
+
+    /// Iterates over the dequantized components without allocating.
+    pub(crate) fn values(&self) -> Values<'_> {
+        Values {
+            vector: self,
+            pos: 0,
+        }
+    }
+
+    /// Returns the dequantized vector, borrowing full-precision vectors.
+    pub(crate) fn dequantize(&self) -> Cow<'_, [f32]> {
+        match self {
+            Self::F32(values) => Cow::Borrowed(values),
+            _ => Cow::Owned(self.values().collect()),
+        }
+    }
+
+    /// Scores the stored vector against an f32 query with `metric`.
+    ///
+    /// Quantized vectors are dequantized first, so this is the exact score of
+    /// the stored vector (not of the original, which is not kept).
+    pub(crate) fn score(&self, metric: DistanceMetric, query: &[f32]) -> f32 {
+        metric.compute(query, &self.dequantize())
+    }
+
+    /// Cheap approximate score against a query quantized with
+    /// [`Int8Query::new`].
+    ///
+    /// Returns `None` unless this is an Int8 vector.
+    pub(crate) fn coarse_score(&self, metric: DistanceMetric, query: &Int8Query) -> Option<f32> {
+        let Self::Int8 { scale, codes } = self else {
+            return None;
+        };
+
+        let score = match metric {
+            DistanceMetric::Dot => scale * query.scale * int_dot(codes, &query.codes) as f32,
+            DistanceMetric::Cosine => {
+                let norms = (int_dot(codes, codes) as f32).sqrt() * query.norm;
+                if norms == 0.0 {
+                    0.0
+                } else {
+                    int_dot(codes, &query.codes) as f32 / norms
+                }
+            }
+            DistanceMetric::L2 => codes
+                .iter()
+                .zip(&query.codes)
+                .map(|(&a, &b)| (f32::from(a) * scale - f32::from(b) * query.scale).powi(2))
+                .sum::<f32>()
+                .sqrt(),
+        };
+        Some(score)
+    }
+
+    /// Euclidean distance between two stored vectors, dequantizing on the fly.
+    pub(crate) fn l2_distance(&self, other: &Self) -> f32 {
+        if let (Self::F32(a), Self::F32(b)) = (self, other) {
+            // Graph construction calls this for every candidate pair, so keep
+            // the unquantized case a plain loop over slices
+            let mut sum: f32 = 0.0;
+            for (a, b) in a.iter().zip(b) {
+                let diff = a - b;
+                sum += diff * diff;
+            }
+            return sum.sqrt();
+        }
+        self.values()
+            .zip(other.values())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+}
+
+/// Iterator over the dequantized components of a [`QuantizedVector`].
+pub(crate) struct Values<'a> {
+    vector: &'a QuantizedVector,
+    pos: usize,
+}
+
+impl Iterator for Values<'_> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let value = match self.vector {
+            QuantizedVector::F32(values) => *values.get(self.pos)?,
+            QuantizedVector::Int8 { scale, codes } => f32::from(*codes.get(self.pos)?) * scale,
+            QuantizedVector::Fp16(bits) => f16::from_bits(*bits.get(self.pos)?).to_f32(),
+        };
+        self.pos += 1;
+        Some(value)
+    }
+}
+
+/// A query quantized for the Int8 coarse pass.
+pub(crate) struct Int8Query {
+    scale: f32,
+    codes: Vec<i8>,
+    /// L2 norm of `codes`
+    norm: f32,
+}
+
+impl Int8Query {
+    pub(crate) fn new(query: &[f32]) -> Self {
+        let (scale, codes) = quantize_int8(query);
+        let norm = (int_dot(&codes, &codes) as f32).sqrt();
+        Self { scale, codes, norm }
+    }
+}
+
+/// Orders scores best-first under `metric` (ascending distance for L2,
+/// descending similarity otherwise).
+pub(crate) fn best_first(metric: DistanceMetric, a: f32, b: f32) -> Ordering {
+    match metric {
+        DistanceMetric::L2 => a.total_cmp(&b),
+        DistanceMetric::Cosine | DistanceMetric::Dot => b.total_cmp(&a),
+    }
+}
+
+/// Symmetric per-vector int8 quantization: the largest magnitude maps to 127.
+fn quantize_int8(vector: &[f32]) -> (f32, Vec<i8>) {
+    let max = vector.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+    if max == 0.0 {
+        return (0.0, vec![0; vector.len()]);
+    }
+
+    let scale = max / INT8_MAX;
+    let codes = vector
+        .iter()
+        .map(|&x| (x / scale).round().clamp(-INT8_MAX, INT8_MAX) as i8)
+        .collect();
+    (scale, codes)
+}
+
+fn int_dot(a: &[i8], b: &[i8]) -> i32 {
+    a.iter()
+        .zip(b)
+        .map(|(&x, &y)| i32::from(x) * i32::from(y))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_int8_round_trip_error_is_bounded() {
+        let vector = vec![0.5, -1.0, 0.25, 0.0, 0.9];
+        let quantized = QuantizedVector::new(vector.clone(), Quantization::Int8);
+        let step = 1.0 / INT8_MAX;
+        for (original, restored) in vector.iter().zip(quantized.values()) {
+            assert!((original - restored).abs() <= step / 2.0 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_fp16_round_trip() {
+        let vector = vec![0.5, -1.0, 0.333, 1024.0];
+        let quantized = QuantizedVector::new(vector.clone(), Quantization::Fp16);
+        for (original, restored) in vector.iter().zip(quantized.values()) {
+            assert!((original - restored).abs() <= original.abs() * 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_zero_vector_quantizes_to_zero() {
+        let quantized = QuantizedVector::new(vec![0.0; 4], Quantization::Int8);
+        assert_eq!(quantized.dequantize().as_ref(), &[0.0; 4]);
+    }
+
+    #[test]
+    fn test_coarse_score_tracks_exact_score() {
+        let stored = vec![0.2, -0.4, 0.8, 0.1];
+        let query = vec![0.3, -0.1, 0.7, 0.2];
+        let quantized = QuantizedVector::new(stored.clone(), Quantization::Int8);
+        let int8_query = Int8Query::new(&query);
+
+        for metric in [
+            DistanceMetric::Cosine,
+            DistanceMetric::Dot,
+            DistanceMetric::L2,
+        ] {
+            let coarse = quantized.coarse_score(metric, &int8_query).unwrap();
+            let exact = metric.compute(&query, &stored);
+            assert!(
+                (coarse - exact).abs() < 0.02,
+                "{metric:?}: {coarse} vs {exact}"
+            );
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Inverted index over sparse vectors.
+//!
+//! `SparseIndex` keeps a posting list per vector index holding the value of
+//! every document that has that component. A query visits only the posting
+//! lists of its own indices and accumulates the dot product of each document
+//! it meets, so search cost grows with the overlap between query and corpus
+//! rather than with the corpus size.
+
+use std::collections::HashMap;
+
+use akidb_core::{DocumentId, SparseVector};
+
+use crate::RwLock;
+
+#[derive(Default)]
+struct SparseState {
+    /// Vector index -> document -> value
+    postings: HashMap<u32, HashMap<DocumentId, f32>>,
+    /// Sparse vector of each document, for removal and retrieval
+    vectors: HashMap<DocumentId, SparseVector>,
+}
+
+/// Dot-product index over the sparse vectors of a collection's documents.
+///
+/// # Example
+///
+/// ```
+/// use akidb_core::{DocumentId, SparseVector};
+/// use akidb_index::SparseIndex;
+///
+/// let index = SparseIndex::new();
+/// let doc_id = DocumentId::new();
+/// index.insert(doc_id, SparseVector::new(vec![3, 17], vec![0.5, 1.5]).unwrap());
+///
+/// let query = SparseVector::new(vec![17], vec![2.0]).unwrap();
+/// assert_eq!(index.search(&query, 10), vec![(doc_id, 3.0)]);
+/// ```
+#[derive(Default)]
+pub struct SparseIndex {
+    state: RwLock<SparseState>,
+}
+
+impl SparseIndex {
+    /// Creates an empty sparse index.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes a document's sparse vector, replacing any previous version.
+    pub fn insert(&self, doc_id: DocumentId, vector: SparseVector) {
+        let mut state = self.state.write();
+        Self::remove_locked(&mut state, doc_id);
+        for (index, value) in vector.iter() {
+            state
+                .postings
+                .entry(index)
+                .or_default()
+                .insert(doc_id, value);
+        }
+        state.vectors.insert(doc_id, vector);
+    }
+
+    /// Removes a document; unknown documents are ignored.
+    pub fn remove(&self, doc_id: DocumentId) {
+        Self::remove_locked(&mut self.state.write(), doc_id);
+    }
+
+    fn remove_locked(state: &mut SparseState, doc_id: DocumentId) {
+        let Some(vector) = state.vectors.remove(&doc_id) else {
+            return;
+        };
+        for &index in vector.indices() {
+            if let Some(postings) = state.postings.get_mut(&index) {
+                postings.remove(&doc_id);
+                if postings.is_empty() {
+                    state.postings.remove(&index);
+                }
+            }
+        }
+    }
+
+    /// Removes every document.
+    pub fn clear(&self) {
+        *self.state.write() = SparseState::default();
+    }
+
+    /// The sparse vector indexed for a document.
+    #[must_use]
+    pub fn get(&self, doc_id: DocumentId) -> Option<SparseVector> {
+        self.state.read().vectors.get(&doc_id).cloned()
+    }
+
+    /// Number of documents with a sparse vector.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.state.read().vectors.len()
+    }
+
+    /// Whether no document has a sparse vector.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `k` documents with the highest dot product with `query`, best
+    /// first.
+    ///
+    /// Documents sharing no index with the query are not returned.
+    #[must_use]
+    pub fn search(&self, query: &SparseVector, k: usize) -> Vec<(DocumentId, f32)> {
+        if query.is_empty() || k == 0 {
+            return Vec::new();
+        }
+
+        let state = self.state.read();
+        let mut scores: HashMap<DocumentId, f32> = HashMap::new();
+        for (index, weight) in query.iter() {
+            let Some(postings) = state.postings.get(&index) else {
+                continue;
+            };
+            for (&doc_id, &value) in postings {
+                *scores.entry(doc_id).or_default() += weight * value;
+            }
+        }
+
+        let mut hits: Vec<(DocumentId, f32)> = scores.into_iter().collect();
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1));
+        hits.truncate(k);
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sparse(pairs: &[(u32, f32)]) -> SparseVector {
+        let (indices, values) = pairs.iter().copied().unzip();
+        SparseVector::new(indices, values).unwrap()
+    }
+
+    #[test]
+    fn test_search_ranks_by_dot_product() {
+        let index = SparseIndex::new();
+        let (strong, weak, unrelated) = (DocumentId::new(), DocumentId::new(), DocumentId::new());
+        index.insert(strong, sparse(&[(1, 2.0), (5, 1.0)]));
+        index.insert(weak, sparse(&[(5, 0.5), (8, 3.0)]));
+        index.insert(unrelated, sparse(&[(9, 4.0)]));
+
+        let hits = index.search(&sparse(&[(1, 1.0), (5, 2.0)]), 10);
+        assert_eq!(hits, vec![(strong, 4.0), (weak, 1.0)]);
+
+        assert_eq!(index.search(&sparse(&[(1, 1.0), (5, 2.0)]), 1).len(), 1);
+        assert!(index.search(&SparseVector::default(), 10).is_empty());
+    }
+
+    #[test]
+    fn test_insert_replaces_and_remove_cleans_postings() {
+        let index = SparseIndex::new();
+        let doc_id = DocumentId::new();
+        index.insert(doc_id, sparse(&[(1, 1.0)]));
+        index.insert(doc_id, sparse(&[(2, 1.0)]));
+
+        assert_eq!(index.len(), 1);
+        assert!(index.search(&sparse(&[(1, 1.0)]), 10).is_empty());
+        assert_eq!(index.get(doc_id), Some(sparse(&[(2, 1.0)])));
+
+        index.remove(doc_id);
+        assert!(index.is_empty());
+        assert!(index.state.read().postings.is_empty());
+    }
+}
@@ -0,0 +1,538 @@
+//! BM25 keyword index over document payload text.
+//!
+//! `TextIndex` keeps one inverted index per top-level metadata key holding a
+//! string (or an array of strings). Text is lowercased and split on
+//! non-alphanumeric characters. A query is scored against each searched
+//! field with Okapi BM25 and the per-field scores are summed.
+//!
+//! `HybridIndex` pairs a vector index with a `TextIndex` and a
+//! `SparseIndex` kept in step with it, so keyword, sparse and vector search
+//! see the same documents.
+
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+
+use akidb_core::{
+    CoreResult, DocumentId, SearchFilter, SearchResult, SparseVector, VectorDocument, VectorIndex,
+};
+
+use crate::{RwLock, SparseIndex};
+
+/// BM25 term-frequency saturation.
+const K1: f32 = 1.2;
+
+/// BM25 document-length normalization.
+const B: f32 = 0.75;
+
+/// Tokens of each text field of a document, as produced by
+/// [`TextIndex::analyze`].
+pub type AnalyzedText = Vec<(String, Vec<String>)>;
+
+/// Inverted index of one payload field.
+#[derive(Default)]
+struct FieldIndex {
+    /// Term -> document -> term frequency
+    postings: HashMap<String, HashMap<DocumentId, u32>>,
+    /// Token count of each document with this field
+    lengths: HashMap<DocumentId, u32>,
+    /// Distinct terms of each document, for removal
+    terms: HashMap<DocumentId, Vec<String>>,
+    /// Sum of `lengths`
+    total_length: u64,
+}
+
+impl FieldIndex {
+    fn insert(&mut self, doc_id: DocumentId, tokens: &[String]) {
+        let mut frequencies: HashMap<&str, u32> = HashMap::new();
+        for token in tokens {
+            *frequencies.entry(token).or_default() += 1;
+        }
+        let mut terms = Vec::with_capacity(frequencies.len());
+        for (term, frequency) in frequencies {
+            self.postings
+                .entry(term.to_string())
+                .or_default()
+                .insert(doc_id, frequency);
+            terms.push(term.to_string());
+        }
+        self.terms.insert(doc_id, terms);
+        self.lengths.insert(doc_id, tokens.len() as u32);
+        self.total_length += tokens.len() as u64;
+    }
+
+    fn remove(&mut self, doc_id: DocumentId) {
+        let Some(length) = self.lengths.remove(&doc_id) else {
+            return;
+        };
+        self.total_length -= u64::from(length);
+        for term in self.terms.remove(&doc_id).unwrap_or_default() {
+            if let Some(docs) = self.postings.get_mut(&term) {
+                docs.remove(&doc_id);
+                if docs.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Adds the BM25 score of every document matching `terms` to `scores`.
+    fn score(&self, terms: &HashSet<String>, scores: &mut HashMap<DocumentId, f32>) {
+        let doc_count = self.lengths.len() as f32;
+        if doc_count == 0.0 {
+            return;
+        }
+        let avg_length = self.total_length as f32 / doc_count;
+
+        for term in terms {
+            let Some(docs) = self.postings.get(term) else {
+                continue;
+            };
+            let matching = docs.len() as f32;
+            let idf = (1.0 + (doc_count - matching + 0.5) / (matching + 0.5)).ln();
+            for (doc_id, &frequency) in docs {
+                let frequency = frequency as f32;
+                let length = self.lengths.get(doc_id).copied().unwrap_or_default() as f32;
+                let norm = K1 * (1.0 - B + B * length / avg_length.max(f32::EPSILON));
+                *scores.entry(*doc_id).or_default() +=
+                    idf * frequency * (K1 + 1.0) / (frequency + norm);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct TextState {
+    fields: HashMap<String, FieldIndex>,
+    /// Fields each document was indexed under
+    doc_fields: HashMap<DocumentId, Vec<String>>,
+}
+
+/// BM25 inverted index over the string fields of document metadata.
+///
+/// # Example
+///
+/// ```
+/// use akidb_core::DocumentId;
+/// use akidb_index::TextIndex;
+/// use serde_json::json;
+///
+/// let index = TextIndex::new();
+/// let doc_id = DocumentId::new();
+/// index.insert(doc_id, TextIndex::analyze(Some(&json!({"title": "Rust vector search"}))));
+///
+/// let hits = index.search("vector", None, 10);
+/// assert_eq!(hits[0].0, doc_id);
+/// ```
+#[derive(Default)]
+pub struct TextIndex {
+    state: RwLock<TextState>,
+}
+
+impl TextIndex {
+    /// Creates an empty text index.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Splits text into lowercase alphanumeric tokens.
+    #[must_use]
+    pub fn tokenize(text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    /// Tokens of each top-level string (or string array) field of `metadata`.
+    #[must_use]
+    pub fn analyze(metadata: Option<&serde_json::Value>) -> AnalyzedText {
+        let Some(serde_json::Value::Object(map)) = metadata else {
+            return Vec::new();
+        };
+        map.iter()
+            .filter_map(|(field, value)| {
+                let tokens = match value {
+                    serde_json::Value::String(text) => Self::tokenize(text),
+                    serde_json::Value::Array(items) => items
+                        .iter()
+                        .filter_map(serde_json::Value::as_str)
+                        .flat_map(Self::tokenize)
+                        .collect(),
+                    _ => return None,
+                };
+                (!tokens.is_empty()).then(|| (field.clone(), tokens))
+            })
+            .collect()
+    }
+
+    /// Indexes a document's analyzed text, replacing any previous version.
+    pub fn insert(&self, doc_id: DocumentId, text: AnalyzedText) {
+        let mut state = self.state.write();
+        Self::remove_locked(&mut state, doc_id);
+        if text.is_empty() {
+            return;
+        }
+
+        let mut fields = Vec::with_capacity(text.len());
+        for (field, tokens) in text {
+            state
+                .fields
+                .entry(field.clone())
+                .or_default()
+                .insert(doc_id, &tokens);
+            fields.push(field);
+        }
+        state.doc_fields.insert(doc_id, fields);
+    }
+
+    /// Removes a document; unknown documents are ignored.
+    pub fn remove(&self, doc_id: DocumentId) {
+        Self::remove_locked(&mut self.state.write(), doc_id);
+    }
+
+    fn remove_locked(state: &mut TextState, doc_id: DocumentId) {
+        let Some(fields) = state.doc_fields.remove(&doc_id) else {
+            return;
+        };
+        for field in fields {
+            if let Some(index) = state.fields.get_mut(&field) {
+                index.remove(doc_id);
+                if index.lengths.is_empty() {
+                    state.fields.remove(&field);
+                }
+            }
+        }
+    }
+
+    /// Removes every document.
+    pub fn clear(&self) {
+        *self.state.write() = TextState::default();
+    }
+
+    /// Number of indexed documents with any text.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.state.read().doc_fields.len()
+    }
+
+    /// Whether no document has any text.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `k` documents with the highest BM25 score for `query`, best first.
+    ///
+    /// Scores of the searched fields (all when `fields` is `None`) are summed.
+    /// Documents matching no query term are not returned.
+    #[must_use]
+    pub fn search(
+        &self,
+        query: &str,
+        fields: Option<&[String]>,
+        k: usize,
+    ) -> Vec<(DocumentId, f32)> {
+        let terms: HashSet<String> = Self::tokenize(query).into_iter().collect();
+        if terms.is_empty() || k == 0 {
+            return Vec::new();
+        }
+
+        let state = self.state.read();
+        let mut scores = HashMap::new();
+        match fields {
+            Some(fields) => {
+                for field in fields {
+                    if let Some(index) = state.fields.get(field) {
+                        index.score(&terms, &mut scores);
+                    }
+                }
+            }
+            None => {
+                for index in state.fields.values() {
+                    index.score(&terms, &mut scores);
+                }
+            }
+        }
+
+        let mut hits: Vec<(DocumentId, f32)> = scores.into_iter().collect();
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1));
+        hits.truncate(k);
+        hits
+    }
+}
+
+/// A vector index with a [`TextIndex`] over its documents' metadata and a
+/// [`SparseIndex`] over their sparse vectors.
+///
+/// Writes go to the vector index first; the text and sparse indexes are only
+/// updated once they succeed. Sparse vectors are kept out of the vector index
+/// and put back on the documents [`VectorIndex::get`] returns.
+/// [`VectorIndex::text_search`] and [`VectorIndex::sparse_search`] answer
+/// from the text and sparse indexes.
+pub struct HybridIndex {
+    vectors: Box<dyn VectorIndex>,
+    text: TextIndex,
+    sparse: SparseIndex,
+}
+
+impl HybridIndex {
+    /// Wraps an empty (or text-less) vector index.
+    #[must_use]
+    pub fn new(vectors: Box<dyn VectorIndex>) -> Self {
+        Self::with_text(vectors, TextIndex::new())
+    }
+
+    /// Wraps a vector index whose documents are already in `text`.
+    #[must_use]
+    pub fn with_text(vectors: Box<dyn VectorIndex>, text: TextIndex) -> Self {
+        Self {
+            vectors,
+            text,
+            sparse: SparseIndex::new(),
+        }
+    }
+
+    /// Replaces the sparse index with one already holding the documents'
+    /// sparse vectors (builder pattern).
+    #[must_use]
+    pub fn with_sparse(mut self, sparse: SparseIndex) -> Self {
+        self.sparse = sparse;
+        self
+    }
+
+    /// The keyword index.
+    #[must_use]
+    pub fn text(&self) -> &TextIndex {
+        &self.text
+    }
+
+    /// The sparse vector index.
+    #[must_use]
+    pub fn sparse(&self) -> &SparseIndex {
+        &self.sparse
+    }
+
+    fn index_sparse(&self, doc_id: DocumentId, sparse: Option<SparseVector>) {
+        match sparse {
+            Some(sparse) => self.sparse.insert(doc_id, sparse),
+            None => self.sparse.remove(doc_id),
+        }
+    }
+}
+
+#[async_trait]
+impl VectorIndex for HybridIndex {
+    async fn insert(&self, mut doc: VectorDocument) -> CoreResult<()> {
+        let doc_id = doc.doc_id;
+        let text = TextIndex::analyze(doc.metadata.as_ref());
+        let sparse = doc.sparse.take();
+        self.vectors.insert(doc).await?;
+        self.text.insert(doc_id, text);
+        self.index_sparse(doc_id, sparse);
+        Ok(())
+    }
+
+    async fn insert_batch(&self, mut docs: Vec<VectorDocument>) -> CoreResult<()> {
+        let extras: Vec<(DocumentId, AnalyzedText, Option<SparseVector>)> = docs
+            .iter_mut()
+            .map(|doc| {
+                let text = TextIndex::analyze(doc.metadata.as_ref());
+                (doc.doc_id, text, doc.sparse.take())
+            })
+            .collect();
+        let result = self.vectors.insert_batch(docs).await;
+        for (doc_id, text, sparse) in extras {
+            // A failed batch may have inserted some documents
+            if result.is_ok() || self.vectors.get(doc_id).await?.is_some() {
+                self.text.insert(doc_id, text);
+                self.index_sparse(doc_id, sparse);
+            }
+        }
+        result
+    }
+
+    async fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        ef_search: Option<usize>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        self.vectors.search(query, k, ef_search).await
+    }
+
+    async fn search_filtered(
+        &self,
+        query: &[f32],
+        k: usize,
+        ef_search: Option<usize>,
+        filter: SearchFilter<'_>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        self.vectors
+            .search_filtered(query, k, ef_search, filter)
+            .await
+    }
+
+    async fn delete(&self, doc_id: DocumentId) -> CoreResult<()> {
+        self.vectors.delete(doc_id).await?;
+        self.text.remove(doc_id);
+        self.sparse.remove(doc_id);
+        Ok(())
+    }
+
+    async fn get(&self, doc_id: DocumentId) -> CoreResult<Option<VectorDocument>> {
+        Ok(self.vectors.get(doc_id).await?.map(|mut doc| {
+            doc.sparse = self.sparse.get(doc_id);
+            doc
+        }))
+    }
+
+    async fn count(&self) -> CoreResult<usize> {
+        self.vectors.count().await
+    }
+
+    async fn clear(&self) -> CoreResult<()> {
+        self.vectors.clear().await?;
+        self.text.clear();
+        self.sparse.clear();
+        Ok(())
+    }
+
+    fn serialize_graph(&self) -> CoreResult<Option<Vec<u8>>> {
+        self.vectors.serialize_graph()
+    }
+
+    fn text_search(
+        &self,
+        query: &str,
+        fields: Option<&[String]>,
+        k: usize,
+    ) -> CoreResult<Option<Vec<(DocumentId, f32)>>> {
+        Ok(Some(self.text.search(query, fields, k)))
+    }
+
+    fn sparse_search(
+        &self,
+        query: &SparseVector,
+        k: usize,
+    ) -> CoreResult<Option<Vec<(DocumentId, f32)>>> {
+        Ok(Some(self.sparse.search(query, k)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BruteForceIndex;
+    use akidb_core::DistanceMetric;
+    use serde_json::json;
+
+    fn doc(metadata: serde_json::Value) -> VectorDocument {
+        VectorDocument::new(DocumentId::new(), vec![1.0, 0.0, 0.0]).with_metadata(metadata)
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            TextIndex::tokenize("Hello, World! rust-lang 2024"),
+            vec!["hello", "world", "rust", "lang", "2024"]
+        );
+    }
+
+    #[test]
+    fn test_bm25_ranks_rarer_and_denser_terms_higher() {
+        let index = TextIndex::new();
+        let a = DocumentId::new();
+        let b = DocumentId::new();
+        let c = DocumentId::new();
+        index.insert(
+            a,
+            TextIndex::analyze(Some(&json!({"text": "rust rust database"}))),
+        );
+        index.insert(
+            b,
+            TextIndex::analyze(Some(&json!({"text": "rust database engine"}))),
+        );
+        index.insert(
+            c,
+            TextIndex::analyze(Some(&json!({"text": "python database"}))),
+        );
+
+        let hits = index.search("rust", None, 10);
+        assert_eq!(hits.iter().map(|hit| hit.0).collect::<Vec<_>>(), vec![a, b]);
+
+        // "database" is in every document, "engine" only in b
+        let hits = index.search("database engine", None, 10);
+        assert_eq!(hits[0].0, b);
+        assert_eq!(hits.len(), 3);
+    }
+
+    #[test]
+    fn test_field_restriction_and_removal() {
+        let index = TextIndex::new();
+        let doc_id = DocumentId::new();
+        index.insert(
+            doc_id,
+            TextIndex::analyze(Some(
+                &json!({"title": "Vectors", "tags": ["search", "ann"], "year": 2024}),
+            )),
+        );
+
+        assert_eq!(index.search("ann", None, 10).len(), 1);
+        assert!(index
+            .search("ann", Some(&["title".to_string()]), 10)
+            .is_empty());
+        assert!(index.search("2024", None, 10).is_empty());
+
+        index.remove(doc_id);
+        assert!(index.is_empty());
+        assert!(index.search("vectors", None, 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_index_tracks_writes() {
+        let index = HybridIndex::new(Box::new(BruteForceIndex::new(3, DistanceMetric::Cosine)));
+        let first = doc(json!({"text": "quantized vectors"}));
+        let second = doc(json!({"text": "keyword search"}));
+        let first_id = first.doc_id;
+        index.insert_batch(vec![first, second]).await.unwrap();
+
+        let hits = index.text_search("vectors", None, 10).unwrap().unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, first_id);
+
+        index.delete(first_id).await.unwrap();
+        assert!(index
+            .text_search("vectors", None, 10)
+            .unwrap()
+            .unwrap()
+            .is_empty());
+
+        // A failed insert leaves the text index alone
+        let duplicate = doc(json!({"text": "keyword"}));
+        index.insert(duplicate.clone()).await.unwrap();
+        assert!(index.insert(duplicate).await.is_err());
+        assert_eq!(index.text().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_index_tracks_sparse_vectors() {
+        let index = HybridIndex::new(Box::new(BruteForceIndex::new(3, DistanceMetric::Cosine)));
+        let sparse = SparseVector::new(vec![4, 10], vec![1.0, 0.5]).unwrap();
+        let doc = doc(json!({})).with_sparse(sparse.clone());
+        let doc_id = doc.doc_id;
+        index.insert(doc).await.unwrap();
+
+        let query = SparseVector::new(vec![10], vec![2.0]).unwrap();
+        let hits = index.sparse_search(&query, 10).unwrap().unwrap();
+        assert_eq!(hits, vec![(doc_id, 1.0)]);
+        assert_eq!(
+            index.get(doc_id).await.unwrap().unwrap().sparse,
+            Some(sparse)
+        );
+
+        index.delete(doc_id).await.unwrap();
+        assert!(index.sparse_search(&query, 10).unwrap().unwrap().is_empty());
+    }
+}
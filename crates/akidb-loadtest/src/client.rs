@@ -0,0 +1,217 @@
+//! Load test client for executing operations
+
+use async_trait::async_trait;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Operation types for load testing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationType {
+    Search,
+    Insert,
+    Update,
+    Delete,
+    Metadata,
+}
+
+/// Target the load is generated against
+#[async_trait]
+pub trait LoadTestClient: Send + Sync {
+    /// Prepare the target (collection, initial dataset) before the run
+    async fn setup(&self, _dataset_size: usize) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Perform one operation
+    async fn perform(&self, op_type: OperationType) -> Result<(), String>;
+
+    /// Clean up what `setup` created
+    async fn teardown(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Execute an operation and return duration
+    async fn execute(&self, op_type: OperationType) -> Result<Duration, String> {
+        let start = std::time::Instant::now();
+        self.perform(op_type).await?;
+        Ok(start.elapsed())
+    }
+}
+
+/// Choose operation type based on workload percentages
+pub fn choose_operation(
+    search_pct: f32,
+    insert_pct: f32,
+    update_pct: f32,
+    delete_pct: f32,
+    _metadata_pct: f32,
+) -> OperationType {
+    let roll: f32 = rand::random();
+
+    if roll < search_pct {
+        OperationType::Search
+    } else if roll < search_pct + insert_pct {
+        OperationType::Insert
+    } else if roll < search_pct + insert_pct + update_pct {
+        OperationType::Update
+    } else if roll < search_pct + insert_pct + update_pct + delete_pct {
+        OperationType::Delete
+    } else {
+        OperationType::Metadata
+    }
+}
+
+/// Random vector of `dimension` components
+pub(crate) fn random_vector(dimension: usize) -> Vec<f32> {
+    (0..dimension).map(|_| rand::random::<f32>()).collect()
+}
+
+/// IDs of the documents a remote client has stored
+///
+/// Updates and deletes pick their document here; each document's external
+/// ID is its document ID, so updates can upsert by external ID.
+#[derive(Default)]
+pub(crate) struct DocumentPool {
+    ids: Mutex<Vec<String>>,
+}
+
+impl DocumentPool {
+    /// Record a stored document
+    pub(crate) fn add(&self, doc_id: String) {
+        self.ids.lock().unwrap().push(doc_id);
+    }
+
+    /// A random stored document, kept in the pool
+    pub(crate) fn pick(&self) -> Option<String> {
+        let ids = self.ids.lock().unwrap();
+        if ids.is_empty() {
+            return None;
+        }
+        Some(ids[rand::random::<usize>() % ids.len()].clone())
+    }
+
+    /// A random stored document, removed from the pool
+    pub(crate) fn take(&self) -> Option<String> {
+        let mut ids = self.ids.lock().unwrap();
+        if ids.is_empty() {
+            return None;
+        }
+        let index = rand::random::<usize>() % ids.len();
+        Some(ids.swap_remove(index))
+    }
+}
+
+/// In-process client simulating operation latencies
+///
+/// Exercises the framework itself without a running server.
+pub struct SimulatedClient {
+    dimension: usize,
+}
+
+impl SimulatedClient {
+    /// Create new simulated client
+    pub fn new(dimension: usize) -> Self {
+        Self { dimension }
+    }
+
+    /// Simulate search operation
+    async fn do_search(&self) -> Result<(), String> {
+        // Simulate vector search with random query
+        tokio::time::sleep(Duration::from_micros(100)).await;
+        Ok(())
+    }
+
+    /// Simulate insert operation
+    async fn do_insert(&self) -> Result<(), String> {
+        // Create random vector
+        let _vector = random_vector(self.dimension);
+
+        // Simulate insert
+        tokio::time::sleep(Duration::from_micros(50)).await;
+        Ok(())
+    }
+
+    /// Simulate update operation
+    async fn do_update(&self) -> Result<(), String> {
+        // Simulate update
+        tokio::time::sleep(Duration::from_micros(75)).await;
+        Ok(())
+    }
+
+    /// Simulate delete operation
+    async fn do_delete(&self) -> Result<(), String> {
+        // Simulate delete
+        tokio::time::sleep(Duration::from_micros(30)).await;
+        Ok(())
+    }
+
+    /// Simulate metadata operation (list, get info)
+    async fn do_metadata(&self) -> Result<(), String> {
+        // Simulate metadata fetch
+        tokio::time::sleep(Duration::from_micros(200)).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LoadTestClient for SimulatedClient {
+    async fn perform(&self, op_type: OperationType) -> Result<(), String> {
+        match op_type {
+            OperationType::Search => self.do_search().await,
+            OperationType::Insert => self.do_insert().await,
+            OperationType::Update => self.do_update().await,
+            OperationType::Delete => self.do_delete().await,
+            OperationType::Metadata => self.do_metadata().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_client_operations() {
+        let client = SimulatedClient::new(128);
+
+        // Test each operation type
+        assert!(client.execute(OperationType::Search).await.is_ok());
+        assert!(client.execute(OperationType::Insert).await.is_ok());
+        assert!(client.execute(OperationType::Update).await.is_ok());
+        assert!(client.execute(OperationType::Delete).await.is_ok());
+        assert!(client.execute(OperationType::Metadata).await.is_ok());
+    }
+
+    #[test]
+    fn test_operation_selection() {
+        // Test that we can select operations based on percentages
+        let mut counts = [0; 5];
+
+        for _ in 0..1000 {
+            let op = choose_operation(0.7, 0.2, 0.05, 0.03, 0.02);
+            match op {
+                OperationType::Search => counts[0] += 1,
+                OperationType::Insert => counts[1] += 1,
+                OperationType::Update => counts[2] += 1,
+                OperationType::Delete => counts[3] += 1,
+                OperationType::Metadata => counts[4] += 1,
+            }
+        }
+
+        // Search should be ~70% (allow 10% variance)
+        assert!(counts[0] > 600 && counts[0] < 800);
+        // Insert should be ~20%
+        assert!(counts[1] > 150 && counts[1] < 250);
+    }
+
+    #[test]
+    fn test_document_pool() {
+        let pool = DocumentPool::default();
+        assert_eq!(pool.pick(), None);
+
+        pool.add("a".to_string());
+        assert_eq!(pool.pick().as_deref(), Some("a"));
+        assert_eq!(pool.take().as_deref(), Some("a"));
+        assert_eq!(pool.take(), None);
+    }
+}
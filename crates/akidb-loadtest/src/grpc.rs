@@ -0,0 +1,201 @@
+//! Load test client for the gRPC API
+
+use crate::client::{random_vector, DocumentPool, LoadTestClient, OperationType};
+use akidb_proto::collection_management_service_client::CollectionManagementServiceClient;
+use akidb_proto::collection_service_client::CollectionServiceClient;
+use akidb_proto::{
+    CreateCollectionRequest, DeleteCollectionRequest, DeleteRequest, DescribeRequest,
+    InsertRequest, QueryRequest, UpsertRequest,
+};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, Endpoint};
+use uuid::Uuid;
+
+/// Nearest neighbours requested by each search
+const SEARCH_TOP_K: i32 = 10;
+
+/// Client driving an `akidb-grpc` server
+pub struct GrpcClient {
+    channel: Channel,
+    api_key: Option<MetadataValue<tonic::metadata::Ascii>>,
+    dimension: usize,
+    collection_id: OnceLock<String>,
+    // Whether setup created the collection (and teardown drops it)
+    created: AtomicBool,
+    docs: DocumentPool,
+}
+
+impl GrpcClient {
+    /// Client for the server at `endpoint` (e.g. `http://localhost:9090`)
+    ///
+    /// Runs against `collection` when given; otherwise setup creates a
+    /// collection that teardown deletes. The connection is made on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `endpoint` is not a valid URI or `api_key` is not a
+    /// valid metadata value
+    pub fn new(
+        endpoint: &str,
+        api_key: Option<String>,
+        collection: Option<String>,
+        dimension: usize,
+    ) -> Result<Self, String> {
+        let channel = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| format!("Invalid gRPC endpoint {}: {}", endpoint, e))?
+            .connect_lazy();
+        let api_key = api_key
+            .map(|key| key.parse().map_err(|e| format!("Invalid API key: {}", e)))
+            .transpose()?;
+        let collection_id = OnceLock::new();
+        if let Some(collection) = collection {
+            let _ = collection_id.set(collection);
+        }
+        Ok(Self {
+            channel,
+            api_key,
+            dimension,
+            collection_id,
+            created: AtomicBool::new(false),
+            docs: DocumentPool::default(),
+        })
+    }
+
+    /// Wrap `message`, attaching the API key
+    fn request<T>(&self, message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        if let Some(key) = &self.api_key {
+            request.metadata_mut().insert("x-api-key", key.clone());
+        }
+        request
+    }
+
+    fn collections(&self) -> CollectionServiceClient<Channel> {
+        CollectionServiceClient::new(self.channel.clone())
+    }
+
+    fn management(&self) -> CollectionManagementServiceClient<Channel> {
+        CollectionManagementServiceClient::new(self.channel.clone())
+    }
+
+    fn collection_id(&self) -> Result<String, String> {
+        self.collection_id
+            .get()
+            .cloned()
+            .ok_or_else(|| "Collection not set up".to_string())
+    }
+
+    async fn insert(&self) -> Result<(), String> {
+        let doc_id = Uuid::now_v7().to_string();
+        let request = self.request(InsertRequest {
+            collection_id: self.collection_id()?,
+            doc_id: doc_id.clone(),
+            external_id: Some(doc_id.clone()),
+            vector: random_vector(self.dimension),
+            ..Default::default()
+        });
+        self.collections()
+            .insert(request)
+            .await
+            .map_err(|status| status_error(&status))?;
+        self.docs.add(doc_id);
+        Ok(())
+    }
+}
+
+fn status_error(status: &tonic::Status) -> String {
+    format!("gRPC {:?}: {}", status.code(), status.message())
+}
+
+#[async_trait]
+impl LoadTestClient for GrpcClient {
+    async fn setup(&self, dataset_size: usize) -> Result<(), String> {
+        if self.collection_id.get().is_none() {
+            let request = self.request(CreateCollectionRequest {
+                name: format!("loadtest-{}", Uuid::now_v7().simple()),
+                dimension: u32::try_from(self.dimension)
+                    .map_err(|_| format!("Dimension {} too large", self.dimension))?,
+                metric: "cosine".to_string(),
+                ..Default::default()
+            });
+            let created = self
+                .management()
+                .create_collection(request)
+                .await
+                .map_err(|status| status_error(&status))?
+                .into_inner();
+            let _ = self.collection_id.set(created.collection_id);
+            self.created.store(true, Ordering::SeqCst);
+        }
+        // The gRPC API inserts one document per call
+        for _ in 0..dataset_size {
+            self.insert().await?;
+        }
+        Ok(())
+    }
+
+    async fn perform(&self, op_type: OperationType) -> Result<(), String> {
+        let collection_id = self.collection_id()?;
+        let result = match op_type {
+            OperationType::Search => self
+                .collections()
+                .query(self.request(QueryRequest {
+                    collection_id,
+                    query_vector: random_vector(self.dimension),
+                    top_k: SEARCH_TOP_K,
+                    ..Default::default()
+                }))
+                .await
+                .map(drop),
+            OperationType::Insert => return self.insert().await,
+            OperationType::Update => {
+                // Nothing to update yet: store a new document instead
+                let Some(external_id) = self.docs.pick() else {
+                    return self.insert().await;
+                };
+                self.collections()
+                    .upsert(self.request(UpsertRequest {
+                        collection_id,
+                        external_id,
+                        vector: random_vector(self.dimension),
+                        metadata: None,
+                    }))
+                    .await
+                    .map(drop)
+            }
+            OperationType::Delete => {
+                let Some(doc_id) = self.docs.take() else {
+                    return self.insert().await;
+                };
+                self.collections()
+                    .delete(self.request(DeleteRequest {
+                        collection_id,
+                        doc_id,
+                    }))
+                    .await
+                    .map(drop)
+            }
+            OperationType::Metadata => self
+                .collections()
+                .describe(self.request(DescribeRequest { collection_id }))
+                .await
+                .map(drop),
+        };
+        result.map_err(|status| status_error(&status))
+    }
+
+    async fn teardown(&self) -> Result<(), String> {
+        if self.created.load(Ordering::SeqCst) {
+            self.management()
+                .delete_collection(self.request(DeleteCollectionRequest {
+                    collection_id: self.collection_id()?,
+                }))
+                .await
+                .map_err(|status| status_error(&status))?;
+        }
+        Ok(())
+    }
+}
@@ -3,22 +3,34 @@
 //! Provides comprehensive load testing infrastructure with:
 //! - Multiple load profiles (constant, ramp, spike, random)
 //! - Configurable workload mixes
+//! - YAML-defined scenarios
+//! - Targets over REST, gRPC or a simulated in-process client
 //! - Detailed metrics collection
 //! - Pass/fail assessment
-//! - Report generation
+//! - Report generation (Markdown, JSON, HTML)
 
-pub mod orchestrator;
+pub mod client;
+pub mod grpc;
 pub mod metrics;
-pub mod reporter;
+pub mod orchestrator;
 pub mod profiles;
-pub mod client;
+pub mod reporter;
+pub mod rest;
+pub mod scenario;
 
+pub use client::{LoadTestClient, OperationType, SimulatedClient};
 pub use orchestrator::{LoadTestOrchestrator, ScenarioConfig};
-pub use reporter::ReportFormat;
+pub use reporter::{ReportFormat, ResultWriter};
 pub use profiles::{LoadProfile, WorkloadMix};
+pub use scenario::{Scenario, TargetConfig};
+
+use serde::Deserialize;
 
 /// Success criteria for load test scenarios
-#[derive(Debug, Clone)]
+///
+/// Criteria omitted from a scenario file keep their default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct SuccessCriteria {
     /// Maximum P95 latency in milliseconds
     pub max_p95_latency_ms: f64,
@@ -54,7 +66,6 @@ impl Default for SuccessCriteria {
 
 impl SuccessCriteria {
     /// Production-ready criteria (strict)
-    #[allow(dead_code)] // Reserved for future production load testing
     pub fn production() -> Self {
         Self {
             max_p95_latency_ms: 25.0,
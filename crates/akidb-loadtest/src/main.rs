@@ -0,0 +1,111 @@
+//! Run a YAML load test scenario against an AkiDB server
+//!
+//! ```text
+//! akidb-loadtest <scenario.yaml> [--report-dir <dir>]
+//! ```
+//!
+//! Writes `<scenario>.html` and `<scenario>.json` reports to the report
+//! directory (`target/load_test_reports` by default). Exits with status 1
+//! when the success criteria are not met and 2 when the run fails.
+
+use akidb_loadtest::{LoadTestOrchestrator, ReportFormat, Scenario};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: akidb-loadtest <scenario.yaml> [--report-dir <dir>]";
+
+/// Default report directory, shared with the load tests in `tests/`
+const DEFAULT_REPORT_DIR: &str = "target/load_test_reports";
+
+struct Args {
+    scenario: PathBuf,
+    report_dir: PathBuf,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut scenario = None;
+    let mut report_dir = PathBuf::from(DEFAULT_REPORT_DIR);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--report-dir" => {
+                report_dir = args
+                    .next()
+                    .map(PathBuf::from)
+                    .ok_or_else(|| "--report-dir needs a value".to_string())?;
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+            _ if scenario.is_none() => scenario = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+    Ok(Args {
+        scenario: scenario.ok_or_else(|| USAGE.to_string())?,
+        report_dir,
+    })
+}
+
+async fn run(args: &Args) -> Result<bool, String> {
+    let scenario = Scenario::load(&args.scenario)?;
+    let orchestrator = LoadTestOrchestrator::with_client(scenario.config(), scenario.client()?);
+    let metrics = orchestrator.run().await?;
+    let writer = orchestrator
+        .create_result_writer(metrics, scenario.criteria.clone())
+        .await;
+
+    std::fs::create_dir_all(&args.report_dir).map_err(|e| {
+        format!(
+            "Failed to create report directory {}: {}",
+            args.report_dir.display(),
+            e
+        )
+    })?;
+    let report_name = args
+        .scenario
+        .file_stem()
+        .map_or_else(|| "report".into(), |stem| stem.to_string_lossy());
+
+    println!("📊 Reports generated:");
+    for format in [ReportFormat::Html, ReportFormat::Json] {
+        let path = report_path(&args.report_dir, &report_name, format);
+        writer
+            .write_report(&path, format)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        println!("   {}", path.display());
+    }
+
+    if writer.passes() {
+        println!("\n✅ {} PASSED", scenario.name);
+    } else {
+        println!(
+            "\n❌ {} FAILED:\n\n{}",
+            scenario.name,
+            writer.failure_summary()
+        );
+    }
+    Ok(writer.passes())
+}
+
+fn report_path(dir: &Path, name: &str, format: ReportFormat) -> PathBuf {
+    dir.join(format!("{}.{}", name, format.extension()))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&args).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -287,10 +287,11 @@ mod tests {
 
     #[test]
     fn test_metrics_percentiles() {
-        let mut metrics = LoadTestMetrics::default();
-
         // Add 100 samples: 0us, 100us, 200us, ..., 9900us
-        metrics.latencies_us = (0..100).map(|i| i * 100).collect();
+        let metrics = LoadTestMetrics {
+            latencies_us: (0..100).map(|i| i * 100).collect(),
+            ..Default::default()
+        };
 
         assert_eq!(metrics.p50_latency(), Duration::from_micros(5000)); // 50th
         assert_eq!(metrics.p95_latency(), Duration::from_micros(9500)); // 95th
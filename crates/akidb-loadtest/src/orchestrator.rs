@@ -1,11 +1,10 @@
 //! Load test orchestrator for running scenarios
 
-use super::client::LoadTestClient;
-use super::metrics::{LoadTestMetrics, MetricsCollector};
-use super::profiles::{LoadProfile, WorkloadMix};
-use super::reporter::ResultWriter;
-use super::SuccessCriteria;
-use akidb_core::ids::CollectionId;
+use crate::client::{choose_operation, LoadTestClient, SimulatedClient};
+use crate::metrics::{LoadTestMetrics, MetricsCollector};
+use crate::profiles::{LoadProfile, WorkloadMix};
+use crate::reporter::ResultWriter;
+use crate::SuccessCriteria;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
@@ -57,14 +56,22 @@ impl Default for ScenarioConfig {
 /// Load test orchestrator
 pub struct LoadTestOrchestrator {
     config: ScenarioConfig,
+    client: Arc<dyn LoadTestClient>,
     collector: Arc<RwLock<MetricsCollector>>,
 }
 
 impl LoadTestOrchestrator {
-    /// Create new orchestrator
+    /// Create new orchestrator running against a simulated client
     pub fn new(config: ScenarioConfig) -> Self {
+        let client = Arc::new(SimulatedClient::new(config.dimension));
+        Self::with_client(config, client)
+    }
+
+    /// Create new orchestrator running against `client`
+    pub fn with_client(config: ScenarioConfig, client: Arc<dyn LoadTestClient>) -> Self {
         Self {
             config,
+            client,
             collector: Arc::new(RwLock::new(MetricsCollector::new())),
         }
     }
@@ -84,9 +91,12 @@ impl LoadTestOrchestrator {
             .validate()
             .map_err(|e| format!("Invalid workload mix: {}", e))?;
 
-        // Create collection for testing
-        let collection_id = CollectionId::new();
-        let client = Arc::new(LoadTestClient::new(collection_id, self.config.dimension));
+        // Prepare the target, then measure from a fresh collector
+        self.client
+            .setup(self.config.dataset_size)
+            .await
+            .map_err(|e| format!("Setup failed: {}", e))?;
+        *self.collector.write().await = MetricsCollector::new();
 
         // Spawn system metrics collector
         let collector_clone = Arc::clone(&self.collector);
@@ -98,11 +108,16 @@ impl LoadTestOrchestrator {
         });
 
         // Run load generation
-        self.run_load_generation(client).await?;
+        self.run_load_generation(Arc::clone(&self.client)).await?;
 
         // Wait for metrics collection to finish
         metrics_handle.await.map_err(|e| format!("Metrics collection failed: {}", e))?;
 
+        self.client
+            .teardown()
+            .await
+            .map_err(|e| format!("Teardown failed: {}", e))?;
+
         // Finalize and return metrics
         let metrics = {
             let collector = self.collector.read().await;
@@ -122,7 +137,7 @@ impl LoadTestOrchestrator {
     }
 
     /// Run load generation loop
-    async fn run_load_generation(&self, client: Arc<LoadTestClient>) -> Result<(), String> {
+    async fn run_load_generation(&self, client: Arc<dyn LoadTestClient>) -> Result<(), String> {
         let start_time = tokio::time::Instant::now();
         let end_time = start_time + self.config.duration;
 
@@ -132,8 +147,12 @@ impl LoadTestOrchestrator {
         let report_interval = Duration::from_secs(10);
         let mut last_report_time = std::time::Duration::ZERO;
 
-        while tokio::time::Instant::now() < end_time {
+        loop {
             ticker.tick().await;
+            // The tick due at the end of the run starts no more operations
+            if tokio::time::Instant::now() >= end_time {
+                break;
+            }
 
             let elapsed = start_time.elapsed();
 
@@ -180,12 +199,12 @@ impl LoadTestOrchestrator {
 
     /// Execute a single operation
     async fn execute_operation(
-        client: Arc<LoadTestClient>,
+        client: Arc<dyn LoadTestClient>,
         collector: Arc<RwLock<MetricsCollector>>,
         workload_mix: WorkloadMix,
     ) {
         // Choose operation type based on workload mix
-        let op_type = LoadTestClient::choose_operation(
+        let op_type = choose_operation(
             workload_mix.search_pct,
             workload_mix.insert_pct,
             workload_mix.update_pct,
@@ -281,7 +300,8 @@ mod tests {
         let config = ScenarioConfig {
             name: "Test with Criteria".to_string(),
             duration: Duration::from_secs(3),
-            load_profile: LoadProfile::Constant { qps: 10 },
+            // Above the 10 QPS development throughput floor
+            load_profile: LoadProfile::Constant { qps: 20 },
             ..Default::default()
         };
 
@@ -1,9 +1,15 @@
 //! Load profiles and workload configuration
 
+use crate::scenario::deserialize_secs;
+use serde::Deserialize;
 use std::time::Duration;
 
 /// Load profile defines how QPS changes over time
-#[derive(Debug, Clone)]
+///
+/// In scenario files the variant is named by `type` and durations are
+/// given in seconds.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum LoadProfile {
     /// Constant QPS throughout the test
     Constant {
@@ -14,6 +20,7 @@ pub enum LoadProfile {
     Ramp {
         from_qps: usize,
         to_qps: usize,
+        #[serde(deserialize_with = "deserialize_secs")]
         ramp_duration: Duration,
     },
 
@@ -21,7 +28,9 @@ pub enum LoadProfile {
     Spike {
         baseline_qps: usize,
         spike_qps: usize,
+        #[serde(deserialize_with = "deserialize_secs")]
         spike_start: Duration,
+        #[serde(deserialize_with = "deserialize_secs")]
         spike_duration: Duration,
     },
 
@@ -29,6 +38,7 @@ pub enum LoadProfile {
     Random {
         min_qps: usize,
         max_qps: usize,
+        #[serde(deserialize_with = "deserialize_secs")]
         change_interval: Duration,
     },
 }
@@ -101,21 +111,28 @@ impl LoadProfile {
 }
 
 /// Workload mix defines the percentage of different operation types
-#[derive(Debug, Clone)]
+///
+/// Operation types omitted from a scenario file get 0%.
+#[derive(Debug, Clone, Deserialize)]
 pub struct WorkloadMix {
     /// Percentage of search operations (0.0-1.0)
+    #[serde(default)]
     pub search_pct: f32,
 
     /// Percentage of insert operations (0.0-1.0)
+    #[serde(default)]
     pub insert_pct: f32,
 
     /// Percentage of update operations (0.0-1.0)
+    #[serde(default)]
     pub update_pct: f32,
 
     /// Percentage of delete operations (0.0-1.0)
+    #[serde(default)]
     pub delete_pct: f32,
 
     /// Percentage of metadata operations (list, get info) (0.0-1.0)
+    #[serde(default)]
     pub metadata_pct: f32,
 }
 
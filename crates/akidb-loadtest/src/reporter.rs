@@ -1,7 +1,7 @@
 //! Report generation for load test results
 
-use super::metrics::LoadTestMetrics;
-use super::SuccessCriteria;
+use crate::metrics::LoadTestMetrics;
+use crate::SuccessCriteria;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
@@ -11,6 +11,18 @@ use std::path::Path;
 pub enum ReportFormat {
     Markdown,
     Json,
+    Html,
+}
+
+impl ReportFormat {
+    /// File extension of reports in this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+            Self::Html => "html",
+        }
+    }
 }
 
 /// Result writer for generating load test reports
@@ -120,6 +132,7 @@ impl ResultWriter {
         let content = match format {
             ReportFormat::Markdown => self.generate_markdown(),
             ReportFormat::Json => self.generate_json(),
+            ReportFormat::Html => self.generate_html(),
         };
 
         let mut file = File::create(path)?;
@@ -317,6 +330,128 @@ impl ResultWriter {
         })
         .to_string()
     }
+
+    /// Generate HTML report (a single self-contained page)
+    fn generate_html(&self) -> String {
+        let passes = self.passes();
+        let p95_ms = self.metrics.p95_latency().as_secs_f64() * 1000.0;
+        let p99_ms = self.metrics.p99_latency().as_secs_f64() * 1000.0;
+
+        let criteria = if passes {
+            "<p class=\"pass\">All criteria passed</p>".to_string()
+        } else {
+            let items: String = self
+                .check_criteria()
+                .iter()
+                .map(|failure| format!("<li>{}</li>", escape_html(failure)))
+                .collect();
+            format!("<ul class=\"fail\">{}</ul>", items)
+        };
+        let errors = if self.metrics.errors.is_empty() {
+            "<p>No errors recorded</p>".to_string()
+        } else {
+            format!(
+                "<p>{} errors:</p><pre>{}</pre>",
+                self.metrics.errors.len(),
+                escape_html(&self.metrics.errors.join("\n"))
+            )
+        };
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Load Test Report: {name}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; margin-bottom: 1.5em; }}
+th, td {{ border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }}
+.pass {{ color: #1a7f37; }}
+.fail {{ color: #cf222e; }}
+</style>
+</head>
+<body>
+<h1>Load Test Report: {name}</h1>
+<p>Status: <strong class="{status_class}">{status}</strong></p>
+
+<h2>Summary</h2>
+<table>
+<tr><th>Duration</th><td>{duration:.1} s</td></tr>
+<tr><th>Total Requests</th><td>{total}</td></tr>
+<tr><th>Successful</th><td>{successful}</td></tr>
+<tr><th>Failed</th><td>{failed}</td></tr>
+<tr><th>Error Rate</th><td>{error_rate:.4}%</td></tr>
+<tr><th>Throughput</th><td>{throughput:.1} QPS</td></tr>
+</table>
+
+<h2>Latency</h2>
+<table>
+<tr><th>Percentile</th><th>Latency</th><th>Target</th></tr>
+<tr><td>P50</td><td>{p50:.2}ms</td><td>-</td></tr>
+<tr><td>P90</td><td>{p90:.2}ms</td><td>-</td></tr>
+<tr><td>P95</td><td>{p95:.2}ms</td><td>&lt;{p95_target:.2}ms</td></tr>
+<tr><td>P99</td><td>{p99:.2}ms</td><td>{p99_target}</td></tr>
+<tr><td>Max</td><td>{max:.2}ms</td><td>-</td></tr>
+</table>
+
+<h2>Resource Utilization</h2>
+<table>
+<tr><th>Memory (average)</th><td>{memory_avg:.1} MB</td></tr>
+<tr><th>Memory (peak)</th><td>{memory_peak:.1} MB</td></tr>
+<tr><th>Memory growth</th><td>{memory_growth:.2} MB/min</td></tr>
+<tr><th>CPU (average)</th><td>{cpu_avg:.1}%</td></tr>
+<tr><th>CPU (peak)</th><td>{cpu_peak:.1}%</td></tr>
+</table>
+
+<h2>Success Criteria</h2>
+{criteria}
+
+<h2>Errors</h2>
+{errors}
+
+<p><small>Report Generated: {generated}</small></p>
+</body>
+</html>
+"#,
+            name = escape_html(&self.scenario_name),
+            status_class = if passes { "pass" } else { "fail" },
+            status = if passes { "PASSED" } else { "FAILED" },
+            duration = self.metrics.duration().as_secs_f64(),
+            total = self.metrics.total_requests,
+            successful = self.metrics.successful_requests,
+            failed = self.metrics.failed_requests,
+            error_rate = self.metrics.error_rate() * 100.0,
+            throughput = self.metrics.throughput_qps(),
+            p50 = self.metrics.p50_latency().as_secs_f64() * 1000.0,
+            p90 = self.metrics.p90_latency().as_secs_f64() * 1000.0,
+            p95 = p95_ms,
+            p95_target = self.success_criteria.max_p95_latency_ms,
+            p99 = p99_ms,
+            p99_target = self
+                .success_criteria
+                .max_p99_latency_ms
+                .map(|m| format!("&lt;{:.2}ms", m))
+                .unwrap_or_else(|| "-".to_string()),
+            max = self.metrics.max_latency().as_secs_f64() * 1000.0,
+            memory_avg = self.metrics.avg_memory_mb(),
+            memory_peak = self.metrics.peak_memory_mb(),
+            memory_growth = self.metrics.memory_growth_mb_per_min(),
+            cpu_avg = self.metrics.avg_cpu_utilization() * 100.0,
+            cpu_peak = self.metrics.peak_cpu_utilization() * 100.0,
+            criteria = criteria,
+            errors = errors,
+            generated = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        )
+    }
+}
+
+/// Escape text for inclusion in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 #[cfg(test)]
@@ -363,4 +498,26 @@ mod tests {
         assert!(writer.failure_summary().contains("Error rate"));
         assert!(writer.failure_summary().contains("P95 latency"));
     }
+
+    #[test]
+    fn test_html_report() {
+        let start = Instant::now();
+        let metrics = LoadTestMetrics {
+            start_time: start,
+            end_time: start + std::time::Duration::from_secs(10),
+            total_requests: 100,
+            successful_requests: 99,
+            failed_requests: 1,
+            latencies_us: vec![10000; 100],
+            errors: vec!["HTTP 500: <internal>".to_string()],
+            ..Default::default()
+        };
+
+        let writer = ResultWriter::new("A & B".to_string(), metrics, SuccessCriteria::default());
+        let html = writer.generate_html();
+
+        assert!(html.contains("<title>Load Test Report: A &amp; B</title>"));
+        assert!(html.contains("FAILED"));
+        assert!(html.contains("HTTP 500: &lt;internal&gt;"));
+    }
 }
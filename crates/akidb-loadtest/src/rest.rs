@@ -0,0 +1,209 @@
+//! Load test client for the REST API
+
+use crate::client::{random_vector, DocumentPool, LoadTestClient, OperationType};
+use async_trait::async_trait;
+use reqwest::{Method, RequestBuilder, Response};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Documents per `insert_batch` request when loading the dataset
+const PRELOAD_BATCH_SIZE: usize = 500;
+
+/// Nearest neighbours requested by each search
+const SEARCH_TOP_K: usize = 10;
+
+#[derive(Deserialize)]
+struct CreateCollectionResponse {
+    collection_id: String,
+}
+
+/// Client driving an `akidb-rest` server
+pub struct RestClient {
+    http: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+    dimension: usize,
+    collection_id: OnceLock<String>,
+    // Whether setup created the collection (and teardown drops it)
+    created: AtomicBool,
+    docs: DocumentPool,
+}
+
+impl RestClient {
+    /// Client for the server at `endpoint` (e.g. `http://localhost:8080`)
+    ///
+    /// Runs against `collection` when given; otherwise setup creates a
+    /// collection that teardown deletes.
+    pub fn new(
+        endpoint: &str,
+        api_key: Option<String>,
+        collection: Option<String>,
+        dimension: usize,
+    ) -> Self {
+        let collection_id = OnceLock::new();
+        if let Some(collection) = collection {
+            let _ = collection_id.set(collection);
+        }
+        Self {
+            http: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key,
+            dimension,
+            collection_id,
+            created: AtomicBool::new(false),
+            docs: DocumentPool::default(),
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let builder = self
+            .http
+            .request(method, format!("{}{}", self.endpoint, path));
+        match &self.api_key {
+            Some(key) => builder.header("x-api-key", key),
+            None => builder,
+        }
+    }
+
+    fn collection_path(&self, suffix: &str) -> Result<String, String> {
+        let collection_id = self
+            .collection_id
+            .get()
+            .ok_or_else(|| "Collection not set up".to_string())?;
+        Ok(format!("/api/v1/collections/{}{}", collection_id, suffix))
+    }
+
+    /// Send `builder`, turning transport errors and error statuses into errors
+    async fn send(builder: RequestBuilder) -> Result<Response, String> {
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(format!("HTTP {}: {}", status.as_u16(), body))
+    }
+
+    async fn insert(&self) -> Result<(), String> {
+        let doc_id = Uuid::now_v7().to_string();
+        let body = json!({
+            "doc_id": doc_id,
+            "external_id": doc_id,
+            "vector": random_vector(self.dimension),
+        });
+        Self::send(
+            self.request(Method::POST, &self.collection_path("/insert")?)
+                .json(&body),
+        )
+        .await?;
+        self.docs.add(doc_id);
+        Ok(())
+    }
+
+    async fn preload(&self, dataset_size: usize) -> Result<(), String> {
+        let path = self.collection_path("/insert_batch")?;
+        let mut remaining = dataset_size;
+        while remaining > 0 {
+            let batch = remaining.min(PRELOAD_BATCH_SIZE);
+            let doc_ids: Vec<String> = (0..batch).map(|_| Uuid::now_v7().to_string()).collect();
+            let documents: Vec<Value> = doc_ids
+                .iter()
+                .map(|doc_id| {
+                    json!({
+                        "doc_id": doc_id,
+                        "external_id": doc_id,
+                        "vector": random_vector(self.dimension),
+                    })
+                })
+                .collect();
+            Self::send(
+                self.request(Method::POST, &path)
+                    .json(&json!({ "documents": documents })),
+            )
+            .await?;
+            for doc_id in doc_ids {
+                self.docs.add(doc_id);
+            }
+            remaining -= batch;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LoadTestClient for RestClient {
+    async fn setup(&self, dataset_size: usize) -> Result<(), String> {
+        if self.collection_id.get().is_none() {
+            let body = json!({
+                "name": format!("loadtest-{}", Uuid::now_v7().simple()),
+                "dimension": self.dimension,
+                "metric": "cosine",
+            });
+            let created: CreateCollectionResponse = Self::send(
+                self.request(Method::POST, "/api/v1/collections")
+                    .json(&body),
+            )
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid create collection response: {}", e))?;
+            let _ = self.collection_id.set(created.collection_id);
+            self.created.store(true, Ordering::SeqCst);
+        }
+        self.preload(dataset_size).await
+    }
+
+    async fn perform(&self, op_type: OperationType) -> Result<(), String> {
+        match op_type {
+            OperationType::Search => {
+                let body = json!({
+                    "query_vector": random_vector(self.dimension),
+                    "top_k": SEARCH_TOP_K,
+                });
+                Self::send(
+                    self.request(Method::POST, &self.collection_path("/query")?)
+                        .json(&body),
+                )
+                .await?;
+            }
+            OperationType::Insert => self.insert().await?,
+            OperationType::Update => {
+                // Nothing to update yet: store a new document instead
+                let Some(external_id) = self.docs.pick() else {
+                    return self.insert().await;
+                };
+                let path =
+                    self.collection_path(&format!("/docs/by-external-id/{}", external_id))?;
+                Self::send(
+                    self.request(Method::PUT, &path)
+                        .json(&json!({ "vector": random_vector(self.dimension) })),
+                )
+                .await?;
+            }
+            OperationType::Delete => {
+                let Some(doc_id) = self.docs.take() else {
+                    return self.insert().await;
+                };
+                let path = self.collection_path(&format!("/docs/{}", doc_id))?;
+                Self::send(self.request(Method::DELETE, &path)).await?;
+            }
+            OperationType::Metadata => {
+                Self::send(self.request(Method::GET, &self.collection_path("")?)).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn teardown(&self) -> Result<(), String> {
+        if self.created.load(Ordering::SeqCst) {
+            Self::send(self.request(Method::DELETE, &self.collection_path("")?)).await?;
+        }
+        Ok(())
+    }
+}
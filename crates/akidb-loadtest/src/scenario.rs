@@ -0,0 +1,286 @@
+//! YAML scenario files
+//!
+//! A scenario names the target, the load profile, the workload mix and the
+//! success criteria of one run. Durations are in seconds:
+//!
+//! ```yaml
+//! name: Baseline
+//! duration: 300
+//! target:
+//!   protocol: rest            # rest, grpc or simulated (default)
+//!   endpoint: http://localhost:8080
+//! profile:
+//!   type: constant
+//!   qps: 100
+//! workload:
+//!   search_pct: 0.7
+//!   insert_pct: 0.2
+//!   metadata_pct: 0.1
+//! criteria:
+//!   max_p95_latency_ms: 25
+//! ```
+
+use crate::client::{LoadTestClient, SimulatedClient};
+use crate::grpc::GrpcClient;
+use crate::orchestrator::ScenarioConfig;
+use crate::profiles::{LoadProfile, WorkloadMix};
+use crate::rest::RestClient;
+use crate::SuccessCriteria;
+use serde::{Deserialize, Deserializer};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Environment variable holding the API key when the target sets none
+pub const API_KEY_ENV: &str = "AKIDB_API_KEY";
+
+/// Server (or simulation) a scenario runs against
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "protocol", rename_all = "snake_case")]
+pub enum TargetConfig {
+    /// In-process client simulating operation latencies
+    #[default]
+    Simulated,
+
+    /// `akidb-rest` server
+    Rest {
+        /// Base URL, e.g. `http://localhost:8080`
+        endpoint: String,
+        /// API key (falls back to `AKIDB_API_KEY`)
+        #[serde(default)]
+        api_key: Option<String>,
+        /// Existing collection to use (a temporary one is created otherwise)
+        #[serde(default)]
+        collection: Option<String>,
+    },
+
+    /// `akidb-grpc` server
+    Grpc {
+        /// Server URI, e.g. `http://localhost:9090`
+        endpoint: String,
+        /// API key (falls back to `AKIDB_API_KEY`)
+        #[serde(default)]
+        api_key: Option<String>,
+        /// Existing collection to use (a temporary one is created otherwise)
+        #[serde(default)]
+        collection: Option<String>,
+    },
+}
+
+/// Load test scenario read from a YAML file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Scenario name
+    pub name: String,
+
+    /// Test duration
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub duration: Duration,
+
+    /// Target to run against
+    #[serde(default)]
+    pub target: TargetConfig,
+
+    /// Load profile (how QPS changes over time)
+    pub profile: LoadProfile,
+
+    /// Workload mix (operation type percentages)
+    #[serde(default)]
+    pub workload: WorkloadMix,
+
+    /// Vectors loaded before the run
+    #[serde(default = "default_dataset_size")]
+    pub dataset_size: usize,
+
+    /// Vector dimension
+    #[serde(default = "default_dimension")]
+    pub dimension: usize,
+
+    /// Number of concurrent clients
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+
+    /// Sample interval for system metrics
+    #[serde(
+        default = "default_sample_interval",
+        deserialize_with = "deserialize_secs"
+    )]
+    pub sample_interval: Duration,
+
+    /// Pass/fail thresholds
+    #[serde(default)]
+    pub criteria: SuccessCriteria,
+}
+
+fn default_dataset_size() -> usize {
+    ScenarioConfig::default().dataset_size
+}
+
+fn default_dimension() -> usize {
+    ScenarioConfig::default().dimension
+}
+
+fn default_concurrency() -> usize {
+    ScenarioConfig::default().concurrency
+}
+
+fn default_sample_interval() -> Duration {
+    ScenarioConfig::default().sample_interval
+}
+
+/// Deserialize a duration given in (whole) seconds
+pub(crate) fn deserialize_secs<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    u64::deserialize(deserializer).map(Duration::from_secs)
+}
+
+impl Scenario {
+    /// Parse and validate a scenario
+    pub fn from_yaml(yaml: &str) -> Result<Self, String> {
+        let scenario: Self =
+            serde_yaml::from_str(yaml).map_err(|e| format!("Invalid scenario: {}", e))?;
+        scenario
+            .workload
+            .validate()
+            .map_err(|e| format!("Invalid workload mix: {}", e))?;
+        Ok(scenario)
+    }
+
+    /// Read a scenario file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let yaml = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// Orchestrator configuration of this scenario
+    pub fn config(&self) -> ScenarioConfig {
+        ScenarioConfig {
+            name: self.name.clone(),
+            duration: self.duration,
+            load_profile: self.profile.clone(),
+            workload_mix: self.workload.clone(),
+            dataset_size: self.dataset_size,
+            dimension: self.dimension,
+            concurrency: self.concurrency,
+            sample_interval: self.sample_interval,
+        }
+    }
+
+    /// Client for the scenario's target
+    pub fn client(&self) -> Result<Arc<dyn LoadTestClient>, String> {
+        let api_key =
+            |key: &Option<String>| key.clone().or_else(|| std::env::var(API_KEY_ENV).ok());
+        Ok(match &self.target {
+            TargetConfig::Simulated => Arc::new(SimulatedClient::new(self.dimension)),
+            TargetConfig::Rest {
+                endpoint,
+                api_key: key,
+                collection,
+            } => Arc::new(RestClient::new(
+                endpoint,
+                api_key(key),
+                collection.clone(),
+                self.dimension,
+            )),
+            TargetConfig::Grpc {
+                endpoint,
+                api_key: key,
+                collection,
+            } => Arc::new(GrpcClient::new(
+                endpoint,
+                api_key(key),
+                collection.clone(),
+                self.dimension,
+            )?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scenario() {
+        let scenario = Scenario::from_yaml(
+            r#"
+name: Spike
+duration: 120
+target:
+  protocol: grpc
+  endpoint: http://localhost:9090
+  collection: docs
+profile:
+  type: spike
+  baseline_qps: 100
+  spike_qps: 500
+  spike_start: 30
+  spike_duration: 10
+workload:
+  search_pct: 0.9
+  insert_pct: 0.1
+dimension: 128
+criteria:
+  max_p95_latency_ms: 100
+  min_throughput_qps: null
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(scenario.duration, Duration::from_secs(120));
+        assert!(matches!(
+            scenario.target,
+            TargetConfig::Grpc { ref collection, .. } if collection.as_deref() == Some("docs")
+        ));
+        assert_eq!(scenario.profile.qps_at(Duration::from_secs(35)), 500);
+        assert_eq!(scenario.workload.update_pct, 0.0);
+        assert_eq!(scenario.dimension, 128);
+        assert_eq!(scenario.dataset_size, 10_000);
+        assert_eq!(scenario.criteria.max_p95_latency_ms, 100.0);
+        assert_eq!(scenario.criteria.min_throughput_qps, None);
+        // Unset criteria keep their default
+        assert_eq!(scenario.criteria.max_error_rate, 0.001);
+    }
+
+    #[test]
+    fn test_defaults_to_simulated_target() {
+        let scenario = Scenario::from_yaml(
+            "name: Smoke\nduration: 10\nprofile:\n  type: constant\n  qps: 10\n",
+        )
+        .unwrap();
+
+        assert!(matches!(scenario.target, TargetConfig::Simulated));
+        assert!(scenario.workload.validate().is_ok());
+    }
+
+    #[test]
+    fn test_rejects_invalid_scenarios() {
+        // Workload mix must sum to 1.0
+        let error = Scenario::from_yaml(
+            "name: Bad\nduration: 10\nprofile:\n  type: constant\n  qps: 10\nworkload:\n  search_pct: 0.5\n",
+        )
+        .unwrap_err();
+        assert!(error.contains("workload"));
+
+        // Unknown protocol
+        assert!(Scenario::from_yaml(
+            "name: Bad\nduration: 10\ntarget:\n  protocol: ftp\nprofile:\n  type: constant\n  qps: 10\n",
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_bundled_scenarios_parse() {
+        for yaml in [
+            include_str!("../scenarios/baseline.yaml"),
+            include_str!("../scenarios/spike.yaml"),
+        ] {
+            Scenario::from_yaml(yaml).unwrap();
+        }
+    }
+}
@@ -10,9 +10,7 @@
 //! 7. Failure Injection (20 min) - TODO
 //! 8. Mixed Workload Chaos (30 min) - TODO
 
-mod load_test_framework;
-
-use load_test_framework::{
+use akidb_loadtest::{
     LoadProfile, LoadTestOrchestrator, ReportFormat, ScenarioConfig, SuccessCriteria, WorkloadMix,
 };
 use std::time::Duration;
@@ -726,14 +724,15 @@ async fn run_scenario(config: ScenarioConfig, criteria: SuccessCriteria, report_
 /// Smoke test: Ultra-quick load test for CI
 ///
 /// Duration: 30 seconds
-/// QPS: 10
+/// QPS: 20
 /// Purpose: Fast validation that load test framework works
 #[tokio::test]
 async fn smoke_test_load_framework() {
     let config = ScenarioConfig {
         name: "Smoke Test".to_string(),
         duration: Duration::from_secs(30),
-        load_profile: LoadProfile::Constant { qps: 10 },
+        // Above the 10 QPS development throughput floor
+        load_profile: LoadProfile::Constant { qps: 20 },
         workload_mix: WorkloadMix::default(),
         dataset_size: 1_000,
         dimension: 128,
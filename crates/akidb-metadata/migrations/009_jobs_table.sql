@@ -0,0 +1,22 @@
+-- Migration: Background jobs for long-running operations
+--
+-- Jobs (bulk delete, reindex, export, restore, ...) run in-process; this
+-- table lets clients poll status/progress and keeps the outcome after the
+-- server restarts. collection_id is intentionally not a foreign key so job
+-- history survives collection deletion.
+
+CREATE TABLE IF NOT EXISTS jobs (
+    job_id BLOB PRIMARY KEY,
+    kind TEXT NOT NULL,
+    collection_id BLOB,
+    status TEXT NOT NULL CHECK(status IN ('pending','running','succeeded','failed')),
+    progress INTEGER NOT NULL DEFAULT 0,
+    total INTEGER,
+    error TEXT,
+    result TEXT,  -- JSON
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+) STRICT;
+
+CREATE INDEX ix_jobs_status ON jobs(status);
+CREATE INDEX ix_jobs_collection_created ON jobs(collection_id, created_at DESC);
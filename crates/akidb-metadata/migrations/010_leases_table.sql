@@ -0,0 +1,15 @@
+-- Migration: Leases for coordinating server processes
+--
+-- A lease gives one process (holder) exclusive use of a named resource, such
+-- as the leader role for background maintenance, until expires_at_ms. Times
+-- are Unix milliseconds so expiry can be compared inside the acquiring
+-- UPDATE. Released leases keep their row (with an expired time) so that
+-- token keeps increasing across holders.
+
+CREATE TABLE IF NOT EXISTS leases (
+    name TEXT PRIMARY KEY,
+    holder TEXT NOT NULL,
+    token INTEGER NOT NULL,
+    acquired_at_ms INTEGER NOT NULL,
+    expires_at_ms INTEGER NOT NULL
+) STRICT;
@@ -0,0 +1,19 @@
+-- Migration: Hourly per-tenant usage rollups for metering and billing
+--
+-- Each server process accumulates usage in memory and periodically adds it
+-- to the row for (tenant, hour), so rows from several processes sum up.
+-- hour_ms is the start of the hour in Unix milliseconds. stored_bytes is the
+-- largest sample seen during the hour rather than a sum.
+
+CREATE TABLE IF NOT EXISTS usage_rollups (
+    tenant_id BLOB NOT NULL REFERENCES tenants(tenant_id) ON DELETE CASCADE,
+    hour_ms INTEGER NOT NULL,
+    queries INTEGER NOT NULL DEFAULT 0,
+    vectors_inserted INTEGER NOT NULL DEFAULT 0,
+    stored_bytes INTEGER NOT NULL DEFAULT 0,
+    embedding_tokens INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (tenant_id, hour_ms)
+) STRICT;
+
+-- Index for exporting all tenants over a time range
+CREATE INDEX ix_usage_rollups_hour ON usage_rollups(hour_ms);
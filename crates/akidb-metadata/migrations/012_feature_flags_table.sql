@@ -0,0 +1,15 @@
+-- Migration: Runtime feature flags
+--
+-- Flags are read by every server process sharing the metadata database and
+-- changed through the admin API. tenant_overrides is a JSON object mapping
+-- tenant UUIDs to true/false; overrides take precedence over enabled and
+-- rollout_percentage. updated_at_ms is Unix milliseconds.
+
+CREATE TABLE IF NOT EXISTS feature_flags (
+    name TEXT PRIMARY KEY,
+    description TEXT,
+    enabled INTEGER NOT NULL CHECK(enabled IN (0, 1)),
+    rollout_percentage INTEGER NOT NULL DEFAULT 100 CHECK(rollout_percentage BETWEEN 0 AND 100),
+    tenant_overrides TEXT NOT NULL DEFAULT '{}',
+    updated_at_ms INTEGER NOT NULL
+) STRICT;
@@ -0,0 +1,6 @@
+-- Migration: Per-key collection allowlists
+--
+-- allowed_collections is a JSON array of collection UUIDs the key may
+-- access; NULL means every collection visible to the key's tenant.
+
+ALTER TABLE api_keys ADD COLUMN allowed_collections TEXT;
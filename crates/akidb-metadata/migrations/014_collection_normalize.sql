@@ -0,0 +1,7 @@
+-- Migration: Per-collection vector normalization
+--
+-- Collections with normalize = 1 L2-normalize vectors at insert and query
+-- time. The service only sets it when a collection is created, since stored
+-- vectors are already normalized (or not).
+
+ALTER TABLE collections ADD COLUMN normalize INTEGER NOT NULL DEFAULT 0 CHECK(normalize IN (0, 1));
@@ -0,0 +1,6 @@
+-- Migration: Per-collection vector quantization
+--
+-- Collections store index vectors as full-precision f32 ('none'), int8 codes
+-- with a per-vector scale, or fp16. The setting is fixed at creation time.
+
+ALTER TABLE collections ADD COLUMN quantization TEXT NOT NULL DEFAULT 'none' CHECK(quantization IN ('none', 'int8', 'fp16'));
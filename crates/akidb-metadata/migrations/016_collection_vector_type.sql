@@ -0,0 +1,60 @@
+-- Migration: Dense, sparse, or hybrid collections
+--
+-- vector_type records whether documents carry a dense vector ('dense'), a
+-- sparse vector ('sparse'), or a dense vector plus an optional sparse vector
+-- ('both'). Sparse-only collections have no dense dimension and store 0, so
+-- the dimension CHECK from 002 is relaxed.
+--
+-- SQLite cannot alter a CHECK constraint, so the table is rebuilt. Dropping
+-- the old table cascades to the tables referencing it, whose rows are set
+-- aside first and put back once the new table is in place.
+
+CREATE TEMP TABLE vector_documents_backup AS SELECT * FROM vector_documents;
+CREATE TEMP TABLE collection_tier_state_backup AS SELECT * FROM collection_tier_state;
+
+CREATE TABLE collections_new (
+    collection_id BLOB PRIMARY KEY,
+    database_id BLOB NOT NULL REFERENCES databases(database_id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    dimension INTEGER NOT NULL CHECK(dimension = 0 OR dimension BETWEEN 16 AND 4096),
+    metric TEXT NOT NULL CHECK(metric IN ('cosine','dot','l2')),
+    embedding_model TEXT NOT NULL,
+    hnsw_m INTEGER NOT NULL DEFAULT 32,
+    hnsw_ef_construction INTEGER NOT NULL DEFAULT 200,
+    max_doc_count INTEGER NOT NULL DEFAULT 50000000,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+    normalize INTEGER NOT NULL DEFAULT 0 CHECK(normalize IN (0, 1)),
+    quantization TEXT NOT NULL DEFAULT 'none' CHECK(quantization IN ('none', 'int8', 'fp16')),
+    vector_type TEXT NOT NULL DEFAULT 'dense' CHECK(vector_type IN ('dense', 'sparse', 'both')),
+    UNIQUE(database_id, name),
+    CHECK((vector_type = 'sparse') = (dimension = 0))
+) STRICT;
+
+INSERT INTO collections_new (
+    collection_id, database_id, name, dimension, metric, embedding_model,
+    hnsw_m, hnsw_ef_construction, max_doc_count, created_at, updated_at,
+    normalize, quantization
+)
+SELECT collection_id, database_id, name, dimension, metric, embedding_model,
+       hnsw_m, hnsw_ef_construction, max_doc_count, created_at, updated_at,
+       normalize, quantization
+  FROM collections;
+
+DROP TABLE collections;
+ALTER TABLE collections_new RENAME TO collections;
+
+INSERT INTO vector_documents SELECT * FROM vector_documents_backup;
+INSERT INTO collection_tier_state SELECT * FROM collection_tier_state_backup;
+DROP TABLE vector_documents_backup;
+DROP TABLE collection_tier_state_backup;
+
+CREATE TRIGGER IF NOT EXISTS trg_collections_updated_at
+AFTER UPDATE ON collections
+FOR EACH ROW
+WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE collections
+       SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now')
+     WHERE rowid = NEW.rowid;
+END;
@@ -0,0 +1,18 @@
+-- Migration: Collection aliases
+--
+-- An alias is a name, unique within a database, that resolves to one
+-- collection of that database. Repointing an alias is a single UPDATE, so
+-- clients querying it switch collections atomically (blue/green reindex).
+-- The service refuses to delete a collection an alias points to; the
+-- cascade only covers deleting the whole database.
+
+CREATE TABLE IF NOT EXISTS collection_aliases (
+    database_id BLOB NOT NULL REFERENCES databases(database_id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    collection_id BLOB NOT NULL REFERENCES collections(collection_id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (database_id, name)
+) STRICT;
+
+CREATE INDEX ix_collection_aliases_collection ON collection_aliases(collection_id);
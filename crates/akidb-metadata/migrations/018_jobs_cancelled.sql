@@ -0,0 +1,27 @@
+-- Migration: Cancelled jobs
+--
+-- Jobs can be cancelled while they run. SQLite cannot alter a CHECK
+-- constraint, so the table is rebuilt with 'cancelled' as a valid status.
+-- No other table references jobs.
+
+CREATE TABLE jobs_new (
+    job_id BLOB PRIMARY KEY,
+    kind TEXT NOT NULL,
+    collection_id BLOB,
+    status TEXT NOT NULL CHECK(status IN ('pending','running','succeeded','failed','cancelled')),
+    progress INTEGER NOT NULL DEFAULT 0,
+    total INTEGER,
+    error TEXT,
+    result TEXT,  -- JSON
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+) STRICT;
+
+INSERT INTO jobs_new SELECT * FROM jobs;
+
+DROP TABLE jobs;
+ALTER TABLE jobs_new RENAME TO jobs;
+
+CREATE INDEX ix_jobs_status ON jobs(status);
+CREATE INDEX ix_jobs_collection_created ON jobs(collection_id, created_at DESC);
+CREATE INDEX ix_jobs_created ON jobs(created_at DESC);
@@ -0,0 +1,117 @@
+//! SQLite implementation of the collection alias repository.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{query, Row, SqlitePool};
+
+use akidb_core::{
+    AliasRepository, CollectionAlias, CollectionId, CoreError, CoreResult, DatabaseId,
+};
+
+/// SQLite implementation of the alias repository.
+pub struct SqliteAliasRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteAliasRepository {
+    /// Creates a new SQLite alias repository.
+    #[must_use]
+    pub const fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AliasRepository for SqliteAliasRepository {
+    async fn list(&self, database_id: DatabaseId) -> CoreResult<Vec<CollectionAlias>> {
+        let rows = query(
+            "SELECT database_id, name, collection_id, created_at, updated_at
+             FROM collection_aliases
+             WHERE database_id = ?1
+             ORDER BY name",
+        )
+        .bind(database_id.to_bytes().to_vec())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        rows.iter().map(parse_alias_row).collect()
+    }
+
+    async fn get(
+        &self,
+        database_id: DatabaseId,
+        name: &str,
+    ) -> CoreResult<Option<CollectionAlias>> {
+        let row = query(
+            "SELECT database_id, name, collection_id, created_at, updated_at
+             FROM collection_aliases
+             WHERE database_id = ?1 AND name = ?2",
+        )
+        .bind(database_id.to_bytes().to_vec())
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        row.as_ref().map(parse_alias_row).transpose()
+    }
+
+    async fn upsert(&self, alias: &CollectionAlias) -> CoreResult<()> {
+        query(
+            "INSERT INTO collection_aliases (database_id, name, collection_id, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(database_id, name) DO UPDATE SET
+                 collection_id = excluded.collection_id,
+                 updated_at = excluded.updated_at",
+        )
+        .bind(alias.database_id.to_bytes().to_vec())
+        .bind(&alias.name)
+        .bind(alias.collection_id.to_bytes().to_vec())
+        .bind(alias.created_at.to_rfc3339())
+        .bind(alias.updated_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, database_id: DatabaseId, name: &str) -> CoreResult<bool> {
+        let result = query("DELETE FROM collection_aliases WHERE database_id = ?1 AND name = ?2")
+            .bind(database_id.to_bytes().to_vec())
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Parse an alias row from SQLite.
+fn parse_alias_row(row: &sqlx::sqlite::SqliteRow) -> CoreResult<CollectionAlias> {
+    let internal = |e: sqlx::Error| CoreError::internal(e.to_string());
+
+    let database_id_bytes: Vec<u8> = row.try_get("database_id").map_err(internal)?;
+    let name: String = row.try_get("name").map_err(internal)?;
+    let collection_id_bytes: Vec<u8> = row.try_get("collection_id").map_err(internal)?;
+    let created_at_str: String = row.try_get("created_at").map_err(internal)?;
+    let updated_at_str: String = row.try_get("updated_at").map_err(internal)?;
+
+    let parse_time = |s: &str| {
+        DateTime::parse_from_rfc3339(s)
+            .map(|t| t.with_timezone(&Utc))
+            .map_err(|e| CoreError::internal(e.to_string()))
+    };
+
+    Ok(CollectionAlias {
+        database_id: DatabaseId::from_bytes(&database_id_bytes)
+            .map_err(|e| CoreError::internal(e.to_string()))?,
+        name,
+        collection_id: CollectionId::from_bytes(&collection_id_bytes)
+            .map_err(|e| CoreError::internal(e.to_string()))?,
+        created_at: parse_time(&created_at_str)?,
+        updated_at: parse_time(&updated_at_str)?,
+    })
+}
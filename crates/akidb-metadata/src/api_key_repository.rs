@@ -5,7 +5,8 @@ use chrono::{DateTime, Utc};
 use sqlx::{query, Executor, Row, Sqlite, SqlitePool};
 
 use akidb_core::{
-    ApiKeyDescriptor, ApiKeyId, ApiKeyRepository, CoreError, CoreResult, TenantId, UserId,
+    ApiKeyDescriptor, ApiKeyId, ApiKeyRepository, CollectionId, CoreError, CoreResult, TenantId,
+    UserId,
 };
 
 /// SQLite implementation of the API key repository.
@@ -39,10 +40,18 @@ impl SqliteApiKeyRepository {
         let expires_at = api_key.expires_at.map(|t| t.to_rfc3339());
         let last_used_at = api_key.last_used_at.map(|t| t.to_rfc3339());
         let created_by = api_key.created_by.map(|id| id.to_bytes().to_vec());
+        let allowed_collections = api_key
+            .allowed_collections
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| {
+                CoreError::internal(format!("Failed to serialize allowed_collections: {e}"))
+            })?;
 
         query(
-            "INSERT INTO api_keys (key_id, tenant_id, key_hash, name, permissions, created_at, expires_at, last_used_at, created_by)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+            "INSERT INTO api_keys (key_id, tenant_id, key_hash, name, permissions, created_at, expires_at, last_used_at, created_by, allowed_collections)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
         )
         .bind(key_id)
         .bind(tenant_id)
@@ -53,6 +62,7 @@ impl SqliteApiKeyRepository {
         .bind(expires_at)
         .bind(last_used_at)
         .bind(created_by)
+        .bind(allowed_collections)
         .execute(executor)
         .await
         .map_err(|e| {
@@ -80,7 +90,7 @@ impl SqliteApiKeyRepository {
         let key_id_bytes = key_id.to_bytes().to_vec();
 
         let row = query(
-            "SELECT key_id, tenant_id, name, permissions, created_at, expires_at, last_used_at, created_by
+            "SELECT key_id, tenant_id, name, permissions, created_at, expires_at, last_used_at, created_by, allowed_collections
              FROM api_keys WHERE key_id = ?1"
         )
         .bind(key_id_bytes)
@@ -101,7 +111,7 @@ impl SqliteApiKeyRepository {
         E: Executor<'e, Database = Sqlite>,
     {
         let row = query(
-            "SELECT key_id, tenant_id, name, permissions, created_at, expires_at, last_used_at, created_by
+            "SELECT key_id, tenant_id, name, permissions, created_at, expires_at, last_used_at, created_by, allowed_collections
              FROM api_keys WHERE key_hash = ?1"
         )
         .bind(key_hash)
@@ -152,7 +162,7 @@ impl ApiKeyRepository for SqliteApiKeyRepository {
         let tenant_id_bytes = tenant_id.to_bytes().to_vec();
 
         let rows = query(
-            "SELECT key_id, tenant_id, name, permissions, created_at, expires_at, last_used_at, created_by
+            "SELECT key_id, tenant_id, name, permissions, created_at, expires_at, last_used_at, created_by, allowed_collections
              FROM api_keys WHERE tenant_id = ?1 ORDER BY created_at DESC"
         )
         .bind(tenant_id_bytes)
@@ -239,6 +249,14 @@ fn parse_api_key_row(row: &sqlx::sqlite::SqliteRow) -> CoreResult<ApiKeyDescript
         .try_get("name")
         .map_err(|e| CoreError::internal(format!("Failed to get name: {e}")))?;
 
+    let allowed_collections_json: Option<String> = row
+        .try_get("allowed_collections")
+        .map_err(|e| CoreError::internal(format!("Failed to get allowed_collections: {e}")))?;
+    let allowed_collections: Option<Vec<CollectionId>> = allowed_collections_json
+        .map(|json| serde_json::from_str(&json))
+        .transpose()
+        .map_err(|e| CoreError::internal(format!("Failed to parse allowed_collections: {e}")))?;
+
     Ok(ApiKeyDescriptor {
         key_id,
         tenant_id,
@@ -248,6 +266,7 @@ fn parse_api_key_row(row: &sqlx::sqlite::SqliteRow) -> CoreResult<ApiKeyDescript
         expires_at,
         last_used_at,
         created_by,
+        allowed_collections,
     })
 }
 
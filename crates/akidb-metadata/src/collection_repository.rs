@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 use akidb_core::{
     CollectionDescriptor, CollectionId, CoreError, CoreResult, DatabaseId, DistanceMetric,
+    Quantization, VectorType,
 };
 use chrono::{DateTime, SecondsFormat, Utc};
 use sqlx::sqlite::SqliteRow;
@@ -66,9 +67,12 @@ impl SqliteCollectionRepository {
                 hnsw_ef_construction,
                 max_doc_count,
                 created_at,
-                updated_at
+                updated_at,
+                normalize,
+                quantization,
+                vector_type
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
             "#,
         )
         .bind(collection_id)
@@ -82,6 +86,9 @@ impl SqliteCollectionRepository {
         .bind(max_doc_count)
         .bind(created_at)
         .bind(updated_at)
+        .bind(collection.normalize)
+        .bind(collection.quantization.as_str())
+        .bind(collection.vector_type.as_str())
         .execute(executor)
         .await
         .map(|_| ())
@@ -126,7 +133,10 @@ impl SqliteCollectionRepository {
                    hnsw_m = ?7,
                    hnsw_ef_construction = ?8,
                    max_doc_count = ?9,
-                   updated_at = ?10
+                   updated_at = ?10,
+                   normalize = ?11,
+                   quantization = ?12,
+                   vector_type = ?13
              WHERE collection_id = ?1
             "#,
         )
@@ -140,6 +150,9 @@ impl SqliteCollectionRepository {
         .bind(hnsw_ef_construction)
         .bind(max_doc_count)
         .bind(updated_at)
+        .bind(collection.normalize)
+        .bind(collection.quantization.as_str())
+        .bind(collection.vector_type.as_str())
         .execute(executor)
         .await
         .map_err(|err| map_sqlx_error("collection", collection.collection_id.to_string(), err))?;
@@ -194,6 +207,15 @@ impl SqliteCollectionRepository {
         let metric: String = row.get("metric");
         let metric = DistanceMetric::from_str(&metric)
             .map_err(|_| CoreError::invalid_state(format!("unknown distance metric `{metric}`")))?;
+        let normalize: bool = row.get("normalize");
+        let quantization: String = row.get("quantization");
+        let quantization = Quantization::from_str(&quantization).map_err(|_| {
+            CoreError::invalid_state(format!("unknown quantization `{quantization}`"))
+        })?;
+        let vector_type: String = row.get("vector_type");
+        let vector_type = VectorType::from_str(&vector_type).map_err(|_| {
+            CoreError::invalid_state(format!("unknown vector type `{vector_type}`"))
+        })?;
         let embedding_model: String = row.get("embedding_model");
         let hnsw_m: i64 = row.get("hnsw_m");
         let hnsw_ef_construction: i64 = row.get("hnsw_ef_construction");
@@ -223,6 +245,9 @@ impl SqliteCollectionRepository {
             name,
             dimension,
             metric,
+            normalize,
+            quantization,
+            vector_type,
             embedding_model,
             hnsw_m,
             hnsw_ef_construction,
@@ -247,6 +272,9 @@ impl akidb_core::CollectionRepository for SqliteCollectionRepository {
                    name,
                    dimension,
                    metric,
+                   normalize,
+                   quantization,
+                   vector_type,
                    embedding_model,
                    hnsw_m,
                    hnsw_ef_construction,
@@ -279,6 +307,9 @@ impl akidb_core::CollectionRepository for SqliteCollectionRepository {
                    name,
                    dimension,
                    metric,
+                   normalize,
+                   quantization,
+                   vector_type,
                    embedding_model,
                    hnsw_m,
                    hnsw_ef_construction,
@@ -306,6 +337,9 @@ impl akidb_core::CollectionRepository for SqliteCollectionRepository {
                    name,
                    dimension,
                    metric,
+                   normalize,
+                   quantization,
+                   vector_type,
                    embedding_model,
                    hnsw_m,
                    hnsw_ef_construction,
@@ -0,0 +1,98 @@
+//! SQLite implementation of the feature flag repository.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{query, Row, SqlitePool};
+
+use akidb_core::{CoreError, CoreResult, FeatureFlag, FeatureFlagRepository};
+
+/// SQLite implementation of the feature flag repository.
+pub struct SqliteFeatureFlagRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteFeatureFlagRepository {
+    /// Creates a new SQLite feature flag repository.
+    #[must_use]
+    pub const fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl FeatureFlagRepository for SqliteFeatureFlagRepository {
+    async fn list(&self) -> CoreResult<Vec<FeatureFlag>> {
+        let rows = query(
+            "SELECT name, description, enabled, rollout_percentage, tenant_overrides, updated_at_ms
+             FROM feature_flags
+             ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        rows.iter().map(parse_flag_row).collect()
+    }
+
+    async fn upsert(&self, flag: &FeatureFlag) -> CoreResult<()> {
+        let overrides = serde_json::to_string(&flag.tenant_overrides)
+            .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        query(
+            "INSERT INTO feature_flags (name, description, enabled, rollout_percentage, tenant_overrides, updated_at_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(name) DO UPDATE SET
+                 description = excluded.description,
+                 enabled = excluded.enabled,
+                 rollout_percentage = excluded.rollout_percentage,
+                 tenant_overrides = excluded.tenant_overrides,
+                 updated_at_ms = excluded.updated_at_ms",
+        )
+        .bind(&flag.name)
+        .bind(&flag.description)
+        .bind(flag.enabled)
+        .bind(i64::from(flag.rollout_percentage))
+        .bind(overrides)
+        .bind(flag.updated_at.timestamp_millis())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, name: &str) -> CoreResult<bool> {
+        let result = query("DELETE FROM feature_flags WHERE name = ?1")
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Parse a feature flag row from SQLite.
+fn parse_flag_row(row: &sqlx::sqlite::SqliteRow) -> CoreResult<FeatureFlag> {
+    let internal = |e: sqlx::Error| CoreError::internal(e.to_string());
+
+    let name: String = row.try_get("name").map_err(internal)?;
+    let description: Option<String> = row.try_get("description").map_err(internal)?;
+    let enabled: bool = row.try_get("enabled").map_err(internal)?;
+    let rollout_percentage: i64 = row.try_get("rollout_percentage").map_err(internal)?;
+    let tenant_overrides: String = row.try_get("tenant_overrides").map_err(internal)?;
+    let updated_at_ms: i64 = row.try_get("updated_at_ms").map_err(internal)?;
+
+    Ok(FeatureFlag {
+        tenant_overrides: serde_json::from_str(&tenant_overrides)
+            .map_err(|e| CoreError::internal(format!("invalid overrides for flag {name}: {e}")))?,
+        rollout_percentage: u8::try_from(rollout_percentage).map_err(|_| {
+            CoreError::internal(format!("invalid rollout percentage: {rollout_percentage}"))
+        })?,
+        updated_at: DateTime::<Utc>::from_timestamp_millis(updated_at_ms)
+            .ok_or_else(|| CoreError::internal(format!("invalid timestamp: {updated_at_ms}")))?,
+        name,
+        description,
+        enabled,
+    })
+}
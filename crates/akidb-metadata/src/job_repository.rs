@@ -0,0 +1,170 @@
+//! SQLite implementation of the background job repository.
+
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{query, Row, SqlitePool};
+
+use akidb_core::{
+    CollectionId, CoreError, CoreResult, JobDescriptor, JobId, JobRepository, JobStatus,
+};
+
+/// SQLite implementation of the job repository.
+pub struct SqliteJobRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteJobRepository {
+    /// Creates a new SQLite job repository.
+    #[must_use]
+    pub const fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl JobRepository for SqliteJobRepository {
+    async fn create(&self, job: &JobDescriptor) -> CoreResult<()> {
+        query(
+            "INSERT INTO jobs (job_id, kind, collection_id, status, progress, total, error, result, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )
+        .bind(job.job_id.to_bytes().to_vec())
+        .bind(&job.kind)
+        .bind(job.collection_id.map(|id| id.to_bytes().to_vec()))
+        .bind(job.status.as_str())
+        .bind(job.progress as i64)
+        .bind(job.total.map(|total| total as i64))
+        .bind(&job.error)
+        .bind(job.result.as_ref().map(|r| r.to_string()))
+        .bind(job.created_at.to_rfc3339())
+        .bind(job.updated_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn update(&self, job: &JobDescriptor) -> CoreResult<()> {
+        let result = query(
+            "UPDATE jobs SET status = ?2, progress = ?3, total = ?4, error = ?5, result = ?6, updated_at = ?7
+             WHERE job_id = ?1",
+        )
+        .bind(job.job_id.to_bytes().to_vec())
+        .bind(job.status.as_str())
+        .bind(job.progress as i64)
+        .bind(job.total.map(|total| total as i64))
+        .bind(&job.error)
+        .bind(job.result.as_ref().map(|r| r.to_string()))
+        .bind(job.updated_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(CoreError::not_found("Job", job.job_id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, job_id: JobId) -> CoreResult<Option<JobDescriptor>> {
+        let row = query(
+            "SELECT job_id, kind, collection_id, status, progress, total, error, result, created_at, updated_at
+             FROM jobs WHERE job_id = ?1",
+        )
+        .bind(job_id.to_bytes().to_vec())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        row.as_ref().map(parse_job_row).transpose()
+    }
+
+    async fn list(
+        &self,
+        status: Option<JobStatus>,
+        collection_id: Option<CollectionId>,
+        limit: usize,
+        offset: usize,
+    ) -> CoreResult<Vec<JobDescriptor>> {
+        let rows = query(
+            "SELECT job_id, kind, collection_id, status, progress, total, error, result, created_at, updated_at
+             FROM jobs
+             WHERE (?1 IS NULL OR status = ?1) AND (?2 IS NULL OR collection_id = ?2)
+             ORDER BY created_at DESC, job_id
+             LIMIT ?3 OFFSET ?4",
+        )
+        .bind(status.map(|status| status.as_str()))
+        .bind(collection_id.map(|id| id.to_bytes().to_vec()))
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        rows.iter().map(parse_job_row).collect()
+    }
+
+    async fn fail_unfinished(&self, reason: &str) -> CoreResult<u64> {
+        let result = query(
+            "UPDATE jobs SET status = 'failed', error = ?1, updated_at = ?2
+             WHERE status IN ('pending', 'running')",
+        )
+        .bind(reason)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Parse a job row from SQLite.
+fn parse_job_row(row: &sqlx::sqlite::SqliteRow) -> CoreResult<JobDescriptor> {
+    let internal = |e: sqlx::Error| CoreError::internal(e.to_string());
+
+    let job_id_bytes: Vec<u8> = row.try_get("job_id").map_err(internal)?;
+    let kind: String = row.try_get("kind").map_err(internal)?;
+    let collection_id_bytes: Option<Vec<u8>> = row.try_get("collection_id").map_err(internal)?;
+    let status_str: String = row.try_get("status").map_err(internal)?;
+    let progress: i64 = row.try_get("progress").map_err(internal)?;
+    let total: Option<i64> = row.try_get("total").map_err(internal)?;
+    let error: Option<String> = row.try_get("error").map_err(internal)?;
+    let result_str: Option<String> = row.try_get("result").map_err(internal)?;
+    let created_at_str: String = row.try_get("created_at").map_err(internal)?;
+    let updated_at_str: String = row.try_get("updated_at").map_err(internal)?;
+
+    let job_id =
+        JobId::from_bytes(&job_id_bytes).map_err(|e| CoreError::internal(e.to_string()))?;
+    let collection_id = collection_id_bytes
+        .map(|bytes| CollectionId::from_bytes(&bytes))
+        .transpose()
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+    let status = JobStatus::from_str(&status_str).map_err(CoreError::invalid_state)?;
+    let result = result_str
+        .map(|s| serde_json::from_str(&s))
+        .transpose()
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+    let parse_time = |s: &str| {
+        DateTime::parse_from_rfc3339(s)
+            .map(|t| t.with_timezone(&Utc))
+            .map_err(|e| CoreError::internal(e.to_string()))
+    };
+
+    Ok(JobDescriptor {
+        job_id,
+        kind,
+        collection_id,
+        status,
+        progress: progress as u64,
+        total: total.map(|total| total as u64),
+        error,
+        result,
+        created_at: parse_time(&created_at_str)?,
+        updated_at: parse_time(&updated_at_str)?,
+    })
+}
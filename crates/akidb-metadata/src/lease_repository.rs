@@ -0,0 +1,111 @@
+//! SQLite implementation of the lease repository.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{query, Row, SqlitePool};
+
+use akidb_core::{CoreError, CoreResult, Lease, LeaseRepository};
+
+/// SQLite implementation of the lease repository.
+///
+/// Every process sharing the metadata database sees the same leases.
+pub struct SqliteLeaseRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteLeaseRepository {
+    /// Creates a new SQLite lease repository.
+    #[must_use]
+    pub const fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl LeaseRepository for SqliteLeaseRepository {
+    async fn acquire(&self, name: &str, holder: &str, ttl: Duration) -> CoreResult<Option<Lease>> {
+        let now = Utc::now().timestamp_millis();
+        let ttl_ms = i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX);
+
+        // Single statement, so two processes cannot both take a free lease
+        let rows = query(
+            "INSERT INTO leases (name, holder, token, acquired_at_ms, expires_at_ms)
+             VALUES (?1, ?2, 1, ?3, ?4)
+             ON CONFLICT(name) DO UPDATE SET
+                 token = CASE WHEN leases.holder = excluded.holder
+                     THEN leases.token ELSE leases.token + 1 END,
+                 acquired_at_ms = CASE WHEN leases.holder = excluded.holder
+                     THEN leases.acquired_at_ms ELSE excluded.acquired_at_ms END,
+                 holder = excluded.holder,
+                 expires_at_ms = excluded.expires_at_ms
+             WHERE leases.holder = excluded.holder OR leases.expires_at_ms <= ?3
+             RETURNING name, holder, token, acquired_at_ms, expires_at_ms",
+        )
+        .bind(name)
+        .bind(holder)
+        .bind(now)
+        .bind(now.saturating_add(ttl_ms))
+        // Not fetch_optional: stopping after the first row leaves the
+        // statement unfinished, so the upsert is not committed
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        rows.first().map(parse_lease_row).transpose()
+    }
+
+    async fn release(&self, name: &str, holder: &str) -> CoreResult<bool> {
+        // Expire rather than delete, so the next holder gets a higher token
+        let result = query(
+            "UPDATE leases SET expires_at_ms = ?3
+             WHERE name = ?1 AND holder = ?2 AND expires_at_ms > ?3",
+        )
+        .bind(name)
+        .bind(holder)
+        .bind(Utc::now().timestamp_millis())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get(&self, name: &str) -> CoreResult<Option<Lease>> {
+        let row = query(
+            "SELECT name, holder, token, acquired_at_ms, expires_at_ms
+             FROM leases WHERE name = ?1",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        row.as_ref().map(parse_lease_row).transpose()
+    }
+}
+
+/// Parse a lease row from SQLite.
+fn parse_lease_row(row: &sqlx::sqlite::SqliteRow) -> CoreResult<Lease> {
+    let internal = |e: sqlx::Error| CoreError::internal(e.to_string());
+
+    let name: String = row.try_get("name").map_err(internal)?;
+    let holder: String = row.try_get("holder").map_err(internal)?;
+    let token: i64 = row.try_get("token").map_err(internal)?;
+    let acquired_at_ms: i64 = row.try_get("acquired_at_ms").map_err(internal)?;
+    let expires_at_ms: i64 = row.try_get("expires_at_ms").map_err(internal)?;
+
+    let parse_time = |millis: i64| {
+        DateTime::<Utc>::from_timestamp_millis(millis)
+            .ok_or_else(|| CoreError::internal(format!("invalid lease timestamp: {millis}")))
+    };
+
+    Ok(Lease {
+        name,
+        holder,
+        token: token as u64,
+        acquired_at: parse_time(acquired_at_ms)?,
+        expires_at: parse_time(expires_at_ms)?,
+    })
+}
@@ -1,22 +1,32 @@
 //! SQLite metadata adapters for the AkiDB 2.0 control plane.
 
+mod alias_repository;
 mod api_key_repository;
 mod audit_repository;
 mod collection_repository;
+mod feature_flag_repository;
+mod job_repository;
+mod lease_repository;
 pub mod password;
 mod repository;
 mod tenant_catalog;
 mod tier_state_repository;
+mod usage_repository;
 mod user_repository;
 mod util;
 mod vector_persistence;
 
+pub use alias_repository::SqliteAliasRepository;
 pub use api_key_repository::SqliteApiKeyRepository;
 pub use audit_repository::SqliteAuditLogRepository;
 pub use collection_repository::SqliteCollectionRepository;
+pub use feature_flag_repository::SqliteFeatureFlagRepository;
+pub use job_repository::SqliteJobRepository;
+pub use lease_repository::SqliteLeaseRepository;
 pub use repository::SqliteDatabaseRepository;
 pub use tenant_catalog::SqliteTenantCatalog;
 pub use tier_state_repository::{Tier, TierState, TierStateRepository};
+pub use usage_repository::SqliteUsageRepository;
 pub use user_repository::SqliteUserRepository;
 pub use util::{create_sqlite_pool, run_migrations};
 pub use vector_persistence::VectorPersistence;
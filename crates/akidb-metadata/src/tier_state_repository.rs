@@ -417,8 +417,8 @@ impl TierState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use akidb_core::{CollectionDescriptor, DatabaseDescriptor, TenantCatalog, TenantDescriptor};
     use crate::{SqliteCollectionRepository, SqliteDatabaseRepository, SqliteTenantCatalog};
+    use akidb_core::{CollectionDescriptor, DatabaseDescriptor, TenantCatalog, TenantDescriptor};
     use chrono::Duration;
 
     async fn setup_db() -> SqlitePool {
@@ -436,13 +436,11 @@ mod tests {
         tenant_catalog.create(&tenant).await.unwrap();
 
         // 2. Create database
-        let database = DatabaseDescriptor::new(
-            tenant_id,
-            "test-database",
-            None,
-        );
+        let database = DatabaseDescriptor::new(tenant_id, "test-database", None);
         let database_id = database.database_id;
-        SqliteDatabaseRepository::create_with_executor(pool, &database).await.unwrap();
+        SqliteDatabaseRepository::create_with_executor(pool, &database)
+            .await
+            .unwrap();
 
         // 3. Create collection
         let collection = CollectionDescriptor::new(
@@ -452,7 +450,9 @@ mod tests {
             "test-model",
         );
         let collection_id = collection.collection_id;
-        SqliteCollectionRepository::create_with_executor(pool, &collection).await.unwrap();
+        SqliteCollectionRepository::create_with_executor(pool, &collection)
+            .await
+            .unwrap();
 
         collection_id
     }
@@ -0,0 +1,91 @@
+//! SQLite implementation of the usage rollup repository.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{query, Row, SqlitePool};
+
+use akidb_core::{CoreError, CoreResult, TenantId, UsageRepository, UsageRollup};
+
+/// SQLite implementation of the usage rollup repository.
+pub struct SqliteUsageRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteUsageRepository {
+    /// Creates a new SQLite usage repository.
+    #[must_use]
+    pub const fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UsageRepository for SqliteUsageRepository {
+    async fn record(&self, rollup: &UsageRollup) -> CoreResult<()> {
+        query(
+            "INSERT INTO usage_rollups (tenant_id, hour_ms, queries, vectors_inserted, stored_bytes, embedding_tokens)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(tenant_id, hour_ms) DO UPDATE SET
+                 queries = usage_rollups.queries + excluded.queries,
+                 vectors_inserted = usage_rollups.vectors_inserted + excluded.vectors_inserted,
+                 stored_bytes = MAX(usage_rollups.stored_bytes, excluded.stored_bytes),
+                 embedding_tokens = usage_rollups.embedding_tokens + excluded.embedding_tokens",
+        )
+        .bind(rollup.tenant_id.to_bytes().to_vec())
+        .bind(rollup.hour.timestamp_millis())
+        .bind(rollup.queries as i64)
+        .bind(rollup.vectors_inserted as i64)
+        .bind(rollup.stored_bytes as i64)
+        .bind(rollup.embedding_tokens as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        tenant_id: Option<TenantId>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> CoreResult<Vec<UsageRollup>> {
+        let rows = query(
+            "SELECT tenant_id, hour_ms, queries, vectors_inserted, stored_bytes, embedding_tokens
+             FROM usage_rollups
+             WHERE (?1 IS NULL OR tenant_id = ?1) AND hour_ms >= ?2 AND hour_ms < ?3
+             ORDER BY hour_ms, tenant_id",
+        )
+        .bind(tenant_id.map(|id| id.to_bytes().to_vec()))
+        .bind(from.timestamp_millis())
+        .bind(to.timestamp_millis())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::internal(e.to_string()))?;
+
+        rows.iter().map(parse_usage_row).collect()
+    }
+}
+
+/// Parse a usage rollup row from SQLite.
+fn parse_usage_row(row: &sqlx::sqlite::SqliteRow) -> CoreResult<UsageRollup> {
+    let internal = |e: sqlx::Error| CoreError::internal(e.to_string());
+
+    let tenant_id_bytes: Vec<u8> = row.try_get("tenant_id").map_err(internal)?;
+    let hour_ms: i64 = row.try_get("hour_ms").map_err(internal)?;
+    let queries: i64 = row.try_get("queries").map_err(internal)?;
+    let vectors_inserted: i64 = row.try_get("vectors_inserted").map_err(internal)?;
+    let stored_bytes: i64 = row.try_get("stored_bytes").map_err(internal)?;
+    let embedding_tokens: i64 = row.try_get("embedding_tokens").map_err(internal)?;
+
+    Ok(UsageRollup {
+        tenant_id: TenantId::from_bytes(&tenant_id_bytes)
+            .map_err(|e| CoreError::internal(e.to_string()))?,
+        hour: DateTime::<Utc>::from_timestamp_millis(hour_ms)
+            .ok_or_else(|| CoreError::internal(format!("invalid usage hour: {hour_ms}")))?,
+        queries: queries as u64,
+        vectors_inserted: vectors_inserted as u64,
+        stored_bytes: stored_bytes as u64,
+        embedding_tokens: embedding_tokens as u64,
+    })
+}
@@ -21,6 +21,15 @@ impl VectorPersistence {
         Self { pool }
     }
 
+    /// Round-trips a trivial query to verify the database is reachable.
+    pub async fn ping(&self) -> CoreResult<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::internal(format!("Failed to ping database: {}", e)))?;
+        Ok(())
+    }
+
     /// Saves a single vector document to SQLite.
     ///
     /// If a document with the same (collection_id, doc_id) already exists,
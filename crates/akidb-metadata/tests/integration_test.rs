@@ -1,16 +1,21 @@
 use std::path::PathBuf;
 
 use akidb_core::{
-    generate_api_key, hash_api_key, Action, ApiKeyDescriptor, ApiKeyRepository, AuditLogEntry,
-    AuditLogRepository, AuditResult, CollectionDescriptor, CollectionRepository, CoreError,
-    DatabaseDescriptor, DatabaseRepository, DatabaseState, DistanceMetric, Role, TenantCatalog,
-    TenantDescriptor, TenantStatus, UserDescriptor, UserRepository, UserStatus,
+    generate_api_key, hash_api_key, Action, AliasRepository, ApiKeyDescriptor, ApiKeyRepository,
+    AuditLogEntry, AuditLogRepository, AuditResult, CollectionAlias, CollectionDescriptor,
+    CollectionId, CollectionRepository, CoreError, DatabaseDescriptor, DatabaseRepository,
+    DatabaseState, DistanceMetric, FeatureFlag, FeatureFlagRepository, JobDescriptor, JobId,
+    JobRepository, JobStatus, LeaseRepository, Quantization, Role, TenantCatalog, TenantDescriptor,
+    TenantStatus, UsageRepository, UsageRollup, UserDescriptor, UserRepository, UserStatus,
+    VectorType,
 };
 use akidb_metadata::{
-    create_sqlite_pool, password, run_migrations, SqliteApiKeyRepository, SqliteAuditLogRepository,
-    SqliteCollectionRepository, SqliteDatabaseRepository, SqliteTenantCatalog,
-    SqliteUserRepository,
+    create_sqlite_pool, password, run_migrations, SqliteAliasRepository, SqliteApiKeyRepository,
+    SqliteAuditLogRepository, SqliteCollectionRepository, SqliteDatabaseRepository,
+    SqliteFeatureFlagRepository, SqliteJobRepository, SqliteLeaseRepository, SqliteTenantCatalog,
+    SqliteUsageRepository, SqliteUserRepository,
 };
+use chrono::{Duration, TimeZone, Utc};
 use uuid::Uuid;
 
 struct TestContext {
@@ -20,6 +25,11 @@ struct TestContext {
     users: SqliteUserRepository,
     audit_logs: SqliteAuditLogRepository,
     api_keys: SqliteApiKeyRepository,
+    jobs: SqliteJobRepository,
+    leases: SqliteLeaseRepository,
+    usage: SqliteUsageRepository,
+    flags: SqliteFeatureFlagRepository,
+    aliases: SqliteAliasRepository,
 }
 
 async fn setup_context() -> TestContext {
@@ -36,7 +46,12 @@ async fn setup_context() -> TestContext {
         collections: SqliteCollectionRepository::new(pool.clone()),
         users: SqliteUserRepository::new(pool.clone()),
         audit_logs: SqliteAuditLogRepository::new(pool.clone()),
-        api_keys: SqliteApiKeyRepository::new(pool),
+        api_keys: SqliteApiKeyRepository::new(pool.clone()),
+        jobs: SqliteJobRepository::new(pool.clone()),
+        leases: SqliteLeaseRepository::new(pool.clone()),
+        usage: SqliteUsageRepository::new(pool.clone()),
+        flags: SqliteFeatureFlagRepository::new(pool.clone()),
+        aliases: SqliteAliasRepository::new(pool),
     }
 }
 
@@ -251,8 +266,10 @@ async fn create_collection_successfully() {
         .await
         .expect("create database");
 
-    let collection =
+    let mut collection =
         CollectionDescriptor::new(database.database_id, "embeddings", 512, "qwen3-embed-8b");
+    collection.normalize = true;
+    collection.quantization = Quantization::Int8;
     ctx.collections
         .create(&collection)
         .await
@@ -267,6 +284,8 @@ async fn create_collection_successfully() {
     assert_eq!(fetched.name, "embeddings");
     assert_eq!(fetched.dimension, 512);
     assert_eq!(fetched.metric, DistanceMetric::Cosine);
+    assert!(fetched.normalize);
+    assert_eq!(fetched.quantization, Quantization::Int8);
 }
 
 #[tokio::test]
@@ -381,6 +400,30 @@ async fn validate_dimension_bounds() {
         .await
         .expect_err("too large");
     assert!(matches!(err, CoreError::InvalidState { .. }));
+
+    // Sparse-only collections have dimension 0, and only they do
+    collection.name = "sparse".to_string();
+    collection.dimension = 0;
+    let err = ctx
+        .collections
+        .create(&collection)
+        .await
+        .expect_err("dense with dimension 0");
+    assert!(matches!(err, CoreError::InvalidState { .. }));
+
+    collection.vector_type = VectorType::Sparse;
+    ctx.collections
+        .create(&collection)
+        .await
+        .expect("create sparse collection");
+    let fetched = ctx
+        .collections
+        .get(collection.collection_id)
+        .await
+        .expect("fetch collection")
+        .expect("collection exists");
+    assert_eq!(fetched.dimension, 0);
+    assert_eq!(fetched.vector_type, VectorType::Sparse);
 }
 
 #[tokio::test]
@@ -858,6 +901,39 @@ async fn get_api_key_by_hash() {
     assert_eq!(fetched.name, "hash-test-key");
 }
 
+#[tokio::test]
+async fn api_key_collection_allowlist_round_trips() {
+    let ctx = setup_context().await;
+    let tenant = TenantDescriptor::new("Ingest Corp", "ingest-corp");
+    ctx.catalog.create(&tenant).await.expect("create tenant");
+
+    let allowed = CollectionId::new();
+    let key_hash = hash_api_key(&generate_api_key());
+    let descriptor = ApiKeyDescriptor::new(
+        tenant.tenant_id,
+        "ingest-key".to_string(),
+        vec!["collection::write".to_string()],
+        None,
+        None,
+    )
+    .with_allowed_collections(vec![allowed]);
+
+    ctx.api_keys
+        .create(&descriptor, &key_hash)
+        .await
+        .expect("create API key");
+
+    let fetched = ctx
+        .api_keys
+        .get_by_hash(&key_hash)
+        .await
+        .expect("get by hash")
+        .expect("key exists");
+    assert_eq!(fetched.allowed_collections, Some(vec![allowed]));
+    assert!(fetched.can_access_collection(allowed));
+    assert!(!fetched.can_access_collection(CollectionId::new()));
+}
+
 #[tokio::test]
 async fn list_api_keys_by_tenant() {
     let ctx = setup_context().await;
@@ -1042,3 +1118,343 @@ async fn enforce_unique_key_hash() {
         .expect_err("duplicate key hash");
     assert!(matches!(err, CoreError::AlreadyExists { .. }));
 }
+
+// ==================== Job Tests ====================
+
+#[tokio::test]
+async fn create_and_update_job() {
+    let ctx = setup_context().await;
+
+    let mut job = JobDescriptor::new("bulk_delete", None).with_total(10);
+    ctx.jobs.create(&job).await.expect("create job");
+
+    job.status = JobStatus::Succeeded;
+    job.progress = 10;
+    job.result = Some(serde_json::json!({"deleted": 10}));
+    ctx.jobs.update(&job).await.expect("update job");
+
+    let stored = ctx
+        .jobs
+        .get(job.job_id)
+        .await
+        .expect("get job")
+        .expect("job exists");
+    assert_eq!(stored.kind, "bulk_delete");
+    assert_eq!(stored.status, JobStatus::Succeeded);
+    assert_eq!(stored.progress, 10);
+    assert_eq!(stored.total, Some(10));
+    assert_eq!(stored.result, Some(serde_json::json!({"deleted": 10})));
+
+    assert!(ctx.jobs.get(JobId::new()).await.expect("get").is_none());
+}
+
+#[tokio::test]
+async fn fail_unfinished_jobs() {
+    let ctx = setup_context().await;
+
+    let running = JobDescriptor::new("reindex", None);
+    ctx.jobs.create(&running).await.expect("create job");
+
+    let mut done = JobDescriptor::new("export", None);
+    done.status = JobStatus::Succeeded;
+    ctx.jobs.create(&done).await.expect("create job");
+
+    let updated = ctx
+        .jobs
+        .fail_unfinished("interrupted")
+        .await
+        .expect("fail unfinished");
+    assert_eq!(updated, 1);
+
+    let stored = ctx.jobs.get(running.job_id).await.unwrap().unwrap();
+    assert_eq!(stored.status, JobStatus::Failed);
+    assert_eq!(stored.error.as_deref(), Some("interrupted"));
+    let stored = ctx.jobs.get(done.job_id).await.unwrap().unwrap();
+    assert_eq!(stored.status, JobStatus::Succeeded);
+}
+
+#[tokio::test]
+async fn list_jobs_newest_first_with_filters() {
+    let ctx = setup_context().await;
+    let collection_id = CollectionId::new();
+
+    let mut older = JobDescriptor::new("reindex", Some(collection_id));
+    older.created_at -= Duration::seconds(10);
+    older.status = JobStatus::Cancelled;
+    older.error = Some("cancelled by request".to_string());
+    ctx.jobs.create(&older).await.expect("create job");
+    let newer = JobDescriptor::new("reindex", Some(collection_id));
+    ctx.jobs.create(&newer).await.expect("create job");
+    let other = JobDescriptor::new("export", None);
+    ctx.jobs.create(&other).await.expect("create job");
+
+    let ids = |jobs: Vec<JobDescriptor>| jobs.into_iter().map(|job| job.job_id).collect::<Vec<_>>();
+    let all = ctx.jobs.list(None, None, 10, 0).await.expect("list jobs");
+    assert_eq!(all.len(), 3);
+    assert_eq!(all[2].job_id, older.job_id);
+    assert_eq!(all[2].status, JobStatus::Cancelled);
+
+    let on_collection = ctx
+        .jobs
+        .list(None, Some(collection_id), 10, 0)
+        .await
+        .expect("list jobs");
+    assert_eq!(ids(on_collection), vec![newer.job_id, older.job_id]);
+
+    let cancelled = ctx
+        .jobs
+        .list(Some(JobStatus::Cancelled), None, 10, 0)
+        .await
+        .expect("list jobs");
+    assert_eq!(ids(cancelled), vec![older.job_id]);
+
+    let page = ctx
+        .jobs
+        .list(None, Some(collection_id), 1, 1)
+        .await
+        .expect("list jobs");
+    assert_eq!(ids(page), vec![older.job_id]);
+}
+
+// ==================== Lease Tests ====================
+
+#[tokio::test]
+async fn lease_is_exclusive_until_released() {
+    let ctx = setup_context().await;
+    let ttl = std::time::Duration::from_secs(30);
+
+    let lease = ctx
+        .leases
+        .acquire("gc", "node-a", ttl)
+        .await
+        .expect("acquire")
+        .expect("lease is free");
+    assert_eq!(lease.holder, "node-a");
+    assert_eq!(lease.token, 1);
+
+    assert!(ctx
+        .leases
+        .acquire("gc", "node-b", ttl)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Renewal extends the lease without changing hands
+    let renewed = ctx
+        .leases
+        .acquire("gc", "node-a", ttl)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(renewed.token, 1);
+    assert_eq!(renewed.acquired_at, lease.acquired_at);
+    assert!(renewed.expires_at >= lease.expires_at);
+
+    assert!(!ctx.leases.release("gc", "node-b").await.unwrap());
+    assert!(ctx.leases.release("gc", "node-a").await.unwrap());
+
+    let taken = ctx
+        .leases
+        .acquire("gc", "node-b", ttl)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(taken.holder, "node-b");
+    assert_eq!(taken.token, 2);
+    assert_eq!(ctx.leases.get("gc").await.unwrap(), Some(taken));
+    assert!(ctx.leases.get("backup").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn expired_lease_can_be_taken_over() {
+    let ctx = setup_context().await;
+
+    ctx.leases
+        .acquire("tiering", "node-a", std::time::Duration::from_millis(20))
+        .await
+        .unwrap()
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let lease = ctx
+        .leases
+        .acquire("tiering", "node-b", std::time::Duration::from_secs(30))
+        .await
+        .unwrap()
+        .expect("expired lease is free");
+    assert_eq!(lease.holder, "node-b");
+    assert_eq!(lease.token, 2);
+}
+
+#[tokio::test]
+async fn usage_rollups_accumulate_per_tenant_and_hour() {
+    let ctx = setup_context().await;
+    let acme = TenantDescriptor::new("Acme", "acme");
+    let globex = TenantDescriptor::new("Globex", "globex");
+    ctx.catalog.create(&acme).await.unwrap();
+    ctx.catalog.create(&globex).await.unwrap();
+
+    let hour = Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap();
+    let usage = |tenant: &TenantDescriptor, at, queries, stored_bytes| UsageRollup {
+        queries,
+        vectors_inserted: 2,
+        stored_bytes,
+        embedding_tokens: 10,
+        ..UsageRollup::new(tenant.tenant_id, at)
+    };
+
+    // Two flushes within the same hour sum up; stored bytes keep the peak
+    ctx.usage
+        .record(&usage(&acme, hour + Duration::minutes(5), 3, 4096))
+        .await
+        .unwrap();
+    ctx.usage
+        .record(&usage(&acme, hour + Duration::minutes(50), 4, 1024))
+        .await
+        .unwrap();
+    ctx.usage
+        .record(&usage(&acme, hour + Duration::hours(1), 1, 2048))
+        .await
+        .unwrap();
+    ctx.usage
+        .record(&usage(&globex, hour, 5, 512))
+        .await
+        .unwrap();
+
+    let acme_usage = ctx
+        .usage
+        .list(Some(acme.tenant_id), hour, hour + Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(acme_usage.len(), 2);
+    assert_eq!(acme_usage[0].hour, hour);
+    assert_eq!(acme_usage[0].queries, 7);
+    assert_eq!(acme_usage[0].vectors_inserted, 4);
+    assert_eq!(acme_usage[0].stored_bytes, 4096);
+    assert_eq!(acme_usage[0].embedding_tokens, 20);
+    assert_eq!(acme_usage[1].queries, 1);
+
+    // The upper bound is exclusive
+    let first_hour = ctx
+        .usage
+        .list(None, hour, hour + Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(first_hour.len(), 2);
+    assert!(first_hour.iter().any(|u| u.tenant_id == globex.tenant_id));
+}
+
+#[tokio::test]
+async fn feature_flags_round_trip_with_overrides() {
+    let ctx = setup_context().await;
+    let acme = TenantDescriptor::new("Acme", "acme");
+
+    let mut flag = FeatureFlag::new("tiering", true);
+    flag.description = Some("Hot/warm/cold tiering".to_string());
+    flag.rollout_percentage = 25;
+    flag.tenant_overrides.insert(acme.tenant_id, false);
+    ctx.flags.upsert(&flag).await.unwrap();
+    ctx.flags
+        .upsert(&FeatureFlag::new("hybrid_search", false))
+        .await
+        .unwrap();
+
+    let flags = ctx.flags.list().await.unwrap();
+    assert_eq!(flags.len(), 2);
+    assert_eq!(flags[0].name, "hybrid_search");
+    assert_eq!(flags[1].rollout_percentage, 25);
+    assert_eq!(flags[1].tenant_overrides.get(&acme.tenant_id), Some(&false));
+    assert_eq!(
+        flags[1].description.as_deref(),
+        Some("Hot/warm/cold tiering")
+    );
+
+    // Upserting replaces the overrides
+    flag.tenant_overrides.clear();
+    ctx.flags.upsert(&flag).await.unwrap();
+    let flags = ctx.flags.list().await.unwrap();
+    assert!(flags[1].tenant_overrides.is_empty());
+
+    assert!(ctx.flags.delete("tiering").await.unwrap());
+    assert!(!ctx.flags.delete("tiering").await.unwrap());
+    assert_eq!(ctx.flags.list().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn alias_repoints_and_cascades_with_database() {
+    let ctx = setup_context().await;
+    let tenant = TenantDescriptor::new("Alias Corp", "alias-corp");
+    ctx.catalog.create(&tenant).await.expect("create tenant");
+    let database = DatabaseDescriptor::new(tenant.tenant_id, "vectors", None);
+    ctx.databases
+        .create(&database)
+        .await
+        .expect("create database");
+
+    let blue = CollectionDescriptor::new(database.database_id, "docs-blue", 512, "model");
+    let green = CollectionDescriptor::new(database.database_id, "docs-green", 512, "model");
+    for collection in [&blue, &green] {
+        ctx.collections.create(collection).await.unwrap();
+    }
+
+    let alias = CollectionAlias::new(database.database_id, "prod-docs", blue.collection_id);
+    ctx.aliases.upsert(&alias).await.unwrap();
+    ctx.aliases
+        .upsert(&CollectionAlias::new(
+            database.database_id,
+            "canary",
+            green.collection_id,
+        ))
+        .await
+        .unwrap();
+
+    // Repointing keeps the alias's creation time
+    let swapped = CollectionAlias {
+        collection_id: green.collection_id,
+        created_at: alias.created_at + Duration::hours(1),
+        updated_at: alias.updated_at + Duration::hours(1),
+        ..alias.clone()
+    };
+    ctx.aliases.upsert(&swapped).await.unwrap();
+    let fetched = ctx
+        .aliases
+        .get(database.database_id, "prod-docs")
+        .await
+        .unwrap()
+        .expect("alias present");
+    assert_eq!(fetched.collection_id, green.collection_id);
+    assert_eq!(
+        fetched.created_at.timestamp_millis(),
+        alias.created_at.timestamp_millis()
+    );
+    assert!(fetched.updated_at > fetched.created_at);
+
+    let names: Vec<String> = ctx
+        .aliases
+        .list(database.database_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|alias| alias.name)
+        .collect();
+    assert_eq!(names, vec!["canary", "prod-docs"]);
+
+    assert!(ctx
+        .aliases
+        .delete(database.database_id, "canary")
+        .await
+        .unwrap());
+    assert!(!ctx
+        .aliases
+        .delete(database.database_id, "canary")
+        .await
+        .unwrap());
+
+    ctx.databases.delete(database.database_id).await.unwrap();
+    assert!(ctx
+        .aliases
+        .list(database.database_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -1,11 +1,16 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+
     tonic_build::configure()
         .build_server(true)
         .build_client(true)
+        // Served by gRPC server reflection (grpcurl, Postman)
+        .file_descriptor_set_path(out_dir.join("akidb_descriptor.bin"))
         .compile(
             &[
                 "proto/akidb/collection/v1/collection.proto",
                 "proto/akidb/embedding/v1/embedding.proto",
+                "proto/akidb/replication/v1/replication.proto",
             ],
             &["proto"],
         )?;
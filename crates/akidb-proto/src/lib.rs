@@ -12,7 +12,17 @@ pub mod akidb {
             tonic::include_proto!("akidb.embedding.v1");
         }
     }
+
+    pub mod replication {
+        pub mod v1 {
+            tonic::include_proto!("akidb.replication.v1");
+        }
+    }
 }
 
 pub use akidb::collection::v1::*;
 pub use akidb::embedding::v1 as embedding;
+pub use akidb::replication::v1 as replication;
+
+/// Encoded `FileDescriptorSet` for all AkiDB protos, for gRPC server reflection.
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("akidb_descriptor");
@@ -0,0 +1,163 @@
+//! JSON error responses for the REST API.
+//!
+//! Every error body has the same shape:
+//!
+//! ```json
+//! {"code": "COLLECTION_NOT_FOUND", "message": "...", "retryable": false, "details": {...}}
+//! ```
+//!
+//! `code` is a stable [`ErrorCode`]; `message` is for humans and may change.
+//! `details` is omitted when empty.
+
+use akidb_core::{CoreError, ErrorCode};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Body of every REST error response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    pub message: String,
+    pub retryable: bool,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub details: Map<String, Value>,
+}
+
+/// Error returned by REST handlers; renders as an [`ErrorResponse`].
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    status: StatusCode,
+    body: ErrorResponse,
+}
+
+impl ApiError {
+    /// Creates an error with an explicit status and code.
+    pub fn new(status: StatusCode, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: ErrorResponse {
+                code,
+                message: message.into(),
+                retryable: code.is_retryable(),
+                details: Map::new(),
+            },
+        }
+    }
+
+    /// `400 Bad Request` with `INVALID_ARGUMENT`.
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, ErrorCode::InvalidArgument, message)
+    }
+
+    /// Adds an entry to `details`.
+    pub fn with_detail(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.body.details.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.body.code
+    }
+}
+
+/// HTTP status used for each error code.
+pub fn status_for_code(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::InvalidArgument
+        | ErrorCode::DimensionMismatch
+        | ErrorCode::FailedPrecondition => StatusCode::BAD_REQUEST,
+        ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        ErrorCode::CollectionNotFound | ErrorCode::DocumentNotFound | ErrorCode::NotFound => {
+            StatusCode::NOT_FOUND
+        }
+        ErrorCode::AlreadyExists | ErrorCode::Conflict => StatusCode::CONFLICT,
+        ErrorCode::QuotaExceeded | ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+        ErrorCode::PermissionDenied => StatusCode::FORBIDDEN,
+        ErrorCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn code_for_status(status: StatusCode) -> ErrorCode {
+    match status {
+        StatusCode::NOT_FOUND => ErrorCode::NotFound,
+        StatusCode::CONFLICT => ErrorCode::Conflict,
+        StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge,
+        StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED => ErrorCode::PermissionDenied,
+        StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimited,
+        StatusCode::SERVICE_UNAVAILABLE => ErrorCode::Unavailable,
+        StatusCode::PRECONDITION_FAILED | StatusCode::NOT_IMPLEMENTED => {
+            ErrorCode::FailedPrecondition
+        }
+        status if status.is_server_error() => ErrorCode::Internal,
+        _ => ErrorCode::InvalidArgument,
+    }
+}
+
+impl From<CoreError> for ApiError {
+    fn from(err: CoreError) -> Self {
+        let code = err.code();
+        let error = Self::new(status_for_code(code), code, err.to_string());
+        match &err {
+            CoreError::NotFound { entity, id } => error
+                .with_detail("entity", *entity)
+                .with_detail("id", id.as_str()),
+            _ => error,
+        }
+    }
+}
+
+/// Plain `(status, message)` errors get the generic code for their status.
+impl From<(StatusCode, String)> for ApiError {
+    fn from((status, message): (StatusCode, String)) -> Self {
+        Self::new(status, code_for_status(status), message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn render(error: ApiError) -> (StatusCode, Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_core_error_body() {
+        let (status, body) = render(CoreError::not_found("Collection", "c1").into()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "COLLECTION_NOT_FOUND");
+        assert_eq!(body["retryable"], false);
+        assert_eq!(body["details"]["id"], "c1");
+
+        let (status, body) = render(CoreError::StorageError("s3 unavailable".into()).into()).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["code"], "UNAVAILABLE");
+        assert_eq!(body["retryable"], true);
+        assert!(body.get("details").is_none());
+    }
+
+    #[test]
+    fn test_status_tuple_conversion() {
+        let error = ApiError::from((StatusCode::BAD_REQUEST, "bad".to_string()));
+        assert_eq!(error.code(), ErrorCode::InvalidArgument);
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,25 +1,50 @@
 //! Admin REST endpoints for operational management (Phase 7 Week 4)
 //!
-//! Provides 3 critical operational endpoints:
+//! Provides operational endpoints:
 //! 1. GET /admin/health - Comprehensive health check
 //! 2. POST /admin/collections/{id}/dlq/retry - DLQ retry (clear)
 //! 3. POST /admin/circuit-breaker/reset - Circuit breaker reset
-
-use akidb_core::CollectionId;
-use akidb_service::CollectionService;
+//! 4. POST /admin/collections/{id}/compact - Compact WAL into a snapshot
+//! 5. GET /admin/collections/{id}/compaction - Compaction state
+//! 6. POST /admin/collections/{id}/restore - Point-in-time restore
+//! 7. GET /admin/collections/{id}/wal - WAL position and upload backlog
+//! 8. POST /admin/collections/{id}/reindex - Rebuild the index as a job
+//! 9. GET /admin/replication - Replication lag and health
+//! 10. GET /admin/leader - Leader election state
+//! 11. POST /admin/drain - Drain before shutdown
+//! 12. GET /admin/drain - Drain progress
+
+use crate::error::ApiError;
+use akidb_core::{CollectionId, Lease};
+use akidb_service::health::{probe_embedding, ProbeResult, ProbeStatus};
+use akidb_service::{
+    CollectionService, CompactionStatus, DrainConfig, DrainStatus, EmbeddingManager,
+    LeaderElection, ReindexOptions, ReplicationMonitor, ReplicationReport, RestoreReport,
+};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
     Json,
 };
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+
+use super::collections::JobAcceptedResponse;
 
 // ============================================================================
 // Health Check
 // ============================================================================
 
+/// Shared state for `GET /admin/health`.
+pub struct HealthState {
+    pub collection_service: Arc<CollectionService>,
+    /// Present when embeddings are configured
+    pub embedding_manager: Option<Arc<EmbeddingManager>>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
     pub status: String,
@@ -30,15 +55,38 @@ pub struct HealthResponse {
 
 #[derive(Debug, Serialize)]
 pub struct HealthComponents {
+    /// SQLite metadata database, probed with a query
     pub database: ComponentHealth,
+    /// Circuit breaker in front of S3 uploads
     pub storage: ComponentHealth,
     pub memory: ComponentHealth,
+    /// S3 bucket, probed with a head-bucket request
+    pub s3: ComponentHealth,
+    /// WAL directory, probed by writing a file
+    pub wal: ComponentHealth,
+    /// Default embedding model, probed with an inference
+    pub embedding: ComponentHealth,
+}
+
+impl HealthComponents {
+    fn all(&self) -> [&ComponentHealth; 6] {
+        [
+            &self.database,
+            &self.storage,
+            &self.memory,
+            &self.s3,
+            &self.wal,
+            &self.embedding,
+        ]
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub struct ComponentHealth {
     pub status: String,
     pub message: Option<String>,
+    /// Duration of the active probe, for probed components
+    pub latency_ms: Option<f64>,
     pub details: Option<serde_json::Value>,
 }
 
@@ -47,6 +95,7 @@ impl ComponentHealth {
         Self {
             status: "healthy".to_string(),
             message: None,
+            latency_ms: None,
             details: None,
         }
     }
@@ -55,6 +104,7 @@ impl ComponentHealth {
         Self {
             status: "unhealthy".to_string(),
             message: Some(message),
+            latency_ms: None,
             details: None,
         }
     }
@@ -63,6 +113,7 @@ impl ComponentHealth {
         Self {
             status: "degraded".to_string(),
             message: Some(message),
+            latency_ms: None,
             details: None,
         }
     }
@@ -71,19 +122,51 @@ impl ComponentHealth {
         self.details = Some(details);
         self
     }
+
+    /// Maps a probe result; a failed probe of a dependency the server can
+    /// limp along without (S3, embeddings) is only `degraded`.
+    fn from_probe(probe: ProbeResult, critical: bool) -> Self {
+        let status = match probe.status {
+            ProbeStatus::Healthy => "healthy",
+            ProbeStatus::Skipped => "skipped",
+            ProbeStatus::Unhealthy if critical => "unhealthy",
+            ProbeStatus::Unhealthy => "degraded",
+        };
+        Self {
+            status: status.to_string(),
+            message: probe.message,
+            latency_ms: probe.latency_ms,
+            details: None,
+        }
+    }
 }
 
 /// GET /admin/health
 ///
-/// Comprehensive health check for Kubernetes liveness/readiness probes
+/// Comprehensive health check for Kubernetes liveness/readiness probes.
+///
+/// Actively probes SQLite, the S3 bucket, the WAL directory and the embedding
+/// model (concurrently, each bounded by [`PROBE_TIMEOUT`]) and reports each
+/// one's latency. A failing database or WAL directory makes the server
+/// unhealthy (503); a failing bucket or model only degrades it.
+///
+/// [`PROBE_TIMEOUT`]: akidb_service::health::PROBE_TIMEOUT
 pub async fn health_check(
-    State(service): State<Arc<CollectionService>>,
+    State(state): State<Arc<HealthState>>,
 ) -> Result<Json<HealthResponse>, (StatusCode, String)> {
-    // Check database connectivity
-    let database_health = match service.list_collections().await {
-        Ok(_) => ComponentHealth::healthy(),
-        Err(e) => ComponentHealth::unhealthy(format!("Database error: {}", e)),
-    };
+    let service = &state.collection_service;
+
+    let (database_probe, s3_probe, wal_probe, embedding_probe) = tokio::join!(
+        service.probe_database(),
+        service.probe_object_store(),
+        service.probe_wal_dir(),
+        async {
+            match &state.embedding_manager {
+                Some(manager) => probe_embedding(manager).await,
+                None => ProbeResult::skipped("embeddings are not configured"),
+            }
+        }
+    );
 
     // Check storage backend
     let storage_health = match service.get_storage_metrics().await {
@@ -147,16 +230,20 @@ pub async fn health_check(
         Err(e) => ComponentHealth::unhealthy(format!("Memory stats error: {}", e)),
     };
 
+    let components = HealthComponents {
+        database: ComponentHealth::from_probe(database_probe, true),
+        storage: storage_health,
+        memory: memory_health,
+        s3: ComponentHealth::from_probe(s3_probe, false),
+        wal: ComponentHealth::from_probe(wal_probe, true),
+        embedding: ComponentHealth::from_probe(embedding_probe, false),
+    };
+
     // Overall status
-    let overall_status = if database_health.status == "unhealthy"
-        || storage_health.status == "unhealthy"
-        || memory_health.status == "unhealthy"
-    {
+    let has_status = |status: &str| components.all().iter().any(|c| c.status == status);
+    let overall_status = if has_status("unhealthy") {
         "unhealthy"
-    } else if database_health.status == "degraded"
-        || storage_health.status == "degraded"
-        || memory_health.status == "degraded"
-    {
+    } else if has_status("degraded") {
         "degraded"
     } else {
         "healthy"
@@ -168,11 +255,7 @@ pub async fn health_check(
         status: overall_status.to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         uptime_seconds: uptime,
-        components: HealthComponents {
-            database: database_health,
-            storage: storage_health,
-            memory: memory_health,
-        },
+        components,
     };
 
     // Return 503 Service Unavailable if unhealthy
@@ -204,13 +287,8 @@ pub struct DLQRetryResponse {
 pub async fn retry_dlq(
     State(service): State<Arc<CollectionService>>,
     Path(collection_id): Path<String>,
-) -> Result<Json<DLQRetryResponse>, (StatusCode, String)> {
-    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid collection ID: {}", e),
-        )
-    })?;
+) -> Result<Json<DLQRetryResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
 
     match service.retry_dlq_entries(collection_id).await {
         Ok(result) => Ok(Json(DLQRetryResponse {
@@ -219,10 +297,7 @@ pub async fn retry_dlq(
             success_count: result.succeeded,
             failed_count: result.failed,
         })),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("DLQ retry failed: {}", e),
-        )),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -243,7 +318,7 @@ pub struct CircuitBreakerResetResponse {
 /// Reset circuit breaker to Closed state (emergency recovery)
 pub async fn reset_circuit_breaker(
     State(service): State<Arc<CollectionService>>,
-) -> Result<Json<CircuitBreakerResetResponse>, (StatusCode, String)> {
+) -> Result<Json<CircuitBreakerResetResponse>, ApiError> {
     match service.reset_circuit_breaker().await {
         Ok(previous_state) => Ok(Json(CircuitBreakerResetResponse {
             status: "success".to_string(),
@@ -251,13 +326,287 @@ pub async fn reset_circuit_breaker(
             previous_state: format!("{:?}", previous_state),
             new_state: "Closed".to_string(),
         })),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Circuit breaker reset failed: {}", e),
-        )),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// ============================================================================
+// Compaction
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct CompactionResponse {
+    pub collection_id: String,
+    pub last_compaction_at: Option<String>,
+    pub compactions: u64,
+    pub wal_size_bytes: u64,
+    pub wal_operations: u64,
+    pub threshold_bytes: u64,
+    pub threshold_ops: u64,
+    pub thresholds_exceeded: bool,
+}
+
+impl CompactionResponse {
+    fn new(collection_id: CollectionId, status: CompactionStatus) -> Self {
+        Self {
+            collection_id: collection_id.to_string(),
+            last_compaction_at: status.last_compaction_at.map(|t| t.to_rfc3339()),
+            compactions: status.compactions,
+            wal_size_bytes: status.wal_size_bytes,
+            wal_operations: status.wal_operations,
+            threshold_bytes: status.threshold_bytes,
+            threshold_ops: status.threshold_ops,
+            thresholds_exceeded: status.thresholds_exceeded,
+        }
     }
 }
 
+fn parse_collection_id(collection_id: &str) -> Result<CollectionId, ApiError> {
+    CollectionId::from_str(collection_id)
+        .map_err(|e| ApiError::invalid_argument(format!("Invalid collection ID: {}", e)))
+}
+
+/// POST /admin/collections/{id}/compact
+///
+/// Compact the collection's WAL into a snapshot now (blocks until done)
+pub async fn compact_collection(
+    State(service): State<Arc<CollectionService>>,
+    Path(collection_id): Path<String>,
+) -> Result<Json<CompactionResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
+    let status = service.compact_collection(collection_id).await?;
+    Ok(Json(CompactionResponse::new(collection_id, status)))
+}
+
+/// GET /admin/collections/{id}/compaction
+///
+/// Last compaction time, WAL growth since then, and whether thresholds are exceeded
+pub async fn get_compaction_status(
+    State(service): State<Arc<CollectionService>>,
+    Path(collection_id): Path<String>,
+) -> Result<Json<CompactionResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
+    let status = service.compaction_status(collection_id).await?;
+    Ok(Json(CompactionResponse::new(collection_id, status)))
+}
+
+// ============================================================================
+// Point-in-time Restore
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct RestoreRequest {
+    /// Point in time to restore to (RFC 3339)
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreResponse {
+    pub collection_id: String,
+    #[serde(flatten)]
+    pub report: RestoreReport,
+}
+
+/// POST /admin/collections/{id}/restore
+///
+/// Restore the collection's documents to their state at `timestamp`, from
+/// the latest snapshot before it plus the WAL (blocks until done, with writes
+/// held back). Requires an API key with the `admin::restore` permission.
+pub async fn restore_collection(
+    State(service): State<Arc<CollectionService>>,
+    Path(collection_id): Path<String>,
+    Json(request): Json<RestoreRequest>,
+) -> Result<Json<RestoreResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
+    let report = service
+        .restore_collection(collection_id, request.timestamp)
+        .await?;
+    Ok(Json(RestoreResponse {
+        collection_id: collection_id.to_string(),
+        report,
+    }))
+}
+
+// ============================================================================
+// Reindex
+// ============================================================================
+
+/// Settings for the rebuilt index; omitted fields keep their current value
+#[derive(Debug, Default, Deserialize)]
+pub struct ReindexRequest {
+    pub hnsw_m: Option<u32>,
+    pub hnsw_ef_construction: Option<u32>,
+    /// Collections of up to 10,000 documents use brute-force search
+    pub max_doc_count: Option<u64>,
+}
+
+/// POST /admin/collections/{id}/reindex
+///
+/// Rebuilds the collection's index in the background and swaps it in; the
+/// current index serves requests until then. Poll `GET /admin/jobs/{job_id}`
+/// for progress. Requires an API key with the `admin::reindex` permission
+/// (and `admin::jobs` to poll the job).
+pub async fn reindex_collection(
+    State(service): State<Arc<CollectionService>>,
+    Path(collection_id): Path<String>,
+    request: Option<Json<ReindexRequest>>,
+) -> Result<(StatusCode, Json<JobAcceptedResponse>), ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
+    let Json(request) = request.unwrap_or_default();
+    let options = ReindexOptions {
+        hnsw_m: request.hnsw_m,
+        hnsw_ef_construction: request.hnsw_ef_construction,
+        max_doc_count: request.max_doc_count,
+    };
+    let job_id = service.reindex_collection(collection_id, options).await?;
+    tracing::info!(
+        "🔁 Reindex of collection {} started as job {}",
+        collection_id,
+        job_id
+    );
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(JobAcceptedResponse {
+            job_id: job_id.to_string(),
+            status_url: format!("/admin/jobs/{}", job_id),
+        }),
+    ))
+}
+
+// ============================================================================
+// WAL State
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct WalStateResponse {
+    pub collection_id: String,
+    pub current_lsn: u64,
+    pub checkpoint_lsn: u64,
+    /// Entries written since the last checkpoint
+    pub lsn_lag: u64,
+    pub file_count: usize,
+    pub disk_bytes: u64,
+    pub pending_uploads: usize,
+    pub pending_retries: usize,
+    pub dlq_size: usize,
+}
+
+/// GET /admin/collections/{id}/wal
+///
+/// WAL position, on-disk footprint, and S3 upload/retry backlog
+pub async fn get_wal_state(
+    State(service): State<Arc<CollectionService>>,
+    Path(collection_id): Path<String>,
+) -> Result<Json<WalStateResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
+    let state = service.wal_state(collection_id).await?;
+
+    Ok(Json(WalStateResponse {
+        collection_id: collection_id.to_string(),
+        current_lsn: state.current_lsn,
+        checkpoint_lsn: state.checkpoint_lsn,
+        lsn_lag: state.current_lsn.saturating_sub(state.checkpoint_lsn),
+        file_count: state.file_count,
+        disk_bytes: state.disk_bytes,
+        pending_uploads: state.pending_uploads,
+        pending_retries: state.pending_retries,
+        dlq_size: state.dlq_size,
+    }))
+}
+
+/// GET /admin/replication
+///
+/// Role, lag behind the primary, and per-collection WAL positions
+pub async fn get_replication_status(
+    State(monitor): State<Arc<ReplicationMonitor>>,
+) -> Json<ReplicationReport> {
+    Json(monitor.report().await)
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaderResponse {
+    pub enabled: bool,
+    /// Identifier this process campaigns under
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub holder: Option<String>,
+    pub is_leader: bool,
+    /// Current lease, whichever process holds it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lease: Option<Lease>,
+}
+
+/// GET /admin/leader
+///
+/// Whether this process runs singleton maintenance, and who holds the lease
+pub async fn get_leader_status(
+    State(election): State<Option<Arc<LeaderElection>>>,
+) -> Result<Json<LeaderResponse>, ApiError> {
+    let Some(election) = election else {
+        return Ok(Json(LeaderResponse {
+            enabled: false,
+            holder: None,
+            is_leader: false,
+            lease: None,
+        }));
+    };
+
+    Ok(Json(LeaderResponse {
+        enabled: true,
+        holder: Some(election.holder().to_string()),
+        is_leader: election.is_leader(),
+        lease: election.current().await?,
+    }))
+}
+
+// ============================================================================
+// Drain
+// ============================================================================
+
+/// Shared state for `/admin/drain`.
+pub struct DrainState {
+    pub collection_service: Arc<CollectionService>,
+    pub config: DrainConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DrainRequest {
+    /// Overrides `drain.grace_period_seconds`
+    pub grace_period_seconds: Option<u64>,
+}
+
+/// POST /admin/drain
+///
+/// Starts draining in the background and returns its progress right away;
+/// poll `GET /admin/drain` until `stage` is `drained`, then shut down.
+/// Starting a drain that is already under way changes nothing.
+pub async fn start_drain(
+    State(state): State<Arc<DrainState>>,
+    request: Option<Json<DrainRequest>>,
+) -> (StatusCode, Json<DrainStatus>) {
+    let Json(request) = request.unwrap_or_default();
+    let grace_period = Duration::from_secs(
+        request
+            .grace_period_seconds
+            .unwrap_or(state.config.grace_period_seconds),
+    );
+    let timeout = Duration::from_secs(state.config.timeout_seconds);
+
+    let service = Arc::clone(&state.collection_service);
+    tokio::spawn(async move { service.drain(grace_period, timeout).await });
+    // Let the drain start, so the response reflects it
+    tokio::task::yield_now().await;
+
+    (
+        StatusCode::ACCEPTED,
+        Json(state.collection_service.drain_controller().status()),
+    )
+}
+
+/// GET /admin/drain
+pub async fn get_drain_status(State(state): State<Arc<DrainState>>) -> Json<DrainStatus> {
+    Json(state.collection_service.drain_controller().status())
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -276,6 +625,9 @@ mod tests {
                 database: ComponentHealth::healthy(),
                 storage: ComponentHealth::healthy(),
                 memory: ComponentHealth::healthy(),
+                s3: ComponentHealth::healthy(),
+                wal: ComponentHealth::healthy(),
+                embedding: ComponentHealth::healthy(),
             },
         };
 
@@ -299,6 +651,23 @@ mod tests {
         assert_eq!(degraded.message, Some("High latency".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_component_health_from_probe() {
+        let failed = || ProbeResult::run(async { Err("unreachable") });
+
+        let critical = ComponentHealth::from_probe(failed().await, true);
+        assert_eq!(critical.status, "unhealthy");
+        assert_eq!(critical.message, Some("unreachable".to_string()));
+        assert!(critical.latency_ms.is_some());
+
+        let optional = ComponentHealth::from_probe(failed().await, false);
+        assert_eq!(optional.status, "degraded");
+
+        let skipped = ComponentHealth::from_probe(ProbeResult::skipped("not configured"), true);
+        assert_eq!(skipped.status, "skipped");
+        assert!(skipped.latency_ms.is_none());
+    }
+
     #[test]
     fn test_dlq_retry_response_structure() {
         let response = DLQRetryResponse {
@@ -325,4 +694,37 @@ mod tests {
         assert_eq!(response.previous_state, "Open");
         assert_eq!(response.new_state, "Closed");
     }
+
+    #[test]
+    fn test_restore_request_parses_rfc3339() {
+        let request: RestoreRequest =
+            serde_json::from_str(r#"{"timestamp": "2025-03-01T12:30:00+02:00"}"#).unwrap();
+        assert_eq!(request.timestamp.to_rfc3339(), "2025-03-01T10:30:00+00:00");
+
+        assert!(serde_json::from_str::<RestoreRequest>(r#"{"timestamp": "yesterday"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_drain_fails_readiness() {
+        let state = Arc::new(DrainState {
+            collection_service: Arc::new(CollectionService::new()),
+            config: DrainConfig::default(),
+        });
+        assert!(state.collection_service.is_ready().await);
+
+        let request = DrainRequest {
+            grace_period_seconds: Some(0),
+        };
+        let (status, _) = start_drain(State(Arc::clone(&state)), Some(Json(request))).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(!state.collection_service.is_ready().await);
+
+        let drain = Arc::clone(state.collection_service.drain_controller());
+        tokio::time::timeout(Duration::from_secs(5), drain.drained())
+            .await
+            .unwrap();
+        let Json(status) = get_drain_status(State(state)).await;
+        assert_eq!(status.stage, akidb_service::DrainStage::Drained);
+        assert!(!status.timed_out);
+    }
 }
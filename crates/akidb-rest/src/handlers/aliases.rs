@@ -0,0 +1,142 @@
+//! Collection aliases.
+//!
+//! - GET /admin/aliases - Every alias of the default database
+//! - GET /admin/aliases/:name - One alias
+//! - PUT /admin/aliases/:name - Point an alias at a collection, creating it
+//!   or atomically swapping its target
+//! - DELETE /admin/aliases/:name - Delete an alias (the collection is kept)
+//!
+//! PUT and DELETE require an API key with the `admin::aliases` permission.
+//!
+//! Collection endpoints under `/api/v1` and `/api/v2` accept an alias
+//! wherever they take a collection ID (see [`crate::middleware::AliasLayer`]).
+
+use crate::error::ApiError;
+use akidb_core::{CollectionAlias, CoreError};
+use akidb_service::CollectionService;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::v2::parse_collection_id;
+
+#[derive(Debug, Serialize)]
+pub struct AliasesResponse {
+    pub aliases: Vec<CollectionAlias>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetAliasRequest {
+    /// Collection the alias should resolve to
+    pub collection_id: String,
+}
+
+/// GET /admin/aliases
+pub async fn list_aliases(
+    State(service): State<Arc<CollectionService>>,
+) -> Result<Json<AliasesResponse>, ApiError> {
+    Ok(Json(AliasesResponse {
+        aliases: service.list_aliases().await?,
+    }))
+}
+
+/// GET /admin/aliases/:name
+pub async fn get_alias(
+    State(service): State<Arc<CollectionService>>,
+    Path(name): Path<String>,
+) -> Result<Json<CollectionAlias>, ApiError> {
+    let alias = service
+        .get_alias(&name)
+        .await?
+        .ok_or_else(|| CoreError::not_found("Alias", name))?;
+    Ok(Json(alias))
+}
+
+/// PUT /admin/aliases/:name
+pub async fn set_alias(
+    State(service): State<Arc<CollectionService>>,
+    Path(name): Path<String>,
+    Json(request): Json<SetAliasRequest>,
+) -> Result<Json<CollectionAlias>, ApiError> {
+    let collection_id = parse_collection_id(&request.collection_id)?;
+    let alias = service.set_alias(&name, collection_id).await?;
+    tracing::info!(
+        "🔀 Alias '{}' now points to collection {}",
+        alias.name,
+        alias.collection_id
+    );
+    Ok(Json(alias))
+}
+
+/// DELETE /admin/aliases/:name
+pub async fn delete_alias(
+    State(service): State<Arc<CollectionService>>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if !service.remove_alias(&name).await? {
+        return Err(CoreError::not_found("Alias", name).into());
+    }
+    tracing::info!("🔀 Alias '{}' deleted", name);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::{DatabaseId, DistanceMetric};
+
+    #[tokio::test]
+    async fn test_set_swap_and_delete_alias() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let blue = service
+            .create_collection("docs-blue".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+        let green = service
+            .create_collection("docs-green".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+
+        for target in [blue, green] {
+            let Json(alias) = set_alias(
+                State(Arc::clone(&service)),
+                Path("prod-docs".to_string()),
+                Json(SetAliasRequest {
+                    collection_id: target.to_string(),
+                }),
+            )
+            .await
+            .unwrap();
+            assert_eq!(alias.collection_id, target);
+            assert_eq!(
+                service.resolve_collection("prod-docs").await.unwrap(),
+                target
+            );
+        }
+
+        // The alias target cannot be deleted out from under its clients
+        assert!(service.delete_collection(green).await.is_err());
+        service.delete_collection(blue).await.unwrap();
+
+        let Json(response) = list_aliases(State(Arc::clone(&service))).await.unwrap();
+        assert_eq!(response.aliases.len(), 1);
+
+        let status = delete_alias(State(Arc::clone(&service)), Path("prod-docs".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(
+            get_alias(State(Arc::clone(&service)), Path("prod-docs".to_string()))
+                .await
+                .is_err()
+        );
+        assert!(service.resolve_collection("prod-docs").await.is_err());
+        service.delete_collection(green).await.unwrap();
+    }
+}
@@ -0,0 +1,299 @@
+//! NDJSON streaming bulk upsert (`POST /api/v1/collections/:id/bulk`).
+//!
+//! The request body is read incrementally: complete lines are parsed as they
+//! arrive and flushed to the service in batches of `limits.max_batch_vectors`.
+//! The body is not polled while a batch is being written, so a fast client is
+//! slowed down to the rate the collection can absorb instead of buffering the
+//! whole import in memory.
+
+use crate::error::ApiError;
+use crate::validation::validation_error_response;
+use akidb_core::{CollectionId, DocumentId, SparseVector, VectorDocument};
+use akidb_service::{validation, CollectionService};
+use axum::{
+    body::Bytes,
+    extract::{BodyStream, Path, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+/// Maximum number of per-line errors echoed back in the response.
+const MAX_REPORTED_ERRORS: usize = 100;
+
+/// One NDJSON line.
+#[derive(Deserialize)]
+struct BulkDocument {
+    /// Existing ID to replace; a new ID is generated when absent
+    #[serde(default)]
+    doc_id: Option<String>,
+    #[serde(default)]
+    external_id: Option<String>,
+    #[serde(default)]
+    vector: Vec<f32>,
+    /// Sparse vector (collections with sparse vectors)
+    #[serde(default)]
+    sparse: Option<SparseVector>,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+pub struct BulkLineError {
+    /// 1-based line number in the request body
+    line: usize,
+    error: String,
+}
+
+#[derive(Serialize)]
+pub struct BulkUpsertResponse {
+    upserted: usize,
+    failed: usize,
+    /// First errors only, capped at `MAX_REPORTED_ERRORS`
+    errors: Vec<BulkLineError>,
+    latency_ms: f64,
+}
+
+/// Accumulates parsed documents and per-line outcomes.
+struct BulkState {
+    service: Arc<CollectionService>,
+    collection_id: CollectionId,
+    batch: Vec<(usize, VectorDocument)>,
+    upserted: usize,
+    failed: usize,
+    errors: Vec<BulkLineError>,
+}
+
+impl BulkState {
+    fn fail(&mut self, line: usize, error: String) {
+        self.failed += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(BulkLineError { line, error });
+        }
+    }
+
+    fn parse_line(&mut self, line_no: usize, line: &[u8]) {
+        // Blank lines (including a trailing `\r\n`) are skipped
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return;
+        }
+
+        let parsed: BulkDocument = match serde_json::from_slice(line) {
+            Ok(parsed) => parsed,
+            Err(e) => return self.fail(line_no, format!("Invalid JSON: {}", e)),
+        };
+
+        let doc_id = match parsed.doc_id.as_deref().map(DocumentId::from_str) {
+            Some(Ok(doc_id)) => doc_id,
+            Some(Err(e)) => return self.fail(line_no, format!("Invalid doc_id: {}", e)),
+            None => DocumentId::new(),
+        };
+
+        if parsed.vector.is_empty() && parsed.sparse.is_none() {
+            return self.fail(line_no, "vector cannot be empty".to_string());
+        }
+
+        let mut doc = VectorDocument::new(doc_id, parsed.vector);
+        if let Some(sparse) = parsed.sparse {
+            doc = doc.with_sparse(sparse);
+        }
+        if let Some(external_id) = parsed.external_id {
+            doc = doc.with_external_id(external_id);
+        }
+        if let Some(metadata) = parsed.metadata {
+            doc = doc.with_metadata(metadata);
+        }
+
+        if let Err(e) = validation::validate_document(&doc, self.service.limits()) {
+            return self.fail(line_no, e.to_string());
+        }
+
+        self.batch.push((line_no, doc));
+    }
+
+    async fn flush(&mut self) -> Result<(), ApiError> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+
+        let (lines, docs): (Vec<_>, Vec<_>) = std::mem::take(&mut self.batch).into_iter().unzip();
+        let results = self.service.upsert_batch(self.collection_id, docs).await?;
+
+        for (line, result) in lines.into_iter().zip(results) {
+            match result {
+                Ok(_) => self.upserted += 1,
+                Err(e) => self.fail(line, e.to_string()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Stream newline-delimited JSON documents into a collection.
+///
+/// Each line is `{"doc_id"?, "external_id"?, "vector", "sparse"?, "metadata"?}`,
+/// where documents of sparse collections give `sparse` instead of `vector`.
+/// Malformed or rejected lines are reported per line and do not abort the
+/// import; a missing collection or a single line longer than
+/// `limits.max_body_bytes` does.
+#[tracing::instrument(skip(service, body), fields(collection_id = %collection_id))]
+pub async fn bulk_upsert(
+    Path(collection_id): Path<String>,
+    State(service): State<Arc<CollectionService>>,
+    mut body: BodyStream,
+) -> Result<Json<BulkUpsertResponse>, Response> {
+    let start = std::time::Instant::now();
+
+    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
+        ApiError::invalid_argument(format!("Invalid collection_id: {}", e)).into_response()
+    })?;
+
+    let limits = service.limits().clone();
+    let mut state = BulkState {
+        service,
+        collection_id,
+        batch: Vec::with_capacity(limits.max_batch_vectors),
+        upserted: 0,
+        failed: 0,
+        errors: Vec::new(),
+    };
+
+    let mut pending = Vec::new();
+    let mut line_no = 0;
+    while let Some(chunk) = body.next().await {
+        let chunk: Bytes = chunk.map_err(|e| {
+            ApiError::invalid_argument(format!("Failed to read body: {}", e)).into_response()
+        })?;
+        pending.extend_from_slice(&chunk);
+
+        let mut consumed = 0;
+        while let Some(newline) = pending[consumed..].iter().position(|&b| b == b'\n') {
+            line_no += 1;
+            state.parse_line(line_no, &pending[consumed..consumed + newline]);
+            consumed += newline + 1;
+
+            if state.batch.len() >= limits.max_batch_vectors {
+                state.flush().await.map_err(IntoResponse::into_response)?;
+            }
+        }
+        pending.drain(..consumed);
+
+        if pending.len() > limits.max_body_bytes {
+            let err = validation::ValidationError::DocumentTooLarge {
+                limit: limits.max_body_bytes,
+                actual: pending.len(),
+            };
+            return Err(validation_error_response(&err));
+        }
+    }
+
+    // Trailing line without a newline
+    if !pending.is_empty() {
+        line_no += 1;
+        state.parse_line(line_no, &pending);
+    }
+    state.flush().await.map_err(IntoResponse::into_response)?;
+
+    Ok(Json(BulkUpsertResponse {
+        upserted: state.upserted,
+        failed: state.failed,
+        errors: state.errors,
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::{DatabaseId, DistanceMetric};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        routing::post,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_bulk_upsert_ndjson() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection("bulk".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+
+        let app = Router::new()
+            .route("/api/v1/collections/:id/bulk", post(bulk_upsert))
+            .with_state(Arc::clone(&service));
+
+        let vector = serde_json::json!(vec![0.1f32; 16]);
+        let body = format!(
+            "{}\n\nnot json\n{}\n{}",
+            serde_json::json!({"vector": vector, "external_id": "a"}),
+            serde_json::json!({"vector": [0.1, 0.2]}),
+            serde_json::json!({"vector": vector}),
+        );
+
+        let response = app
+            .oneshot(
+                Request::post(format!("/api/v1/collections/{}/bulk", collection_id))
+                    .header("content-type", "application/x-ndjson")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["upserted"], 2);
+        assert_eq!(json["failed"], 2);
+        assert_eq!(json["errors"][0]["line"], 3);
+        assert_eq!(json["errors"][1]["line"], 4);
+        assert_eq!(service.get_count(collection_id).await.unwrap(), 2);
+
+        service.delete_collection(collection_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bulk_upsert_error_body() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        let app = Router::new()
+            .route("/api/v1/collections/:id/bulk", post(bulk_upsert))
+            .with_state(service);
+
+        let send = |collection_id: String| {
+            app.clone().oneshot(
+                Request::post(format!("/api/v1/collections/{}/bulk", collection_id))
+                    .header("content-type", "application/x-ndjson")
+                    .body(Body::from(
+                        serde_json::json!({"vector": [0.1, 0.2]}).to_string(),
+                    ))
+                    .unwrap(),
+            )
+        };
+
+        let missing = CollectionId::new().to_string();
+        let response = send(missing.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: crate::error::ErrorResponse = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body.code, akidb_core::ErrorCode::CollectionNotFound);
+        assert!(!body.retryable);
+        assert_eq!(body.details["id"], missing.as_str());
+
+        let response = send("not-a-uuid".to_string()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: crate::error::ErrorResponse = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body.code, akidb_core::ErrorCode::InvalidArgument);
+    }
+}
@@ -1,8 +1,16 @@
-use akidb_core::{CollectionId, DocumentId, VectorDocument};
-use akidb_service::CollectionService;
+use super::v2::{
+    insert_document, parse_collection_id, search_collection, SearchHybrid, SearchRequest,
+};
+use crate::error::ApiError;
+use crate::validation::validation_error_response;
+use akidb_core::{
+    CollectionId, CoreError, CoreResult, DocumentId, JobDescriptor, SparseVector, VectorDocument,
+};
+use akidb_service::{validation, BatchDeleteStatus, CollectionService, FilterTree, JobHandle};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
@@ -11,8 +19,28 @@ use std::sync::Arc;
 
 #[derive(Deserialize)]
 pub struct QueryRequest {
+    #[serde(default)]
     query_vector: Vec<f32>,
+    /// Sparse query vector, ranked by dot product in place of `query_vector`
+    #[serde(default)]
+    sparse_query: Option<SparseVector>,
     top_k: usize,
+    /// Metadata filter, e.g. `{"category": "news", "year": {"$gte": 2020}}`
+    #[serde(default)]
+    filter: Option<serde_json::Value>,
+    /// Drop matches scoring worse than this (metric-dependent direction)
+    #[serde(default)]
+    score_threshold: Option<f32>,
+    #[serde(default)]
+    include_payload: bool,
+    /// Return only these top-level payload keys (all when absent)
+    #[serde(default)]
+    payload_fields: Option<Vec<String>>,
+    #[serde(default)]
+    include_vector: bool,
+    /// Fuse with BM25 keyword matches over payload text
+    #[serde(default)]
+    hybrid: Option<SearchHybrid>,
 }
 
 #[derive(Serialize)]
@@ -26,47 +54,48 @@ pub struct MatchResult {
     doc_id: String,
     external_id: Option<String>,
     distance: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vector: Option<Vec<f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sparse: Option<SparseVector>,
 }
 
+/// Similarity search (`POST /api/v1/collections/:id/query`).
+///
+/// Adapter over the v2 search; v1 omits metadata unless asked and reports
+/// the score as `distance`.
 #[tracing::instrument(skip(service, req), fields(collection_id = %collection_id, top_k = req.top_k))]
 pub async fn query_vectors(
     Path(collection_id): Path<String>,
     State(service): State<Arc<CollectionService>>,
     Json(req): Json<QueryRequest>,
-) -> Result<Json<QueryResponse>, (StatusCode, String)> {
+) -> Result<Json<QueryResponse>, ApiError> {
     let start = std::time::Instant::now();
 
-    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid collection_id: {}", e),
-        )
-    })?;
-
-    if req.query_vector.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            "query_vector cannot be empty".to_string(),
-        ));
-    }
-
-    let results = service
-        .query(collection_id, req.query_vector, req.top_k)
-        .await
-        .map_err(|e| {
-            if e.to_string().contains("not found") {
-                (StatusCode::NOT_FOUND, e.to_string())
-            } else {
-                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-            }
-        })?;
-
-    let matches = results
+    let search = SearchRequest {
+        vector: req.query_vector,
+        sparse: req.sparse_query,
+        top_k: req.top_k,
+        filter: req.filter,
+        score_threshold: req.score_threshold,
+        include_payload: req.include_payload,
+        payload_fields: req.payload_fields,
+        include_vector: req.include_vector,
+        rerank: None,
+        hybrid: req.hybrid,
+    };
+    let matches = search_collection(&service, &collection_id, search)
+        .await?
         .into_iter()
         .map(|r| MatchResult {
             doc_id: r.doc_id.to_string(),
             external_id: r.external_id,
             distance: r.score,
+            metadata: r.metadata,
+            vector: r.vector,
+            sparse: r.sparse,
         })
         .collect();
 
@@ -80,7 +109,13 @@ pub async fn query_vectors(
 pub struct InsertRequest {
     doc_id: String,
     external_id: Option<String>,
+    #[serde(default)]
     vector: Vec<f32>,
+    /// Sparse vector (collections with sparse vectors)
+    #[serde(default)]
+    sparse: Option<SparseVector>,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
 }
 
 #[derive(Serialize)]
@@ -89,43 +124,33 @@ pub struct InsertResponse {
     latency_ms: f64,
 }
 
+/// Insert a document (`POST /api/v1/collections/:id/insert`).
+///
+/// Adapter over the v2 document insert; v1 requires the caller to supply `doc_id`.
 #[tracing::instrument(skip(service, req), fields(collection_id = %collection_id, doc_id = %req.doc_id))]
 pub async fn insert_vector(
     Path(collection_id): Path<String>,
     State(service): State<Arc<CollectionService>>,
     Json(req): Json<InsertRequest>,
-) -> Result<Json<InsertResponse>, (StatusCode, String)> {
+) -> Result<Json<InsertResponse>, Response> {
     let start = std::time::Instant::now();
 
-    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid collection_id: {}", e),
-        )
+    let doc_id = DocumentId::from_str(&req.doc_id).map_err(|e| {
+        ApiError::invalid_argument(format!("Invalid doc_id: {}", e)).into_response()
     })?;
 
-    let doc_id = DocumentId::from_str(&req.doc_id)
-        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid doc_id: {}", e)))?;
-
-    if req.vector.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            "vector cannot be empty".to_string(),
-        ));
-    }
-
     let mut doc = VectorDocument::new(doc_id, req.vector);
+    if let Some(sparse) = req.sparse {
+        doc = doc.with_sparse(sparse);
+    }
     if let Some(external_id) = req.external_id {
         doc = doc.with_external_id(external_id);
     }
+    if let Some(metadata) = req.metadata {
+        doc = doc.with_metadata(metadata);
+    }
 
-    let inserted_id = service.insert(collection_id, doc).await.map_err(|e| {
-        if e.to_string().contains("not found") {
-            (StatusCode::NOT_FOUND, e.to_string())
-        } else {
-            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
-    })?;
+    let inserted_id = insert_document(&service, &collection_id, doc).await?;
 
     Ok(Json(InsertResponse {
         doc_id: inserted_id.to_string(),
@@ -133,6 +158,158 @@ pub async fn insert_vector(
     }))
 }
 
+#[derive(Deserialize)]
+pub struct InsertBatchRequest {
+    documents: Vec<InsertBatchDocument>,
+}
+
+#[derive(Deserialize)]
+pub struct InsertBatchDocument {
+    /// Document ID; generated when absent
+    #[serde(default)]
+    doc_id: Option<String>,
+    #[serde(default)]
+    external_id: Option<String>,
+    #[serde(default)]
+    vector: Vec<f32>,
+    /// Sparse vector (collections with sparse vectors)
+    #[serde(default)]
+    sparse: Option<SparseVector>,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+pub struct InsertBatchResult {
+    /// Document ID, as given in the request or generated
+    doc_id: String,
+    /// One of `inserted`, `invalid`, `already_exists`, `error`
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl InsertBatchResult {
+    fn failed(doc_id: String, error: CoreError) -> Self {
+        let status = match error {
+            CoreError::ValidationError(_) => "invalid",
+            CoreError::AlreadyExists { .. } => "already_exists",
+            _ => "error",
+        };
+        Self {
+            doc_id,
+            status,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct InsertBatchResponse {
+    results: Vec<InsertBatchResult>,
+    inserted: usize,
+    latency_ms: f64,
+}
+
+/// Insert many documents (`POST /api/v1/collections/:id/insert_batch`).
+///
+/// Accepts up to `limits.max_batch_vectors` documents, written to the WAL
+/// with a single batch append. Results are returned per document in request
+/// order; an invalid or duplicate document does not fail the batch.
+#[tracing::instrument(skip(service, req), fields(collection_id = %collection_id, count = req.documents.len()))]
+pub async fn insert_batch(
+    Path(collection_id): Path<String>,
+    State(service): State<Arc<CollectionService>>,
+    Json(req): Json<InsertBatchRequest>,
+) -> Result<Json<InsertBatchResponse>, Response> {
+    let start = std::time::Instant::now();
+
+    let collection_id = parse_collection_id(&collection_id).map_err(IntoResponse::into_response)?;
+
+    if req.documents.is_empty() {
+        return Err(ApiError::invalid_argument("documents must not be empty").into_response());
+    }
+    validation::validate_batch_size(req.documents.len(), service.limits())
+        .map_err(|e| validation_error_response(&e))?;
+
+    // Results are filled in request order; `pending` tracks which slots
+    // are waiting on the service batch insert.
+    let mut results = Vec::with_capacity(req.documents.len());
+    let mut pending = Vec::new();
+    let mut docs = Vec::new();
+    for document in req.documents {
+        let doc_id = match document.doc_id.as_deref().map(DocumentId::from_str) {
+            Some(Ok(doc_id)) => doc_id,
+            Some(Err(e)) => {
+                let error = CoreError::ValidationError(format!("Invalid doc_id: {}", e));
+                results.push(InsertBatchResult::failed(
+                    document.doc_id.unwrap_or_default(),
+                    error,
+                ));
+                continue;
+            }
+            None => DocumentId::new(),
+        };
+
+        let mut doc = VectorDocument::new(doc_id, document.vector);
+        if let Some(sparse) = document.sparse {
+            doc = doc.with_sparse(sparse);
+        }
+        if let Some(external_id) = document.external_id {
+            doc = doc.with_external_id(external_id);
+        }
+        if let Some(metadata) = document.metadata {
+            doc = doc.with_metadata(metadata);
+        }
+
+        let invalid = if doc.vector.is_empty() && doc.sparse.is_none() {
+            Some("vector cannot be empty".to_string())
+        } else {
+            validation::validate_document(&doc, service.limits())
+                .err()
+                .map(|e| e.to_string())
+        };
+        if let Some(error) = invalid {
+            results.push(InsertBatchResult::failed(
+                doc_id.to_string(),
+                CoreError::ValidationError(error),
+            ));
+            continue;
+        }
+
+        pending.push(results.len());
+        results.push(InsertBatchResult {
+            doc_id: doc_id.to_string(),
+            status: "pending",
+            error: None,
+        });
+        docs.push(doc);
+    }
+
+    if !docs.is_empty() {
+        let statuses = service
+            .insert_batch(collection_id, docs)
+            .await
+            .map_err(|e| ApiError::from(e).into_response())?;
+        for (slot, status) in pending.into_iter().zip(statuses) {
+            match status {
+                Ok(_) => results[slot].status = "inserted",
+                Err(e) => {
+                    let doc_id = std::mem::take(&mut results[slot].doc_id);
+                    results[slot] = InsertBatchResult::failed(doc_id, e);
+                }
+            }
+        }
+    }
+    let inserted = results.iter().filter(|r| r.status == "inserted").count();
+
+    Ok(Json(InsertBatchResponse {
+        results,
+        inserted,
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+    }))
+}
+
 #[derive(Serialize)]
 pub struct GetResponse {
     document: Option<VectorDocumentResponse>,
@@ -149,24 +326,13 @@ pub struct VectorDocumentResponse {
 pub async fn get_vector(
     Path((collection_id, doc_id)): Path<(String, String)>,
     State(service): State<Arc<CollectionService>>,
-) -> Result<Json<GetResponse>, (StatusCode, String)> {
-    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid collection_id: {}", e),
-        )
-    })?;
+) -> Result<Json<GetResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
 
     let doc_id = DocumentId::from_str(&doc_id)
-        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid doc_id: {}", e)))?;
+        .map_err(|e| ApiError::invalid_argument(format!("Invalid doc_id: {}", e)))?;
 
-    let doc = service.get(collection_id, doc_id).await.map_err(|e| {
-        if e.to_string().contains("not found") {
-            (StatusCode::NOT_FOUND, e.to_string())
-        } else {
-            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
-    })?;
+    let doc = service.get(collection_id, doc_id).await?;
 
     let document = doc.map(|d| VectorDocumentResponse {
         doc_id: d.doc_id.to_string(),
@@ -186,32 +352,292 @@ pub struct DeleteResponse {
 pub async fn delete_vector(
     Path((collection_id, doc_id)): Path<(String, String)>,
     State(service): State<Arc<CollectionService>>,
-) -> Result<Json<DeleteResponse>, (StatusCode, String)> {
+) -> Result<Json<DeleteResponse>, ApiError> {
     let start = std::time::Instant::now();
 
-    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid collection_id: {}", e),
-        )
-    })?;
+    let collection_id = parse_collection_id(&collection_id)?;
 
     let doc_id = DocumentId::from_str(&doc_id)
-        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid doc_id: {}", e)))?;
+        .map_err(|e| ApiError::invalid_argument(format!("Invalid doc_id: {}", e)))?;
 
-    service.delete(collection_id, doc_id).await.map_err(|e| {
-        if e.to_string().contains("not found") {
-            (StatusCode::NOT_FOUND, e.to_string())
-        } else {
-            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
-    })?;
+    service.delete(collection_id, doc_id).await?;
 
     Ok(Json(DeleteResponse {
         latency_ms: start.elapsed().as_secs_f64() * 1000.0,
     }))
 }
 
+#[derive(Deserialize)]
+pub struct BatchDeleteRequest {
+    #[serde(default)]
+    ids: Vec<String>,
+    #[serde(default)]
+    external_ids: Vec<String>,
+    /// Metadata filter selecting the documents (`:deleteByFilter` only)
+    #[serde(default)]
+    filter: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+pub struct BatchDeleteResult {
+    /// Document ID or external ID, as given in the request
+    id: String,
+    /// One of `deleted`, `not_found`, `invalid_id`, `error`
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl BatchDeleteResult {
+    fn new(id: String, status: &'static str) -> Self {
+        Self {
+            id,
+            status,
+            error: None,
+        }
+    }
+
+    fn set_status(&mut self, status: BatchDeleteStatus) {
+        match status {
+            BatchDeleteStatus::Deleted => self.status = "deleted",
+            BatchDeleteStatus::NotFound => self.status = "not_found",
+            BatchDeleteStatus::Failed(error) => {
+                self.status = "error";
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct BatchDeleteResponse {
+    results: Vec<BatchDeleteResult>,
+    deleted: usize,
+    latency_ms: f64,
+}
+
+/// Query parameters for batch delete.
+#[derive(Debug, Deserialize, Default)]
+pub struct BatchDeleteParams {
+    /// Run as a background job and return `202 Accepted` with the job ID
+    #[serde(default, rename = "async")]
+    run_async: bool,
+}
+
+#[derive(Serialize)]
+pub struct JobAcceptedResponse {
+    pub job_id: String,
+    pub status_url: String,
+}
+
+/// Documents deleted per service call when running as a job, so progress
+/// advances steadily.
+const BATCH_DELETE_JOB_CHUNK: usize = 100;
+
+/// Delete many documents by document ID and/or external ID
+/// (`POST /api/v1/collections/:id/docs:batchDelete`,
+/// `POST /api/v2/collections/:id/documents:batchDelete`), or every document
+/// whose metadata matches `filter` (`docs:deleteByFilter`,
+/// `documents:deleteByFilter`).
+///
+/// The router treats `:` as the start of a path parameter, so the route is
+/// registered as `docs:action` (`documents:action`) and the custom method is
+/// checked here.
+///
+/// Results are returned per ID in request order (`ids` first, then
+/// `external_ids`); one failing ID does not fail the batch. A filter delete
+/// returns one result per matched document. With `?async=true` the delete
+/// runs as a background job and the per-ID results become the job result.
+#[tracing::instrument(skip(service, req), fields(collection_id = %collection_id))]
+pub async fn batch_delete_vectors(
+    Path((collection_id, action)): Path<(String, String)>,
+    Query(params): Query<BatchDeleteParams>,
+    State(service): State<Arc<CollectionService>>,
+    Json(req): Json<BatchDeleteRequest>,
+) -> Result<Response, Response> {
+    let start = std::time::Instant::now();
+
+    let by_filter = match action.as_str() {
+        ":batchDelete" => false,
+        ":deleteByFilter" => true,
+        _ => {
+            return Err(ApiError::from((
+                StatusCode::NOT_FOUND,
+                format!("Unknown document method: {}", action),
+            ))
+            .into_response());
+        }
+    };
+
+    let collection_id = parse_collection_id(&collection_id).map_err(IntoResponse::into_response)?;
+
+    let total = req.ids.len() + req.external_ids.len();
+    let filter = if by_filter {
+        if total > 0 {
+            return Err(ApiError::invalid_argument(
+                "ids and external_ids are not accepted by :deleteByFilter",
+            )
+            .into_response());
+        }
+        let filter = req
+            .filter
+            .as_ref()
+            .ok_or_else(|| ApiError::invalid_argument("filter is required").into_response())?;
+        Some(FilterTree::parse(filter).map_err(|e| ApiError::from(e).into_response())?)
+    } else {
+        if req.filter.is_some() {
+            return Err(
+                ApiError::invalid_argument("filter is only accepted by :deleteByFilter")
+                    .into_response(),
+            );
+        }
+        if total == 0 {
+            return Err(
+                ApiError::invalid_argument("ids or external_ids must not be empty").into_response(),
+            );
+        }
+        validation::validate_batch_size(total, service.limits())
+            .map_err(|e| validation_error_response(&e))?;
+        None
+    };
+
+    if params.run_async {
+        let mut job = JobDescriptor::new("bulk_delete", Some(collection_id));
+        if filter.is_none() {
+            job = job.with_total(total as u64);
+        }
+        let job_service = Arc::clone(&service);
+        let job_id = service
+            .jobs()
+            .spawn(job, move |handle| async move {
+                let results = match filter {
+                    Some(filter) => {
+                        let results =
+                            run_delete_by_filter(&job_service, collection_id, &filter).await?;
+                        handle.advance(results.len() as u64).await;
+                        results
+                    }
+                    None => {
+                        run_batch_delete(&job_service, collection_id, req, Some(&handle)).await?
+                    }
+                };
+                let deleted = results.iter().filter(|r| r.status == "deleted").count();
+                Ok(serde_json::json!({ "deleted": deleted, "results": results }))
+            })
+            .await
+            .map_err(|e| ApiError::from(e).into_response())?;
+
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(JobAcceptedResponse {
+                job_id: job_id.to_string(),
+                status_url: format!("/api/v1/jobs/{}", job_id),
+            }),
+        )
+            .into_response());
+    }
+
+    let results = match filter {
+        Some(filter) => run_delete_by_filter(&service, collection_id, &filter).await,
+        None => run_batch_delete(&service, collection_id, req, None).await,
+    }
+    .map_err(|e| ApiError::from(e).into_response())?;
+    let deleted = results.iter().filter(|r| r.status == "deleted").count();
+
+    Ok(Json(BatchDeleteResponse {
+        results,
+        deleted,
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+    })
+    .into_response())
+}
+
+/// Resolve and delete the requested IDs, reporting progress to `job` if set.
+async fn run_batch_delete(
+    service: &CollectionService,
+    collection_id: CollectionId,
+    req: BatchDeleteRequest,
+    job: Option<&JobHandle>,
+) -> CoreResult<Vec<BatchDeleteResult>> {
+    let resolved = if req.external_ids.is_empty() {
+        Default::default()
+    } else {
+        service
+            .resolve_external_ids(collection_id, &req.external_ids)
+            .await?
+    };
+
+    // Results are filled in request order; `pending` tracks which slots
+    // are waiting on the service batch delete.
+    let mut results = Vec::with_capacity(req.ids.len() + req.external_ids.len());
+    let mut pending = Vec::new();
+    for id in req.ids {
+        let status = match DocumentId::from_str(&id) {
+            Ok(doc_id) => {
+                pending.push((results.len(), doc_id));
+                "pending"
+            }
+            Err(_) => "invalid_id",
+        };
+        results.push(BatchDeleteResult::new(id, status));
+    }
+    for external_id in req.external_ids {
+        let status = match resolved.get(&external_id) {
+            Some(doc_id) => {
+                pending.push((results.len(), *doc_id));
+                "pending"
+            }
+            None => "not_found",
+        };
+        results.push(BatchDeleteResult::new(external_id, status));
+    }
+
+    if let Some(job) = job {
+        job.advance((results.len() - pending.len()) as u64).await;
+    }
+
+    let chunk_size = if job.is_some() {
+        BATCH_DELETE_JOB_CHUNK
+    } else {
+        pending.len().max(1)
+    };
+    for chunk in pending.chunks(chunk_size) {
+        // A cancelled job keeps the deletes of the chunks already done
+        if let Some(job) = job {
+            job.check_cancelled().await?;
+        }
+        let doc_ids = chunk.iter().map(|(_, doc_id)| *doc_id).collect();
+        let statuses = service.delete_batch(collection_id, doc_ids).await?;
+
+        for ((slot, _), (_, status)) in chunk.iter().zip(statuses) {
+            results[*slot].set_status(status);
+        }
+
+        if let Some(job) = job {
+            job.advance(chunk.len() as u64).await;
+        }
+    }
+
+    Ok(results)
+}
+
+/// Delete the documents matching `filter`, one result per matched document.
+async fn run_delete_by_filter(
+    service: &CollectionService,
+    collection_id: CollectionId,
+    filter: &FilterTree,
+) -> CoreResult<Vec<BatchDeleteResult>> {
+    let statuses = service.delete_by_filter(collection_id, filter).await?;
+    Ok(statuses
+        .into_iter()
+        .map(|(doc_id, status)| {
+            let mut result = BatchDeleteResult::new(doc_id.to_string(), "pending");
+            result.set_status(status);
+            result
+        })
+        .collect())
+}
+
 #[derive(Serialize)]
 pub struct HealthResponse {
     status: String,
@@ -224,3 +650,338 @@ pub async fn health() -> Json<HealthResponse> {
         version: env!("CARGO_PKG_VERSION").to_string(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::{DatabaseId, DistanceMetric};
+    use axum::{
+        body::Body,
+        http::Request,
+        routing::{delete, get, post},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_query_with_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection("query-filter".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+
+        for lang in ["en", "de", "en"] {
+            let doc = VectorDocument::new(DocumentId::new(), vec![0.1; 16])
+                .with_metadata(serde_json::json!({"lang": lang}));
+            service.insert(collection_id, doc).await.unwrap();
+        }
+
+        let app = Router::new()
+            .route("/api/v1/collections/:id/query", post(query_vectors))
+            .with_state(Arc::clone(&service));
+        let query = |body: serde_json::Value| {
+            Request::post(format!("/api/v1/collections/{}/query", collection_id))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(query(serde_json::json!({
+                "query_vector": vec![0.1f32; 16],
+                "top_k": 10,
+                "filter": {"lang": "de"},
+                "include_payload": true,
+            })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let matches = json["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["metadata"]["lang"], "de");
+        assert!(matches[0].get("vector").is_none());
+
+        let response = app
+            .oneshot(query(serde_json::json!({
+                "query_vector": vec![0.1f32; 16],
+                "top_k": 10,
+                "filter": {"lang": {"$regex": "e."}},
+            })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        service.delete_collection(collection_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_insert_batch_route() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection("insert-batch".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+
+        let app = Router::new()
+            .route("/api/v1/collections/:id/insert_batch", post(insert_batch))
+            .with_state(Arc::clone(&service));
+
+        let doc_id = DocumentId::new();
+        let body = serde_json::json!({
+            "documents": [
+                {"doc_id": doc_id.to_string(), "vector": vec![0.1f32; 16]},
+                {"vector": vec![0.2f32; 16], "metadata": {"lang": "en"}},
+                {"doc_id": "not-a-uuid", "vector": vec![0.1f32; 16]},
+                {"vector": vec![0.1f32; 4]},
+                {"doc_id": doc_id.to_string(), "vector": vec![0.3f32; 16]},
+            ]
+        });
+        let response = app
+            .oneshot(
+                Request::post(format!(
+                    "/api/v1/collections/{}/insert_batch",
+                    collection_id
+                ))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        assert_eq!(json["inserted"], 2);
+        let statuses: Vec<&str> = json["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["status"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                "inserted",
+                "inserted",
+                "invalid",
+                "invalid",
+                "already_exists"
+            ]
+        );
+        assert_eq!(json["results"][0]["doc_id"], doc_id.to_string());
+        assert_eq!(service.get_count(collection_id).await.unwrap(), 2);
+
+        service.delete_collection(collection_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_batch_delete_route() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection("batch-delete".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+
+        let doc = VectorDocument::new(DocumentId::new(), vec![0.1; 16]);
+        let doc_id = doc.doc_id;
+        service.insert(collection_id, doc).await.unwrap();
+        let tagged = VectorDocument::new(DocumentId::new(), vec![0.2; 16])
+            .with_external_id("ext-1".to_string());
+        service.insert(collection_id, tagged).await.unwrap();
+
+        let app = Router::new()
+            .route(
+                "/api/v1/collections/:id/docs/:doc_id",
+                delete(delete_vector),
+            )
+            .route(
+                "/api/v1/collections/:id/docs:action",
+                post(batch_delete_vectors),
+            )
+            .with_state(Arc::clone(&service));
+
+        let body = serde_json::json!({
+            "ids": [doc_id.to_string(), DocumentId::new().to_string(), "bogus"],
+            "external_ids": ["ext-1", "ext-missing"],
+        });
+        let response = app
+            .oneshot(
+                Request::post(format!(
+                    "/api/v1/collections/{}/docs:batchDelete",
+                    collection_id
+                ))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(
+            status,
+            StatusCode::OK,
+            "{}",
+            String::from_utf8_lossy(&bytes)
+        );
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let statuses: Vec<&str> = json["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["status"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec!["deleted", "not_found", "invalid_id", "deleted", "not_found"]
+        );
+        assert_eq!(json["deleted"], 2);
+        assert_eq!(service.get_count(collection_id).await.unwrap(), 0);
+
+        service.delete_collection(collection_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_filter_route() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection(
+                "delete-by-filter".to_string(),
+                16,
+                DistanceMetric::Cosine,
+                None,
+            )
+            .await
+            .unwrap();
+
+        for lang in ["en", "de", "en"] {
+            let doc = VectorDocument::new(DocumentId::new(), vec![0.1; 16])
+                .with_metadata(serde_json::json!({"lang": lang}));
+            service.insert(collection_id, doc).await.unwrap();
+        }
+
+        let app = Router::new()
+            .route(
+                "/api/v2/collections/:id/documents:action",
+                post(batch_delete_vectors),
+            )
+            .with_state(Arc::clone(&service));
+        let call = |body: serde_json::Value| {
+            app.clone().oneshot(
+                Request::post(format!(
+                    "/api/v2/collections/{}/documents:deleteByFilter",
+                    collection_id
+                ))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            )
+        };
+
+        let response = call(serde_json::json!({ "filter": {"lang": "en"} }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["deleted"], 2);
+        assert_eq!(json["results"].as_array().unwrap().len(), 2);
+        assert_eq!(service.get_count(collection_id).await.unwrap(), 1);
+
+        for body in [
+            serde_json::json!({}),
+            serde_json::json!({ "filter": {"lang": "de"}, "ids": [DocumentId::new().to_string()] }),
+        ] {
+            let response = call(body).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(service.get_count(collection_id).await.unwrap(), 1);
+
+        service.delete_collection(collection_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_batch_delete_async_job() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection(
+                "batch-delete-async".to_string(),
+                16,
+                DistanceMetric::Cosine,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let doc = VectorDocument::new(DocumentId::new(), vec![0.1; 16]);
+        let doc_id = doc.doc_id;
+        service.insert(collection_id, doc).await.unwrap();
+
+        let app = Router::new()
+            .route(
+                "/api/v1/collections/:id/docs:action",
+                post(batch_delete_vectors),
+            )
+            .route("/api/v1/jobs/:id", get(crate::handlers::get_job))
+            .with_state(Arc::clone(&service));
+
+        let body = serde_json::json!({ "ids": [doc_id.to_string()] });
+        let response = app
+            .clone()
+            .oneshot(
+                Request::post(format!(
+                    "/api/v1/collections/{}/docs:batchDelete?async=true",
+                    collection_id
+                ))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let accepted: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let status_url = accepted["status_url"].as_str().unwrap().to_string();
+
+        let mut job = serde_json::Value::Null;
+        for _ in 0..100 {
+            let response = app
+                .clone()
+                .oneshot(Request::get(&status_url).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            job = serde_json::from_slice(&bytes).unwrap();
+            if job["status"] == "succeeded" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(job["status"], "succeeded");
+        assert_eq!(job["kind"], "bulk_delete");
+        assert_eq!(job["progress"], 1);
+        assert_eq!(job["result"]["deleted"], 1);
+        assert_eq!(service.get_count(collection_id).await.unwrap(), 0);
+
+        service.delete_collection(collection_id).await.unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+//! Ops-only runtime diagnostics (`[debug] enabled = true`).
+//!
+//! - GET /debug/tasks - Tokio runtime metrics and a backtrace of every task
+//! - GET /debug/queues - Storage and embedding queue depths
+//! - GET /debug/locks - Wait counters of the service's shared locks
+//! - GET /debug/allocator - Heap statistics
+//!
+//! The router is wrapped in [`AdminAuthLayer`](crate::middleware::AdminAuthLayer)
+//! requiring the `admin::debug` permission.
+
+use akidb_service::debug::{
+    allocator_stats, runtime_stats, task_dump, AllocatorStats, CollectionQueueDepths, LockStats,
+    RuntimeStats,
+};
+use akidb_service::{CollectionService, EmbeddingManager};
+use axum::{extract::State, Json};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Shared state for the `/debug` routes.
+pub struct DebugState {
+    pub collection_service: Arc<CollectionService>,
+    /// Present when embeddings are configured
+    pub embedding_manager: Option<Arc<EmbeddingManager>>,
+    pub task_dump_timeout: Duration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TasksResponse {
+    pub runtime: Option<RuntimeStats>,
+    /// One backtrace per task
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<Vec<String>>,
+    /// Why `tasks` is missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueuesResponse {
+    pub collections: Vec<CollectionQueueDepths>,
+    /// Embedding requests in flight; absent without embeddings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_embeddings: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LocksResponse {
+    pub locks: Vec<LockStats>,
+}
+
+/// GET /debug/tasks
+///
+/// A dump that times out means a worker is blocked and never yields.
+pub async fn debug_tasks(State(state): State<Arc<DebugState>>) -> Json<TasksResponse> {
+    let (tasks, tasks_error) = match task_dump(state.task_dump_timeout).await {
+        Ok(tasks) => (Some(tasks), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    Json(TasksResponse {
+        runtime: runtime_stats(),
+        tasks,
+        tasks_error,
+    })
+}
+
+/// GET /debug/queues
+pub async fn debug_queues(State(state): State<Arc<DebugState>>) -> Json<QueuesResponse> {
+    Json(QueuesResponse {
+        collections: state.collection_service.queue_depths().await,
+        pending_embeddings: state
+            .embedding_manager
+            .as_ref()
+            .map(|manager| manager.pending_requests()),
+    })
+}
+
+/// GET /debug/locks
+pub async fn debug_locks(State(state): State<Arc<DebugState>>) -> Json<LocksResponse> {
+    Json(LocksResponse {
+        locks: state.collection_service.lock_stats(),
+    })
+}
+
+/// GET /debug/allocator
+pub async fn debug_allocator() -> Json<AllocatorStats> {
+    Json(allocator_stats())
+}
@@ -1,25 +1,51 @@
 //! Embedding generation handlers for REST API
 
-use axum::{extract::State, http::StatusCode, Json};
+use super::v2::parse_collection_id;
+use crate::error::ApiError;
+use crate::middleware::backpressure::overload_response;
+use crate::middleware::collection_acl::check_collection_grant;
+use crate::middleware::{ClientAddr, TenantContext};
+use akidb_core::ErrorCode;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use akidb_service::EmbeddingManager;
+use akidb_service::{
+    CollectionGrants, CollectionService, EmbeddingHealth, EmbeddingInput, EmbeddingManager,
+    ImageInput, KeyIdentity,
+};
 
 /// Application state containing embedding manager
 pub struct AppState {
     pub embedding_manager: Arc<EmbeddingManager>,
+    /// Resolves `collection_id` to the collection's embedding model
+    pub collection_service: Arc<CollectionService>,
 }
 
 /// Request payload for embedding generation
 #[derive(Debug, Deserialize)]
 pub struct EmbedRequest {
     /// List of texts to embed
+    #[serde(default)]
     pub texts: Vec<String>,
 
-    /// Optional model name (default: "qwen3-0.6b-4bit")
-    #[serde(default = "default_model")]
-    pub model: String,
+    /// Images to embed after the texts (requires a multimodal model)
+    #[serde(default)]
+    pub images: Vec<EmbedImage>,
+
+    /// Optional model name (default: the server's default embedding model)
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Optional collection whose `embedding_model` selects the model
+    #[serde(default)]
+    pub collection_id: Option<String>,
 
     /// Optional pooling strategy (default: "mean")
     #[serde(default = "default_pooling")]
@@ -30,8 +56,31 @@ pub struct EmbedRequest {
     pub normalize: bool,
 }
 
-fn default_model() -> String {
-    "qwen3-0.6b-4bit".to_string()
+/// An image to embed, given by URL or inline as base64
+#[derive(Debug, Deserialize)]
+pub struct EmbedImage {
+    /// http(s) URL the server downloads the image from
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Base64-encoded image bytes (PNG or JPEG)
+    #[serde(default)]
+    pub data: Option<String>,
+}
+
+impl EmbedImage {
+    fn into_input(self) -> Result<ImageInput, ApiError> {
+        match (self.url, self.data) {
+            (Some(url), None) => Ok(ImageInput::Url(url)),
+            (None, Some(data)) => base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .map(ImageInput::Bytes)
+                .map_err(|e| ApiError::invalid_argument(format!("Invalid image data: {}", e))),
+            _ => Err(ApiError::invalid_argument(
+                "Each image needs exactly one of url or data",
+            )),
+        }
+    }
 }
 
 fn default_pooling() -> String {
@@ -45,7 +94,7 @@ fn default_normalize() -> bool {
 /// Response payload for embedding generation
 #[derive(Debug, Serialize)]
 pub struct EmbedResponse {
-    /// Generated embeddings (one per input text)
+    /// Generated embeddings (one per input text, then one per image)
     pub embeddings: Vec<Vec<f32>>,
 
     /// Model name used
@@ -68,14 +117,23 @@ pub struct UsageInfo {
     pub duration_ms: u64,
 }
 
-/// POST /embed - Generate embeddings for texts
+/// POST /embed - Generate embeddings for texts and images
+///
+/// Images need a multimodal model (e.g. the `clip` provider); their
+/// embeddings follow the text embeddings in the response.
+///
+/// Requests over the tenant's `embedding.tenant_qps` get `429 Too Many
+/// Requests`, and requests that find every `embedding.max_concurrent_requests`
+/// slot busy for `concurrency_wait_ms` get `503 Service Unavailable`.
 ///
 /// # Request
 ///
 /// ```json
 /// {
 ///   "texts": ["Hello world", "Machine learning"],
+///   "images": [{"url": "https://..."}, {"data": "iVBORw0..."}],  // optional
 ///   "model": "qwen3-0.6b-4bit",  // optional
+///   "collection_id": "...",       // optional, uses the collection's model
 ///   "pooling": "mean",            // optional
 ///   "normalize": true             // optional
 /// }
@@ -96,57 +154,125 @@ pub struct UsageInfo {
 /// ```
 pub async fn embed_handler(
     State(state): State<Arc<AppState>>,
+    tenant: Option<Extension<TenantContext>>,
+    key: Option<Extension<KeyIdentity>>,
+    grants: Option<Extension<CollectionGrants>>,
+    client: Option<ConnectInfo<ClientAddr>>,
     Json(request): Json<EmbedRequest>,
-) -> Result<Json<EmbedResponse>, (StatusCode, String)> {
+) -> Result<Response, ApiError> {
     // Validate input
-    if request.texts.is_empty() {
-        return Err((StatusCode::BAD_REQUEST, "texts cannot be empty".to_string()));
+    if request.texts.is_empty() && request.images.is_empty() {
+        return Err(ApiError::invalid_argument("texts cannot be empty"));
     }
 
-    if request.texts.len() > 32 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            "Maximum 32 texts per request".to_string(),
-        ));
+    if request.texts.len() + request.images.len() > 32 {
+        return Err(ApiError::invalid_argument("Maximum 32 inputs per request"));
     }
 
+    // Usage is billed to the resolved tenant; without one, to the default
+    // database's tenant
+    let tenant_id = tenant.as_ref().map(|Extension(tenant)| tenant.tenant_id);
+    let manager = &state.embedding_manager;
+    if let Err(overload) = manager.check_tenant_rate(&rate_limit_key(key, client)) {
+        tracing::warn!("Rejecting embed request: {}", overload);
+        return Ok(overload_response(&overload));
+    }
+
+    let model = match (&request.collection_id, &request.model) {
+        (Some(_), Some(_)) => {
+            return Err(ApiError::invalid_argument(
+                "Specify either model or collection_id, not both",
+            ))
+        }
+        (Some(collection_id), None) => {
+            let collection_id = parse_collection_id(collection_id)?;
+            check_collection_grant(grants.as_ref().map(|Extension(g)| g), collection_id)?;
+            let collection = state
+                .collection_service
+                .get_collection(collection_id)
+                .await?;
+            manager
+                .model_for_collection(&collection)
+                .ok_or_else(|| {
+                    ApiError::invalid_argument(format!(
+                        "Collection embedding model '{}' is not configured",
+                        collection.embedding_model
+                    ))
+                })?
+                .to_string()
+        }
+        (None, Some(model)) if !manager.has_model(model) => {
+            return Err(ApiError::invalid_argument(format!(
+                "Unknown embedding model '{}'. Available: {}",
+                model,
+                manager.model_names().join(", ")
+            )))
+        }
+        (None, Some(model)) => model.clone(),
+        (None, None) => manager.model_name().to_string(),
+    };
+
     // Record start time
     let start = std::time::Instant::now();
 
     tracing::info!(
-        "Embedding request: {} texts, model: {}, pooling: {}, normalize: {}",
+        "Embedding request: {} texts, {} images, model: {}, pooling: {}, normalize: {}",
         request.texts.len(),
-        request.model,
+        request.images.len(),
+        model,
         request.pooling,
         request.normalize
     );
 
     // Generate embeddings
-    let embeddings = state
-        .embedding_manager
-        .embed(request.texts.clone())
-        .await
-        .map_err(|e| {
-            tracing::error!("Embedding generation failed: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, e)
-        })?;
+    let image_count = request.images.len();
+    let embeddings = if request.images.is_empty() {
+        manager
+            .embed_with_model(Some(&model), request.texts.clone())
+            .await
+    } else {
+        let mut inputs: Vec<EmbeddingInput> = request
+            .texts
+            .iter()
+            .cloned()
+            .map(EmbeddingInput::Text)
+            .collect();
+        for image in request.images {
+            inputs.push(EmbeddingInput::Image(image.into_input()?));
+        }
+        manager.embed_inputs(Some(&model), inputs).await
+    }
+    .map_err(|e| {
+        tracing::error!("Embedding generation failed: {}", e);
+        if e.contains("Invalid input:") {
+            ApiError::invalid_argument(e)
+        } else if e.contains("Service unavailable:") {
+            ApiError::new(StatusCode::SERVICE_UNAVAILABLE, ErrorCode::Unavailable, e)
+        } else {
+            ApiError::from((StatusCode::INTERNAL_SERVER_ERROR, e))
+        }
+    })?;
 
     // Calculate duration
     let duration_ms = start.elapsed().as_millis() as u64;
 
     // Get model info for dimension
-    let model_info = state.embedding_manager.model_info().await.map_err(|e| {
+    let model_info = manager.model_info_for(Some(&model)).await.map_err(|e| {
         tracing::error!("Failed to get model info: {}", e);
         (StatusCode::INTERNAL_SERVER_ERROR, e)
     })?;
 
-    // Estimate token count (rough: 1 token ~= 4 characters)
-    let total_tokens: usize = request
-        .texts
-        .iter()
-        .map(|s| s.len() / 4)
-        .sum::<usize>()
-        .max(request.texts.len());
+    // Tokens embedded after truncation, counted with the model's tokenizer;
+    // each image counts as one
+    let total_tokens = manager
+        .count_tokens(Some(&model), &request.texts)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        + image_count;
+    state
+        .collection_service
+        .record_embedding_usage(tenant_id, total_tokens as u64)
+        .await;
 
     tracing::info!(
         "Embedding completed: {} embeddings generated in {}ms (dimension: {})",
@@ -157,18 +283,77 @@ pub async fn embed_handler(
 
     Ok(Json(EmbedResponse {
         embeddings,
-        model: request.model,
+        model,
         dimension: model_info.dimension,
         usage: UsageInfo {
             total_tokens,
             duration_ms,
         },
-    }))
+    })
+    .into_response())
+}
+
+/// QPS budget an embed request draws from: the tenant owning the request's API
+/// key, else the client IP as in the request rate limiter, else one budget
+/// shared by all anonymous requests whose address is unknown. Headers the
+/// caller controls, such as `X-Tenant-ID`, never pick the budget.
+fn rate_limit_key(
+    key: Option<Extension<KeyIdentity>>,
+    client: Option<ConnectInfo<ClientAddr>>,
+) -> String {
+    if let Some(Extension(key)) = key {
+        return format!("tenant:{}", key.tenant_id);
+    }
+    match client {
+        Some(ConnectInfo(ClientAddr(Some(addr)))) => format!("ip:{}", addr.ip()),
+        _ => "anonymous".to_string(),
+    }
+}
+
+/// Response payload for the embedding health check
+#[derive(Debug, Serialize)]
+pub struct EmbedHealthResponse {
+    /// "ready" or "not_ready"
+    pub status: &'static str,
+
+    /// Model, device, warmup and latency details
+    #[serde(flatten)]
+    pub health: EmbeddingHealth,
+}
+
+/// GET /embed/health - Whether the embedding model is loaded and serving
+///
+/// Returns 200 once warmup has finished and the provider passes its health
+/// check, and 503 otherwise, so a Kubernetes readiness probe can wait for
+/// the model rather than only the process.
+///
+/// # Response
+///
+/// ```json
+/// {
+///   "status": "ready",
+///   "model": "sentence-transformers/all-MiniLM-L6-v2",
+///   "dimension": 384,
+///   "device": "cuda",
+///   "warmup": {"state": "done", "duration_ms": 812},
+///   "last_inference_ms": 4.2,
+///   "error": null
+/// }
+/// ```
+pub async fn embed_health_handler(State(state): State<Arc<AppState>>) -> Response {
+    let health = state.embedding_manager.health().await;
+    let (status, code) = if health.is_ready() {
+        ("ready", StatusCode::OK)
+    } else {
+        ("not_ready", StatusCode::SERVICE_UNAVAILABLE)
+    };
+    (code, Json(EmbedHealthResponse { status, health })).into_response()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use akidb_core::{ApiKeyId, TenantId};
 
     #[test]
     fn test_embed_request_defaults() {
@@ -176,7 +361,9 @@ mod tests {
         let request: EmbedRequest = serde_json::from_str(json).unwrap();
 
         assert_eq!(request.texts.len(), 1);
-        assert_eq!(request.model, "qwen3-0.6b-4bit");
+        assert!(request.images.is_empty());
+        assert_eq!(request.model, None);
+        assert_eq!(request.collection_id, None);
         assert_eq!(request.pooling, "mean");
         assert_eq!(request.normalize, true);
     }
@@ -191,8 +378,106 @@ mod tests {
         }"#;
         let request: EmbedRequest = serde_json::from_str(json).unwrap();
 
-        assert_eq!(request.model, "custom-model");
+        assert_eq!(request.model.as_deref(), Some("custom-model"));
         assert_eq!(request.pooling, "cls");
         assert_eq!(request.normalize, false);
     }
+
+    #[test]
+    fn test_embed_request_images() {
+        let json = r#"{
+            "images": [{"url": "https://example.com/cat.png"}, {"data": "AQID"}]
+        }"#;
+        let request: EmbedRequest = serde_json::from_str(json).unwrap();
+        assert!(request.texts.is_empty());
+
+        let inputs: Vec<ImageInput> = request
+            .images
+            .into_iter()
+            .map(|image| image.into_input().unwrap())
+            .collect();
+        assert_eq!(
+            inputs,
+            vec![
+                ImageInput::Url("https://example.com/cat.png".to_string()),
+                ImageInput::Bytes(vec![1, 2, 3]),
+            ]
+        );
+
+        let both = EmbedImage {
+            url: Some("https://example.com/cat.png".to_string()),
+            data: Some("AQID".to_string()),
+        };
+        assert!(both.into_input().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_embed_health_waits_for_warmup() {
+        let manager = Arc::new(
+            EmbeddingManager::from_config("mock", "mock-embed-512", None)
+                .await
+                .unwrap(),
+        );
+        let state = Arc::new(AppState {
+            embedding_manager: Arc::clone(&manager),
+            collection_service: Arc::new(CollectionService::new()),
+        });
+
+        manager.spawn_warmup();
+        let response = embed_health_handler(State(Arc::clone(&state))).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        manager.warmup().await.unwrap();
+        let response = embed_health_handler(State(state)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["model"], "mock-embed-512");
+        assert_eq!(body["warmup"]["state"], "done");
+        assert!(body["last_inference_ms"].is_number());
+    }
+
+    #[tokio::test]
+    async fn test_embed_tenant_qps_limit() {
+        let manager = EmbeddingManager::from_config("mock", "mock-embed-512", None)
+            .await
+            .unwrap()
+            .with_tenant_qps(1);
+        let state = Arc::new(AppState {
+            embedding_manager: Arc::new(manager),
+            collection_service: Arc::new(CollectionService::new()),
+        });
+        let key = KeyIdentity {
+            key_id: ApiKeyId::new(),
+            tenant_id: TenantId::new(),
+        };
+        let client = |ip: &str| Some(ConnectInfo(ClientAddr(Some(ip.parse().unwrap()))));
+        let embed = |key: Option<KeyIdentity>, client: Option<ConnectInfo<ClientAddr>>| {
+            let request = serde_json::from_str(r#"{"texts": ["Hello world"]}"#).unwrap();
+            embed_handler(
+                State(Arc::clone(&state)),
+                None,
+                key.map(Extension),
+                None,
+                client,
+                Json(request),
+            )
+        };
+
+        // A key's tenant has one budget, whichever address it calls from
+        let response = embed(Some(key), client("10.0.0.1:1000")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = embed(Some(key), client("10.0.0.2:1000")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"], "1");
+
+        // Anonymous requests are limited per client IP
+        let response = embed(None, client("10.0.0.1:1000")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = embed(None, client("10.0.0.1:2000")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let response = embed(None, client("10.0.0.2:1000")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
 }
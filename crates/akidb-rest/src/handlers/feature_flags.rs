@@ -0,0 +1,178 @@
+//! Runtime feature flags.
+//!
+//! - GET /admin/feature-flags - Every flag in effect
+//! - GET /admin/feature-flags/:name - One flag; `?tenant_id=` also evaluates it
+//!   for that tenant
+//! - PUT /admin/feature-flags/:name - Store a flag, replacing its default
+//! - DELETE /admin/feature-flags/:name - Delete the stored flag, restoring its
+//!   default
+//!
+//! PUT and DELETE require an API key with the `admin::feature_flags`
+//! permission.
+//!
+//! Changes take effect on this server immediately and on the others within
+//! `features.flag_refresh_interval_seconds`.
+
+use crate::error::ApiError;
+use akidb_core::{CoreError, FeatureFlag, TenantId};
+use akidb_service::CollectionService;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Debug, Serialize)]
+pub struct FeatureFlagsResponse {
+    pub flags: Vec<FeatureFlag>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeatureFlagQuery {
+    pub tenant_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeatureFlagResponse {
+    #[serde(flatten)]
+    pub flag: FeatureFlag,
+    /// Whether the flag is on for the `tenant_id` in the query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_for_tenant: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetFeatureFlagRequest {
+    pub enabled: bool,
+    /// Share of tenants the flag is on for when enabled (default: 100)
+    #[serde(default = "default_rollout_percentage")]
+    pub rollout_percentage: u8,
+    pub description: Option<String>,
+    /// Per-tenant state, overriding `enabled` and the rollout
+    #[serde(default)]
+    pub tenant_overrides: HashMap<TenantId, bool>,
+}
+
+fn default_rollout_percentage() -> u8 {
+    100
+}
+
+/// GET /admin/feature-flags
+pub async fn list_feature_flags(
+    State(service): State<Arc<CollectionService>>,
+) -> Json<FeatureFlagsResponse> {
+    Json(FeatureFlagsResponse {
+        flags: service.feature_flags().list(),
+    })
+}
+
+/// GET /admin/feature-flags/:name
+pub async fn get_feature_flag(
+    State(service): State<Arc<CollectionService>>,
+    Path(name): Path<String>,
+    Query(query): Query<FeatureFlagQuery>,
+) -> Result<Json<FeatureFlagResponse>, ApiError> {
+    let flag = service
+        .feature_flags()
+        .get(&name)
+        .ok_or_else(|| CoreError::not_found("feature_flag", name))?;
+    let enabled_for_tenant = query
+        .tenant_id
+        .as_deref()
+        .map(TenantId::from_str)
+        .transpose()
+        .map_err(|e| ApiError::invalid_argument(format!("Invalid tenant_id: {}", e)))?
+        .map(|tenant_id| flag.is_enabled_for(Some(tenant_id)));
+
+    Ok(Json(FeatureFlagResponse {
+        flag,
+        enabled_for_tenant,
+    }))
+}
+
+/// PUT /admin/feature-flags/:name
+pub async fn set_feature_flag(
+    State(service): State<Arc<CollectionService>>,
+    Path(name): Path<String>,
+    Json(request): Json<SetFeatureFlagRequest>,
+) -> Result<Json<FeatureFlag>, ApiError> {
+    let flag = FeatureFlag {
+        description: request.description,
+        rollout_percentage: request.rollout_percentage,
+        tenant_overrides: request.tenant_overrides,
+        ..FeatureFlag::new(name, request.enabled)
+    };
+    let flag = service.feature_flags().set(flag).await?;
+    tracing::info!(
+        "🚩 Feature flag '{}' set: enabled={}, rollout={}%, {} tenant override(s)",
+        flag.name,
+        flag.enabled,
+        flag.rollout_percentage,
+        flag.tenant_overrides.len()
+    );
+    Ok(Json(flag))
+}
+
+/// DELETE /admin/feature-flags/:name
+pub async fn reset_feature_flag(
+    State(service): State<Arc<CollectionService>>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if !service.feature_flags().reset(&name).await? {
+        return Err(CoreError::not_found("feature_flag", name).into());
+    }
+    tracing::info!("🚩 Feature flag '{}' reset to its default", name);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_service::feature_flags::TIERING;
+
+    #[tokio::test]
+    async fn test_set_and_reset_flag() {
+        let service = Arc::new(CollectionService::new());
+        let tenant_id = TenantId::new();
+
+        let request = SetFeatureFlagRequest {
+            enabled: false,
+            rollout_percentage: 100,
+            description: None,
+            tenant_overrides: HashMap::from([(tenant_id, true)]),
+        };
+        let Json(flag) = set_feature_flag(
+            State(Arc::clone(&service)),
+            Path(TIERING.to_string()),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(flag.rollout_percentage, 100);
+
+        let Json(response) = get_feature_flag(
+            State(Arc::clone(&service)),
+            Path(TIERING.to_string()),
+            Query(FeatureFlagQuery {
+                tenant_id: Some(tenant_id.to_string()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(!response.flag.enabled);
+        assert_eq!(response.enabled_for_tenant, Some(true));
+
+        let status = reset_feature_flag(State(Arc::clone(&service)), Path(TIERING.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(service.feature_flags().is_enabled(TIERING, None));
+
+        let missing = reset_feature_flag(State(service), Path(TIERING.to_string())).await;
+        assert!(missing.is_err());
+    }
+}
@@ -4,6 +4,7 @@
 //!
 //! - `GET /health` - Liveness probe (is the service alive?)
 //! - `GET /ready` - Readiness probe (is the service ready to serve traffic?)
+//! - `GET /statusz` - Bootstrap progress (collections loaded, WAL recovery, object store)
 
 use axum::{
     extract::State,
@@ -67,7 +68,8 @@ pub async fn health_handler(
 ///
 /// # Readiness Criteria
 ///
-/// - All collections have been loaded from database
+/// - All collections have been loaded from database and their WALs replayed
+/// - The object store is reachable
 /// - All storage backends are operational (circuit breakers not open)
 ///
 /// # Kubernetes Configuration
@@ -104,6 +106,22 @@ pub async fn ready_handler(
     }
 }
 
+/// Bootstrap progress handler.
+///
+/// Always returns 200 OK; `ready` mirrors `GET /ready`, `bootstrap` shows
+/// how far loading has got and `drain` how far a drain has, so a pod stuck
+/// as not ready can be diagnosed without reading its logs.
+pub async fn statusz_handler(
+    State(service): State<Arc<CollectionService>>,
+) -> Json<serde_json::Value> {
+    Json(json!({
+        "ready": service.is_ready().await,
+        "uptime_seconds": service.uptime_seconds(),
+        "bootstrap": service.readiness().status(),
+        "drain": service.drain_controller().status(),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +141,29 @@ mod tests {
         let response = ready_handler(State(service)).await.into_response();
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_not_ready_until_bootstrapped() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        akidb_metadata::run_migrations(&pool).await.unwrap();
+        let service = Arc::new(CollectionService::with_repository(Arc::new(
+            akidb_metadata::SqliteCollectionRepository::new(pool),
+        )));
+
+        let response = ready_handler(State(Arc::clone(&service)))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        service.bootstrap().await.unwrap();
+        let response = ready_handler(State(Arc::clone(&service)))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let Json(status) = statusz_handler(State(service)).await;
+        assert_eq!(status["ready"], true);
+        assert_eq!(status["bootstrap"]["stage"], "ready");
+        assert_eq!(status["bootstrap"]["wal_recovered"], true);
+    }
 }
@@ -0,0 +1,247 @@
+//! Background job endpoints.
+//!
+//! - GET /api/v1/jobs/{id} - Status, progress, and outcome of a job
+//!   (also served at /api/v2/jobs/{id} and /admin/jobs/{id})
+//! - GET /admin/jobs - Jobs, newest first
+//! - POST /admin/jobs/{id}/cancel - Ask a running job to stop
+//!
+//! Under `/api/` a tenant only sees jobs on its own collections. The
+//! `/admin/jobs` endpoints cover every tenant and require an API key with the
+//! `admin::jobs` permission.
+//!
+//! Query parameters of `GET /admin/jobs`:
+//! - `status` - `pending`, `running`, `succeeded`, `failed` or `cancelled`
+//! - `collection_id` - jobs on one collection
+//! - `limit` (default 50, at most 1000) and `offset`
+
+use crate::error::ApiError;
+use crate::middleware::TenantContext;
+use akidb_core::{CollectionId, CoreError, JobDescriptor, JobId, JobStatus};
+use akidb_service::CollectionService;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Jobs returned by `GET /admin/jobs` when `limit` is not set
+const DEFAULT_JOB_LIST_LIMIT: usize = 50;
+/// Upper bound on `limit` for `GET /admin/jobs`
+const MAX_JOB_LIST_LIMIT: usize = 1000;
+
+/// Job status response
+#[derive(Serialize)]
+pub struct JobStatusResponse {
+    pub job_id: String,
+    pub kind: String,
+    pub collection_id: Option<String>,
+    pub status: String,
+    pub progress: u64,
+    pub total: Option<u64>,
+    pub percent_complete: Option<f64>,
+    pub error: Option<String>,
+    pub result: Option<serde_json::Value>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<JobDescriptor> for JobStatusResponse {
+    fn from(job: JobDescriptor) -> Self {
+        Self {
+            job_id: job.job_id.to_string(),
+            kind: job.kind.clone(),
+            collection_id: job.collection_id.map(|id| id.to_string()),
+            status: job.status.as_str().to_string(),
+            progress: job.progress,
+            total: job.total,
+            percent_complete: job.percent_complete(),
+            error: job.error,
+            result: job.result,
+            created_at: job.created_at.to_rfc3339(),
+            updated_at: job.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListJobsQuery {
+    pub status: Option<String>,
+    pub collection_id: Option<String>,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+#[derive(Serialize)]
+pub struct ListJobsResponse {
+    pub jobs: Vec<JobStatusResponse>,
+}
+
+/// Get job status
+///
+/// Jobs on another tenant's collections, and jobs not tied to a collection,
+/// are reported as not found to tenant-scoped requests.
+pub async fn get_job(
+    Path(job_id): Path<String>,
+    State(service): State<Arc<CollectionService>>,
+    tenant: Option<Extension<TenantContext>>,
+) -> Result<Json<JobStatusResponse>, ApiError> {
+    let job_id = parse_job_id(&job_id)?;
+    let not_found = || CoreError::not_found("Job", job_id.to_string());
+
+    let job = service.jobs().get(job_id).await?.ok_or_else(not_found)?;
+    if let Some(Extension(tenant)) = &tenant {
+        let owned = match job.collection_id {
+            Some(collection_id) => service
+                .get_collection(collection_id)
+                .await
+                .is_ok_and(|collection| tenant.owns(&collection)),
+            None => false,
+        };
+        if !owned {
+            return Err(not_found().into());
+        }
+    }
+
+    Ok(Json(job.into()))
+}
+
+/// GET /admin/jobs
+pub async fn list_jobs(
+    State(service): State<Arc<CollectionService>>,
+    Query(query): Query<ListJobsQuery>,
+) -> Result<Json<ListJobsResponse>, ApiError> {
+    let status = query
+        .status
+        .as_deref()
+        .map(JobStatus::from_str)
+        .transpose()
+        .map_err(ApiError::invalid_argument)?;
+    let collection_id = query
+        .collection_id
+        .as_deref()
+        .map(CollectionId::from_str)
+        .transpose()
+        .map_err(|e| ApiError::invalid_argument(format!("Invalid collection_id: {}", e)))?;
+    let limit = query.limit.unwrap_or(DEFAULT_JOB_LIST_LIMIT);
+    if limit == 0 || limit > MAX_JOB_LIST_LIMIT {
+        return Err(ApiError::invalid_argument(format!(
+            "limit must be between 1 and {}",
+            MAX_JOB_LIST_LIMIT
+        )));
+    }
+
+    let jobs = service
+        .jobs()
+        .list(status, collection_id, limit, query.offset)
+        .await?;
+    Ok(Json(ListJobsResponse {
+        jobs: jobs.into_iter().map(JobStatusResponse::from).collect(),
+    }))
+}
+
+/// POST /admin/jobs/{id}/cancel
+///
+/// The job stops at its next checkpoint; poll it until its status is
+/// `cancelled` (or `succeeded`, if it finished first).
+pub async fn cancel_job(
+    State(service): State<Arc<CollectionService>>,
+    Path(job_id): Path<String>,
+) -> Result<(StatusCode, Json<JobStatusResponse>), ApiError> {
+    let job_id = parse_job_id(&job_id)?;
+    let job = service.jobs().cancel(job_id).await?;
+    tracing::info!("🛑 Cancellation of job {} ({}) requested", job_id, job.kind);
+    Ok((StatusCode::ACCEPTED, Json(job.into())))
+}
+
+fn parse_job_id(job_id: &str) -> Result<JobId, ApiError> {
+    JobId::from_str(job_id)
+        .map_err(|e| ApiError::invalid_argument(format!("Invalid job_id: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_list_and_cancel_jobs() {
+        let service = Arc::new(CollectionService::new());
+        let job_id = service
+            .jobs()
+            .spawn(JobDescriptor::new("export", None), |handle| async move {
+                loop {
+                    handle.check_cancelled().await?;
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            })
+            .await
+            .unwrap();
+
+        let (status, Json(job)) = cancel_job(State(Arc::clone(&service)), Path(job_id.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(job.job_id, job_id.to_string());
+
+        let query = |status: &str| ListJobsQuery {
+            status: Some(status.to_string()),
+            collection_id: None,
+            limit: None,
+            offset: 0,
+        };
+        for _ in 0..100 {
+            let Json(response) = list_jobs(State(Arc::clone(&service)), Query(query("cancelled")))
+                .await
+                .unwrap();
+            if !response.jobs.is_empty() {
+                assert_eq!(response.jobs[0].job_id, job_id.to_string());
+                assert!(
+                    cancel_job(State(Arc::clone(&service)), Path(job_id.to_string()))
+                        .await
+                        .is_err()
+                );
+                assert!(list_jobs(State(Arc::clone(&service)), Query(query("done")))
+                    .await
+                    .is_err());
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("job {} was not cancelled", job_id);
+    }
+
+    #[tokio::test]
+    async fn test_get_job_scoped_to_tenant() {
+        let service = Arc::new(CollectionService::new());
+        let job_id = service
+            .jobs()
+            .spawn(JobDescriptor::new("export", None), |_| async {
+                Ok(serde_json::Value::Null)
+            })
+            .await
+            .unwrap();
+        let tenant = TenantContext {
+            tenant_id: akidb_core::TenantId::new(),
+            database_ids: vec![akidb_core::DatabaseId::new()],
+        };
+
+        // Admin lookups see every job
+        let Json(job) = get_job(Path(job_id.to_string()), State(Arc::clone(&service)), None)
+            .await
+            .unwrap();
+        assert_eq!(job.job_id, job_id.to_string());
+
+        // A tenant does not see jobs outside its collections
+        assert!(get_job(
+            Path(job_id.to_string()),
+            State(Arc::clone(&service)),
+            Some(Extension(tenant)),
+        )
+        .await
+        .is_err());
+    }
+}
@@ -1,9 +1,12 @@
-use akidb_core::{CollectionId, DistanceMetric};
-use akidb_service::CollectionService;
+use super::v2::parse_collection_id;
+use crate::error::ApiError;
+use crate::middleware::TenantContext;
+use akidb_core::{DistanceMetric, Quantization, VectorType};
+use akidb_service::{CollectionGrants, CollectionOptions, CollectionService};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -12,10 +15,21 @@ use std::sync::Arc;
 #[derive(Deserialize)]
 pub struct CreateCollectionRequest {
     name: String,
+    /// Dense vector dimension (omitted or 0 for sparse collections)
+    #[serde(default)]
     dimension: u32,
     metric: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     embedding_model: Option<String>,
+    /// L2-normalize vectors at insert and query time
+    #[serde(default)]
+    normalize: bool,
+    /// Index vector precision: "none" (default), "int8" or "fp16"
+    #[serde(default)]
+    quantization: Option<String>,
+    /// Vectors per document: "dense" (default), "sparse" or "both"
+    #[serde(default)]
+    vector_type: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -24,16 +38,20 @@ pub struct CreateCollectionResponse {
     name: String,
     dimension: u32,
     metric: String,
+    normalize: bool,
+    quantization: String,
+    vector_type: String,
 }
 
 #[tracing::instrument(skip(service, req), fields(name = %req.name, dimension = req.dimension, metric = %req.metric))]
 pub async fn create_collection(
     State(service): State<Arc<CollectionService>>,
+    tenant: Option<Extension<TenantContext>>,
     Json(req): Json<CreateCollectionRequest>,
-) -> Result<(StatusCode, Json<CreateCollectionResponse>), (StatusCode, String)> {
+) -> Result<(StatusCode, Json<CreateCollectionResponse>), ApiError> {
     // Validate name
     if req.name.is_empty() {
-        return Err((StatusCode::BAD_REQUEST, "name cannot be empty".to_string()));
+        return Err(ApiError::invalid_argument("name cannot be empty"));
     }
 
     // Parse metric
@@ -42,27 +60,54 @@ pub async fn create_collection(
         "l2" => DistanceMetric::L2,
         "dot" => DistanceMetric::Dot,
         _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                format!(
-                    "invalid metric: '{}', must be one of: cosine, l2, dot",
-                    req.metric
-                ),
-            ))
+            return Err(ApiError::invalid_argument(format!(
+                "invalid metric: '{}', must be one of: cosine, l2, dot",
+                req.metric
+            )))
+        }
+    };
+
+    let quantization = match req.quantization.as_deref() {
+        Some(quantization) => {
+            Quantization::from_str(&quantization.to_lowercase()).map_err(|()| {
+                ApiError::invalid_argument(format!(
+                    "invalid quantization: '{}', must be one of: none, int8, fp16",
+                    quantization
+                ))
+            })?
         }
+        None => Quantization::None,
+    };
+
+    let vector_type = match req.vector_type.as_deref() {
+        Some(vector_type) => VectorType::from_str(&vector_type.to_lowercase()).map_err(|()| {
+            ApiError::invalid_argument(format!(
+                "invalid vector_type: '{}', must be one of: dense, sparse, both",
+                vector_type
+            ))
+        })?,
+        None => VectorType::Dense,
     };
 
     // Create collection
+    let database_id = match tenant {
+        Some(Extension(tenant)) => tenant.database_id(),
+        None => service.default_database_id().await,
+    };
     let collection_id = service
-        .create_collection(req.name.clone(), req.dimension, metric, req.embedding_model)
-        .await
-        .map_err(|e| {
-            if e.to_string().contains("dimension") {
-                (StatusCode::BAD_REQUEST, e.to_string())
-            } else {
-                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-            }
-        })?;
+        .create_collection_in(
+            database_id,
+            req.name.clone(),
+            req.dimension,
+            metric,
+            req.embedding_model,
+            CollectionOptions {
+                normalize: req.normalize,
+                quantization,
+                vector_type,
+            },
+        )
+        .await?;
 
     Ok((
         StatusCode::CREATED,
@@ -71,6 +116,9 @@ pub async fn create_collection(
             name: req.name,
             dimension: req.dimension,
             metric: req.metric,
+            normalize: req.normalize,
+            quantization: quantization.as_str().to_string(),
+            vector_type: vector_type.as_str().to_string(),
         }),
     ))
 }
@@ -86,25 +134,36 @@ pub struct CollectionInfo {
     name: String,
     dimension: u32,
     metric: String,
+    normalize: bool,
+    quantization: String,
+    vector_type: String,
     document_count: u64,
     created_at: String,
 }
 
 pub async fn list_collections(
     State(service): State<Arc<CollectionService>>,
-) -> Result<Json<ListCollectionsResponse>, (StatusCode, String)> {
-    let collections = service
-        .list_collections()
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    tenant: Option<Extension<TenantContext>>,
+    grants: Option<Extension<CollectionGrants>>,
+) -> Result<Json<ListCollectionsResponse>, ApiError> {
+    let collections = service.list_collections().await?;
 
     let collection_infos = collections
         .into_iter()
+        .filter(|c| tenant.as_ref().map_or(true, |Extension(t)| t.owns(c)))
+        .filter(|c| {
+            grants
+                .as_ref()
+                .map_or(true, |Extension(g)| g.allows(c.collection_id))
+        })
         .map(|c| CollectionInfo {
             collection_id: c.collection_id.to_string(),
             name: c.name,
             dimension: c.dimension,
             metric: c.metric.as_str().to_string(),
+            normalize: c.normalize,
+            quantization: c.quantization.as_str().to_string(),
+            vector_type: c.vector_type.as_str().to_string(),
             document_count: 0, // TODO: Get actual count from service
             created_at: c.created_at.to_rfc3339(),
         })
@@ -123,21 +182,10 @@ pub struct GetCollectionResponse {
 pub async fn get_collection(
     Path(collection_id): Path<String>,
     State(service): State<Arc<CollectionService>>,
-) -> Result<Json<GetCollectionResponse>, (StatusCode, String)> {
-    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid collection_id: {}", e),
-        )
-    })?;
+) -> Result<Json<GetCollectionResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
 
-    let collection = service.get_collection(collection_id).await.map_err(|e| {
-        if e.to_string().contains("not found") {
-            (StatusCode::NOT_FOUND, e.to_string())
-        } else {
-            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
-    })?;
+    let collection = service.get_collection(collection_id).await?;
 
     // Get document count
     let document_count = service.get_count(collection_id).await.unwrap_or(0) as u64;
@@ -148,6 +196,9 @@ pub async fn get_collection(
             name: collection.name,
             dimension: collection.dimension,
             metric: collection.metric.as_str().to_string(),
+            normalize: collection.normalize,
+            quantization: collection.quantization.as_str().to_string(),
+            vector_type: collection.vector_type.as_str().to_string(),
             document_count,
             created_at: collection.created_at.to_rfc3339(),
         },
@@ -157,24 +208,10 @@ pub async fn get_collection(
 pub async fn delete_collection(
     Path(collection_id): Path<String>,
     State(service): State<Arc<CollectionService>>,
-) -> Result<StatusCode, (StatusCode, String)> {
-    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid collection_id: {}", e),
-        )
-    })?;
-
-    service
-        .delete_collection(collection_id)
-        .await
-        .map_err(|e| {
-            if e.to_string().contains("not found") {
-                (StatusCode::NOT_FOUND, e.to_string())
-            } else {
-                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-            }
-        })?;
+) -> Result<StatusCode, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
+
+    service.delete_collection(collection_id).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -190,43 +227,8 @@ pub async fn delete_collection(
 pub async fn metrics(
     State(service): State<Arc<CollectionService>>,
 ) -> Result<(StatusCode, String), (StatusCode, String)> {
-    let mut output = String::new();
-
-    // Header
-    output.push_str("# AkiDB 2.0 Metrics\n");
-    output.push_str("# Prometheus Text Format v0.0.4\n\n");
-
-    // Service metrics (collections, vectors, searches)
-    match service.metrics() {
-        Some(metrics) => {
-            output.push_str(&metrics.export_prometheus().await);
-            output.push('\n');
-        }
-        None => {
-            output.push_str("# Service metrics not available\n");
-            output.push_str("# Service was not created with full persistence\n\n");
-        }
-    }
-
-    // Storage metrics (S3, DLQ, circuit breaker, WAL)
-    match service.storage_metrics().await {
-        Some(storage_metrics) => {
-            output.push_str(&storage_metrics.export_prometheus());
-            output.push('\n');
-        }
-        None => {
-            output.push_str("# Storage metrics not available\n");
-            output.push_str("# No storage backends configured\n\n");
-        }
-    }
-
-    // Build info
-    output.push_str("# HELP akidb_build_info Build information\n");
-    output.push_str("# TYPE akidb_build_info gauge\n");
-    output.push_str(&format!(
-        "akidb_build_info{{version=\"{}\"}} 1\n",
-        env!("CARGO_PKG_VERSION")
-    ));
-
-    Ok((StatusCode::OK, output))
+    Ok((
+        StatusCode::OK,
+        akidb_service::metrics::export_all(&service).await,
+    ))
 }
@@ -1,15 +1,41 @@
 pub mod admin;
+pub mod aliases; // Collection aliases
+pub mod bulk; // NDJSON streaming bulk upsert
 pub mod collections;
+pub mod debug; // Ops-only runtime diagnostics
 pub mod embedding;
+pub mod feature_flags; // Runtime feature flags
 pub mod health; // Kubernetes health and readiness probes
+pub mod jobs; // Background job status, listing and cancellation
 pub mod management;
+pub mod rerank; // Cross-encoder reranking
 pub mod tier; // Phase 10 Week 3: Tier control endpoints
+pub mod usage; // Per-tenant usage export for billing
+pub mod v2; // REST API v2 (documents/search naming)
+pub mod watch; // WebSocket change feed
 
-pub use admin::{health_check, reset_circuit_breaker, retry_dlq};
-pub use collections::{delete_vector, get_vector, insert_vector, query_vectors};
-pub use embedding::{embed_handler, AppState as EmbeddingAppState};
-pub use health::{health_handler, ready_handler};
+pub use admin::{
+    compact_collection, get_compaction_status, get_drain_status, get_leader_status,
+    get_replication_status, get_wal_state, health_check, reindex_collection, reset_circuit_breaker,
+    restore_collection, retry_dlq, start_drain, DrainState, HealthState,
+};
+pub use aliases::{delete_alias, get_alias, list_aliases, set_alias};
+pub use bulk::bulk_upsert;
+pub use collections::{
+    batch_delete_vectors, delete_vector, get_vector, insert_batch, insert_vector, query_vectors,
+};
+pub use debug::{debug_allocator, debug_locks, debug_queues, debug_tasks, DebugState};
+pub use embedding::{embed_handler, embed_health_handler, AppState as EmbeddingAppState};
+pub use feature_flags::{
+    get_feature_flag, list_feature_flags, reset_feature_flag, set_feature_flag,
+};
+pub use health::{health_handler, ready_handler, statusz_handler};
+pub use jobs::{cancel_job, get_job, list_jobs};
 pub use management::{
     create_collection, delete_collection, get_collection, list_collections, metrics,
 };
+pub use rerank::rerank_handler;
 pub use tier::{get_collection_tier, get_tier_metrics, update_collection_tier};
+pub use usage::get_usage;
+pub use v2::{create_document, get_document, search, search_stream, upsert_by_external_id};
+pub use watch::watch_collection;
@@ -0,0 +1,161 @@
+//! Cross-encoder reranking handler for REST API
+
+use super::embedding::UsageInfo;
+use crate::error::ApiError;
+use akidb_service::{EmbeddingError, Reranker};
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Request payload for reranking
+#[derive(Debug, Deserialize)]
+pub struct RerankRequest {
+    /// Query the documents are scored against
+    pub query: String,
+
+    /// Documents to score
+    pub documents: Vec<String>,
+
+    /// Return only the best `top_n` documents (default: all)
+    #[serde(default)]
+    pub top_n: Option<usize>,
+}
+
+/// Response payload for reranking
+#[derive(Debug, Serialize)]
+pub struct RerankResponse {
+    /// Documents ordered by relevance, most relevant first
+    pub results: Vec<RerankResult>,
+
+    /// Model name used
+    pub model: String,
+
+    /// Usage information
+    pub usage: UsageInfo,
+}
+
+/// A scored document
+#[derive(Debug, Serialize)]
+pub struct RerankResult {
+    /// Position of the document in the request
+    pub index: usize,
+
+    /// Relevance score (higher is more relevant)
+    pub score: f32,
+}
+
+/// POST /api/v1/rerank - Score documents against a query
+///
+/// # Request
+///
+/// ```json
+/// {
+///   "query": "what is a vector database?",
+///   "documents": ["AkiDB stores embeddings", "Cats sleep a lot"],
+///   "top_n": 1  // optional
+/// }
+/// ```
+///
+/// # Response
+///
+/// ```json
+/// {
+///   "results": [{"index": 0, "score": 0.93}],
+///   "model": "cross-encoder/ms-marco-MiniLM-L-6-v2",
+///   "usage": {
+///     "total_tokens": 24,
+///     "duration_ms": 12
+///   }
+/// }
+/// ```
+pub async fn rerank_handler(
+    State(reranker): State<Arc<Reranker>>,
+    Json(request): Json<RerankRequest>,
+) -> Result<Json<RerankResponse>, ApiError> {
+    if request.query.trim().is_empty() {
+        return Err(ApiError::invalid_argument("query cannot be empty"));
+    }
+    if request.documents.is_empty() {
+        return Err(ApiError::invalid_argument("documents cannot be empty"));
+    }
+
+    let reranked = reranker
+        .rerank(&request.query, request.documents)
+        .await
+        .map_err(|e| match e {
+            EmbeddingError::InvalidInput(message) => ApiError::invalid_argument(message),
+            other => {
+                tracing::error!("Reranking failed: {}", other);
+                ApiError::from((StatusCode::INTERNAL_SERVER_ERROR, other.to_string()))
+            }
+        })?;
+
+    let top_n = request.top_n.unwrap_or(reranked.hits.len());
+    Ok(Json(RerankResponse {
+        results: reranked
+            .hits
+            .into_iter()
+            .take(top_n)
+            .map(|hit| RerankResult {
+                index: hit.index,
+                score: hit.score,
+            })
+            .collect(),
+        model: reranked.model,
+        usage: UsageInfo {
+            total_tokens: reranked.usage.total_tokens,
+            duration_ms: reranked.usage.duration_ms,
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_service::MockRerankProvider;
+
+    fn reranker() -> Arc<Reranker> {
+        Arc::new(Reranker::new(
+            Arc::new(MockRerankProvider::new()),
+            MockRerankProvider::MODEL,
+            2,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_rerank_handler_orders_and_truncates() {
+        let request = RerankRequest {
+            query: "vector database".to_string(),
+            documents: vec!["sleepy cats".to_string(), "a vector database".to_string()],
+            top_n: Some(1),
+        };
+
+        let Json(response) = rerank_handler(State(reranker()), Json(request))
+            .await
+            .unwrap();
+        assert_eq!(response.model, "mock-rerank");
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].index, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rerank_handler_rejects_invalid_input() {
+        let empty = RerankRequest {
+            query: "query".to_string(),
+            documents: vec![],
+            top_n: None,
+        };
+        assert!(rerank_handler(State(reranker()), Json(empty))
+            .await
+            .is_err());
+
+        let too_many = RerankRequest {
+            query: "query".to_string(),
+            documents: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            top_n: None,
+        };
+        assert!(rerank_handler(State(reranker()), Json(too_many))
+            .await
+            .is_err());
+    }
+}
@@ -5,7 +5,8 @@
 //! - POST /collections/{id}/tier - Manual tier control (admin)
 //! - GET /metrics/tiers - Tier distribution stats
 
-use akidb_core::CollectionId;
+use super::v2::parse_collection_id;
+use crate::error::ApiError;
 use akidb_service::CollectionService;
 use axum::{
     extract::{Path, State},
@@ -13,7 +14,6 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
 use std::sync::Arc;
 
 /// Tier status response
@@ -61,13 +61,8 @@ pub struct TierMetrics {
 pub async fn get_collection_tier(
     Path(collection_id): Path<String>,
     State(service): State<Arc<CollectionService>>,
-) -> Result<Json<TierStatusResponse>, (StatusCode, String)> {
-    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid collection_id: {}", e),
-        )
-    })?;
+) -> Result<Json<TierStatusResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
 
     // Check if tiering is enabled
     let tiering_manager = service.tiering_manager().ok_or_else(|| {
@@ -78,16 +73,7 @@ pub async fn get_collection_tier(
     })?;
 
     // Get tier state
-    let tier_state = tiering_manager
-        .get_tier_state(collection_id)
-        .await
-        .map_err(|e| {
-            if e.to_string().contains("not found") {
-                (StatusCode::NOT_FOUND, e.to_string())
-            } else {
-                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-            }
-        })?;
+    let tier_state = tiering_manager.get_tier_state(collection_id).await?;
 
     Ok(Json(TierStatusResponse {
         collection_id: collection_id.to_string(),
@@ -109,13 +95,8 @@ pub async fn update_collection_tier(
     Path(collection_id): Path<String>,
     State(service): State<Arc<CollectionService>>,
     Json(req): Json<UpdateTierRequest>,
-) -> Result<Json<TierStatusResponse>, (StatusCode, String)> {
-    let collection_id = CollectionId::from_str(&collection_id).map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Invalid collection_id: {}", e),
-        )
-    })?;
+) -> Result<Json<TierStatusResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
 
     // Check if tiering is enabled
     let tiering_manager = service.tiering_manager().ok_or_else(|| {
@@ -128,10 +109,7 @@ pub async fn update_collection_tier(
     // Execute action
     match req.action {
         TierAction::PromoteToHot => {
-            tiering_manager
-                .promote_from_warm(collection_id)
-                .await
-                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            tiering_manager.promote_from_warm(collection_id).await?;
         }
         TierAction::DemoteToWarm => {
             // Note: demote_to_warm is private in TieringManager
@@ -139,27 +117,31 @@ pub async fn update_collection_tier(
             return Err((
                 StatusCode::NOT_IMPLEMENTED,
                 "Manual demotion to warm not yet implemented".to_string(),
-            ));
+            )
+                .into());
         }
         TierAction::DemoteToRold => {
             // Note: demote_to_cold is private in TieringManager
             return Err((
                 StatusCode::NOT_IMPLEMENTED,
                 "Manual demotion to cold not yet implemented".to_string(),
-            ));
+            )
+                .into());
         }
         TierAction::Pin => {
             // Note: pin_collection needs to be exposed in TieringManager
             return Err((
                 StatusCode::NOT_IMPLEMENTED,
                 "Pin/unpin not yet implemented in TieringManager API".to_string(),
-            ));
+            )
+                .into());
         }
         TierAction::Unpin => {
             return Err((
                 StatusCode::NOT_IMPLEMENTED,
                 "Pin/unpin not yet implemented in TieringManager API".to_string(),
-            ));
+            )
+                .into());
         }
     }
 
@@ -174,7 +156,7 @@ pub async fn update_collection_tier(
 /// query all collections from the tier state repository.
 pub async fn get_tier_metrics(
     State(service): State<Arc<CollectionService>>,
-) -> Result<Json<TierMetrics>, (StatusCode, String)> {
+) -> Result<Json<TierMetrics>, ApiError> {
     // Check if tiering is enabled
     let _tiering_manager = service.tiering_manager().ok_or_else(|| {
         (
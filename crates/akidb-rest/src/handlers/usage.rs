@@ -0,0 +1,133 @@
+//! Usage export for billing.
+//!
+//! - GET /admin/usage - Hourly per-tenant usage rollups as JSON or CSV
+//!
+//! Query parameters:
+//! - `tenant_id` - one tenant (default: all tenants)
+//! - `from`, `to` - RFC 3339 time range, `from` inclusive and `to` exclusive
+//!   (default: the last 24 hours)
+//! - `format` - `json` (default) or `csv`
+
+use crate::error::ApiError;
+use akidb_core::{hour_of, CoreError, TenantId, UsageRollup};
+use akidb_service::CollectionService;
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Columns of the CSV export, in order
+const CSV_HEADER: &str = "tenant_id,hour,queries,vectors_inserted,stored_bytes,embedding_tokens";
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    pub tenant_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub format: UsageFormat,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageResponse {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub usage: Vec<UsageRollup>,
+}
+
+/// GET /admin/usage
+///
+/// Flushes this process's pending counts first, so the export is current.
+/// Requires an API key with the `admin::usage` permission.
+pub async fn get_usage(
+    State(service): State<Arc<CollectionService>>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Response, ApiError> {
+    let meter = service.usage_meter().ok_or_else(|| {
+        CoreError::invalid_state("Usage metering is not enabled (set [metering] enabled = true)")
+    })?;
+
+    let tenant_id = query
+        .tenant_id
+        .as_deref()
+        .map(TenantId::from_str)
+        .transpose()
+        .map_err(|e| ApiError::invalid_argument(format!("Invalid tenant_id: {}", e)))?;
+    let to = query
+        .to
+        .unwrap_or_else(|| hour_of(Utc::now()) + Duration::hours(1));
+    let from = query.from.unwrap_or(to - Duration::hours(24));
+    if from >= to {
+        return Err(ApiError::invalid_argument("from must be earlier than to"));
+    }
+
+    service.flush_usage().await?;
+    let usage = meter.list(tenant_id, from, to).await?;
+
+    Ok(match query.format {
+        UsageFormat::Json => Json(UsageResponse { from, to, usage }).into_response(),
+        UsageFormat::Csv => (
+            [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            to_csv(&usage),
+        )
+            .into_response(),
+    })
+}
+
+fn to_csv(usage: &[UsageRollup]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for rollup in usage {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            rollup.tenant_id,
+            rollup.hour.to_rfc3339(),
+            rollup.queries,
+            rollup.vectors_inserted,
+            rollup.stored_bytes,
+            rollup.embedding_tokens
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_to_csv() {
+        let tenant_id = TenantId::new();
+        let hour = Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap();
+        let rollup = UsageRollup {
+            queries: 7,
+            vectors_inserted: 3,
+            stored_bytes: 4096,
+            embedding_tokens: 42,
+            ..UsageRollup::new(tenant_id, hour)
+        };
+
+        let csv = to_csv(&[rollup]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            format!("{},2026-03-01T10:00:00+00:00,7,3,4096,42", tenant_id)
+        );
+    }
+}
@@ -0,0 +1,733 @@
+//! REST API v2 handlers.
+//!
+//! v2 settles the naming drift in v1: documents live under `/documents`
+//! (not `/docs` and `/insert`), similarity search is `/search` (not `/query`),
+//! identifiers are `id`, and match quality is `score`. Search takes the full
+//! request (filter, score threshold, payload options) and returns metadata by
+//! default.
+//!
+//! The v1 handlers that have a v2 counterpart are thin adapters over the
+//! shared functions here, so both versions share validation and error mapping.
+//!
+//! Search takes an optional `hybrid` object (`query`, `fields`, `fusion` of
+//! `rrf` or `weighted` with `alpha`, `candidates`) that fuses the vector
+//! ranking with BM25 keyword matches over payload text.
+//!
+//! Collections with sparse vectors take a `sparse` object (`indices`,
+//! `values`) on documents, and on searches in place of `vector`.
+//!
+//! `POST /search/stream` returns hits as NDJSON batches instead of one JSON
+//! document, for very large `top_k` and scroll-style paging (mirroring the
+//! gRPC `QueryStream` RPC).
+
+use crate::error::ApiError;
+use crate::validation::validation_error_response;
+use akidb_core::{CollectionId, CoreError, DocumentId, SearchResult, SparseVector, VectorDocument};
+use akidb_service::{
+    validation, CollectionService, FilterTree, Fusion, HybridOptions, RerankOptions, SearchOptions,
+};
+use axum::{
+    body::{Bytes, StreamBody},
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+/// Hits per streamed batch when the client does not specify a batch size.
+const DEFAULT_STREAM_BATCH_SIZE: usize = 100;
+
+/// Upper bound on hits per streamed batch.
+const MAX_STREAM_BATCH_SIZE: usize = 1_000;
+
+fn default_top_k() -> usize {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_rerank_text_field() -> String {
+    "text".to_string()
+}
+
+fn default_rerank_candidates() -> usize {
+    50
+}
+
+fn default_hybrid_candidates() -> usize {
+    100
+}
+
+#[derive(Deserialize)]
+pub struct SearchRequest {
+    #[serde(default)]
+    pub(crate) vector: Vec<f32>,
+    /// Sparse query vector, ranked by dot product in place of `vector`
+    #[serde(default)]
+    pub(crate) sparse: Option<SparseVector>,
+    #[serde(default = "default_top_k")]
+    pub(crate) top_k: usize,
+    /// Metadata filter, e.g. `{"category": "news", "year": {"$gte": 2020}}`
+    #[serde(default)]
+    pub(crate) filter: Option<JsonValue>,
+    /// Drop hits scoring worse than this (metric-dependent direction)
+    #[serde(default)]
+    pub(crate) score_threshold: Option<f32>,
+    #[serde(default = "default_true")]
+    pub(crate) include_payload: bool,
+    /// Return only these top-level payload keys (all when absent)
+    #[serde(default)]
+    pub(crate) payload_fields: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) include_vector: bool,
+    /// Rerank candidates with the server's cross-encoder
+    #[serde(default)]
+    pub(crate) rerank: Option<SearchRerank>,
+    /// Fuse with BM25 keyword matches over payload text
+    #[serde(default)]
+    pub(crate) hybrid: Option<SearchHybrid>,
+}
+
+/// Rerank stage of a search request
+#[derive(Deserialize)]
+pub struct SearchRerank {
+    /// Query text the candidates are scored against
+    pub(crate) query: String,
+    /// Payload key holding each document's text
+    #[serde(default = "default_rerank_text_field")]
+    pub(crate) text_field: String,
+    /// Candidates fetched from the index and reranked
+    #[serde(default = "default_rerank_candidates")]
+    pub(crate) candidates: usize,
+}
+
+/// Keyword stage of a hybrid search request
+#[derive(Deserialize)]
+pub struct SearchHybrid {
+    /// Keyword query scored with BM25
+    pub(crate) query: String,
+    /// Payload keys searched (every string key when absent)
+    #[serde(default)]
+    pub(crate) fields: Option<Vec<String>>,
+    /// `rrf` (default) or `weighted`
+    #[serde(default)]
+    pub(crate) fusion: Option<String>,
+    /// Vector weight for `weighted` fusion, in [0, 1] (default 0.5)
+    #[serde(default)]
+    pub(crate) alpha: Option<f32>,
+    /// Candidates taken from each ranking before fusion
+    #[serde(default = "default_hybrid_candidates")]
+    pub(crate) candidates: usize,
+}
+
+impl SearchHybrid {
+    fn into_options(self) -> Result<HybridOptions, ApiError> {
+        let fusion = match self.fusion.as_deref().unwrap_or("rrf") {
+            "rrf" => {
+                if self.alpha.is_some() {
+                    return Err(ApiError::invalid_argument(
+                        "alpha only applies to weighted fusion",
+                    ));
+                }
+                Fusion::Rrf
+            }
+            "weighted" => Fusion::Weighted {
+                alpha: self.alpha.unwrap_or(0.5),
+            },
+            other => {
+                return Err(ApiError::invalid_argument(format!(
+                    "invalid fusion: '{}', must be one of: rrf, weighted",
+                    other
+                )))
+            }
+        };
+        Ok(HybridOptions {
+            query: self.query,
+            fields: self.fields,
+            fusion,
+            candidates: self.candidates,
+        })
+    }
+}
+
+#[derive(Serialize)]
+pub struct SearchResponse {
+    hits: Vec<SearchHit>,
+    latency_ms: f64,
+}
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    id: String,
+    external_id: Option<String>,
+    score: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vector: Option<Vec<f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sparse: Option<SparseVector>,
+}
+
+impl From<SearchResult> for SearchHit {
+    fn from(result: SearchResult) -> Self {
+        Self {
+            id: result.doc_id.to_string(),
+            external_id: result.external_id,
+            score: result.score,
+            metadata: result.metadata,
+            vector: result.vector,
+            sparse: result.sparse,
+        }
+    }
+}
+
+pub(crate) fn parse_collection_id(collection_id: &str) -> Result<CollectionId, ApiError> {
+    CollectionId::from_str(collection_id)
+        .map_err(|e| ApiError::invalid_argument(format!("Invalid collection_id: {}", e)))
+}
+
+/// Run a search request against a collection (shared by v1 `query` and v2 `search`).
+pub(crate) async fn search_collection(
+    service: &CollectionService,
+    collection_id: &str,
+    req: SearchRequest,
+) -> Result<Vec<SearchResult>, ApiError> {
+    let collection_id = parse_collection_id(collection_id)?;
+
+    if req.vector.is_empty() && req.sparse.is_none() {
+        return Err(ApiError::invalid_argument("query vector cannot be empty"));
+    }
+
+    let filter = req
+        .filter
+        .as_ref()
+        .map(FilterTree::parse)
+        .transpose()
+        .map_err(|e| ApiError::invalid_argument(e.to_string()))?;
+    let options = SearchOptions {
+        filter,
+        score_threshold: req.score_threshold,
+        include_payload: req.include_payload,
+        payload_fields: req.payload_fields,
+        include_vector: req.include_vector,
+        rerank: req.rerank.map(|rerank| RerankOptions {
+            query: rerank.query,
+            text_field: rerank.text_field,
+            candidates: rerank.candidates,
+        }),
+        hybrid: req.hybrid.map(SearchHybrid::into_options).transpose()?,
+        sparse: req.sparse,
+    };
+
+    Ok(service
+        .search(collection_id, req.vector, req.top_k, &options)
+        .await?)
+}
+
+/// Similarity search (`POST /api/v2/collections/:id/search`).
+#[tracing::instrument(skip(service, req), fields(collection_id = %collection_id, top_k = req.top_k))]
+pub async fn search(
+    Path(collection_id): Path<String>,
+    State(service): State<Arc<CollectionService>>,
+    Json(req): Json<SearchRequest>,
+) -> Result<Json<SearchResponse>, ApiError> {
+    let start = std::time::Instant::now();
+
+    let hits = search_collection(&service, &collection_id, req)
+        .await?
+        .into_iter()
+        .map(SearchHit::from)
+        .collect();
+
+    Ok(Json(SearchResponse {
+        hits,
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct SearchStreamRequest {
+    #[serde(flatten)]
+    search: SearchRequest,
+    /// Hits per streamed batch (server default when absent)
+    #[serde(default)]
+    batch_size: Option<usize>,
+    /// Scroll cursor: number of leading hits to skip
+    #[serde(default)]
+    offset: usize,
+}
+
+/// One NDJSON line of a streamed search.
+#[derive(Serialize)]
+pub struct SearchStreamBatch {
+    hits: Vec<SearchHit>,
+    /// Offset to pass on the next request to continue scrolling
+    next_offset: usize,
+    /// True on the final batch of the stream
+    done: bool,
+}
+
+/// Streamed similarity search (`POST /api/v2/collections/:id/search/stream`).
+///
+/// Searches for `offset + top_k` hits and streams all but the first `offset`
+/// as `application/x-ndjson` batches of `batch_size`. Errors (unknown
+/// collection, invalid `top_k`) are returned before the stream starts; an
+/// empty result is a single batch with `done: true`.
+#[tracing::instrument(skip(service, req), fields(collection_id = %collection_id, top_k = req.search.top_k, offset = req.offset))]
+pub async fn search_stream(
+    Path(collection_id): Path<String>,
+    State(service): State<Arc<CollectionService>>,
+    Json(req): Json<SearchStreamRequest>,
+) -> Result<Response, ApiError> {
+    let batch_size = match req.batch_size {
+        None | Some(0) => DEFAULT_STREAM_BATCH_SIZE,
+        Some(n) => n.min(MAX_STREAM_BATCH_SIZE),
+    };
+    let offset = req.offset;
+
+    let mut search = req.search;
+    search.top_k = offset
+        .checked_add(search.top_k)
+        .ok_or_else(|| ApiError::invalid_argument("offset + top_k is out of range"))?;
+    let results = search_collection(&service, &collection_id, search).await?;
+
+    let mut remaining = results.len().saturating_sub(offset);
+    let mut hits = results.into_iter().skip(offset).map(SearchHit::from);
+    let mut next_offset = offset;
+    let mut done = false;
+    let batches = std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let batch: Vec<SearchHit> = hits.by_ref().take(batch_size).collect();
+        remaining -= batch.len();
+        next_offset += batch.len();
+        done = remaining == 0;
+        Some(SearchStreamBatch {
+            hits: batch,
+            next_offset,
+            done,
+        })
+    });
+
+    // Batches are serialized as the client reads them
+    let body = StreamBody::new(tokio_stream::iter(batches).map(|batch| {
+        serde_json::to_vec(&batch).map(|mut line| {
+            line.push(b'\n');
+            Bytes::from(line)
+        })
+    }));
+
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], body).into_response())
+}
+
+/// Validate and insert a document (shared by v1 `insert` and v2 `documents`).
+pub(crate) async fn insert_document(
+    service: &CollectionService,
+    collection_id: &str,
+    doc: VectorDocument,
+) -> Result<DocumentId, Response> {
+    let collection_id = parse_collection_id(collection_id).map_err(IntoResponse::into_response)?;
+
+    if doc.vector.is_empty() && doc.sparse.is_none() {
+        return Err(ApiError::invalid_argument("vector cannot be empty").into_response());
+    }
+
+    validation::validate_document(&doc, service.limits())
+        .map_err(|e| validation_error_response(&e))?;
+
+    service
+        .insert(collection_id, doc)
+        .await
+        .map_err(|e| ApiError::from(e).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct CreateDocumentRequest {
+    /// Document ID; generated when absent
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    external_id: Option<String>,
+    #[serde(default)]
+    vector: Vec<f32>,
+    /// Sparse vector (collections with sparse vectors)
+    #[serde(default)]
+    sparse: Option<SparseVector>,
+    #[serde(default)]
+    metadata: Option<JsonValue>,
+}
+
+#[derive(Serialize)]
+pub struct CreateDocumentResponse {
+    id: String,
+    latency_ms: f64,
+}
+
+/// Insert a document (`POST /api/v2/collections/:id/documents`).
+#[tracing::instrument(skip(service, req), fields(collection_id = %collection_id))]
+pub async fn create_document(
+    Path(collection_id): Path<String>,
+    State(service): State<Arc<CollectionService>>,
+    Json(req): Json<CreateDocumentRequest>,
+) -> Result<(StatusCode, Json<CreateDocumentResponse>), Response> {
+    let start = std::time::Instant::now();
+
+    let doc_id = match req.id.as_deref().map(DocumentId::from_str) {
+        Some(Ok(doc_id)) => doc_id,
+        Some(Err(e)) => {
+            return Err(ApiError::invalid_argument(format!("Invalid id: {}", e)).into_response())
+        }
+        None => DocumentId::new(),
+    };
+
+    let mut doc = VectorDocument::new(doc_id, req.vector);
+    if let Some(sparse) = req.sparse {
+        doc = doc.with_sparse(sparse);
+    }
+    if let Some(external_id) = req.external_id {
+        doc = doc.with_external_id(external_id);
+    }
+    if let Some(metadata) = req.metadata {
+        doc = doc.with_metadata(metadata);
+    }
+
+    let id = insert_document(&service, &collection_id, doc).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateDocumentResponse {
+            id: id.to_string(),
+            latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+        }),
+    ))
+}
+
+#[derive(Serialize)]
+pub struct DocumentResponse {
+    id: String,
+    external_id: Option<String>,
+    vector: Vec<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sparse: Option<SparseVector>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<JsonValue>,
+    inserted_at: String,
+}
+
+/// Fetch a document (`GET /api/v2/collections/:id/documents/:doc_id`).
+///
+/// Unlike v1, a missing document is a 404 rather than `{"document": null}`.
+pub async fn get_document(
+    Path((collection_id, doc_id)): Path<(String, String)>,
+    State(service): State<Arc<CollectionService>>,
+) -> Result<Json<DocumentResponse>, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
+
+    let doc_id = DocumentId::from_str(&doc_id)
+        .map_err(|e| ApiError::invalid_argument(format!("Invalid id: {}", e)))?;
+
+    let doc = service
+        .get(collection_id, doc_id)
+        .await?
+        .ok_or_else(|| CoreError::not_found("Document", doc_id.to_string()))?;
+
+    Ok(Json(DocumentResponse {
+        id: doc.doc_id.to_string(),
+        external_id: doc.external_id,
+        vector: doc.vector,
+        sparse: doc.sparse,
+        metadata: doc.metadata,
+        inserted_at: doc.inserted_at.to_rfc3339(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct UpsertByExternalIdRequest {
+    vector: Vec<f32>,
+    #[serde(default)]
+    metadata: Option<JsonValue>,
+}
+
+#[derive(Serialize)]
+pub struct UpsertByExternalIdResponse {
+    id: String,
+    external_id: String,
+    /// False when an existing document was replaced
+    created: bool,
+    latency_ms: f64,
+}
+
+/// Insert or replace a document by external ID
+/// (`PUT /api/v2/collections/:id/documents/by-external-id/:external_id`,
+/// also served at `/api/v1/collections/:id/docs/by-external-id/:external_id`).
+///
+/// A replaced document keeps its ID. Responds 201 when a document was
+/// created and 200 when one was replaced.
+#[tracing::instrument(skip(service, req), fields(collection_id = %collection_id))]
+pub async fn upsert_by_external_id(
+    Path((collection_id, external_id)): Path<(String, String)>,
+    State(service): State<Arc<CollectionService>>,
+    Json(req): Json<UpsertByExternalIdRequest>,
+) -> Result<(StatusCode, Json<UpsertByExternalIdResponse>), Response> {
+    let start = std::time::Instant::now();
+    let collection_id = parse_collection_id(&collection_id).map_err(IntoResponse::into_response)?;
+
+    if req.vector.is_empty() {
+        return Err(ApiError::invalid_argument("vector cannot be empty").into_response());
+    }
+
+    // The document ID is assigned by the service; only the payload is checked here
+    let mut doc =
+        VectorDocument::new(DocumentId::new(), Vec::new()).with_external_id(external_id.clone());
+    doc.metadata = req.metadata;
+    validation::validate_document(&doc, service.limits())
+        .map_err(|e| validation_error_response(&e))?;
+
+    let (id, created) = service
+        .upsert_by_external_id(collection_id, external_id.clone(), req.vector, doc.metadata)
+        .await
+        .map_err(|e| ApiError::from(e).into_response())?;
+
+    Ok((
+        if created {
+            StatusCode::CREATED
+        } else {
+            StatusCode::OK
+        },
+        Json(UpsertByExternalIdResponse {
+            id: id.to_string(),
+            external_id,
+            created,
+            latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::{DatabaseId, DistanceMetric};
+    use axum::{
+        body::Body,
+        http::Request,
+        routing::{get, post, put},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_v2_document_and_search_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection("v2".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+
+        let app = Router::new()
+            .route("/api/v2/collections/:id/documents", post(create_document))
+            .route(
+                "/api/v2/collections/:id/documents/:doc_id",
+                get(get_document),
+            )
+            .route("/api/v2/collections/:id/search", post(search))
+            .with_state(Arc::clone(&service));
+        let post_json = |uri: String, body: JsonValue| {
+            Request::post(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(post_json(
+                format!("/api/v2/collections/{}/documents", collection_id),
+                serde_json::json!({"vector": vec![0.1f32; 16], "metadata": {"lang": "en"}}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let created: JsonValue = serde_json::from_slice(&bytes).unwrap();
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let response = app
+            .clone()
+            .oneshot(post_json(
+                format!("/api/v2/collections/{}/search", collection_id),
+                serde_json::json!({"vector": vec![0.1f32; 16], "filter": {"lang": "en"}}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: JsonValue = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["hits"][0]["id"], id);
+        assert_eq!(json["hits"][0]["metadata"]["lang"], "en");
+
+        let response = app
+            .oneshot(
+                Request::get(format!(
+                    "/api/v2/collections/{}/documents/{}",
+                    collection_id,
+                    DocumentId::new()
+                ))
+                .body(Body::empty())
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: JsonValue = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["code"], "DOCUMENT_NOT_FOUND");
+        assert_eq!(json["retryable"], false);
+
+        service.delete_collection(collection_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upsert_by_external_id_keeps_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection("upserts".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+
+        // Registered next to the document ID route, as in the server
+        let app = Router::new()
+            .route(
+                "/api/v2/collections/:id/documents/:doc_id",
+                get(get_document),
+            )
+            .route(
+                "/api/v2/collections/:id/documents/by-external-id/:external_id",
+                put(upsert_by_external_id),
+            )
+            .with_state(Arc::clone(&service));
+        let put_json = |body: JsonValue| {
+            Request::put(format!(
+                "/api/v2/collections/{}/documents/by-external-id/sku-1",
+                collection_id
+            ))
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(put_json(serde_json::json!({"vector": vec![0.1f32; 16]})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let created: JsonValue = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(created["created"], true);
+
+        let response = app
+            .oneshot(put_json(serde_json::json!({
+                "vector": vec![0.2f32; 16],
+                "metadata": {"color": "red"}
+            })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let replaced: JsonValue = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(replaced["created"], false);
+        assert_eq!(replaced["id"], created["id"]);
+
+        let doc_id = DocumentId::from_str(replaced["id"].as_str().unwrap()).unwrap();
+        let doc = service.get(collection_id, doc_id).await.unwrap().unwrap();
+        assert_eq!(doc.external_id.as_deref(), Some("sku-1"));
+        assert_eq!(doc.metadata.unwrap()["color"], "red");
+        assert_eq!(service.get_count(collection_id).await.unwrap(), 1);
+
+        service.delete_collection(collection_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_stream_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection("stream".to_string(), 16, DistanceMetric::L2, None)
+            .await
+            .unwrap();
+        for i in 0..5 {
+            let doc = VectorDocument::new(DocumentId::new(), vec![i as f32; 16]);
+            service.insert(collection_id, doc).await.unwrap();
+        }
+
+        let app = Router::new()
+            .route("/api/v2/collections/:id/search/stream", post(search_stream))
+            .with_state(Arc::clone(&service));
+        let stream = |body: JsonValue| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(
+                        Request::post(format!(
+                            "/api/v2/collections/{}/search/stream",
+                            collection_id
+                        ))
+                        .header("content-type", "application/json")
+                        .body(Body::from(body.to_string()))
+                        .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+                let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                bytes
+                    .split(|b| *b == b'\n')
+                    .filter(|line| !line.is_empty())
+                    .map(|line| serde_json::from_slice::<JsonValue>(line).unwrap())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // Skip the nearest hit, then stream the next four two at a time
+        let batches = stream(serde_json::json!({
+            "vector": vec![0.0f32; 16],
+            "top_k": 4,
+            "offset": 1,
+            "batch_size": 2,
+        }))
+        .await;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0]["hits"].as_array().unwrap().len(), 2);
+        assert_eq!(batches[0]["next_offset"], 3);
+        assert_eq!(batches[0]["done"], false);
+        assert_eq!(batches[1]["next_offset"], 5);
+        assert_eq!(batches[1]["done"], true);
+        assert_eq!(batches[1]["hits"][1]["score"], 16.0);
+
+        // Scrolling past the end yields a single empty, final batch
+        let batches = stream(serde_json::json!({
+            "vector": vec![0.0f32; 16],
+            "offset": 10,
+        }))
+        .await;
+        assert_eq!(batches.len(), 1);
+        assert!(batches[0]["hits"].as_array().unwrap().is_empty());
+        assert_eq!(batches[0]["next_offset"], 10);
+        assert_eq!(batches[0]["done"], true);
+    }
+}
@@ -0,0 +1,161 @@
+//! Collection change feed over WebSocket (`GET /api/v1/collections/:id/watch`).
+//!
+//! After the upgrade, every insert, delete, and tier change on the collection
+//! is pushed to the client as a JSON text message (see
+//! `akidb_service::ChangeEvent`). If the client falls too far behind, a
+//! `{"type": "lagged", "missed": N}` message is sent and the stream continues
+//! with the newest events.
+//!
+//! The feed is server-to-client only. Pings are answered and client data
+//! messages are ignored.
+
+use super::v2::parse_collection_id;
+use crate::error::ApiError;
+use akidb_service::{ChangeEvent, CollectionService};
+use axum::{
+    extract::{
+        ws::{rejection::WebSocketUpgradeRejection, Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    response::Response,
+};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Largest client message accepted; the feed only expects control frames.
+const MAX_CLIENT_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// Upgrade to a WebSocket streaming the collection's change events.
+#[tracing::instrument(skip(service, ws), fields(collection_id = %collection_id))]
+pub async fn watch_collection(
+    Path(collection_id): Path<String>,
+    State(service): State<Arc<CollectionService>>,
+    ws: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Result<Response, ApiError> {
+    let collection_id = parse_collection_id(&collection_id)?;
+    let ws = ws.map_err(|e| ApiError::from((e.status(), e.body_text())))?;
+
+    service.get_collection(collection_id).await?;
+
+    // Subscribe before answering so no event between the response and the
+    // upgrade completing is lost
+    let events = service.events().subscribe_collection(collection_id);
+
+    Ok(ws
+        .max_message_size(MAX_CLIENT_MESSAGE_BYTES)
+        .on_upgrade(move |socket| async move {
+            tracing::debug!("Watch stream opened for collection {}", collection_id);
+            serve_watch(socket, events).await;
+            tracing::debug!("Watch stream closed for collection {}", collection_id);
+        }))
+}
+
+/// Push `events` to the client until either side closes.
+async fn serve_watch(mut socket: WebSocket, mut events: broadcast::Receiver<ChangeEvent>) {
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let text = match event {
+                    Ok(event) => match serde_json::to_string(&event) {
+                        Ok(json) => json,
+                        Err(e) => {
+                            tracing::warn!("Failed to serialize change event: {}", e);
+                            continue;
+                        }
+                    },
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        serde_json::json!({"type": "lagged", "missed": missed}).to_string()
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    return;
+                }
+            }
+            // Pings are answered by the WebSocket implementation
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) => {
+                    // The close reply is sent on the next read
+                    while let Some(Ok(_)) = socket.recv().await {}
+                    return;
+                }
+                Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = socket.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::{CollectionId, DocumentId};
+    use akidb_service::{ChangeKind, EventBus};
+    use axum::{routing::get, Router};
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+    #[tokio::test]
+    async fn test_serve_watch_streams_collection_events() {
+        let bus = EventBus::new();
+        let collection_id = CollectionId::new();
+
+        let app = Router::new().route("/watch", {
+            let bus = bus.clone();
+            get(move |ws: WebSocketUpgrade| async move {
+                let events = bus.subscribe_collection(collection_id);
+                ws.on_upgrade(move |socket| serve_watch(socket, events))
+            })
+        });
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/watch", addr))
+            .await
+            .unwrap();
+
+        // The subscription exists once the upgrade completes
+        while bus.subscriber_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+        let doc_id = DocumentId::new();
+        bus.publish(ChangeEvent::new(
+            CollectionId::new(),
+            ChangeKind::Delete { doc_id },
+        ));
+        bus.publish(ChangeEvent::new(
+            collection_id,
+            ChangeKind::Insert { doc_id },
+        ));
+
+        // Only the event for the watched collection is delivered
+        let ClientMessage::Text(text) = client.next().await.unwrap().unwrap() else {
+            panic!("expected a text message");
+        };
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["type"], "insert");
+        assert_eq!(json["collection_id"], collection_id.to_string());
+
+        client
+            .send(ClientMessage::Ping(b"ok".to_vec()))
+            .await
+            .unwrap();
+        assert_eq!(
+            client.next().await.unwrap().unwrap(),
+            ClientMessage::Pong(b"ok".to_vec())
+        );
+
+        client.send(ClientMessage::Close(None)).await.unwrap();
+        assert!(matches!(
+            client.next().await.unwrap().unwrap(),
+            ClientMessage::Close(_)
+        ));
+    }
+}
@@ -1,2 +1,8 @@
+pub mod error;
 pub mod handlers;
+pub mod middleware;
 pub mod tracing_init;
+pub mod validation;
+
+#[cfg(test)]
+mod test_support;
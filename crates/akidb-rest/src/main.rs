@@ -1,12 +1,42 @@
-use akidb_metadata::{SqliteCollectionRepository, VectorPersistence};
+use akidb_core::Action;
+use akidb_metadata::{
+    SqliteAliasRepository, SqliteApiKeyRepository, SqliteAuditLogRepository,
+    SqliteCollectionRepository, SqliteDatabaseRepository, SqliteFeatureFlagRepository,
+    SqliteJobRepository, SqliteLeaseRepository, SqliteTenantCatalog, SqliteUsageRepository,
+    TierStateRepository, VectorPersistence,
+};
 use akidb_rest::handlers;
-use akidb_service::{CollectionService, Config, EmbeddingManager};
+use akidb_rest::middleware::{
+    compression_layer, cors_layer, AdminAuthLayer, AliasLayer, BackpressureLayer, ClientAddr,
+    CollectionAclLayer, DeprecationLayer, DrainLayer, IdempotencyLayer, MetricsLayer,
+    RateLimitLayer, RateLimiter, TenantLayer, TenantResolver, TraceContextLayer,
+};
+use akidb_service::debug::CountingAllocator;
+use akidb_service::reload::init_logging;
+use akidb_service::tls::ReloadableTlsConfig;
+use akidb_service::{
+    AuditTrail, CdcPublisher, CollectionAcl, CollectionService, Config, ConfigReloader,
+    DrainConfig, EmbeddingManager, FeatureFlags, LeaderElection, OtlpMetricsConfig,
+    OtlpMetricsExporter, ReplicationMonitor, Reranker, SnapshotScheduler, TieringManager,
+    UsageMeter,
+};
 use axum::{
-    routing::{delete, get, post},
-    Router,
+    body::Body,
+    extract::DefaultBodyLimit,
+    http::Request,
+    routing::{delete, get, post, put},
+    Router, ServiceExt,
 };
 use sqlx::SqlitePool;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tower::Layer;
+
+// Heap statistics for /debug/allocator
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -20,51 +50,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     config.validate()?;
 
     // Initialize distributed tracing with OpenTelemetry (optional)
-    // Set ENABLE_TRACING=true to enable Jaeger tracing
-    if std::env::var("ENABLE_TRACING").unwrap_or_else(|_| "false".to_string()) == "true" {
-        tracing::info!("🔍 Initializing distributed tracing with Jaeger...");
-        if let Err(e) = akidb_rest::tracing_init::init_from_env() {
-            tracing::warn!(
-                "⚠️  Failed to initialize tracing: {}. Falling back to basic logging.",
-                e
-            );
-            // Fall back to basic logging
-            let subscriber =
-                tracing_subscriber::fmt().with_max_level(match config.logging.level.as_str() {
-                    "trace" => tracing::Level::TRACE,
-                    "debug" => tracing::Level::DEBUG,
-                    "info" => tracing::Level::INFO,
-                    "warn" => tracing::Level::WARN,
-                    "error" => tracing::Level::ERROR,
-                    _ => tracing::Level::INFO,
-                });
-
-            if config.logging.format == "json" {
-                subscriber.json().init();
+    // Set ENABLE_TRACING=true to enable Jaeger tracing. Only basic logging's
+    // level can be changed by a config reload.
+    let log_level =
+        if std::env::var("ENABLE_TRACING").unwrap_or_else(|_| "false".to_string()) == "true" {
+            tracing::info!("🔍 Initializing distributed tracing with Jaeger...");
+            if let Err(e) = akidb_rest::tracing_init::init_from_env() {
+                tracing::warn!(
+                    "⚠️  Failed to initialize tracing: {}. Falling back to basic logging.",
+                    e
+                );
+                // Fall back to basic logging
+                Some(init_logging(&config.logging))
             } else {
-                subscriber.init();
+                tracing::info!("✅ Distributed tracing initialized");
+                None
             }
         } else {
-            tracing::info!("✅ Distributed tracing initialized");
-        }
-    } else {
-        // Use basic logging (no distributed tracing)
-        let subscriber =
-            tracing_subscriber::fmt().with_max_level(match config.logging.level.as_str() {
-                "trace" => tracing::Level::TRACE,
-                "debug" => tracing::Level::DEBUG,
-                "info" => tracing::Level::INFO,
-                "warn" => tracing::Level::WARN,
-                "error" => tracing::Level::ERROR,
-                _ => tracing::Level::INFO,
-            });
-
-        if config.logging.format == "json" {
-            subscriber.json().init();
-        } else {
-            subscriber.init();
-        }
-    }
+            // Use basic logging (no distributed tracing)
+            Some(init_logging(&config.logging))
+        };
 
     // Initialize SQLite database
     tracing::info!("📦 Connecting to database: {}", config.database.path);
@@ -76,13 +81,119 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .run(&pool)
         .await?;
 
+    // Runtime feature flags, stored in the metadata database and evaluated per tenant
+    let feature_flags = Arc::new(FeatureFlags::new(&config.features.flags).with_repository(
+        Arc::new(SqliteFeatureFlagRepository::new(pool.clone())),
+        Arc::new(SqliteDatabaseRepository::new(pool.clone())),
+    ));
+    feature_flags.refresh().await?;
+    let flag_refresh = akidb_service::feature_flags::spawn_refresh(
+        Arc::clone(&feature_flags),
+        Duration::from_secs(config.features.flag_refresh_interval_seconds),
+    );
+
     // Create repository and service with full persistence (collections + vectors + metrics)
     let repository = Arc::new(SqliteCollectionRepository::new(pool.clone()));
     let vector_persistence = Arc::new(VectorPersistence::new(pool.clone()));
-    let service = Arc::new(CollectionService::with_full_persistence(
-        repository,
-        vector_persistence,
-    ));
+    let service = CollectionService::with_full_persistence(repository, vector_persistence)
+        .with_limits(config.limits.clone())
+        .with_index_graph_persistence(&config.hnsw)
+        .with_idempotency(&config.idempotency)
+        .with_backpressure(&config.backpressure)
+        .with_job_repository(Arc::new(SqliteJobRepository::new(pool.clone())))
+        .with_alias_repository(Arc::new(SqliteAliasRepository::new(pool.clone())))
+        .with_audit(Arc::new(AuditTrail::new(
+            Arc::new(SqliteAuditLogRepository::new(pool.clone())),
+            Arc::new(SqliteDatabaseRepository::new(pool.clone())),
+            &config.audit,
+        )))
+        .with_feature_flags(Arc::clone(&feature_flags));
+    let service = if config.metering.enabled {
+        tracing::info!(
+            "🧾 Metering per-tenant usage, flushing every {}s",
+            config.metering.flush_interval_seconds
+        );
+        service.with_metering(Arc::new(UsageMeter::new(
+            Arc::new(SqliteUsageRepository::new(pool.clone())),
+            Arc::new(SqliteDatabaseRepository::new(pool.clone())),
+        )))
+    } else {
+        service
+    };
+
+    // Leader election for singleton maintenance across processes sharing the database
+    let leader_election = config.leader_election.enabled.then(|| {
+        let election = Arc::new(LeaderElection::from_config(
+            Arc::new(SqliteLeaseRepository::new(pool.clone())),
+            &config.leader_election,
+        ));
+        tracing::info!(
+            "🗳️  Campaigning for lease '{}' as {}",
+            election.name(),
+            election.holder()
+        );
+        let campaign = election.spawn();
+        (election, campaign)
+    });
+
+    // Hot/warm/cold tiering of collections by access pattern
+    let tiering_manager = if config.tiering.enabled {
+        tracing::info!("🌡️  Enabling collection tiering");
+        let mut manager = TieringManager::new(
+            config.tiering.policy.clone(),
+            Arc::new(TierStateRepository::new(pool.clone())),
+        )?;
+        // Under leader election only the leader sweeps (below)
+        if leader_election.is_none() {
+            manager.start_worker();
+        }
+        Some(Arc::new(manager))
+    } else {
+        None
+    };
+    let tiering_sweep = match (&leader_election, &tiering_manager) {
+        (Some((election, _)), Some(manager)) => {
+            let manager = Arc::clone(manager);
+            Some(election.spawn_singleton(
+                "tiering",
+                manager.policy().worker_interval(),
+                move || {
+                    let manager = Arc::clone(&manager);
+                    async move { manager.run_tiering_cycle().await }
+                },
+            ))
+        }
+        _ => None,
+    };
+    let service = match &tiering_manager {
+        Some(manager) => service.with_tiering_manager(Arc::clone(manager)),
+        None => service,
+    };
+
+    // Cross-encoder reranker for search and /rerank (optional)
+    let reranker = match Reranker::from_config(&config.rerank) {
+        Ok(reranker) => reranker.map(Arc::new),
+        Err(e) => {
+            tracing::warn!(
+                "⚠️  Failed to initialize reranker: {}. Reranking will not be available.",
+                e
+            );
+            None
+        }
+    };
+    let service = Arc::new(match &reranker {
+        Some(reranker) => service.with_reranker(Arc::clone(reranker)),
+        None => service,
+    });
+
+    // Jobs run in-process; anything unfinished from a previous run was lost
+    let interrupted_jobs = service.jobs().recover().await?;
+    if interrupted_jobs > 0 {
+        tracing::warn!(
+            "⚠️  Marked {} unfinished job(s) from previous run as failed",
+            interrupted_jobs
+        );
+    }
 
     // Initialize default database_id for RC1 (single-database mode)
     tracing::info!("🔍 Initializing default tenant and database...");
@@ -136,11 +247,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     service.set_default_database_id(database_id).await;
     tracing::info!("✅ Using default database_id: {}", database_id);
 
-    // Load existing collections from database
+    // Load existing collections in the background; not ready until done (see /statusz)
     tracing::info!("🔄 Loading collections from database...");
-    service.load_all_collections().await?;
-    let collection_count = service.list_collections().await?.len();
-    tracing::info!("✅ Loaded {} collection(s)", collection_count);
+    let bootstrap = {
+        let service = Arc::clone(&service);
+        tokio::spawn(async move {
+            match service.bootstrap().await {
+                Ok(()) => {
+                    let status = service.readiness().status();
+                    tracing::info!(
+                        "✅ Loaded {} collection(s), ready to serve traffic",
+                        status.collections_loaded
+                    );
+                }
+                Err(e) => tracing::error!("❌ Failed to load collections: {}", e),
+            }
+        })
+    };
 
     // Initialize EmbeddingManager from configuration
     let embedding_config = &config.embedding;
@@ -150,13 +273,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         embedding_config.model
     );
 
-    let embedding_manager = match EmbeddingManager::from_config(
-        &embedding_config.provider,
-        &embedding_config.model,
-        embedding_config.python_path.as_deref(),
-    )
-    .await
-    {
+    let embedding_manager = match EmbeddingManager::from_embedding_config(embedding_config).await {
         Ok(manager) => {
             tracing::info!(
                 "✅ EmbeddingManager initialized (provider: {}, model: {}, dimension: {})",
@@ -164,7 +281,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 embedding_config.model,
                 manager.dimension()
             );
-            Some(Arc::new(manager))
+            let manager = Arc::new(manager.with_backpressure(&config.backpressure));
+            if embedding_config.warmup {
+                manager.spawn_warmup();
+            }
+            Some(manager)
         }
         Err(e) => {
             tracing::warn!(
@@ -176,9 +297,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     // Build embedding state if manager is available
-    let embedding_state = embedding_manager.map(|manager| {
+    let embedding_state = embedding_manager.clone().map(|manager| {
         Arc::new(handlers::EmbeddingAppState {
             embedding_manager: manager,
+            collection_service: Arc::clone(&service),
         })
     });
 
@@ -187,6 +309,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Kubernetes health and readiness probes
         .route("/health", get(handlers::health_handler))
         .route("/ready", get(handlers::ready_handler))
+        .route("/statusz", get(handlers::statusz_handler))
         .route("/metrics", get(handlers::metrics))
         // Collection management endpoints
         .route("/api/v1/collections", post(handlers::create_collection))
@@ -205,6 +328,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/api/v1/collections/:id/insert",
             post(handlers::insert_vector),
         )
+        .route(
+            "/api/v1/collections/:id/insert_batch",
+            post(handlers::insert_batch),
+        )
         .route(
             "/api/v1/collections/:id/docs/:doc_id",
             get(handlers::get_vector),
@@ -213,16 +340,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/api/v1/collections/:id/docs/:doc_id",
             delete(handlers::delete_vector),
         )
+        .route(
+            "/api/v1/collections/:id/docs/by-external-id/:external_id",
+            put(handlers::upsert_by_external_id),
+        )
+        .route("/api/v1/collections/:id/bulk", post(handlers::bulk_upsert))
+        .route(
+            "/api/v1/collections/:id/watch",
+            get(handlers::watch_collection),
+        )
+        // docs:batchDelete, docs:deleteByFilter (matched as a parameter, see batch_delete_vectors)
+        .route(
+            "/api/v1/collections/:id/docs:action",
+            post(handlers::batch_delete_vectors),
+        )
         // Admin/Operations endpoints (Phase 7 Week 4)
-        .route("/admin/health", get(handlers::health_check))
         .route(
             "/admin/collections/:id/dlq/retry",
             post(handlers::retry_dlq),
         )
+        .route(
+            "/admin/collections/:id/compact",
+            post(handlers::compact_collection),
+        )
+        .route(
+            "/admin/collections/:id/compaction",
+            get(handlers::get_compaction_status),
+        )
+        .route("/admin/collections/:id/wal", get(handlers::get_wal_state))
         .route(
             "/admin/circuit-breaker/reset",
             post(handlers::reset_circuit_breaker),
         )
+        .route("/admin/aliases", get(handlers::list_aliases))
+        .route("/admin/aliases/:name", get(handlers::get_alias))
+        .route("/admin/feature-flags", get(handlers::list_feature_flags))
+        .route(
+            "/admin/feature-flags/:name",
+            get(handlers::get_feature_flag),
+        )
         // Tier management endpoints (Phase 10 Week 3)
         .route(
             "/api/v1/collections/:id/tier",
@@ -233,8 +389,145 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             post(handlers::update_collection_tier),
         )
         .route("/api/v1/metrics/tiers", get(handlers::get_tier_metrics))
+        // Background jobs
+        .route("/api/v1/jobs/:id", get(handlers::get_job))
+        // REST API v2 (v1 responses carry Deprecation headers pointing here)
+        .route("/api/v2/collections", post(handlers::create_collection))
+        .route("/api/v2/collections", get(handlers::list_collections))
+        .route("/api/v2/collections/:id", get(handlers::get_collection))
+        .route(
+            "/api/v2/collections/:id",
+            delete(handlers::delete_collection),
+        )
+        .route("/api/v2/collections/:id/search", post(handlers::search))
+        .route(
+            "/api/v2/collections/:id/search/stream",
+            post(handlers::search_stream),
+        )
+        .route(
+            "/api/v2/collections/:id/documents",
+            post(handlers::create_document),
+        )
+        .route(
+            "/api/v2/collections/:id/documents/:doc_id",
+            get(handlers::get_document),
+        )
+        .route(
+            "/api/v2/collections/:id/documents/:doc_id",
+            delete(handlers::delete_vector),
+        )
+        .route(
+            "/api/v2/collections/:id/documents/by-external-id/:external_id",
+            put(handlers::upsert_by_external_id),
+        )
+        .route(
+            "/api/v2/collections/:id/documents:action",
+            post(handlers::batch_delete_vectors),
+        )
+        .route("/api/v2/collections/:id/bulk", post(handlers::bulk_upsert))
+        .route(
+            "/api/v2/collections/:id/watch",
+            get(handlers::watch_collection),
+        )
+        .route(
+            "/api/v2/collections/:id/tier",
+            get(handlers::get_collection_tier),
+        )
+        .route(
+            "/api/v2/collections/:id/tier",
+            post(handlers::update_collection_tier),
+        )
+        .route("/api/v2/metrics/tiers", get(handlers::get_tier_metrics))
+        .route("/api/v2/jobs/:id", get(handlers::get_job))
         .with_state(Arc::clone(&service));
 
+    // Point-in-time restore rolls a collection back, so it needs an
+    // admin::restore API key
+    let app = app.merge(
+        Router::new()
+            .route(
+                "/admin/collections/:id/restore",
+                post(handlers::restore_collection),
+            )
+            .with_state(Arc::clone(&service))
+            .layer(AdminAuthLayer::new(
+                Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+                Action::CollectionRestore,
+            )),
+    );
+
+    // Reindexing rebuilds the whole index and can change its settings, so
+    // it needs an admin::reindex API key
+    let app = app.merge(
+        Router::new()
+            .route(
+                "/admin/collections/:id/reindex",
+                post(handlers::reindex_collection),
+            )
+            .with_state(Arc::clone(&service))
+            .layer(AdminAuthLayer::new(
+                Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+                Action::CollectionReindex,
+            )),
+    );
+
+    // The job endpoints under /admin cover every tenant's jobs, and
+    // cancelling one stops work a tenant started, so they need an
+    // admin::jobs API key
+    let app = app.merge(
+        Router::new()
+            .route("/admin/jobs", get(handlers::list_jobs))
+            .route("/admin/jobs/:id", get(handlers::get_job))
+            .route("/admin/jobs/:id/cancel", post(handlers::cancel_job))
+            .with_state(Arc::clone(&service))
+            .layer(AdminAuthLayer::new(
+                Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+                Action::JobAdmin,
+            )),
+    );
+
+    // The usage export reports every tenant's traffic and storage, so it
+    // needs an admin::usage API key
+    let app = app.merge(
+        Router::new()
+            .route("/admin/usage", get(handlers::get_usage))
+            .with_state(Arc::clone(&service))
+            .layer(AdminAuthLayer::new(
+                Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+                Action::UsageRead,
+            )),
+    );
+
+    // Repointing an alias redirects its readers and writers, so alias
+    // changes need an admin::aliases API key
+    let app = app.merge(
+        Router::new()
+            .route(
+                "/admin/aliases/:name",
+                put(handlers::set_alias).delete(handlers::delete_alias),
+            )
+            .with_state(Arc::clone(&service))
+            .layer(AdminAuthLayer::new(
+                Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+                Action::AliasWrite,
+            )),
+    );
+
+    // Feature flags switch features on for every tenant, so changing them
+    // needs an admin::feature_flags API key
+    let app = app.merge(
+        Router::new()
+            .route(
+                "/admin/feature-flags/:name",
+                put(handlers::set_feature_flag).delete(handlers::reset_feature_flag),
+            )
+            .with_state(Arc::clone(&service))
+            .layer(AdminAuthLayer::new(
+                Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+                Action::FeatureFlagWrite,
+            )),
+    );
+
     // Clone service for shutdown handler before moving it into router state
     let service_for_shutdown = Arc::clone(&service);
 
@@ -243,6 +536,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("🔌 Adding /api/v1/embed endpoint");
         let embedding_router = Router::new()
             .route("/api/v1/embed", post(handlers::embed_handler))
+            .route("/api/v2/embed", post(handlers::embed_handler))
+            .route("/api/v1/embed/health", get(handlers::embed_health_handler))
+            .route("/api/v2/embed/health", get(handlers::embed_health_handler))
             .with_state(state);
 
         app.merge(embedding_router)
@@ -250,18 +546,386 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         app
     };
 
-    let addr = format!("{}:{}", config.server.host, config.server.rest_port).parse()?;
+    // Detailed health, actively probing the database, bucket, WAL directory and model
+    let app = app.merge(
+        Router::new()
+            .route("/admin/health", get(handlers::health_check))
+            .with_state(Arc::new(handlers::HealthState {
+                collection_service: Arc::clone(&service),
+                embedding_manager: embedding_manager.clone(),
+            })),
+    );
 
-    tracing::info!("🌐 REST server listening on {}", addr);
+    // Drain before shutdown: fail readiness, wait out the grace period, flush.
+    // Restricted to API keys with the admin::drain permission
+    let app = app.merge(
+        Router::new()
+            .route(
+                "/admin/drain",
+                get(handlers::get_drain_status).post(handlers::start_drain),
+            )
+            .with_state(Arc::new(handlers::DrainState {
+                collection_service: Arc::clone(&service),
+                config: config.drain.clone(),
+            }))
+            .layer(AdminAuthLayer::new(
+                Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+                Action::NodeDrain,
+            )),
+    );
+
+    // Replication lag and health, also exported as akidb_replication_* metrics
+    let replication_monitor = Arc::new(ReplicationMonitor::new(
+        Arc::clone(&service),
+        config.replication.clone(),
+    ));
+    if config.replication.role != "none" {
+        Arc::clone(&replication_monitor).spawn_refresh(Duration::from_secs(5));
+    }
+    let app = app.merge(
+        Router::new()
+            .route("/admin/replication", get(handlers::get_replication_status))
+            .with_state(replication_monitor),
+    );
+
+    // Ops-only diagnostics, restricted to API keys with the admin::debug permission
+    let app = if config.debug.enabled {
+        tracing::info!("🩺 Adding /debug endpoints (requires admin::debug API keys)");
+        let debug_state = Arc::new(handlers::DebugState {
+            collection_service: Arc::clone(&service),
+            embedding_manager: embedding_manager.clone(),
+            task_dump_timeout: Duration::from_millis(config.debug.task_dump_timeout_ms),
+        });
+        app.merge(
+            Router::new()
+                .route("/debug/tasks", get(handlers::debug_tasks))
+                .route("/debug/queues", get(handlers::debug_queues))
+                .route("/debug/locks", get(handlers::debug_locks))
+                .route("/debug/allocator", get(handlers::debug_allocator))
+                .with_state(debug_state)
+                .layer(AdminAuthLayer::new(
+                    Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+                    Action::DebugRead,
+                )),
+        )
+    } else {
+        app
+    };
+
+    // Push metrics to an OpenTelemetry collector when OTEL_EXPORTER_OTLP_* is set
+    let otlp_metrics = OtlpMetricsConfig::from_env().map(|otlp_config| {
+        tracing::info!(
+            "📤 Exporting OTLP metrics to {} every {:?}",
+            otlp_config.endpoint,
+            otlp_config.interval
+        );
+        let exporter = Arc::new(OtlpMetricsExporter::new("akidb-rest", otlp_config));
+        let task = Arc::clone(&exporter).spawn(Arc::clone(&service));
+        (exporter, task)
+    });
+
+    // Persist usage rollups periodically
+    let usage_flush = config.metering.enabled.then(|| {
+        akidb_service::metering::spawn_flush(
+            Arc::clone(&service),
+            Duration::from_secs(config.metering.flush_interval_seconds),
+        )
+    });
+
+    // Publish WAL changes to Kafka or NATS
+    let cdc = if config.cdc.enabled {
+        let sink = akidb_service::cdc::connect_sink(&config.cdc).await?;
+        tracing::info!(
+            "📡 Publishing change events to {} at {}",
+            config.cdc.sink,
+            config.cdc.servers
+        );
+        Some(CdcPublisher::new(Arc::clone(&service), sink, &config.cdc)?.spawn())
+    } else {
+        None
+    };
+
+    // Snapshot collections on their schedules, as `snapshot` jobs
+    let snapshot_schedule = (!config.snapshots.schedules.is_empty()).then(|| {
+        tracing::info!(
+            "📸 Scheduling snapshots ({} schedule(s))",
+            config.snapshots.schedules.len()
+        );
+        let scheduler = Arc::new(SnapshotScheduler::new(
+            Arc::clone(&service),
+            config.snapshots.schedules.clone(),
+        ));
+        let interval = Duration::from_secs(config.snapshots.check_interval_seconds);
+        match &leader_election {
+            // Only the leader takes scheduled snapshots
+            Some((election, _)) => election.spawn_singleton("snapshots", interval, move || {
+                let scheduler = Arc::clone(&scheduler);
+                async move { scheduler.run_due().await.map(|_| ()) }
+            }),
+            None => scheduler.spawn(interval),
+        }
+    });
+
+    // S3 garbage collection deletes shared objects, so only the leader runs it
+    let gc = match &leader_election {
+        Some((election, _)) if service.gc_config().enabled => {
+            let service = Arc::clone(&service);
+            Some(election.spawn_singleton(
+                "gc",
+                Duration::from_secs(service.gc_config().interval_seconds),
+                move || {
+                    let service = Arc::clone(&service);
+                    async move {
+                        let report = service.collect_garbage().await?;
+                        tracing::info!(
+                            "🧹 S3 garbage collection deleted {} of {} objects",
+                            report.objects_deleted,
+                            report.objects_scanned
+                        );
+                        Ok(())
+                    }
+                },
+            ))
+        }
+        None if service.gc_config().enabled => {
+            tracing::warn!("⚠️  S3 garbage collection requires [leader_election]; not running it");
+            None
+        }
+        _ => None,
+    };
+    let app = app.merge(
+        Router::new()
+            .route("/admin/leader", get(handlers::get_leader_status))
+            .with_state(
+                leader_election
+                    .as_ref()
+                    .map(|(election, _)| Arc::clone(election)),
+            ),
+    );
+
+    // Add rerank endpoint if a reranker is configured
+    let app = if let Some(reranker) = reranker {
+        tracing::info!("🔌 Adding /api/v1/rerank endpoint");
+        let rerank_router = Router::new()
+            .route("/api/v1/rerank", post(handlers::rerank_handler))
+            .route("/api/v2/rerank", post(handlers::rerank_handler))
+            .with_state(reranker);
+
+        app.merge(rerank_router)
+    } else {
+        app
+    };
+
+    // Replay responses to retried POSTs carrying an Idempotency-Key; inside the
+    // tenant and ACL layers, so replays are checked and scoped by API key (or
+    // tenant, for requests without one)
+    let app = if config.idempotency.enabled {
+        app.layer(IdempotencyLayer::new(Arc::clone(service.idempotency())))
+    } else {
+        app
+    };
+
+    // Scope collection operations by the API key's tenant (or X-Tenant-ID without a key)
+    let app = if config.features.multi_tenancy_enabled {
+        tracing::info!("🏢 Enabling multi-tenancy (X-Tenant-ID)");
+        let resolver = TenantResolver::new(
+            Arc::new(SqliteTenantCatalog::new(pool.clone())),
+            Arc::new(SqliteDatabaseRepository::new(pool.clone())),
+            tenant_id,
+        );
+        app.layer(TenantLayer::new(Arc::new(resolver), Arc::clone(&service)))
+    } else {
+        app
+    };
+
+    // Keep API keys restricted to specific collections out of all others
+    if config.features.require_api_key {
+        tracing::info!("🔑 Requiring an API key on /api/ routes");
+    }
+    let collection_acl = CollectionAcl::new(Arc::new(SqliteApiKeyRepository::new(pool.clone())))
+        .require_key(config.features.require_api_key);
+    let app = app.layer(CollectionAclLayer::new(Arc::new(collection_acl)));
+
+    // Mark v1 responses as deprecated in favour of v2
+    let mut deprecation = DeprecationLayer::new("/api/v1/", "/api/v2");
+    if let Some(sunset) = &config.server.api_v1_sunset {
+        deprecation = deprecation.with_sunset(sunset);
+    }
+    let app = app.layer(deprecation);
+
+    // Reject writes with 429/503 while upload, retry, or embedding queues are backed up
+    let app = if config.backpressure.enabled {
+        app.layer(BackpressureLayer::new(
+            Arc::clone(&service),
+            embedding_manager.clone(),
+        ))
+    } else {
+        app
+    };
+
+    // Reject oversized request bodies with 413 before they are buffered
+    let app = app.layer(DefaultBodyLimit::max(config.limits.max_body_bytes));
+
+    // Count in-flight requests for /admin/drain; refuse new work late in a drain
+    let app = app.layer(DrainLayer::new(Arc::clone(service.drain_controller())));
+
+    // Rate limiting sized from tenant QPS quotas, by client IP for anonymous
+    // requests; always layered so a config reload can switch it on or off
+    let rate_limiter = Arc::new(RateLimiter::with_repositories(
+        Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+        Arc::new(SqliteTenantCatalog::new(pool.clone())),
+    ));
+    rate_limiter.set_enabled(config.features.rate_limiting_enabled);
+    if config.features.rate_limiting_enabled {
+        tracing::info!("🚦 Enabling per-tenant rate limiting");
+    }
+    let app = app.layer(RateLimitLayer::new(Arc::clone(&rate_limiter)));
+
+    // gzip/zstd for large JSON responses (search results, metrics)
+    let app = if config.server.compression.enabled {
+        app.layer(compression_layer(&config.server.compression))
+    } else {
+        app
+    };
+
+    // Per-route request counts and latency (includes rate-limited requests)
+    let app = if config.features.metrics_enabled {
+        akidb_service::metrics::init_metrics();
+        app.layer(MetricsLayer::new())
+    } else {
+        app
+    };
+
+    // Request span joined to the caller's trace via `traceparent`
+    let app = app.layer(TraceContextLayer::new());
+
+    // CORS wraps everything else so preflight requests are answered before
+    // rate limiting and body limits apply
+    let app = if let Some(cors) = &config.server.cors {
+        tracing::info!(
+            "🌍 Enabling CORS for origins: {}",
+            cors.allowed_origins.join(", ")
+        );
+        app.layer(cors_layer(cors)?)
+    } else {
+        app
+    };
+
+    // Apply config file changes on SIGHUP or file change, keeping indexes in memory
+    let config_reload = config.reload.enabled.then(|| {
+        let reloader = Arc::new(ConfigReloader::new(Config::DEFAULT_PATH, config.clone()));
+        if let Some(handle) = log_level {
+            reloader.on_reload(move |config| {
+                if let Err(e) = handle.set_level(&config.logging.level) {
+                    tracing::warn!("⚠️  {}", e);
+                }
+            });
+        }
+        reloader.on_reload(move |config| {
+            rate_limiter.set_enabled(config.features.rate_limiting_enabled)
+        });
+        if let Some(manager) = embedding_manager {
+            reloader.on_reload(move |config| manager.set_tenant_qps(config.embedding.tenant_qps));
+        }
+        if let Some(manager) = tiering_manager.clone() {
+            reloader.on_reload(move |config| {
+                if let Err(e) = manager.set_policy(config.tiering.policy.clone()) {
+                    tracing::warn!("⚠️  Failed to apply tiering policy: {}", e);
+                }
+            });
+        }
+        reloader.spawn(Duration::from_secs(config.reload.watch_interval_seconds))
+    });
+
+    // Resolve collection aliases in paths before routing (wraps the whole router)
+    let app = AliasLayer::new(Arc::clone(&service)).layer(app);
+
+    let addr: std::net::SocketAddr =
+        format!("{}:{}", config.server.host, config.server.rest_port).parse()?;
 
     // Setup graceful shutdown
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
-        .with_graceful_shutdown(shutdown_signal(service_for_shutdown))
-        .await?;
+    if let Some(tls_config) = &config.server.tls {
+        let tls = Arc::new(ReloadableTlsConfig::load(
+            tls_config,
+            vec![b"http/1.1".to_vec()],
+        )?);
+        tls.spawn_reloader();
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!(
+            "🌐 REST server listening on {} ({})",
+            addr,
+            if tls.is_mutual() { "mTLS" } else { "TLS" }
+        );
+
+        let incoming = ReceiverStream::new(tls.accept(listener)).map(Ok::<_, std::io::Error>);
+        axum::Server::builder(hyper::server::accept::from_stream(incoming))
+            .serve(
+                ServiceExt::<Request<Body>>::into_make_service_with_connect_info::<ClientAddr>(app),
+            )
+            .with_graceful_shutdown(shutdown_signal(service_for_shutdown, config.drain.clone()))
+            .await?;
+    } else {
+        tracing::info!("🌐 REST server listening on {}", addr);
+        axum::Server::bind(&addr)
+            .serve(
+                ServiceExt::<Request<Body>>::into_make_service_with_connect_info::<ClientAddr>(app),
+            )
+            .with_graceful_shutdown(shutdown_signal(service_for_shutdown, config.drain.clone()))
+            .await?;
+    }
 
     tracing::info!("✅ Server shutdown complete");
 
+    // Stop waiting for the object store if it never became reachable
+    bootstrap.abort();
+    flag_refresh.abort();
+
+    if let Some(task) = config_reload {
+        task.abort();
+    }
+
+    // Unacknowledged events are published again after the restart
+    if let Some(task) = cdc {
+        task.abort();
+    }
+
+    if let Some(task) = snapshot_schedule {
+        task.abort();
+    }
+
+    if let Some(task) = gc {
+        task.abort();
+    }
+
+    if let Some(task) = tiering_sweep {
+        task.abort();
+    }
+
+    // Hand over the lease instead of letting it expire
+    if let Some((election, campaign)) = leader_election {
+        campaign.abort();
+        if let Err(e) = election.resign().await {
+            tracing::warn!("Failed to release lease '{}': {}", election.name(), e);
+        }
+    }
+
+    // Persist usage counted since the last flush
+    if let Some(task) = usage_flush {
+        task.abort();
+        if let Err(e) = service.flush_usage().await {
+            tracing::warn!("Final usage flush failed: {}", e);
+        }
+    }
+
+    // Flush final metric values
+    if let Some((exporter, task)) = otlp_metrics {
+        task.abort();
+        if let Err(e) = exporter.export(&service).await {
+            tracing::warn!("Final OTLP metrics export failed: {}", e);
+        }
+    }
+
     // Shutdown tracing provider (flushes pending spans)
     if std::env::var("ENABLE_TRACING").unwrap_or_else(|_| "false".to_string()) == "true" {
         tracing::info!("🔍 Shutting down tracing...");
@@ -275,9 +939,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///
 /// When a signal is received, this function:
 /// 1. Logs the signal type
-/// 2. Calls CollectionService::shutdown() to flush WAL, stop background tasks, etc.
-/// 3. Returns to allow Axum to complete in-flight requests
-async fn shutdown_signal(service: Arc<CollectionService>) {
+/// 2. Drains (unless `POST /admin/drain` already did), so the load balancer
+///    stops routing here before the listener closes
+/// 3. Calls CollectionService::shutdown() to flush WAL, stop background tasks, etc.
+/// 4. Returns to allow Axum to complete in-flight requests
+async fn shutdown_signal(service: Arc<CollectionService>, drain: DrainConfig) {
     use tokio::signal;
 
     let ctrl_c = async {
@@ -306,6 +972,13 @@ async fn shutdown_signal(service: Arc<CollectionService>) {
         },
     }
 
+    service
+        .drain(
+            Duration::from_secs(drain.grace_period_seconds),
+            Duration::from_secs(drain.timeout_seconds),
+        )
+        .await;
+
     // Shutdown collection service (flush WAL, stop background tasks)
     if let Err(e) = service.shutdown().await {
         tracing::error!("❌ Error during collection service shutdown: {}", e);
@@ -0,0 +1,181 @@
+//! API key authorization for ops-only routes.
+//!
+//! Every request must carry an API key (`X-API-Key` header or
+//! `Authorization: Bearer`) that exists, has not expired and grants the
+//! required permission (e.g. `admin::debug`). Requests without a valid key get
+//! `401 Unauthorized`; keys lacking the permission get `403 Forbidden`.
+
+use super::rate_limit::extract_api_key;
+use crate::error::ApiError;
+use akidb_core::{hash_api_key, Action, ApiKeyRepository, ErrorCode};
+use axum::body::BoxBody;
+use axum::http::{Request, Response, StatusCode};
+use axum::response::IntoResponse;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Tower layer admitting only requests whose API key grants `permission`.
+#[derive(Clone)]
+pub struct AdminAuthLayer {
+    api_keys: Arc<dyn ApiKeyRepository>,
+    permission: Action,
+}
+
+impl AdminAuthLayer {
+    /// Creates a layer checking keys in `api_keys` for `permission`.
+    pub fn new(api_keys: Arc<dyn ApiKeyRepository>, permission: Action) -> Self {
+        Self {
+            api_keys,
+            permission,
+        }
+    }
+
+    async fn authorize(&self, api_key: Option<&str>) -> Result<(), ApiError> {
+        let unauthorized = |message: &str| {
+            ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::PermissionDenied,
+                message,
+            )
+        };
+
+        let api_key = match api_key {
+            Some(key) if !key.is_empty() => key,
+            _ => return Err(unauthorized("An API key is required")),
+        };
+        let descriptor = match self.api_keys.get_by_hash(&hash_api_key(api_key)).await? {
+            Some(descriptor) if !descriptor.is_expired() => descriptor,
+            _ => return Err(unauthorized("Invalid or expired API key")),
+        };
+
+        if !descriptor.has_permission(self.permission.as_str()) {
+            return Err(ApiError::new(
+                StatusCode::FORBIDDEN,
+                ErrorCode::PermissionDenied,
+                format!(
+                    "API key lacks the '{}' permission",
+                    self.permission.as_str()
+                ),
+            )
+            .with_detail("key_id", descriptor.key_id.to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl<S> Layer<S> for AdminAuthLayer {
+    type Service = AdminAuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AdminAuthService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`AdminAuthLayer`].
+#[derive(Clone)]
+pub struct AdminAuthService<S> {
+    inner: S,
+    layer: AdminAuthLayer,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for AdminAuthService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let layer = self.layer.clone();
+        // Use the service that was driven to readiness; leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let api_key = extract_api_key(req.headers()).map(str::to_string);
+            if let Err(rejection) = layer.authorize(api_key.as_deref()).await {
+                return Ok(rejection.into_response());
+            }
+            inner.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::{ApiKeyDescriptor, TenantCatalog, TenantDescriptor};
+    use akidb_metadata::{SqliteApiKeyRepository, SqliteTenantCatalog};
+    use axum::{body::Body, routing::get, Router};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_requires_key_with_permission() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        akidb_metadata::run_migrations(&pool).await.unwrap();
+        let tenant = TenantDescriptor::new("ops", "ops");
+        SqliteTenantCatalog::new(pool.clone())
+            .create(&tenant)
+            .await
+            .unwrap();
+
+        let api_keys = Arc::new(SqliteApiKeyRepository::new(pool));
+        for (key, permissions) in [
+            ("ak_admin", vec![Action::DebugRead.as_str().to_string()]),
+            (
+                "ak_reader",
+                vec![Action::CollectionRead.as_str().to_string()],
+            ),
+        ] {
+            let descriptor =
+                ApiKeyDescriptor::new(tenant.tenant_id, key.to_string(), permissions, None, None);
+            api_keys
+                .create(&descriptor, &hash_api_key(key))
+                .await
+                .unwrap();
+        }
+
+        let app = Router::new()
+            .route("/debug/locks", get(|| async { "ok" }))
+            .layer(AdminAuthLayer::new(api_keys, Action::DebugRead));
+        let call = |key: Option<&str>| {
+            let mut builder = Request::get("/debug/locks");
+            if let Some(key) = key {
+                builder = builder.header("x-api-key", key);
+            }
+            app.clone().oneshot(builder.body(Body::empty()).unwrap())
+        };
+
+        assert_eq!(call(None).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            call(Some("ak_unknown")).await.unwrap().status(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            call(Some("ak_reader")).await.unwrap().status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            call(Some("ak_admin")).await.unwrap().status(),
+            StatusCode::OK
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Collection aliases in REST paths.
+//!
+//! Rewrites `/api/<version>/collections/<alias>/...` to the ID of the
+//! collection the alias points to, so handlers and the ACL and tenant layers
+//! only ever see collection IDs and all check the same collection even if
+//! the alias is swapped mid-request. Unknown aliases get `404 Not Found`.
+//!
+//! `Router::layer` runs after routing, when path parameters have already
+//! been extracted, so this layer must wrap the whole router instead.
+
+use crate::error::ApiError;
+use akidb_core::{CollectionId, CoreError};
+use akidb_service::CollectionService;
+use axum::body::BoxBody;
+use axum::http::uri::PathAndQuery;
+use axum::http::{Request, Response, Uri};
+use axum::response::IntoResponse;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Tower layer resolving collection aliases in request paths.
+#[derive(Clone)]
+pub struct AliasLayer {
+    service: Arc<CollectionService>,
+}
+
+impl AliasLayer {
+    /// Creates a layer resolving aliases through `service`.
+    pub fn new(service: Arc<CollectionService>) -> Self {
+        Self { service }
+    }
+}
+
+impl<S> Layer<S> for AliasLayer {
+    type Service = AliasService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AliasService {
+            inner,
+            service: Arc::clone(&self.service),
+        }
+    }
+}
+
+/// Service produced by [`AliasLayer`].
+#[derive(Clone)]
+pub struct AliasService<S> {
+    inner: S,
+    service: Arc<CollectionService>,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for AliasService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        let service = Arc::clone(&self.service);
+        // Use the service that was driven to readiness; leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let Some(alias) = alias_from_path(req.uri().path()) else {
+                return inner.call(req).await;
+            };
+
+            let collection_id = match service.resolve_collection(alias).await {
+                Ok(collection_id) => collection_id,
+                Err(e) => return Ok(ApiError::from(e).into_response()),
+            };
+            match with_collection_id(req.uri(), collection_id) {
+                Some(uri) => *req.uri_mut() = uri,
+                None => {
+                    let e = CoreError::internal(format!("cannot rewrite path {}", req.uri()));
+                    return Ok(ApiError::from(e).into_response());
+                }
+            }
+            inner.call(req).await
+        })
+    }
+}
+
+/// Collection segment of `/api/<version>/collections/<segment>/...` when it
+/// is not a collection ID.
+fn alias_from_path(path: &str) -> Option<&str> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    match (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) {
+        (Some("api"), Some(_), Some("collections"), Some(segment))
+            if !segment.is_empty() && CollectionId::from_str(segment).is_err() =>
+        {
+            Some(segment)
+        }
+        _ => None,
+    }
+}
+
+/// `uri` with its collection segment replaced by `collection_id`.
+fn with_collection_id(uri: &Uri, collection_id: CollectionId) -> Option<Uri> {
+    let mut segments: Vec<String> = uri.path().split('/').map(str::to_string).collect();
+    // ["", "api", <version>, "collections", <alias>, ...]
+    *segments.get_mut(4)? = collection_id.to_string();
+    let path = segments.join("/");
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path,
+    };
+
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(PathAndQuery::from_str(&path_and_query).ok()?);
+    Uri::from_parts(parts).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::{DatabaseId, DistanceMetric};
+    use axum::body::Body;
+    use axum::extract::Path;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_alias_from_path() {
+        let id = CollectionId::new();
+        assert_eq!(
+            alias_from_path("/api/v2/collections/prod-docs/search"),
+            Some("prod-docs")
+        );
+        assert_eq!(
+            alias_from_path("/api/v1/collections/prod-docs"),
+            Some("prod-docs")
+        );
+        assert_eq!(
+            alias_from_path(&format!("/api/v1/collections/{}/query", id)),
+            None
+        );
+        assert_eq!(alias_from_path("/api/v2/collections"), None);
+        assert_eq!(alias_from_path("/api/v2/collections/"), None);
+        assert_eq!(alias_from_path("/admin/aliases/prod-docs"), None);
+    }
+
+    #[tokio::test]
+    async fn test_rewrites_alias_before_routing() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        service.set_default_database_id(DatabaseId::new()).await;
+        let collection_id = service
+            .create_collection("docs".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+        service.set_alias("prod-docs", collection_id).await.unwrap();
+
+        let router = Router::new().route(
+            "/api/v2/collections/:id/search",
+            get(|Path(id): Path<String>| async move { id }),
+        );
+        let app = AliasLayer::new(Arc::clone(&service)).layer(router);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get("/api/v2/collections/prod-docs/search?top_k=3")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, collection_id.to_string());
+
+        let response = app
+            .oneshot(
+                Request::get("/api/v2/collections/missing/search")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,157 @@
+//! Queue-depth backpressure for REST writes (`[backpressure]` config).
+//!
+//! Document inserts and bulk upserts are rejected while the storage upload or
+//! retry queues are over their thresholds, and `/embed` requests while too
+//! many embedding calls are in flight. Rejected requests get `429 Too Many
+//! Requests` (or `503 Service Unavailable` when S3 retries are backing up)
+//! with a `Retry-After` that grows with the backlog; see
+//! [`akidb_service::backpressure`].
+
+use crate::error::{status_for_code, ApiError};
+use akidb_service::{CollectionService, EmbeddingManager, Overload};
+use axum::body::BoxBody;
+use axum::http::{header, HeaderValue, Method, Request, Response};
+use axum::response::IntoResponse;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Path suffixes of POST routes that queue S3 uploads.
+const WRITE_ROUTE_SUFFIXES: &[&str] = &["/insert", "/documents", "/bulk"];
+
+/// Renders an overload as an error body with `Retry-After`.
+pub fn overload_response(overload: &Overload) -> Response<BoxBody> {
+    let code = overload.code();
+    let mut response = ApiError::new(status_for_code(code), code, overload.to_string())
+        .with_detail("queue", overload.queue.as_str())
+        .with_detail("depth", overload.depth)
+        .with_detail("limit", overload.limit)
+        .into_response();
+    response.headers_mut().insert(
+        header::RETRY_AFTER,
+        HeaderValue::from(overload.retry_after_secs()),
+    );
+    response
+}
+
+/// Tower layer rejecting writes while internal queues are backed up.
+#[derive(Clone)]
+pub struct BackpressureLayer {
+    service: Arc<CollectionService>,
+    embedding: Option<Arc<EmbeddingManager>>,
+}
+
+impl BackpressureLayer {
+    /// Creates a layer checking `service`'s storage queues and, when given,
+    /// the embedding manager's in-flight requests.
+    pub fn new(service: Arc<CollectionService>, embedding: Option<Arc<EmbeddingManager>>) -> Self {
+        Self { service, embedding }
+    }
+
+    async fn check(&self, method: &Method, path: &str) -> Result<(), Overload> {
+        if method != Method::POST {
+            return Ok(());
+        }
+        if path.ends_with("/embed") {
+            return match &self.embedding {
+                Some(embedding) => embedding.check_backpressure(),
+                None => Ok(()),
+            };
+        }
+        if path.starts_with("/api/")
+            && WRITE_ROUTE_SUFFIXES
+                .iter()
+                .any(|suffix| path.ends_with(suffix))
+        {
+            return self.service.check_write_backpressure().await;
+        }
+        Ok(())
+    }
+}
+
+impl<S> Layer<S> for BackpressureLayer {
+    type Service = BackpressureService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BackpressureService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`BackpressureLayer`].
+#[derive(Clone)]
+pub struct BackpressureService<S> {
+    inner: S,
+    layer: BackpressureLayer,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for BackpressureService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let layer = self.layer.clone();
+        // Use the service that was driven to readiness; leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+
+        Box::pin(async move {
+            if let Err(overload) = layer.check(&method, &path).await {
+                tracing::warn!(
+                    queue = %overload.queue,
+                    depth = overload.depth,
+                    limit = overload.limit,
+                    "Rejecting {} {}: queue over threshold",
+                    method,
+                    path
+                );
+                return Ok(overload_response(&overload));
+            }
+            inner.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_service::QueueKind;
+    use axum::http::StatusCode;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_overload_response() {
+        let overload = Overload {
+            queue: QueueKind::Retries,
+            depth: 1_500,
+            limit: 1_000,
+            retry_after: Duration::from_secs(16),
+        };
+        let response = overload_response(&overload);
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "16");
+
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["code"], "UNAVAILABLE");
+        assert_eq!(body["details"]["queue"], "s3_retry");
+        assert_eq!(body["details"]["depth"], 1_500);
+    }
+}
@@ -0,0 +1,243 @@
+//! Per-API-key collection allowlists for the REST API.
+//!
+//! Requests addressing a collection (`/api/<version>/collections/<id>/...`)
+//! with an API key restricted to other collections get `403 Forbidden`. Every
+//! `/api/` request carries the key's [`CollectionGrants`] as an extension, so
+//! handlers can filter listings and check collections named in the body, and
+//! requests with a valid, unexpired key also carry its [`KeyIdentity`].
+//! When [`CollectionAcl::require_key`] is set, `/api/` requests without a
+//! valid key get `401 Unauthorized`. See [`CollectionAcl`] for how keys are
+//! resolved.
+
+use super::rate_limit::extract_api_key;
+use super::tenant::collection_id_from_path;
+use crate::error::ApiError;
+use akidb_core::{ApiKeyId, CollectionId, ErrorCode};
+use akidb_service::{CollectionAccess, CollectionAcl, CollectionGrants, KeyIdentity};
+use axum::body::BoxBody;
+use axum::http::{Request, Response, StatusCode};
+use axum::response::IntoResponse;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Error for a key that may not access `collection_id`.
+pub fn collection_denied(key_id: ApiKeyId, collection_id: CollectionId) -> ApiError {
+    ApiError::new(
+        StatusCode::FORBIDDEN,
+        ErrorCode::PermissionDenied,
+        format!("API key may not access collection {}", collection_id),
+    )
+    .with_detail("key_id", key_id.to_string())
+    .with_detail("collection_id", collection_id.to_string())
+}
+
+/// Checks a collection named in a request body against the request's grants.
+///
+/// Requests that did not pass through [`CollectionAclLayer`] carry no grants
+/// and are allowed.
+pub fn check_collection_grant(
+    grants: Option<&CollectionGrants>,
+    collection_id: CollectionId,
+) -> Result<(), ApiError> {
+    match grants.map(|grants| grants.access(collection_id)) {
+        Some(CollectionAccess::Denied { key_id }) => Err(collection_denied(key_id, collection_id)),
+        _ => Ok(()),
+    }
+}
+
+/// Tower layer enforcing API key collection allowlists.
+#[derive(Clone)]
+pub struct CollectionAclLayer {
+    acl: Arc<CollectionAcl>,
+}
+
+impl CollectionAclLayer {
+    /// Creates a layer checking requests against `acl`.
+    pub fn new(acl: Arc<CollectionAcl>) -> Self {
+        Self { acl }
+    }
+}
+
+impl<S> Layer<S> for CollectionAclLayer {
+    type Service = CollectionAclService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CollectionAclService {
+            inner,
+            acl: Arc::clone(&self.acl),
+        }
+    }
+}
+
+/// Service produced by [`CollectionAclLayer`].
+#[derive(Clone)]
+pub struct CollectionAclService<S> {
+    inner: S,
+    acl: Arc<CollectionAcl>,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for CollectionAclService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        let acl = Arc::clone(&self.acl);
+        // Use the service that was driven to readiness; leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            if !req.uri().path().starts_with("/api/") {
+                return inner.call(req).await;
+            }
+
+            let api_key = extract_api_key(req.headers());
+            let grants = match acl.grants(api_key).await {
+                Ok(Some(grants)) => grants,
+                Ok(None) => {
+                    let message = match api_key {
+                        Some(key) if !key.is_empty() => "Invalid or expired API key",
+                        _ => "An API key is required",
+                    };
+                    return Ok(ApiError::new(
+                        StatusCode::UNAUTHORIZED,
+                        ErrorCode::PermissionDenied,
+                        message,
+                    )
+                    .into_response());
+                }
+                Err(e) => return Ok(ApiError::from(e).into_response()),
+            };
+
+            if let Some(collection_id) = collection_id_from_path(req.uri().path()) {
+                if let Err(e) = check_collection_grant(Some(&grants), collection_id) {
+                    return Ok(e.into_response());
+                }
+            }
+
+            match acl.identity(api_key).await {
+                Ok(Some(identity)) => {
+                    req.extensions_mut().insert::<KeyIdentity>(identity);
+                }
+                Ok(None) => {}
+                Err(e) => return Ok(ApiError::from(e).into_response()),
+            }
+
+            req.extensions_mut().insert(grants);
+            inner.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::{
+        hash_api_key, ApiKeyDescriptor, ApiKeyRepository, CollectionId, TenantCatalog,
+        TenantDescriptor,
+    };
+    use akidb_metadata::{SqliteApiKeyRepository, SqliteTenantCatalog};
+    use axum::{body::Body, routing::get, Extension, Router};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_restricted_key_only_reaches_allowed_collections() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        akidb_metadata::run_migrations(&pool).await.unwrap();
+        let tenant = TenantDescriptor::new("acme", "acme");
+        SqliteTenantCatalog::new(pool.clone())
+            .create(&tenant)
+            .await
+            .unwrap();
+
+        let ingest = CollectionId::new();
+        let other = CollectionId::new();
+        let api_keys = Arc::new(SqliteApiKeyRepository::new(pool));
+        for (key, allowed) in [("ak_ingest", Some(vec![ingest])), ("ak_full", None)] {
+            let mut descriptor =
+                ApiKeyDescriptor::new(tenant.tenant_id, key.to_string(), vec![], None, None);
+            descriptor.allowed_collections = allowed;
+            api_keys
+                .create(&descriptor, &hash_api_key(key))
+                .await
+                .unwrap();
+        }
+
+        for required in [false, true] {
+            let acl = CollectionAcl::new(api_keys.clone()).require_key(required);
+            let app = Router::new()
+                .route("/api/v2/collections/:id/search", get(|| async { "ok" }))
+                .route(
+                    "/api/v2/collections",
+                    get(
+                        move |Extension(grants): Extension<CollectionGrants>| async move {
+                            [ingest, other]
+                                .into_iter()
+                                .filter(|id| grants.allows(*id))
+                                .count()
+                                .to_string()
+                        },
+                    ),
+                )
+                .layer(CollectionAclLayer::new(Arc::new(acl)));
+            let call = |path: String, key: Option<&str>| {
+                let mut builder = Request::get(path);
+                if let Some(key) = key {
+                    builder = builder.header("x-api-key", key);
+                }
+                app.clone().oneshot(builder.body(Body::empty()).unwrap())
+            };
+
+            // Without a required key, anonymous and unknown keys fall through
+            let anonymous = if required {
+                StatusCode::UNAUTHORIZED
+            } else {
+                StatusCode::OK
+            };
+            for (collection_id, key, status) in [
+                (ingest, Some("ak_ingest"), StatusCode::OK),
+                (other, Some("ak_ingest"), StatusCode::FORBIDDEN),
+                (other, Some("ak_full"), StatusCode::OK),
+                (other, Some("ak_unknown"), anonymous),
+                (other, None, anonymous),
+            ] {
+                let path = format!("/api/v2/collections/{}/search", collection_id);
+                assert_eq!(
+                    call(path, key).await.unwrap().status(),
+                    status,
+                    "{:?} on {} (required: {})",
+                    key,
+                    collection_id,
+                    required
+                );
+            }
+
+            // Listings only see the key's collections
+            for (key, visible) in [("ak_ingest", "1"), ("ak_full", "2")] {
+                let response = call("/api/v2/collections".to_string(), Some(key))
+                    .await
+                    .unwrap();
+                let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                assert_eq!(body, visible, "{} (required: {})", key, required);
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Response compression (gzip/zstd) for large search results and metrics.
+//!
+//! The encoding is negotiated from `Accept-Encoding`. Only responses whose
+//! media type is listed in [`CompressionConfig::content_types`] and whose body
+//! is at least `min_size_bytes` are compressed; WebSocket upgrades and other
+//! content pass through untouched.
+
+use akidb_service::CompressionConfig;
+use axum::body::HttpBody;
+use axum::http::{header, HeaderMap};
+use std::sync::Arc;
+use tower_http::compression::predicate::{And, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+
+/// Compresses responses whose media type is in an allowlist.
+#[derive(Clone)]
+pub struct ContentTypeAllowlist {
+    content_types: Arc<[String]>,
+}
+
+impl ContentTypeAllowlist {
+    fn matches(&self, headers: &HeaderMap) -> bool {
+        let Some(content_type) = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+        else {
+            return false;
+        };
+        // Ignore parameters such as `; charset=utf-8` or `; version=0.0.4`
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        self.content_types
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(media_type))
+    }
+}
+
+impl Predicate for ContentTypeAllowlist {
+    fn should_compress<B>(&self, response: &axum::http::Response<B>) -> bool
+    where
+        B: HttpBody,
+    {
+        self.matches(response.headers())
+    }
+}
+
+/// Builds the compression layer from configuration.
+pub fn compression_layer(
+    config: &CompressionConfig,
+) -> CompressionLayer<And<SizeAbove, ContentTypeAllowlist>> {
+    let allowlist = ContentTypeAllowlist {
+        content_types: config.content_types.clone().into(),
+    };
+
+    CompressionLayer::new()
+        .gzip(true)
+        .zstd(true)
+        .no_br()
+        .no_deflate()
+        .compress_when(SizeAbove::new(config.min_size_bytes).and(allowlist))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(config: &CompressionConfig) -> Router {
+        Router::new()
+            .route("/json", get(|| async { axum::Json(vec!["akidb"; 512]) }))
+            .route("/small", get(|| async { axum::Json(vec!["akidb"]) }))
+            .route(
+                "/html",
+                get(|| async { axum::response::Html("<p>akidb</p>".repeat(512)) }),
+            )
+            .layer(compression_layer(config))
+    }
+
+    async fn encoding(app: Router, uri: &str, accept: &str) -> Option<String> {
+        let response = app
+            .oneshot(
+                Request::get(uri)
+                    .header("accept-encoding", accept)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response
+            .headers()
+            .get("content-encoding")
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_compresses_large_allowed_responses() {
+        let config = CompressionConfig::default();
+
+        assert_eq!(
+            encoding(app(&config), "/json", "gzip").await.as_deref(),
+            Some("gzip")
+        );
+        assert_eq!(
+            encoding(app(&config), "/json", "zstd").await.as_deref(),
+            Some("zstd")
+        );
+        assert_eq!(encoding(app(&config), "/json", "identity").await, None);
+        assert_eq!(encoding(app(&config), "/small", "gzip").await, None);
+        assert_eq!(encoding(app(&config), "/html", "gzip").await, None);
+    }
+}
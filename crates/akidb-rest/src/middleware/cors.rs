@@ -0,0 +1,111 @@
+//! CORS for browser-based clients (dashboards calling the REST API directly).
+//!
+//! Built from [`CorsConfig`]; preflight (`OPTIONS`) requests are answered by
+//! the layer itself, so it should wrap every other layer.
+
+use akidb_service::CorsConfig;
+use axum::http::{HeaderName, HeaderValue, Method};
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Builds the CORS layer, rejecting origins, methods or headers that are not
+/// valid HTTP tokens.
+pub fn cors_layer(config: &CorsConfig) -> Result<CorsLayer, String> {
+    let origins = if config.allows_any_origin() {
+        AllowOrigin::any()
+    } else {
+        let origins = config
+            .allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin)
+                    .map_err(|e| format!("invalid CORS origin '{}': {}", origin, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        AllowOrigin::list(origins)
+    };
+
+    let methods = config
+        .allowed_methods
+        .iter()
+        .map(|method| {
+            Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                .map_err(|e| format!("invalid CORS method '{}': {}", method, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let headers = config
+        .allowed_headers
+        .iter()
+        .map(|header| {
+            HeaderName::from_bytes(header.as_bytes())
+                .map_err(|e| format!("invalid CORS header '{}': {}", header, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(headers)
+        .allow_credentials(config.allow_credentials)
+        .max_age(Duration::from_secs(config.max_age_seconds)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_preflight_allowed_origin() {
+        let config = CorsConfig::new(vec!["https://dashboard.example.com".to_string()]);
+        let app = Router::new()
+            .route("/api/v2/collections", get(|| async { "ok" }))
+            .layer(cors_layer(&config).unwrap());
+
+        let preflight = |origin: &str| {
+            Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/api/v2/collections")
+                .header("origin", origin)
+                .header("access-control-request-method", "POST")
+                .header("access-control-request-headers", "content-type")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(preflight("https://dashboard.example.com"))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        let headers = response.headers();
+        assert_eq!(
+            headers["access-control-allow-origin"],
+            "https://dashboard.example.com"
+        );
+        assert!(headers["access-control-allow-methods"]
+            .to_str()
+            .unwrap()
+            .contains("POST"));
+        assert_eq!(headers["access-control-max-age"], "3600");
+
+        let response = app
+            .oneshot(preflight("https://evil.example.com"))
+            .await
+            .unwrap();
+        assert!(response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+    }
+
+    #[test]
+    fn test_invalid_header_rejected() {
+        let mut config = CorsConfig::new(vec!["*".to_string()]);
+        config.allowed_headers = vec!["bad header".to_string()];
+        assert!(cors_layer(&config).is_err());
+    }
+}
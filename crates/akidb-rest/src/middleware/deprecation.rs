@@ -0,0 +1,136 @@
+//! Deprecation headers for superseded API versions.
+//!
+//! Responses to requests under a deprecated path prefix (e.g. `/api/v1/`) carry
+//! `Deprecation: true` and a `Link: <successor>; rel="successor-version"`
+//! header (RFC 8594 / draft-ietf-httpapi-deprecation-header), plus `Sunset`
+//! when a removal date has been announced. Other requests pass through
+//! untouched.
+
+use axum::http::{HeaderValue, Request, Response};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Tower layer marking responses under `prefix` as deprecated.
+#[derive(Clone)]
+pub struct DeprecationLayer {
+    prefix: &'static str,
+    link: HeaderValue,
+    sunset: Option<HeaderValue>,
+}
+
+impl DeprecationLayer {
+    /// Marks paths starting with `prefix` as deprecated in favour of `successor`.
+    pub fn new(prefix: &'static str, successor: &str) -> Self {
+        let link = format!("<{}>; rel=\"successor-version\"", successor);
+        Self {
+            prefix,
+            link: HeaderValue::from_str(&link).expect("successor must be a valid header value"),
+            sunset: None,
+        }
+    }
+
+    /// Announces the removal date (an HTTP-date, e.g. `Wed, 01 Jul 2026 00:00:00 GMT`).
+    pub fn with_sunset(mut self, http_date: &str) -> Self {
+        self.sunset = HeaderValue::from_str(http_date).ok();
+        self
+    }
+}
+
+impl<S> Layer<S> for DeprecationLayer {
+    type Service = DeprecationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DeprecationService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`DeprecationLayer`].
+#[derive(Clone)]
+pub struct DeprecationService<S> {
+    inner: S,
+    layer: DeprecationLayer,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for DeprecationService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let deprecated = req.uri().path().starts_with(self.layer.prefix);
+        let layer = self.layer.clone();
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let mut response = future.await?;
+            if deprecated {
+                let headers = response.headers_mut();
+                headers.insert("deprecation", HeaderValue::from_static("true"));
+                headers.append("link", layer.link);
+                if let Some(sunset) = layer.sunset {
+                    headers.insert("sunset", sunset);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{Body, BoxBody};
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_deprecation_headers_only_on_prefix() {
+        let service = DeprecationLayer::new("/api/v1/", "/api/v2")
+            .with_sunset("Wed, 01 Jul 2026 00:00:00 GMT")
+            .layer(tower::service_fn(|_req: Request<Body>| async {
+                Ok::<_, Infallible>(Response::new(BoxBody::default()))
+            }));
+
+        let response = service
+            .clone()
+            .oneshot(
+                Request::get("/api/v1/collections")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["deprecation"], "true");
+        assert_eq!(
+            response.headers()["link"],
+            "</api/v2>; rel=\"successor-version\""
+        );
+        assert_eq!(
+            response.headers()["sunset"],
+            "Wed, 01 Jul 2026 00:00:00 GMT"
+        );
+
+        let response = service
+            .oneshot(
+                Request::get("/api/v2/collections")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(response.headers().get("deprecation").is_none());
+    }
+}
@@ -0,0 +1,128 @@
+//! In-flight request tracking for draining (`POST /admin/drain`).
+//!
+//! Every request is counted while it is being served, so a drain can wait
+//! for them to finish. Once the drain refuses new work, requests get `503
+//! Service Unavailable` and should be retried against another server. Probe
+//! and drain routes are neither counted nor refused; see
+//! [`akidb_service::drain`].
+
+use crate::error::ApiError;
+use akidb_core::ErrorCode;
+use akidb_service::DrainController;
+use axum::body::BoxBody;
+use axum::http::{header, HeaderValue, Request, Response, StatusCode};
+use axum::response::IntoResponse;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Routes served throughout a drain, so probes keep reporting its progress.
+const EXEMPT_PATHS: &[&str] = &[
+    "/health",
+    "/ready",
+    "/statusz",
+    "/metrics",
+    "/admin/health",
+    "/admin/drain",
+];
+
+/// Renders the rejection of a request arriving after new work is refused.
+pub fn draining_response() -> Response<BoxBody> {
+    let mut response = ApiError::new(
+        StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::Unavailable,
+        "Server is draining for shutdown; retry against another server",
+    )
+    .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(1));
+    response
+        .headers_mut()
+        .insert(header::CONNECTION, HeaderValue::from_static("close"));
+    response
+}
+
+/// Tower layer counting in-flight requests and refusing new ones while draining.
+#[derive(Clone)]
+pub struct DrainLayer {
+    drain: Arc<DrainController>,
+}
+
+impl DrainLayer {
+    /// Creates a layer reporting to `drain`.
+    pub fn new(drain: Arc<DrainController>) -> Self {
+        Self { drain }
+    }
+}
+
+impl<S> Layer<S> for DrainLayer {
+    type Service = DrainService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DrainService {
+            inner,
+            drain: Arc::clone(&self.drain),
+        }
+    }
+}
+
+/// Service produced by [`DrainLayer`].
+#[derive(Clone)]
+pub struct DrainService<S> {
+    inner: S,
+    drain: Arc<DrainController>,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for DrainService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        // Use the service that was driven to readiness; leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        if EXEMPT_PATHS.contains(&req.uri().path()) {
+            return Box::pin(inner.call(req));
+        }
+        let Some(guard) = self.drain.begin_request() else {
+            return Box::pin(async { Ok(draining_response()) });
+        };
+
+        Box::pin(async move {
+            let response = inner.call(req).await;
+            drop(guard);
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_draining_response() {
+        let response = draining_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["code"], "UNAVAILABLE");
+        assert_eq!(body["retryable"], true);
+    }
+}
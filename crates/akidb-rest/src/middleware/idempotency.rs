@@ -0,0 +1,431 @@
+//! `Idempotency-Key` handling for POST requests.
+//!
+//! A POST carrying an `Idempotency-Key` header is run once; its response is
+//! recorded in the service's [`IdempotencyStore`] and replayed (with
+//! `Idempotent-Replayed: true`) when the same key is sent again for the same
+//! route and API key within the retention window. A retry arriving while the
+//! original is still running gets `409 Conflict`.
+//!
+//! Server errors (5xx) are not recorded, so retrying after one runs the request
+//! again. Keys are scoped by the authenticated [`KeyIdentity`], or by the
+//! request's [`TenantContext`] when it carries no valid API key, so the layer
+//! must run inside the collection ACL and tenant layers. A keyed request with
+//! neither gets `400 Bad Request` rather than running without protection.
+
+use crate::error::ApiError;
+use crate::middleware::TenantContext;
+use akidb_core::ErrorCode;
+use akidb_service::{
+    IdempotencyKey, IdempotencyOutcome, IdempotencyStore, KeyIdentity, StoredResponse,
+};
+use axum::body::{boxed, BoxBody, Full};
+use axum::http::{header, HeaderValue, Method, Request, Response, StatusCode};
+use axum::response::IntoResponse;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Header carrying the client-chosen idempotency key.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header marking a replayed response.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Longest accepted idempotency key.
+const MAX_KEY_LEN: usize = 255;
+
+/// Tower layer applying idempotency-key replay to POST requests.
+#[derive(Clone)]
+pub struct IdempotencyLayer {
+    store: Arc<IdempotencyStore>,
+}
+
+impl IdempotencyLayer {
+    /// Creates a layer backed by a shared store.
+    pub fn new(store: Arc<IdempotencyStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyService {
+            inner,
+            store: Arc::clone(&self.store),
+        }
+    }
+}
+
+/// Service produced by [`IdempotencyLayer`].
+#[derive(Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+    store: Arc<IdempotencyStore>,
+}
+
+/// Releases a claimed key if the request does not finish (error or client
+/// disconnect), so it does not block retries for the whole window.
+struct Claim {
+    store: Arc<IdempotencyStore>,
+    key: IdempotencyKey,
+    completed: bool,
+}
+
+impl Claim {
+    fn complete(mut self, response: StoredResponse) {
+        self.store.complete(&self.key, response);
+        self.completed = true;
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.store.abandon(&self.key);
+        }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for IdempotencyService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let store = Arc::clone(&self.store);
+        // Use the service that was driven to readiness; leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            if req.method() != Method::POST {
+                return inner.call(req).await;
+            }
+            let key = match req.headers().get(IDEMPOTENCY_KEY_HEADER) {
+                None => return inner.call(req).await,
+                Some(value) => match value.to_str() {
+                    Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key.to_string(),
+                    _ => {
+                        return Ok(ApiError::invalid_argument(format!(
+                            "Idempotency-Key must be 1-{} visible ASCII characters",
+                            MAX_KEY_LEN
+                        ))
+                        .into_response())
+                    }
+                },
+            };
+
+            let Some(scope) = request_scope(&req) else {
+                return Ok(ApiError::invalid_argument(
+                    "Idempotency-Key requires an API key or a tenant to scope it by",
+                )
+                .into_response());
+            };
+            let key = IdempotencyKey {
+                scope,
+                route: format!("{} {}", req.method(), req.uri().path()),
+                key,
+            };
+
+            match store.begin(&key) {
+                IdempotencyOutcome::InProgress => {
+                    return Ok(ApiError::new(
+                        StatusCode::CONFLICT,
+                        ErrorCode::Conflict,
+                        "A request with this Idempotency-Key is still in progress",
+                    )
+                    .into_response())
+                }
+                IdempotencyOutcome::Replay(stored) => return Ok(replay(&stored)),
+                IdempotencyOutcome::Proceed => {}
+            }
+
+            let claim = Claim {
+                store,
+                key,
+                completed: false,
+            };
+            let response = inner.call(req).await?;
+            if response.status().is_server_error() || response.status().is_informational() {
+                return Ok(response);
+            }
+
+            let (parts, body) = response.into_parts();
+            let bytes = match hyper::body::to_bytes(body).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    return Ok(ApiError::new(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        ErrorCode::Internal,
+                        format!("Failed to read response body: {}", e),
+                    )
+                    .into_response())
+                }
+            };
+            claim.complete(StoredResponse {
+                status: parts.status.as_u16(),
+                content_type: parts
+                    .headers
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map(String::from),
+                body: bytes.to_vec(),
+            });
+
+            Ok(Response::from_parts(parts, boxed(Full::from(bytes))))
+        })
+    }
+}
+
+/// The authenticated API key, else the tenant the request acts for, or `None`
+/// for anonymous requests outside tenant scoping.
+fn request_scope<B>(req: &Request<B>) -> Option<String> {
+    let extensions = req.extensions();
+    extensions
+        .get::<KeyIdentity>()
+        .map(|identity| format!("key:{}", identity.key_id))
+        .or_else(|| {
+            extensions
+                .get::<TenantContext>()
+                .map(|tenant| format!("tenant:{}", tenant.tenant_id))
+        })
+}
+
+fn replay(stored: &StoredResponse) -> Response<BoxBody> {
+    let mut response = Response::new(boxed(Full::from(stored.body.clone())));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    if let Some(content_type) = stored
+        .content_type
+        .as_deref()
+        .and_then(|v| HeaderValue::from_str(v).ok())
+    {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, content_type);
+    }
+    response
+        .headers_mut()
+        .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::CollectionAclLayer;
+    use akidb_core::{
+        hash_api_key, ApiKeyDescriptor, ApiKeyId, ApiKeyRepository, DatabaseId, TenantCatalog,
+        TenantDescriptor, TenantId,
+    };
+    use akidb_metadata::{SqliteApiKeyRepository, SqliteTenantCatalog};
+    use akidb_service::CollectionAcl;
+    use axum::{body::Body, routing::post, Router};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    /// API keys `ak_one` and `ak_two` of one tenant, with their ids.
+    async fn api_keys() -> (Arc<SqliteApiKeyRepository>, Vec<ApiKeyId>) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        akidb_metadata::run_migrations(&pool).await.unwrap();
+        let tenant = TenantDescriptor::new("acme", "acme");
+        SqliteTenantCatalog::new(pool.clone())
+            .create(&tenant)
+            .await
+            .unwrap();
+
+        let api_keys = Arc::new(SqliteApiKeyRepository::new(pool));
+        let mut key_ids = Vec::new();
+        for key in ["ak_one", "ak_two"] {
+            let descriptor =
+                ApiKeyDescriptor::new(tenant.tenant_id, key.to_string(), vec![], None, None);
+            api_keys
+                .create(&descriptor, &hash_api_key(key))
+                .await
+                .unwrap();
+            key_ids.push(descriptor.key_id);
+        }
+        (api_keys, key_ids)
+    }
+
+    async fn app(calls: Arc<AtomicUsize>) -> Router {
+        let (api_keys, _) = api_keys().await;
+        app_with_store(calls, Arc::new(IdempotencyStore::default()), api_keys)
+    }
+
+    fn app_with_store(
+        calls: Arc<AtomicUsize>,
+        store: Arc<IdempotencyStore>,
+        api_keys: Arc<SqliteApiKeyRepository>,
+    ) -> Router {
+        Router::new()
+            .route(
+                "/api/v2/collections/c/documents",
+                post(move || async move {
+                    let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                    (
+                        StatusCode::CREATED,
+                        axum::Json(serde_json::json!({"call": n})),
+                    )
+                }),
+            )
+            .layer(IdempotencyLayer::new(store))
+            .layer(CollectionAclLayer::new(Arc::new(CollectionAcl::new(
+                api_keys,
+            ))))
+    }
+
+    fn request(key: Option<&str>, api_key: Option<&str>) -> Request<Body> {
+        let mut builder = Request::post("/api/v2/collections/c/documents");
+        if let Some(key) = key {
+            builder = builder.header(IDEMPOTENCY_KEY_HEADER, key);
+        }
+        if let Some(api_key) = api_key {
+            builder = builder.header("x-api-key", api_key);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    async fn body(response: Response<BoxBody>) -> serde_json::Value {
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retry_replays_response() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(Arc::clone(&calls)).await;
+
+        let first = app
+            .clone()
+            .oneshot(request(Some("k1"), Some("ak_one")))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert_eq!(body(first).await["call"], 1);
+
+        let retry = app
+            .clone()
+            .oneshot(request(Some("k1"), Some("ak_one")))
+            .await
+            .unwrap();
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(retry.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(body(retry).await["call"], 1);
+
+        // Requests without an Idempotency-Key are not deduplicated
+        let unkeyed = app.oneshot(request(None, Some("ak_one"))).await.unwrap();
+        assert_eq!(body(unkeyed).await["call"], 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_other_key_does_not_get_replay() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(Arc::clone(&calls)).await;
+
+        let first = app
+            .clone()
+            .oneshot(request(Some("k1"), Some("ak_one")))
+            .await
+            .unwrap();
+        assert_eq!(body(first).await["call"], 1);
+
+        // Another key of the same tenant reusing the Idempotency-Key runs its own request
+        let other = app
+            .clone()
+            .oneshot(request(Some("k1"), Some("ak_two")))
+            .await
+            .unwrap();
+        assert!(other.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        assert_eq!(body(other).await["call"], 2);
+
+        // Anonymous and unknown-key requests without a tenant are rejected
+        for api_key in [None, Some("ak_unknown")] {
+            let response = app
+                .clone()
+                .oneshot(request(Some("k1"), api_key))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_retry_scoped_by_tenant() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(Arc::clone(&calls)).await;
+        let anonymous = |tenant_id: TenantId| {
+            let mut request = request(Some("k1"), None);
+            request.extensions_mut().insert(TenantContext {
+                tenant_id,
+                database_ids: vec![DatabaseId::new()],
+            });
+            request
+        };
+        let tenant_id = TenantId::new();
+
+        let first = app.clone().oneshot(anonymous(tenant_id)).await.unwrap();
+        assert_eq!(body(first).await["call"], 1);
+
+        let retry = app.clone().oneshot(anonymous(tenant_id)).await.unwrap();
+        assert_eq!(retry.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(body(retry).await["call"], 1);
+
+        // The same Idempotency-Key from another tenant runs its own request
+        let other = app.oneshot(anonymous(TenantId::new())).await.unwrap();
+        assert_eq!(body(other).await["call"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_retry_conflicts() {
+        let store = Arc::new(IdempotencyStore::default());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let (api_keys, key_ids) = api_keys().await;
+        let app = app_with_store(Arc::clone(&calls), Arc::clone(&store), api_keys);
+
+        // Simulate the original request still running
+        let in_flight = IdempotencyKey {
+            scope: format!("key:{}", key_ids[0]),
+            route: "POST /api/v2/collections/c/documents".to_string(),
+            key: "k1".to_string(),
+        };
+        assert_eq!(store.begin(&in_flight), IdempotencyOutcome::Proceed);
+
+        let response = app
+            .oneshot(request(Some("k1"), Some("ak_one")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_key_rejected() {
+        let app = app(Arc::new(AtomicUsize::new(0))).await;
+        let key = "k".repeat(MAX_KEY_LEN + 1);
+        let response = app
+            .oneshot(request(Some(&key), Some("ak_one")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,169 @@
+//! Per-route request metrics.
+//!
+//! Records `akidb_http_requests_total` and `akidb_http_request_duration_seconds`
+//! for every request. The `path` label is the matched route template (e.g.
+//! `/api/v2/collections/:id/search`), never the raw URI, so collection and
+//! document ids do not create new series. The `tenant` label is the
+//! `X-Tenant-ID` header (`default` without one); only the first
+//! [`MAX_TENANT_LABELS`] distinct tenants get their own series, later ones are
+//! reported as `other`.
+
+use super::tenant::TENANT_HEADER;
+use akidb_service::metrics::{HTTP_REQUESTS_TOTAL, HTTP_REQUEST_DURATION_SECONDS};
+use axum::extract::MatchedPath;
+use axum::http::{Request, Response};
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+
+/// Distinct tenant label values before falling back to `other`.
+pub const MAX_TENANT_LABELS: usize = 100;
+
+/// Label for requests that did not match any route.
+const UNMATCHED_PATH: &str = "unmatched";
+
+/// Bounded set of tenant label values.
+#[derive(Default)]
+struct TenantLabels {
+    seen: Mutex<HashSet<String>>,
+}
+
+impl TenantLabels {
+    fn label(&self, tenant: Option<&str>) -> String {
+        let Some(tenant) = tenant.map(str::trim).filter(|t| !t.is_empty()) else {
+            return "default".to_string();
+        };
+        let mut seen = self.seen.lock().expect("tenant label lock poisoned");
+        if seen.contains(tenant) {
+            return tenant.to_string();
+        }
+        if seen.len() < MAX_TENANT_LABELS {
+            seen.insert(tenant.to_string());
+            return tenant.to_string();
+        }
+        "other".to_string()
+    }
+}
+
+/// Tower layer recording request counts and latency per route.
+///
+/// Must be added with `Router::layer` so the matched route is known.
+#[derive(Clone, Default)]
+pub struct MetricsLayer {
+    tenants: Arc<TenantLabels>,
+}
+
+impl MetricsLayer {
+    /// Creates a layer recording into the global Prometheus registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsService {
+            inner,
+            tenants: Arc::clone(&self.tenants),
+        }
+    }
+}
+
+/// Service produced by [`MetricsLayer`].
+#[derive(Clone)]
+pub struct MetricsService<S> {
+    inner: S,
+    tenants: Arc<TenantLabels>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for MetricsService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let start = Instant::now();
+        let method = req.method().to_string();
+        let path = req
+            .extensions()
+            .get::<MatchedPath>()
+            .map_or_else(|| UNMATCHED_PATH.to_string(), |p| p.as_str().to_string());
+        let tenant = self.tenants.label(
+            req.headers()
+                .get(TENANT_HEADER)
+                .and_then(|v| v.to_str().ok()),
+        );
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let response = future.await?;
+            let status = response.status().as_u16().to_string();
+            HTTP_REQUESTS_TOTAL
+                .with_label_values(&[&method, &path, &status])
+                .inc();
+            HTTP_REQUEST_DURATION_SECONDS
+                .with_label_values(&[&method, &path, &status, &tenant])
+                .observe(start.elapsed().as_secs_f64());
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_records_route_template() {
+        let app = Router::new()
+            .route("/api/v2/metrics-test/:id", get(|| async { "ok" }))
+            .layer(MetricsLayer::new());
+
+        for id in ["a", "b"] {
+            app.clone()
+                .oneshot(
+                    Request::get(format!("/api/v2/metrics-test/{}", id))
+                        .header(TENANT_HEADER, "tenant-a")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let histogram = HTTP_REQUEST_DURATION_SECONDS.with_label_values(&[
+            "GET",
+            "/api/v2/metrics-test/:id",
+            "200",
+            "tenant-a",
+        ]);
+        assert_eq!(histogram.get_sample_count(), 2);
+    }
+
+    #[test]
+    fn test_tenant_labels_are_bounded() {
+        let labels = TenantLabels::default();
+        assert_eq!(labels.label(None), "default");
+        for i in 0..MAX_TENANT_LABELS {
+            assert_eq!(labels.label(Some(&i.to_string())), i.to_string());
+        }
+        assert_eq!(labels.label(Some("one-too-many")), "other");
+        assert_eq!(labels.label(Some("0")), "0");
+    }
+}
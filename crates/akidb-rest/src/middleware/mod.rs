@@ -0,0 +1,32 @@
+//! Tower middleware for the REST API.
+//!
+//! Layers in this module are generic over the request/response body so they can
+//! be reused by any tower-based HTTP server (axum, tonic).
+
+pub mod admin_auth;
+pub mod alias;
+pub mod backpressure;
+pub mod collection_acl;
+pub mod compression;
+pub mod cors;
+pub mod deprecation;
+pub mod drain;
+pub mod idempotency;
+pub mod metrics;
+pub mod rate_limit;
+pub mod tenant;
+pub mod trace_context;
+
+pub use admin_auth::AdminAuthLayer;
+pub use alias::AliasLayer;
+pub use backpressure::BackpressureLayer;
+pub use collection_acl::CollectionAclLayer;
+pub use compression::compression_layer;
+pub use cors::cors_layer;
+pub use deprecation::DeprecationLayer;
+pub use drain::DrainLayer;
+pub use idempotency::IdempotencyLayer;
+pub use metrics::MetricsLayer;
+pub use rate_limit::{ClientAddr, RateLimitLayer, RateLimiter};
+pub use tenant::{TenantContext, TenantLayer, TenantResolver};
+pub use trace_context::TraceContextLayer;
@@ -0,0 +1,257 @@
+//! Per-tenant and per-client rate limiting.
+//!
+//! Applies [`RateLimiter`] to every request. Requests carrying an API key
+//! (`X-API-Key` header or `Authorization: Bearer`) that resolves to a tenant
+//! draw from the tenant's bucket; all other requests are limited by client IP,
+//! taken from [`ClientAddr`] connect info.
+//!
+//! Throttled requests receive `429 Too Many Requests` with `Retry-After` and
+//! a `RATE_LIMITED` [`ErrorResponse`](crate::error::ErrorResponse) body.
+//! Every limited response carries `X-RateLimit-Limit`, `X-RateLimit-Remaining`
+//! and `X-RateLimit-Reset` headers.
+
+use crate::error::ApiError;
+use akidb_core::ErrorCode;
+use axum::extract::connect_info::{ConnectInfo, Connected};
+use axum::http::{header, HeaderMap, HeaderValue, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use hyper::server::conn::AddrStream;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::net::TcpStream;
+use tokio_rustls::server::TlsStream;
+use tower::{Layer, Service};
+
+pub use akidb_service::{RateLimitDecision, RateLimiter};
+
+/// Header carrying the API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Peer address of a connection, for
+/// `into_make_service_with_connect_info::<ClientAddr>()`.
+///
+/// Works for both plain and TLS listeners; `None` if the peer address of a
+/// TLS socket cannot be read.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientAddr(pub Option<SocketAddr>);
+
+impl Connected<&AddrStream> for ClientAddr {
+    fn connect_info(target: &AddrStream) -> Self {
+        Self(Some(target.remote_addr()))
+    }
+}
+
+impl Connected<&TlsStream<TcpStream>> for ClientAddr {
+    fn connect_info(target: &TlsStream<TcpStream>) -> Self {
+        Self(target.get_ref().0.peer_addr().ok())
+    }
+}
+
+/// Extracts the API key from `X-API-Key` or `Authorization: Bearer <key>`.
+pub fn extract_api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()) {
+        return Some(key.trim());
+    }
+
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+fn apply_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert("x-ratelimit-limit", HeaderValue::from(decision.limit));
+    headers.insert(
+        "x-ratelimit-remaining",
+        HeaderValue::from(decision.remaining),
+    );
+    headers.insert(
+        "x-ratelimit-reset",
+        HeaderValue::from(decision.reset_after.as_secs_f64().ceil() as u64),
+    );
+}
+
+/// Tower layer applying [`RateLimiter`] to every request.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    /// Creates a layer backed by a shared limiter.
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: Arc::clone(&self.limiter),
+        }
+    }
+}
+
+/// Service produced by [`RateLimitLayer`].
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for RateLimitService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let limiter = Arc::clone(&self.limiter);
+        // Use the service that was driven to readiness; leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            if !limiter.is_enabled() {
+                return inner.call(req).await;
+            }
+            let client_ip = req
+                .extensions()
+                .get::<ConnectInfo<ClientAddr>>()
+                .and_then(|ConnectInfo(ClientAddr(addr))| addr.map(|addr| addr.ip()));
+            let api_key = extract_api_key(req.headers()).map(str::to_string);
+
+            let decision = limiter.check(api_key.as_deref(), client_ip).await;
+            if decision.limit == 0 {
+                return inner.call(req).await;
+            }
+
+            if !decision.allowed {
+                let retry_after = decision.reset_after.as_secs_f64().ceil().max(1.0) as u64;
+                let mut response = ApiError::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    ErrorCode::RateLimited,
+                    format!(
+                        "Rate limit of {} requests per second exceeded",
+                        decision.limit
+                    ),
+                )
+                .with_detail("retry_after_seconds", retry_after)
+                .into_response();
+                apply_headers(response.headers_mut(), &decision);
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+                return Ok(response);
+            }
+
+            let mut response = inner.call(req).await?;
+            apply_headers(response.headers_mut(), &decision);
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{Body, BoxBody};
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_extract_api_key() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(extract_api_key(&headers), None);
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer ak_abc"),
+        );
+        assert_eq!(extract_api_key(&headers), Some("ak_abc"));
+
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("ak_xyz"));
+        assert_eq!(extract_api_key(&headers), Some("ak_xyz"));
+    }
+
+    #[tokio::test]
+    async fn test_layer_returns_429_with_headers() {
+        let limiter = Arc::new(RateLimiter::new(1));
+        let layer = RateLimitLayer::new(Arc::clone(&limiter));
+        let service = layer.layer(tower::service_fn(|_req: Request<Body>| async {
+            Ok::<_, Infallible>(Response::new(BoxBody::default()))
+        }));
+
+        let request = |api_key: Option<&str>, client: [u8; 4]| {
+            let mut builder = Request::builder().extension(ConnectInfo(ClientAddr(Some(
+                SocketAddr::from((client, 40000)),
+            ))));
+            if let Some(api_key) = api_key {
+                builder = builder.header(API_KEY_HEADER, api_key);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        let ok = service
+            .clone()
+            .oneshot(request(Some("ak_test"), [10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(ok.status(), StatusCode::OK);
+        assert_eq!(ok.headers()["x-ratelimit-limit"], "1");
+        assert_eq!(ok.headers()["x-ratelimit-remaining"], "0");
+
+        let limited = service
+            .clone()
+            .oneshot(request(Some("ak_test"), [10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(limited.headers().contains_key(header::RETRY_AFTER));
+        let bytes = hyper::body::to_bytes(limited.into_body()).await.unwrap();
+        let body: crate::error::ErrorResponse = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body.code, ErrorCode::RateLimited);
+        assert!(body.retryable);
+
+        // Dropping the key does not escape the client's bucket
+        let anonymous = service
+            .clone()
+            .oneshot(request(None, [10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(anonymous.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Other clients have their own bucket
+        let other = service
+            .clone()
+            .oneshot(request(None, [10, 0, 0, 2]))
+            .await
+            .unwrap();
+        assert_eq!(other.status(), StatusCode::OK);
+        assert_eq!(other.headers()["x-ratelimit-limit"], "1");
+
+        // Disabling at runtime lets the throttled key through
+        limiter.set_enabled(false);
+        let unlimited = service
+            .clone()
+            .oneshot(request(Some("ak_test"), [10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(unlimited.status(), StatusCode::OK);
+    }
+}
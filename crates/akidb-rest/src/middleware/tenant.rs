@@ -0,0 +1,480 @@
+//! Tenant scoping for the REST API (`features.multi_tenancy_enabled`).
+//!
+//! Requests under `/api/` authenticated with an API key act for the tenant
+//! that owns the key; an `X-Tenant-ID` header naming any other tenant gets
+//! `403 Forbidden`. Requests without a key may name a tenant with
+//! `X-Tenant-ID` (the tenant's UUID), and otherwise act for the default
+//! tenant. The key is resolved by the collection ACL layer, which must run
+//! first (see [`KeyIdentity`]). The tenant is
+//! checked against the tenants table and its databases are attached to the
+//! request as a [`TenantContext`] extension, which the collection handlers use
+//! to list and create collections. Requests addressing a collection owned by
+//! another tenant get `404 Not Found`, exactly as if it did not exist.
+
+use crate::error::ApiError;
+use akidb_core::{
+    CollectionDescriptor, CollectionId, CoreError, DatabaseDescriptor, DatabaseId,
+    DatabaseRepository, ErrorCode, TenantCatalog, TenantId, TenantStatus,
+};
+use akidb_service::{CollectionService, KeyIdentity};
+use axum::body::BoxBody;
+use axum::http::{Request, Response, StatusCode};
+use axum::response::IntoResponse;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+
+/// Header naming the tenant a request acts for.
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// How long a resolved tenant is cached before it is looked up again.
+const TENANT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Tenant a request acts for, attached as a request extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantContext {
+    pub tenant_id: TenantId,
+    /// Databases owned by the tenant; new collections go into the first one
+    pub database_ids: Vec<DatabaseId>,
+}
+
+impl TenantContext {
+    /// Database that new collections are created in.
+    pub fn database_id(&self) -> DatabaseId {
+        self.database_ids[0]
+    }
+
+    /// Returns `true` when the collection belongs to this tenant.
+    pub fn owns(&self, collection: &CollectionDescriptor) -> bool {
+        self.database_ids.contains(&collection.database_id)
+    }
+}
+
+/// Resolves requests to the tenant they act for.
+pub struct TenantResolver {
+    tenants: Arc<dyn TenantCatalog>,
+    databases: Arc<dyn DatabaseRepository>,
+    default_tenant: TenantId,
+    cache: Mutex<HashMap<TenantId, (Instant, TenantContext)>>,
+}
+
+impl TenantResolver {
+    /// Creates a resolver; requests without a tenant header act for `default_tenant`.
+    pub fn new(
+        tenants: Arc<dyn TenantCatalog>,
+        databases: Arc<dyn DatabaseRepository>,
+        default_tenant: TenantId,
+    ) -> Self {
+        Self {
+            tenants,
+            databases,
+            default_tenant,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Resolves the tenant of the request's API key, checking it against the
+    /// header if both are present. Without a key, resolves the tenant named by
+    /// the header (or the default tenant).
+    pub async fn resolve(
+        &self,
+        header: Option<&str>,
+        key: Option<&KeyIdentity>,
+    ) -> Result<TenantContext, ApiError> {
+        let requested = match header.map(str::trim) {
+            None => None,
+            Some(value) => Some(TenantId::from_str(value).map_err(|_| {
+                ApiError::invalid_argument(format!(
+                    "Invalid X-Tenant-ID '{}': expected a tenant UUID",
+                    value
+                ))
+            })?),
+        };
+        let tenant_id = match (requested, key) {
+            (Some(requested), Some(key)) if requested != key.tenant_id => {
+                return Err(ApiError::new(
+                    StatusCode::FORBIDDEN,
+                    ErrorCode::PermissionDenied,
+                    format!("API key does not belong to tenant {}", requested),
+                )
+                .with_detail("key_id", key.key_id.to_string())
+                .with_detail("tenant_id", requested.to_string()));
+            }
+            (_, Some(key)) => key.tenant_id,
+            (Some(requested), None) => requested,
+            (None, None) => self.default_tenant,
+        };
+
+        {
+            let cache = self.cache.lock().expect("tenant cache lock poisoned");
+            if let Some((resolved_at, context)) = cache.get(&tenant_id) {
+                if resolved_at.elapsed() < TENANT_CACHE_TTL {
+                    return Ok(context.clone());
+                }
+            }
+        }
+
+        let context = self.load(tenant_id).await?;
+        self.cache
+            .lock()
+            .expect("tenant cache lock poisoned")
+            .insert(tenant_id, (Instant::now(), context.clone()));
+        Ok(context)
+    }
+
+    async fn load(&self, tenant_id: TenantId) -> Result<TenantContext, ApiError> {
+        let forbidden = |message: String| {
+            ApiError::new(StatusCode::FORBIDDEN, ErrorCode::PermissionDenied, message)
+                .with_detail("tenant_id", tenant_id.to_string())
+        };
+
+        let tenant = self
+            .tenants
+            .get(tenant_id)
+            .await?
+            .ok_or_else(|| forbidden(format!("Unknown tenant: {}", tenant_id)))?;
+        if matches!(
+            tenant.status,
+            TenantStatus::Suspended | TenantStatus::Decommissioned
+        ) {
+            return Err(forbidden(format!(
+                "Tenant {} is {}",
+                tenant_id,
+                tenant.status.as_str()
+            )));
+        }
+
+        let mut database_ids: Vec<DatabaseId> = self
+            .databases
+            .list_by_tenant(tenant_id)
+            .await?
+            .into_iter()
+            .map(|db| db.database_id)
+            .collect();
+        if database_ids.is_empty() {
+            // Tenants created through the admin API start without a database
+            let database = DatabaseDescriptor::new(tenant_id, "default", None);
+            self.databases.create(&database).await?;
+            database_ids.push(database.database_id);
+        }
+
+        Ok(TenantContext {
+            tenant_id,
+            database_ids,
+        })
+    }
+}
+
+/// Collection id from `/api/<version>/collections/<id>/...`, if any.
+pub(crate) fn collection_id_from_path(path: &str) -> Option<CollectionId> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    match (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) {
+        (Some("api"), Some(_), Some("collections"), Some(id)) => CollectionId::from_str(id).ok(),
+        _ => None,
+    }
+}
+
+/// Tower layer attaching a [`TenantContext`] to `/api/` requests.
+#[derive(Clone)]
+pub struct TenantLayer {
+    resolver: Arc<TenantResolver>,
+    service: Arc<CollectionService>,
+}
+
+impl TenantLayer {
+    /// Creates a layer checking collection ownership against `service`.
+    pub fn new(resolver: Arc<TenantResolver>, service: Arc<CollectionService>) -> Self {
+        Self { resolver, service }
+    }
+}
+
+impl<S> Layer<S> for TenantLayer {
+    type Service = TenantService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TenantService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`TenantLayer`].
+#[derive(Clone)]
+pub struct TenantService<S> {
+    inner: S,
+    layer: TenantLayer,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for TenantService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        let layer = self.layer.clone();
+        // Use the service that was driven to readiness; leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            if !req.uri().path().starts_with("/api/") {
+                return inner.call(req).await;
+            }
+
+            let header = match req.headers().get(TENANT_HEADER).map(|v| v.to_str()) {
+                None => None,
+                Some(Ok(value)) => Some(value.to_string()),
+                Some(Err(_)) => {
+                    return Ok(
+                        ApiError::invalid_argument("X-Tenant-ID must be ASCII").into_response()
+                    )
+                }
+            };
+            let key = req.extensions().get::<KeyIdentity>().copied();
+            let context = match layer
+                .resolver
+                .resolve(header.as_deref(), key.as_ref())
+                .await
+            {
+                Ok(context) => context,
+                Err(rejection) => return Ok(rejection.into_response()),
+            };
+
+            if let Some(collection_id) = collection_id_from_path(req.uri().path()) {
+                // Missing collections are reported by the handler itself
+                if let Ok(collection) = layer.service.get_collection(collection_id).await {
+                    if !context.owns(&collection) {
+                        let not_found =
+                            CoreError::not_found("Collection", collection_id.to_string());
+                        return Ok(ApiError::from(not_found).into_response());
+                    }
+                }
+            }
+
+            req.extensions_mut().insert(context);
+            inner.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::CollectionAclLayer;
+    use akidb_core::{
+        hash_api_key, ApiKeyDescriptor, ApiKeyRepository, DistanceMetric, TenantDescriptor,
+    };
+    use akidb_metadata::{SqliteApiKeyRepository, SqliteDatabaseRepository, SqliteTenantCatalog};
+    use akidb_service::CollectionAcl;
+    use axum::{body::Body, extract::Path, routing::get, Extension, Router};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tower::ServiceExt;
+
+    async fn tenant(catalog: &SqliteTenantCatalog, slug: &str, status: TenantStatus) -> TenantId {
+        let mut tenant = TenantDescriptor::new(slug, slug);
+        tenant.status = status;
+        catalog.create(&tenant).await.unwrap();
+        tenant.tenant_id
+    }
+
+    #[tokio::test]
+    async fn test_tenant_header_scopes_collections() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        akidb_metadata::run_migrations(&pool).await.unwrap();
+        let catalog = SqliteTenantCatalog::new(pool.clone());
+        let acme = tenant(&catalog, "acme", TenantStatus::Active).await;
+        let globex = tenant(&catalog, "globex", TenantStatus::Active).await;
+        let suspended = tenant(&catalog, "initech", TenantStatus::Suspended).await;
+
+        let resolver = Arc::new(TenantResolver::new(
+            Arc::new(catalog),
+            Arc::new(SqliteDatabaseRepository::new(pool)),
+            acme,
+        ));
+        let acme_db = resolver.resolve(None, None).await.unwrap().database_id();
+
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        let collection_id = service
+            .create_collection_in(
+                acme_db,
+                "docs".to_string(),
+                16,
+                DistanceMetric::Cosine,
+                None,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        let app = Router::new()
+            .route(
+                "/api/v2/collections/:id",
+                get(
+                    |Path(id): Path<String>, Extension(ctx): Extension<TenantContext>| async move {
+                        format!("{} {}", id, ctx.tenant_id)
+                    },
+                ),
+            )
+            .layer(TenantLayer::new(resolver, service));
+
+        let call = |tenant: Option<String>| {
+            let mut builder = Request::get(format!("/api/v2/collections/{}", collection_id));
+            if let Some(tenant) = tenant {
+                builder = builder.header(TENANT_HEADER, tenant);
+            }
+            app.clone().oneshot(builder.body(Body::empty()).unwrap())
+        };
+
+        assert_eq!(call(None).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(
+            call(Some(acme.to_string())).await.unwrap().status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            call(Some(globex.to_string())).await.unwrap().status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            call(Some(suspended.to_string())).await.unwrap().status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            call(Some(TenantId::new().to_string()))
+                .await
+                .unwrap()
+                .status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            call(Some("acme".to_string())).await.unwrap().status(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_key_cannot_address_another_tenant() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        akidb_metadata::run_migrations(&pool).await.unwrap();
+        let catalog = SqliteTenantCatalog::new(pool.clone());
+        let acme = tenant(&catalog, "acme", TenantStatus::Active).await;
+        let globex = tenant(&catalog, "globex", TenantStatus::Active).await;
+
+        let api_keys = Arc::new(SqliteApiKeyRepository::new(pool.clone()));
+        for (key, tenant_id) in [("ak_acme", acme), ("ak_globex", globex)] {
+            let descriptor = ApiKeyDescriptor::new(tenant_id, key.to_string(), vec![], None, None);
+            api_keys
+                .create(&descriptor, &hash_api_key(key))
+                .await
+                .unwrap();
+        }
+
+        let resolver = Arc::new(TenantResolver::new(
+            Arc::new(catalog),
+            Arc::new(SqliteDatabaseRepository::new(pool)),
+            acme,
+        ));
+        let acme_db = resolver.resolve(None, None).await.unwrap().database_id();
+
+        let dir = tempfile::tempdir().unwrap();
+        let service = crate::test_support::in_memory_service(&dir);
+        let collection_id = service
+            .create_collection_in(
+                acme_db,
+                "docs".to_string(),
+                16,
+                DistanceMetric::Cosine,
+                None,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        let app =
+            Router::new()
+                .route(
+                    "/api/v2/collections/:id",
+                    get(|Extension(ctx): Extension<TenantContext>| async move {
+                        ctx.tenant_id.to_string()
+                    }),
+                )
+                .layer(TenantLayer::new(resolver, service))
+                .layer(CollectionAclLayer::new(Arc::new(CollectionAcl::new(
+                    api_keys,
+                ))));
+
+        let call = |key: &str, tenant: Option<TenantId>| {
+            let mut builder = Request::get(format!("/api/v2/collections/{}", collection_id))
+                .header("x-api-key", key);
+            if let Some(tenant) = tenant {
+                builder = builder.header(TENANT_HEADER, tenant.to_string());
+            }
+            app.clone().oneshot(builder.body(Body::empty()).unwrap())
+        };
+
+        // The key's own tenant, named or implied
+        for tenant in [None, Some(acme)] {
+            let response = call("ak_acme", tenant).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            assert_eq!(body, acme.to_string());
+        }
+
+        // A key cannot act for another tenant by naming it
+        assert_eq!(
+            call("ak_globex", Some(acme)).await.unwrap().status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            call("ak_acme", Some(globex)).await.unwrap().status(),
+            StatusCode::FORBIDDEN
+        );
+        // Without a header the key acts for its own tenant, which does not own the collection
+        assert_eq!(
+            call("ak_globex", None).await.unwrap().status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[test]
+    fn test_collection_id_from_path() {
+        let id = CollectionId::new();
+        assert_eq!(
+            collection_id_from_path(&format!("/api/v1/collections/{}/docs:action", id)),
+            Some(id)
+        );
+        assert_eq!(collection_id_from_path("/api/v2/collections"), None);
+        assert_eq!(
+            collection_id_from_path(&format!("/admin/collections/{}/dlq/retry", id)),
+            None
+        );
+    }
+}
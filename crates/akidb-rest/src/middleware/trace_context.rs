@@ -0,0 +1,127 @@
+//! W3C trace context for REST requests.
+//!
+//! Each request runs inside an `http.request` span whose parent is taken from
+//! the `traceparent` / `tracestate` headers, so handler, service, storage, and
+//! embedding spans join the caller's distributed trace instead of starting a
+//! new one. The response carries the server span's `traceparent`, letting
+//! clients look the request up in Jaeger.
+
+use akidb_service::trace_context::{inject_headers, set_parent_from_headers};
+use axum::extract::MatchedPath;
+use axum::http::{Request, Response};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::Instrument;
+
+/// Tower layer running each request in a span joined to the caller's trace.
+#[derive(Clone, Copy, Default)]
+pub struct TraceContextLayer;
+
+impl TraceContextLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for TraceContextLayer {
+    type Service = TraceContextService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TraceContextService { inner }
+    }
+}
+
+/// Service produced by [`TraceContextLayer`].
+#[derive(Clone)]
+pub struct TraceContextService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for TraceContextService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let route = req
+            .extensions()
+            .get::<MatchedPath>()
+            .map_or_else(|| req.uri().path().to_string(), |p| p.as_str().to_string());
+        let span = tracing::info_span!(
+            "http.request",
+            otel.name = %format!("{} {}", req.method(), route),
+            otel.kind = "server",
+            http.method = %req.method(),
+            http.route = %route,
+            http.status_code = tracing::field::Empty,
+        );
+        set_parent_from_headers(&span, req.headers());
+
+        let future = span.in_scope(|| self.inner.call(req));
+        Box::pin(
+            async move {
+                let mut response = future.await?;
+                let span = tracing::Span::current();
+                span.record("http.status_code", response.status().as_u16());
+                inject_headers(&span, response.headers_mut());
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_service::trace_context::{install_propagator, TRACEPARENT_HEADER};
+    use axum::{body::Body, routing::get, Router};
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[tokio::test]
+    async fn test_response_continues_incoming_trace() {
+        install_propagator();
+        // The tracer only holds a weak reference to its provider; keep it alive
+        let provider = TracerProvider::builder().build();
+        let tracer = provider.tracer("test");
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = Router::new()
+            .route("/api/v2/collections", get(|| async { "ok" }))
+            .layer(TraceContextLayer::new());
+
+        let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+        let response = app
+            .oneshot(
+                Request::get("/api/v2/collections")
+                    .header(
+                        TRACEPARENT_HEADER,
+                        format!("00-{}-00f067aa0ba902b7-01", trace_id),
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let traceparent = response.headers()[TRACEPARENT_HEADER].to_str().unwrap();
+        let parts: Vec<&str> = traceparent.split('-').collect();
+        assert_eq!(parts[1], trace_id);
+        assert_ne!(parts[2], "00f067aa0ba902b7", "server span gets its own id");
+    }
+}
@@ -0,0 +1,14 @@
+//! Shared helpers for handler and middleware tests.
+
+use akidb_service::{CollectionService, StorageConfig};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// In-memory service whose per-collection WALs live under `dir`, not the
+/// crate directory
+pub(crate) fn in_memory_service(dir: &TempDir) -> Arc<CollectionService> {
+    Arc::new(
+        CollectionService::new()
+            .with_storage_config(StorageConfig::memory(dir.path().join("akidb.wal"))),
+    )
+}
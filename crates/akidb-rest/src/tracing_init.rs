@@ -6,7 +6,8 @@
 //! # Features
 //! - Jaeger exporter for distributed tracing
 //! - Automatic span creation for HTTP requests
-//! - Trace context propagation
+//! - W3C trace context (`traceparent`) propagation, see
+//!   [`middleware::TraceContextLayer`](crate::middleware::TraceContextLayer)
 //! - Integration with Prometheus exemplars
 //!
 //! # Example
@@ -26,13 +27,8 @@
 //! }
 //! ```
 
+use opentelemetry::global;
 use opentelemetry::trace::TraceError;
-use opentelemetry::{global, KeyValue};
-use opentelemetry_sdk::trace::{self, Sampler};
-use opentelemetry_sdk::Resource;
-use tracing_subscriber::layer::SubscriberExt;
-use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{EnvFilter, Registry};
 
 /// Initialize OpenTelemetry tracing with Jaeger exporter
 ///
@@ -51,41 +47,7 @@ use tracing_subscriber::{EnvFilter, Registry};
 ///     .expect("Failed to initialize tracing");
 /// ```
 pub fn init_tracing(service_name: &str, jaeger_endpoint: &str) -> Result<(), TraceError> {
-    // Create Jaeger exporter
-    let tracer = opentelemetry_jaeger::new_agent_pipeline()
-        .with_service_name(service_name)
-        .with_endpoint(jaeger_endpoint)
-        .with_trace_config(
-            trace::config()
-                .with_sampler(Sampler::AlwaysOn) // Sample all traces (adjust for production)
-                .with_resource(Resource::new(vec![
-                    KeyValue::new("service.name", service_name.to_string()),
-                    KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
-                ])),
-        )
-        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
-
-    // Create OpenTelemetry tracing layer
-    let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
-
-    // Create env filter for log level control
-    let env_filter =
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info,akidb=debug"));
-
-    // Create formatting layer for console output
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .with_target(true)
-        .with_thread_ids(false)
-        .with_line_number(true);
-
-    // Combine layers and initialize global subscriber
-    Registry::default()
-        .with(env_filter)
-        .with(fmt_layer)
-        .with(telemetry)
-        .init();
-
-    Ok(())
+    akidb_service::trace_context::init_tracing(service_name, jaeger_endpoint)
 }
 
 /// Initialize tracing with default settings
@@ -0,0 +1,55 @@
+//! Structured HTTP responses for payload validation failures.
+
+use crate::error::ApiError;
+use akidb_core::ErrorCode;
+use akidb_service::validation::ValidationError;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+/// Convert a validation error into a JSON error response.
+///
+/// Size limit violations map to `413 Payload Too Large` (`PAYLOAD_TOO_LARGE`);
+/// malformed payloads (e.g. metadata nested too deeply) map to
+/// `422 Unprocessable Entity` (`INVALID_ARGUMENT`). The specific check, limit,
+/// and actual value are in `details`.
+pub fn validation_error_response(err: &ValidationError) -> Response {
+    let (status, code) = if err.is_payload_too_large() {
+        (StatusCode::PAYLOAD_TOO_LARGE, ErrorCode::PayloadTooLarge)
+    } else {
+        (StatusCode::UNPROCESSABLE_ENTITY, ErrorCode::InvalidArgument)
+    };
+
+    ApiError::new(status, code, err.to_string())
+        .with_detail("reason", err.code())
+        .with_detail("limit", err.limit())
+        .with_detail("actual", err.actual())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_mapping() {
+        let too_large = ValidationError::TooManyVectors {
+            limit: 10,
+            actual: 11,
+        };
+        assert_eq!(
+            validation_error_response(&too_large).status(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+
+        let too_deep = ValidationError::MetadataTooDeep {
+            limit: 4,
+            actual: 5,
+        };
+        assert_eq!(
+            validation_error_response(&too_deep).status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Collection aliases.
+//!
+//! Clients address a collection through an alias (e.g. `prod-docs`) instead
+//! of its ID. Operators build a replacement collection next to the live one
+//! and repoint the alias in a single write; requests that resolve the alias
+//! afterwards go to the new collection (blue/green reindexing).
+//!
+//! Aliases are read through the optional [`AliasRepository`] on every
+//! resolution, so a swap made through one server is seen by all servers
+//! sharing the metadata database at once. Without a repository they live in
+//! memory and are lost on restart.
+
+use akidb_core::{
+    AliasRepository, CollectionAlias, CollectionId, CoreError, CoreResult, DatabaseId,
+};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Maximum alias name length.
+const MAX_ALIAS_NAME_LEN: usize = 255;
+
+/// Stores and resolves collection aliases.
+pub struct CollectionAliases {
+    aliases: RwLock<HashMap<(DatabaseId, String), CollectionAlias>>,
+    repository: Option<Arc<dyn AliasRepository>>,
+}
+
+impl CollectionAliases {
+    /// Creates an in-memory alias registry (aliases are lost on restart).
+    pub fn new() -> Self {
+        Self {
+            aliases: RwLock::new(HashMap::new()),
+            repository: None,
+        }
+    }
+
+    /// Creates an alias registry persisted to `repository`.
+    pub fn with_repository(repository: Arc<dyn AliasRepository>) -> Self {
+        Self {
+            aliases: RwLock::new(HashMap::new()),
+            repository: Some(repository),
+        }
+    }
+
+    /// Fetches the alias `name` of `database_id`.
+    pub async fn get(
+        &self,
+        database_id: DatabaseId,
+        name: &str,
+    ) -> CoreResult<Option<CollectionAlias>> {
+        match &self.repository {
+            Some(repository) => repository.get(database_id, name).await,
+            None => Ok(self
+                .aliases
+                .read()
+                .await
+                .get(&(database_id, name.to_string()))
+                .cloned()),
+        }
+    }
+
+    /// Lists the aliases of `database_id` ordered by name.
+    pub async fn list(&self, database_id: DatabaseId) -> CoreResult<Vec<CollectionAlias>> {
+        if let Some(repository) = &self.repository {
+            return repository.list(database_id).await;
+        }
+
+        let mut aliases: Vec<CollectionAlias> = self
+            .aliases
+            .read()
+            .await
+            .values()
+            .filter(|alias| alias.database_id == database_id)
+            .cloned()
+            .collect();
+        aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(aliases)
+    }
+
+    /// Points `name` at `collection_id`, creating the alias if needed.
+    ///
+    /// Returns the stored alias and the collection it pointed to before, if
+    /// it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ValidationError` for an invalid name, or the repository's
+    /// error.
+    pub async fn set(
+        &self,
+        database_id: DatabaseId,
+        name: &str,
+        collection_id: CollectionId,
+    ) -> CoreResult<(CollectionAlias, Option<CollectionId>)> {
+        validate_alias_name(name)?;
+
+        let previous = self.get(database_id, name).await?;
+        let alias = match &previous {
+            Some(previous) => CollectionAlias {
+                collection_id,
+                updated_at: Utc::now(),
+                ..previous.clone()
+            },
+            None => CollectionAlias::new(database_id, name, collection_id),
+        };
+
+        match &self.repository {
+            Some(repository) => repository.upsert(&alias).await?,
+            None => {
+                self.aliases
+                    .write()
+                    .await
+                    .insert((database_id, name.to_string()), alias.clone());
+            }
+        }
+        Ok((alias, previous.map(|previous| previous.collection_id)))
+    }
+
+    /// Deletes the alias `name` of `database_id`; returns `false` if it did
+    /// not exist.
+    pub async fn remove(&self, database_id: DatabaseId, name: &str) -> CoreResult<bool> {
+        match &self.repository {
+            Some(repository) => repository.delete(database_id, name).await,
+            None => Ok(self
+                .aliases
+                .write()
+                .await
+                .remove(&(database_id, name.to_string()))
+                .is_some()),
+        }
+    }
+
+    /// Names of the aliases of `database_id` pointing at `collection_id`.
+    pub async fn pointing_at(
+        &self,
+        database_id: DatabaseId,
+        collection_id: CollectionId,
+    ) -> CoreResult<Vec<String>> {
+        Ok(self
+            .list(database_id)
+            .await?
+            .into_iter()
+            .filter(|alias| alias.collection_id == collection_id)
+            .map(|alias| alias.name)
+            .collect())
+    }
+}
+
+impl Default for CollectionAliases {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks that `name` can be used in a URL path in place of a collection ID.
+///
+/// Names are 1-255 characters from `[A-Za-z0-9._-]` and must not parse as a
+/// collection ID, which would make them ambiguous.
+pub fn validate_alias_name(name: &str) -> CoreResult<()> {
+    if name.is_empty() || name.len() > MAX_ALIAS_NAME_LEN {
+        return Err(CoreError::ValidationError(format!(
+            "alias name must be 1-{} characters (got {})",
+            MAX_ALIAS_NAME_LEN,
+            name.len()
+        )));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        || name == "."
+        || name == ".."
+    {
+        return Err(CoreError::ValidationError(format!(
+            "alias name '{}' may only contain letters, digits, '.', '_' and '-'",
+            name
+        )));
+    }
+    if CollectionId::from_str(name).is_ok() {
+        return Err(CoreError::ValidationError(format!(
+            "alias name '{}' must not be a collection ID",
+            name
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_alias_name() {
+        assert!(validate_alias_name("prod-docs").is_ok());
+        assert!(validate_alias_name("docs_v2.1").is_ok());
+        assert!(validate_alias_name("").is_err());
+        assert!(validate_alias_name("..").is_err());
+        assert!(validate_alias_name("a/b").is_err());
+        assert!(validate_alias_name(&"a".repeat(256)).is_err());
+        assert!(validate_alias_name(&CollectionId::new().to_string()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_repoints_alias() {
+        let aliases = CollectionAliases::new();
+        let database_id = DatabaseId::new();
+        let (blue, green) = (CollectionId::new(), CollectionId::new());
+
+        let (created, previous) = aliases.set(database_id, "docs", blue).await.unwrap();
+        assert_eq!(previous, None);
+
+        let (swapped, previous) = aliases.set(database_id, "docs", green).await.unwrap();
+        assert_eq!(previous, Some(blue));
+        assert_eq!(swapped.collection_id, green);
+        assert_eq!(swapped.created_at, created.created_at);
+
+        assert_eq!(
+            aliases.pointing_at(database_id, green).await.unwrap(),
+            vec!["docs".to_string()]
+        );
+        assert!(aliases.list(DatabaseId::new()).await.unwrap().is_empty());
+        assert!(aliases.remove(database_id, "docs").await.unwrap());
+        assert!(aliases.get(database_id, "docs").await.unwrap().is_none());
+    }
+}
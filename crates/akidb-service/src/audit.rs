@@ -0,0 +1,137 @@
+//! Audit entries for vector writes and storage admin actions.
+//!
+//! Entries go to the [`AuditLogRepository`] under the tenant owning the
+//! affected collection's database (for node-wide actions, the default
+//! database). Vector writes are high-volume, so they are
+//! off unless `audit.data_plane` is set and can be sampled with
+//! `audit.write_sample_rate`; admin actions are never sampled.
+//!
+//! Failing to write an entry is logged and never fails the audited operation.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use akidb_core::{
+    Action, AuditLogEntry, AuditLogRepository, AuditResult, CollectionId, DatabaseId,
+    DatabaseRepository,
+};
+use serde_json::{json, Value as JsonValue};
+
+use crate::config::AuditConfig;
+use crate::tenant_lookup::TenantLookup;
+
+/// Records data-plane and admin audit entries.
+pub struct AuditTrail {
+    entries: Arc<dyn AuditLogRepository>,
+    tenants: TenantLookup,
+    config: AuditConfig,
+    // Vector write calls seen so far, for sampling
+    writes: AtomicU64,
+}
+
+impl AuditTrail {
+    /// Creates an audit trail resolving tenants through `databases`.
+    pub fn new(
+        entries: Arc<dyn AuditLogRepository>,
+        databases: Arc<dyn DatabaseRepository>,
+        config: &AuditConfig,
+    ) -> Self {
+        Self {
+            entries,
+            tenants: TenantLookup::new(databases),
+            config: config.clone(),
+            writes: AtomicU64::new(0),
+        }
+    }
+
+    /// Records a vector write (`DocumentInsert`, `DocumentUpdate` or
+    /// `DocumentDelete`) on a collection, subject to sampling.
+    ///
+    /// `metadata` describes the call (document ID, or counts for batches);
+    /// the sample rate is added to it.
+    pub async fn record_write(
+        &self,
+        database_id: DatabaseId,
+        collection_id: CollectionId,
+        action: Action,
+        mut metadata: JsonValue,
+    ) {
+        if !self.config.data_plane || !self.sample() {
+            return;
+        }
+
+        metadata["sample_rate"] = json!(self.config.write_sample_rate);
+        let Some(tenant_id) = self.tenants.tenant_of(database_id).await else {
+            return;
+        };
+        self.record(
+            AuditLogEntry::new(
+                tenant_id,
+                None,
+                action,
+                "collection",
+                collection_id.to_string(),
+                AuditResult::Allowed,
+            )
+            .with_metadata(metadata),
+        )
+        .await;
+    }
+
+    /// Records an admin action on a collection in `database_id`, or on the
+    /// whole node when `collection_id` is `None`.
+    pub async fn record_admin(
+        &self,
+        database_id: DatabaseId,
+        collection_id: Option<CollectionId>,
+        action: Action,
+        metadata: JsonValue,
+    ) {
+        if !self.config.admin_actions {
+            return;
+        }
+
+        let Some(tenant_id) = self.tenants.tenant_of(database_id).await else {
+            return;
+        };
+        let (resource_type, resource_id) = match collection_id {
+            Some(collection_id) => ("collection", collection_id.to_string()),
+            None => ("node", "*".to_string()),
+        };
+        self.record(
+            AuditLogEntry::new(
+                tenant_id,
+                None,
+                action,
+                resource_type,
+                resource_id,
+                AuditResult::Allowed,
+            )
+            .with_metadata(metadata),
+        )
+        .await;
+    }
+
+    /// Picks evenly spaced write calls: with rate `r`, the n-th call is
+    /// recorded when `floor(n * r)` increases.
+    fn sample(&self) -> bool {
+        let rate = self.config.write_sample_rate;
+        if rate >= 1.0 {
+            return true;
+        }
+        let n = self.writes.fetch_add(1, Ordering::Relaxed) + 1;
+        (n as f64 * rate).floor() > ((n - 1) as f64 * rate).floor()
+    }
+
+    async fn record(&self, entry: AuditLogEntry) {
+        if let Err(e) = self.entries.create(&entry).await {
+            tracing::warn!(
+                "Failed to write audit entry {} on {} {}: {}",
+                entry.action.as_str(),
+                entry.resource_type,
+                entry.resource_id,
+                e
+            );
+        }
+    }
+}
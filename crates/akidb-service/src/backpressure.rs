@@ -0,0 +1,212 @@
+//! Queue-depth backpressure.
+//!
+//! Writes are acknowledged once they reach the WAL and the index; the S3
+//! upload happens later from a queue, and failed uploads sit in a retry queue
+//! until they succeed or land in the dead letter queue. Under sustained load
+//! those queues grow without bound, so the API layers consult
+//! [`Backpressure`] before accepting work and reject it while a queue is over
+//! its threshold.
+//!
+//! A full upload or embedding queue means the caller is sending faster than
+//! the server drains (`RATE_LIMITED`, 429). A full retry queue means S3 itself
+//! is failing (`UNAVAILABLE`, 503). Either way the suggested `Retry-After`
+//! grows with how far the queue is over its limit.
+
+use crate::config::BackpressureConfig;
+use akidb_core::ErrorCode;
+use std::fmt;
+use std::time::Duration;
+
+/// Queue checked by [`Backpressure`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueKind {
+    /// Documents waiting for their first S3 upload
+    Uploads,
+    /// Failed S3 uploads waiting for retry
+    Retries,
+    /// Embedding requests in flight
+    Embeddings,
+    /// A tenant's embed requests per second (`embedding.tenant_qps`)
+    EmbeddingRate,
+}
+
+impl QueueKind {
+    /// Name used in error details and logs.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Uploads => "s3_upload",
+            Self::Retries => "s3_retry",
+            Self::Embeddings => "embedding",
+            Self::EmbeddingRate => "embedding_rate",
+        }
+    }
+}
+
+impl fmt::Display for QueueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A queue over its threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overload {
+    pub queue: QueueKind,
+    pub depth: usize,
+    pub limit: usize,
+    /// How long the client should wait before retrying
+    pub retry_after: Duration,
+}
+
+impl Overload {
+    /// Error code reported to the client.
+    pub fn code(&self) -> ErrorCode {
+        match self.queue {
+            QueueKind::Retries => ErrorCode::Unavailable,
+            QueueKind::Uploads | QueueKind::Embeddings | QueueKind::EmbeddingRate => {
+                ErrorCode::RateLimited
+            }
+        }
+    }
+
+    /// `Retry-After` value in whole seconds.
+    pub fn retry_after_secs(&self) -> u64 {
+        self.retry_after.as_secs()
+    }
+}
+
+impl fmt::Display for Overload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.queue == QueueKind::EmbeddingRate {
+            return write!(
+                f,
+                "embedding rate limit exceeded ({} requests/s); retry after {}s",
+                self.limit,
+                self.retry_after_secs()
+            );
+        }
+        write!(
+            f,
+            "{} queue is full ({} pending, limit {}); retry after {}s",
+            self.queue,
+            self.depth,
+            self.limit,
+            self.retry_after_secs()
+        )
+    }
+}
+
+/// Admission control against configured queue-depth thresholds.
+#[derive(Debug, Clone, Default)]
+pub struct Backpressure {
+    config: BackpressureConfig,
+}
+
+impl Backpressure {
+    pub fn new(config: BackpressureConfig) -> Self {
+        Self { config }
+    }
+
+    /// Checks the storage queues a write would add to.
+    pub fn check_writes(
+        &self,
+        pending_uploads: usize,
+        pending_retries: usize,
+    ) -> Result<(), Overload> {
+        self.check(
+            QueueKind::Retries,
+            pending_retries,
+            self.config.max_pending_retries,
+        )?;
+        self.check(
+            QueueKind::Uploads,
+            pending_uploads,
+            self.config.max_pending_uploads,
+        )
+    }
+
+    /// Checks the embedding queue.
+    pub fn check_embeddings(&self, pending: usize) -> Result<(), Overload> {
+        self.check(
+            QueueKind::Embeddings,
+            pending,
+            self.config.max_pending_embeddings,
+        )
+    }
+
+    fn check(&self, queue: QueueKind, depth: usize, limit: usize) -> Result<(), Overload> {
+        if !self.config.enabled || limit == 0 || depth < limit {
+            return Ok(());
+        }
+        Err(Overload {
+            queue,
+            depth,
+            limit,
+            retry_after: self.retry_after(depth, limit),
+        })
+    }
+
+    /// One second at the threshold, growing linearly to the configured
+    /// maximum when the queue is twice its limit.
+    fn retry_after(&self, depth: usize, limit: usize) -> Duration {
+        let max = self.config.max_retry_after_seconds.max(1);
+        let excess = (depth - limit) as u64;
+        let secs = 1 + excess.saturating_mul(max) / limit as u64;
+        Duration::from_secs(secs.min(max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backpressure() -> Backpressure {
+        Backpressure::new(BackpressureConfig {
+            enabled: true,
+            max_pending_uploads: 100,
+            max_pending_retries: 10,
+            max_pending_embeddings: 0,
+            max_retry_after_seconds: 30,
+        })
+    }
+
+    #[test]
+    fn test_under_threshold_is_admitted() {
+        let backpressure = backpressure();
+        assert!(backpressure.check_writes(99, 9).is_ok());
+        // A zero threshold disables the check
+        assert!(backpressure.check_embeddings(10_000).is_ok());
+    }
+
+    #[test]
+    fn test_retry_after_grows_with_depth() {
+        let backpressure = backpressure();
+
+        let overload = backpressure.check_writes(100, 0).unwrap_err();
+        assert_eq!(overload.queue, QueueKind::Uploads);
+        assert_eq!(overload.code(), ErrorCode::RateLimited);
+        assert_eq!(overload.retry_after_secs(), 1);
+
+        let overload = backpressure.check_writes(150, 0).unwrap_err();
+        assert_eq!(overload.retry_after_secs(), 16);
+
+        let overload = backpressure.check_writes(1_000, 0).unwrap_err();
+        assert_eq!(overload.retry_after_secs(), 30);
+    }
+
+    #[test]
+    fn test_retry_queue_reports_unavailable() {
+        let overload = backpressure().check_writes(500, 10).unwrap_err();
+        assert_eq!(overload.queue, QueueKind::Retries);
+        assert_eq!(overload.code(), ErrorCode::Unavailable);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let backpressure = Backpressure::new(BackpressureConfig {
+            enabled: false,
+            ..BackpressureConfig::default()
+        });
+        assert!(backpressure.check_writes(usize::MAX, usize::MAX).is_ok());
+    }
+}
@@ -0,0 +1,522 @@
+//! Change data capture to Kafka or NATS (`[cdc]`).
+//!
+//! [`CdcPublisher`] tails every collection's write-ahead log and publishes
+//! each document write as a [`CdcEvent`] carrying its LSN, so downstream
+//! search or analytics systems can stay in sync without polling. Events of a
+//! collection go to the topic (Kafka) or subject (NATS JetStream)
+//! `{topic_prefix}.{collection name}`, in LSN order.
+//!
+//! Delivery is at-least-once: the last published LSN of each collection is
+//! checkpointed to `cdc.checkpoint_path` only after the broker acknowledged
+//! the batch, and publishing resumes after it on restart or failure.
+//! Consumers should skip events with an LSN they have already applied. When
+//! the entries after a checkpoint are gone (WAL files before a checkpoint
+//! are deleted after compaction), every document of the collection is
+//! published again with `snapshot: true`, as replication does.
+//!
+//! The sinks need the `kafka` or `nats` cargo feature.
+
+use crate::config::CdcConfig;
+use crate::metrics::{CDC_EVENTS_PUBLISHED_TOTAL, CDC_PUBLISH_ERRORS_TOTAL};
+use crate::CollectionService;
+use akidb_core::{CollectionDescriptor, CollectionId, CoreError, CoreResult, DocumentId};
+use akidb_storage::LogEntry;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// A document write.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum CdcOp {
+    /// Document inserted or replaced
+    Upsert {
+        doc_id: DocumentId,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        external_id: Option<String>,
+        vector: Vec<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        metadata: Option<serde_json::Value>,
+    },
+    /// Document deleted
+    Delete { doc_id: DocumentId },
+}
+
+/// One change published to the broker, serialized as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CdcEvent {
+    pub collection_id: CollectionId,
+    pub collection: String,
+    /// Position in the collection's WAL; events of a full copy all carry the
+    /// position the copy reflects
+    pub lsn: u64,
+    #[serde(flatten)]
+    pub op: CdcOp,
+    pub timestamp: DateTime<Utc>,
+    /// Part of a full copy of the collection
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub snapshot: bool,
+}
+
+/// Destination of change events.
+#[async_trait]
+pub trait CdcSink: Send + Sync {
+    /// Short name for logs and metrics (e.g. "kafka").
+    fn name(&self) -> &'static str;
+
+    /// Publishes `events` to `topic` in order, returning once the broker has
+    /// acknowledged all of them.
+    async fn publish(&self, topic: &str, events: &[CdcEvent]) -> CoreResult<()>;
+}
+
+/// Connects the sink selected by `config.sink`.
+///
+/// # Errors
+///
+/// Returns an error if the broker is unreachable, or if this build lacks the
+/// cargo feature of the sink.
+pub async fn connect_sink(config: &CdcConfig) -> CoreResult<Arc<dyn CdcSink>> {
+    match config.sink.as_str() {
+        #[cfg(feature = "kafka")]
+        "kafka" => Ok(Arc::new(kafka::KafkaSink::connect(&config.servers)?)),
+        #[cfg(feature = "nats")]
+        "nats" => Ok(Arc::new(nats::NatsSink::connect(&config.servers).await?)),
+        #[cfg(not(feature = "kafka"))]
+        "kafka" => Err(missing_feature("kafka")),
+        #[cfg(not(feature = "nats"))]
+        "nats" => Err(missing_feature("nats")),
+        other => Err(CoreError::ValidationError(format!(
+            "unknown cdc.sink \"{}\"",
+            other
+        ))),
+    }
+}
+
+#[cfg(not(all(feature = "kafka", feature = "nats")))]
+fn missing_feature(sink: &str) -> CoreError {
+    CoreError::invalid_state(format!(
+        "cdc.sink = \"{}\" needs akidb built with the `{}` feature",
+        sink, sink
+    ))
+}
+
+/// Last published LSN per collection, persisted across restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CdcCheckpoint {
+    positions: HashMap<CollectionId, u64>,
+}
+
+impl CdcCheckpoint {
+    /// Checkpoint stored at `path`, or an empty one if there is none yet.
+    fn load(path: &Path) -> CoreResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let bytes = std::fs::read(path)
+            .map_err(|e| CoreError::internal(format!("failed to read {:?}: {}", path, e)))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| CoreError::internal(format!("invalid CDC checkpoint {:?}: {}", path, e)))
+    }
+
+    fn save(&self, path: &Path) -> CoreResult<()> {
+        let bytes = serde_json::to_vec(self)
+            .map_err(|e| CoreError::internal(format!("failed to encode CDC checkpoint: {}", e)))?;
+        // Write-then-rename so a crash never leaves a truncated file
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)
+            .and_then(|()| std::fs::rename(&tmp, path))
+            .map_err(|e| CoreError::internal(format!("failed to write {:?}: {}", path, e)))
+    }
+}
+
+/// Publishes WAL entries of the selected collections to a [`CdcSink`].
+pub struct CdcPublisher {
+    service: Arc<CollectionService>,
+    sink: Arc<dyn CdcSink>,
+    topic_prefix: String,
+    collections: Vec<String>,
+    batch_size: usize,
+    poll_interval: Duration,
+    checkpoint_path: PathBuf,
+    checkpoint: CdcCheckpoint,
+}
+
+impl CdcPublisher {
+    /// Publishes from `service` to `sink`, resuming from the checkpoint at
+    /// `config.checkpoint_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint exists but cannot be read.
+    pub fn new(
+        service: Arc<CollectionService>,
+        sink: Arc<dyn CdcSink>,
+        config: &CdcConfig,
+    ) -> CoreResult<Self> {
+        Ok(Self {
+            service,
+            sink,
+            topic_prefix: config.topic_prefix.clone(),
+            collections: config.collections.clone(),
+            batch_size: config.batch_size,
+            poll_interval: Duration::from_millis(config.poll_interval_ms),
+            checkpoint: CdcCheckpoint::load(&config.checkpoint_path)?,
+            checkpoint_path: config.checkpoint_path.clone(),
+        })
+    }
+
+    /// Last published LSN per collection.
+    pub fn positions(&self) -> &HashMap<CollectionId, u64> {
+        &self.checkpoint.positions
+    }
+
+    /// Spawns the publishing loop: a pass after every write and at least
+    /// every `poll_interval`. A failed pass is retried from the checkpoint.
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut changes = self.service.events().subscribe();
+            loop {
+                if let Err(e) = self.publish_pass().await {
+                    CDC_PUBLISH_ERRORS_TOTAL
+                        .with_label_values(&[self.sink.name()])
+                        .inc();
+                    tracing::warn!("CDC: publishing to {} failed: {}", self.sink.name(), e);
+                    // Back off instead of retrying at every write
+                    tokio::time::sleep(self.poll_interval).await;
+                    continue;
+                }
+                // Lagging behind the event bus is fine: the next pass reads
+                // the WAL itself
+                tokio::select! {
+                    _ = changes.recv() => {}
+                    _ = tokio::time::sleep(self.poll_interval) => {}
+                }
+            }
+        })
+    }
+
+    /// Publishes everything written since the checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first publishing or checkpointing error; collections
+    /// published before it keep their progress.
+    pub async fn publish_pass(&mut self) -> CoreResult<()> {
+        let collections = self.service.list_collections().await?;
+
+        // Forget deleted collections, so a new one never inherits a position
+        let live: HashSet<CollectionId> = collections.iter().map(|c| c.collection_id).collect();
+        let before = self.checkpoint.positions.len();
+        self.checkpoint.positions.retain(|id, _| live.contains(id));
+        if self.checkpoint.positions.len() != before {
+            self.checkpoint.save(&self.checkpoint_path)?;
+        }
+
+        for descriptor in collections {
+            if self.is_selected(&descriptor.name) {
+                self.publish_collection(&descriptor).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_selected(&self, name: &str) -> bool {
+        self.collections.is_empty()
+            || self
+                .collections
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => name.starts_with(prefix),
+                    None => name == pattern,
+                })
+    }
+
+    async fn publish_collection(&mut self, descriptor: &CollectionDescriptor) -> CoreResult<()> {
+        let collection_id = descriptor.collection_id;
+        let Some(current) = self.service.wal_position(collection_id).await? else {
+            return Ok(());
+        };
+        let from = self
+            .checkpoint
+            .positions
+            .get(&collection_id)
+            .copied()
+            .unwrap_or(0);
+        if from == current {
+            return Ok(());
+        }
+
+        let entries = if from < current {
+            self.service.read_wal(collection_id, from + 1).await?
+        } else {
+            Vec::new()
+        };
+        // Entries after the checkpoint are gone, or the checkpoint is ahead
+        // of this WAL (e.g. the collection was restored): start over
+        let gap = from > current
+            || entries
+                .first()
+                .map_or(from < current, |(lsn, _)| *lsn > from + 1);
+        if gap {
+            return self.publish_snapshot(descriptor).await;
+        }
+
+        let topic = self.topic(&descriptor.name);
+        for chunk in entries.chunks(self.batch_size) {
+            let events: Vec<CdcEvent> = chunk
+                .iter()
+                .filter_map(|(lsn, entry)| cdc_event(descriptor, *lsn, entry))
+                .collect();
+            if !events.is_empty() {
+                self.publish(&topic, &events).await?;
+            }
+            // Entries without an event (e.g. checkpoints) still advance
+            if let Some((lsn, _)) = chunk.last() {
+                self.advance(collection_id, *lsn)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Publishes every document of the collection.
+    async fn publish_snapshot(&mut self, descriptor: &CollectionDescriptor) -> CoreResult<()> {
+        let collection_id = descriptor.collection_id;
+        let (lsn, docs) = self.service.replication_snapshot(collection_id).await?;
+        tracing::info!(
+            "CDC: publishing full copy of collection {} ({} documents, LSN {})",
+            descriptor.name,
+            docs.len(),
+            lsn
+        );
+
+        let topic = self.topic(&descriptor.name);
+        for chunk in docs.chunks(self.batch_size) {
+            let events: Vec<CdcEvent> = chunk
+                .iter()
+                .map(|doc| CdcEvent {
+                    collection_id,
+                    collection: descriptor.name.clone(),
+                    lsn,
+                    op: CdcOp::Upsert {
+                        doc_id: doc.doc_id,
+                        external_id: doc.external_id.clone(),
+                        vector: doc.vector.clone(),
+                        metadata: doc.metadata.clone(),
+                    },
+                    timestamp: doc.inserted_at,
+                    snapshot: true,
+                })
+                .collect();
+            self.publish(&topic, &events).await?;
+        }
+        // Only complete once every document is out; a retry starts over
+        self.advance(collection_id, lsn)
+    }
+
+    async fn publish(&self, topic: &str, events: &[CdcEvent]) -> CoreResult<()> {
+        self.sink.publish(topic, events).await?;
+        CDC_EVENTS_PUBLISHED_TOTAL
+            .with_label_values(&[self.sink.name()])
+            .inc_by(events.len() as f64);
+        Ok(())
+    }
+
+    fn advance(&mut self, collection_id: CollectionId, lsn: u64) -> CoreResult<()> {
+        self.checkpoint.positions.insert(collection_id, lsn);
+        self.checkpoint.save(&self.checkpoint_path)
+    }
+
+    /// Topic of a collection; characters brokers reject become `_`.
+    fn topic(&self, collection: &str) -> String {
+        let name: String = collection
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}.{}", self.topic_prefix, name)
+    }
+}
+
+/// Document writes are published; collection and checkpoint records are not.
+fn cdc_event(descriptor: &CollectionDescriptor, lsn: u64, entry: &LogEntry) -> Option<CdcEvent> {
+    let (op, timestamp) = match entry {
+        LogEntry::Upsert {
+            doc_id,
+            vector,
+            external_id,
+            metadata,
+            timestamp,
+            ..
+        } => (
+            CdcOp::Upsert {
+                doc_id: *doc_id,
+                external_id: external_id.clone(),
+                vector: vector.clone(),
+                metadata: metadata.clone(),
+            },
+            *timestamp,
+        ),
+        LogEntry::Delete {
+            doc_id, timestamp, ..
+        } => (CdcOp::Delete { doc_id: *doc_id }, *timestamp),
+        _ => return None,
+    };
+    Some(CdcEvent {
+        collection_id: descriptor.collection_id,
+        collection: descriptor.name.clone(),
+        lsn,
+        op,
+        timestamp,
+        snapshot: false,
+    })
+}
+
+#[cfg(any(feature = "kafka", feature = "nats"))]
+fn encode(event: &CdcEvent) -> CoreResult<Vec<u8>> {
+    serde_json::to_vec(event)
+        .map_err(|e| CoreError::internal(format!("failed to encode CDC event: {}", e)))
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use super::{encode, CdcEvent, CdcSink};
+    use akidb_core::{CoreError, CoreResult};
+    use async_trait::async_trait;
+    use rdkafka::config::ClientConfig;
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+
+    /// Publishes to Kafka, keyed by collection so a collection's events stay
+    /// in one partition, in order.
+    pub struct KafkaSink {
+        producer: FutureProducer,
+    }
+
+    impl KafkaSink {
+        pub fn connect(bootstrap_servers: &str) -> CoreResult<Self> {
+            let producer = ClientConfig::new()
+                .set("bootstrap.servers", bootstrap_servers)
+                // Keeps order across retries while several batches are in flight
+                .set("enable.idempotence", "true")
+                .set("acks", "all")
+                .create()
+                .map_err(|e| {
+                    CoreError::internal(format!("failed to create Kafka producer: {}", e))
+                })?;
+            Ok(Self { producer })
+        }
+    }
+
+    #[async_trait]
+    impl CdcSink for KafkaSink {
+        fn name(&self) -> &'static str {
+            "kafka"
+        }
+
+        async fn publish(&self, topic: &str, events: &[CdcEvent]) -> CoreResult<()> {
+            let mut deliveries = Vec::with_capacity(events.len());
+            for event in events {
+                let payload = encode(event)?;
+                let key = event.collection_id.to_string();
+                let record = FutureRecord::to(topic)
+                    .key(key.as_str())
+                    .payload(payload.as_slice());
+                let delivery = self.producer.send_result(record).map_err(|(e, _)| {
+                    CoreError::internal(format!("failed to queue Kafka message: {}", e))
+                })?;
+                deliveries.push(delivery);
+            }
+            for delivery in deliveries {
+                match delivery.await {
+                    Ok(Ok(_)) => {}
+                    Ok(Err((e, _))) => {
+                        return Err(CoreError::internal(format!("Kafka delivery failed: {}", e)))
+                    }
+                    Err(_) => return Err(CoreError::internal("Kafka delivery canceled")),
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "nats")]
+mod nats {
+    use super::{encode, CdcEvent, CdcSink};
+    use akidb_core::{CoreError, CoreResult};
+    use async_trait::async_trait;
+
+    /// Publishes to NATS JetStream; the streams must already capture the
+    /// subjects.
+    pub struct NatsSink {
+        jetstream: async_nats::jetstream::Context,
+    }
+
+    impl NatsSink {
+        pub async fn connect(url: &str) -> CoreResult<Self> {
+            let client = async_nats::connect(url)
+                .await
+                .map_err(|e| CoreError::internal(format!("failed to connect to NATS: {}", e)))?;
+            Ok(Self {
+                jetstream: async_nats::jetstream::new(client),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl CdcSink for NatsSink {
+        fn name(&self) -> &'static str {
+            "nats"
+        }
+
+        async fn publish(&self, subject: &str, events: &[CdcEvent]) -> CoreResult<()> {
+            let mut acks = Vec::with_capacity(events.len());
+            for event in events {
+                let ack = self
+                    .jetstream
+                    .publish(subject.to_string(), encode(event)?.into())
+                    .await
+                    .map_err(|e| CoreError::internal(format!("NATS publish failed: {}", e)))?;
+                acks.push(ack);
+            }
+            for ack in acks {
+                ack.await.map_err(|e| {
+                    CoreError::internal(format!("NATS publish not acknowledged: {}", e))
+                })?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = CdcEvent {
+            collection_id: CollectionId::new(),
+            collection: "docs".to_string(),
+            lsn: 7,
+            op: CdcOp::Delete {
+                doc_id: DocumentId::new(),
+            },
+            timestamp: Utc::now(),
+            snapshot: false,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["op"], "delete");
+        assert_eq!(json["lsn"], 7);
+        assert!(json.get("snapshot").is_none());
+    }
+}
@@ -0,0 +1,218 @@
+//! Per-API-key collection allowlists.
+//!
+//! An API key may be restricted to a set of collections
+//! (`ApiKeyDescriptor::allowed_collections`), so a leaked ingestion key for one
+//! dataset cannot read the tenant's other collections. [`CollectionAcl`] is
+//! consulted by the REST middleware and the gRPC handlers before a request
+//! touches a collection, and by the list and embed handlers to filter or check
+//! the collections they return or read.
+//!
+//! By default requests without an API key, or with an unknown key, are left to
+//! the other authorization layers. With [`CollectionAcl::require_key`] they are
+//! rejected instead, so an allowlist cannot be bypassed by leaving the key out.
+//!
+//! [`CollectionAcl::identity`] also reports which key and tenant a request was
+//! authenticated as, for layers that must scope by the caller rather than by
+//! headers the caller chooses.
+//!
+//! Resolved keys are cached for a minute, so allowlist changes take up to that
+//! long to apply.
+
+use akidb_core::{hash_api_key, ApiKeyId, ApiKeyRepository, CollectionId, CoreResult, TenantId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a resolved key is cached before it is looked up again.
+const KEY_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Resolved keys are dropped once this many are cached.
+const MAX_CACHED_KEYS: usize = 10_000;
+
+/// Outcome of checking a request against its key's allowlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionAccess {
+    /// The key may access the collection (or the request carries no restricted key)
+    Allowed,
+    /// The key is restricted to other collections
+    Denied { key_id: ApiKeyId },
+    /// A valid key is required but the request has none
+    Unauthenticated,
+}
+
+/// Collections a request may access.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollectionGrants {
+    /// Every collection: an unrestricted key, or no key when none is required
+    All,
+    /// Only the collections on the key's allowlist
+    Only {
+        key_id: ApiKeyId,
+        collections: Arc<[CollectionId]>,
+    },
+}
+
+impl CollectionGrants {
+    /// Whether `collection_id` may be accessed.
+    pub fn allows(&self, collection_id: CollectionId) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only { collections, .. } => collections.contains(&collection_id),
+        }
+    }
+
+    /// Checks `collection_id` against the grants.
+    pub fn access(&self, collection_id: CollectionId) -> CollectionAccess {
+        match self {
+            Self::Only { key_id, .. } if !self.allows(collection_id) => {
+                CollectionAccess::Denied { key_id: *key_id }
+            }
+            _ => CollectionAccess::Allowed,
+        }
+    }
+}
+
+/// Valid, unexpired API key a request was authenticated with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyIdentity {
+    pub key_id: ApiKeyId,
+    /// Tenant that owns the key
+    pub tenant_id: TenantId,
+}
+
+/// A key found in the repository.
+#[derive(Debug, Clone)]
+struct ResolvedKey {
+    /// `None` when the key has expired
+    identity: Option<KeyIdentity>,
+    grants: CollectionGrants,
+}
+
+/// Resolved key: `None` for unknown keys (and expired ones when a valid key is
+/// required).
+type CachedKey = Option<ResolvedKey>;
+
+/// Checks API keys against their collection allowlists.
+pub struct CollectionAcl {
+    api_keys: Arc<dyn ApiKeyRepository>,
+    key_required: bool,
+    cache: Mutex<HashMap<String, (Instant, CachedKey)>>,
+}
+
+impl CollectionAcl {
+    /// Creates an ACL resolving keys through `api_keys`.
+    pub fn new(api_keys: Arc<dyn ApiKeyRepository>) -> Self {
+        Self {
+            api_keys,
+            key_required: false,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Rejects requests without a valid API key (`features.require_api_key`).
+    pub fn require_key(mut self, required: bool) -> Self {
+        self.key_required = required;
+        self
+    }
+
+    /// Whether requests must carry a valid API key.
+    pub fn is_key_required(&self) -> bool {
+        self.key_required
+    }
+
+    /// Collections `api_key` may access, or `None` if a valid key is required
+    /// and `api_key` is missing, unknown or expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be looked up.
+    pub async fn grants(&self, api_key: Option<&str>) -> CoreResult<Option<CollectionGrants>> {
+        let grants = match api_key.filter(|key| !key.is_empty()) {
+            Some(api_key) => self
+                .resolve(&hash_api_key(api_key))
+                .await?
+                .map(|resolved| resolved.grants),
+            None => None,
+        };
+
+        Ok(match grants {
+            Some(grants) => Some(grants),
+            None if self.key_required => None,
+            None => Some(CollectionGrants::All),
+        })
+    }
+
+    /// Key and tenant `api_key` authenticates as, or `None` if it is missing,
+    /// unknown or expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be looked up.
+    pub async fn identity(&self, api_key: Option<&str>) -> CoreResult<Option<KeyIdentity>> {
+        Ok(match api_key.filter(|key| !key.is_empty()) {
+            Some(api_key) => self
+                .resolve(&hash_api_key(api_key))
+                .await?
+                .and_then(|resolved| resolved.identity),
+            None => None,
+        })
+    }
+
+    /// Checks whether `api_key` may access `collection_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be looked up.
+    pub async fn check(
+        &self,
+        api_key: Option<&str>,
+        collection_id: CollectionId,
+    ) -> CoreResult<CollectionAccess> {
+        Ok(match self.grants(api_key).await? {
+            Some(grants) => grants.access(collection_id),
+            None => CollectionAccess::Unauthenticated,
+        })
+    }
+
+    async fn resolve(&self, key_hash: &str) -> CoreResult<CachedKey> {
+        {
+            let cache = self.cache.lock().expect("collection ACL lock poisoned");
+            if let Some((resolved_at, resolved)) = cache.get(key_hash) {
+                if resolved_at.elapsed() < KEY_CACHE_TTL {
+                    return Ok(resolved.clone());
+                }
+            }
+        }
+
+        // Without `key_required`, expired keys are rejected by the layers that
+        // require a valid key; their allowlist still applies here
+        let resolved = self
+            .api_keys
+            .get_by_hash(key_hash)
+            .await?
+            .filter(|descriptor| !(self.key_required && descriptor.is_expired()))
+            .map(|descriptor| ResolvedKey {
+                identity: (!descriptor.is_expired()).then_some(KeyIdentity {
+                    key_id: descriptor.key_id,
+                    tenant_id: descriptor.tenant_id,
+                }),
+                grants: match descriptor.allowed_collections {
+                    Some(allowed) => CollectionGrants::Only {
+                        key_id: descriptor.key_id,
+                        collections: Arc::from(allowed),
+                    },
+                    None => CollectionGrants::All,
+                },
+            });
+
+        let mut cache = self.cache.lock().expect("collection ACL lock poisoned");
+        if cache.len() >= MAX_CACHED_KEYS {
+            cache.retain(|_, (resolved_at, _)| resolved_at.elapsed() < KEY_CACHE_TTL);
+            if cache.len() >= MAX_CACHED_KEYS {
+                cache.clear();
+            }
+        }
+        cache.insert(key_hash.to_string(), (Instant::now(), resolved.clone()));
+        Ok(resolved)
+    }
+}
@@ -2,18 +2,44 @@
 //! Shared by gRPC and REST APIs.
 
 use akidb_core::{
-    CollectionDescriptor, CollectionId, CollectionRepository, CoreError, CoreResult, DatabaseId,
-    DistanceMetric, DocumentId, SearchResult, VectorDocument, VectorIndex,
+    Action, CollectionAlias, CollectionDescriptor, CollectionId, CollectionRepository, CoreError,
+    CoreResult, DatabaseId, DistanceMetric, DocumentId, JobDescriptor, JobId, Quantization,
+    SearchResult, SparseVector, TenantId, VectorDocument, VectorIndex, VectorType,
 };
-use akidb_index::{BruteForceIndex, InstantDistanceConfig, InstantDistanceIndex};
+use akidb_index::{
+    BruteForceIndex, HnswConfig as HnswIndexConfig, HnswIndex, HybridIndex, InstantDistanceConfig,
+    InstantDistanceIndex, SparseIndex, TextIndex,
+};
+use akidb_storage::snapshotter::{SnapshotId, SnapshotMetadata};
 use akidb_storage::{
-    CacheStats, CircuitBreakerState, StorageBackend, StorageConfig, StorageMetrics,
+    CacheStats, CircuitBreakerState, GcConfig, GcReport, LogEntry, LogSequenceNumber,
+    RestoreReport, StorageBackend, StorageConfig, StorageMetrics, TieringPolicy, WalState,
 };
-use chrono::Utc;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tracing::Instrument;
+
+use crate::aliases::CollectionAliases;
+use crate::audit::AuditTrail;
+use crate::backpressure::{Backpressure, Overload};
+use crate::config::{BackpressureConfig, HnswConfig, IdempotencyConfig, LimitsConfig};
+use crate::debug::{CollectionQueueDepths, LockStats, TrackedRwLock};
+use crate::drain::{DrainController, DrainStage, DrainStatus, QUEUE_POLL_INTERVAL};
+use crate::events::{ChangeEvent, ChangeKind, EventBus};
+use crate::feature_flags::{self, FeatureFlags};
+use crate::filter::FilterTree;
+use crate::health::ProbeResult;
+use crate::hybrid::{self, HybridOptions};
+use crate::idempotency::IdempotencyStore;
+use crate::jobs::{JobHandle, JobManager};
+use crate::metering::UsageMeter;
+use crate::readiness::{Readiness, OBJECT_STORE_RETRY_INTERVAL};
+use crate::reranker::Reranker;
 
 // Import metrics for instrumentation
 use crate::metrics::*;
@@ -21,6 +47,15 @@ use crate::metrics::*;
 // Phase 10 Week 3: Tiering manager integration
 use akidb_storage::tiering_manager::TieringManager;
 
+/// Upper bound on `top_k` for searches.
+///
+/// FIX BUG #8: Validate top_k to prevent DoS via memory exhaustion
+/// Reasonable limit: 10,000 results (prevents usize::MAX attacks)
+const MAX_TOP_K: usize = 10_000;
+
+/// Documents indexed by a reindex job between progress updates.
+const REINDEX_PROGRESS_STEP: u64 = 1_000;
+
 /// Result of DLQ retry operation
 #[derive(Debug, Clone)]
 pub struct DLQRetryResult {
@@ -29,6 +64,124 @@ pub struct DLQRetryResult {
     pub failed: usize,
 }
 
+/// Compaction state of a collection's storage backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionStatus {
+    /// When the WAL was last compacted into a snapshot (since startup)
+    pub last_compaction_at: Option<DateTime<Utc>>,
+    /// Compactions performed since startup
+    pub compactions: u64,
+    /// WAL bytes written since the last compaction
+    pub wal_size_bytes: u64,
+    /// Inserts since the last compaction
+    pub wal_operations: u64,
+    pub threshold_bytes: u64,
+    pub threshold_ops: u64,
+    /// Whether either threshold is exceeded (compaction is due)
+    pub thresholds_exceeded: bool,
+}
+
+/// Outcome of deleting a single document in a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchDeleteStatus {
+    /// Document was removed from storage and the index
+    Deleted,
+    /// Document does not exist in the collection
+    NotFound,
+    /// Delete failed; other documents in the batch are unaffected
+    Failed(String),
+}
+
+/// Optional post-processing for [`CollectionService::search`]
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Only return documents whose metadata matches this filter
+    pub filter: Option<FilterTree>,
+    /// Drop results worse than this score (`>=` for cosine/dot, `<=` for L2)
+    pub score_threshold: Option<f32>,
+    /// Keep document metadata in the results
+    pub include_payload: bool,
+    /// Project the kept metadata onto these top-level keys (all keys when `None`)
+    pub payload_fields: Option<Vec<String>>,
+    /// Attach the stored vector to each result
+    pub include_vector: bool,
+    /// Reorder candidates with the configured cross-encoder reranker
+    pub rerank: Option<RerankOptions>,
+    /// Fuse the vector ranking with BM25 keyword matches
+    pub hybrid: Option<HybridOptions>,
+    /// Rank by dot product with this sparse query instead of the dense query
+    /// vector, which must then be empty
+    pub sparse: Option<SparseVector>,
+}
+
+/// Per-collection settings fixed at [`CollectionService::create_collection_in`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CollectionOptions {
+    /// L2-normalize vectors at insert and query time
+    pub normalize: bool,
+    /// Precision of the vectors held in the index
+    pub quantization: Quantization,
+    /// Dense, sparse, or both kinds of vectors per document
+    pub vector_type: VectorType,
+}
+
+/// Collection settings changed by [`CollectionService::reindex_collection`];
+/// unset fields keep their current value
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReindexOptions {
+    /// HNSW graph degree (2-100)
+    pub hnsw_m: Option<u32>,
+    /// HNSW construction EF (10-1000)
+    pub hnsw_ef_construction: Option<u32>,
+    /// Document count guardrail; collections of up to 10,000 documents use
+    /// brute-force search instead of HNSW
+    pub max_doc_count: Option<u64>,
+}
+
+impl ReindexOptions {
+    /// Validates the options and applies them to `collection`.
+    fn apply(&self, collection: &mut CollectionDescriptor) -> CoreResult<()> {
+        if let Some(m) = self.hnsw_m {
+            if !(2..=100).contains(&m) {
+                return Err(CoreError::ValidationError(format!(
+                    "hnsw_m must be between 2 and 100 (got {})",
+                    m
+                )));
+            }
+            collection.hnsw_m = m;
+        }
+        if let Some(ef_construction) = self.hnsw_ef_construction {
+            if !(10..=1000).contains(&ef_construction) {
+                return Err(CoreError::ValidationError(format!(
+                    "hnsw_ef_construction must be between 10 and 1000 (got {})",
+                    ef_construction
+                )));
+            }
+            collection.hnsw_ef_construction = ef_construction;
+        }
+        if let Some(max_doc_count) = self.max_doc_count {
+            if max_doc_count == 0 {
+                return Err(CoreError::ValidationError(
+                    "max_doc_count must be positive".to_string(),
+                ));
+            }
+            collection.max_doc_count = max_doc_count;
+        }
+        Ok(())
+    }
+}
+
+/// Cross-encoder rerank stage of [`CollectionService::search`]
+#[derive(Debug, Clone)]
+pub struct RerankOptions {
+    /// Query text the documents are scored against
+    pub query: String,
+    /// Metadata field holding each document's text
+    pub text_field: String,
+    /// Candidates fetched from the index and reranked (at least `top_k`)
+    pub candidates: usize,
+}
+
 /// Service-level metrics for collections, vectors, and operations
 #[derive(Debug, Clone)]
 pub struct ServiceMetrics {
@@ -98,16 +251,16 @@ pub struct CollectionService {
     vector_persistence: Option<Arc<akidb_metadata::VectorPersistence>>,
 
     // In-memory cache for fast reads (synced with repository)
-    collections: Arc<RwLock<HashMap<CollectionId, CollectionDescriptor>>>,
+    collections: Arc<TrackedRwLock<HashMap<CollectionId, CollectionDescriptor>>>,
 
     // In-memory vector indexes (collection_id -> VectorIndex)
-    indexes: Arc<RwLock<HashMap<CollectionId, Box<dyn VectorIndex>>>>,
+    indexes: Arc<TrackedRwLock<HashMap<CollectionId, Box<dyn VectorIndex>>>>,
 
     // Default database_id for RC1 (single-database mode)
-    default_database_id: Arc<RwLock<Option<DatabaseId>>>,
+    default_database_id: Arc<TrackedRwLock<Option<DatabaseId>>>,
 
     // Storage backends (Phase 6 Week 5+: per-collection tiered storage)
-    storage_backends: Arc<RwLock<HashMap<CollectionId, Arc<StorageBackend>>>>,
+    storage_backends: Arc<TrackedRwLock<HashMap<CollectionId, Arc<StorageBackend>>>>,
 
     // Storage configuration (used when creating new storage backends)
     storage_config: StorageConfig,
@@ -118,6 +271,48 @@ pub struct CollectionService {
     // Tiering manager for hot/warm/cold tier management (Phase 10 Week 3)
     // Optional: If None, tiering is disabled (backward compatible)
     tiering_manager: Option<Arc<TieringManager>>,
+
+    // Request size and payload limits enforced by the API layers
+    limits: LimitsConfig,
+
+    // Change events for watch subscribers
+    events: EventBus,
+
+    // Background jobs for long-running operations
+    jobs: Arc<JobManager>,
+
+    // Alternate names clients use in place of collection IDs
+    aliases: Arc<CollectionAliases>,
+
+    // Collections with a reindex job in progress
+    reindexing: Arc<TrackedRwLock<HashSet<CollectionId>>>,
+
+    // Responses replayed for retried requests carrying an idempotency key
+    idempotency: Arc<IdempotencyStore>,
+
+    // Queue-depth thresholds for admitting writes
+    backpressure: Backpressure,
+
+    // Cross-encoder for the search rerank stage (optional)
+    reranker: Option<Arc<Reranker>>,
+
+    // Audit entries for vector writes and admin actions (optional)
+    audit: Option<Arc<AuditTrail>>,
+
+    // Per-tenant usage counters for billing (optional)
+    metering: Option<Arc<UsageMeter>>,
+
+    // Bootstrap progress gating readiness
+    readiness: Arc<Readiness>,
+
+    // Runtime feature flags, evaluated per tenant
+    feature_flags: Arc<FeatureFlags>,
+
+    // In-flight requests and drain progress before shutdown
+    drain: Arc<DrainController>,
+
+    // Use the native HNSW index for large collections and persist its graph
+    persist_index_graphs: bool,
 }
 
 impl CollectionService {
@@ -127,13 +322,27 @@ impl CollectionService {
         Self {
             repository: None,
             vector_persistence: None,
-            collections: Arc::new(RwLock::new(HashMap::new())),
-            indexes: Arc::new(RwLock::new(HashMap::new())),
-            default_database_id: Arc::new(RwLock::new(None)),
-            storage_backends: Arc::new(RwLock::new(HashMap::new())),
+            collections: Arc::new(TrackedRwLock::new("collections", HashMap::new())),
+            indexes: Arc::new(TrackedRwLock::new("indexes", HashMap::new())),
+            default_database_id: Arc::new(TrackedRwLock::new("default_database_id", None)),
+            storage_backends: Arc::new(TrackedRwLock::new("storage_backends", HashMap::new())),
             storage_config: StorageConfig::default(),
             start_time: Instant::now(),
             tiering_manager: None,
+            limits: LimitsConfig::default(),
+            events: EventBus::new(),
+            jobs: Arc::new(JobManager::new()),
+            aliases: Arc::new(CollectionAliases::new()),
+            reindexing: Arc::new(TrackedRwLock::new("reindexing", HashSet::new())),
+            idempotency: Arc::new(IdempotencyStore::default()),
+            backpressure: Backpressure::default(),
+            reranker: None,
+            audit: None,
+            metering: None,
+            readiness: Arc::new(Readiness::new()),
+            feature_flags: Arc::new(FeatureFlags::default()),
+            drain: Arc::new(DrainController::new()),
+            persist_index_graphs: false,
         }
     }
 
@@ -143,13 +352,27 @@ impl CollectionService {
         Self {
             repository: Some(repository),
             vector_persistence: None,
-            collections: Arc::new(RwLock::new(HashMap::new())),
-            indexes: Arc::new(RwLock::new(HashMap::new())),
-            default_database_id: Arc::new(RwLock::new(None)),
-            storage_backends: Arc::new(RwLock::new(HashMap::new())),
+            collections: Arc::new(TrackedRwLock::new("collections", HashMap::new())),
+            indexes: Arc::new(TrackedRwLock::new("indexes", HashMap::new())),
+            default_database_id: Arc::new(TrackedRwLock::new("default_database_id", None)),
+            storage_backends: Arc::new(TrackedRwLock::new("storage_backends", HashMap::new())),
             storage_config: StorageConfig::default(),
             start_time: Instant::now(),
             tiering_manager: None,
+            limits: LimitsConfig::default(),
+            events: EventBus::new(),
+            jobs: Arc::new(JobManager::new()),
+            aliases: Arc::new(CollectionAliases::new()),
+            reindexing: Arc::new(TrackedRwLock::new("reindexing", HashSet::new())),
+            idempotency: Arc::new(IdempotencyStore::default()),
+            backpressure: Backpressure::default(),
+            reranker: None,
+            audit: None,
+            metering: None,
+            readiness: Arc::new(Readiness::new()),
+            feature_flags: Arc::new(FeatureFlags::default()),
+            drain: Arc::new(DrainController::new()),
+            persist_index_graphs: false,
         }
     }
 
@@ -162,13 +385,27 @@ impl CollectionService {
         Self {
             repository: Some(repository),
             vector_persistence: Some(vector_persistence),
-            collections: Arc::new(RwLock::new(HashMap::new())),
-            indexes: Arc::new(RwLock::new(HashMap::new())),
-            default_database_id: Arc::new(RwLock::new(None)),
-            storage_backends: Arc::new(RwLock::new(HashMap::new())),
+            collections: Arc::new(TrackedRwLock::new("collections", HashMap::new())),
+            indexes: Arc::new(TrackedRwLock::new("indexes", HashMap::new())),
+            default_database_id: Arc::new(TrackedRwLock::new("default_database_id", None)),
+            storage_backends: Arc::new(TrackedRwLock::new("storage_backends", HashMap::new())),
             storage_config: StorageConfig::default(),
             start_time: Instant::now(),
             tiering_manager: None,
+            limits: LimitsConfig::default(),
+            events: EventBus::new(),
+            jobs: Arc::new(JobManager::new()),
+            aliases: Arc::new(CollectionAliases::new()),
+            reindexing: Arc::new(TrackedRwLock::new("reindexing", HashSet::new())),
+            idempotency: Arc::new(IdempotencyStore::default()),
+            backpressure: Backpressure::default(),
+            reranker: None,
+            audit: None,
+            metering: None,
+            readiness: Arc::new(Readiness::new()),
+            feature_flags: Arc::new(FeatureFlags::default()),
+            drain: Arc::new(DrainController::new()),
+            persist_index_graphs: false,
         }
     }
 
@@ -183,13 +420,27 @@ impl CollectionService {
         Self {
             repository: Some(repository),
             vector_persistence: Some(vector_persistence),
-            collections: Arc::new(RwLock::new(HashMap::new())),
-            indexes: Arc::new(RwLock::new(HashMap::new())),
-            default_database_id: Arc::new(RwLock::new(None)),
-            storage_backends: Arc::new(RwLock::new(HashMap::new())),
+            collections: Arc::new(TrackedRwLock::new("collections", HashMap::new())),
+            indexes: Arc::new(TrackedRwLock::new("indexes", HashMap::new())),
+            default_database_id: Arc::new(TrackedRwLock::new("default_database_id", None)),
+            storage_backends: Arc::new(TrackedRwLock::new("storage_backends", HashMap::new())),
             storage_config,
             start_time: Instant::now(),
             tiering_manager: None,
+            limits: LimitsConfig::default(),
+            events: EventBus::new(),
+            jobs: Arc::new(JobManager::new()),
+            aliases: Arc::new(CollectionAliases::new()),
+            reindexing: Arc::new(TrackedRwLock::new("reindexing", HashSet::new())),
+            idempotency: Arc::new(IdempotencyStore::default()),
+            backpressure: Backpressure::default(),
+            reranker: None,
+            audit: None,
+            metering: None,
+            readiness: Arc::new(Readiness::new()),
+            feature_flags: Arc::new(FeatureFlags::default()),
+            drain: Arc::new(DrainController::new()),
+            persist_index_graphs: false,
         }
     }
 
@@ -202,19 +453,442 @@ impl CollectionService {
         storage_config: StorageConfig,
         tiering_manager: Arc<TieringManager>,
     ) -> Self {
+        let events = EventBus::new();
+        publish_tier_changes(&tiering_manager, &events);
         Self {
             repository: Some(repository),
             vector_persistence: Some(vector_persistence),
-            collections: Arc::new(RwLock::new(HashMap::new())),
-            indexes: Arc::new(RwLock::new(HashMap::new())),
-            default_database_id: Arc::new(RwLock::new(None)),
-            storage_backends: Arc::new(RwLock::new(HashMap::new())),
+            collections: Arc::new(TrackedRwLock::new("collections", HashMap::new())),
+            indexes: Arc::new(TrackedRwLock::new("indexes", HashMap::new())),
+            default_database_id: Arc::new(TrackedRwLock::new("default_database_id", None)),
+            storage_backends: Arc::new(TrackedRwLock::new("storage_backends", HashMap::new())),
             storage_config,
             start_time: Instant::now(),
             tiering_manager: Some(tiering_manager),
+            limits: LimitsConfig::default(),
+            events,
+            jobs: Arc::new(JobManager::new()),
+            aliases: Arc::new(CollectionAliases::new()),
+            reindexing: Arc::new(TrackedRwLock::new("reindexing", HashSet::new())),
+            idempotency: Arc::new(IdempotencyStore::default()),
+            backpressure: Backpressure::default(),
+            reranker: None,
+            audit: None,
+            metering: None,
+            readiness: Arc::new(Readiness::new()),
+            feature_flags: Arc::new(FeatureFlags::default()),
+            drain: Arc::new(DrainController::new()),
+            persist_index_graphs: false,
+        }
+    }
+
+    /// Attaches a hot/warm/cold tiering manager (builder pattern).
+    pub fn with_tiering_manager(mut self, tiering_manager: Arc<TieringManager>) -> Self {
+        publish_tier_changes(&tiering_manager, &self.events);
+        self.tiering_manager = Some(tiering_manager);
+        self
+    }
+
+    /// Sets where per-collection WALs and snapshots live (builder pattern).
+    ///
+    /// In-memory services default to `./collections` under the working
+    /// directory; tests point this at a temporary directory instead.
+    pub fn with_storage_config(mut self, storage_config: StorageConfig) -> Self {
+        self.storage_config = storage_config;
+        self
+    }
+
+    /// Sets the request size and payload limits (builder pattern).
+    pub fn with_limits(mut self, limits: LimitsConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Persists the HNSW graphs of large collections (builder pattern).
+    ///
+    /// Large collections then use the native [`HnswIndex`], whose graph is
+    /// saved on snapshot, compaction and shutdown and restored by
+    /// `load_collection` instead of being rebuilt.
+    pub fn with_index_graph_persistence(mut self, config: &HnswConfig) -> Self {
+        self.persist_index_graphs = config.persist_graph;
+        self
+    }
+
+    /// Request size and payload limits for the API layers.
+    pub fn limits(&self) -> &LimitsConfig {
+        &self.limits
+    }
+
+    /// Sets the idempotency retention window and capacity (builder pattern).
+    pub fn with_idempotency(mut self, config: &IdempotencyConfig) -> Self {
+        self.idempotency = Arc::new(IdempotencyStore::from_config(config));
+        self
+    }
+
+    /// Store of responses replayed for retried requests.
+    pub fn idempotency(&self) -> &Arc<IdempotencyStore> {
+        &self.idempotency
+    }
+
+    /// Sets the queue-depth thresholds for admitting writes (builder pattern).
+    pub fn with_backpressure(mut self, config: &BackpressureConfig) -> Self {
+        self.backpressure = Backpressure::new(config.clone());
+        self
+    }
+
+    /// Checks whether the storage upload queues can take another write.
+    ///
+    /// The API layers call this before inserts and reject the request with the
+    /// returned [`Overload`] instead of queueing uploads that would end up in
+    /// the dead letter queue.
+    pub async fn check_write_backpressure(&self) -> Result<(), Overload> {
+        let backends = self.storage_backends.read().await;
+        let (uploads, retries) = backends.values().fold((0, 0), |(uploads, retries), b| {
+            (uploads + b.pending_uploads(), retries + b.pending_retries())
+        });
+        self.backpressure.check_writes(uploads, retries)
+    }
+
+    /// Storage queue depths of every collection with a storage backend.
+    pub async fn queue_depths(&self) -> Vec<CollectionQueueDepths> {
+        let backends = self.storage_backends.read().await;
+        let mut depths: Vec<CollectionQueueDepths> = backends
+            .iter()
+            .map(|(collection_id, backend)| CollectionQueueDepths {
+                collection_id: *collection_id,
+                pending_uploads: backend.pending_uploads(),
+                pending_retries: backend.pending_retries(),
+                dead_letters: backend.metrics().dlq_size,
+            })
+            .collect();
+        depths.sort_by_key(|depth| depth.collection_id.to_string());
+        depths
+    }
+
+    /// Wait counters of the service's shared locks.
+    pub fn lock_stats(&self) -> Vec<LockStats> {
+        vec![
+            self.collections.stats(),
+            self.indexes.stats(),
+            self.default_database_id.stats(),
+            self.storage_backends.stats(),
+        ]
+    }
+
+    /// Runs a query against the metadata database.
+    pub async fn probe_database(&self) -> ProbeResult {
+        match &self.vector_persistence {
+            Some(persistence) => ProbeResult::run(persistence.ping()).await,
+            None => ProbeResult::skipped("in-memory mode, no database"),
+        }
+    }
+
+    /// Checks the bucket behind the collections' object store.
+    ///
+    /// All collections share one bucket, so probing any backend's store is
+    /// enough.
+    pub async fn probe_object_store(&self) -> ProbeResult {
+        let backend = self.storage_backends.read().await.values().next().cloned();
+        let Some(backend) = backend else {
+            return ProbeResult::skipped("no collection storage is open");
+        };
+        match backend.object_store() {
+            Some(store) => ProbeResult::run(async move { store.check().await }).await,
+            None => ProbeResult::skipped("tiering policy does not use an object store"),
+        }
+    }
+
+    /// Writes, syncs and removes a file in the directory holding the
+    /// collections' WALs.
+    pub async fn probe_wal_dir(&self) -> ProbeResult {
+        static PROBES: AtomicU64 = AtomicU64::new(0);
+
+        if self.vector_persistence.is_none() {
+            return ProbeResult::skipped("in-memory mode, no WAL");
+        }
+        let dir = self
+            .storage_config
+            .wal_path
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."))
+            .join("collections");
+        let probe = dir.join(format!(
+            ".health-probe-{}-{}",
+            std::process::id(),
+            PROBES.fetch_add(1, Ordering::Relaxed)
+        ));
+        ProbeResult::run(async move {
+            tokio::fs::create_dir_all(&dir).await?;
+            let mut file = tokio::fs::File::create(&probe).await?;
+            file.write_all(b"ok").await?;
+            file.sync_all().await?;
+            drop(file);
+            tokio::fs::remove_file(&probe).await
+        })
+        .await
+    }
+
+    /// Enables the search rerank stage (builder pattern).
+    pub fn with_reranker(mut self, reranker: Arc<Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
+    /// Reranker used by searches that request reranking, if configured.
+    pub fn reranker(&self) -> Option<&Arc<Reranker>> {
+        self.reranker.as_ref()
+    }
+
+    /// Records vector writes and admin actions to `audit` (builder pattern).
+    pub fn with_audit(mut self, audit: Arc<AuditTrail>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Records a vector write on `collection_id` if auditing is configured.
+    async fn audit_write(
+        &self,
+        collection_id: CollectionId,
+        action: Action,
+        metadata: serde_json::Value,
+    ) {
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        if let Some(database_id) = self.database_of(collection_id).await {
+            audit
+                .record_write(database_id, collection_id, action, metadata)
+                .await;
+        }
+    }
+
+    /// Counts per-tenant usage for billing (builder pattern).
+    pub fn with_metering(mut self, meter: Arc<UsageMeter>) -> Self {
+        self.metering = Some(meter);
+        self
+    }
+
+    /// Usage meter, if metering is enabled.
+    pub fn usage_meter(&self) -> Option<&Arc<UsageMeter>> {
+        self.metering.as_ref()
+    }
+
+    /// Counts `tokens` embedded for `tenant_id` (the default database's
+    /// tenant if `None`) when metering is enabled.
+    pub async fn record_embedding_usage(&self, tenant_id: Option<TenantId>, tokens: u64) {
+        let Some(meter) = &self.metering else {
+            return;
+        };
+        let tenant_id = match tenant_id {
+            Some(tenant_id) => Some(tenant_id),
+            None => match *self.default_database_id.read().await {
+                Some(database_id) => meter.tenant_of(database_id).await,
+                None => None,
+            },
+        };
+        if let Some(tenant_id) = tenant_id {
+            meter.record_embedding_tokens(tenant_id, tokens);
+        }
+    }
+
+    /// Samples stored bytes per tenant and writes pending usage to the
+    /// metadata database. Does nothing when metering is disabled.
+    pub async fn flush_usage(&self) -> CoreResult<()> {
+        let Some(meter) = &self.metering else {
+            return Ok(());
+        };
+
+        let collections: Vec<(CollectionId, DatabaseId, u64)> = self
+            .collections
+            .read()
+            .await
+            .values()
+            .map(|c| (c.collection_id, c.database_id, u64::from(c.dimension)))
+            .collect();
+        let mut bytes_by_database: HashMap<DatabaseId, u64> = HashMap::new();
+        for (collection_id, database_id, dimension) in collections {
+            // Unloaded collections are not counted
+            let Ok(count) = self.get_count(collection_id).await else {
+                continue;
+            };
+            let bytes = count as u64 * dimension * std::mem::size_of::<f32>() as u64;
+            *bytes_by_database.entry(database_id).or_default() += bytes;
+        }
+        meter.record_stored_bytes(bytes_by_database).await;
+
+        meter.flush().await
+    }
+
+    async fn meter_queries(&self, collection_id: CollectionId) {
+        if let Some(meter) = &self.metering {
+            if let Some(database_id) = self.database_of(collection_id).await {
+                meter.record_queries(database_id, 1).await;
+            }
+        }
+    }
+
+    async fn meter_inserts(&self, collection_id: CollectionId, count: u64) {
+        if let Some(meter) = &self.metering {
+            if let Some(database_id) = self.database_of(collection_id).await {
+                meter.record_inserts(database_id, count).await;
+            }
+        }
+    }
+
+    /// Evaluates runtime feature flags per tenant (builder pattern).
+    pub fn with_feature_flags(mut self, flags: Arc<FeatureFlags>) -> Self {
+        self.feature_flags = flags;
+        self
+    }
+
+    /// Runtime feature flags.
+    pub fn feature_flags(&self) -> &Arc<FeatureFlags> {
+        &self.feature_flags
+    }
+
+    /// Whether `flag` is on for the tenant owning `collection_id`.
+    pub async fn flag_enabled(&self, flag: &str, collection_id: CollectionId) -> bool {
+        match self.database_of(collection_id).await {
+            Some(database_id) => {
+                self.feature_flags
+                    .is_enabled_for_database(flag, database_id)
+                    .await
+            }
+            None => self.feature_flags.is_enabled(flag, None),
+        }
+    }
+
+    /// Records an access for tiering unless the `tiering` flag is off for
+    /// the collection's tenant.
+    async fn record_tier_access(&self, collection_id: CollectionId) {
+        let Some(tiering_manager) = &self.tiering_manager else {
+            return;
+        };
+        if self.flag_enabled(feature_flags::TIERING, collection_id).await {
+            // Ignore errors from access tracking (non-critical)
+            let _ = tiering_manager.record_access(collection_id).await;
+        }
+    }
+
+    async fn database_of(&self, collection_id: CollectionId) -> Option<DatabaseId> {
+        self.collections
+            .read()
+            .await
+            .get(&collection_id)
+            .map(|collection| collection.database_id)
+    }
+
+    /// Persists background job state to `repository` (builder pattern).
+    pub fn with_job_repository(mut self, repository: Arc<dyn akidb_core::JobRepository>) -> Self {
+        self.jobs = Arc::new(JobManager::with_repository(repository));
+        self
+    }
+
+    /// Background job manager.
+    pub fn jobs(&self) -> &Arc<JobManager> {
+        &self.jobs
+    }
+
+    /// Persists collection aliases to `repository` (builder pattern).
+    pub fn with_alias_repository(
+        mut self,
+        repository: Arc<dyn akidb_core::AliasRepository>,
+    ) -> Self {
+        self.aliases = Arc::new(CollectionAliases::with_repository(repository));
+        self
+    }
+
+    /// Collection alias registry.
+    pub fn aliases(&self) -> &Arc<CollectionAliases> {
+        &self.aliases
+    }
+
+    /// Resolves a collection ID or an alias of the default database to a
+    /// collection ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if `id_or_alias` is neither a collection ID nor a
+    /// known alias.
+    pub async fn resolve_collection(&self, id_or_alias: &str) -> CoreResult<CollectionId> {
+        if let Ok(collection_id) = CollectionId::from_str(id_or_alias) {
+            return Ok(collection_id);
+        }
+        let database_id = (*self.default_database_id.read().await)
+            .ok_or_else(|| CoreError::not_found("Collection", id_or_alias))?;
+        self.aliases
+            .get(database_id, id_or_alias)
+            .await?
+            .map(|alias| alias.collection_id)
+            .ok_or_else(|| CoreError::not_found("Collection", id_or_alias))
+    }
+
+    /// Points the alias `name` at `collection_id`, creating the alias or
+    /// atomically swapping its target.
+    ///
+    /// The alias belongs to the collection's database.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` for an unknown collection and `ValidationError`
+    /// for an invalid alias name.
+    pub async fn set_alias(
+        &self,
+        name: &str,
+        collection_id: CollectionId,
+    ) -> CoreResult<CollectionAlias> {
+        let database_id = self
+            .database_of(collection_id)
+            .await
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+        let (alias, previous) = self.aliases.set(database_id, name, collection_id).await?;
+
+        if let Some(audit) = &self.audit {
+            audit
+                .record_admin(
+                    database_id,
+                    Some(collection_id),
+                    Action::CollectionUpdate,
+                    serde_json::json!({
+                        "alias": name,
+                        "previous_collection_id": previous.map(|id| id.to_string()),
+                    }),
+                )
+                .await;
+        }
+        Ok(alias)
+    }
+
+    /// Lists the aliases of the default database.
+    pub async fn list_aliases(&self) -> CoreResult<Vec<CollectionAlias>> {
+        match *self.default_database_id.read().await {
+            Some(database_id) => self.aliases.list(database_id).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Fetches an alias of the default database.
+    pub async fn get_alias(&self, name: &str) -> CoreResult<Option<CollectionAlias>> {
+        match *self.default_database_id.read().await {
+            Some(database_id) => self.aliases.get(database_id, name).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Deletes an alias of the default database; returns `false` if it did
+    /// not exist.
+    pub async fn remove_alias(&self, name: &str) -> CoreResult<bool> {
+        match *self.default_database_id.read().await {
+            Some(database_id) => self.aliases.remove(database_id, name).await,
+            None => Ok(false),
         }
     }
 
+    /// Change event bus (insert/delete/tier-change notifications).
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
     /// Gets a reference to the tiering manager (if enabled).
     /// (Phase 10 Week 3: Tiering manager integration).
     pub fn tiering_manager(&self) -> Option<Arc<TieringManager>> {
@@ -271,6 +945,12 @@ impl CollectionService {
             aggregated.dlq_size = aggregated
                 .dlq_size
                 .saturating_add(backend_metrics.dlq_size);
+            aggregated.gc_objects_scanned = aggregated
+                .gc_objects_scanned
+                .saturating_add(backend_metrics.gc_objects_scanned);
+            aggregated.gc_objects_deleted = aggregated
+                .gc_objects_deleted
+                .saturating_add(backend_metrics.gc_objects_deleted);
 
             // Take the highest error rate and breaker state across all backends
             if backend_metrics.circuit_breaker_error_rate > aggregated.circuit_breaker_error_rate {
@@ -306,8 +986,9 @@ impl CollectionService {
         *default_db = Some(database_id);
     }
 
-    /// Get the default database_id, or create a new one if not set (for in-memory mode).
-    async fn get_or_create_database_id(&self) -> DatabaseId {
+    /// Database that collections created without a tenant go into (a fresh ID
+    /// in in-memory mode).
+    pub async fn default_database_id(&self) -> DatabaseId {
         let default_db = self.default_database_id.read().await;
         match *default_db {
             Some(id) => id,
@@ -374,6 +1055,7 @@ impl CollectionService {
 
         // Load all collections from SQLite
         let descriptors = repo.list_all().await?;
+        self.readiness.loading(descriptors.len());
 
         // Populate cache and load indexes
         for descriptor in descriptors {
@@ -388,12 +1070,59 @@ impl CollectionService {
                     descriptor.collection_id,
                     e
                 );
+                self.readiness
+                    .collection_failed(descriptor.collection_id, e.to_string());
+                continue;
             }
+            let vectors = match self.indexes.read().await.get(&descriptor.collection_id) {
+                Some(index) => index.count().await.unwrap_or(0),
+                None => 0,
+            };
+            self.readiness.collection_loaded(vectors);
         }
+        self.readiness.collections_loaded();
 
         Ok(())
     }
 
+    /// Loads all collections, then waits for the object store to be
+    /// reachable, marking the service ready once both are done.
+    ///
+    /// Meant to run in the background while the server already accepts
+    /// probes: `/ready` reports not ready and `/statusz` reports progress
+    /// until this returns. The object store is re-probed every
+    /// [`OBJECT_STORE_RETRY_INTERVAL`] until it answers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and leaves the service not ready, if the collection
+    /// list cannot be read.
+    pub async fn bootstrap(&self) -> CoreResult<()> {
+        if let Err(e) = self.load_all_collections().await {
+            self.readiness.failed(e.to_string());
+            return Err(e);
+        }
+
+        loop {
+            let probe = self.probe_object_store().await;
+            let message = probe.message.clone();
+            if self.readiness.object_store_probed(probe) {
+                return Ok(());
+            }
+            tracing::warn!(
+                "Object store unreachable, retrying in {:?}: {}",
+                OBJECT_STORE_RETRY_INTERVAL,
+                message.unwrap_or_default()
+            );
+            tokio::time::sleep(OBJECT_STORE_RETRY_INTERVAL).await;
+        }
+    }
+
+    /// Bootstrap progress reported by `/statusz`.
+    pub fn readiness(&self) -> &Readiness {
+        &self.readiness
+    }
+
     /// Create a new collection.
     pub async fn create_collection(
         &self,
@@ -401,6 +1130,29 @@ impl CollectionService {
         dimension: u32,
         metric: DistanceMetric,
         embedding_model: Option<String>,
+    ) -> CoreResult<CollectionId> {
+        // Get database_id for RC1 single-database mode
+        let database_id = self.default_database_id().await;
+        self.create_collection_in(
+            database_id,
+            name,
+            dimension,
+            metric,
+            embedding_model,
+            CollectionOptions::default(),
+        )
+        .await
+    }
+
+    /// Create a new collection in a specific database (multi-tenant mode).
+    pub async fn create_collection_in(
+        &self,
+        database_id: DatabaseId,
+        name: String,
+        dimension: u32,
+        metric: DistanceMetric,
+        embedding_model: Option<String>,
+        options: CollectionOptions,
     ) -> CoreResult<CollectionId> {
         // FIX BUG #14: Validate collection name (prevent path traversal, DoS, file system attacks)
         const MAX_COLLECTION_NAME_LEN: usize = 255; // File system path component limit
@@ -441,8 +1193,20 @@ impl CollectionService {
             ));
         }
 
-        // Validate dimension
-        if !(16..=4096).contains(&dimension) {
+        // Validate dimension (sparse-only collections have none)
+        if options.vector_type == VectorType::Sparse {
+            if dimension != 0 {
+                return Err(CoreError::invalid_state(format!(
+                    "sparse collections must have dimension 0, got {}",
+                    dimension
+                )));
+            }
+            if options.normalize || options.quantization != Quantization::None {
+                return Err(CoreError::ValidationError(
+                    "normalize and quantization apply to dense vectors only".to_string(),
+                ));
+            }
+        } else if !(16..=4096).contains(&dimension) {
             return Err(CoreError::invalid_state(format!(
                 "dimension must be between 16 and 4096, got {}",
                 dimension
@@ -468,9 +1232,6 @@ impl CollectionService {
             None => "none".to_string(),
         };
 
-        // Get database_id for RC1 single-database mode
-        let database_id = self.get_or_create_database_id().await;
-
         // Create collection descriptor
         let collection_id = CollectionId::new();
         let collection = CollectionDescriptor {
@@ -479,6 +1240,9 @@ impl CollectionService {
             name: name.clone(),
             dimension,
             metric,
+            normalize: options.normalize,
+            quantization: options.quantization,
+            vector_type: options.vector_type,
             embedding_model: embedding_model_validated,
             hnsw_m: 32,
             hnsw_ef_construction: 200,
@@ -487,6 +1251,16 @@ impl CollectionService {
             updated_at: Utc::now(),
         };
 
+        self.register_collection(collection).await
+    }
+
+    /// Persist, cache and load a new collection, rolling back on failure.
+    async fn register_collection(
+        &self,
+        collection: CollectionDescriptor,
+    ) -> CoreResult<CollectionId> {
+        let collection_id = collection.collection_id;
+
         // FIX BUG #7: Atomic creation with rollback on failure
         // Use early-return pattern to ensure all steps succeed or rollback
 
@@ -552,6 +1326,18 @@ impl CollectionService {
 
     /// Delete a collection.
     pub async fn delete_collection(&self, collection_id: CollectionId) -> CoreResult<()> {
+        // Deleting an aliased collection would break every client using the alias
+        if let Some(database_id) = self.database_of(collection_id).await {
+            let aliases = self.aliases.pointing_at(database_id, collection_id).await?;
+            if !aliases.is_empty() {
+                return Err(CoreError::invalid_state(format!(
+                    "collection {} is the target of alias(es) {}; repoint or delete them first",
+                    collection_id,
+                    aliases.join(", ")
+                )));
+            }
+        }
+
         // Delete from SQLite if repository exists
         if let Some(repo) = &self.repository {
             repo.delete(collection_id).await?;
@@ -565,6 +1351,19 @@ impl CollectionService {
                 .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
         }
 
+        // The saved index graph would otherwise outlive the collection
+        if self.persist_index_graphs {
+            if let Ok(backend) = self.storage_backend(collection_id).await {
+                if let Err(e) = backend.delete_index_graph().await {
+                    tracing::warn!(
+                        "Failed to delete index graph for collection {}: {}",
+                        collection_id,
+                        e
+                    );
+                }
+            }
+        }
+
         // Unload index
         self.unload_collection(collection_id).await?;
 
@@ -592,17 +1391,34 @@ impl CollectionService {
     // ========== Vector Operations ==========
 
     /// Query vectors (k-NN search).
+    #[tracing::instrument(name = "service.query", skip_all, fields(collection_id = %collection_id, top_k))]
     pub async fn query(
         &self,
         collection_id: CollectionId,
         query_vector: Vec<f32>,
         top_k: usize,
+    ) -> CoreResult<Vec<SearchResult>> {
+        let results = self
+            .search_index(collection_id, query_vector, top_k, None)
+            .await?;
+        self.meter_queries(collection_id).await;
+        Ok(results)
+    }
+
+    /// k-NN search of the collection's index, without metering (search()
+    /// may run several passes for one request).
+    ///
+    /// A filter is pushed down into the index, which only returns matching
+    /// documents.
+    async fn search_index(
+        &self,
+        collection_id: CollectionId,
+        mut query_vector: Vec<f32>,
+        top_k: usize,
+        filter: Option<&FilterTree>,
     ) -> CoreResult<Vec<SearchResult>> {
         let start = Instant::now();
 
-        // FIX BUG #8: Validate top_k to prevent DoS via memory exhaustion
-        // Reasonable limit: 10,000 results (prevents usize::MAX attacks)
-        const MAX_TOP_K: usize = 10_000;
         if top_k == 0 {
             return Err(CoreError::ValidationError(
                 "top_k must be greater than 0".to_string(),
@@ -615,12 +1431,19 @@ impl CollectionService {
             )));
         }
 
-        // Record access for tiering (Phase 10 Week 3)
-        if let Some(tiering_manager) = &self.tiering_manager {
-            // Ignore errors from access tracking (non-critical)
-            let _ = tiering_manager.record_access(collection_id).await;
+        let normalize = self
+            .collections
+            .read()
+            .await
+            .get(&collection_id)
+            .is_some_and(|collection| collection.normalize);
+        if normalize {
+            normalize_vector(&mut query_vector)?;
         }
 
+        // Record access for tiering (Phase 10 Week 3)
+        self.record_tier_access(collection_id).await;
+
         // Get index
         let indexes = self.indexes.read().await;
         let index = indexes
@@ -628,31 +1451,357 @@ impl CollectionService {
             .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
 
         // Perform search
-        let result = index.search(&query_vector, top_k, None).await;
+        let result = match filter {
+            Some(filter) => {
+                let matches = |_: DocumentId, metadata: Option<&serde_json::Value>| {
+                    filter.matches(metadata)
+                };
+                index
+                    .search_filtered(&query_vector, top_k, None, &matches)
+                    .instrument(tracing::info_span!("index.search", k = top_k, filtered = true))
+                    .await
+            }
+            None => {
+                index
+                    .search(&query_vector, top_k, None)
+                    .instrument(tracing::info_span!("index.search", k = top_k))
+                    .await
+            }
+        };
 
         // Record metrics
         let duration = start.elapsed().as_secs_f64();
         VECTOR_SEARCH_DURATION_SECONDS
             .with_label_values(&["hot"]) // TODO: Get actual tier from TieringManager
             .observe(duration);
+        INDEX_OPERATION_DURATION_SECONDS
+            .with_label_values(&["search"])
+            .observe(duration);
 
         result
     }
 
-    /// Insert single vector.
-    pub async fn insert(
+    /// Ranks documents by the dot product of their sparse vector with `query`.
+    ///
+    /// The sparse index only returns document IDs, so each hit is read back
+    /// from the index for its payload and, with a filter, checked against it
+    /// until `top_k` matches are found.
+    async fn search_sparse_index(
         &self,
         collection_id: CollectionId,
-        doc: VectorDocument,
-    ) -> CoreResult<DocumentId> {
+        query: &SparseVector,
+        top_k: usize,
+        filter: Option<&FilterTree>,
+    ) -> CoreResult<Vec<SearchResult>> {
         let start = Instant::now();
 
-        // Record access for tiering (Phase 10 Week 3)
-        if let Some(tiering_manager) = &self.tiering_manager {
-            // Ignore errors from access tracking (non-critical)
-            let _ = tiering_manager.record_access(collection_id).await;
+        if top_k == 0 {
+            return Err(CoreError::ValidationError(
+                "top_k must be greater than 0".to_string(),
+            ));
+        }
+        if top_k > MAX_TOP_K {
+            return Err(CoreError::ValidationError(format!(
+                "top_k must be <= {} (got {})",
+                MAX_TOP_K, top_k
+            )));
+        }
+
+        // Record access for tiering (Phase 10 Week 3)
+        self.record_tier_access(collection_id).await;
+
+        let indexes = self.indexes.read().await;
+        let index = indexes
+            .get(&collection_id)
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+
+        // A filter may reject any number of hits, so rank every candidate
+        let candidates = if filter.is_some() { usize::MAX } else { top_k };
+        let hits = index.sparse_search(query, candidates)?.ok_or_else(|| {
+            CoreError::invalid_state("Collection index has no sparse index".to_string())
+        })?;
+
+        let mut results = Vec::with_capacity(top_k.min(hits.len()));
+        for (doc_id, score) in hits {
+            if results.len() == top_k {
+                break;
+            }
+            let Some(doc) = index.get(doc_id).await? else {
+                continue;
+            };
+            if !filter.map_or(true, |f| f.matches(doc.metadata.as_ref())) {
+                continue;
+            }
+            let mut result = SearchResult::new(doc_id, score);
+            if let Some(external_id) = doc.external_id {
+                result = result.with_external_id(external_id);
+            }
+            if let Some(metadata) = doc.metadata {
+                result = result.with_metadata(metadata);
+            }
+            results.push(result);
         }
 
+        let duration = start.elapsed().as_secs_f64();
+        VECTOR_SEARCH_DURATION_SECONDS
+            .with_label_values(&["hot"])
+            .observe(duration);
+        INDEX_OPERATION_DURATION_SECONDS
+            .with_label_values(&["search"])
+            .observe(duration);
+
+        Ok(results)
+    }
+
+    /// Search with metadata filtering, a score threshold, and payload options.
+    ///
+    /// Filters are pushed down into the index search, so only matching
+    /// documents are ranked and a selective filter still returns `top_k`
+    /// results when that many match. The score threshold then drops the
+    /// tail of the ranking.
+    ///
+    /// With `options.hybrid`, vector candidates collected this way are fused
+    /// with the best keyword matches (see [`crate::hybrid`]) and `score` is
+    /// the fused score, higher being better for every metric.
+    ///
+    /// With `options.rerank`, `candidates` results are collected this way and
+    /// reordered by the reranker before the best `top_k` are returned.
+    ///
+    /// With `options.sparse`, documents are ranked by the dot product of their
+    /// sparse vector with the sparse query (see [`Self::search_sparse_index`]).
+    #[tracing::instrument(name = "service.search", skip_all, fields(collection_id = %collection_id, top_k, filtered = options.filter.is_some()))]
+    pub async fn search(
+        &self,
+        collection_id: CollectionId,
+        query_vector: Vec<f32>,
+        top_k: usize,
+        options: &SearchOptions,
+    ) -> CoreResult<Vec<SearchResult>> {
+        let start = Instant::now();
+        let reranker = match &options.rerank {
+            Some(_) => Some(self.reranker.as_ref().ok_or_else(|| {
+                CoreError::ValidationError("Reranking is not enabled on this server".to_string())
+            })?),
+            None => None,
+        };
+        let collection = self.get_collection(collection_id).await?;
+        validate_query(collection.vector_type, &query_vector, options)?;
+        if let Some(hybrid) = &options.hybrid {
+            self.validate_hybrid(collection_id, hybrid, options).await?;
+        }
+        let wanted = options
+            .rerank
+            .as_ref()
+            .map_or(top_k, |rerank| rerank.candidates.max(top_k));
+        // Hybrid search fuses this many vector candidates
+        let fetch_wanted = options
+            .hybrid
+            .as_ref()
+            .map_or(wanted, |hybrid| hybrid.candidates.max(wanted));
+        // Sparse queries are scored by dot product
+        let metric = if options.sparse.is_some() {
+            DistanceMetric::Dot
+        } else {
+            collection.metric
+        };
+        let passes_threshold = |score: f32| match (options.score_threshold, metric) {
+            (None, _) => true,
+            (Some(threshold), DistanceMetric::L2) => score <= threshold,
+            (Some(threshold), DistanceMetric::Cosine | DistanceMetric::Dot) => score >= threshold,
+        };
+
+        let mut results = match &options.sparse {
+            Some(sparse) => {
+                self.search_sparse_index(
+                    collection_id,
+                    sparse,
+                    fetch_wanted,
+                    options.filter.as_ref(),
+                )
+                .await?
+            }
+            None => {
+                self.search_index(
+                    collection_id,
+                    query_vector.clone(),
+                    fetch_wanted,
+                    options.filter.as_ref(),
+                )
+                .await?
+            }
+        };
+        results.retain(|r| passes_threshold(r.score));
+
+        if let Some(hybrid) = &options.hybrid {
+            results = self
+                .fuse_keyword_matches(collection_id, &query_vector, results, hybrid, options)
+                .instrument(tracing::info_span!("hybrid", candidates = fetch_wanted))
+                .await?;
+            results.truncate(wanted);
+        }
+
+        if let (Some(reranker), Some(rerank)) = (reranker, &options.rerank) {
+            results = reranker
+                .rerank_results(&rerank.query, &rerank.text_field, results)
+                .instrument(tracing::info_span!("rerank", candidates = wanted))
+                .await?;
+            results.truncate(top_k);
+        }
+
+        if options.include_vector {
+            let indexes = self.indexes.read().await;
+            let index = indexes
+                .get(&collection_id)
+                .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+            for result in &mut results {
+                if let Some(doc) = index.get(result.doc_id).await? {
+                    result.vector = Some(doc.vector);
+                    result.sparse = doc.sparse;
+                }
+            }
+        }
+        if !options.include_payload {
+            for result in &mut results {
+                result.metadata = None;
+            }
+        } else if let Some(fields) = &options.payload_fields {
+            for result in &mut results {
+                if let Some(serde_json::Value::Object(map)) = &mut result.metadata {
+                    map.retain(|key, _| fields.iter().any(|field| field == key));
+                }
+            }
+        }
+
+        SEARCH_DURATION_SECONDS
+            .with_label_values(&[if options.filter.is_some() {
+                "true"
+            } else {
+                "false"
+            }])
+            .observe(start.elapsed().as_secs_f64());
+        self.meter_queries(collection_id).await;
+
+        Ok(results)
+    }
+
+    /// Rejects hybrid searches that are switched off or malformed.
+    async fn validate_hybrid(
+        &self,
+        collection_id: CollectionId,
+        hybrid: &HybridOptions,
+        options: &SearchOptions,
+    ) -> CoreResult<()> {
+        if !self
+            .flag_enabled(feature_flags::HYBRID_SEARCH, collection_id)
+            .await
+        {
+            return Err(CoreError::ValidationError(
+                "Hybrid search is disabled for this tenant".to_string(),
+            ));
+        }
+        if hybrid.query.trim().is_empty() {
+            return Err(CoreError::ValidationError(
+                "hybrid query cannot be empty".to_string(),
+            ));
+        }
+        if let hybrid::Fusion::Weighted { alpha } = hybrid.fusion {
+            if !(0.0..=1.0).contains(&alpha) {
+                return Err(CoreError::ValidationError(format!(
+                    "hybrid alpha must be between 0 and 1, got {}",
+                    alpha
+                )));
+            }
+        }
+        if options.score_threshold.is_some() {
+            // Fused scores have no fixed scale to compare a threshold with
+            return Err(CoreError::ValidationError(
+                "score_threshold cannot be combined with hybrid search".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fuses vector candidates with the collection's best keyword matches.
+    ///
+    /// Keyword matches that are not vector candidates are read from the
+    /// index, filtered, and scored against the query vector.
+    async fn fuse_keyword_matches(
+        &self,
+        collection_id: CollectionId,
+        query_vector: &[f32],
+        vector_hits: Vec<SearchResult>,
+        hybrid: &HybridOptions,
+        options: &SearchOptions,
+    ) -> CoreResult<Vec<SearchResult>> {
+        let collection = self.get_collection(collection_id).await?;
+        let mut query_vector = query_vector.to_vec();
+        if collection.normalize {
+            normalize_vector(&mut query_vector)?;
+        }
+        let candidates = hybrid.candidates.max(vector_hits.len()).min(MAX_TOP_K);
+
+        let indexes = self.indexes.read().await;
+        let index = indexes
+            .get(&collection_id)
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+        let keyword_hits = index
+            .text_search(&hybrid.query, hybrid.fields.as_deref(), candidates)?
+            .ok_or_else(|| {
+                CoreError::invalid_state("Collection index has no keyword index".to_string())
+            })?;
+
+        let vector_ids: std::collections::HashSet<DocumentId> =
+            vector_hits.iter().map(|hit| hit.doc_id).collect();
+        let mut keyword_hits_kept = Vec::with_capacity(keyword_hits.len());
+        let mut keyword_only = Vec::new();
+        for (doc_id, score) in keyword_hits {
+            if vector_ids.contains(&doc_id) {
+                keyword_hits_kept.push((doc_id, score));
+                continue;
+            }
+            let Some(doc) = index.get(doc_id).await? else {
+                continue;
+            };
+            if !options
+                .filter
+                .as_ref()
+                .map_or(true, |f| f.matches(doc.metadata.as_ref()))
+            {
+                continue;
+            }
+            let mut result =
+                SearchResult::new(doc_id, collection.metric.compute(&query_vector, &doc.vector));
+            if let Some(external_id) = doc.external_id {
+                result = result.with_external_id(external_id);
+            }
+            if let Some(metadata) = doc.metadata {
+                result = result.with_metadata(metadata);
+            }
+            keyword_only.push(result);
+            keyword_hits_kept.push((doc_id, score));
+        }
+
+        Ok(hybrid::fuse(
+            hybrid.fusion,
+            collection.metric,
+            vector_hits,
+            keyword_only,
+            &keyword_hits_kept,
+        ))
+    }
+
+    /// Insert single vector.
+    #[tracing::instrument(name = "service.insert", skip_all, fields(collection_id = %collection_id))]
+    pub async fn insert(
+        &self,
+        collection_id: CollectionId,
+        mut doc: VectorDocument,
+    ) -> CoreResult<DocumentId> {
+        let start = Instant::now();
+
+        // Record access for tiering (Phase 10 Week 3)
+        self.record_tier_access(collection_id).await;
+
         // Validate vector dimension matches collection's expected dimension
         {
             let collections = self.collections.read().await;
@@ -660,14 +1809,9 @@ impl CollectionService {
                 .get(&collection_id)
                 .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
 
-            let expected_dim = collection.dimension as usize;
-            let actual_dim = doc.vector.len();
-
-            if actual_dim != expected_dim {
-                return Err(CoreError::ValidationError(format!(
-                    "Vector dimension mismatch: expected {}, got {}",
-                    expected_dim, actual_dim
-                )));
+            validate_vectors(collection.dimension as usize, collection.vector_type, &doc)?;
+            if collection.normalize {
+                normalize_vector(&mut doc.vector)?;
             }
         }
 
@@ -692,7 +1836,14 @@ impl CollectionService {
 
             // Insert into in-memory index FIRST
             // If this fails, we return error WITHOUT persisting to WAL
-            index.insert(doc.clone()).await?;
+            let index_start = Instant::now();
+            index
+                .insert(doc.clone())
+                .instrument(tracing::info_span!("index.insert"))
+                .await?;
+            INDEX_OPERATION_DURATION_SECONDS
+                .with_label_values(&["insert"])
+                .observe(index_start.elapsed().as_secs_f64());
 
             // Only persist to StorageBackend AFTER successful index insert
             // This prevents WAL/index inconsistency on index failures (Bug #1)
@@ -739,20 +1890,366 @@ impl CollectionService {
             .with_label_values(&[&collection_id.to_string()])
             .inc();
 
+        self.events.publish(ChangeEvent::new(
+            collection_id,
+            ChangeKind::Insert { doc_id },
+        ));
+
+        self.audit_write(
+            collection_id,
+            Action::DocumentInsert,
+            serde_json::json!({ "doc_id": doc_id.to_string() }),
+        )
+        .await;
+        self.meter_inserts(collection_id, 1).await;
+
         Ok(doc_id)
     }
 
+    /// Insert or replace multiple vectors.
+    ///
+    /// Both locks are taken once for the whole batch. Each document is applied
+    /// independently (index first, then WAL, with rollback on persistence
+    /// failure), so one bad document does not abort the rest. Results are in
+    /// request order.
+    pub async fn upsert_batch(
+        &self,
+        collection_id: CollectionId,
+        docs: Vec<VectorDocument>,
+    ) -> CoreResult<Vec<CoreResult<DocumentId>>> {
+        let start = Instant::now();
+
+        // Record access for tiering (Phase 10 Week 3)
+        self.record_tier_access(collection_id).await;
+
+        let (expected_dim, vector_type, normalize) = {
+            let collections = self.collections.read().await;
+            let collection = collections
+                .get(&collection_id)
+                .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+            (
+                collection.dimension as usize,
+                collection.vector_type,
+                collection.normalize,
+            )
+        };
+
+        // Same lock order as insert(): index, then WAL
+        let indexes = self.indexes.read().await;
+        let backends = self.storage_backends.read().await;
+
+        let index = indexes
+            .get(&collection_id)
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+        let storage_backend = backends.get(&collection_id);
+
+        let mut results = Vec::with_capacity(docs.len());
+        let mut inserted = 0u64;
+        for mut doc in docs {
+            let doc_id = doc.doc_id;
+
+            if let Err(e) = validate_vectors(expected_dim, vector_type, &doc) {
+                results.push(Err(e));
+                continue;
+            }
+            if normalize {
+                if let Err(e) = normalize_vector(&mut doc.vector) {
+                    results.push(Err(e));
+                    continue;
+                }
+            }
+
+            // Replace: remove the previous version from the index, keeping it
+            // around to restore if the new version cannot be applied
+            let previous = index.get(doc_id).await?;
+            if previous.is_some() {
+                index.delete(doc_id).await?;
+            }
+
+            let restore = |previous: Option<VectorDocument>| async {
+                if let Some(previous) = previous {
+                    if let Err(e) = index.insert(previous).await {
+                        tracing::error!(
+                            "Failed to restore doc {} after failed upsert: {}. Index may be inconsistent.",
+                            doc_id, e
+                        );
+                    }
+                }
+            };
+
+            if let Err(e) = index.insert(doc.clone()).await {
+                restore(previous).await;
+                results.push(Err(e));
+                continue;
+            }
+
+            let persisted = if let Some(storage_backend) = storage_backend {
+                storage_backend.insert_with_auto_compact(doc).await
+            } else if let Some(persistence) = &self.vector_persistence {
+                // Fallback: Legacy persistence (Phase 5 compatibility)
+                persistence.save_vector(collection_id, &doc).await
+            } else {
+                Ok(())
+            };
+
+            if let Err(e) = persisted {
+                if let Err(rollback_err) = index.delete(doc_id).await {
+                    tracing::error!(
+                        "Failed to rollback index upsert after persistence failure for doc {}: {}. Index may be inconsistent.",
+                        doc_id, rollback_err
+                    );
+                }
+                restore(previous).await;
+                results.push(Err(e));
+                continue;
+            }
+
+            if previous.is_none() {
+                inserted += 1;
+            }
+            self.events.publish(ChangeEvent::new(
+                collection_id,
+                ChangeKind::Insert { doc_id },
+            ));
+            results.push(Ok(doc_id));
+        }
+
+        // Record metrics
+        let duration = start.elapsed().as_secs_f64();
+        VECTOR_INSERT_DURATION_SECONDS
+            .with_label_values(&[&collection_id.to_string()])
+            .observe(duration);
+
+        COLLECTION_SIZE_VECTORS
+            .with_label_values(&[&collection_id.to_string()])
+            .add(inserted as f64);
+
+        drop(backends);
+        drop(indexes);
+        self.audit_write(
+            collection_id,
+            Action::DocumentUpdate,
+            serde_json::json!({
+                "count": results.len(),
+                "succeeded": inserted,
+            }),
+        )
+        .await;
+        self.meter_inserts(collection_id, inserted).await;
+
+        Ok(results)
+    }
+
+    /// Insert multiple new vectors.
+    ///
+    /// Each document is validated on its own: a dimension mismatch, a
+    /// non-finite component, or an ID that already exists (or repeats within
+    /// the batch) fails only that document. The remaining documents go into
+    /// the index with one `insert_batch` call and into the WAL with one batch
+    /// append and a single flush. Results are in request order.
+    ///
+    /// Applying the valid documents is all-or-nothing: if the index or WAL
+    /// batch fails, they are removed from the index again and the error is
+    /// returned for the whole call.
+    #[tracing::instrument(name = "service.insert_batch", skip_all, fields(collection_id = %collection_id, count = docs.len()))]
+    pub async fn insert_batch(
+        &self,
+        collection_id: CollectionId,
+        docs: Vec<VectorDocument>,
+    ) -> CoreResult<Vec<CoreResult<DocumentId>>> {
+        let start = Instant::now();
+
+        // Record access for tiering (Phase 10 Week 3)
+        self.record_tier_access(collection_id).await;
+
+        let (expected_dim, vector_type, normalize) = {
+            let collections = self.collections.read().await;
+            let collection = collections
+                .get(&collection_id)
+                .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+            (
+                collection.dimension as usize,
+                collection.vector_type,
+                collection.normalize,
+            )
+        };
+
+        // Same lock order as insert(): index, then WAL
+        let indexes = self.indexes.read().await;
+        let backends = self.storage_backends.read().await;
+
+        let index = indexes
+            .get(&collection_id)
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+
+        let mut results = Vec::with_capacity(docs.len());
+        let mut seen = std::collections::HashSet::with_capacity(docs.len());
+        let mut valid = Vec::with_capacity(docs.len());
+        for mut doc in docs {
+            let doc_id = doc.doc_id;
+
+            if let Err(e) = validate_vectors(expected_dim, vector_type, &doc) {
+                results.push(Err(e));
+                continue;
+            }
+            if doc.vector.iter().any(|x| !x.is_finite()) {
+                results.push(Err(CoreError::ValidationError(
+                    "Vector contains non-finite values".to_string(),
+                )));
+                continue;
+            }
+            if normalize {
+                if let Err(e) = normalize_vector(&mut doc.vector) {
+                    results.push(Err(e));
+                    continue;
+                }
+            }
+            if !seen.insert(doc_id) || index.get(doc_id).await?.is_some() {
+                results.push(Err(CoreError::already_exists(
+                    "Document",
+                    doc_id.to_string(),
+                )));
+                continue;
+            }
+
+            results.push(Ok(doc_id));
+            valid.push(doc);
+        }
+
+        let doc_ids: Vec<DocumentId> = valid.iter().map(|doc| doc.doc_id).collect();
+        let rollback = |e: CoreError| async {
+            for doc_id in &doc_ids {
+                if let Err(rollback_err) = index.delete(*doc_id).await {
+                    if !matches!(rollback_err, CoreError::NotFound { .. }) {
+                        tracing::error!(
+                            "Failed to rollback index insert after batch failure for doc {}: {}. Index may be inconsistent.",
+                            doc_id, rollback_err
+                        );
+                    }
+                }
+            }
+            e
+        };
+
+        if !valid.is_empty() {
+            let index_start = Instant::now();
+            if let Err(e) = index
+                .insert_batch(valid.clone())
+                .instrument(tracing::info_span!("index.insert_batch"))
+                .await
+            {
+                return Err(rollback(e).await);
+            }
+            INDEX_OPERATION_DURATION_SECONDS
+                .with_label_values(&["insert_batch"])
+                .observe(index_start.elapsed().as_secs_f64());
+
+            let persisted = if let Some(storage_backend) = backends.get(&collection_id) {
+                storage_backend.insert_batch_with_auto_compact(valid).await
+            } else if let Some(persistence) = &self.vector_persistence {
+                // Fallback: Legacy persistence (Phase 5 compatibility)
+                let mut persisted = Ok(());
+                for doc in &valid {
+                    persisted = persistence.save_vector(collection_id, doc).await;
+                    if persisted.is_err() {
+                        break;
+                    }
+                }
+                persisted
+            } else {
+                Ok(())
+            };
+            if let Err(e) = persisted {
+                return Err(rollback(e).await);
+            }
+        }
+
+        // Record metrics
+        let duration = start.elapsed().as_secs_f64();
+        VECTOR_INSERT_DURATION_SECONDS
+            .with_label_values(&[&collection_id.to_string()])
+            .observe(duration);
+
+        let inserted = doc_ids.len() as u64;
+        COLLECTION_SIZE_VECTORS
+            .with_label_values(&[&collection_id.to_string()])
+            .add(inserted as f64);
+
+        for doc_id in &doc_ids {
+            self.events.publish(ChangeEvent::new(
+                collection_id,
+                ChangeKind::Insert { doc_id: *doc_id },
+            ));
+        }
+
+        drop(backends);
+        drop(indexes);
+        self.audit_write(
+            collection_id,
+            Action::DocumentInsert,
+            serde_json::json!({
+                "count": results.len(),
+                "succeeded": inserted,
+            }),
+        )
+        .await;
+        self.meter_inserts(collection_id, inserted).await;
+
+        Ok(results)
+    }
+
+    /// Insert or replace the document with the given external ID.
+    ///
+    /// An existing document keeps its ID and has its vector and metadata
+    /// replaced; otherwise a document with a new ID is created. Returns the
+    /// document ID and whether it was created.
+    ///
+    /// The lookup uses the collection's external ID index (see
+    /// [`resolve_external_ids`](Self::resolve_external_ids)). Upserts by
+    /// external ID are serialized per collection so that concurrent calls for
+    /// an unknown ID create a single document.
+    pub async fn upsert_by_external_id(
+        &self,
+        collection_id: CollectionId,
+        external_id: String,
+        vector: Vec<f32>,
+        metadata: Option<serde_json::Value>,
+    ) -> CoreResult<(DocumentId, bool)> {
+        if external_id.is_empty() {
+            return Err(CoreError::ValidationError(
+                "external_id cannot be empty".to_string(),
+            ));
+        }
+
+        let backend = self.storage_backend(collection_id).await?;
+        let _serialized = backend.lock_external_ids().await;
+        let existing = backend
+            .resolve_external_ids(std::slice::from_ref(&external_id))
+            .remove(&external_id);
+
+        let mut doc = VectorDocument::new(existing.unwrap_or_else(DocumentId::new), vector)
+            .with_external_id(external_id);
+        if let Some(metadata) = metadata {
+            doc = doc.with_metadata(metadata);
+        }
+        let doc_id = self
+            .upsert_batch(collection_id, vec![doc])
+            .await?
+            .pop()
+            .unwrap_or_else(|| Err(CoreError::internal("upsert returned no result")))?;
+
+        Ok((doc_id, existing.is_none()))
+    }
+
     /// Get vector by ID.
+    #[tracing::instrument(name = "service.get", skip_all, fields(collection_id = %collection_id))]
     pub async fn get(
         &self,
         collection_id: CollectionId,
         doc_id: DocumentId,
     ) -> CoreResult<Option<VectorDocument>> {
         // Record access for tiering (Phase 10 Week 3)
-        if let Some(tiering_manager) = &self.tiering_manager {
-            // Ignore errors from access tracking (non-critical)
-            let _ = tiering_manager.record_access(collection_id).await;
-        }
+        self.record_tier_access(collection_id).await;
 
         let indexes = self.indexes.read().await;
         let index = indexes
@@ -763,12 +2260,10 @@ impl CollectionService {
     }
 
     /// Delete vector by ID.
+    #[tracing::instrument(name = "service.delete", skip_all, fields(collection_id = %collection_id))]
     pub async fn delete(&self, collection_id: CollectionId, doc_id: DocumentId) -> CoreResult<()> {
         // Record access for tiering (Phase 10 Week 3)
-        if let Some(tiering_manager) = &self.tiering_manager {
-            // Ignore errors from access tracking (non-critical)
-            let _ = tiering_manager.record_access(collection_id).await;
-        }
+        self.record_tier_access(collection_id).await;
 
         // FIX BUG #6: Delete from WAL first, then index
         // Hold BOTH locks simultaneously to prevent collection deletion race condition
@@ -783,46 +2278,228 @@ impl CollectionService {
             let backends = self.storage_backends.read().await;
             let indexes = self.indexes.read().await;
 
-            // Delete from WAL-backed storage FIRST (durability first)
-            if let Some(storage_backend) = backends.get(&collection_id) {
-                storage_backend.delete(&doc_id).await?;
-            } else {
-                // Fallback: Legacy persistence (Phase 5 compatibility)
-                if let Some(persistence) = &self.vector_persistence {
-                    persistence.delete_vector(collection_id, doc_id).await?;
-                }
+            // Delete from WAL-backed storage FIRST (durability first)
+            if let Some(storage_backend) = backends.get(&collection_id) {
+                storage_backend.delete(&doc_id).await?;
+            } else {
+                // Fallback: Legacy persistence (Phase 5 compatibility)
+                if let Some(persistence) = &self.vector_persistence {
+                    persistence.delete_vector(collection_id, doc_id).await?;
+                }
+            }
+
+            // Delete from index AFTER successful WAL delete
+            let index = indexes
+                .get(&collection_id)
+                .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+
+            let index_start = Instant::now();
+            index
+                .delete(doc_id)
+                .instrument(tracing::info_span!("index.delete"))
+                .await?;
+            INDEX_OPERATION_DURATION_SECONDS
+                .with_label_values(&["delete"])
+                .observe(index_start.elapsed().as_secs_f64());
+
+            // Both locks released here - collection cannot be deleted during delete operation
+        }
+
+        self.events.publish(ChangeEvent::new(
+            collection_id,
+            ChangeKind::Delete { doc_id },
+        ));
+
+        self.audit_write(
+            collection_id,
+            Action::DocumentDelete,
+            serde_json::json!({ "doc_id": doc_id.to_string() }),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Delete multiple vectors by ID.
+    ///
+    /// Both locks are taken once for the whole batch. Each document is deleted
+    /// independently, so a missing or failing document does not abort the rest;
+    /// the returned statuses are in request order.
+    #[tracing::instrument(name = "service.delete_batch", skip_all, fields(collection_id = %collection_id, count = doc_ids.len()))]
+    pub async fn delete_batch(
+        &self,
+        collection_id: CollectionId,
+        doc_ids: Vec<DocumentId>,
+    ) -> CoreResult<Vec<(DocumentId, BatchDeleteStatus)>> {
+        // Record access for tiering (Phase 10 Week 3)
+        self.record_tier_access(collection_id).await;
+
+        // Same lock order as delete(): WAL first, then index
+        let backends = self.storage_backends.read().await;
+        let indexes = self.indexes.read().await;
+
+        let index = indexes
+            .get(&collection_id)
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+        let storage_backend = backends.get(&collection_id);
+        let statuses = self
+            .delete_locked(collection_id, index.as_ref(), storage_backend, doc_ids)
+            .await?;
+
+        drop(indexes);
+        drop(backends);
+        self.audit_deletes(collection_id, &statuses, false).await;
+
+        Ok(statuses)
+    }
+
+    /// Delete every document whose metadata matches `filter`.
+    ///
+    /// The collection is scanned and the matches deleted under the same locks
+    /// as [`delete_batch`](Self::delete_batch), so a collection delete cannot
+    /// interleave. Documents are removed from the WAL (or legacy persistence)
+    /// before the index; the returned statuses list every matched document.
+    ///
+    /// Collections using the S3Only tiering policy are rejected with
+    /// `CoreError::InvalidState`: their storage backend only holds a cache of
+    /// the documents, so the scan would miss the rest.
+    #[tracing::instrument(name = "service.delete_by_filter", skip_all, fields(collection_id = %collection_id))]
+    pub async fn delete_by_filter(
+        &self,
+        collection_id: CollectionId,
+        filter: &FilterTree,
+    ) -> CoreResult<Vec<(DocumentId, BatchDeleteStatus)>> {
+        // Record access for tiering (Phase 10 Week 3)
+        self.record_tier_access(collection_id).await;
+
+        // Same lock order as delete(): WAL first, then index
+        let backends = self.storage_backends.read().await;
+        let indexes = self.indexes.read().await;
+
+        let index = indexes
+            .get(&collection_id)
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+        let storage_backend = backends.get(&collection_id);
+
+        let docs = match (storage_backend, &self.vector_persistence) {
+            (Some(storage_backend), _)
+                if storage_backend.config().tiering_policy == TieringPolicy::S3Only =>
+            {
+                return Err(CoreError::invalid_state(
+                    "delete by filter is not supported with the S3Only tiering policy",
+                ));
             }
+            (Some(storage_backend), _) => storage_backend.all_vectors(),
+            // Fallback: Legacy persistence (Phase 5 compatibility)
+            (None, Some(persistence)) => persistence.load_all_vectors(collection_id).await?,
+            (None, None) => Vec::new(),
+        };
+        let doc_ids = docs
+            .into_iter()
+            .filter(|doc| filter.matches(doc.metadata.as_ref()))
+            .map(|doc| doc.doc_id)
+            .collect();
+        let statuses = self
+            .delete_locked(collection_id, index.as_ref(), storage_backend, doc_ids)
+            .await?;
+
+        drop(indexes);
+        drop(backends);
+        self.audit_deletes(collection_id, &statuses, true).await;
+
+        Ok(statuses)
+    }
 
-            // Delete from index AFTER successful WAL delete
-            let index = indexes
-                .get(&collection_id)
-                .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+    /// Delete documents with the collection's WAL and index locks held.
+    async fn delete_locked(
+        &self,
+        collection_id: CollectionId,
+        index: &dyn VectorIndex,
+        storage_backend: Option<&Arc<StorageBackend>>,
+        doc_ids: Vec<DocumentId>,
+    ) -> CoreResult<Vec<(DocumentId, BatchDeleteStatus)>> {
+        let mut statuses = Vec::with_capacity(doc_ids.len());
+        for doc_id in doc_ids {
+            // Skip WAL writes for documents that were never inserted
+            if index.get(doc_id).await?.is_none() {
+                statuses.push((doc_id, BatchDeleteStatus::NotFound));
+                continue;
+            }
 
-            index.delete(doc_id).await?;
+            let persisted = if let Some(storage_backend) = storage_backend {
+                storage_backend.delete(&doc_id).await
+            } else if let Some(persistence) = &self.vector_persistence {
+                // Fallback: Legacy persistence (Phase 5 compatibility)
+                persistence.delete_vector(collection_id, doc_id).await
+            } else {
+                Ok(())
+            };
+
+            // Only touch the index once the delete is durable
+            let result = match persisted {
+                Ok(()) => index.delete(doc_id).await,
+                Err(e) => Err(e),
+            };
+
+            let status = match result {
+                Ok(()) => {
+                    self.events.publish(ChangeEvent::new(
+                        collection_id,
+                        ChangeKind::Delete { doc_id },
+                    ));
+                    BatchDeleteStatus::Deleted
+                }
+                Err(CoreError::NotFound { .. }) => BatchDeleteStatus::NotFound,
+                Err(e) => BatchDeleteStatus::Failed(e.to_string()),
+            };
+            statuses.push((doc_id, status));
+        }
+        Ok(statuses)
+    }
 
-            // Both locks released here - collection cannot be deleted during delete operation
+    async fn audit_deletes(
+        &self,
+        collection_id: CollectionId,
+        statuses: &[(DocumentId, BatchDeleteStatus)],
+        by_filter: bool,
+    ) {
+        let deleted = statuses
+            .iter()
+            .filter(|(_, status)| matches!(status, BatchDeleteStatus::Deleted))
+            .count();
+        let mut details = serde_json::json!({
+            "count": statuses.len(),
+            "succeeded": deleted,
+        });
+        if by_filter {
+            details["by_filter"] = serde_json::Value::Bool(true);
         }
+        self.audit_write(collection_id, Action::DocumentDelete, details)
+            .await;
+    }
 
-        Ok(())
+    /// Resolve external IDs to document IDs.
+    ///
+    /// Looks the IDs up in the collection's external ID index, which covers
+    /// every stored document whatever the tiering policy. Unknown external
+    /// IDs are omitted from the result.
+    pub async fn resolve_external_ids(
+        &self,
+        collection_id: CollectionId,
+        external_ids: &[String],
+    ) -> CoreResult<HashMap<String, DocumentId>> {
+        Ok(self
+            .storage_backend(collection_id)
+            .await?
+            .resolve_external_ids(external_ids))
     }
 
     /// Load collection into memory (called on startup or creation).
     /// Creates appropriate index based on collection config.
     /// If vector persistence is enabled, loads all vectors from SQLite.
     pub async fn load_collection(&self, collection: &CollectionDescriptor) -> CoreResult<()> {
-        // Create appropriate index based on collection config
-        let index: Box<dyn VectorIndex> = if collection.max_doc_count <= 10_000 {
-            // Use BruteForce for small collections
-            Box::new(BruteForceIndex::new(
-                collection.dimension as usize,
-                collection.metric,
-            ))
-        } else {
-            // Use InstantDistance for large collections
-            let config =
-                InstantDistanceConfig::balanced(collection.dimension as usize, collection.metric);
-            Box::new(InstantDistanceIndex::new(config)?)
-        };
+        let persist_graph = self.persists_index_graph(collection);
+        let mut index = Self::new_index(collection, persist_graph)?;
 
         // Phase 6 Week 5 Day 3: Create StorageBackend FIRST to enable WAL recovery
         let storage_config = self.create_storage_backend_for_collection(collection)?;
@@ -830,7 +2507,43 @@ impl CollectionService {
 
         // Load vectors from StorageBackend (recovered from WAL)
         let recovered_vectors = storage_backend.all_vectors();
-        if !recovered_vectors.is_empty() {
+        let restored = if persist_graph && !recovered_vectors.is_empty() {
+            Self::restore_index_graph(collection, &storage_backend).await
+        } else {
+            None
+        };
+        if let Some(restored) = restored {
+            let expected_dim = collection.dimension as usize;
+            let (docs, skipped): (Vec<_>, Vec<_>) = recovered_vectors
+                .into_iter()
+                .partition(|doc| doc.vector.len() == expected_dim);
+            for doc in &skipped {
+                tracing::error!(
+                    "Skipping corrupted vector {} from WAL: expected dimension {}, got {}",
+                    doc.doc_id,
+                    expected_dim,
+                    doc.vector.len()
+                );
+            }
+
+            let text = TextIndex::new();
+            let sparse = SparseIndex::new();
+            for doc in &docs {
+                text.insert(doc.doc_id, TextIndex::analyze(doc.metadata.as_ref()));
+                if let Some(vector) = &doc.sparse {
+                    sparse.insert(doc.doc_id, vector.clone());
+                }
+            }
+
+            // Apply the writes made since the graph was saved
+            let changed = Self::reconcile_index_graph(&restored, docs).await?;
+            tracing::info!(
+                "Restored HNSW graph for collection {} ({} change(s) applied since it was saved)",
+                collection.collection_id,
+                changed
+            );
+            index = Box::new(HybridIndex::with_text(Box::new(restored), text).with_sparse(sparse));
+        } else if !recovered_vectors.is_empty() {
             tracing::info!(
                 "Loading {} vector(s) from StorageBackend for collection {}",
                 recovered_vectors.len(),
@@ -931,6 +2644,154 @@ impl CollectionService {
         Ok(())
     }
 
+    /// Whether the collection uses the native HNSW index and saves its graph.
+    fn persists_index_graph(&self, collection: &CollectionDescriptor) -> bool {
+        // The native HNSW index only stores full-precision vectors
+        !Self::is_small(collection)
+            && self.persist_index_graphs
+            && collection.vector_type != VectorType::Sparse
+            && collection.quantization == Quantization::None
+    }
+
+    /// Collections small enough for brute-force search.
+    fn is_small(collection: &CollectionDescriptor) -> bool {
+        collection.max_doc_count <= 10_000
+    }
+
+    /// An empty index for the collection, chosen by its configuration.
+    fn new_index(
+        collection: &CollectionDescriptor,
+        persist_graph: bool,
+    ) -> CoreResult<Box<dyn VectorIndex>> {
+        let dim = collection.dimension as usize;
+        let m = collection.hnsw_m as usize;
+        let ef_construction = collection.hnsw_ef_construction as usize;
+        // Create appropriate index based on collection config
+        let vectors: Box<dyn VectorIndex> = if collection.vector_type == VectorType::Sparse {
+            // Holds the documents' (empty) dense vectors; searches go to the
+            // sparse index. Cosine would reject the empty vectors.
+            Box::new(BruteForceIndex::new(0, DistanceMetric::Dot))
+        } else if Self::is_small(collection) {
+            // Use BruteForce for small collections
+            Box::new(
+                BruteForceIndex::new(dim, collection.metric)
+                    .with_quantization(collection.quantization),
+            )
+        } else if persist_graph {
+            // Native HNSW, whose graph can be persisted
+            let config = HnswIndexConfig {
+                m,
+                m0: m * 2,
+                ef_construction,
+                ml: 1.0 / (m as f64).ln(),
+                ..HnswIndexConfig::balanced(dim, collection.metric)
+            };
+            Box::new(HnswIndex::new(config))
+        } else {
+            // Use InstantDistance for large collections
+            let config = InstantDistanceConfig {
+                m,
+                ef_construction,
+                ..InstantDistanceConfig::balanced(dim, collection.metric)
+            };
+            Box::new(InstantDistanceIndex::new(config)?.with_quantization(collection.quantization))
+        };
+        // Keyword index over payload text (hybrid search) and sparse vector index
+        Ok(Box::new(HybridIndex::new(vectors)))
+    }
+
+    /// The collection's saved HNSW graph, if one exists and matches the
+    /// collection; unreadable graphs are logged and ignored.
+    async fn restore_index_graph(
+        collection: &CollectionDescriptor,
+        storage_backend: &StorageBackend,
+    ) -> Option<HnswIndex> {
+        let restored = match storage_backend.load_index_graph().await {
+            Ok(Some(graph)) => HnswIndex::from_bytes(&graph),
+            Ok(None) => return None,
+            Err(e) => Err(e),
+        };
+        match restored {
+            Ok(index)
+                if index.config().dim == collection.dimension as usize
+                    && index.config().metric == collection.metric =>
+            {
+                Some(index)
+            }
+            Ok(_) => {
+                tracing::warn!(
+                    "Ignoring HNSW graph of collection {}: dimension or metric changed",
+                    collection.collection_id
+                );
+                None
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Ignoring HNSW graph of collection {}, rebuilding: {}",
+                    collection.collection_id,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Brings a restored graph in line with the recovered documents,
+    /// returning the number of documents inserted, replaced or deleted.
+    async fn reconcile_index_graph(
+        index: &HnswIndex,
+        docs: Vec<VectorDocument>,
+    ) -> CoreResult<usize> {
+        let mut stale: std::collections::HashSet<DocumentId> =
+            index.doc_ids().into_iter().collect();
+        let mut changed = 0;
+        for doc in docs {
+            stale.remove(&doc.doc_id);
+            match index.get(doc.doc_id).await? {
+                Some(indexed)
+                    if indexed.vector == doc.vector
+                        && indexed.external_id == doc.external_id
+                        && indexed.metadata == doc.metadata => {}
+                Some(_) => {
+                    index.delete(doc.doc_id).await?;
+                    index.insert(doc).await?;
+                    changed += 1;
+                }
+                None => {
+                    index.insert(doc).await?;
+                    changed += 1;
+                }
+            }
+        }
+        for doc_id in stale {
+            index.delete(doc_id).await?;
+            changed += 1;
+        }
+        Ok(changed)
+    }
+
+    /// Saves the collection's index graph if graph persistence is enabled and
+    /// the index supports it.
+    async fn persist_index_graph(&self, collection_id: CollectionId) -> CoreResult<()> {
+        if !self.persist_index_graphs {
+            return Ok(());
+        }
+        let graph = {
+            let indexes = self.indexes.read().await;
+            match indexes.get(&collection_id) {
+                Some(index) => index.serialize_graph()?,
+                None => None,
+            }
+        };
+        if let Some(graph) = graph {
+            self.storage_backend(collection_id)
+                .await?
+                .save_index_graph(graph)
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Unload collection from memory (called on deletion).
     pub async fn unload_collection(&self, collection_id: CollectionId) -> CoreResult<()> {
         // Remove from collections cache (BUG FIX #7: Keep cache consistent)
@@ -989,6 +2850,8 @@ impl CollectionService {
                 dlq_size: 0,
                 circuit_breaker_state: 0, // Closed = 0
                 circuit_breaker_error_rate: 0.0,
+                gc_objects_scanned: 0,
+                gc_objects_deleted: 0,
             });
         }
 
@@ -1009,6 +2872,8 @@ impl CollectionService {
             dlq_size: 0,
             circuit_breaker_state: 0, // Closed = 0
             circuit_breaker_error_rate: 0.0,
+            gc_objects_scanned: 0,
+            gc_objects_deleted: 0,
         };
 
         for backend in backends.values() {
@@ -1035,6 +2900,12 @@ impl CollectionService {
                 .s3_permanent_failures
                 .saturating_add(metrics.s3_permanent_failures);
             total_metrics.dlq_size = total_metrics.dlq_size.saturating_add(metrics.dlq_size);
+            total_metrics.gc_objects_scanned = total_metrics
+                .gc_objects_scanned
+                .saturating_add(metrics.gc_objects_scanned);
+            total_metrics.gc_objects_deleted = total_metrics
+                .gc_objects_deleted
+                .saturating_add(metrics.gc_objects_deleted);
 
             // Use max error rate
             total_metrics.circuit_breaker_error_rate = total_metrics
@@ -1148,6 +3019,20 @@ impl CollectionService {
 
         // Clear DLQ (in the future, could retry each entry)
         backend.clear_dead_letter_queue();
+        drop(backends);
+
+        if let Some(audit) = &self.audit {
+            if let Some(database_id) = self.database_of(collection_id).await {
+                audit
+                    .record_admin(
+                        database_id,
+                        Some(collection_id),
+                        Action::DlqRetry,
+                        serde_json::json!({ "entries": total }),
+                    )
+                    .await;
+            }
+        }
 
         Ok(DLQRetryResult {
             total,
@@ -1156,6 +3041,447 @@ impl CollectionService {
         })
     }
 
+    /// Compact a collection's WAL into a snapshot now, returning the new state.
+    pub async fn compact_collection(
+        &self,
+        collection_id: CollectionId,
+    ) -> CoreResult<CompactionStatus> {
+        // Don't hold the map lock while the snapshot is written
+        let backend = self
+            .storage_backends
+            .read()
+            .await
+            .get(&collection_id)
+            .cloned()
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+
+        backend.compact().await?;
+        self.persist_index_graph(collection_id).await?;
+        Ok(Self::compaction_status_of(&backend))
+    }
+
+    /// Report when a collection was last compacted and whether compaction is due.
+    pub async fn compaction_status(
+        &self,
+        collection_id: CollectionId,
+    ) -> CoreResult<CompactionStatus> {
+        let backends = self.storage_backends.read().await;
+        let backend = backends
+            .get(&collection_id)
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+
+        Ok(Self::compaction_status_of(backend))
+    }
+
+    fn compaction_status_of(backend: &StorageBackend) -> CompactionStatus {
+        let metrics = backend.metrics();
+        let config = backend.config();
+        CompactionStatus {
+            last_compaction_at: metrics.last_snapshot_at,
+            compactions: metrics.compactions,
+            wal_size_bytes: metrics.wal_size_bytes,
+            wal_operations: metrics.inserts,
+            threshold_bytes: config.compaction_threshold_bytes,
+            threshold_ops: config.compaction_threshold_ops,
+            thresholds_exceeded: backend.should_compact(),
+        }
+    }
+
+    /// Snapshot a collection's documents now, keeping its WAL.
+    pub async fn snapshot_collection(
+        &self,
+        collection_id: CollectionId,
+    ) -> CoreResult<SnapshotMetadata> {
+        let metadata = self.storage_backend(collection_id).await?.snapshot().await?;
+        self.persist_index_graph(collection_id).await?;
+        Ok(metadata)
+    }
+
+    /// A collection's snapshots, newest first.
+    pub async fn list_snapshots(
+        &self,
+        collection_id: CollectionId,
+    ) -> CoreResult<Vec<SnapshotMetadata>> {
+        self.storage_backend(collection_id)
+            .await?
+            .list_snapshots()
+            .await
+    }
+
+    /// Delete all but a collection's newest `keep` snapshots.
+    pub async fn prune_snapshots(
+        &self,
+        collection_id: CollectionId,
+        keep: usize,
+    ) -> CoreResult<Vec<SnapshotId>> {
+        self.storage_backend(collection_id)
+            .await?
+            .prune_snapshots(keep)
+            .await
+    }
+
+    /// S3 garbage collection settings of the collections' storage.
+    pub fn gc_config(&self) -> &GcConfig {
+        &self.storage_config.gc_config
+    }
+
+    /// Run one S3 garbage collection pass over every collection whose
+    /// storage has it enabled, returning the combined report.
+    ///
+    /// Run this on the elected leader only, with
+    /// [`LeaderElection::spawn_singleton`](crate::LeaderElection::spawn_singleton).
+    /// Collections that fail (e.g. because another node holds their lock)
+    /// are logged and retried at the next pass.
+    pub async fn collect_garbage(&self) -> CoreResult<GcReport> {
+        let backends: Vec<(CollectionId, Arc<StorageBackend>)> = self
+            .storage_backends
+            .read()
+            .await
+            .iter()
+            .filter(|(_, backend)| backend.config().gc_config.enabled)
+            .map(|(collection_id, backend)| (*collection_id, Arc::clone(backend)))
+            .collect();
+
+        let mut total = GcReport::default();
+        for (collection_id, backend) in backends {
+            match backend.collect_garbage().await {
+                Ok(report) => {
+                    total.objects_scanned += report.objects_scanned;
+                    total.objects_deleted += report.objects_deleted;
+                    total.bytes_deleted += report.bytes_deleted;
+                }
+                Err(e) => tracing::warn!(
+                    "S3 garbage collection of collection {} failed: {}",
+                    collection_id,
+                    e
+                ),
+            }
+        }
+        Ok(total)
+    }
+
+    /// Restore a collection's documents to their state at `target`.
+    ///
+    /// The storage backend rebuilds the state from its snapshots and WAL
+    /// and writes the changes through the WAL; the same changes are then
+    /// applied to the index. Writes are blocked from the diff until the index
+    /// is updated, as for the swap in [`Self::reindex_collection`], so a
+    /// concurrent write lands either before the restore (and is rolled back)
+    /// or after it.
+    pub async fn restore_collection(
+        &self,
+        collection_id: CollectionId,
+        target: DateTime<Utc>,
+    ) -> CoreResult<RestoreReport> {
+        let backend = self.storage_backend(collection_id).await?;
+
+        let report = {
+            // Writes hold the read lock while they update the index and the
+            // WAL, so none is in flight or can start until both are restored
+            let indexes = self.indexes.write().await;
+            let index = indexes
+                .get(&collection_id)
+                .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+            let outcome = backend.restore_to(target).await?;
+
+            for doc_id in &outcome.deleted {
+                if index.get(*doc_id).await?.is_some() {
+                    index.delete(*doc_id).await?;
+                }
+            }
+            for doc in outcome.upserted {
+                if index.get(doc.doc_id).await?.is_some() {
+                    index.delete(doc.doc_id).await?;
+                }
+                index.insert(doc).await?;
+            }
+            outcome.report
+        };
+        self.persist_index_graph(collection_id).await?;
+
+        self.audit_write(
+            collection_id,
+            Action::CollectionUpdate,
+            serde_json::json!({
+                "restore_to": target,
+                "upserted": report.upserted,
+                "deleted": report.deleted,
+            }),
+        )
+        .await;
+
+        Ok(report)
+    }
+
+    /// Rebuilds a collection's index in the background and swaps it in.
+    ///
+    /// Runs as a `reindex` job. The new index is built from the documents in
+    /// the collection's storage backend, with `options` applied to the
+    /// collection's settings, while the current index keeps serving reads
+    /// and writes. Writes made during the build are replayed from the WAL;
+    /// the last of them with writes blocked, just before the swap.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` for an unknown collection, `ValidationError` for
+    /// out-of-range options, and `InvalidState` if the collection is already
+    /// being reindexed.
+    pub async fn reindex_collection(
+        self: &Arc<Self>,
+        collection_id: CollectionId,
+        options: ReindexOptions,
+    ) -> CoreResult<JobId> {
+        let mut collection = self
+            .collections
+            .read()
+            .await
+            .get(&collection_id)
+            .cloned()
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+        options.apply(&mut collection)?;
+        let backend = self.storage_backend(collection_id).await?;
+
+        if !self.reindexing.write().await.insert(collection_id) {
+            return Err(CoreError::invalid_state(format!(
+                "collection {} is already being reindexed",
+                collection_id
+            )));
+        }
+
+        let job =
+            JobDescriptor::new("reindex", Some(collection_id)).with_total(backend.count() as u64);
+        let service = Arc::clone(self);
+        let spawned = self
+            .jobs
+            .spawn(job, move |handle| async move {
+                let outcome = service
+                    .rebuild_index(collection, options, &backend, &handle)
+                    .await;
+                service.reindexing.write().await.remove(&collection_id);
+                outcome
+            })
+            .await;
+
+        if spawned.is_err() {
+            self.reindexing.write().await.remove(&collection_id);
+        }
+        spawned
+    }
+
+    /// Body of a `reindex` job: builds the index, catches up with the WAL
+    /// and swaps the new index in.
+    async fn rebuild_index(
+        &self,
+        collection: CollectionDescriptor,
+        options: ReindexOptions,
+        backend: &StorageBackend,
+        handle: &JobHandle,
+    ) -> CoreResult<serde_json::Value> {
+        let collection_id = collection.collection_id;
+        let persist_graph = self.persists_index_graph(&collection);
+        let mut rebuild = IndexRebuild::new(Self::new_index(&collection, persist_graph)?);
+
+        // No write is in flight while the index map is locked, so the
+        // documents read afterwards include every write up to `lsn`. Later
+        // writes they may include are replayed again, which is harmless.
+        rebuild.lsn = {
+            let _indexes = self.indexes.write().await;
+            backend.current_lsn().await?.value()
+        };
+        let docs = backend.all_vectors();
+        handle.set_total(docs.len() as u64).await;
+
+        let expected_dim = collection.dimension as usize;
+        let (mut pending, mut skipped) = (0, 0);
+        for doc in docs {
+            if doc.vector.len() == expected_dim {
+                rebuild.upsert(doc).await?;
+            } else {
+                skipped += 1;
+            }
+            pending += 1;
+            if pending == REINDEX_PROGRESS_STEP {
+                handle.advance(pending).await;
+                handle.check_cancelled().await?;
+                pending = 0;
+                // Let requests served by the current index run
+                tokio::task::yield_now().await;
+            }
+        }
+        handle.advance(pending).await;
+        if skipped > 0 {
+            tracing::warn!(
+                "Skipped {} document(s) with a wrong dimension while reindexing collection {}",
+                skipped,
+                collection_id
+            );
+        }
+
+        // Catch up while writes continue, so few are left once they are blocked
+        let mut replayed = rebuild.catch_up(backend).await?;
+        // Last chance to stop; the current index is kept
+        handle.check_cancelled().await?;
+
+        // Settings first: the swapped-in index must survive a restart
+        let descriptor = {
+            let mut collections = self.collections.write().await;
+            let current = collections
+                .get_mut(&collection_id)
+                .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+            options.apply(current)?;
+            current.updated_at = Utc::now();
+            current.clone()
+        };
+        if let Some(repository) = &self.repository {
+            repository.update(&descriptor).await?;
+        }
+
+        {
+            // Writes hold the read lock while they update the index and the
+            // WAL, so none is in flight or can start until the swap is done
+            let mut indexes = self.indexes.write().await;
+            let index = indexes
+                .get_mut(&collection_id)
+                .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+            replayed += rebuild.catch_up(backend).await?;
+            *index = rebuild.index;
+        }
+
+        let graph = if persist_graph {
+            self.persist_index_graph(collection_id).await
+        } else if self.persist_index_graphs {
+            // The saved graph belongs to the replaced index
+            backend.delete_index_graph().await
+        } else {
+            Ok(())
+        };
+        if let Err(e) = graph {
+            tracing::warn!(
+                "Failed to update the saved HNSW graph of collection {}: {}",
+                collection_id,
+                e
+            );
+        }
+
+        let documents = rebuild.doc_ids.len();
+        tracing::info!(
+            "🔁 Reindexed collection {} ({} documents, {} write(s) replayed)",
+            collection_id,
+            documents,
+            replayed
+        );
+        let details = serde_json::json!({
+            "documents": documents,
+            "replayed": replayed,
+            "skipped": skipped,
+            "hnsw_m": descriptor.hnsw_m,
+            "hnsw_ef_construction": descriptor.hnsw_ef_construction,
+            "max_doc_count": descriptor.max_doc_count,
+        });
+        self.audit_write(
+            collection_id,
+            Action::CollectionUpdate,
+            serde_json::json!({ "reindex": details }),
+        )
+        .await;
+        Ok(details)
+    }
+
+    /// Storage backend of a collection, without holding the map lock.
+    async fn storage_backend(&self, collection_id: CollectionId) -> CoreResult<Arc<StorageBackend>> {
+        self.storage_backends
+            .read()
+            .await
+            .get(&collection_id)
+            .cloned()
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))
+    }
+
+    /// Report a collection's WAL position, disk footprint, and upload backlog.
+    pub async fn wal_state(&self, collection_id: CollectionId) -> CoreResult<WalState> {
+        let backend = self
+            .storage_backends
+            .read()
+            .await
+            .get(&collection_id)
+            .cloned()
+            .ok_or_else(|| CoreError::not_found("Collection", collection_id.to_string()))?;
+
+        backend.wal_state().await
+    }
+
+    // ========== Replication ==========
+
+    async fn replication_backend(
+        &self,
+        collection_id: CollectionId,
+    ) -> CoreResult<Arc<StorageBackend>> {
+        let backends = self.storage_backends.read().await;
+        backends.get(&collection_id).cloned().ok_or_else(|| {
+            CoreError::invalid_state(format!(
+                "collection {} has no write-ahead log to replicate",
+                collection_id
+            ))
+        })
+    }
+
+    /// Highest LSN in a collection's WAL, or `None` for collections without
+    /// one (legacy persistence), which cannot be replicated.
+    pub(crate) async fn wal_position(
+        &self,
+        collection_id: CollectionId,
+    ) -> CoreResult<Option<u64>> {
+        let backend = self
+            .storage_backends
+            .read()
+            .await
+            .get(&collection_id)
+            .cloned();
+        match backend {
+            Some(backend) => Ok(Some(backend.current_lsn().await?.value())),
+            None => Ok(None),
+        }
+    }
+
+    /// WAL entries of a collection with LSN >= `from_lsn` still on disk.
+    pub(crate) async fn read_wal(
+        &self,
+        collection_id: CollectionId,
+        from_lsn: u64,
+    ) -> CoreResult<Vec<(u64, LogEntry)>> {
+        let backend = self.replication_backend(collection_id).await?;
+        let entries = backend.read_wal(LogSequenceNumber::new(from_lsn)).await?;
+        Ok(entries
+            .into_iter()
+            .map(|(lsn, entry)| (lsn.value(), entry))
+            .collect())
+    }
+
+    /// Every document of a collection, with the WAL position they reflect.
+    ///
+    /// The position is read first, so entries written while the documents
+    /// are collected may be both in the snapshot and after the position.
+    pub(crate) async fn replication_snapshot(
+        &self,
+        collection_id: CollectionId,
+    ) -> CoreResult<(u64, Vec<VectorDocument>)> {
+        let backend = self.replication_backend(collection_id).await?;
+        let lsn = backend.current_lsn().await?.value();
+        Ok((lsn, backend.all_vectors()))
+    }
+
+    /// Create a collection replicated from another node, keeping its ID.
+    ///
+    /// The collection goes into this node's default database, since database
+    /// IDs are not shared between nodes.
+    pub(crate) async fn create_replica_collection(
+        &self,
+        mut collection: CollectionDescriptor,
+    ) -> CoreResult<CollectionId> {
+        collection.database_id = self.default_database_id().await;
+        self.register_collection(collection).await
+    }
+
     /// Reset circuit breaker (emergency recovery)
     pub async fn reset_circuit_breaker(&self) -> CoreResult<CircuitBreakerState> {
         // Reset circuit breaker for all storage backends
@@ -1178,6 +3504,20 @@ impl CollectionService {
             1 => CircuitBreakerState::HalfOpen,
             _ => CircuitBreakerState::Open,
         };
+        drop(backends);
+
+        if let (Some(audit), Some(database_id)) =
+            (&self.audit, *self.default_database_id.read().await)
+        {
+            audit
+                .record_admin(
+                    database_id,
+                    None,
+                    Action::CircuitBreakerReset,
+                    serde_json::json!({ "previous_state": format!("{:?}", previous_state) }),
+                )
+                .await;
+        }
 
         Ok(previous_state)
     }
@@ -1212,6 +3552,21 @@ impl CollectionService {
 
         let shutdown_start = std::time::Instant::now();
 
+        // Save index graphs first so the next start can skip the rebuild
+        if self.persist_index_graphs {
+            let collection_ids: Vec<CollectionId> =
+                self.indexes.read().await.keys().copied().collect();
+            for collection_id in collection_ids {
+                if let Err(e) = self.persist_index_graph(collection_id).await {
+                    tracing::warn!(
+                        "Failed to save index graph for collection {}: {}",
+                        collection_id,
+                        e
+                    );
+                }
+            }
+        }
+
         // Step 1: Shutdown all storage backends
         // This is CRITICAL - ensures WAL flush and task cleanup
         {
@@ -1289,22 +3644,89 @@ impl CollectionService {
         Ok(())
     }
 
+    /// Drains the service for shutdown (see [`crate::drain`]).
+    ///
+    /// Fails readiness and keeps serving for `grace_period`, so load
+    /// balancers stop routing here first; then refuses new work and waits up
+    /// to `timeout` for in-flight requests and the S3 upload queues to empty.
+    /// A drain already under way is waited for rather than started again.
+    pub async fn drain(&self, grace_period: Duration, timeout: Duration) -> DrainStatus {
+        if !self.drain.start(grace_period) {
+            self.drain.drained().await;
+            return self.drain.status();
+        }
+
+        tracing::info!(
+            "Draining: readiness failing, still serving for {:?}",
+            grace_period
+        );
+        tokio::time::sleep(grace_period).await;
+
+        self.drain.advance(DrainStage::Rejecting);
+        tracing::info!(
+            "Draining: refusing new work, waiting for {} in-flight request(s)",
+            self.drain.in_flight()
+        );
+        let flushed = tokio::time::timeout(timeout, async {
+            loop {
+                let pending: usize = self
+                    .queue_depths()
+                    .await
+                    .iter()
+                    .map(|depth| depth.pending_uploads + depth.pending_retries)
+                    .sum();
+                self.drain.record_pending_uploads(pending);
+                if pending == 0 && self.drain.in_flight() == 0 {
+                    return;
+                }
+                tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+            }
+        })
+        .await;
+        if flushed.is_err() {
+            self.drain.record_timeout();
+        }
+
+        self.drain.advance(DrainStage::Drained);
+        let status = self.drain.status();
+        if status.timed_out {
+            tracing::warn!(
+                "Drain timed out after {:?} with {} request(s) in flight and {} upload(s) queued",
+                timeout,
+                status.in_flight,
+                status.pending_uploads
+            );
+        } else {
+            tracing::info!("Drained: safe to shut down");
+        }
+        status
+    }
+
+    /// In-flight request tracking and drain progress.
+    pub fn drain_controller(&self) -> &Arc<DrainController> {
+        &self.drain
+    }
+
     /// Check if the service is ready to serve requests.
     ///
     /// Returns `true` if:
-    /// - All required dependencies are initialized
-    /// - At least one collection is loaded (if applicable)
+    /// - [`bootstrap`](Self::bootstrap) has completed (when backed by a repository)
+    /// - No [`drain`](Self::drain) has started
     /// - Storage backends are healthy
     ///
     /// This is used for Kubernetes readiness probes.
     pub async fn is_ready(&self) -> bool {
-        // Check 1: At least have the basic structures initialized
-        if self.collections.read().await.is_empty() && self.repository.is_some() {
-            // If we have a repository but no collections, we haven't loaded yet
+        // Check 1: Collections are loaded, WALs replayed and the object store reachable
+        if self.repository.is_some() && !self.readiness.is_ready() {
+            return false;
+        }
+
+        // Check 2: Not draining for shutdown
+        if self.drain.is_draining() {
             return false;
         }
 
-        // Check 2: Verify storage backends are healthy (not in permanent failure state)
+        // Check 3: Verify storage backends are healthy (not in permanent failure state)
         let backends = self.storage_backends.read().await;
         for backend in backends.values() {
             // Check if circuit breaker is permanently open (indicates S3 down)
@@ -1341,6 +3763,179 @@ impl Default for CollectionService {
     }
 }
 
+/// Checks that a search brings the query vector its collection is ranked by:
+/// a sparse query replaces the dense one, and only collections with sparse
+/// vectors take it.
+fn validate_query(
+    vector_type: VectorType,
+    query_vector: &[f32],
+    options: &SearchOptions,
+) -> CoreResult<()> {
+    match (&options.sparse, vector_type) {
+        (Some(_), VectorType::Dense) => Err(CoreError::ValidationError(
+            "Collection does not store sparse vectors".to_string(),
+        )),
+        (Some(_), _) if !query_vector.is_empty() => Err(CoreError::ValidationError(
+            "Provide either a dense or a sparse query vector, not both".to_string(),
+        )),
+        (Some(_), _) if options.hybrid.is_some() => Err(CoreError::ValidationError(
+            "hybrid search cannot be combined with a sparse query".to_string(),
+        )),
+        (None, VectorType::Sparse) => Err(CoreError::ValidationError(
+            "Sparse collections are searched with a sparse query vector".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a document carries the vectors its collection stores: a dense
+/// vector of `expected_dim` components (none in sparse-only collections) and a
+/// sparse vector only where the collection has them.
+fn validate_vectors(
+    expected_dim: usize,
+    vector_type: VectorType,
+    doc: &VectorDocument,
+) -> CoreResult<()> {
+    if doc.vector.len() != expected_dim {
+        return Err(CoreError::ValidationError(format!(
+            "Vector dimension mismatch: expected {}, got {}",
+            expected_dim,
+            doc.vector.len()
+        )));
+    }
+    match (vector_type, &doc.sparse) {
+        (VectorType::Dense, Some(_)) => Err(CoreError::ValidationError(
+            "Collection does not store sparse vectors".to_string(),
+        )),
+        (VectorType::Sparse, None) => Err(CoreError::ValidationError(
+            "Documents of a sparse collection need a sparse vector".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// L2-normalizes a vector of a `normalize` collection in place.
+fn normalize_vector(vector: &mut [f32]) -> CoreResult<()> {
+    if akidb_core::vector::l2_normalize(vector) {
+        Ok(())
+    } else {
+        Err(CoreError::ValidationError(
+            "Vector cannot be normalized: its L2 norm is zero or not finite".to_string(),
+        ))
+    }
+}
+
+/// Publishes a `TierChange` event for every tier transition the tiering
+/// manager makes, including those of its background worker.
+fn publish_tier_changes(tiering_manager: &TieringManager, events: &EventBus) {
+    let events = events.clone();
+    tiering_manager.on_tier_change(move |collection_id, tier| {
+        events.publish(ChangeEvent::new(
+            collection_id,
+            ChangeKind::TierChange {
+                tier: tier.to_string(),
+            },
+        ));
+    });
+}
+
+/// Index being built by a reindex job, with the documents it holds.
+struct IndexRebuild {
+    index: Box<dyn VectorIndex>,
+    doc_ids: HashSet<DocumentId>,
+    /// WAL position the index reflects
+    lsn: u64,
+}
+
+impl IndexRebuild {
+    fn new(index: Box<dyn VectorIndex>) -> Self {
+        Self {
+            index,
+            doc_ids: HashSet::new(),
+            lsn: 0,
+        }
+    }
+
+    async fn upsert(&mut self, doc: VectorDocument) -> CoreResult<()> {
+        if !self.doc_ids.insert(doc.doc_id) {
+            self.index.delete(doc.doc_id).await?;
+        }
+        self.index.insert(doc).await
+    }
+
+    async fn delete(&mut self, doc_id: DocumentId) -> CoreResult<()> {
+        if self.doc_ids.remove(&doc_id) {
+            self.index.delete(doc_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Applies the writes logged after `lsn`, returning how many there were.
+    ///
+    /// If the WAL has been checkpointed past `lsn`, every document is
+    /// reapplied from the storage backend instead.
+    async fn catch_up(&mut self, backend: &StorageBackend) -> CoreResult<usize> {
+        let current = backend.current_lsn().await?.value();
+        if current <= self.lsn {
+            return Ok(0);
+        }
+
+        let entries = backend
+            .read_wal(LogSequenceNumber::new(self.lsn + 1))
+            .await?;
+        let gap = entries
+            .first()
+            .map_or(true, |(lsn, _)| lsn.value() > self.lsn + 1);
+        if gap {
+            let docs = backend.all_vectors();
+            let mut stale = self.doc_ids.clone();
+            let changed = docs.len();
+            for doc in docs {
+                stale.remove(&doc.doc_id);
+                self.upsert(doc).await?;
+            }
+            for doc_id in stale {
+                self.delete(doc_id).await?;
+            }
+            self.lsn = current;
+            return Ok(changed);
+        }
+
+        let mut applied = 0;
+        for (lsn, entry) in entries {
+            match entry {
+                LogEntry::Upsert {
+                    doc_id,
+                    vector,
+                    sparse,
+                    external_id,
+                    metadata,
+                    timestamp,
+                    ..
+                } => {
+                    self.upsert(VectorDocument {
+                        doc_id,
+                        external_id,
+                        vector,
+                        sparse,
+                        metadata,
+                        inserted_at: timestamp,
+                    })
+                    .await?;
+                    applied += 1;
+                }
+                LogEntry::Delete { doc_id, .. } => {
+                    self.delete(doc_id).await?;
+                    applied += 1;
+                }
+                _ => {}
+            }
+            self.lsn = lsn.value();
+        }
+        Ok(applied)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1349,6 +3944,12 @@ mod tests {
     use async_trait::async_trait;
     use chrono::Utc;
 
+    /// In-memory service that keeps its WALs under `dir`
+    fn in_memory_service(dir: &tempfile::TempDir) -> CollectionService {
+        CollectionService::new()
+            .with_storage_config(StorageConfig::memory(dir.path().join("akidb.wal")))
+    }
+
     fn create_test_collection() -> CollectionDescriptor {
         CollectionDescriptor {
             collection_id: CollectionId::new(),
@@ -1356,6 +3957,9 @@ mod tests {
             name: "test-collection".to_string(),
             dimension: 128,
             metric: DistanceMetric::Cosine,
+            normalize: false,
+            quantization: Quantization::None,
+            vector_type: VectorType::Dense,
             embedding_model: "test-model".to_string(),
             hnsw_m: 32,
             hnsw_ef_construction: 200,
@@ -1367,7 +3971,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_load_and_insert() {
-        let service = CollectionService::new();
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
         let collection = create_test_collection();
 
         // Load collection
@@ -1384,7 +3989,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_query() {
-        let service = CollectionService::new();
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
         let collection = create_test_collection();
 
         service.load_collection(&collection).await.unwrap();
@@ -1408,7 +4014,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete() {
-        let service = CollectionService::new();
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
         let collection = create_test_collection();
 
         service.load_collection(&collection).await.unwrap();
@@ -1429,6 +4036,447 @@ mod tests {
         assert!(retrieved.is_none());
     }
 
+    #[tokio::test]
+    async fn test_delete_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
+        let collection = create_test_collection();
+
+        service.load_collection(&collection).await.unwrap();
+
+        let doc = VectorDocument::new(DocumentId::new(), vec![0.1; 128]);
+        let doc_id = doc.doc_id;
+        service.insert(collection.collection_id, doc).await.unwrap();
+
+        let missing_id = DocumentId::new();
+        let statuses = service
+            .delete_batch(collection.collection_id, vec![doc_id, missing_id])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            statuses,
+            vec![
+                (doc_id, BatchDeleteStatus::Deleted),
+                (missing_id, BatchDeleteStatus::NotFound),
+            ]
+        );
+        assert!(service
+            .get(collection.collection_id, doc_id)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Unknown collection fails the whole batch
+        assert!(service
+            .delete_batch(CollectionId::new(), vec![doc_id])
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_with_filter_and_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
+        let collection = create_test_collection();
+        let collection_id = collection.collection_id;
+
+        service.load_collection(&collection).await.unwrap();
+
+        for i in 0..20 {
+            let mut vector = vec![0.0; 128];
+            vector[0] = 1.0;
+            vector[1] = i as f32 * 0.1;
+            let doc = VectorDocument::new(DocumentId::new(), vector)
+                .with_metadata(serde_json::json!({"even": i % 2 == 0, "rank": i}));
+            service.insert(collection_id, doc).await.unwrap();
+        }
+
+        let mut query = vec![0.0; 128];
+        query[0] = 1.0;
+
+        let options = SearchOptions {
+            filter: Some(
+                FilterTree::parse(&serde_json::json!({"even": false, "rank": {"$gte": 10}}))
+                    .unwrap(),
+            ),
+            include_payload: true,
+            include_vector: true,
+            ..SearchOptions::default()
+        };
+        let results = service
+            .search(collection_id, query.clone(), 3, &options)
+            .await
+            .unwrap();
+        let ranks: Vec<_> = results
+            .iter()
+            .map(|r| r.metadata.as_ref().unwrap()["rank"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ranks, vec![11, 13, 15]);
+        assert!(results
+            .iter()
+            .all(|r| r.vector.as_ref().unwrap().len() == 128));
+
+        // Projection keeps only the requested payload keys
+        let options = SearchOptions {
+            include_payload: true,
+            payload_fields: Some(vec!["rank".to_string()]),
+            ..SearchOptions::default()
+        };
+        let results = service
+            .search(collection_id, query.clone(), 3, &options)
+            .await
+            .unwrap();
+        assert!(results.iter().all(|r| {
+            let metadata = r.metadata.as_ref().unwrap().as_object().unwrap();
+            metadata.len() == 1 && metadata.contains_key("rank")
+        }));
+
+        // Threshold keeps only close matches; payload is stripped by default
+        let options = SearchOptions {
+            score_threshold: Some(0.99),
+            ..SearchOptions::default()
+        };
+        let results = service
+            .search(collection_id, query, 20, &options)
+            .await
+            .unwrap();
+        assert!(!results.is_empty() && results.len() < 20);
+        assert!(results
+            .iter()
+            .all(|r| r.score >= 0.99 && r.metadata.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_selective_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
+        let collection = create_test_collection();
+        let collection_id = collection.collection_id;
+        service.load_collection(&collection).await.unwrap();
+
+        // Only three documents match, all far down the unfiltered ranking
+        for i in 0..300 {
+            let mut vector = vec![0.0; 128];
+            vector[0] = 1.0;
+            vector[1] = i as f32 * 0.01;
+            let tag = if i % 100 == 99 { "rare" } else { "common" };
+            let doc = VectorDocument::new(DocumentId::new(), vector)
+                .with_metadata(serde_json::json!({ "tag": tag }));
+            service.insert(collection_id, doc).await.unwrap();
+        }
+
+        let mut query = vec![0.0; 128];
+        query[0] = 1.0;
+        let options = SearchOptions {
+            filter: Some(FilterTree::parse(&serde_json::json!({"tag": "rare"})).unwrap()),
+            include_payload: true,
+            ..SearchOptions::default()
+        };
+        let results = service
+            .search(collection_id, query, 5, &options)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results
+            .iter()
+            .all(|r| r.metadata.as_ref().unwrap()["tag"] == "rare"));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_rerank() {
+        let collection = create_test_collection();
+        let collection_id = collection.collection_id;
+        let texts = ["cats and dogs", "rust vector database", "vector math"];
+
+        let options = SearchOptions {
+            rerank: Some(RerankOptions {
+                query: "rust vector database".to_string(),
+                text_field: "text".to_string(),
+                candidates: 3,
+            }),
+            ..SearchOptions::default()
+        };
+
+        // Rejected unless a reranker is configured
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
+        service.load_collection(&collection).await.unwrap();
+        let result = service
+            .search(collection_id, vec![1.0; 128], 1, &options)
+            .await;
+        assert!(matches!(result, Err(CoreError::ValidationError(_))));
+
+        let reranker = Reranker::new(
+            Arc::new(akidb_embedding::MockRerankProvider::new()),
+            "mock-rerank",
+            10,
+        );
+        let service = in_memory_service(&dir).with_reranker(Arc::new(reranker));
+        service.load_collection(&collection).await.unwrap();
+        let mut ids = Vec::new();
+        for (i, text) in texts.iter().enumerate() {
+            // Vector similarity ranks the documents in insertion order
+            let mut vector = vec![0.0; 128];
+            vector[0] = 1.0;
+            vector[1] = i as f32;
+            let doc = VectorDocument::new(DocumentId::new(), vector)
+                .with_metadata(serde_json::json!({ "text": text }));
+            ids.push(service.insert(collection_id, doc).await.unwrap());
+        }
+
+        let mut query = vec![0.0; 128];
+        query[0] = 1.0;
+        let results = service
+            .search(collection_id, query, 1, &options)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, ids[1]);
+        assert_eq!(results[0].score, 1.0);
+        // Payload was needed for reranking but is still stripped by default
+        assert!(results[0].metadata.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
+        let collection = create_test_collection();
+        let collection_id = collection.collection_id;
+        service.load_collection(&collection).await.unwrap();
+
+        // Vector similarity ranks the first three in insertion order; the
+        // last points away from the query
+        let texts = [
+            "cats and dogs",
+            "vector math",
+            "rust database",
+            "rust database engine",
+        ];
+        let mut ids = Vec::new();
+        for (i, text) in texts.iter().enumerate() {
+            let mut vector = vec![0.0; 128];
+            if i < 3 {
+                vector[0] = 1.0;
+                vector[1] = i as f32;
+            } else {
+                vector[0] = -1.0;
+            }
+            let doc = VectorDocument::new(DocumentId::new(), vector)
+                .with_metadata(serde_json::json!({ "text": text }));
+            ids.push(service.insert(collection_id, doc).await.unwrap());
+        }
+
+        let mut query = vec![0.0; 128];
+        query[0] = 1.0;
+        let hybrid = HybridOptions {
+            query: "rust database".to_string(),
+            fields: None,
+            fusion: hybrid::Fusion::Rrf,
+            candidates: 3,
+        };
+        let options = SearchOptions {
+            hybrid: Some(hybrid.clone()),
+            ..SearchOptions::default()
+        };
+        // Ranked by both, the short keyword match overtakes the best vector
+        let results = service
+            .search(collection_id, query.clone(), 2, &options)
+            .await
+            .unwrap();
+        let order: Vec<_> = results.iter().map(|r| r.doc_id).collect();
+        assert_eq!(order, vec![ids[2], ids[0]]);
+
+        // Keyword-only matches outside the vector candidates are included
+        let options = SearchOptions {
+            hybrid: Some(HybridOptions {
+                fusion: hybrid::Fusion::Weighted { alpha: 0.0 },
+                ..hybrid.clone()
+            }),
+            include_payload: true,
+            ..SearchOptions::default()
+        };
+        let results = service
+            .search(collection_id, query.clone(), 2, &options)
+            .await
+            .unwrap();
+        let order: Vec<_> = results.iter().map(|r| r.doc_id).collect();
+        assert_eq!(order, vec![ids[2], ids[3]]);
+        assert_eq!(
+            results[1].metadata.as_ref().unwrap()["text"],
+            "rust database engine"
+        );
+
+        // Filters apply to keyword matches too
+        let options = SearchOptions {
+            hybrid: Some(HybridOptions {
+                fusion: hybrid::Fusion::Weighted { alpha: 0.0 },
+                ..hybrid.clone()
+            }),
+            filter: Some(
+                FilterTree::parse(&serde_json::json!({"text": "rust database engine"})).unwrap(),
+            ),
+            ..SearchOptions::default()
+        };
+        let results = service
+            .search(collection_id, query.clone(), 2, &options)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, ids[3]);
+
+        for options in [
+            SearchOptions {
+                hybrid: Some(HybridOptions {
+                    query: " ".to_string(),
+                    ..hybrid.clone()
+                }),
+                ..SearchOptions::default()
+            },
+            SearchOptions {
+                hybrid: Some(HybridOptions {
+                    fusion: hybrid::Fusion::Weighted { alpha: 1.5 },
+                    ..hybrid.clone()
+                }),
+                ..SearchOptions::default()
+            },
+            SearchOptions {
+                hybrid: Some(hybrid.clone()),
+                score_threshold: Some(0.5),
+                ..SearchOptions::default()
+            },
+        ] {
+            let result = service
+                .search(collection_id, query.clone(), 2, &options)
+                .await;
+            assert!(matches!(result, Err(CoreError::ValidationError(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
+        let collection = create_test_collection();
+
+        service.load_collection(&collection).await.unwrap();
+
+        let doc = VectorDocument::new(DocumentId::new(), vec![0.1; 128]);
+        let doc_id = doc.doc_id;
+        service.insert(collection.collection_id, doc).await.unwrap();
+
+        let replacement = VectorDocument::new(doc_id, vec![0.2; 128]);
+        let new_doc = VectorDocument::new(DocumentId::new(), vec![0.3; 128]);
+        let bad_dim = VectorDocument::new(DocumentId::new(), vec![0.1; 4]);
+
+        let results = service
+            .upsert_batch(
+                collection.collection_id,
+                vec![replacement, new_doc.clone(), bad_dim],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &doc_id);
+        assert_eq!(results[1].as_ref().unwrap(), &new_doc.doc_id);
+        assert!(results[2].is_err());
+
+        assert_eq!(
+            service.get_count(collection.collection_id).await.unwrap(),
+            2
+        );
+        let replaced = service
+            .get(collection.collection_id, doc_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(replaced.vector, vec![0.2; 128]);
+    }
+
+    #[tokio::test]
+    async fn test_insert_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
+        let collection = create_test_collection();
+        let collection_id = collection.collection_id;
+        service.load_collection(&collection).await.unwrap();
+
+        let existing = VectorDocument::new(DocumentId::new(), vec![0.1; 128]);
+        service.insert(collection_id, existing.clone()).await.unwrap();
+
+        let first = VectorDocument::new(DocumentId::new(), vec![0.2; 128]);
+        let second = VectorDocument::new(DocumentId::new(), vec![0.3; 128]);
+        let bad_dim = VectorDocument::new(DocumentId::new(), vec![0.1; 4]);
+        let results = service
+            .insert_batch(
+                collection_id,
+                vec![
+                    first.clone(),
+                    bad_dim,
+                    existing,
+                    second.clone(),
+                    first.clone(),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_ref().unwrap(), &first.doc_id);
+        assert!(matches!(results[1], Err(CoreError::ValidationError(_))));
+        assert!(matches!(results[2], Err(CoreError::AlreadyExists { .. })));
+        assert_eq!(results[3].as_ref().unwrap(), &second.doc_id);
+        assert!(matches!(results[4], Err(CoreError::AlreadyExists { .. })));
+
+        assert_eq!(service.get_count(collection_id).await.unwrap(), 3);
+        let stored = service
+            .get(collection_id, second.doc_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.vector, vec![0.3; 128]);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_by_external_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = in_memory_service(&dir);
+        let collection = create_test_collection();
+        let collection_id = collection.collection_id;
+        service.load_collection(&collection).await.unwrap();
+
+        let (doc_id, created) = service
+            .upsert_by_external_id(collection_id, "sku-1".to_string(), vec![0.1; 128], None)
+            .await
+            .unwrap();
+        assert!(created);
+
+        let (replaced_id, created) = service
+            .upsert_by_external_id(
+                collection_id,
+                "sku-1".to_string(),
+                vec![0.2; 128],
+                Some(serde_json::json!({ "version": 2 })),
+            )
+            .await
+            .unwrap();
+        assert!(!created);
+        assert_eq!(replaced_id, doc_id);
+        assert_eq!(service.get_count(collection_id).await.unwrap(), 1);
+
+        let doc = service.get(collection_id, doc_id).await.unwrap().unwrap();
+        assert_eq!(doc.vector, vec![0.2; 128]);
+        assert_eq!(doc.external_id.as_deref(), Some("sku-1"));
+        assert_eq!(doc.metadata, Some(serde_json::json!({ "version": 2 })));
+
+        assert!(service
+            .upsert_by_external_id(collection_id, String::new(), vec![0.1; 128], None)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_collection_service_with_storage_config() {
         use akidb_storage::{StorageConfig, TieringPolicy};
@@ -1802,6 +4850,49 @@ mod tests {
         assert_eq!(results[0].doc_id, doc_id);
     }
 
+    #[tokio::test]
+    async fn test_manual_compaction_status() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let storage_config = StorageConfig::memory(temp_dir.path().join("akidb.wal"))
+            .with_compaction_thresholds(1_000_000, 1_000);
+
+        let service = CollectionService::with_storage(
+            Arc::new(MockCollectionRepository {}),
+            Arc::new(akidb_metadata::VectorPersistence::new(
+                create_test_db().await,
+            )),
+            storage_config,
+        );
+        service.set_default_database_id(DatabaseId::new()).await;
+
+        let collection_id = service
+            .create_collection("compact".to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+        for i in 0..3 {
+            let doc = VectorDocument::new(DocumentId::new(), vec![0.1 * (i + 1) as f32; 16]);
+            service.insert(collection_id, doc).await.unwrap();
+        }
+
+        let before = service.compaction_status(collection_id).await.unwrap();
+        assert_eq!(before.wal_operations, 3);
+        assert_eq!(before.compactions, 0);
+        assert!(before.last_compaction_at.is_none());
+        assert!(!before.thresholds_exceeded);
+
+        let after = service.compact_collection(collection_id).await.unwrap();
+        assert_eq!(after.compactions, 1);
+        assert_eq!(after.wal_operations, 0);
+        assert!(after.last_compaction_at.is_some());
+
+        assert!(service
+            .compaction_status(CollectionId::new())
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     #[ignore = "Memory policy doesn't support S3 compaction - compaction only works with MemoryS3 or S3Only policies"]
     async fn test_auto_compaction_triggered() {
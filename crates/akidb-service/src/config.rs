@@ -5,8 +5,11 @@
 //! 2. TOML configuration file
 //! 3. Default values (lowest priority)
 
+use akidb_embedding::{HttpProviderConfig, HttpRerankConfig, TruncationStrategy};
+use akidb_storage::tiering_manager::TieringPolicyConfig;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Main configuration structure for AkiDB servers.
 ///
@@ -24,6 +27,10 @@ pub struct Config {
     #[serde(default)]
     pub embedding: EmbeddingConfig,
 
+    /// Cross-encoder reranking of search results
+    #[serde(default)]
+    pub rerank: RerankConfig,
+
     /// Optional features
     #[serde(default)]
     pub features: FeaturesConfig,
@@ -35,6 +42,58 @@ pub struct Config {
     /// Logging configuration
     #[serde(default)]
     pub logging: LoggingConfig,
+
+    /// Request size and payload limits
+    #[serde(default)]
+    pub limits: LimitsConfig,
+
+    /// `Idempotency-Key` handling for POST requests
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
+
+    /// Queue-depth thresholds above which writes are rejected
+    #[serde(default)]
+    pub backpressure: BackpressureConfig,
+
+    /// WAL shipping to a warm standby node
+    #[serde(default)]
+    pub replication: ReplicationConfig,
+
+    /// Leader election among processes sharing the metadata database
+    #[serde(default)]
+    pub leader_election: LeaderElectionConfig,
+
+    /// Audit logging of vector writes and admin actions
+    #[serde(default)]
+    pub audit: AuditConfig,
+
+    /// Per-tenant usage metering for billing
+    #[serde(default)]
+    pub metering: MeteringConfig,
+
+    /// Ops-only runtime diagnostics under `/debug`
+    #[serde(default)]
+    pub debug: DebugConfig,
+
+    /// Hot/warm/cold tiering of collections by access pattern
+    #[serde(default)]
+    pub tiering: TieringConfig,
+
+    /// Applying config file changes without a restart
+    #[serde(default)]
+    pub reload: ReloadConfig,
+
+    /// Draining before shutdown (`POST /admin/drain` and SIGTERM)
+    #[serde(default)]
+    pub drain: DrainConfig,
+
+    /// Change data capture to Kafka or NATS
+    #[serde(default)]
+    pub cdc: CdcConfig,
+
+    /// Scheduled collection snapshots with retention
+    #[serde(default)]
+    pub snapshots: SnapshotsConfig,
 }
 
 /// Server configuration (host, port, protocol)
@@ -55,6 +114,101 @@ pub struct ServerConfig {
     /// Request timeout in seconds (default: 30)
     #[serde(default = "default_timeout")]
     pub timeout_seconds: u64,
+
+    /// TLS configuration (default: disabled, plaintext)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+
+    /// Announced removal date of the REST v1 API, sent as the `Sunset` header
+    /// on v1 responses (HTTP-date, default: none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_v1_sunset: Option<String>,
+
+    /// CORS for browser clients of the REST API (default: disabled)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<CorsConfig>,
+
+    /// REST response compression
+    #[serde(default)]
+    pub compression: CompressionConfig,
+}
+
+/// REST response compression (gzip/zstd, negotiated via `Accept-Encoding`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Enable response compression (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Responses smaller than this are sent uncompressed (default: 1024)
+    #[serde(default = "default_compression_min_size")]
+    pub min_size_bytes: u16,
+
+    /// Content types eligible for compression, matched on the media type
+    /// (default: application/json, application/x-ndjson, text/plain)
+    #[serde(default = "default_compression_content_types")]
+    pub content_types: Vec<String>,
+}
+
+/// CORS configuration for the REST server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API, e.g. "https://dashboard.example.com" ("*" = any)
+    pub allowed_origins: Vec<String>,
+
+    /// Allowed request methods (default: GET, POST, PUT, DELETE)
+    #[serde(default = "default_cors_methods")]
+    pub allowed_methods: Vec<String>,
+
+    /// Allowed request headers (default: content-type, authorization, x-api-key)
+    #[serde(default = "default_cors_headers")]
+    pub allowed_headers: Vec<String>,
+
+    /// Allow cookies/authorization on cross-origin requests (default: false).
+    /// Cannot be combined with a "*" origin.
+    #[serde(default)]
+    pub allow_credentials: bool,
+
+    /// How long browsers may cache preflight responses, in seconds (default: 3600)
+    #[serde(default = "default_cors_max_age")]
+    pub max_age_seconds: u64,
+}
+
+impl CorsConfig {
+    /// CORS allowing `origins` with default methods and headers.
+    pub fn new(origins: Vec<String>) -> Self {
+        Self {
+            allowed_origins: origins,
+            allowed_methods: default_cors_methods(),
+            allowed_headers: default_cors_headers(),
+            allow_credentials: false,
+            max_age_seconds: default_cors_max_age(),
+        }
+    }
+
+    /// Whether any origin is allowed.
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+}
+
+/// TLS configuration shared by the REST and gRPC servers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Path to the PEM-encoded server certificate chain
+    pub cert_path: PathBuf,
+
+    /// Path to the PEM-encoded server private key
+    pub key_path: PathBuf,
+
+    /// Path to a PEM-encoded CA bundle for verifying client certificates.
+    /// When set, clients must present a valid certificate (mTLS).
+    #[serde(default)]
+    pub client_ca_path: Option<PathBuf>,
+
+    /// How often to check certificate files for rotation, in seconds (default: 60, 0 = never)
+    #[serde(default = "default_tls_reload_interval")]
+    pub reload_interval_seconds: u64,
 }
 
 /// Database configuration
@@ -76,7 +230,7 @@ pub struct DatabaseConfig {
 /// Embedding provider configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingConfig {
-    /// Embedding provider type: "mlx", "python-bridge", "mock" (default: "mlx")
+    /// Embedding provider type: "mlx", "python-bridge", "http", "candle", "clip", "mock" (default: "mlx")
     #[serde(default = "default_embedding_provider")]
     pub provider: String,
 
@@ -87,6 +241,151 @@ pub struct EmbeddingConfig {
     /// Optional path to Python executable for python-bridge provider
     #[serde(default)]
     pub python_path: Option<String>,
+
+    /// Local model directory for the candle provider (tokenizer.json plus a
+    /// `.gguf` file or model.safetensors with config.json) or the clip
+    /// provider (text_model.onnx, vision_model.onnx and tokenizer.json)
+    #[serde(default)]
+    pub model_path: Option<PathBuf>,
+
+    /// Endpoint, templates and auth for the http provider
+    #[serde(default)]
+    pub http: Option<HttpProviderConfig>,
+
+    /// Hosts the clip provider may download image URLs from; empty allows
+    /// any host resolving to public addresses (default: empty)
+    #[serde(default)]
+    pub image_url_hosts: Vec<String>,
+
+    /// Provider failure rate that opens the circuit breaker (default: 0.5)
+    #[serde(default = "default_embedding_failure_threshold")]
+    pub circuit_failure_threshold: f64,
+
+    /// Seconds the circuit stays open before probing the provider (default: 30)
+    #[serde(default = "default_embedding_circuit_cooldown")]
+    pub circuit_cooldown_seconds: u64,
+
+    /// Embeddings kept in the result cache; 0 disables it (default: 10000)
+    #[serde(default = "default_embedding_cache_max_entries")]
+    pub cache_max_entries: usize,
+
+    /// File persisting the embedding cache across restarts (default: memory only)
+    #[serde(default)]
+    pub cache_path: Option<PathBuf>,
+
+    /// Most texts coalesced into one provider call (default: 32)
+    #[serde(default = "default_embedding_max_batch_size")]
+    pub max_batch_size: usize,
+
+    /// Milliseconds a request waits for others to join its batch; 0 disables
+    /// micro-batching (default: 5)
+    #[serde(default = "default_embedding_max_batch_wait_ms")]
+    pub max_batch_wait_ms: u64,
+
+    /// Provider calls allowed to run at once across all models; 0 means
+    /// unlimited (default: 0)
+    #[serde(default)]
+    pub max_concurrent_requests: usize,
+
+    /// Milliseconds a call waits for a free slot before failing with 503
+    /// (default: 1000)
+    #[serde(default = "default_embedding_concurrency_wait_ms")]
+    pub concurrency_wait_ms: u64,
+
+    /// Embed requests per second allowed per tenant; 0 means unlimited
+    /// (default: 0)
+    #[serde(default)]
+    pub tenant_qps: u32,
+
+    /// Handling of inputs over the model's token limit: "truncate_tail",
+    /// "truncate_head", "chunk_and_average" or "error" (default: "truncate_tail")
+    #[serde(default)]
+    pub truncation: TruncationStrategy,
+
+    /// Embed a dummy batch at startup so the model is loaded before traffic
+    /// arrives; `/api/v1/embed/health` reports not ready until it finishes
+    /// (default: true)
+    #[serde(default = "default_true")]
+    pub warmup: bool,
+
+    /// Additional models collections can select via `embedding_model`
+    #[serde(default)]
+    pub models: Vec<EmbeddingModelConfig>,
+
+    /// Additional models kept loaded; the least recently used is unloaded
+    /// beyond this (default: 4)
+    #[serde(default = "default_embedding_max_loaded_models")]
+    pub max_loaded_models: usize,
+}
+
+/// An additional embedding model, loaded on first use
+///
+/// Cache, batching, truncation and circuit breaker settings are inherited from
+/// `[embedding]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingModelConfig {
+    /// Name collections and `/embed` requests refer to the model by
+    pub name: String,
+
+    /// Embedding provider type: "python-bridge", "http", "candle", "clip", "mock"
+    pub provider: String,
+
+    /// Model identifier passed to the provider (default: `name`)
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Optional path to Python executable for python-bridge provider
+    #[serde(default)]
+    pub python_path: Option<String>,
+
+    /// Local model directory for the candle and clip providers
+    #[serde(default)]
+    pub model_path: Option<PathBuf>,
+
+    /// Endpoint, templates and auth for the http provider
+    #[serde(default)]
+    pub http: Option<HttpProviderConfig>,
+}
+
+impl EmbeddingConfig {
+    /// Settings for the additional model `name`, or `None` if it is not configured.
+    pub fn for_model(&self, name: &str) -> Option<EmbeddingConfig> {
+        let model = self.models.iter().find(|m| m.name == name)?;
+        Some(EmbeddingConfig {
+            provider: model.provider.clone(),
+            model: model.model.clone().unwrap_or_else(|| model.name.clone()),
+            python_path: model.python_path.clone(),
+            model_path: model.model_path.clone(),
+            http: model.http.clone(),
+            models: Vec::new(),
+            ..self.clone()
+        })
+    }
+}
+
+/// Cross-encoder reranker configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankConfig {
+    /// Reranker type: "none", "onnx", "http", "mock" (default: "none")
+    #[serde(default = "default_rerank_provider")]
+    pub provider: String,
+
+    /// Model name (default: "cross-encoder/ms-marco-MiniLM-L-6-v2")
+    #[serde(default = "default_rerank_model")]
+    pub model: String,
+
+    /// ONNX model file for the onnx provider; tokenizer.json is read from the
+    /// same directory
+    #[serde(default)]
+    pub model_path: Option<PathBuf>,
+
+    /// Endpoint, template and auth for the http provider
+    #[serde(default)]
+    pub http: Option<HttpRerankConfig>,
+
+    /// Most documents scored per rerank request (default: 100)
+    #[serde(default = "default_rerank_max_documents")]
+    pub max_documents: usize,
 }
 
 /// Optional features configuration
@@ -103,6 +402,34 @@ pub struct FeaturesConfig {
     /// Enable auto-initialization of default tenant/database (default: true)
     #[serde(default = "default_true")]
     pub auto_initialize: bool,
+
+    /// Enable rate limiting from tenant QPS quotas, by client IP for requests
+    /// without a known API key (default: true)
+    #[serde(default = "default_true")]
+    pub rate_limiting_enabled: bool,
+
+    /// Scope REST collection operations by the `X-Tenant-ID` header (default: false)
+    ///
+    /// Requests without the header act for the default tenant.
+    #[serde(default)]
+    pub multi_tenancy_enabled: bool,
+
+    /// Require a valid API key on REST `/api/` routes and gRPC collection
+    /// calls, so collection allowlists cannot be bypassed by leaving the key
+    /// out (default: false)
+    #[serde(default)]
+    pub require_api_key: bool,
+
+    /// Default state of runtime feature flags, by name (default: built-in defaults)
+    ///
+    /// Flags set through `/admin/feature-flags` take precedence.
+    #[serde(default)]
+    pub flags: BTreeMap<String, bool>,
+
+    /// How often each server re-reads feature flags from the metadata
+    /// database, in seconds (default: 30)
+    #[serde(default = "default_flag_refresh_interval")]
+    pub flag_refresh_interval_seconds: u64,
 }
 
 /// HNSW index tuning parameters
@@ -120,6 +447,13 @@ pub struct HnswConfig {
     /// Collections with fewer documents use brute-force search
     #[serde(default = "default_hnsw_threshold")]
     pub threshold: usize,
+
+    /// Persist HNSW graphs across restarts (default: false)
+    /// Large collections use the native HNSW index, whose graph is saved on
+    /// snapshot, compaction and shutdown and restored instead of rebuilt.
+    /// Quantized collections are not persisted
+    #[serde(default)]
+    pub persist_graph: bool,
 }
 
 /// Logging configuration
@@ -134,6 +468,410 @@ pub struct LoggingConfig {
     pub format: String,
 }
 
+/// Request size and payload limits
+///
+/// Enforced by the API layers before requests reach the index, so abusive
+/// batches are rejected with 413/422 instead of exhausting memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitsConfig {
+    /// Maximum request body size in bytes (default: 16 MiB)
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+
+    /// Maximum vectors accepted in a single batch request (default: 1000)
+    #[serde(default = "default_max_batch_vectors")]
+    pub max_batch_vectors: usize,
+
+    /// Maximum serialized metadata + external_id size per document in bytes (default: 64 KiB)
+    #[serde(default = "default_max_document_payload_bytes")]
+    pub max_document_payload_bytes: usize,
+
+    /// Maximum nesting depth of document metadata (default: 16)
+    #[serde(default = "default_max_metadata_depth")]
+    pub max_metadata_depth: usize,
+}
+
+/// Replay of responses to POST requests carrying an `Idempotency-Key` header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyConfig {
+    /// Honor `Idempotency-Key` headers (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// How long a response is replayed for retries, in seconds (default: 86400)
+    #[serde(default = "default_idempotency_window")]
+    pub window_seconds: u64,
+
+    /// Maximum keys remembered; the oldest are forgotten first (default: 10000)
+    #[serde(default = "default_idempotency_max_entries")]
+    pub max_entries: usize,
+}
+
+/// Queue-depth admission control for writes and embedding requests
+///
+/// When a queue is over its threshold, new work for it is rejected with
+/// 429/503 and a `Retry-After` that grows with the backlog, instead of being
+/// accepted and later dropped to the dead letter queue. A threshold of 0
+/// disables the check for that queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackpressureConfig {
+    /// Reject writes when over a threshold (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Documents waiting for their first S3 upload, summed over collections (default: 10000)
+    #[serde(default = "default_max_pending_uploads")]
+    pub max_pending_uploads: usize,
+
+    /// Failed S3 uploads waiting for retry, summed over collections (default: 1000)
+    #[serde(default = "default_max_pending_retries")]
+    pub max_pending_retries: usize,
+
+    /// Embedding requests in flight (default: 256)
+    #[serde(default = "default_max_pending_embeddings")]
+    pub max_pending_embeddings: usize,
+
+    /// Upper bound on the `Retry-After` sent to clients, in seconds (default: 30)
+    #[serde(default = "default_max_retry_after")]
+    pub max_retry_after_seconds: u64,
+}
+
+/// Leader election for singleton background work
+///
+/// Processes sharing the metadata database (REST and gRPC servers, or several
+/// replicas of either) compete for one lease; only the holder runs
+/// maintenance that must happen exactly once. A leader that stops renewing
+/// loses the role after `lease_ttl_seconds`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderElectionConfig {
+    /// Take part in the election (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How long a lease lasts without renewal, in seconds (default: 15)
+    #[serde(default = "default_lease_ttl")]
+    pub lease_ttl_seconds: u64,
+
+    /// Identifier this process holds the lease under (default: host name,
+    /// process ID and start time)
+    #[serde(default)]
+    pub holder_id: Option<String>,
+}
+
+/// Audit logging beyond control-plane actions
+///
+/// Entries go to the `audit_logs` table under the tenant owning the affected
+/// collection. Vector writes can be sampled: with `write_sample_rate = 0.01`
+/// one in every hundred insert/delete calls is recorded, and each entry
+/// carries the rate so counts can be scaled back up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Record vector inserts, upserts and deletes (default: false)
+    #[serde(default)]
+    pub data_plane: bool,
+
+    /// Fraction of vector write calls recorded, in (0, 1] (default: 1.0)
+    #[serde(default = "default_audit_write_sample_rate")]
+    pub write_sample_rate: f64,
+
+    /// Record admin actions such as DLQ retries and circuit breaker resets;
+    /// these are never sampled (default: true)
+    #[serde(default = "default_true")]
+    pub admin_actions: bool,
+}
+
+/// Per-tenant usage metering
+///
+/// Queries, inserted vectors, stored bytes and embedding tokens are rolled up
+/// per tenant and hour in the metadata database, and exported for billing by
+/// `GET /admin/usage`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeteringConfig {
+    /// Count usage (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How often counts are written to the metadata database and stored
+    /// bytes are sampled, in seconds (default: 60)
+    #[serde(default = "default_metering_flush_interval")]
+    pub flush_interval_seconds: u64,
+}
+
+/// Ops-only runtime diagnostics
+///
+/// `/debug/tasks`, `/debug/queues`, `/debug/locks` and `/debug/allocator`
+/// report Tokio tasks, queue depths, lock contention and heap statistics.
+/// Every request needs an API key with the `admin::debug` permission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugConfig {
+    /// Serve the `/debug` endpoints (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How long `/debug/tasks` waits for workers to yield before giving up on
+    /// the task dump, in milliseconds (default: 2000)
+    #[serde(default = "default_debug_task_dump_timeout")]
+    pub task_dump_timeout_ms: u64,
+}
+
+/// Automatic hot/warm/cold tiering
+///
+/// Idle collections are demoted to warm and then cold storage; frequently
+/// accessed warm collections are promoted back to hot.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TieringConfig {
+    /// Run the tiering manager (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Demotion and promotion thresholds and the worker interval
+    #[serde(flatten)]
+    pub policy: TieringPolicyConfig,
+}
+
+/// Hot reload of the config file
+///
+/// On SIGHUP, or when the file's modification time changes, the server
+/// re-reads it and applies `logging.level`, `features.rate_limiting_enabled`,
+/// `embedding.tenant_qps` and the `[tiering]` thresholds in place, keeping
+/// the in-memory indexes. Changes to any other setting are logged and need a
+/// restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadConfig {
+    /// Reload on SIGHUP and file changes (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// How often the file is checked for changes, in seconds; 0 reloads on
+    /// SIGHUP only (default: 10)
+    #[serde(default = "default_reload_watch_interval")]
+    pub watch_interval_seconds: u64,
+}
+
+/// Draining before shutdown
+///
+/// `POST /admin/drain` and SIGTERM fail readiness, keep serving for the
+/// grace period so load balancers stop routing here, then refuse new work
+/// and wait for in-flight requests and S3 upload queues to empty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrainConfig {
+    /// Seconds to keep serving after readiness starts failing; should exceed
+    /// the load balancer's health check interval (default: 10)
+    #[serde(default = "default_drain_grace_period")]
+    pub grace_period_seconds: u64,
+
+    /// Seconds to wait for in-flight requests and uploads before giving up
+    /// (default: 30)
+    #[serde(default = "default_drain_timeout")]
+    pub timeout_seconds: u64,
+}
+
+/// Change data capture
+///
+/// Tails every collection's write-ahead log and publishes document upserts
+/// and deletes, with their LSN, to Kafka or NATS JetStream. The last
+/// published LSN per collection is checkpointed after the broker
+/// acknowledges it, so events are delivered at least once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdcConfig {
+    /// Publish change events (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// "kafka" or "nats"; needs akidb built with the feature of the same name (default: "kafka")
+    #[serde(default = "default_cdc_sink")]
+    pub sink: String,
+
+    /// Kafka bootstrap servers (e.g. "kafka:9092") or NATS server URL (e.g. "nats://nats:4222")
+    #[serde(default)]
+    pub servers: String,
+
+    /// Events of a collection go to the topic or subject "{topic_prefix}.{collection}" (default: "akidb.cdc")
+    #[serde(default = "default_cdc_topic_prefix")]
+    pub topic_prefix: String,
+
+    /// Collections to publish, by name or name prefix ending in `*` (default: all)
+    #[serde(default)]
+    pub collections: Vec<String>,
+
+    /// Most events published before a checkpoint (default: 500)
+    #[serde(default = "default_cdc_batch_size")]
+    pub batch_size: usize,
+
+    /// Longest wait between WAL scans, in milliseconds (default: 1000)
+    #[serde(default = "default_cdc_poll_interval")]
+    pub poll_interval_ms: u64,
+
+    /// File recording the last published LSN per collection (default: "cdc_checkpoint.json")
+    #[serde(default = "default_cdc_checkpoint_path")]
+    pub checkpoint_path: PathBuf,
+}
+
+/// Scheduled collection snapshots
+///
+/// Snapshots are otherwise only taken by compaction. Each schedule takes a
+/// snapshot of the matching collections every `interval_seconds` or every
+/// `every_ops` writes, whichever comes first, as a `snapshot` job, then
+/// deletes all but the newest `keep_last` snapshots of the collection
+/// (compaction snapshots included).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotsConfig {
+    /// How often schedules are checked, in seconds (default: 60)
+    #[serde(default = "default_snapshot_check_interval")]
+    pub check_interval_seconds: u64,
+
+    /// Snapshot schedules; the first one matching a collection applies (default: none)
+    #[serde(default)]
+    pub schedules: Vec<SnapshotSchedule>,
+}
+
+/// When to snapshot the collections matching a name, and how many to keep
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotSchedule {
+    /// Collection name, or a name prefix ending in `*` ("*" matches all)
+    pub collections: String,
+
+    /// Snapshot when the newest snapshot is older than this, in seconds
+    #[serde(default)]
+    pub interval_seconds: Option<u64>,
+
+    /// Snapshot after this many writes since the last scheduled snapshot
+    #[serde(default)]
+    pub every_ops: Option<u64>,
+
+    /// Snapshots kept per collection; older ones are deleted (default: 7)
+    #[serde(default = "default_snapshot_keep_last")]
+    pub keep_last: usize,
+}
+
+impl SnapshotSchedule {
+    /// Whether the schedule's pattern matches the collection `name`.
+    pub fn matches(&self, name: &str) -> bool {
+        match self.collections.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == self.collections,
+        }
+    }
+
+    /// Checks the pattern, the triggers and the retention.
+    pub fn validate(&self) -> Result<(), String> {
+        let pattern = self
+            .collections
+            .strip_suffix('*')
+            .unwrap_or(&self.collections);
+        if self.collections.is_empty() || pattern.contains('*') {
+            return Err(format!(
+                "snapshot schedule pattern must be a collection name or a prefix ending in '*' (got \"{}\")",
+                self.collections
+            ));
+        }
+        if self.interval_seconds.is_none() && self.every_ops.is_none() {
+            return Err(format!(
+                "snapshot schedule for \"{}\" needs interval_seconds or every_ops",
+                self.collections
+            ));
+        }
+        if self.interval_seconds == Some(0) || self.every_ops == Some(0) || self.keep_last == 0 {
+            return Err(format!(
+                "snapshot schedule for \"{}\": interval_seconds, every_ops and keep_last must be greater than 0",
+                self.collections
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Node-level replication by WAL shipping
+///
+/// A primary serves its collections' write-ahead logs over gRPC. A standby
+/// follows `primary_url` and applies every entry to its own indexes, so it
+/// can take over without rebuilding them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicationConfig {
+    /// Role of this node: "none", "primary" or "standby" (default: "none")
+    #[serde(default = "default_replication_role")]
+    pub role: String,
+
+    /// gRPC address of the primary, required for standbys (e.g. "http://primary:9090")
+    #[serde(default)]
+    pub primary_url: Option<String>,
+
+    /// Name the standby reports to the primary (default: "standby")
+    #[serde(default = "default_standby_id")]
+    pub standby_id: String,
+
+    /// File where a standby records the last applied LSN per collection (default: "replication_state.json")
+    #[serde(default = "default_replication_state_path")]
+    pub state_path: PathBuf,
+
+    /// Longest wait between WAL scans on the primary, in milliseconds; also the heartbeat interval (default: 1000)
+    #[serde(default = "default_replication_poll_interval")]
+    pub poll_interval_ms: u64,
+
+    /// Delay before a standby reconnects after the stream fails, in milliseconds (default: 1000)
+    #[serde(default = "default_replication_reconnect_backoff")]
+    pub reconnect_backoff_ms: u64,
+
+    /// Lag above which a standby is reported unhealthy, in seconds (default: 30)
+    #[serde(default = "default_replication_max_lag")]
+    pub max_lag_seconds: u64,
+
+    /// Collections a primary ships (default: all). Can be replaced on a live
+    /// primary with the `SetRules` RPC.
+    #[serde(default)]
+    pub rules: Vec<ReplicationRule>,
+
+    /// Shared secret authenticating standbys to the primary, sent as
+    /// `x-replication-token` metadata; required for primaries and standbys.
+    /// Prefer the AKIDB_REPLICATION_TOKEN environment variable.
+    #[serde(default)]
+    pub auth_token: Option<String>,
+}
+
+/// Selects collections to replicate by name
+///
+/// The matching rule with the highest priority decides; with any rules
+/// configured, collections no rule matches are not replicated. Replicated
+/// collections are shipped in priority order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplicationRule {
+    /// Collection name, or a name prefix ending in `*` ("*" matches all)
+    pub collections: String,
+
+    /// Replicate the matching collections; false excludes them (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Higher priorities win over overlapping rules and ship first (default: 0)
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl ReplicationRule {
+    /// Whether the rule's pattern matches the collection `name`.
+    pub fn matches(&self, name: &str) -> bool {
+        match self.collections.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == self.collections,
+        }
+    }
+
+    /// Checks the pattern: non-empty, with `*` only at the end.
+    pub fn validate(&self) -> Result<(), String> {
+        let pattern = self
+            .collections
+            .strip_suffix('*')
+            .unwrap_or(&self.collections);
+        if self.collections.is_empty() || pattern.contains('*') {
+            return Err(format!(
+                "replication rule pattern must be a collection name or a prefix ending in '*' (got \"{}\")",
+                self.collections
+            ));
+        }
+        Ok(())
+    }
+}
+
 // Default value functions
 fn default_host() -> String {
     "0.0.0.0".to_string()
@@ -151,6 +889,42 @@ fn default_timeout() -> u64 {
     30
 }
 
+fn default_tls_reload_interval() -> u64 {
+    60
+}
+
+fn default_idempotency_window() -> u64 {
+    86_400
+}
+
+fn default_idempotency_max_entries() -> usize {
+    10_000
+}
+
+fn default_compression_min_size() -> u16 {
+    1024
+}
+
+fn default_compression_content_types() -> Vec<String> {
+    ["application/json", "application/x-ndjson", "text/plain"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_cors_methods() -> Vec<String> {
+    ["GET", "POST", "PUT", "DELETE"].map(String::from).to_vec()
+}
+
+fn default_cors_headers() -> Vec<String> {
+    ["content-type", "authorization", "x-api-key"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_cors_max_age() -> u64 {
+    3600
+}
+
 fn default_db_path() -> String {
     "sqlite://akidb.db".to_string()
 }
@@ -187,6 +961,122 @@ fn default_log_format() -> String {
     "pretty".to_string()
 }
 
+fn default_max_body_bytes() -> usize {
+    16 * 1024 * 1024
+}
+
+fn default_max_batch_vectors() -> usize {
+    1_000
+}
+
+fn default_max_document_payload_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_max_metadata_depth() -> usize {
+    16
+}
+
+fn default_max_pending_uploads() -> usize {
+    10_000
+}
+
+fn default_max_pending_retries() -> usize {
+    1_000
+}
+
+fn default_max_pending_embeddings() -> usize {
+    256
+}
+
+fn default_max_retry_after() -> u64 {
+    30
+}
+
+fn default_replication_role() -> String {
+    "none".to_string()
+}
+
+fn default_standby_id() -> String {
+    "standby".to_string()
+}
+
+fn default_replication_state_path() -> PathBuf {
+    PathBuf::from("replication_state.json")
+}
+
+fn default_replication_poll_interval() -> u64 {
+    1_000
+}
+
+fn default_replication_reconnect_backoff() -> u64 {
+    1_000
+}
+
+fn default_replication_max_lag() -> u64 {
+    30
+}
+
+fn default_lease_ttl() -> u64 {
+    15
+}
+
+fn default_audit_write_sample_rate() -> f64 {
+    1.0
+}
+
+fn default_metering_flush_interval() -> u64 {
+    60
+}
+
+fn default_flag_refresh_interval() -> u64 {
+    30
+}
+
+fn default_debug_task_dump_timeout() -> u64 {
+    2000
+}
+
+fn default_reload_watch_interval() -> u64 {
+    10
+}
+
+fn default_drain_grace_period() -> u64 {
+    10
+}
+
+fn default_drain_timeout() -> u64 {
+    30
+}
+
+fn default_snapshot_check_interval() -> u64 {
+    60
+}
+
+fn default_snapshot_keep_last() -> usize {
+    7
+}
+
+fn default_cdc_sink() -> String {
+    "kafka".to_string()
+}
+
+fn default_cdc_topic_prefix() -> String {
+    "akidb.cdc".to_string()
+}
+
+fn default_cdc_batch_size() -> usize {
+    500
+}
+
+fn default_cdc_poll_interval() -> u64 {
+    1000
+}
+
+fn default_cdc_checkpoint_path() -> PathBuf {
+    PathBuf::from("cdc_checkpoint.json")
+}
+
 fn default_embedding_provider() -> String {
     "mlx".to_string()
 }
@@ -195,15 +1085,69 @@ fn default_embedding_model() -> String {
     "sentence-transformers/all-MiniLM-L6-v2".to_string()
 }
 
+fn default_embedding_failure_threshold() -> f64 {
+    0.5
+}
+
+fn default_embedding_circuit_cooldown() -> u64 {
+    30
+}
+
+fn default_embedding_cache_max_entries() -> usize {
+    10_000
+}
+
+fn default_embedding_max_batch_size() -> usize {
+    32
+}
+
+fn default_embedding_max_batch_wait_ms() -> u64 {
+    5
+}
+
+fn default_embedding_concurrency_wait_ms() -> u64 {
+    1_000
+}
+
+fn default_embedding_max_loaded_models() -> usize {
+    4
+}
+
+fn default_rerank_provider() -> String {
+    "none".to_string()
+}
+
+fn default_rerank_model() -> String {
+    "cross-encoder/ms-marco-MiniLM-L-6-v2".to_string()
+}
+
+fn default_rerank_max_documents() -> usize {
+    100
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             server: ServerConfig::default(),
             database: DatabaseConfig::default(),
             embedding: EmbeddingConfig::default(),
+            rerank: RerankConfig::default(),
             features: FeaturesConfig::default(),
             hnsw: HnswConfig::default(),
             logging: LoggingConfig::default(),
+            limits: LimitsConfig::default(),
+            idempotency: IdempotencyConfig::default(),
+            backpressure: BackpressureConfig::default(),
+            replication: ReplicationConfig::default(),
+            leader_election: LeaderElectionConfig::default(),
+            audit: AuditConfig::default(),
+            metering: MeteringConfig::default(),
+            debug: DebugConfig::default(),
+            tiering: TieringConfig::default(),
+            reload: ReloadConfig::default(),
+            drain: DrainConfig::default(),
+            cdc: CdcConfig::default(),
+            snapshots: SnapshotsConfig::default(),
         }
     }
 }
@@ -215,6 +1159,20 @@ impl Default for ServerConfig {
             rest_port: default_rest_port(),
             grpc_port: default_grpc_port(),
             timeout_seconds: default_timeout(),
+            tls: None,
+            api_v1_sunset: None,
+            cors: None,
+            compression: CompressionConfig::default(),
+        }
+    }
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_size_bytes: default_compression_min_size(),
+            content_types: default_compression_content_types(),
         }
     }
 }
@@ -235,6 +1193,34 @@ impl Default for EmbeddingConfig {
             provider: default_embedding_provider(),
             model: default_embedding_model(),
             python_path: None,
+            model_path: None,
+            http: None,
+            image_url_hosts: Vec::new(),
+            circuit_failure_threshold: default_embedding_failure_threshold(),
+            circuit_cooldown_seconds: default_embedding_circuit_cooldown(),
+            cache_max_entries: default_embedding_cache_max_entries(),
+            cache_path: None,
+            max_batch_size: default_embedding_max_batch_size(),
+            max_batch_wait_ms: default_embedding_max_batch_wait_ms(),
+            max_concurrent_requests: 0,
+            concurrency_wait_ms: default_embedding_concurrency_wait_ms(),
+            tenant_qps: 0,
+            truncation: TruncationStrategy::default(),
+            warmup: true,
+            models: Vec::new(),
+            max_loaded_models: default_embedding_max_loaded_models(),
+        }
+    }
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            provider: default_rerank_provider(),
+            model: default_rerank_model(),
+            model_path: None,
+            http: None,
+            max_documents: default_rerank_max_documents(),
         }
     }
 }
@@ -245,6 +1231,11 @@ impl Default for FeaturesConfig {
             metrics_enabled: true,
             vector_persistence_enabled: true,
             auto_initialize: true,
+            rate_limiting_enabled: true,
+            multi_tenancy_enabled: false,
+            require_api_key: false,
+            flags: BTreeMap::new(),
+            flag_refresh_interval_seconds: default_flag_refresh_interval(),
         }
     }
 }
@@ -255,6 +1246,7 @@ impl Default for HnswConfig {
             m: default_hnsw_m(),
             ef_construction: default_hnsw_ef_construction(),
             threshold: default_hnsw_threshold(),
+            persist_graph: false,
         }
     }
 }
@@ -268,7 +1260,139 @@ impl Default for LoggingConfig {
     }
 }
 
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: default_max_body_bytes(),
+            max_batch_vectors: default_max_batch_vectors(),
+            max_document_payload_bytes: default_max_document_payload_bytes(),
+            max_metadata_depth: default_max_metadata_depth(),
+        }
+    }
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_seconds: default_idempotency_window(),
+            max_entries: default_idempotency_max_entries(),
+        }
+    }
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_pending_uploads: default_max_pending_uploads(),
+            max_pending_retries: default_max_pending_retries(),
+            max_pending_embeddings: default_max_pending_embeddings(),
+            max_retry_after_seconds: default_max_retry_after(),
+        }
+    }
+}
+
+impl Default for ReplicationConfig {
+    fn default() -> Self {
+        Self {
+            role: default_replication_role(),
+            primary_url: None,
+            standby_id: default_standby_id(),
+            state_path: default_replication_state_path(),
+            poll_interval_ms: default_replication_poll_interval(),
+            reconnect_backoff_ms: default_replication_reconnect_backoff(),
+            max_lag_seconds: default_replication_max_lag(),
+            rules: Vec::new(),
+            auth_token: None,
+        }
+    }
+}
+
+impl Default for LeaderElectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lease_ttl_seconds: default_lease_ttl(),
+            holder_id: None,
+        }
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            data_plane: false,
+            write_sample_rate: default_audit_write_sample_rate(),
+            admin_actions: true,
+        }
+    }
+}
+
+impl Default for MeteringConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            flush_interval_seconds: default_metering_flush_interval(),
+        }
+    }
+}
+
+impl Default for DebugConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            task_dump_timeout_ms: default_debug_task_dump_timeout(),
+        }
+    }
+}
+
+impl Default for ReloadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            watch_interval_seconds: default_reload_watch_interval(),
+        }
+    }
+}
+
+impl Default for DrainConfig {
+    fn default() -> Self {
+        Self {
+            grace_period_seconds: default_drain_grace_period(),
+            timeout_seconds: default_drain_timeout(),
+        }
+    }
+}
+
+impl Default for SnapshotsConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_seconds: default_snapshot_check_interval(),
+            schedules: Vec::new(),
+        }
+    }
+}
+
+impl Default for CdcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sink: default_cdc_sink(),
+            servers: String::new(),
+            topic_prefix: default_cdc_topic_prefix(),
+            collections: Vec::new(),
+            batch_size: default_cdc_batch_size(),
+            poll_interval_ms: default_cdc_poll_interval(),
+            checkpoint_path: default_cdc_checkpoint_path(),
+        }
+    }
+}
+
 impl Config {
+    /// File read by [`load`](Self::load), relative to the working directory
+    pub const DEFAULT_PATH: &'static str = "config.toml";
+
     /// Load configuration from a TOML file.
     ///
     /// Returns an error if the file doesn't exist or has invalid TOML syntax.
@@ -293,10 +1417,19 @@ impl Config {
     /// - `AKIDB_GRPC_PORT` - gRPC API port
     /// - `AKIDB_DB_PATH` - Database path
     /// - `AKIDB_LOG_LEVEL` - Log level
+    /// - `AKIDB_TLS_CERT_PATH`, `AKIDB_TLS_KEY_PATH` - Enable TLS
+    /// - `AKIDB_TLS_CLIENT_CA_PATH` - Require client certificates (mTLS)
+    /// - `AKIDB_CORS_ALLOWED_ORIGINS` - Comma-separated origins; enables CORS
     pub fn load() -> Result<Self, ConfigError> {
-        // Try to load from config.toml, otherwise use defaults
-        let mut config = if std::path::Path::new("config.toml").exists() {
-            Self::from_file("config.toml")?
+        Self::load_from(Self::DEFAULT_PATH)
+    }
+
+    /// Like [`load`](Self::load), reading `path` instead of `config.toml`.
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        // Try to load from the file, otherwise use defaults
+        let path = path.as_ref();
+        let mut config = if path.exists() {
+            Self::from_file(path)?
         } else {
             Self::default()
         };
@@ -351,6 +1484,24 @@ impl Config {
             }
         }
 
+        if let Ok(enabled) = std::env::var("AKIDB_RATE_LIMITING_ENABLED") {
+            if let Ok(enabled) = enabled.parse() {
+                self.features.rate_limiting_enabled = enabled;
+            }
+        }
+
+        if let Ok(enabled) = std::env::var("AKIDB_MULTI_TENANCY_ENABLED") {
+            if let Ok(enabled) = enabled.parse() {
+                self.features.multi_tenancy_enabled = enabled;
+            }
+        }
+
+        if let Ok(bytes) = std::env::var("AKIDB_MAX_BODY_BYTES") {
+            if let Ok(bytes) = bytes.parse() {
+                self.limits.max_body_bytes = bytes;
+            }
+        }
+
         if let Ok(provider) = std::env::var("AKIDB_EMBEDDING_PROVIDER") {
             self.embedding.provider = provider;
         }
@@ -359,8 +1510,56 @@ impl Config {
             self.embedding.model = model;
         }
 
-        if let Ok(python_path) = std::env::var("AKIDB_EMBEDDING_PYTHON_PATH") {
-            self.embedding.python_path = Some(python_path);
+        if let Ok(python_path) = std::env::var("AKIDB_EMBEDDING_PYTHON_PATH") {
+            self.embedding.python_path = Some(python_path);
+        }
+
+        if let (Ok(cert_path), Ok(key_path)) = (
+            std::env::var("AKIDB_TLS_CERT_PATH"),
+            std::env::var("AKIDB_TLS_KEY_PATH"),
+        ) {
+            let reload_interval_seconds = self
+                .server
+                .tls
+                .as_ref()
+                .map_or_else(default_tls_reload_interval, |tls| {
+                    tls.reload_interval_seconds
+                });
+            let client_ca_path = self
+                .server
+                .tls
+                .as_ref()
+                .and_then(|tls| tls.client_ca_path.clone());
+
+            self.server.tls = Some(TlsConfig {
+                cert_path: cert_path.into(),
+                key_path: key_path.into(),
+                client_ca_path,
+                reload_interval_seconds,
+            });
+        }
+
+        if let Ok(ca_path) = std::env::var("AKIDB_TLS_CLIENT_CA_PATH") {
+            if let Some(tls) = self.server.tls.as_mut() {
+                tls.client_ca_path = Some(ca_path.into());
+            }
+        }
+
+        if let Ok(token) = std::env::var("AKIDB_REPLICATION_TOKEN") {
+            self.replication.auth_token = Some(token);
+        }
+
+        if let Ok(origins) = std::env::var("AKIDB_CORS_ALLOWED_ORIGINS") {
+            let origins: Vec<String> = origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(String::from)
+                .collect();
+            match self.server.cors.as_mut() {
+                Some(cors) => cors.allowed_origins = origins,
+                None => self.server.cors = Some(CorsConfig::new(origins)),
+            }
         }
     }
 
@@ -452,6 +1651,55 @@ impl Config {
             ));
         }
 
+        // Validate TLS certificate paths
+        if let Some(tls) = &self.server.tls {
+            let mut paths = vec![
+                ("server.tls.cert_path", &tls.cert_path),
+                ("server.tls.key_path", &tls.key_path),
+            ];
+            if let Some(ca_path) = &tls.client_ca_path {
+                paths.push(("server.tls.client_ca_path", ca_path));
+            }
+
+            for (name, path) in paths {
+                if !path.exists() {
+                    return Err(ConfigError::ValidationError(format!(
+                        "{} does not exist: {}",
+                        name,
+                        path.display()
+                    )));
+                }
+            }
+        }
+
+        // Validate CORS settings
+        if let Some(cors) = &self.server.cors {
+            if cors.allowed_origins.is_empty() {
+                return Err(ConfigError::ValidationError(
+                    "server.cors.allowed_origins must not be empty".to_string(),
+                ));
+            }
+
+            // Browsers reject credentialed responses with a wildcard origin
+            if cors.allow_credentials && cors.allows_any_origin() {
+                return Err(ConfigError::ValidationError(
+                    "server.cors.allow_credentials cannot be used with a \"*\" origin".to_string(),
+                ));
+            }
+
+            let valid_methods = ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+            if let Some(method) = cors
+                .allowed_methods
+                .iter()
+                .find(|m| !valid_methods.contains(&m.to_ascii_uppercase().as_str()))
+            {
+                return Err(ConfigError::ValidationError(format!(
+                    "server.cors.allowed_methods contains unknown method: {}",
+                    method
+                )));
+            }
+        }
+
         // Validate HNSW parameters
         if self.hnsw.m < 2 || self.hnsw.m > 100 {
             return Err(ConfigError::ValidationError(
@@ -465,6 +1713,235 @@ impl Config {
             ));
         }
 
+        // Validate embedding provider settings
+        if self.embedding.provider == "http" && self.embedding.http.is_none() {
+            return Err(ConfigError::ValidationError(
+                "embedding.http must be set when embedding.provider = \"http\"".to_string(),
+            ));
+        }
+
+        if matches!(self.embedding.provider.as_str(), "candle" | "clip")
+            && self.embedding.model_path.is_none()
+        {
+            return Err(ConfigError::ValidationError(format!(
+                "embedding.model_path must be set when embedding.provider = \"{}\"",
+                self.embedding.provider
+            )));
+        }
+
+        if !(0.0..=1.0).contains(&self.embedding.circuit_failure_threshold) {
+            return Err(ConfigError::ValidationError(
+                "embedding.circuit_failure_threshold must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        if self.embedding.max_batch_size == 0 {
+            return Err(ConfigError::ValidationError(
+                "embedding.max_batch_size must be greater than 0".to_string(),
+            ));
+        }
+
+        if !self.embedding.models.is_empty() && self.embedding.max_loaded_models == 0 {
+            return Err(ConfigError::ValidationError(
+                "embedding.max_loaded_models must be greater than 0".to_string(),
+            ));
+        }
+
+        let mut model_names = std::collections::HashSet::new();
+        for model in &self.embedding.models {
+            if model.name.is_empty() || model.name == self.embedding.model {
+                return Err(ConfigError::ValidationError(format!(
+                    "embedding.models name must be non-empty and differ from embedding.model (got \"{}\")",
+                    model.name
+                )));
+            }
+            if !model_names.insert(model.name.as_str()) {
+                return Err(ConfigError::ValidationError(format!(
+                    "embedding.models contains duplicate name \"{}\"",
+                    model.name
+                )));
+            }
+            if model.provider == "http" && model.http.is_none() {
+                return Err(ConfigError::ValidationError(format!(
+                    "embedding.models \"{}\" needs http settings for provider \"http\"",
+                    model.name
+                )));
+            }
+            if matches!(model.provider.as_str(), "candle" | "clip") && model.model_path.is_none() {
+                return Err(ConfigError::ValidationError(format!(
+                    "embedding.models \"{}\" needs model_path for provider \"{}\"",
+                    model.name, model.provider
+                )));
+            }
+        }
+
+        // Validate reranker settings
+        match self.rerank.provider.as_str() {
+            "none" | "mock" => {}
+            "onnx" if self.rerank.model_path.is_none() => {
+                return Err(ConfigError::ValidationError(
+                    "rerank.model_path must be set when rerank.provider = \"onnx\"".to_string(),
+                ));
+            }
+            "http" if self.rerank.http.is_none() => {
+                return Err(ConfigError::ValidationError(
+                    "rerank.http must be set when rerank.provider = \"http\"".to_string(),
+                ));
+            }
+            "onnx" | "http" => {}
+            other => {
+                return Err(ConfigError::ValidationError(format!(
+                    "rerank.provider must be one of none, onnx, http, mock (got \"{}\")",
+                    other
+                )));
+            }
+        }
+
+        if self.rerank.max_documents == 0 {
+            return Err(ConfigError::ValidationError(
+                "rerank.max_documents must be greater than 0".to_string(),
+            ));
+        }
+
+        // Validate request limits
+        let limits = [
+            ("limits.max_body_bytes", self.limits.max_body_bytes),
+            ("limits.max_batch_vectors", self.limits.max_batch_vectors),
+            (
+                "limits.max_document_payload_bytes",
+                self.limits.max_document_payload_bytes,
+            ),
+            ("limits.max_metadata_depth", self.limits.max_metadata_depth),
+        ];
+        for (name, value) in limits {
+            if value == 0 {
+                return Err(ConfigError::ValidationError(format!(
+                    "{} must be > 0",
+                    name
+                )));
+            }
+        }
+
+        // Validate replication settings
+        match self.replication.role.as_str() {
+            "none" | "primary" => {}
+            "standby" if self.replication.primary_url.is_none() => {
+                return Err(ConfigError::ValidationError(
+                    "replication.primary_url must be set when replication.role = \"standby\""
+                        .to_string(),
+                ));
+            }
+            "standby" => {}
+            other => {
+                return Err(ConfigError::ValidationError(format!(
+                    "replication.role must be one of none, primary, standby (got \"{}\")",
+                    other
+                )));
+            }
+        }
+
+        let token_missing = self
+            .replication
+            .auth_token
+            .as_deref()
+            .map_or(true, |token| token.trim().is_empty());
+        if self.replication.role != "none" && token_missing {
+            return Err(ConfigError::ValidationError(format!(
+                "replication.auth_token (or AKIDB_REPLICATION_TOKEN) must be set when replication.role = \"{}\"",
+                self.replication.role
+            )));
+        }
+
+        for rule in &self.replication.rules {
+            rule.validate().map_err(ConfigError::ValidationError)?;
+        }
+
+        if self.replication.poll_interval_ms == 0 {
+            return Err(ConfigError::ValidationError(
+                "replication.poll_interval_ms must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.leader_election.lease_ttl_seconds == 0 {
+            return Err(ConfigError::ValidationError(
+                "leader_election.lease_ttl_seconds must be greater than 0".to_string(),
+            ));
+        }
+
+        let rate = self.audit.write_sample_rate;
+        if !(rate > 0.0 && rate <= 1.0) {
+            return Err(ConfigError::ValidationError(
+                "audit.write_sample_rate must be greater than 0 and at most 1".to_string(),
+            ));
+        }
+
+        if self.features.flag_refresh_interval_seconds == 0 {
+            return Err(ConfigError::ValidationError(
+                "features.flag_refresh_interval_seconds must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.drain.timeout_seconds == 0 {
+            return Err(ConfigError::ValidationError(
+                "drain.timeout_seconds must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.snapshots.check_interval_seconds == 0 {
+            return Err(ConfigError::ValidationError(
+                "snapshots.check_interval_seconds must be greater than 0".to_string(),
+            ));
+        }
+
+        for schedule in &self.snapshots.schedules {
+            schedule.validate().map_err(ConfigError::ValidationError)?;
+        }
+
+        if !matches!(self.cdc.sink.as_str(), "kafka" | "nats") {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid cdc.sink: {}. Must be 'kafka' or 'nats'",
+                self.cdc.sink
+            )));
+        }
+
+        if self.cdc.enabled && self.cdc.servers.is_empty() {
+            return Err(ConfigError::ValidationError(
+                "cdc.servers is required when CDC is enabled".to_string(),
+            ));
+        }
+
+        for pattern in &self.cdc.collections {
+            let name = pattern.strip_suffix('*').unwrap_or(pattern);
+            if pattern.is_empty() || name.contains('*') {
+                return Err(ConfigError::ValidationError(format!(
+                    "cdc.collections entries must be a collection name or a prefix ending in '*' (got \"{}\")",
+                    pattern
+                )));
+            }
+        }
+
+        if self.cdc.batch_size == 0 || self.cdc.poll_interval_ms == 0 {
+            return Err(ConfigError::ValidationError(
+                "cdc.batch_size and cdc.poll_interval_ms must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.metering.flush_interval_seconds == 0 {
+            return Err(ConfigError::ValidationError(
+                "metering.flush_interval_seconds must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.debug.task_dump_timeout_ms == 0 {
+            return Err(ConfigError::ValidationError(
+                "debug.task_dump_timeout_ms must be greater than 0".to_string(),
+            ));
+        }
+
+        if let Err(e) = self.tiering.policy.validate() {
+            return Err(ConfigError::ValidationError(format!("tiering.{}", e)));
+        }
+
         // Validate log level
         let valid_levels = ["trace", "debug", "info", "warn", "error"];
         if !valid_levels.contains(&self.logging.level.as_str()) {
@@ -568,6 +2045,19 @@ mod tests {
             .contains("logging.level must be"));
     }
 
+    #[test]
+    fn test_config_validation_zero_limit() {
+        let mut config = Config::default();
+        config.limits.max_batch_vectors = 0;
+
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("limits.max_batch_vectors must be > 0"));
+    }
+
     #[test]
     fn test_toml_serialization() {
         let config = Config::default();
@@ -606,6 +2096,283 @@ mod tests {
         assert_eq!(config.hnsw.ef_construction, 100);
     }
 
+    #[test]
+    fn test_config_validation_missing_tls_cert() {
+        let mut config = Config::default();
+        config.server.tls = Some(TlsConfig {
+            cert_path: PathBuf::from("/nonexistent/server.crt"),
+            key_path: PathBuf::from("/nonexistent/server.key"),
+            client_ca_path: None,
+            reload_interval_seconds: 60,
+        });
+
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("server.tls.cert_path does not exist"));
+    }
+
+    #[test]
+    fn test_tls_toml_deserialization() {
+        let toml_str = r#"
+            [server]
+            [server.tls]
+            cert_path = "/etc/akidb/server.crt"
+            key_path = "/etc/akidb/server.key"
+            client_ca_path = "/etc/akidb/ca.crt"
+
+            [database]
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let tls = config.server.tls.unwrap();
+        assert_eq!(tls.cert_path, PathBuf::from("/etc/akidb/server.crt"));
+        assert_eq!(tls.client_ca_path, Some(PathBuf::from("/etc/akidb/ca.crt")));
+        assert_eq!(tls.reload_interval_seconds, 60);
+    }
+
+    #[test]
+    fn test_cors_toml_and_validation() {
+        let toml_str = r#"
+            [server.cors]
+            allowed_origins = ["https://dashboard.example.com"]
+            allow_credentials = true
+
+            [database]
+        "#;
+
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let cors = config.server.cors.as_ref().unwrap();
+        assert_eq!(cors.allowed_methods, vec!["GET", "POST", "PUT", "DELETE"]);
+        assert_eq!(cors.max_age_seconds, 3600);
+        assert!(config.validate().is_ok());
+
+        config.server.cors.as_mut().unwrap().allowed_origins = vec!["*".to_string()];
+        let result = config.validate();
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("allow_credentials cannot be used"));
+    }
+
+    #[test]
+    fn test_http_embedding_toml_and_validation() {
+        let toml_str = r#"
+            [server]
+            [database]
+
+            [embedding]
+            provider = "http"
+            model = "in-house-embed"
+
+            [embedding.http]
+            url = "http://embedder.internal:8000/v1/embed"
+            auth_value = "Bearer secret"
+            request_template = { texts = "{{inputs}}" }
+            response_path = "/vectors"
+        "#;
+
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let http = config.embedding.http.as_ref().unwrap();
+        assert_eq!(http.auth_header, "Authorization");
+        assert_eq!(http.request_template["texts"], "{{inputs}}");
+        assert_eq!(http.timeout_ms, 30_000);
+        assert_eq!(config.embedding.circuit_cooldown_seconds, 30);
+        assert_eq!(config.embedding.cache_max_entries, 10_000);
+        assert!(config.embedding.cache_path.is_none());
+        assert_eq!(config.embedding.max_batch_size, 32);
+        assert!(config.embedding.warmup);
+        assert_eq!(
+            config.embedding.truncation,
+            TruncationStrategy::TruncateTail
+        );
+        assert_eq!(config.embedding.max_concurrent_requests, 0);
+        assert_eq!(config.embedding.concurrency_wait_ms, 1_000);
+        assert_eq!(config.embedding.tenant_qps, 0);
+        assert!(config.validate().is_ok());
+
+        config.embedding.http = None;
+        let result = config.validate();
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("embedding.http must be set"));
+    }
+
+    #[test]
+    fn test_additional_embedding_models() {
+        let toml_str = r#"
+            [server]
+            [database]
+
+            [embedding]
+            provider = "mock"
+            model = "default-model"
+            max_batch_size = 16
+
+            [[embedding.models]]
+            name = "multilingual"
+            provider = "http"
+            model = "text-embedding-3-small"
+
+            [embedding.models.http]
+            url = "http://embedder.internal:8000/v1/embeddings"
+        "#;
+
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.embedding.max_loaded_models, 4);
+        assert!(config.validate().is_ok());
+
+        let model = config.embedding.for_model("multilingual").unwrap();
+        assert_eq!(model.provider, "http");
+        assert_eq!(model.model, "text-embedding-3-small");
+        assert_eq!(model.max_batch_size, 16);
+        assert!(model.models.is_empty());
+        assert!(config.embedding.for_model("unknown").is_none());
+
+        config.embedding.models.push(config.embedding.models[0].clone());
+        let result = config.validate();
+        assert!(result.unwrap_err().to_string().contains("duplicate name"));
+    }
+
+    #[test]
+    fn test_clip_embedding_requires_model_path() {
+        let toml_str = r#"
+            [server]
+            [database]
+
+            [embedding]
+            provider = "clip"
+            model = "openai/clip-vit-base-patch32"
+            model_path = "models/clip"
+        "#;
+
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.validate().is_ok());
+
+        config.embedding.model_path = None;
+        let result = config.validate();
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("embedding.model_path must be set when embedding.provider = \"clip\""));
+    }
+
+    #[test]
+    fn test_rerank_toml_and_validation() {
+        let toml_str = r#"
+            [server]
+            [database]
+
+            [rerank]
+            provider = "http"
+            model = "rerank-english-v3.0"
+
+            [rerank.http]
+            url = "https://api.cohere.com/v2/rerank"
+        "#;
+
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let http = config.rerank.http.as_ref().unwrap();
+        assert_eq!(http.results_path, "/results");
+        assert_eq!(http.score_pointer, "/relevance_score");
+        assert_eq!(config.rerank.max_documents, 100);
+        assert!(config.validate().is_ok());
+
+        config.rerank.provider = "onnx".to_string();
+        let result = config.validate();
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("rerank.model_path must be set"));
+
+        config.rerank.provider = "bm25".to_string();
+        assert!(config.validate().is_err());
+        assert_eq!(Config::default().rerank.provider, "none");
+    }
+
+    #[test]
+    fn test_replication_toml_and_validation() {
+        let toml_str = r#"
+            [server]
+            [database]
+
+            [replication]
+            role = "standby"
+            primary_url = "http://primary:9090"
+            auth_token = "replication-secret"
+        "#;
+
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.replication.standby_id, "standby");
+        assert_eq!(
+            config.replication.state_path,
+            PathBuf::from("replication_state.json")
+        );
+        assert_eq!(config.replication.poll_interval_ms, 1_000);
+        assert_eq!(config.replication.max_lag_seconds, 30);
+        assert!(config.validate().is_ok());
+
+        // Standbys and primaries must authenticate each other
+        config.replication.auth_token = None;
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("replication.auth_token"));
+        config.replication.role = "primary".to_string();
+        assert!(config.validate().is_err());
+        config.replication.auth_token = Some("replication-secret".to_string());
+        assert!(config.validate().is_ok());
+
+        config.replication.role = "standby".to_string();
+        config.replication.primary_url = None;
+        let result = config.validate();
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("replication.primary_url must be set"));
+
+        config.replication.role = "leader".to_string();
+        assert!(config.validate().is_err());
+        assert_eq!(Config::default().replication.role, "none");
+    }
+
+    #[test]
+    fn test_replication_rules() {
+        let toml_str = r#"
+            [server]
+            [database]
+
+            [replication]
+            role = "primary"
+            auth_token = "replication-secret"
+
+            [[replication.rules]]
+            collections = "scratch-*"
+            enabled = false
+            priority = 10
+
+            [[replication.rules]]
+            collections = "*"
+        "#;
+
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let rules = &config.replication.rules;
+        assert_eq!(rules.len(), 2);
+        assert!(rules[0].matches("scratch-embeddings"));
+        assert!(!rules[0].matches("products"));
+        assert!(rules[1].enabled);
+        assert_eq!(rules[1].priority, 0);
+        assert!(rules[1].matches("products"));
+        assert!(config.validate().is_ok());
+
+        config.replication.rules[0].collections = "scratch-*-tmp".to_string();
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_env_override() {
         std::env::set_var("AKIDB_HOST", "192.168.1.100");
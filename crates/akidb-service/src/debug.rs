@@ -0,0 +1,325 @@
+//! Runtime introspection for the ops-only `/debug` endpoints.
+//!
+//! Makes a hung or slow process diagnosable without attaching a debugger:
+//! - Tokio runtime metrics, plus a backtrace of every task when task dumps
+//!   are compiled in (see [`task_dump`])
+//! - Wait counters for the service's shared locks ([`TrackedRwLock`])
+//! - Heap statistics, when [`CountingAllocator`] is the global allocator
+//!
+//! Storage queue depths come from [`CollectionService::queue_depths`].
+//!
+//! [`CollectionService::queue_depths`]: crate::CollectionService::queue_depths
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use akidb_core::CollectionId;
+use serde::Serialize;
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// [`tokio::sync::RwLock`] counting how often, and how long, callers waited
+/// to acquire it.
+pub struct TrackedRwLock<T> {
+    name: &'static str,
+    lock: RwLock<T>,
+    acquisitions: AtomicU64,
+    contended: AtomicU64,
+    wait_micros: AtomicU64,
+    max_wait_micros: AtomicU64,
+}
+
+/// Wait counters of one [`TrackedRwLock`] since startup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LockStats {
+    pub name: &'static str,
+    pub acquisitions: u64,
+    /// Acquisitions that could not take the lock immediately
+    pub contended: u64,
+    pub total_wait_micros: u64,
+    pub max_wait_micros: u64,
+}
+
+impl<T> TrackedRwLock<T> {
+    /// Creates a lock reported as `name`.
+    pub fn new(name: &'static str, value: T) -> Self {
+        Self {
+            name,
+            lock: RwLock::new(value),
+            acquisitions: AtomicU64::new(0),
+            contended: AtomicU64::new(0),
+            wait_micros: AtomicU64::new(0),
+            max_wait_micros: AtomicU64::new(0),
+        }
+    }
+
+    /// Locks for reading, waiting while a writer holds the lock.
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        if let Ok(guard) = self.lock.try_read() {
+            self.acquired(None);
+            return guard;
+        }
+        let start = Instant::now();
+        let guard = self.lock.read().await;
+        self.acquired(Some(start.elapsed()));
+        guard
+    }
+
+    /// Locks for writing, waiting while any reader or writer holds the lock.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        if let Ok(guard) = self.lock.try_write() {
+            self.acquired(None);
+            return guard;
+        }
+        let start = Instant::now();
+        let guard = self.lock.write().await;
+        self.acquired(Some(start.elapsed()));
+        guard
+    }
+
+    /// Counters since the lock was created.
+    pub fn stats(&self) -> LockStats {
+        LockStats {
+            name: self.name,
+            acquisitions: self.acquisitions.load(Ordering::Relaxed),
+            contended: self.contended.load(Ordering::Relaxed),
+            total_wait_micros: self.wait_micros.load(Ordering::Relaxed),
+            max_wait_micros: self.max_wait_micros.load(Ordering::Relaxed),
+        }
+    }
+
+    fn acquired(&self, waited: Option<Duration>) {
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        if let Some(waited) = waited {
+            let micros = waited.as_micros() as u64;
+            self.contended.fetch_add(1, Ordering::Relaxed);
+            self.wait_micros.fetch_add(micros, Ordering::Relaxed);
+            self.max_wait_micros.fetch_max(micros, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Storage queues of one collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CollectionQueueDepths {
+    pub collection_id: CollectionId,
+    /// Documents waiting for their first S3 upload
+    pub pending_uploads: usize,
+    /// Failed S3 uploads waiting for retry
+    pub pending_retries: usize,
+    /// Uploads that exhausted their retries
+    pub dead_letters: usize,
+}
+
+/// Tokio runtime metrics.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeStats {
+    pub workers: usize,
+    pub alive_tasks: usize,
+    /// Tasks scheduled from outside the runtime, waiting for a worker
+    pub global_queue_depth: usize,
+    /// Time each worker has spent running tasks; a worker whose busy time
+    /// grows while its park count does not is stuck in one task
+    pub worker_busy_millis: Vec<u64>,
+    pub worker_park_counts: Vec<u64>,
+}
+
+/// Metrics of the runtime the caller is running on, if any.
+pub fn runtime_stats() -> Option<RuntimeStats> {
+    let metrics = tokio::runtime::Handle::try_current().ok()?.metrics();
+    let workers = metrics.num_workers();
+    Some(RuntimeStats {
+        workers,
+        alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+        worker_busy_millis: (0..workers)
+            .map(|worker| metrics.worker_total_busy_duration(worker).as_millis() as u64)
+            .collect(),
+        worker_park_counts: (0..workers)
+            .map(|worker| metrics.worker_park_count(worker))
+            .collect(),
+    })
+}
+
+/// Why [`task_dump`] returned no tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskDumpError {
+    /// This binary was built without task dump support
+    Unsupported,
+    /// A worker did not yield in time, typically because it is blocked
+    TimedOut,
+}
+
+impl fmt::Display for TaskDumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported => f.write_str(
+                "task dumps require building on Linux with the `taskdump` feature and \
+                 RUSTFLAGS=\"--cfg tokio_unstable\"",
+            ),
+            Self::TimedOut => f.write_str("timed out waiting for runtime workers to yield"),
+        }
+    }
+}
+
+impl std::error::Error for TaskDumpError {}
+
+/// Backtraces of every task on the current runtime, one string per task.
+///
+/// Tracing pauses all workers, so a worker blocked in synchronous code makes
+/// the dump time out after `timeout`; that itself points at the culprit.
+#[cfg(all(feature = "taskdump", tokio_unstable, target_os = "linux"))]
+pub async fn task_dump(timeout: Duration) -> Result<Vec<String>, TaskDumpError> {
+    let handle = tokio::runtime::Handle::current();
+    let dump = tokio::time::timeout(timeout, handle.dump())
+        .await
+        .map_err(|_| TaskDumpError::TimedOut)?;
+    Ok(dump
+        .tasks()
+        .iter()
+        .map(|task| format!("task {}:\n{}", task.id(), task.trace()))
+        .collect())
+}
+
+/// Backtraces of every task on the current runtime, one string per task.
+///
+/// Always [`TaskDumpError::Unsupported`] in this build.
+#[cfg(not(all(feature = "taskdump", tokio_unstable, target_os = "linux")))]
+pub async fn task_dump(_timeout: Duration) -> Result<Vec<String>, TaskDumpError> {
+    Err(TaskDumpError::Unsupported)
+}
+
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting live heap bytes for [`allocator_stats`].
+///
+/// Install it in a binary with
+/// `#[global_allocator] static ALLOCATOR: CountingAllocator = CountingAllocator;`.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn grew(bytes: usize) {
+        let allocated = ALLOCATED_BYTES.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        PEAK_ALLOCATED_BYTES.fetch_max(allocated, Ordering::Relaxed);
+    }
+
+    fn shrank(bytes: usize) {
+        ALLOCATED_BYTES.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: every call is forwarded to `System` unchanged; only counters are added.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        Self::shrank(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                Self::grew(new_size - layout.size());
+            } else {
+                Self::shrank(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Heap statistics.
+#[derive(Debug, Clone, Serialize)]
+pub struct AllocatorStats {
+    /// Whether [`CountingAllocator`] is the global allocator; the heap
+    /// counters are zero otherwise
+    pub counting: bool,
+    pub allocated_bytes: usize,
+    pub peak_allocated_bytes: usize,
+    /// Allocations and deallocations since startup (reallocations excluded)
+    pub allocations: u64,
+    pub deallocations: u64,
+    /// Resident set size reported by the OS (Linux only)
+    pub resident_bytes: Option<u64>,
+}
+
+/// Current heap statistics.
+pub fn allocator_stats() -> AllocatorStats {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    AllocatorStats {
+        counting: allocations > 0,
+        allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+        peak_allocated_bytes: PEAK_ALLOCATED_BYTES.load(Ordering::Relaxed),
+        allocations,
+        deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+        resident_bytes: resident_bytes(),
+    }
+}
+
+/// `VmRSS` from `/proc/self/status`.
+fn resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_tracked_lock_counts_contention() {
+        let lock = Arc::new(TrackedRwLock::new("test", 0u32));
+
+        drop(lock.read().await);
+        let guard = lock.write().await;
+        let waiter = {
+            let lock = Arc::clone(&lock);
+            tokio::spawn(async move { *lock.write().await += 1 })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(guard);
+        waiter.await.unwrap();
+
+        let stats = lock.stats();
+        assert_eq!(stats.name, "test");
+        assert_eq!(stats.acquisitions, 3);
+        assert_eq!(stats.contended, 1);
+        assert!(stats.max_wait_micros >= 10_000);
+        assert_eq!(stats.total_wait_micros, stats.max_wait_micros);
+    }
+
+    #[cfg(not(all(feature = "taskdump", tokio_unstable, target_os = "linux")))]
+    #[tokio::test]
+    async fn test_task_dump_unsupported_by_default() {
+        assert_eq!(
+            task_dump(Duration::from_secs(1)).await,
+            Err(TaskDumpError::Unsupported)
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Draining a server before shutdown (`[drain]`).
+//!
+//! Stopping a pod used to race with the load balancer: the process stopped
+//! accepting connections while the balancer was still routing to it. A drain
+//! runs in stages instead:
+//!
+//! 1. [`DrainStage::Draining`]: readiness fails, so the balancer takes the
+//!    pod out of rotation, but requests are still served for the grace period
+//! 2. [`DrainStage::Rejecting`]: new work is refused with 503 while
+//!    in-flight requests finish and the S3 upload queues flush
+//! 3. [`DrainStage::Drained`]: nothing is left in flight; shutting down loses
+//!    nothing
+//!
+//! A drain is started with `POST /admin/drain` (e.g. from a preStop hook,
+//! with an `admin::drain` API key) or by SIGTERM, and runs through
+//! [`CollectionService::drain`]. Draining twice is harmless: the second call
+//! waits for the first.
+//!
+//! [`CollectionService::drain`]: crate::CollectionService::drain
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::watch;
+
+/// How often the upload queues are checked while waiting for them to flush.
+pub const QUEUE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Step a drain is at, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DrainStage {
+    /// Not draining
+    Serving,
+    /// Readiness fails; requests are still served during the grace period
+    Draining,
+    /// New work is refused; waiting for in-flight requests and uploads
+    Rejecting,
+    /// Safe to shut down
+    Drained,
+}
+
+/// Snapshot of a drain's progress.
+#[derive(Debug, Clone, Serialize)]
+pub struct DrainStatus {
+    pub stage: DrainStage,
+    pub started_at: Option<DateTime<Utc>>,
+    pub grace_period_secs: Option<u64>,
+    /// Requests currently being served
+    pub in_flight: usize,
+    /// Uploads and retries still queued, as of the last check
+    pub pending_uploads: usize,
+    pub drained_at: Option<DateTime<Utc>>,
+    /// Set when the drain gave up waiting; shutting down may lose work
+    pub timed_out: bool,
+}
+
+/// Tracks in-flight requests and the drain stage.
+pub struct DrainController {
+    stage: watch::Sender<DrainStage>,
+    in_flight: Arc<AtomicUsize>,
+    status: Mutex<DrainStatus>,
+}
+
+/// Counts a request as in flight until dropped.
+pub struct InFlightGuard {
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl DrainController {
+    /// A controller for a server that is serving.
+    pub fn new() -> Self {
+        Self {
+            stage: watch::Sender::new(DrainStage::Serving),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            status: Mutex::new(DrainStatus {
+                stage: DrainStage::Serving,
+                started_at: None,
+                grace_period_secs: None,
+                in_flight: 0,
+                pending_uploads: 0,
+                drained_at: None,
+                timed_out: false,
+            }),
+        }
+    }
+
+    /// Current stage.
+    pub fn stage(&self) -> DrainStage {
+        *self.stage.borrow()
+    }
+
+    /// Whether a drain has started; readiness fails from then on.
+    pub fn is_draining(&self) -> bool {
+        self.stage() != DrainStage::Serving
+    }
+
+    /// Counts a new request as in flight, or returns `None` if new work is
+    /// being refused.
+    pub fn begin_request(&self) -> Option<InFlightGuard> {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let guard = InFlightGuard {
+            in_flight: Arc::clone(&self.in_flight),
+        };
+        // Checked after counting, so a drain never misses a request it admitted
+        (self.stage() < DrainStage::Rejecting).then_some(guard)
+    }
+
+    /// Requests currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Current progress.
+    pub fn status(&self) -> DrainStatus {
+        let mut status = self.lock().clone();
+        status.in_flight = self.in_flight();
+        status
+    }
+
+    /// Enters [`DrainStage::Draining`]; returns `false` if a drain had
+    /// already started.
+    pub(crate) fn start(&self, grace_period: Duration) -> bool {
+        let started = self.stage.send_if_modified(|stage| {
+            if *stage != DrainStage::Serving {
+                return false;
+            }
+            *stage = DrainStage::Draining;
+            true
+        });
+        if started {
+            let mut status = self.lock();
+            status.stage = DrainStage::Draining;
+            status.started_at = Some(Utc::now());
+            status.grace_period_secs = Some(grace_period.as_secs());
+        }
+        started
+    }
+
+    pub(crate) fn advance(&self, stage: DrainStage) {
+        self.stage.send_replace(stage);
+        let mut status = self.lock();
+        status.stage = stage;
+        if stage == DrainStage::Drained {
+            status.drained_at = Some(Utc::now());
+        }
+    }
+
+    pub(crate) fn record_pending_uploads(&self, pending_uploads: usize) {
+        self.lock().pending_uploads = pending_uploads;
+    }
+
+    pub(crate) fn record_timeout(&self) {
+        self.lock().timed_out = true;
+    }
+
+    /// Waits until the drain has finished.
+    pub async fn drained(&self) {
+        let mut stage = self.stage.subscribe();
+        // The sender lives as long as `self`, so this cannot fail
+        let _ = stage.wait_for(|stage| *stage == DrainStage::Drained).await;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DrainStatus> {
+        self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for DrainController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_new_requests_once_rejecting() {
+        let drain = DrainController::new();
+        let request = drain.begin_request().unwrap();
+        assert_eq!(drain.in_flight(), 1);
+
+        assert!(drain.start(Duration::from_secs(5)));
+        assert!(!drain.start(Duration::from_secs(5)));
+        assert!(drain.is_draining());
+        // Still served during the grace period
+        drop(drain.begin_request().unwrap());
+
+        drain.advance(DrainStage::Rejecting);
+        assert!(drain.begin_request().is_none());
+        assert_eq!(drain.in_flight(), 1);
+        drop(request);
+        assert_eq!(drain.status().in_flight, 0);
+    }
+}
@@ -0,0 +1,306 @@
+//! Dynamic micro-batching for embedding requests.
+//!
+//! Many small concurrent requests (e.g. one REST call per document) leave an
+//! accelerator mostly idle. The batcher queues requests and hands them to the
+//! provider as one batch once `max_batch_size` texts are waiting or the
+//! oldest request has waited `max_wait`, whichever comes first.
+//!
+//! If a coalesced batch is rejected as invalid input, each request is retried
+//! on its own so one bad text only fails the request that sent it.
+
+use akidb_embedding::{
+    BatchEmbeddingRequest, EmbeddingError, EmbeddingProvider, EmbeddingResult, TruncationStrategy,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+
+type Provider = Arc<dyn EmbeddingProvider + Send + Sync>;
+
+/// Requests queued before senders wait for the batcher to catch up.
+const QUEUE_CAPACITY: usize = 1024;
+
+/// One caller's texts and where to send their embeddings.
+struct Job {
+    texts: Vec<String>,
+    reply: oneshot::Sender<EmbeddingResult<Vec<Vec<f32>>>>,
+}
+
+/// Coalesces concurrent embed calls into provider batches.
+pub(crate) struct EmbeddingBatcher {
+    jobs: mpsc::Sender<Job>,
+    max_batch_size: usize,
+}
+
+impl EmbeddingBatcher {
+    /// Starts the batching task. Must be called within a Tokio runtime.
+    pub(crate) fn spawn(
+        provider: Provider,
+        model: String,
+        truncation: TruncationStrategy,
+        max_batch_size: usize,
+        max_wait: Duration,
+    ) -> Self {
+        let (jobs, rx) = mpsc::channel(QUEUE_CAPACITY);
+        tokio::spawn(run(
+            rx,
+            provider,
+            model,
+            truncation,
+            max_batch_size,
+            max_wait,
+        ));
+        Self {
+            jobs,
+            max_batch_size,
+        }
+    }
+
+    /// Largest batch sent to the provider.
+    pub(crate) fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
+
+    /// Embeds `texts` as part of the next batch.
+    pub(crate) async fn embed(&self, texts: Vec<String>) -> EmbeddingResult<Vec<Vec<f32>>> {
+        let (reply, response) = oneshot::channel();
+        let stopped =
+            || EmbeddingError::ServiceUnavailable("Embedding batcher has stopped".to_string());
+
+        self.jobs
+            .send(Job { texts, reply })
+            .await
+            .map_err(|_| stopped())?;
+        response.await.map_err(|_| stopped())?
+    }
+}
+
+/// Collects jobs into batches until every sender is dropped.
+async fn run(
+    mut rx: mpsc::Receiver<Job>,
+    provider: Provider,
+    model: String,
+    truncation: TruncationStrategy,
+    max_batch_size: usize,
+    max_wait: Duration,
+) {
+    let mut carried: Option<Job> = None;
+
+    loop {
+        let first = match carried.take() {
+            Some(job) => job,
+            None => match rx.recv().await {
+                Some(job) => job,
+                None => return,
+            },
+        };
+
+        let deadline = Instant::now() + max_wait;
+        let mut size = first.texts.len();
+        let mut batch = vec![first];
+        while size < max_batch_size {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(job)) if size + job.texts.len() > max_batch_size => {
+                    // Would overflow this batch; it starts the next one
+                    carried = Some(job);
+                    break;
+                }
+                Ok(Some(job)) => {
+                    size += job.texts.len();
+                    batch.push(job);
+                }
+                // Deadline reached or all senders gone
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        tracing::debug!(
+            requests = batch.len(),
+            texts = size,
+            "Dispatching embedding batch"
+        );
+        tokio::spawn(dispatch(provider.clone(), model.clone(), truncation, batch));
+    }
+}
+
+/// Embeds a batch and returns each job its share of the results.
+async fn dispatch(
+    provider: Provider,
+    model: String,
+    truncation: TruncationStrategy,
+    batch: Vec<Job>,
+) {
+    let inputs: Vec<String> = batch.iter().flat_map(|job| job.texts.clone()).collect();
+    let expected = inputs.len();
+
+    let result = embed(&provider, &model, truncation, inputs)
+        .await
+        .and_then(|embeddings| {
+            if embeddings.len() == expected {
+                Ok(embeddings)
+            } else {
+                Err(EmbeddingError::Internal(format!(
+                    "Provider returned {} embeddings for {} inputs",
+                    embeddings.len(),
+                    expected
+                )))
+            }
+        });
+
+    match result {
+        Ok(embeddings) => {
+            let mut embeddings = embeddings.into_iter();
+            for job in batch {
+                let share = embeddings.by_ref().take(job.texts.len()).collect();
+                let _ = job.reply.send(Ok(share));
+            }
+        }
+        Err(EmbeddingError::InvalidInput(_)) if batch.len() > 1 => {
+            // Find out whose input was rejected
+            for job in batch {
+                let result = embed(&provider, &model, truncation, job.texts).await;
+                let _ = job.reply.send(result);
+            }
+        }
+        Err(e) => {
+            for job in batch {
+                let _ = job.reply.send(Err(clone_error(&e)));
+            }
+        }
+    }
+}
+
+async fn embed(
+    provider: &Provider,
+    model: &str,
+    truncation: TruncationStrategy,
+    inputs: Vec<String>,
+) -> EmbeddingResult<Vec<Vec<f32>>> {
+    let request = BatchEmbeddingRequest {
+        model: model.to_string(),
+        inputs,
+        normalize: true,
+        truncation,
+    };
+    Ok(provider.embed_batch(request).await?.embeddings)
+}
+
+fn clone_error(error: &EmbeddingError) -> EmbeddingError {
+    match error {
+        EmbeddingError::ModelNotFound(m) => EmbeddingError::ModelNotFound(m.clone()),
+        EmbeddingError::InvalidInput(m) => EmbeddingError::InvalidInput(m.clone()),
+        EmbeddingError::ServiceUnavailable(m) => EmbeddingError::ServiceUnavailable(m.clone()),
+        EmbeddingError::Internal(m) => EmbeddingError::Internal(m.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_embedding::{BatchEmbeddingResponse, ModelInfo, Usage};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Records batch sizes; rejects the text "bad".
+    #[derive(Default)]
+    struct RecordingProvider(Mutex<Vec<usize>>);
+
+    #[async_trait]
+    impl EmbeddingProvider for RecordingProvider {
+        async fn embed_batch(
+            &self,
+            request: BatchEmbeddingRequest,
+        ) -> EmbeddingResult<BatchEmbeddingResponse> {
+            self.0.lock().unwrap().push(request.inputs.len());
+            if request.inputs.iter().any(|t| t == "bad") {
+                return Err(EmbeddingError::InvalidInput("bad input".to_string()));
+            }
+            Ok(BatchEmbeddingResponse {
+                model: request.model,
+                embeddings: request
+                    .inputs
+                    .iter()
+                    .map(|t| vec![t.len() as f32])
+                    .collect(),
+                usage: Usage {
+                    total_tokens: 0,
+                    duration_ms: 0,
+                },
+            })
+        }
+
+        async fn model_info(&self) -> EmbeddingResult<ModelInfo> {
+            unreachable!()
+        }
+
+        async fn health_check(&self) -> EmbeddingResult<()> {
+            Ok(())
+        }
+    }
+
+    fn texts(items: &[&str]) -> Vec<String> {
+        items.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_are_coalesced() {
+        let provider = Arc::new(RecordingProvider::default());
+        let batcher = EmbeddingBatcher::spawn(
+            provider.clone(),
+            "m".to_string(),
+            TruncationStrategy::default(),
+            8,
+            Duration::from_millis(50),
+        );
+
+        let (a, b, c) = tokio::join!(
+            batcher.embed(texts(&["a"])),
+            batcher.embed(texts(&["bb", "ccc"])),
+            batcher.embed(texts(&["dddd"])),
+        );
+        assert_eq!(a.unwrap(), vec![vec![1.0]]);
+        assert_eq!(b.unwrap(), vec![vec![2.0], vec![3.0]]);
+        assert_eq!(c.unwrap(), vec![vec![4.0]]);
+        assert_eq!(*provider.0.lock().unwrap(), vec![4]);
+    }
+
+    #[tokio::test]
+    async fn test_batches_respect_max_size() {
+        let provider = Arc::new(RecordingProvider::default());
+        let batcher = EmbeddingBatcher::spawn(
+            provider.clone(),
+            "m".to_string(),
+            TruncationStrategy::default(),
+            2,
+            Duration::from_millis(50),
+        );
+
+        let (a, b) = tokio::join!(
+            batcher.embed(texts(&["a"])),
+            batcher.embed(texts(&["bb", "ccc"])),
+        );
+        assert_eq!(a.unwrap(), vec![vec![1.0]]);
+        assert_eq!(b.unwrap(), vec![vec![2.0], vec![3.0]]);
+        assert_eq!(*provider.0.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_input_only_fails_its_request() {
+        let provider = Arc::new(RecordingProvider::default());
+        let batcher = EmbeddingBatcher::spawn(
+            provider.clone(),
+            "m".to_string(),
+            TruncationStrategy::default(),
+            8,
+            Duration::from_millis(50),
+        );
+
+        let (good, bad) = tokio::join!(
+            batcher.embed(texts(&["good"])),
+            batcher.embed(texts(&["bad"])),
+        );
+        assert_eq!(good.unwrap(), vec![vec![4.0]]);
+        assert!(matches!(bad, Err(EmbeddingError::InvalidInput(_))));
+    }
+}
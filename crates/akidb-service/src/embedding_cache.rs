@@ -0,0 +1,303 @@
+//! Embedding result cache.
+//!
+//! Maps `(model, normalized text)` to the embedding the provider returned, so
+//! re-ingesting overlapping corpora does not recompute vectors. Text is
+//! normalized by trimming and collapsing whitespace runs before hashing; case
+//! and punctuation are preserved since models are sensitive to both.
+//!
+//! Entries are kept in memory with LRU eviction. When a cache file is
+//! configured, new entries are appended to it and reloaded on startup, so the
+//! cache survives restarts. The file is compacted to the retained entries each
+//! time it is opened.
+
+use crate::config::EmbeddingConfig;
+use crate::metrics::EMBEDDING_CACHE_REQUESTS_TOTAL;
+use lru::LruCache;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Cache key: SHA-256 of the model name and normalized text.
+pub type CacheKey = [u8; 32];
+
+/// Hit/miss counters and occupancy of an [`EmbeddingCache`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingCacheStats {
+    /// Entries currently cached
+    pub entries: usize,
+    /// Maximum number of entries
+    pub capacity: usize,
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that went to the provider
+    pub misses: u64,
+    /// Cache hit rate (0.0 - 1.0)
+    pub hit_rate: f64,
+}
+
+/// LRU cache of embeddings with optional on-disk persistence.
+pub struct EmbeddingCache {
+    entries: Mutex<LruCache<CacheKey, Vec<f32>>>,
+    capacity: usize,
+    /// Append-only cache file, when persistence is enabled
+    file: Option<Mutex<BufWriter<File>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl EmbeddingCache {
+    /// Creates an in-memory cache holding at most `capacity` embeddings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).expect("embedding cache capacity must be > 0");
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            capacity: capacity.get(),
+            file: None,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Creates a cache backed by `path`, loading any entries already stored there.
+    ///
+    /// A truncated trailing record (e.g. after a crash) is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or rewritten.
+    pub fn open(capacity: usize, path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut cache = Self::new(capacity);
+
+        if path.exists() {
+            let mut reader = BufReader::new(File::open(path)?);
+            let entries = cache.entries.get_mut().unwrap_or_else(|e| e.into_inner());
+            while let Some((key, embedding)) = read_record(&mut reader)? {
+                entries.put(key, embedding);
+            }
+        } else if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Compact: rewrite only the retained entries, oldest first so LRU
+        // order is preserved on the next load
+        let tmp = temp_path(path);
+        {
+            let mut writer = BufWriter::new(File::create(&tmp)?);
+            let entries = cache.entries.get_mut().unwrap_or_else(|e| e.into_inner());
+            for (key, embedding) in entries.iter().rev() {
+                write_record(&mut writer, key, embedding)?;
+            }
+            writer.flush()?;
+        }
+        fs::rename(&tmp, path)?;
+
+        let file = OpenOptions::new().append(true).open(path)?;
+        cache.file = Some(Mutex::new(BufWriter::new(file)));
+        Ok(cache)
+    }
+
+    /// Builds the cache described by the `[embedding]` section.
+    ///
+    /// Returns `None` when caching is disabled (`cache_max_entries = 0`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache file cannot be opened.
+    pub fn from_config(config: &EmbeddingConfig) -> io::Result<Option<Self>> {
+        if config.cache_max_entries == 0 {
+            return Ok(None);
+        }
+        match &config.cache_path {
+            Some(path) => Self::open(config.cache_max_entries, path).map(Some),
+            None => Ok(Some(Self::new(config.cache_max_entries))),
+        }
+    }
+
+    /// Computes the cache key for `text` embedded with `model`.
+    pub fn key(model: &str, text: &str) -> CacheKey {
+        let mut hasher = Sha256::new();
+        hasher.update(model.as_bytes());
+        hasher.update([0u8]);
+        for (i, word) in text.split_whitespace().enumerate() {
+            if i > 0 {
+                hasher.update(b" ");
+            }
+            hasher.update(word.as_bytes());
+        }
+        hasher.finalize().into()
+    }
+
+    /// Looks up an embedding, counting the hit or miss.
+    pub fn get(&self, key: &CacheKey) -> Option<Vec<f32>> {
+        let found = self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .cloned();
+
+        let (counter, result) = match found {
+            Some(_) => (&self.hits, "hit"),
+            None => (&self.misses, "miss"),
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        EMBEDDING_CACHE_REQUESTS_TOTAL
+            .with_label_values(&[result])
+            .inc();
+        found
+    }
+
+    /// Stores embeddings, appending them to the cache file if there is one.
+    ///
+    /// Persistence failures are logged and otherwise ignored; the entries
+    /// stay cached in memory.
+    pub fn insert_all(&self, items: impl IntoIterator<Item = (CacheKey, Vec<f32>)>) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = self
+            .file
+            .as_ref()
+            .map(|file| file.lock().unwrap_or_else(|e| e.into_inner()));
+
+        for (key, embedding) in items {
+            if let Some(writer) = file.as_mut() {
+                if let Err(e) = write_record(&mut **writer, &key, &embedding) {
+                    tracing::warn!(error = %e, "Failed to persist embedding cache entry");
+                }
+            }
+            entries.put(key, embedding);
+        }
+
+        if let Some(writer) = file.as_mut() {
+            if let Err(e) = writer.flush() {
+                tracing::warn!(error = %e, "Failed to flush embedding cache file");
+            }
+        }
+    }
+
+    /// Returns hit/miss counters and occupancy.
+    pub fn stats(&self) -> EmbeddingCacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let total = hits + misses;
+        EmbeddingCacheStats {
+            entries: self.entries.lock().unwrap_or_else(|e| e.into_inner()).len(),
+            capacity: self.capacity,
+            hits,
+            misses,
+            hit_rate: if total == 0 {
+                0.0
+            } else {
+                hits as f64 / total as f64
+            },
+        }
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Record layout: 32-byte key, u32 LE dimension, `dimension` f32 LE values.
+fn write_record(writer: &mut impl Write, key: &CacheKey, embedding: &[f32]) -> io::Result<()> {
+    writer.write_all(key)?;
+    writer.write_all(&(embedding.len() as u32).to_le_bytes())?;
+    for value in embedding {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Reads one record; `None` at end of file or on a truncated record.
+fn read_record(reader: &mut impl Read) -> io::Result<Option<(CacheKey, Vec<f32>)>> {
+    let mut key = [0u8; 32];
+    let mut len = [0u8; 4];
+    if !read_full(reader, &mut key)? || !read_full(reader, &mut len)? {
+        return Ok(None);
+    }
+
+    let mut data = vec![0u8; u32::from_le_bytes(len) as usize * 4];
+    if !read_full(reader, &mut data)? {
+        return Ok(None);
+    }
+    let embedding = data
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+    Ok(Some((key, embedding)))
+}
+
+/// Fills `buf`, returning `false` if the input ends first.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<bool> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_normalizes_whitespace_per_model() {
+        let key = EmbeddingCache::key("m", "hello world");
+        assert_eq!(key, EmbeddingCache::key("m", "  hello \n\t world "));
+        assert_ne!(key, EmbeddingCache::key("m", "Hello world"));
+        assert_ne!(key, EmbeddingCache::key("other", "hello world"));
+    }
+
+    #[test]
+    fn test_lru_eviction_and_stats() {
+        let cache = EmbeddingCache::new(2);
+        let (a, b, c) = (
+            EmbeddingCache::key("m", "a"),
+            EmbeddingCache::key("m", "b"),
+            EmbeddingCache::key("m", "c"),
+        );
+        cache.insert_all([(a, vec![1.0]), (b, vec![2.0])]);
+        assert_eq!(cache.get(&a), Some(vec![1.0]));
+        cache.insert_all([(c, vec![3.0])]);
+
+        // b was least recently used
+        assert_eq!(cache.get(&b), None);
+        assert_eq!(cache.get(&c), Some(vec![3.0]));
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.capacity), (2, 2));
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        assert!((stats.hit_rate - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_persistent_cache_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache/embeddings.bin");
+        let key = EmbeddingCache::key("m", "persisted");
+
+        {
+            let cache = EmbeddingCache::open(10, &path).unwrap();
+            cache.insert_all([(key, vec![0.25, -0.5])]);
+        }
+
+        // A torn trailing record is dropped on load
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[7u8; 10]).unwrap();
+        drop(file);
+
+        let cache = EmbeddingCache::open(10, &path).unwrap();
+        assert_eq!(cache.get(&key), Some(vec![0.25, -0.5]));
+        assert_eq!(cache.stats().entries, 1);
+    }
+}
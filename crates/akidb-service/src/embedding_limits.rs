@@ -0,0 +1,273 @@
+//! Concurrency and per-tenant rate limits for embedding calls.
+//!
+//! Embedding models run on the same CPU or GPU as search, so a burst of
+//! `/embed` traffic can push search latency up for everyone. Two limits keep
+//! it in check (`[embedding]` config):
+//!
+//! - [`ConcurrencyLimit`] caps the provider calls running at once
+//!   (`max_concurrent_requests`). Calls over the cap wait up to
+//!   `concurrency_wait_ms` for a slot and then fail with `ServiceUnavailable`.
+//!   The limit is shared by the default model and every additional model.
+//! - [`TenantRateLimiter`] gives each tenant a token bucket of `tenant_qps`
+//!   embed requests per second. Requests over it are rejected with an
+//!   [`Overload`] (`RATE_LIMITED`, 429) before any work is done.
+
+use crate::backpressure::{Overload, QueueKind};
+use crate::config::EmbeddingConfig;
+use akidb_embedding::{
+    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingProvider,
+    EmbeddingResult, ModelInfo, MultimodalEmbeddingRequest,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Idle tenant buckets are evicted once this many are tracked.
+const MAX_TRACKED_TENANTS: usize = 10_000;
+
+/// Cap on embedding provider calls running at once.
+#[derive(Debug)]
+pub struct ConcurrencyLimit {
+    permits: Semaphore,
+    max: usize,
+    wait: Duration,
+}
+
+impl ConcurrencyLimit {
+    /// Allows `max` concurrent calls; others wait up to `wait` for a slot.
+    pub fn new(max: usize, wait: Duration) -> Self {
+        Self {
+            permits: Semaphore::new(max),
+            max,
+            wait,
+        }
+    }
+
+    /// Limit configured by `max_concurrent_requests`, or `None` when it is 0.
+    pub fn from_config(config: &EmbeddingConfig) -> Option<Arc<Self>> {
+        (config.max_concurrent_requests > 0).then(|| {
+            Arc::new(Self::new(
+                config.max_concurrent_requests,
+                Duration::from_millis(config.concurrency_wait_ms),
+            ))
+        })
+    }
+
+    /// Calls currently holding a slot.
+    pub fn in_flight(&self) -> usize {
+        self.max - self.permits.available_permits()
+    }
+
+    async fn acquire(&self) -> EmbeddingResult<SemaphorePermit<'_>> {
+        match tokio::time::timeout(self.wait, self.permits.acquire()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            // The semaphore is never closed
+            Ok(Err(_)) | Err(_) => Err(EmbeddingError::ServiceUnavailable(format!(
+                "Too many concurrent embedding requests (limit {})",
+                self.max
+            ))),
+        }
+    }
+}
+
+/// Provider wrapper that holds a [`ConcurrencyLimit`] slot while embedding.
+///
+/// Model info, health checks and tokenization do not take a slot, so probes
+/// keep answering while the provider is saturated.
+pub(crate) struct ConcurrencyLimitedProvider {
+    inner: Arc<dyn EmbeddingProvider + Send + Sync>,
+    limit: Arc<ConcurrencyLimit>,
+}
+
+impl ConcurrencyLimitedProvider {
+    pub(crate) fn new(
+        inner: Arc<dyn EmbeddingProvider + Send + Sync>,
+        limit: Arc<ConcurrencyLimit>,
+    ) -> Self {
+        Self { inner, limit }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for ConcurrencyLimitedProvider {
+    async fn embed_batch(
+        &self,
+        request: BatchEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        let _permit = self.limit.acquire().await?;
+        self.inner.embed_batch(request).await
+    }
+
+    async fn model_info(&self) -> EmbeddingResult<ModelInfo> {
+        self.inner.model_info().await
+    }
+
+    async fn health_check(&self) -> EmbeddingResult<()> {
+        self.inner.health_check().await
+    }
+
+    async fn embed_multimodal(
+        &self,
+        request: MultimodalEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        let _permit = self.limit.acquire().await?;
+        self.inner.embed_multimodal(request).await
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<Range<usize>> {
+        self.inner.token_spans(text)
+    }
+}
+
+/// Token bucket refilled continuously at `qps` tokens per second, holding
+/// at most one second worth of requests.
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, qps: f64, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * qps).min(qps);
+        self.last_refill = now;
+    }
+}
+
+/// Per-tenant embed request rate limit.
+#[derive(Debug)]
+pub struct TenantRateLimiter {
+    qps: AtomicU32,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl TenantRateLimiter {
+    /// Allows each tenant `qps` requests per second, with bursts of up to
+    /// `qps` requests. A `qps` of 0 disables limiting.
+    pub fn new(qps: u32) -> Self {
+        Self {
+            qps: AtomicU32::new(qps),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Changes the per-tenant limit; buckets above the new limit are
+    /// trimmed on their next request.
+    pub fn set_qps(&self, qps: u32) {
+        self.qps.store(qps, Ordering::Relaxed);
+    }
+
+    /// Checks and consumes one request for `tenant`.
+    pub fn check(&self, tenant: &str) -> Result<(), Overload> {
+        let limit = self.qps.load(Ordering::Relaxed);
+        if limit == 0 {
+            return Ok(());
+        }
+        let qps = f64::from(limit);
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_TENANTS && !buckets.contains_key(tenant) {
+            // Full buckets carry no state worth keeping
+            buckets.retain(|_, bucket| {
+                bucket.refill(qps, now);
+                bucket.tokens < qps
+            });
+        }
+
+        let bucket = buckets
+            .entry(tenant.to_string())
+            .or_insert_with(|| TokenBucket {
+                tokens: qps,
+                last_refill: now,
+            });
+        bucket.refill(qps, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+
+        // Whole seconds until the next token, at least one
+        let wait = ((1.0 - bucket.tokens) / qps).ceil().max(1.0);
+        Err(Overload {
+            queue: QueueKind::EmbeddingRate,
+            depth: limit as usize,
+            limit: limit as usize,
+            retry_after: Duration::from_secs(wait as u64),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::ErrorCode;
+    use akidb_embedding::MockEmbeddingProvider;
+
+    fn request(text: &str) -> BatchEmbeddingRequest {
+        BatchEmbeddingRequest {
+            model: "mock-embed-512".to_string(),
+            inputs: vec![text.to_string()],
+            normalize: true,
+            truncation: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_rejects_after_wait() {
+        let limit = Arc::new(ConcurrencyLimit::new(1, Duration::from_millis(20)));
+        let provider =
+            ConcurrencyLimitedProvider::new(Arc::new(MockEmbeddingProvider::new()), limit.clone());
+
+        // A free slot is used and released
+        provider.embed_batch(request("hello")).await.unwrap();
+        assert_eq!(limit.in_flight(), 0);
+
+        // With the only slot held, the call times out
+        let held = limit.acquire().await.unwrap();
+        assert_eq!(limit.in_flight(), 1);
+        let err = provider.embed_batch(request("hello")).await.unwrap_err();
+        assert!(matches!(err, EmbeddingError::ServiceUnavailable(_)));
+
+        // Model info does not need a slot
+        assert!(provider.model_info().await.is_ok());
+
+        drop(held);
+        assert!(provider.embed_batch(request("hello")).await.is_ok());
+    }
+
+    #[test]
+    fn test_tenant_rate_limit() {
+        let limiter = TenantRateLimiter::new(2);
+        assert!(limiter.check("tenant-a").is_ok());
+        assert!(limiter.check("tenant-a").is_ok());
+
+        let overload = limiter.check("tenant-a").unwrap_err();
+        assert_eq!(overload.queue, QueueKind::EmbeddingRate);
+        assert_eq!(overload.code(), ErrorCode::RateLimited);
+        assert_eq!(overload.limit, 2);
+        assert_eq!(overload.retry_after_secs(), 1);
+
+        // Other tenants have their own budget
+        assert!(limiter.check("tenant-b").is_ok());
+
+        // Zero disables limiting
+        let unlimited = TenantRateLimiter::new(0);
+        for _ in 0..100 {
+            assert!(unlimited.check("tenant-a").is_ok());
+        }
+
+        // Limits can be lifted and reinstated at runtime
+        limiter.set_qps(0);
+        assert!(limiter.check("tenant-a").is_ok());
+        limiter.set_qps(1);
+        assert!(limiter.check("tenant-a").is_err());
+    }
+}
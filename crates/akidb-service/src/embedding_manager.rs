@@ -1,21 +1,240 @@
 //! Embedding Manager - Service layer for embedding generation
 //!
-//! Supports multiple embedding providers (Python-bridge, Mock)
-//! configured via the service Config struct.
+//! Supports multiple embedding providers (Python-bridge, HTTP, Candle, Mock)
+//! configured via the service Config struct. Remote providers are wrapped in a
+//! circuit breaker so an unhealthy embedding service fails fast instead of
+//! tying up request handlers. Results are cached by model and normalized text
+//! (see [`EmbeddingCache`]) so repeated inputs skip the provider, and small
+//! concurrent requests are coalesced into provider batches.
+//!
+//! Besides its default model, a manager can serve additional models declared
+//! under `[[embedding.models]]`. They are loaded on first use and the least
+//! recently used one is unloaded once `max_loaded_models` are resident.
+//! Collections pick a model through their `embedding_model` field.
+//!
+//! [`warmup`](EmbeddingManager::warmup) runs a dummy batch so the first real
+//! request does not pay for model loading, and [`health`](EmbeddingManager::health)
+//! reports whether the model is loaded and how fast it last answered.
+//!
+//! Inputs longer than the model's token limit are handled by the configured
+//! [`TruncationStrategy`] (see [`TruncatingProvider`]).
+//!
+//! Provider calls can be capped by a [`ConcurrencyLimit`] and embed requests
+//! by a per-tenant QPS limit (see [`crate::embedding_limits`]).
 //!
 //! Note: MLX provider has been deprecated in favor of Python-bridge with ONNX Runtime.
 
+use crate::backpressure::{Backpressure, Overload};
+use crate::config::{BackpressureConfig, EmbeddingConfig};
+use crate::embedding_batcher::EmbeddingBatcher;
+use crate::embedding_cache::{EmbeddingCache, EmbeddingCacheStats};
+use crate::embedding_limits::{ConcurrencyLimit, ConcurrencyLimitedProvider, TenantRateLimiter};
+use akidb_core::CollectionDescriptor;
 use akidb_embedding::{
-    BatchEmbeddingRequest, EmbeddingProvider, MockEmbeddingProvider, ModelInfo,
-    PythonBridgeProvider,
+    BatchEmbeddingRequest, BatchEmbeddingResponse, EmbeddingError, EmbeddingInput,
+    EmbeddingProvider, EmbeddingResult, HttpEmbeddingProvider, MockEmbeddingProvider, ModelInfo,
+    MultimodalEmbeddingRequest, PythonBridgeProvider, TruncatingProvider, TruncationStrategy,
 };
-use std::sync::Arc;
+use akidb_storage::{CircuitBreaker, CircuitBreakerConfig};
+use async_trait::async_trait;
+use lru::LruCache;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// `embedding_model` of collections created without one; uses the default model.
+const NO_COLLECTION_MODEL: &str = "none";
+
+/// Input embedded by [`EmbeddingManager::warmup`].
+const WARMUP_TEXT: &str = "warmup";
 
 /// Manages embedding generation using configured provider
 pub struct EmbeddingManager {
     provider: Arc<dyn EmbeddingProvider + Send + Sync>,
     model_name: String,
     dimension: u32,
+    /// Model token limit per input, including special tokens
+    max_tokens: usize,
+    /// Applied to inputs longer than `max_tokens`
+    truncation: TruncationStrategy,
+    /// Embed calls currently waiting on the provider
+    pending: AtomicUsize,
+    backpressure: Backpressure,
+    /// Caps provider calls running at once, shared with additional models
+    concurrency: Option<Arc<ConcurrencyLimit>>,
+    /// Embed requests per second allowed per tenant
+    tenant_limiter: TenantRateLimiter,
+    /// Cached embeddings keyed by model and normalized text
+    cache: Option<Arc<EmbeddingCache>>,
+    /// Coalesces small concurrent requests into provider batches
+    batcher: Option<EmbeddingBatcher>,
+    /// Additional models selectable by name
+    models: ModelRegistry,
+    /// Progress of the startup warmup batch
+    warmup: Mutex<WarmupStatus>,
+    /// Provider latency of the most recent successful embed call
+    last_inference: Mutex<Option<Duration>>,
+}
+
+/// Progress of [`EmbeddingManager::warmup`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum WarmupStatus {
+    /// Warmup was not requested; the model loads on first use
+    Skipped,
+    /// The warmup batch is running
+    Running,
+    /// The warmup batch completed
+    Done {
+        /// Time the warmup batch took, in milliseconds
+        duration_ms: u64,
+    },
+    /// The warmup batch failed
+    Failed {
+        /// Provider error
+        error: String,
+    },
+}
+
+/// Health of the default embedding model, for readiness probes.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingHealth {
+    /// Default model name
+    pub model: String,
+    /// Embedding dimension
+    pub dimension: u32,
+    /// Device or execution provider the model runs on, if reported
+    pub device: Option<String>,
+    /// Startup warmup progress
+    pub warmup: WarmupStatus,
+    /// Provider latency of the most recent embed call, in milliseconds
+    pub last_inference_ms: Option<f64>,
+    /// Provider health check error, if unhealthy
+    pub error: Option<String>,
+}
+
+impl EmbeddingHealth {
+    /// Whether the model is loaded and may receive traffic: the provider is
+    /// healthy and warmup is not running or failed.
+    pub fn is_ready(&self) -> bool {
+        self.error.is_none()
+            && matches!(
+                self.warmup,
+                WarmupStatus::Skipped | WarmupStatus::Done { .. }
+            )
+    }
+}
+
+/// Additional models, loaded on first use.
+struct ModelRegistry {
+    configs: HashMap<String, EmbeddingConfig>,
+    /// Loaded models, least recently used evicted first
+    loaded: Mutex<LruCache<String, Arc<EmbeddingManager>>>,
+}
+
+impl ModelRegistry {
+    fn new(configs: HashMap<String, EmbeddingConfig>, max_loaded: usize) -> Self {
+        let capacity = NonZeroUsize::new(max_loaded).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            configs,
+            loaded: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    fn loaded(&self) -> std::sync::MutexGuard<'_, LruCache<String, Arc<EmbeddingManager>>> {
+        self.loaded.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for ModelRegistry {
+    fn default() -> Self {
+        Self::new(HashMap::new(), 1)
+    }
+}
+
+/// Decrements the pending counter when an embed call finishes or is dropped.
+struct PendingGuard<'a>(&'a AtomicUsize);
+
+impl<'a> PendingGuard<'a> {
+    fn new(pending: &'a AtomicUsize) -> Self {
+        pending.fetch_add(1, Ordering::Relaxed);
+        Self(pending)
+    }
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Provider wrapper that stops calling an unhealthy backend.
+///
+/// Calls are rejected with `ServiceUnavailable` while the circuit is open.
+/// Invalid input is the caller's fault and does not count as a failure.
+struct CircuitBreakingProvider {
+    inner: Arc<dyn EmbeddingProvider + Send + Sync>,
+    breaker: CircuitBreaker,
+}
+
+impl CircuitBreakingProvider {
+    fn new(inner: Arc<dyn EmbeddingProvider + Send + Sync>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            inner,
+            breaker: CircuitBreaker::new(config),
+        }
+    }
+
+    fn admit(&self) -> EmbeddingResult<()> {
+        if self.breaker.should_allow_request() {
+            Ok(())
+        } else {
+            Err(EmbeddingError::ServiceUnavailable(
+                "Embedding provider circuit breaker is open".to_string(),
+            ))
+        }
+    }
+
+    fn record<T>(&self, result: EmbeddingResult<T>) -> EmbeddingResult<T> {
+        let success = !matches!(
+            result,
+            Err(EmbeddingError::ServiceUnavailable(_) | EmbeddingError::Internal(_))
+        );
+        self.breaker.record_result(success);
+        result
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for CircuitBreakingProvider {
+    async fn embed_batch(
+        &self,
+        request: BatchEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        self.admit()?;
+        self.record(self.inner.embed_batch(request).await)
+    }
+
+    async fn model_info(&self) -> EmbeddingResult<ModelInfo> {
+        self.admit()?;
+        self.record(self.inner.model_info().await)
+    }
+
+    async fn health_check(&self) -> EmbeddingResult<()> {
+        self.admit()?;
+        self.record(self.inner.health_check().await)
+    }
+
+    async fn embed_multimodal(
+        &self,
+        request: MultimodalEmbeddingRequest,
+    ) -> EmbeddingResult<BatchEmbeddingResponse> {
+        self.admit()?;
+        self.record(self.inner.embed_multimodal(request).await)
+    }
 }
 
 impl EmbeddingManager {
@@ -62,14 +281,180 @@ impl EmbeddingManager {
                         .to_string(),
                 );
             }
+            "http" => {
+                return Err(
+                    "The http provider is configured via [embedding.http]; use from_embedding_config."
+                        .to_string(),
+                );
+            }
             _ => {
                 return Err(format!(
-                    "Unknown provider type: '{}'. Supported: python-bridge, mock",
+                    "Unknown provider type: '{}'. Supported: python-bridge, http, mock",
                     provider_type
                 ))
             }
         };
 
+        Self::with_provider(provider, provider_type, model_name).await
+    }
+
+    /// Create EmbeddingManager from the `[embedding]` config section
+    ///
+    /// Handles every provider `from_config` does plus `"http"`, which calls
+    /// the service described by `[embedding.http]` behind a circuit breaker,
+    /// `"candle"` (with the `candle` feature), which runs the model in
+    /// `model_path` in-process, and `"clip"` (with the `clip` feature), which
+    /// embeds both text and images with the CLIP model in `model_path`. The
+    /// result cache is set up from
+    /// `cache_max_entries` and `cache_path`, micro-batching from
+    /// `max_batch_size` and `max_batch_wait_ms`, request limits from
+    /// `max_concurrent_requests`, `concurrency_wait_ms` and `tenant_qps`, and
+    /// additional models from `models` and `max_loaded_models`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the provider is unknown, misconfigured, or fails to
+    /// report its model info, or if the cache file cannot be opened.
+    pub async fn from_embedding_config(config: &EmbeddingConfig) -> Result<Self, String> {
+        let concurrency = ConcurrencyLimit::from_config(config);
+        let mut manager = Self::uncached_from_embedding_config(config, concurrency)
+            .await?
+            .with_tenant_qps(config.tenant_qps);
+        manager.cache = EmbeddingCache::from_config(config)
+            .map_err(|e| format!("Failed to open embedding cache: {}", e))?
+            .map(Arc::new);
+
+        let models = config.models.iter().filter_map(|model| {
+            let settings = config.for_model(&model.name)?;
+            Some((model.name.clone(), settings))
+        });
+        Ok(manager.with_models(models, config.max_loaded_models))
+    }
+
+    /// Provider and micro-batching for `config`, without cache or extra models.
+    async fn uncached_from_embedding_config(
+        config: &EmbeddingConfig,
+        concurrency: Option<Arc<ConcurrencyLimit>>,
+    ) -> Result<Self, String> {
+        let mut manager = Self::provider_from_embedding_config(config)
+            .await?
+            .with_truncation(config.truncation);
+        if let Some(limit) = concurrency {
+            manager = manager.with_concurrency_limit(limit);
+        }
+        if config.max_batch_wait_ms > 0 {
+            Ok(manager.with_batching(
+                config.max_batch_size,
+                Duration::from_millis(config.max_batch_wait_ms),
+            ))
+        } else {
+            Ok(manager)
+        }
+    }
+
+    async fn provider_from_embedding_config(config: &EmbeddingConfig) -> Result<Self, String> {
+        if config.provider == "candle" {
+            return Self::from_candle_config(config).await;
+        }
+        if config.provider == "clip" {
+            return Self::from_clip_config(config).await;
+        }
+        if config.provider != "http" {
+            return Self::from_config(
+                &config.provider,
+                &config.model,
+                config.python_path.as_deref(),
+            )
+            .await;
+        }
+
+        let http_config = akidb_embedding::HttpProviderConfig {
+            model: config.model.clone(),
+            ..config
+                .http
+                .clone()
+                .ok_or("embedding.http must be set for the http provider")?
+        };
+        tracing::info!(
+            provider = "http",
+            model = %config.model,
+            url = %http_config.url,
+            "Initializing embedding manager"
+        );
+
+        let http = HttpEmbeddingProvider::new(http_config)
+            .map_err(|e| format!("Failed to initialize HTTP provider: {}", e))?;
+        let breaker = CircuitBreakerConfig {
+            failure_threshold: config.circuit_failure_threshold,
+            cooldown_duration: Duration::from_secs(config.circuit_cooldown_seconds),
+            half_open_successes: 1,
+            ..CircuitBreakerConfig::default()
+        };
+        let provider = Arc::new(CircuitBreakingProvider::new(Arc::new(http), breaker));
+
+        Self::with_provider(provider, "http", &config.model).await
+    }
+
+    #[cfg(feature = "candle")]
+    async fn from_candle_config(config: &EmbeddingConfig) -> Result<Self, String> {
+        let dir = config
+            .model_path
+            .as_ref()
+            .ok_or("embedding.model_path must be set for the candle provider")?;
+        tracing::info!(
+            provider = "candle",
+            model = %config.model,
+            path = %dir.display(),
+            "Initializing embedding manager"
+        );
+
+        let provider = akidb_embedding::CandleEmbeddingProvider::with_config(
+            akidb_embedding::CandleConfig::from_dir(dir, &config.model),
+        )
+        .await
+        .map_err(|e| format!("Failed to initialize Candle provider: {}", e))?;
+
+        Self::with_provider(Arc::new(provider), "candle", &config.model).await
+    }
+
+    #[cfg(not(feature = "candle"))]
+    async fn from_candle_config(_config: &EmbeddingConfig) -> Result<Self, String> {
+        Err("The candle provider requires building with the `candle` feature".to_string())
+    }
+
+    #[cfg(feature = "clip")]
+    async fn from_clip_config(config: &EmbeddingConfig) -> Result<Self, String> {
+        let dir = config
+            .model_path
+            .as_ref()
+            .ok_or("embedding.model_path must be set for the clip provider")?;
+        tracing::info!(
+            provider = "clip",
+            model = %config.model,
+            path = %dir.display(),
+            "Initializing embedding manager"
+        );
+
+        let clip_config = akidb_embedding::ClipConfig {
+            allowed_image_hosts: config.image_url_hosts.clone(),
+            ..akidb_embedding::ClipConfig::from_dir(dir, &config.model)
+        };
+        let provider = akidb_embedding::ClipEmbeddingProvider::with_config(clip_config)
+            .map_err(|e| format!("Failed to initialize CLIP provider: {}", e))?;
+
+        Self::with_provider(Arc::new(provider), "clip", &config.model).await
+    }
+
+    #[cfg(not(feature = "clip"))]
+    async fn from_clip_config(_config: &EmbeddingConfig) -> Result<Self, String> {
+        Err("The clip provider requires building with the `clip` feature".to_string())
+    }
+
+    async fn with_provider(
+        provider: Arc<dyn EmbeddingProvider + Send + Sync>,
+        provider_type: &str,
+        model_name: &str,
+    ) -> Result<Self, String> {
         // Get model info
         let model_info = provider
             .model_info()
@@ -84,12 +469,340 @@ impl EmbeddingManager {
         );
 
         Ok(Self {
-            provider,
+            provider: Arc::new(TruncatingProvider::new(provider, model_info.max_tokens)),
             model_name: model_name.to_string(),
             dimension: model_info.dimension,
+            max_tokens: model_info.max_tokens,
+            truncation: TruncationStrategy::default(),
+            pending: AtomicUsize::new(0),
+            backpressure: Backpressure::default(),
+            concurrency: None,
+            tenant_limiter: TenantRateLimiter::new(0),
+            cache: None,
+            batcher: None,
+            models: ModelRegistry::default(),
+            warmup: Mutex::new(WarmupStatus::Skipped),
+            last_inference: Mutex::new(None),
         })
     }
 
+    /// Coalesces concurrent embed calls into batches of up to `max_batch_size`
+    /// texts, waiting at most `max_wait` for a batch to fill (builder pattern).
+    ///
+    /// Calls with `max_batch_size` or more texts go to the provider directly.
+    /// Must be called within a Tokio runtime.
+    pub fn with_batching(mut self, max_batch_size: usize, max_wait: Duration) -> Self {
+        self.batcher = Some(EmbeddingBatcher::spawn(
+            self.provider.clone(),
+            self.model_name.clone(),
+            self.truncation,
+            max_batch_size,
+            max_wait,
+        ));
+        self
+    }
+
+    /// Handles inputs over the model's token limit with `truncation`
+    /// (builder pattern). Call before [`with_batching`](Self::with_batching),
+    /// which captures the strategy.
+    pub fn with_truncation(mut self, truncation: TruncationStrategy) -> Self {
+        self.truncation = truncation;
+        self
+    }
+
+    /// Holds a slot of `limit` while calling the provider (builder pattern).
+    /// Call before [`with_batching`](Self::with_batching), which captures
+    /// the provider.
+    pub fn with_concurrency_limit(mut self, limit: Arc<ConcurrencyLimit>) -> Self {
+        self.provider = Arc::new(ConcurrencyLimitedProvider::new(
+            self.provider,
+            limit.clone(),
+        ));
+        self.concurrency = Some(limit);
+        self
+    }
+
+    /// Allows each tenant `qps` embed requests per second; 0 disables the
+    /// limit (builder pattern). Enforced by [`check_tenant_rate`](Self::check_tenant_rate).
+    pub fn with_tenant_qps(mut self, qps: u32) -> Self {
+        self.tenant_limiter = TenantRateLimiter::new(qps);
+        self
+    }
+
+    /// Serves repeated inputs from `cache` instead of the provider (builder pattern).
+    pub fn with_cache(mut self, cache: EmbeddingCache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Hit/miss counters of the result cache, if caching is enabled.
+    pub fn cache_stats(&self) -> Option<EmbeddingCacheStats> {
+        self.cache.as_deref().map(EmbeddingCache::stats)
+    }
+
+    /// Registers additional models by name, keeping at most `max_loaded` of
+    /// them loaded at once (builder pattern).
+    ///
+    /// Models are loaded on first use with `from_embedding_config` semantics
+    /// and share this manager's result cache.
+    pub fn with_models(
+        mut self,
+        models: impl IntoIterator<Item = (String, EmbeddingConfig)>,
+        max_loaded: usize,
+    ) -> Self {
+        self.models = ModelRegistry::new(models.into_iter().collect(), max_loaded);
+        self
+    }
+
+    /// Whether `model` is the default model or a registered additional model.
+    pub fn has_model(&self, model: &str) -> bool {
+        model == self.model_name || self.models.configs.contains_key(model)
+    }
+
+    /// Default model followed by the additional models, sorted by name.
+    pub fn model_names(&self) -> Vec<String> {
+        let mut extra: Vec<_> = self.models.configs.keys().cloned().collect();
+        extra.sort();
+        std::iter::once(self.model_name.clone())
+            .chain(extra)
+            .collect()
+    }
+
+    /// Additional models currently loaded, most recently used first.
+    pub fn loaded_models(&self) -> Vec<String> {
+        self.models
+            .loaded()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Model embedding texts for `collection`, if it is served by this manager.
+    pub fn model_for_collection<'a>(
+        &'a self,
+        collection: &'a CollectionDescriptor,
+    ) -> Option<&'a str> {
+        match collection.embedding_model.as_str() {
+            NO_COLLECTION_MODEL => Some(&self.model_name),
+            model if self.has_model(model) => Some(model),
+            _ => None,
+        }
+    }
+
+    /// Generate embeddings with the model selected by `collection`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the collection's model is not configured or embedding fails.
+    pub async fn embed_for_collection(
+        &self,
+        collection: &CollectionDescriptor,
+        texts: Vec<String>,
+    ) -> Result<Vec<Vec<f32>>, String> {
+        let model = self.model_for_collection(collection).ok_or_else(|| {
+            format!(
+                "Collection '{}' uses embedding model '{}', which is not configured",
+                collection.name, collection.embedding_model
+            )
+        })?;
+        self.embed_with_model(Some(model), texts).await
+    }
+
+    /// Generate embeddings with `model`, or the default model when `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the model is unknown, fails to load, or embedding fails.
+    pub async fn embed_with_model(
+        &self,
+        model: Option<&str>,
+        texts: Vec<String>,
+    ) -> Result<Vec<Vec<f32>>, String> {
+        match model {
+            Some(name) if name != self.model_name => {
+                let manager = self.model_manager(name).await?;
+                // Count toward this manager's backpressure as well
+                let _pending = PendingGuard::new(&self.pending);
+                manager.embed(texts).await
+            }
+            _ => self.embed(texts).await,
+        }
+    }
+
+    /// Generate embeddings for mixed text and image inputs with `model`, or
+    /// the default model when `None`, returned in input order.
+    ///
+    /// Texts go through the cache like [`embed`](Self::embed); images are
+    /// sent to the provider, which must support them (e.g. `clip`).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the model is unknown, does not accept images, or
+    /// embedding fails.
+    pub async fn embed_inputs(
+        &self,
+        model: Option<&str>,
+        inputs: Vec<EmbeddingInput>,
+    ) -> Result<Vec<Vec<f32>>, String> {
+        if let Some(name) = model.filter(|name| *name != self.model_name) {
+            let manager = self.model_manager(name).await?;
+            let _pending = PendingGuard::new(&self.pending);
+            return manager.embed_default_inputs(inputs).await;
+        }
+        self.embed_default_inputs(inputs).await
+    }
+
+    async fn embed_default_inputs(
+        &self,
+        inputs: Vec<EmbeddingInput>,
+    ) -> Result<Vec<Vec<f32>>, String> {
+        if inputs.is_empty() {
+            return Err("Cannot embed empty input list".to_string());
+        }
+
+        let mut texts = Vec::new();
+        let mut images = Vec::new();
+        for input in &inputs {
+            match input {
+                EmbeddingInput::Text(text) => texts.push(text.clone()),
+                EmbeddingInput::Image(_) => images.push(input.clone()),
+            }
+        }
+
+        let mut text_embeddings = if texts.is_empty() {
+            Vec::new()
+        } else {
+            self.embed(texts).await?
+        }
+        .into_iter();
+        let mut image_embeddings = if images.is_empty() {
+            Vec::new()
+        } else {
+            let _pending = PendingGuard::new(&self.pending);
+            self.provider
+                .embed_multimodal(MultimodalEmbeddingRequest {
+                    model: self.model_name.clone(),
+                    inputs: images,
+                    normalize: true,
+                })
+                .await
+                .map_err(|e| format!("Embedding failed: {}", e))?
+                .embeddings
+        }
+        .into_iter();
+
+        inputs
+            .iter()
+            .map(|input| match input {
+                EmbeddingInput::Text(_) => text_embeddings.next(),
+                EmbeddingInput::Image(_) => image_embeddings.next(),
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| "Embedding failed: provider returned too few embeddings".to_string())
+    }
+
+    /// Tokens `texts` use with `model`, or the default model when `None`,
+    /// after truncation. Counted with the model's tokenizer where available.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the model is unknown or fails to load, or if a text
+    /// is too long and the truncation strategy is `error`.
+    pub async fn count_tokens(
+        &self,
+        model: Option<&str>,
+        texts: &[String],
+    ) -> Result<usize, String> {
+        match model {
+            Some(name) if name != self.model_name => {
+                self.model_manager(name).await?.count_default_tokens(texts)
+            }
+            _ => self.count_default_tokens(texts),
+        }
+    }
+
+    fn count_default_tokens(&self, texts: &[String]) -> Result<usize, String> {
+        akidb_embedding::prepare_inputs(
+            self.provider.as_ref(),
+            texts.to_vec(),
+            self.max_tokens,
+            self.truncation,
+        )
+        .map(|prepared| prepared.total_tokens())
+        .map_err(|e| e.to_string())
+    }
+
+    /// Get model information for `model`, or the default model when `None`.
+    pub async fn model_info_for(&self, model: Option<&str>) -> Result<ModelInfo, String> {
+        match model {
+            Some(name) if name != self.model_name => {
+                self.model_manager(name).await?.model_info().await
+            }
+            _ => self.model_info().await,
+        }
+    }
+
+    /// Returns the loaded additional model `name`, loading it if needed.
+    async fn model_manager(&self, name: &str) -> Result<Arc<EmbeddingManager>, String> {
+        if let Some(manager) = self.models.loaded().get(name) {
+            return Ok(manager.clone());
+        }
+        let config = self.models.configs.get(name).ok_or_else(|| {
+            format!(
+                "Unknown embedding model '{}'. Configured: {}",
+                name,
+                self.model_names().join(", ")
+            )
+        })?;
+
+        tracing::info!(model = %name, provider = %config.provider, "Loading embedding model");
+        let mut manager =
+            Self::uncached_from_embedding_config(config, self.concurrency.clone()).await?;
+        manager.cache = self.cache.clone();
+        let manager = Arc::new(manager);
+
+        let mut loaded = self.models.loaded();
+        if let Some(existing) = loaded.get(name) {
+            // Loaded concurrently by another request
+            return Ok(existing.clone());
+        }
+        if let Some((evicted, _)) = loaded.push(name.to_string(), manager.clone()) {
+            tracing::info!(model = %evicted, "Unloaded least recently used embedding model");
+        }
+        Ok(manager)
+    }
+
+    /// Sets the queue-depth threshold for admitting embed requests (builder pattern).
+    pub fn with_backpressure(mut self, config: &BackpressureConfig) -> Self {
+        self.backpressure = Backpressure::new(config.clone());
+        self
+    }
+
+    /// Number of embed calls currently in flight.
+    pub fn pending_requests(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Checks whether another embed request can be admitted.
+    pub fn check_backpressure(&self) -> Result<(), Overload> {
+        self.backpressure.check_embeddings(self.pending_requests())
+    }
+
+    /// Changes the per-tenant embed request limit at runtime; 0 disables it.
+    pub fn set_tenant_qps(&self, qps: u32) {
+        self.tenant_limiter.set_qps(qps);
+    }
+
+    /// Checks and consumes one embed request from `tenant`'s QPS budget.
+    pub fn check_tenant_rate(&self, tenant: &str) -> Result<(), Overload> {
+        self.tenant_limiter.check(tenant)
+    }
+
+    /// Provider calls currently holding a concurrency slot, if limited.
+    pub fn concurrent_requests(&self) -> Option<usize> {
+        self.concurrency.as_deref().map(ConcurrencyLimit::in_flight)
+    }
+
     /// Generate embeddings for a list of texts
     ///
     /// # Arguments
@@ -103,15 +816,79 @@ impl EmbeddingManager {
     /// # Errors
     ///
     /// Returns error if embedding generation fails
+    #[tracing::instrument(name = "embedding.embed", skip_all, fields(model = %self.model_name, texts = texts.len()))]
     pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
         if texts.is_empty() {
             return Err("Cannot embed empty text list".to_string());
         }
 
+        let Some(cache) = &self.cache else {
+            return self.embed_uncached(texts).await;
+        };
+
+        // Serve what we can from the cache; embed each distinct miss once
+        let keys: Vec<_> = texts
+            .iter()
+            .map(|text| EmbeddingCache::key(&self.model_name, text))
+            .collect();
+        let mut results: Vec<Option<Vec<f32>>> = keys.iter().map(|key| cache.get(key)).collect();
+
+        let mut miss_index = HashMap::new();
+        let mut miss_keys = Vec::new();
+        let mut miss_texts = Vec::new();
+        for (text, (key, result)) in texts.into_iter().zip(keys.iter().zip(&results)) {
+            if result.is_none() && !miss_index.contains_key(key) {
+                miss_index.insert(*key, miss_keys.len());
+                miss_keys.push(*key);
+                miss_texts.push(text);
+            }
+        }
+
+        if !miss_texts.is_empty() {
+            let embeddings = self.embed_uncached(miss_texts).await?;
+            if embeddings.len() != miss_keys.len() {
+                return Err(format!(
+                    "Embedding failed: provider returned {} embeddings for {} inputs",
+                    embeddings.len(),
+                    miss_keys.len()
+                ));
+            }
+            for (slot, key) in results.iter_mut().zip(&keys) {
+                if slot.is_none() {
+                    *slot = Some(embeddings[miss_index[key]].clone());
+                }
+            }
+            cache.insert_all(miss_keys.into_iter().zip(embeddings));
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    pub(crate) async fn embed_uncached(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+        let start = Instant::now();
+        let result = self.embed_with_provider(texts).await;
+        if result.is_ok() {
+            *self.last_inference.lock().unwrap() = Some(start.elapsed());
+        }
+        result
+    }
+
+    async fn embed_with_provider(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+        let _pending = PendingGuard::new(&self.pending);
+        if let Some(batcher) = &self.batcher {
+            if texts.len() < batcher.max_batch_size() {
+                return batcher
+                    .embed(texts)
+                    .await
+                    .map_err(|e| format!("Embedding failed: {}", e));
+            }
+        }
+
         let request = BatchEmbeddingRequest {
             model: self.model_name.clone(),
             inputs: texts,
             normalize: true,
+            truncation: self.truncation,
         };
 
         let response = self
@@ -123,6 +900,84 @@ impl EmbeddingManager {
         Ok(response.embeddings)
     }
 
+    /// Embeds a dummy batch with the default model, bypassing the cache, so
+    /// the model is loaded (and kernels compiled) before real traffic.
+    ///
+    /// Progress is reported by [`health`](Self::health) while it runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the provider fails to embed the batch.
+    pub async fn warmup(&self) -> Result<Duration, String> {
+        *self.warmup.lock().unwrap() = WarmupStatus::Running;
+        let start = Instant::now();
+        let result = self
+            .embed_uncached(vec![WARMUP_TEXT.to_string()])
+            .await
+            .map(|_| start.elapsed());
+
+        *self.warmup.lock().unwrap() = match &result {
+            Ok(duration) => WarmupStatus::Done {
+                duration_ms: duration.as_millis() as u64,
+            },
+            Err(e) => WarmupStatus::Failed { error: e.clone() },
+        };
+        result
+    }
+
+    /// Runs [`warmup`](Self::warmup) in the background. Health reports
+    /// `running` from the moment this returns until the batch finishes.
+    pub fn spawn_warmup(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        *self.warmup.lock().unwrap() = WarmupStatus::Running;
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            match manager.warmup().await {
+                Ok(duration) => tracing::info!(
+                    model = %manager.model_name,
+                    duration_ms = duration.as_millis() as u64,
+                    "Embedding model warmed up"
+                ),
+                Err(e) => tracing::warn!(
+                    model = %manager.model_name,
+                    error = %e,
+                    "Embedding model warmup failed"
+                ),
+            }
+        })
+    }
+
+    /// Current warmup progress.
+    pub fn warmup_status(&self) -> WarmupStatus {
+        self.warmup.lock().unwrap().clone()
+    }
+
+    /// Provider latency of the most recent successful embed call.
+    pub fn last_inference_latency(&self) -> Option<Duration> {
+        *self.last_inference.lock().unwrap()
+    }
+
+    /// Checks the default model's provider and reports its readiness.
+    pub async fn health(&self) -> EmbeddingHealth {
+        let (device, error) = match self.provider.health_check().await {
+            Ok(()) => match self.provider.model_info().await {
+                Ok(info) => (info.execution_provider, None),
+                Err(e) => (None, Some(format!("Failed to get model info: {}", e))),
+            },
+            Err(e) => (None, Some(format!("Health check failed: {}", e))),
+        };
+
+        EmbeddingHealth {
+            model: self.model_name.clone(),
+            dimension: self.dimension,
+            device,
+            warmup: self.warmup_status(),
+            last_inference_ms: self
+                .last_inference_latency()
+                .map(|latency| latency.as_secs_f64() * 1000.0),
+            error,
+        }
+    }
+
     /// Get model information
     ///
     /// # Returns
@@ -250,4 +1105,320 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("empty"));
     }
+
+    /// Provider that always fails, counting how often it is called.
+    struct FailingProvider(AtomicUsize);
+
+    #[async_trait]
+    impl EmbeddingProvider for FailingProvider {
+        async fn embed_batch(
+            &self,
+            _request: BatchEmbeddingRequest,
+        ) -> EmbeddingResult<BatchEmbeddingResponse> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(EmbeddingError::ServiceUnavailable("down".to_string()))
+        }
+
+        async fn model_info(&self) -> EmbeddingResult<ModelInfo> {
+            unreachable!()
+        }
+
+        async fn health_check(&self) -> EmbeddingResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_rejects_after_failures() {
+        let inner = Arc::new(FailingProvider(AtomicUsize::new(0)));
+        let provider = CircuitBreakingProvider::new(inner.clone(), CircuitBreakerConfig::default());
+        let request = || BatchEmbeddingRequest {
+            model: "m".to_string(),
+            inputs: vec!["text".to_string()],
+            normalize: true,
+            truncation: TruncationStrategy::default(),
+        };
+
+        for _ in 0..10 {
+            assert!(provider.embed_batch(request()).await.is_err());
+        }
+        assert_eq!(inner.0.load(Ordering::SeqCst), 10);
+
+        // Open circuit: rejected without reaching the backend
+        let err = provider.embed_batch(request()).await.unwrap_err();
+        assert!(err.to_string().contains("circuit breaker is open"));
+        assert_eq!(inner.0.load(Ordering::SeqCst), 10);
+    }
+
+    #[tokio::test]
+    async fn test_http_provider_requires_config() {
+        let config = EmbeddingConfig {
+            provider: "http".to_string(),
+            ..EmbeddingConfig::default()
+        };
+        let result = EmbeddingManager::from_embedding_config(&config).await;
+        assert!(result.err().unwrap().contains("embedding.http"));
+    }
+
+    /// Provider embedding each text as `[text length]`, counting inputs seen.
+    struct CountingProvider(AtomicUsize);
+
+    #[async_trait]
+    impl EmbeddingProvider for CountingProvider {
+        async fn embed_batch(
+            &self,
+            request: BatchEmbeddingRequest,
+        ) -> EmbeddingResult<BatchEmbeddingResponse> {
+            self.0.fetch_add(request.inputs.len(), Ordering::SeqCst);
+            Ok(BatchEmbeddingResponse {
+                model: request.model,
+                embeddings: request
+                    .inputs
+                    .iter()
+                    .map(|t| vec![t.len() as f32])
+                    .collect(),
+                usage: akidb_embedding::Usage {
+                    total_tokens: 0,
+                    duration_ms: 0,
+                },
+            })
+        }
+
+        async fn model_info(&self) -> EmbeddingResult<ModelInfo> {
+            Ok(ModelInfo {
+                model: "counting".to_string(),
+                dimension: 1,
+                max_tokens: 512,
+                execution_provider: None,
+            })
+        }
+
+        async fn health_check(&self) -> EmbeddingResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_skips_provider_for_repeated_texts() {
+        let provider = Arc::new(CountingProvider(AtomicUsize::new(0)));
+        let manager = EmbeddingManager::with_provider(provider.clone(), "counting", "counting")
+            .await
+            .unwrap()
+            .with_cache(EmbeddingCache::new(100));
+        let texts = |items: &[&str]| items.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        let first = manager.embed(texts(&["a", "bb", "a"])).await.unwrap();
+        assert_eq!(first, vec![vec![1.0], vec![2.0], vec![1.0]]);
+        assert_eq!(provider.0.load(Ordering::SeqCst), 2);
+
+        // Only "ccc" is new; " bb " normalizes to a cached text
+        let second = manager.embed(texts(&[" bb ", "ccc"])).await.unwrap();
+        assert_eq!(second, vec![vec![2.0], vec![3.0]]);
+        assert_eq!(provider.0.load(Ordering::SeqCst), 3);
+
+        let stats = manager.cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses), (1, 4));
+        assert_eq!(stats.entries, 3);
+    }
+
+    #[tokio::test]
+    async fn test_batching_coalesces_concurrent_embeds() {
+        let provider = Arc::new(CountingProvider(AtomicUsize::new(0)));
+        let manager = EmbeddingManager::with_provider(provider.clone(), "counting", "counting")
+            .await
+            .unwrap()
+            .with_batching(8, Duration::from_millis(50));
+
+        let (a, b) = tokio::join!(
+            manager.embed(vec!["a".to_string()]),
+            manager.embed(vec!["bb".to_string()]),
+        );
+        assert_eq!(a.unwrap(), vec![vec![1.0]]);
+        assert_eq!(b.unwrap(), vec![vec![2.0]]);
+        assert_eq!(provider.0.load(Ordering::SeqCst), 2);
+    }
+
+    /// Settings for an additional model served by the mock provider.
+    fn mock_config() -> EmbeddingConfig {
+        EmbeddingConfig {
+            provider: "mock".to_string(),
+            model: "mock-embed-512".to_string(),
+            ..EmbeddingConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_additional_models_load_lazily_with_lru_unload() {
+        let manager = EmbeddingManager::from_config("mock", "mock-embed-512", None)
+            .await
+            .unwrap()
+            .with_models(
+                [
+                    ("a".to_string(), mock_config()),
+                    ("b".to_string(), mock_config()),
+                ],
+                1,
+            );
+        assert_eq!(manager.model_names(), vec!["mock-embed-512", "a", "b"]);
+        assert!(manager.loaded_models().is_empty());
+
+        let texts = vec!["hello".to_string()];
+        manager
+            .embed_with_model(Some("a"), texts.clone())
+            .await
+            .unwrap();
+        assert_eq!(manager.loaded_models(), vec!["a"]);
+
+        manager
+            .embed_with_model(Some("b"), texts.clone())
+            .await
+            .unwrap();
+        assert_eq!(manager.loaded_models(), vec!["b"]);
+
+        // The default model is always resident
+        manager.embed_with_model(None, texts.clone()).await.unwrap();
+        manager
+            .embed_with_model(Some("mock-embed-512"), texts.clone())
+            .await
+            .unwrap();
+        assert_eq!(manager.loaded_models(), vec!["b"]);
+
+        let err = manager
+            .embed_with_model(Some("c"), texts)
+            .await
+            .unwrap_err();
+        assert!(err.contains("Unknown embedding model 'c'"));
+    }
+
+    #[tokio::test]
+    async fn test_collection_selects_model() {
+        let manager = EmbeddingManager::from_config("mock", "mock-embed-512", None)
+            .await
+            .unwrap()
+            .with_models([("multilingual".to_string(), mock_config())], 2);
+        let collection = |model: &str| {
+            CollectionDescriptor::new(akidb_core::DatabaseId::new(), "docs", 512, model)
+        };
+
+        assert_eq!(
+            manager.model_for_collection(&collection("none")),
+            Some("mock-embed-512")
+        );
+        assert_eq!(
+            manager.model_for_collection(&collection("multilingual")),
+            Some("multilingual")
+        );
+        assert_eq!(manager.model_for_collection(&collection("other")), None);
+
+        let texts = vec!["hola".to_string()];
+        manager
+            .embed_for_collection(&collection("multilingual"), texts.clone())
+            .await
+            .unwrap();
+        assert_eq!(manager.loaded_models(), vec!["multilingual"]);
+        let err = manager
+            .embed_for_collection(&collection("other"), texts)
+            .await
+            .unwrap_err();
+        assert!(err.contains("not configured"));
+    }
+
+    #[tokio::test]
+    async fn test_embed_inputs_mixes_text_and_images() {
+        let manager = EmbeddingManager::from_config("mock", "mock-embed-512", None)
+            .await
+            .unwrap()
+            .with_cache(EmbeddingCache::new(100));
+        let image = akidb_embedding::ImageInput::Url("https://example.com/cat.png".to_string());
+
+        let embeddings = manager
+            .embed_inputs(
+                None,
+                vec![
+                    EmbeddingInput::Image(image.clone()),
+                    EmbeddingInput::Text("a cat".to_string()),
+                    EmbeddingInput::Image(image),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(embeddings.len(), 3);
+        assert_eq!(embeddings[0], embeddings[2]);
+        assert_ne!(embeddings[0], embeddings[1]);
+
+        // Text goes through the same cached path as `embed`
+        let text = manager.embed(vec!["a cat".to_string()]).await.unwrap();
+        assert_eq!(embeddings[1], text[0]);
+        assert_eq!(manager.cache_stats().unwrap().hits, 1);
+
+        // Text-only providers reject images
+        let counting = EmbeddingManager::with_provider(
+            Arc::new(CountingProvider(AtomicUsize::new(0))),
+            "counting",
+            "counting",
+        )
+        .await
+        .unwrap();
+        let err = counting
+            .embed_inputs(
+                None,
+                vec![EmbeddingInput::Image(akidb_embedding::ImageInput::Bytes(
+                    vec![1, 2, 3],
+                ))],
+            )
+            .await
+            .unwrap_err();
+        assert!(err.contains("does not support image inputs"));
+    }
+
+    #[tokio::test]
+    async fn test_truncation_strategy_and_token_count() {
+        let manager = EmbeddingManager::from_config("mock", "mock-embed-512", None)
+            .await
+            .unwrap();
+        let long = vec!["word ".repeat(9000)];
+        let short = vec!["a short text".to_string()];
+
+        // Default: truncated to the limit minus two special tokens
+        assert_eq!(manager.count_tokens(None, &long).await.unwrap(), 8190);
+        assert_eq!(manager.count_tokens(None, &short).await.unwrap(), 4);
+        assert_eq!(manager.embed(long.clone()).await.unwrap().len(), 1);
+
+        let strict = EmbeddingManager::from_config("mock", "mock-embed-512", None)
+            .await
+            .unwrap()
+            .with_truncation(TruncationStrategy::Error);
+        let err = strict.embed(long).await.unwrap_err();
+        assert!(err.contains("Invalid input: Input 0 has 9000 tokens"));
+        assert!(strict.embed(short).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_and_tenant_limits_from_config() {
+        let config = EmbeddingConfig {
+            max_concurrent_requests: 2,
+            tenant_qps: 1,
+            cache_max_entries: 0,
+            ..mock_config()
+        };
+        let manager = EmbeddingManager::from_embedding_config(&config)
+            .await
+            .unwrap();
+
+        assert!(manager.embed(vec!["hello".to_string()]).await.is_ok());
+        assert_eq!(manager.concurrent_requests(), Some(0));
+
+        assert!(manager.check_tenant_rate("tenant-a").is_ok());
+        let overload = manager.check_tenant_rate("tenant-a").unwrap_err();
+        assert_eq!(overload.queue, crate::QueueKind::EmbeddingRate);
+        assert!(manager.check_tenant_rate("tenant-b").is_ok());
+
+        // Unlimited by default
+        let unlimited = EmbeddingManager::from_embedding_config(&mock_config())
+            .await
+            .unwrap();
+        assert_eq!(unlimited.concurrent_requests(), None);
+        assert!(unlimited.check_tenant_rate("tenant-a").is_ok());
+        assert!(unlimited.check_tenant_rate("tenant-a").is_ok());
+    }
 }
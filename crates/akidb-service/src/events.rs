@@ -0,0 +1,185 @@
+//! In-process change event bus.
+//!
+//! The collection service publishes an event after every successful mutation
+//! so API layers can push changes to subscribers (e.g. the REST watch
+//! WebSocket) instead of clients polling. Delivery is best-effort: slow
+//! subscribers that fall more than `EVENT_BUS_CAPACITY` events behind skip
+//! ahead and are told how many events they missed.
+//!
+//! Subscribers interested in one collection use
+//! [`EventBus::subscribe_collection`], which has its own channel, so a busy
+//! unrelated collection cannot make them lag.
+
+use akidb_core::{CollectionId, DocumentId};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before the oldest are dropped.
+pub const EVENT_BUS_CAPACITY: usize = 1024;
+
+/// What changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Document inserted or replaced
+    Insert { doc_id: DocumentId },
+    /// Document deleted
+    Delete { doc_id: DocumentId },
+    /// Collection moved between storage tiers
+    TierChange { tier: String },
+}
+
+/// A change to a single collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangeEvent {
+    pub collection_id: CollectionId,
+    #[serde(flatten)]
+    pub kind: ChangeKind,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ChangeEvent {
+    /// Creates an event timestamped now.
+    pub fn new(collection_id: CollectionId, kind: ChangeKind) -> Self {
+        Self {
+            collection_id,
+            kind,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Broadcast channels for change events: one for all collections and one per
+/// watched collection.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ChangeEvent>,
+    collections: Arc<Mutex<HashMap<CollectionId, broadcast::Sender<ChangeEvent>>>>,
+}
+
+impl EventBus {
+    /// Creates an event bus with `EVENT_BUS_CAPACITY` buffered events.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self {
+            sender,
+            collections: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Publishes an event. A no-op when nobody is subscribed.
+    pub fn publish(&self, event: ChangeEvent) {
+        {
+            let mut collections = self.collections.lock().expect("event bus lock poisoned");
+            if let Some(sender) = collections.get(&event.collection_id) {
+                // Drop the channel once its last subscriber has gone
+                if sender.send(event.clone()).is_err() {
+                    collections.remove(&event.collection_id);
+                }
+            }
+        }
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribes to events published from now on (all collections).
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribes to events for `collection_id` published from now on.
+    pub fn subscribe_collection(
+        &self,
+        collection_id: CollectionId,
+    ) -> broadcast::Receiver<ChangeEvent> {
+        let mut collections = self.collections.lock().expect("event bus lock poisoned");
+        // Subscribers of collections without events since they left are pruned here
+        collections.retain(|_, sender| sender.receiver_count() > 0);
+        collections
+            .entry(collection_id)
+            .or_insert_with(|| broadcast::channel(EVENT_BUS_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Number of active subscribers, across all channels.
+    pub fn subscriber_count(&self) -> usize {
+        let collections = self.collections.lock().expect("event bus lock poisoned");
+        self.sender.receiver_count()
+            + collections
+                .values()
+                .map(broadcast::Sender::receiver_count)
+                .sum::<usize>()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_subscribe() {
+        let bus = EventBus::new();
+        let collection_id = CollectionId::new();
+
+        // Publishing without subscribers is fine
+        bus.publish(ChangeEvent::new(
+            collection_id,
+            ChangeKind::TierChange {
+                tier: "hot".to_string(),
+            },
+        ));
+
+        let mut rx = bus.subscribe();
+        assert_eq!(bus.subscriber_count(), 1);
+
+        let doc_id = DocumentId::new();
+        bus.publish(ChangeEvent::new(
+            collection_id,
+            ChangeKind::Insert { doc_id },
+        ));
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.kind, ChangeKind::Insert { doc_id });
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "insert");
+        assert_eq!(json["doc_id"], doc_id.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_collection_subscribers_only_buffer_their_collection() {
+        let bus = EventBus::new();
+        let watched = CollectionId::new();
+        let mut rx = bus.subscribe_collection(watched);
+
+        // A busy unrelated collection does not fill the watcher's buffer
+        for _ in 0..EVENT_BUS_CAPACITY * 2 {
+            bus.publish(ChangeEvent::new(
+                CollectionId::new(),
+                ChangeKind::Delete {
+                    doc_id: DocumentId::new(),
+                },
+            ));
+        }
+        let doc_id = DocumentId::new();
+        bus.publish(ChangeEvent::new(watched, ChangeKind::Insert { doc_id }));
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.collection_id, watched);
+        assert_eq!(event.kind, ChangeKind::Insert { doc_id });
+
+        // The channel is dropped once its last subscriber leaves
+        drop(rx);
+        bus.publish(ChangeEvent::new(watched, ChangeKind::Insert { doc_id }));
+        assert_eq!(bus.subscriber_count(), 0);
+        assert!(bus.collections.lock().unwrap().is_empty());
+    }
+}
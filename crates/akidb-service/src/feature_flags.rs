@@ -0,0 +1,261 @@
+//! Runtime feature flags, evaluated per tenant.
+//!
+//! Every flag has a default: the built-in one from [`BUILTIN_FLAGS`],
+//! replaced by `[features] flags` in the config file. A flag stored in the
+//! metadata database (through the `/admin/feature-flags` API) replaces the
+//! default, and can be rolled out to a share of tenants or switched per
+//! tenant (see [`FeatureFlag::is_enabled_for`]). Unknown flags are off.
+//!
+//! Each process keeps the stored flags in memory and re-reads them every
+//! `features.flag_refresh_interval_seconds` (see [`spawn_refresh`]), so a
+//! change made through one server reaches the others within that interval.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use akidb_core::{
+    CoreError, CoreResult, DatabaseId, DatabaseRepository, FeatureFlag, FeatureFlagRepository,
+    TenantId,
+};
+use chrono::Utc;
+use tokio::task::JoinHandle;
+
+use crate::tenant_lookup::TenantLookup;
+
+/// Recording collection accesses for hot/warm/cold tiering.
+pub const TIERING: &str = "tiering";
+
+/// Hybrid (vector + BM25 keyword) search requests.
+pub const HYBRID_SEARCH: &str = "hybrid_search";
+
+/// Flags every deployment knows about: name, default state and description.
+pub const BUILTIN_FLAGS: &[(&str, bool, &str)] = &[
+    (
+        TIERING,
+        true,
+        "Record collection accesses for hot/warm/cold tiering",
+    ),
+    (
+        HYBRID_SEARCH,
+        true,
+        "Allow search requests that fuse vector and BM25 keyword scores",
+    ),
+];
+
+/// Feature flag registry backed by the metadata database.
+pub struct FeatureFlags {
+    repository: Option<Arc<dyn FeatureFlagRepository>>,
+    tenants: Option<TenantLookup>,
+    defaults: HashMap<String, FeatureFlag>,
+    stored: RwLock<HashMap<String, FeatureFlag>>,
+}
+
+impl FeatureFlags {
+    /// Creates an in-memory registry with the built-in defaults, replaced by
+    /// `overrides` (usually `features.flags`).
+    pub fn new(overrides: &BTreeMap<String, bool>) -> Self {
+        let mut defaults: HashMap<String, FeatureFlag> = BUILTIN_FLAGS
+            .iter()
+            .map(|(name, enabled, description)| {
+                let mut flag = FeatureFlag::new(*name, *enabled);
+                flag.description = Some((*description).to_string());
+                (flag.name.clone(), flag)
+            })
+            .collect();
+        for (name, enabled) in overrides {
+            defaults
+                .entry(name.clone())
+                .and_modify(|flag| flag.enabled = *enabled)
+                .or_insert_with(|| FeatureFlag::new(name.clone(), *enabled));
+        }
+
+        Self {
+            repository: None,
+            tenants: None,
+            defaults,
+            stored: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Persists flags to `repository` and resolves the tenant of a database
+    /// through `databases` (builder pattern).
+    pub fn with_repository(
+        mut self,
+        repository: Arc<dyn FeatureFlagRepository>,
+        databases: Arc<dyn DatabaseRepository>,
+    ) -> Self {
+        self.repository = Some(repository);
+        self.tenants = Some(TenantLookup::new(databases));
+        self
+    }
+
+    /// Re-reads the stored flags.
+    ///
+    /// # Errors
+    ///
+    /// Returns the repository's error, keeping the flags read last time.
+    pub async fn refresh(&self) -> CoreResult<()> {
+        let Some(repository) = &self.repository else {
+            return Ok(());
+        };
+        let flags = repository.list().await?;
+        *self.stored.write().expect("feature flag lock poisoned") = flags
+            .into_iter()
+            .map(|flag| (flag.name.clone(), flag))
+            .collect();
+        Ok(())
+    }
+
+    /// Whether `name` is on for `tenant_id`; without a tenant, whether it is
+    /// enabled at all.
+    pub fn is_enabled(&self, name: &str, tenant_id: Option<TenantId>) -> bool {
+        self.get(name)
+            .is_some_and(|flag| flag.is_enabled_for(tenant_id))
+    }
+
+    /// Whether `name` is on for the tenant owning `database_id`.
+    pub async fn is_enabled_for_database(&self, name: &str, database_id: DatabaseId) -> bool {
+        let tenant_id = match &self.tenants {
+            Some(tenants) => tenants.tenant_of(database_id).await,
+            None => None,
+        };
+        self.is_enabled(name, tenant_id)
+    }
+
+    /// The flag in effect for `name`: the stored one, else its default.
+    pub fn get(&self, name: &str) -> Option<FeatureFlag> {
+        self.stored
+            .read()
+            .expect("feature flag lock poisoned")
+            .get(name)
+            .or_else(|| self.defaults.get(name))
+            .cloned()
+    }
+
+    /// Every flag in effect, ordered by name.
+    pub fn list(&self) -> Vec<FeatureFlag> {
+        let stored = self.stored.read().expect("feature flag lock poisoned");
+        let mut flags: BTreeMap<&str, &FeatureFlag> = self
+            .defaults
+            .iter()
+            .map(|(name, flag)| (name.as_str(), flag))
+            .collect();
+        flags.extend(stored.iter().map(|(name, flag)| (name.as_str(), flag)));
+        flags.into_values().cloned().collect()
+    }
+
+    /// Stores `flag`, taking effect in this process immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error for an invalid name or rollout percentage,
+    /// or the repository's error.
+    pub async fn set(&self, mut flag: FeatureFlag) -> CoreResult<FeatureFlag> {
+        validate(&flag)?;
+        flag.updated_at = Utc::now();
+        if let Some(repository) = &self.repository {
+            repository.upsert(&flag).await?;
+        }
+        self.stored
+            .write()
+            .expect("feature flag lock poisoned")
+            .insert(flag.name.clone(), flag.clone());
+        Ok(flag)
+    }
+
+    /// Deletes the stored flag, so its default applies again. Returns
+    /// `false` if nothing was stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the repository's error.
+    pub async fn reset(&self, name: &str) -> CoreResult<bool> {
+        let deleted = match &self.repository {
+            Some(repository) => repository.delete(name).await?,
+            None => false,
+        };
+        let removed = self
+            .stored
+            .write()
+            .expect("feature flag lock poisoned")
+            .remove(name)
+            .is_some();
+        Ok(deleted || removed)
+    }
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new())
+    }
+}
+
+fn validate(flag: &FeatureFlag) -> CoreResult<()> {
+    let valid_name = !flag.name.is_empty()
+        && flag.name.len() <= 64
+        && flag
+            .name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+    if !valid_name {
+        return Err(CoreError::ValidationError(format!(
+            "Invalid feature flag name '{}': use 1-64 lowercase letters, digits or '_'",
+            flag.name
+        )));
+    }
+    if flag.rollout_percentage > 100 {
+        return Err(CoreError::ValidationError(format!(
+            "rollout_percentage must be 0-100, got {}",
+            flag.rollout_percentage
+        )));
+    }
+    Ok(())
+}
+
+/// Spawns a task re-reading `flags` from the metadata database every `interval`.
+pub fn spawn_refresh(flags: Arc<FeatureFlags>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            if let Err(e) = flags.refresh().await {
+                tracing::warn!("Failed to refresh feature flags: {}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stored_flag_replaces_default() {
+        let overrides = BTreeMap::from([("experimental".to_string(), true)]);
+        let flags = FeatureFlags::new(&overrides);
+        assert!(flags.is_enabled(TIERING, None));
+        assert!(flags.is_enabled("experimental", None));
+        assert!(!flags.is_enabled("unknown", None));
+
+        let tenant = TenantId::new();
+        let mut tiering = FeatureFlag::new(TIERING, false);
+        tiering.tenant_overrides.insert(tenant, true);
+        flags.set(tiering).await.unwrap();
+        assert!(!flags.is_enabled(TIERING, None));
+        assert!(flags.is_enabled(TIERING, Some(tenant)));
+        assert_eq!(flags.list().len(), BUILTIN_FLAGS.len() + 1);
+
+        assert!(flags.reset(TIERING).await.unwrap());
+        assert!(flags.is_enabled(TIERING, None));
+
+        let mut invalid = FeatureFlag::new("Bad Name", true);
+        assert!(flags.set(invalid.clone()).await.is_err());
+        invalid.name = "ok".to_string();
+        invalid.rollout_percentage = 101;
+        assert!(flags.set(invalid).await.is_err());
+    }
+}
@@ -0,0 +1,291 @@
+//! Metadata filters for search requests.
+//!
+//! Filters use a small MongoDB-style JSON syntax evaluated against each
+//! candidate's metadata:
+//!
+//! ```json
+//! {"category": "news", "year": {"$gte": 2020}, "$or": [{"lang": "en"}, {"lang": "de"}]}
+//! ```
+//!
+//! - `{"field": value}` — equality (dotted paths reach nested objects)
+//! - `{"field": {"$eq" | "$ne" | "$gt" | "$gte" | "$lt" | "$lte": value}}`
+//! - `{"field": {"$in" | "$nin": [values]}}`, `{"field": {"$exists": bool}}`
+//! - `{"$and": [..]}`, `{"$or": [..]}`, `{"$not": {..}}`
+//!
+//! Multiple keys in one object are combined with AND. Ordering comparisons
+//! apply to numbers and strings; comparing mismatched types never matches.
+
+use akidb_core::{CoreError, CoreResult};
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+
+/// Maximum nesting of `$and`/`$or`/`$not` accepted from clients.
+const MAX_FILTER_DEPTH: usize = 16;
+
+/// Comparison operator applied to a single field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// Parsed metadata filter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterTree {
+    /// All children must match (an empty `And` matches everything)
+    And(Vec<FilterTree>),
+    /// At least one child must match
+    Or(Vec<FilterTree>),
+    /// Child must not match
+    Not(Box<FilterTree>),
+    /// Compare a field against a value
+    Compare {
+        path: String,
+        op: FilterOp,
+        value: JsonValue,
+    },
+    /// Field value is one of `values`
+    In {
+        path: String,
+        values: Vec<JsonValue>,
+    },
+    /// Field is present (`true`) or absent (`false`)
+    Exists { path: String, exists: bool },
+}
+
+impl FilterTree {
+    /// Parses a JSON filter expression.
+    ///
+    /// Returns `CoreError::ValidationError` for unknown operators or
+    /// malformed operands.
+    pub fn parse(filter: &JsonValue) -> CoreResult<Self> {
+        Self::parse_at(filter, 0)
+    }
+
+    fn parse_at(filter: &JsonValue, depth: usize) -> CoreResult<Self> {
+        if depth > MAX_FILTER_DEPTH {
+            return Err(invalid(format!(
+                "filter nesting exceeds {} levels",
+                MAX_FILTER_DEPTH
+            )));
+        }
+
+        let fields = filter
+            .as_object()
+            .ok_or_else(|| invalid("filter must be a JSON object"))?;
+
+        let mut clauses = Vec::with_capacity(fields.len());
+        for (key, operand) in fields {
+            let clause = match key.as_str() {
+                "$and" => Self::And(Self::parse_list(key, operand, depth)?),
+                "$or" => Self::Or(Self::parse_list(key, operand, depth)?),
+                "$not" => Self::Not(Box::new(Self::parse_at(operand, depth + 1)?)),
+                op if op.starts_with('$') => {
+                    return Err(invalid(format!("unknown filter operator '{}'", op)));
+                }
+                path => Self::parse_field(path, operand)?,
+            };
+            clauses.push(clause);
+        }
+
+        Ok(if clauses.len() == 1 {
+            clauses.remove(0)
+        } else {
+            Self::And(clauses)
+        })
+    }
+
+    fn parse_list(key: &str, operand: &JsonValue, depth: usize) -> CoreResult<Vec<Self>> {
+        operand
+            .as_array()
+            .ok_or_else(|| invalid(format!("'{}' expects an array of filters", key)))?
+            .iter()
+            .map(|item| Self::parse_at(item, depth + 1))
+            .collect()
+    }
+
+    fn parse_field(path: &str, operand: &JsonValue) -> CoreResult<Self> {
+        // Plain values (and objects without operators) are equality checks
+        let operators = match operand.as_object() {
+            Some(map) if map.keys().any(|k| k.starts_with('$')) => map,
+            _ => {
+                return Ok(Self::Compare {
+                    path: path.to_string(),
+                    op: FilterOp::Eq,
+                    value: operand.clone(),
+                })
+            }
+        };
+
+        let mut clauses = Vec::with_capacity(operators.len());
+        for (op, value) in operators {
+            let compare = |op| Self::Compare {
+                path: path.to_string(),
+                op,
+                value: value.clone(),
+            };
+            let clause = match op.as_str() {
+                "$eq" => compare(FilterOp::Eq),
+                "$ne" => compare(FilterOp::Ne),
+                "$gt" => compare(FilterOp::Gt),
+                "$gte" => compare(FilterOp::Gte),
+                "$lt" => compare(FilterOp::Lt),
+                "$lte" => compare(FilterOp::Lte),
+                "$in" | "$nin" => {
+                    let values = value
+                        .as_array()
+                        .ok_or_else(|| invalid(format!("'{}' expects an array", op)))?
+                        .clone();
+                    let clause = Self::In {
+                        path: path.to_string(),
+                        values,
+                    };
+                    if op == "$in" {
+                        clause
+                    } else {
+                        Self::Not(Box::new(clause))
+                    }
+                }
+                "$exists" => Self::Exists {
+                    path: path.to_string(),
+                    exists: value
+                        .as_bool()
+                        .ok_or_else(|| invalid("'$exists' expects a boolean"))?,
+                },
+                other => {
+                    return Err(invalid(format!(
+                        "unknown operator '{}' on field '{}'",
+                        other, path
+                    )))
+                }
+            };
+            clauses.push(clause);
+        }
+
+        Ok(if clauses.len() == 1 {
+            clauses.remove(0)
+        } else {
+            Self::And(clauses)
+        })
+    }
+
+    /// Evaluates the filter against a document's metadata.
+    ///
+    /// Documents without metadata only match filters that do not require a
+    /// field to be present (e.g. `$ne`, `$exists: false`).
+    pub fn matches(&self, metadata: Option<&JsonValue>) -> bool {
+        match self {
+            Self::And(children) => children.iter().all(|c| c.matches(metadata)),
+            Self::Or(children) => children.iter().any(|c| c.matches(metadata)),
+            Self::Not(child) => !child.matches(metadata),
+            Self::Compare { path, op, value } => {
+                let field = metadata.and_then(|m| lookup(m, path));
+                match op {
+                    FilterOp::Eq => field == Some(value),
+                    FilterOp::Ne => field != Some(value),
+                    FilterOp::Gt => compare(field, value) == Some(Ordering::Greater),
+                    FilterOp::Gte => matches!(
+                        compare(field, value),
+                        Some(Ordering::Greater | Ordering::Equal)
+                    ),
+                    FilterOp::Lt => compare(field, value) == Some(Ordering::Less),
+                    FilterOp::Lte => matches!(
+                        compare(field, value),
+                        Some(Ordering::Less | Ordering::Equal)
+                    ),
+                }
+            }
+            Self::In { path, values } => metadata
+                .and_then(|m| lookup(m, path))
+                .is_some_and(|field| values.contains(field)),
+            Self::Exists { path, exists } => {
+                metadata.and_then(|m| lookup(m, path)).is_some() == *exists
+            }
+        }
+    }
+}
+
+/// Resolves a dotted path (`a.b.c`) inside a JSON object.
+fn lookup<'a>(value: &'a JsonValue, path: &str) -> Option<&'a JsonValue> {
+    path.split('.')
+        .try_fold(value, |current, segment| current.get(segment))
+}
+
+/// Orders a field against a filter operand when both are numbers or strings.
+fn compare(field: Option<&JsonValue>, value: &JsonValue) -> Option<Ordering> {
+    match (field?, value) {
+        (JsonValue::Number(a), JsonValue::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (JsonValue::String(a), JsonValue::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+fn invalid(message: impl Into<String>) -> CoreError {
+    CoreError::ValidationError(format!("Invalid filter: {}", message.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn matches(filter: JsonValue, metadata: JsonValue) -> bool {
+        FilterTree::parse(&filter).unwrap().matches(Some(&metadata))
+    }
+
+    #[test]
+    fn test_equality_and_nested_paths() {
+        let doc = json!({"category": "news", "author": {"name": "ada"}, "year": 2021});
+
+        assert!(matches(json!({"category": "news"}), doc.clone()));
+        assert!(!matches(json!({"category": "blog"}), doc.clone()));
+        assert!(matches(json!({"author.name": "ada"}), doc.clone()));
+        assert!(matches(
+            json!({"category": "news", "year": {"$gte": 2020, "$lt": 2022}}),
+            doc.clone()
+        ));
+        assert!(!matches(json!({"year": {"$gt": 2021}}), doc));
+    }
+
+    #[test]
+    fn test_logical_and_set_operators() {
+        let doc = json!({"lang": "de", "tags": "rust"});
+
+        assert!(matches(
+            json!({"$or": [{"lang": "en"}, {"lang": "de"}]}),
+            doc.clone()
+        ));
+        assert!(matches(json!({"lang": {"$in": ["de", "fr"]}}), doc.clone()));
+        assert!(!matches(json!({"lang": {"$nin": ["de"]}}), doc.clone()));
+        assert!(matches(json!({"$not": {"lang": "en"}}), doc.clone()));
+        assert!(matches(json!({"missing": {"$exists": false}}), doc.clone()));
+        assert!(!matches(json!({"lang": {"$gt": 1}}), doc));
+    }
+
+    #[test]
+    fn test_missing_metadata() {
+        let filter = FilterTree::parse(&json!({"lang": {"$ne": "en"}})).unwrap();
+        assert!(filter.matches(None));
+
+        let filter = FilterTree::parse(&json!({"lang": "en"})).unwrap();
+        assert!(!filter.matches(None));
+    }
+
+    #[test]
+    fn test_invalid_filters() {
+        for filter in [
+            json!("news"),
+            json!({"$xor": []}),
+            json!({"year": {"$between": [1, 2]}}),
+            json!({"lang": {"$in": "en"}}),
+            json!({"$and": {"lang": "en"}}),
+        ] {
+            let err = FilterTree::parse(&filter).unwrap_err();
+            assert!(matches!(err, CoreError::ValidationError(_)), "{:?}", err);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Active dependency probes for the detailed health check.
+//!
+//! Each probe exercises its dependency for real (a SQLite round trip, an S3
+//! head-bucket, a write to the WAL directory, a model inference) and reports
+//! how long that took. Probes give up after [`PROBE_TIMEOUT`], so a hung
+//! dependency reports unhealthy instead of hanging the health check.
+//!
+//! The storage probes are [`CollectionService::probe_database`],
+//! [`CollectionService::probe_object_store`] and
+//! [`CollectionService::probe_wal_dir`].
+//!
+//! [`CollectionService::probe_database`]: crate::CollectionService::probe_database
+//! [`CollectionService::probe_object_store`]: crate::CollectionService::probe_object_store
+//! [`CollectionService::probe_wal_dir`]: crate::CollectionService::probe_wal_dir
+
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::EmbeddingManager;
+
+/// Upper bound on a single probe.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Text embedded by [`probe_embedding`].
+const PROBE_TEXT: &str = "akidb health check";
+
+/// Outcome of one dependency probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeStatus {
+    Healthy,
+    Unhealthy,
+    /// The dependency is not configured in this deployment
+    Skipped,
+}
+
+/// Result of probing one dependency.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeResult {
+    pub status: ProbeStatus,
+    /// Time the probe took; absent when skipped
+    pub latency_ms: Option<f64>,
+    pub message: Option<String>,
+}
+
+impl ProbeResult {
+    /// A probe that did not run because the dependency is not configured.
+    pub fn skipped(reason: impl Into<String>) -> Self {
+        Self {
+            status: ProbeStatus::Skipped,
+            latency_ms: None,
+            message: Some(reason.into()),
+        }
+    }
+
+    /// Runs `probe`, timing it and bounding it by [`PROBE_TIMEOUT`].
+    pub async fn run<F, E>(probe: F) -> Self
+    where
+        F: Future<Output = Result<(), E>>,
+        E: fmt::Display,
+    {
+        let start = Instant::now();
+        let outcome = tokio::time::timeout(PROBE_TIMEOUT, probe).await;
+        let latency_ms = Some(start.elapsed().as_secs_f64() * 1000.0);
+
+        let (status, message) = match outcome {
+            Ok(Ok(())) => (ProbeStatus::Healthy, None),
+            Ok(Err(e)) => (ProbeStatus::Unhealthy, Some(e.to_string())),
+            Err(_) => (
+                ProbeStatus::Unhealthy,
+                Some(format!("timed out after {:?}", PROBE_TIMEOUT)),
+            ),
+        };
+        Self {
+            status,
+            latency_ms,
+            message,
+        }
+    }
+
+    pub fn is_unhealthy(&self) -> bool {
+        self.status == ProbeStatus::Unhealthy
+    }
+}
+
+/// Embeds a short text with the default model, bypassing the embedding cache.
+pub async fn probe_embedding(manager: &EmbeddingManager) -> ProbeResult {
+    ProbeResult::run(async {
+        let vectors = manager.embed_uncached(vec![PROBE_TEXT.to_string()]).await?;
+        match vectors.first() {
+            Some(vector) if vector.len() == manager.dimension() as usize => Ok(()),
+            Some(vector) => Err(format!(
+                "model returned {} dimensions, expected {}",
+                vector.len(),
+                manager.dimension()
+            )),
+            None => Err("model returned no embedding".to_string()),
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_probe_result_records_outcome() {
+        let healthy = ProbeResult::run(async { Ok::<(), String>(()) }).await;
+        assert_eq!(healthy.status, ProbeStatus::Healthy);
+        assert!(healthy.latency_ms.is_some());
+        assert!(healthy.message.is_none());
+
+        let unhealthy = ProbeResult::run(async { Err("connection refused") }).await;
+        assert!(unhealthy.is_unhealthy());
+        assert_eq!(unhealthy.message.as_deref(), Some("connection refused"));
+
+        let skipped = ProbeResult::skipped("not configured");
+        assert_eq!(skipped.status, ProbeStatus::Skipped);
+        assert!(skipped.latency_ms.is_none());
+    }
+}
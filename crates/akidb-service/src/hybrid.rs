@@ -0,0 +1,166 @@
+//! Hybrid search: fusing vector similarity with BM25 keyword relevance.
+//!
+//! Every loaded collection keeps a BM25 index over its payload text (see
+//! `akidb_index::HybridIndex`). A hybrid search takes the best vector
+//! candidates and the best keyword matches and merges the two rankings into
+//! one list ordered by a fused score, where higher is always better.
+
+use std::collections::HashMap;
+
+use akidb_core::{DistanceMetric, DocumentId, SearchResult};
+
+/// Rank constant of reciprocal rank fusion (the value from the original
+/// RRF paper, which damps the weight of the very top ranks).
+pub const RRF_K: f32 = 60.0;
+
+/// How the vector and keyword rankings of a hybrid search are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Fusion {
+    /// Reciprocal rank fusion: the sum of `1 / (RRF_K + rank)` over the
+    /// rankings a document appears in
+    #[default]
+    Rrf,
+    /// `alpha * vector + (1 - alpha) * keyword`, with both scores scaled to
+    /// [0, 1] over the candidates
+    Weighted { alpha: f32 },
+}
+
+/// Keyword stage of a hybrid [`crate::CollectionService::search`].
+#[derive(Debug, Clone)]
+pub struct HybridOptions {
+    /// Keyword query scored with BM25 against payload text
+    pub query: String,
+    /// Payload keys searched (every string key when `None`)
+    pub fields: Option<Vec<String>>,
+    /// How the two rankings are combined
+    pub fusion: Fusion,
+    /// Candidates taken from each ranking before fusion (at least `top_k`)
+    pub candidates: usize,
+}
+
+/// Merges vector candidates (best first) with keyword matches (best first)
+/// and returns them ordered by fused score, which replaces `score`.
+///
+/// `keyword_only` holds the keyword matches missing from `vector_hits`,
+/// scored against the query vector; they take no vector rank under RRF.
+pub(crate) fn fuse(
+    fusion: Fusion,
+    metric: DistanceMetric,
+    vector_hits: Vec<SearchResult>,
+    keyword_only: Vec<SearchResult>,
+    keyword_hits: &[(DocumentId, f32)],
+) -> Vec<SearchResult> {
+    let keyword: HashMap<DocumentId, (usize, f32)> = keyword_hits
+        .iter()
+        .enumerate()
+        .map(|(rank, &(doc_id, score))| (doc_id, (rank, score)))
+        .collect();
+    let vector_ranks = vector_hits.len();
+    let mut results: Vec<SearchResult> = vector_hits.into_iter().chain(keyword_only).collect();
+
+    let fused: Vec<f32> = match fusion {
+        Fusion::Rrf => results
+            .iter()
+            .enumerate()
+            .map(|(position, result)| {
+                let vector = if position < vector_ranks {
+                    1.0 / (RRF_K + position as f32 + 1.0)
+                } else {
+                    0.0
+                };
+                let keyword = keyword
+                    .get(&result.doc_id)
+                    .map_or(0.0, |&(rank, _)| 1.0 / (RRF_K + rank as f32 + 1.0));
+                vector + keyword
+            })
+            .collect(),
+        Fusion::Weighted { alpha } => {
+            let (min, max) = results
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |acc, r| {
+                    (acc.0.min(r.score), acc.1.max(r.score))
+                });
+            let max_keyword = keyword_hits
+                .iter()
+                .fold(0.0f32, |max, &(_, score)| max.max(score));
+            results
+                .iter()
+                .map(|result| {
+                    let vector = if max > min {
+                        match metric {
+                            // Distances: the closest candidate scores 1
+                            DistanceMetric::L2 => (max - result.score) / (max - min),
+                            DistanceMetric::Cosine | DistanceMetric::Dot => {
+                                (result.score - min) / (max - min)
+                            }
+                        }
+                    } else {
+                        1.0
+                    };
+                    let keyword = match keyword.get(&result.doc_id) {
+                        Some(&(_, score)) if max_keyword > 0.0 => score / max_keyword,
+                        _ => 0.0,
+                    };
+                    alpha * vector + (1.0 - alpha) * keyword
+                })
+                .collect()
+        }
+    };
+
+    for (result, score) in results.iter_mut().zip(fused) {
+        result.score = score;
+    }
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(doc_id: DocumentId, score: f32) -> SearchResult {
+        SearchResult::new(doc_id, score)
+    }
+
+    #[test]
+    fn test_rrf_rewards_agreement() {
+        let (a, b, c) = (DocumentId::new(), DocumentId::new(), DocumentId::new());
+        // a is the best vector match, b is second in both rankings, c is
+        // the best keyword match
+        let fused = fuse(
+            Fusion::Rrf,
+            DistanceMetric::Cosine,
+            vec![hit(a, 0.9), hit(b, 0.8)],
+            vec![hit(c, 0.1)],
+            &[(c, 5.0), (b, 3.0)],
+        );
+        let order: Vec<DocumentId> = fused.iter().map(|r| r.doc_id).collect();
+        assert_eq!(order, vec![b, a, c]);
+        assert!((fused[0].score - 2.0 / (RRF_K + 2.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_weighted_fusion_inverts_distances() {
+        let (a, b) = (DocumentId::new(), DocumentId::new());
+        // L2: a is closer; b is the only keyword match
+        let vector_only = fuse(
+            Fusion::Weighted { alpha: 1.0 },
+            DistanceMetric::L2,
+            vec![hit(a, 0.1), hit(b, 0.5)],
+            Vec::new(),
+            &[(b, 2.0)],
+        );
+        assert_eq!(vector_only[0].doc_id, a);
+        assert_eq!(vector_only[0].score, 1.0);
+
+        let keyword_only = fuse(
+            Fusion::Weighted { alpha: 0.0 },
+            DistanceMetric::L2,
+            vec![hit(a, 0.1), hit(b, 0.5)],
+            Vec::new(),
+            &[(b, 2.0)],
+        );
+        assert_eq!(keyword_only[0].doc_id, b);
+        assert_eq!(keyword_only[1].score, 0.0);
+    }
+}
@@ -0,0 +1,207 @@
+//! Idempotency store for retried write requests.
+//!
+//! API layers record the response to a request carrying an idempotency key
+//! and replay it when the same key is presented again within the retention
+//! window, so a client retrying after a timeout does not insert twice. Keys
+//! are scoped by caller and route: the same key on a different endpoint or
+//! from a different API key (or, for requests without one, a different
+//! tenant) is unrelated.
+//!
+//! Entries live in memory; they are lost on restart and not shared between
+//! replicas.
+
+use crate::config::IdempotencyConfig;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Identity of an idempotent request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey {
+    /// API key (or, without one, tenant) the key belongs to
+    pub scope: String,
+    /// Method and path, e.g. `POST /api/v2/collections/…/documents`
+    pub route: String,
+    /// Client-supplied `Idempotency-Key`
+    pub key: String,
+}
+
+/// Response recorded for replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// What the API layer should do with an incoming keyed request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyOutcome {
+    /// First time this key is seen: run the request, then `complete` or `abandon`
+    Proceed,
+    /// A request with this key is still running
+    InProgress,
+    /// The request already ran; return the stored response
+    Replay(Arc<StoredResponse>),
+}
+
+#[derive(Debug)]
+enum Entry {
+    InFlight,
+    Done(Arc<StoredResponse>),
+}
+
+/// In-memory idempotency store with a fixed retention window.
+pub struct IdempotencyStore {
+    entries: Mutex<HashMap<IdempotencyKey, (Instant, Entry)>>,
+    window: Duration,
+    max_entries: usize,
+}
+
+impl IdempotencyStore {
+    /// Creates a store keeping responses for `window`, tracking at most `max_entries` keys.
+    pub fn new(window: Duration, max_entries: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            window,
+            max_entries,
+        }
+    }
+
+    /// Creates a store from configuration.
+    pub fn from_config(config: &IdempotencyConfig) -> Self {
+        Self::new(
+            Duration::from_secs(config.window_seconds),
+            config.max_entries,
+        )
+    }
+
+    /// Claims `key` for a new request, or reports that it is running or done.
+    pub fn begin(&self, key: &IdempotencyKey) -> IdempotencyOutcome {
+        self.begin_at(key, Instant::now())
+    }
+
+    fn begin_at(&self, key: &IdempotencyKey, now: Instant) -> IdempotencyOutcome {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some((at, entry)) = entries.get(key) {
+            if now.saturating_duration_since(*at) < self.window {
+                return match entry {
+                    Entry::InFlight => IdempotencyOutcome::InProgress,
+                    Entry::Done(response) => IdempotencyOutcome::Replay(Arc::clone(response)),
+                };
+            }
+        }
+
+        if entries.len() >= self.max_entries {
+            let window = self.window;
+            entries.retain(|_, (at, _)| now.saturating_duration_since(*at) < window);
+        }
+        if entries.len() >= self.max_entries {
+            // Still full of live keys: forget the oldest one
+            if let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, (at, _))| *at)
+                .map(|(k, _)| k.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(key.clone(), (now, Entry::InFlight));
+        IdempotencyOutcome::Proceed
+    }
+
+    /// Records the response for a key claimed with [`begin`](Self::begin).
+    pub fn complete(&self, key: &IdempotencyKey, response: StoredResponse) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(
+            key.clone(),
+            (Instant::now(), Entry::Done(Arc::new(response))),
+        );
+    }
+
+    /// Releases a claimed key without recording a response, so a retry runs again.
+    pub fn abandon(&self, key: &IdempotencyKey) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if matches!(entries.get(key), Some((_, Entry::InFlight))) {
+            entries.remove(key);
+        }
+    }
+}
+
+impl Default for IdempotencyStore {
+    fn default() -> Self {
+        Self::from_config(&IdempotencyConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: &str) -> IdempotencyKey {
+        IdempotencyKey {
+            scope: "tenant-a".to_string(),
+            route: "POST /api/v2/collections/c/documents".to_string(),
+            key: key.to_string(),
+        }
+    }
+
+    fn response() -> StoredResponse {
+        StoredResponse {
+            status: 201,
+            content_type: Some("application/json".to_string()),
+            body: br#"{"id":"1"}"#.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_replay_after_completion() {
+        let store = IdempotencyStore::new(Duration::from_secs(60), 100);
+
+        assert_eq!(store.begin(&key("k1")), IdempotencyOutcome::Proceed);
+        assert_eq!(store.begin(&key("k1")), IdempotencyOutcome::InProgress);
+
+        store.complete(&key("k1"), response());
+        assert_eq!(
+            store.begin(&key("k1")),
+            IdempotencyOutcome::Replay(Arc::new(response()))
+        );
+
+        // Same key from another tenant is independent
+        let mut other = key("k1");
+        other.scope = "tenant-b".to_string();
+        assert_eq!(store.begin(&other), IdempotencyOutcome::Proceed);
+    }
+
+    #[test]
+    fn test_abandon_and_expiry() {
+        let store = IdempotencyStore::new(Duration::from_secs(60), 100);
+
+        assert_eq!(store.begin(&key("k1")), IdempotencyOutcome::Proceed);
+        store.abandon(&key("k1"));
+        assert_eq!(store.begin(&key("k1")), IdempotencyOutcome::Proceed);
+
+        store.complete(&key("k1"), response());
+        let later = Instant::now() + Duration::from_secs(61);
+        assert_eq!(
+            store.begin_at(&key("k1"), later),
+            IdempotencyOutcome::Proceed
+        );
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let store = IdempotencyStore::new(Duration::from_secs(60), 2);
+
+        let now = Instant::now();
+        store.begin_at(&key("k1"), now);
+        store.begin_at(&key("k2"), now + Duration::from_secs(1));
+        store.begin_at(&key("k3"), now + Duration::from_secs(2));
+
+        let entries = store.entries.lock().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(!entries.contains_key(&key("k1")));
+    }
+}
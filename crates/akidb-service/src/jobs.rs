@@ -0,0 +1,461 @@
+//! Background jobs for long-running operations.
+//!
+//! API handlers hand work that would outlive a request (bulk delete, reindex,
+//! export, restore) to [`JobManager::spawn`] and answer `202 Accepted` with
+//! the job ID. Clients then poll the job for status and progress.
+//!
+//! Jobs run as tokio tasks in this process. State transitions (pending →
+//! running → succeeded/failed/cancelled) are persisted through the optional
+//! [`JobRepository`]; progress is tracked in memory while the job runs and
+//! persisted when it finishes.
+//!
+//! Cancellation is cooperative: [`JobManager::cancel`] flags the job, and the
+//! task stops at its next [`JobHandle::check_cancelled`], leaving its data in
+//! a consistent state.
+
+use akidb_core::{
+    CollectionId, CoreError, CoreResult, JobDescriptor, JobId, JobRepository, JobStatus,
+};
+use chrono::Utc;
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Finished jobs kept in memory when there is no repository to serve them from.
+const MAX_FINISHED_JOBS_IN_MEMORY: usize = 1000;
+
+/// Tracks and runs background jobs.
+pub struct JobManager {
+    jobs: RwLock<HashMap<JobId, JobDescriptor>>,
+    repository: Option<Arc<dyn JobRepository>>,
+    /// Running jobs asked to stop
+    cancelled: RwLock<HashSet<JobId>>,
+}
+
+impl JobManager {
+    /// Creates an in-memory job manager (job history is lost on restart).
+    pub fn new() -> Self {
+        Self {
+            jobs: RwLock::new(HashMap::new()),
+            repository: None,
+            cancelled: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Creates a job manager that persists job state to `repository`.
+    pub fn with_repository(repository: Arc<dyn JobRepository>) -> Self {
+        Self {
+            jobs: RwLock::new(HashMap::new()),
+            repository: Some(repository),
+            cancelled: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Marks jobs left unfinished by a previous process as failed.
+    ///
+    /// Call once on startup, before accepting requests.
+    pub async fn recover(&self) -> CoreResult<u64> {
+        match &self.repository {
+            Some(repository) => {
+                repository
+                    .fail_unfinished("interrupted by server restart")
+                    .await
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Registers `job` and runs `task` in the background.
+    ///
+    /// The task receives a [`JobHandle`] for progress reporting; its `Ok`
+    /// value becomes the job result and its `Err` the job error. A task that
+    /// fails after being cancelled ends `Cancelled` instead of `Failed`.
+    pub async fn spawn<F, Fut>(self: &Arc<Self>, job: JobDescriptor, task: F) -> CoreResult<JobId>
+    where
+        F: FnOnce(JobHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = CoreResult<JsonValue>> + Send + 'static,
+    {
+        let job_id = job.job_id;
+        if let Some(repository) = &self.repository {
+            repository.create(&job).await?;
+        }
+        self.jobs.write().await.insert(job_id, job);
+
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            manager
+                .transition(job_id, |job| job.status = JobStatus::Running)
+                .await;
+
+            let handle = JobHandle {
+                manager: Arc::clone(&manager),
+                job_id,
+            };
+            let outcome = task(handle).await;
+
+            let cancelled = manager.cancelled.read().await.contains(&job_id);
+            manager
+                .transition(job_id, |job| match outcome {
+                    Ok(result) => {
+                        job.status = JobStatus::Succeeded;
+                        job.result = Some(result);
+                    }
+                    Err(_) if cancelled => {
+                        job.status = JobStatus::Cancelled;
+                        job.error = Some("cancelled by request".to_string());
+                    }
+                    Err(e) => {
+                        job.status = JobStatus::Failed;
+                        job.error = Some(e.to_string());
+                    }
+                })
+                .await;
+            // The job is terminal now, so it cannot be cancelled again
+            manager.cancelled.write().await.remove(&job_id);
+        });
+
+        Ok(job_id)
+    }
+
+    /// Fetches a job, from memory if it is active, otherwise from the repository.
+    pub async fn get(&self, job_id: JobId) -> CoreResult<Option<JobDescriptor>> {
+        if let Some(job) = self.jobs.read().await.get(&job_id) {
+            return Ok(Some(job.clone()));
+        }
+
+        match &self.repository {
+            Some(repository) => repository.get(job_id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Lists jobs, newest first, optionally only those with `status` or on
+    /// `collection_id`.
+    ///
+    /// Running jobs show their current progress, which is only persisted
+    /// when they finish.
+    pub async fn list(
+        &self,
+        status: Option<JobStatus>,
+        collection_id: Option<CollectionId>,
+        limit: usize,
+        offset: usize,
+    ) -> CoreResult<Vec<JobDescriptor>> {
+        if let Some(repository) = &self.repository {
+            let mut listed = repository
+                .list(status, collection_id, limit, offset)
+                .await?;
+            let jobs = self.jobs.read().await;
+            for job in &mut listed {
+                if let Some(active) = jobs.get(&job.job_id) {
+                    *job = active.clone();
+                }
+            }
+            return Ok(listed);
+        }
+
+        let mut listed: Vec<JobDescriptor> = self
+            .jobs
+            .read()
+            .await
+            .values()
+            .filter(|job| status.map_or(true, |status| job.status == status))
+            .filter(|job| collection_id.map_or(true, |id| job.collection_id == Some(id)))
+            .cloned()
+            .collect();
+        listed.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.job_id.to_bytes().cmp(&b.job_id.to_bytes()))
+        });
+        Ok(listed.into_iter().skip(offset).take(limit).collect())
+    }
+
+    /// Asks a running job to stop; it ends `Cancelled` once its task
+    /// notices.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` for an unknown job, and `InvalidState` if the job
+    /// has finished or runs in another server process.
+    pub async fn cancel(&self, job_id: JobId) -> CoreResult<JobDescriptor> {
+        // Checked and flagged under the lock, so the job cannot finish in
+        // between and leave the flag behind
+        let jobs = self.jobs.read().await;
+        let job = match jobs.get(&job_id) {
+            Some(job) => job.clone(),
+            None => {
+                drop(jobs);
+                let job = self
+                    .get(job_id)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found("Job", job_id.to_string()))?;
+                if job.status.is_terminal() {
+                    return Err(already_finished(&job));
+                }
+                return Err(CoreError::invalid_state(format!(
+                    "job {} is not running on this server",
+                    job_id
+                )));
+            }
+        };
+        if job.status.is_terminal() {
+            return Err(already_finished(&job));
+        }
+
+        self.cancelled.write().await.insert(job_id);
+        Ok(job)
+    }
+
+    /// Applies a status change and persists it.
+    async fn transition(&self, job_id: JobId, apply: impl FnOnce(&mut JobDescriptor)) {
+        let snapshot = {
+            let mut jobs = self.jobs.write().await;
+            let Some(job) = jobs.get_mut(&job_id) else {
+                return;
+            };
+            apply(job);
+            job.updated_at = Utc::now();
+            job.clone()
+        };
+
+        if let Some(repository) = &self.repository {
+            if let Err(e) = repository.update(&snapshot).await {
+                tracing::warn!("Failed to persist job {} state: {}", job_id, e);
+                return;
+            }
+        }
+
+        if snapshot.status.is_terminal() {
+            self.evict_finished(job_id).await;
+        }
+    }
+
+    /// Drops finished jobs from memory once they can be served from elsewhere.
+    async fn evict_finished(&self, job_id: JobId) {
+        let mut jobs = self.jobs.write().await;
+
+        if self.repository.is_some() {
+            jobs.remove(&job_id);
+            return;
+        }
+
+        let mut finished: Vec<_> = jobs
+            .values()
+            .filter(|job| job.status.is_terminal())
+            .map(|job| (job.updated_at, job.job_id))
+            .collect();
+        if finished.len() > MAX_FINISHED_JOBS_IN_MEMORY {
+            finished.sort_unstable_by_key(|(updated_at, _)| *updated_at);
+            let excess = finished.len() - MAX_FINISHED_JOBS_IN_MEMORY;
+            for (_, id) in finished.into_iter().take(excess) {
+                jobs.remove(&id);
+            }
+        }
+    }
+}
+
+/// Error for cancelling a job that has finished.
+fn already_finished(job: &JobDescriptor) -> CoreError {
+    CoreError::invalid_state(format!(
+        "job {} already {}",
+        job.job_id,
+        job.status.as_str()
+    ))
+}
+
+impl Default for JobManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Progress reporting handle passed to a running job.
+pub struct JobHandle {
+    manager: Arc<JobManager>,
+    job_id: JobId,
+}
+
+impl JobHandle {
+    /// ID of the running job.
+    pub fn job_id(&self) -> JobId {
+        self.job_id
+    }
+
+    /// Sets the total amount of work, if it was not known at submission.
+    pub async fn set_total(&self, total: u64) {
+        if let Some(job) = self.manager.jobs.write().await.get_mut(&self.job_id) {
+            job.total = Some(total);
+            job.updated_at = Utc::now();
+        }
+    }
+
+    /// Whether the job has been asked to stop.
+    pub async fn is_cancelled(&self) -> bool {
+        self.manager.cancelled.read().await.contains(&self.job_id)
+    }
+
+    /// Returns an error if the job has been asked to stop.
+    ///
+    /// Call between units of work and propagate the error with `?`.
+    pub async fn check_cancelled(&self) -> CoreResult<()> {
+        if self.is_cancelled().await {
+            return Err(CoreError::invalid_state(format!(
+                "job {} was cancelled",
+                self.job_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Records `done` additional units of completed work.
+    pub async fn advance(&self, done: u64) {
+        if let Some(job) = self.manager.jobs.write().await.get_mut(&self.job_id) {
+            job.progress += done;
+            job.updated_at = Utc::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::CoreError;
+    use std::time::Duration;
+
+    async fn wait_for_terminal(manager: &JobManager, job_id: JobId) -> JobDescriptor {
+        for _ in 0..100 {
+            let job = manager.get(job_id).await.unwrap().unwrap();
+            if job.status.is_terminal() {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("job {} did not finish", job_id);
+    }
+
+    #[tokio::test]
+    async fn test_job_succeeds_with_progress() {
+        let manager = Arc::new(JobManager::new());
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let job = JobDescriptor::new("bulk_delete", None).with_total(2);
+        let job_id = manager
+            .spawn(job, |handle| async move {
+                handle.advance(1).await;
+                release_rx.await.ok();
+                handle.advance(1).await;
+                Ok(serde_json::json!({"deleted": 2}))
+            })
+            .await
+            .unwrap();
+
+        // Wait until the first unit of work is reported
+        for _ in 0..100 {
+            if manager.get(job_id).await.unwrap().unwrap().progress == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let running = manager.get(job_id).await.unwrap().unwrap();
+        assert_eq!(running.status, JobStatus::Running);
+        assert_eq!(running.percent_complete(), Some(50.0));
+
+        release_tx.send(()).unwrap();
+        let done = wait_for_terminal(&manager, job_id).await;
+        assert_eq!(done.status, JobStatus::Succeeded);
+        assert_eq!(done.progress, 2);
+        assert_eq!(done.result, Some(serde_json::json!({"deleted": 2})));
+    }
+
+    #[tokio::test]
+    async fn test_job_failure_is_recorded() {
+        let manager = Arc::new(JobManager::new());
+
+        let job_id = manager
+            .spawn(JobDescriptor::new("export", None), |_| async {
+                Err(CoreError::internal("disk full"))
+            })
+            .await
+            .unwrap();
+
+        let done = wait_for_terminal(&manager, job_id).await;
+        assert_eq!(done.status, JobStatus::Failed);
+        assert!(done.error.unwrap().contains("disk full"));
+
+        assert!(manager.get(JobId::new()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_running_job() {
+        let manager = Arc::new(JobManager::new());
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let job_id = manager
+            .spawn(JobDescriptor::new("reindex", None), |handle| async move {
+                started_tx.send(()).ok();
+                loop {
+                    handle.check_cancelled().await?;
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            })
+            .await
+            .unwrap();
+        started_rx.await.unwrap();
+
+        let running = manager.cancel(job_id).await.unwrap();
+        assert_eq!(running.status, JobStatus::Running);
+        let done = wait_for_terminal(&manager, job_id).await;
+        assert_eq!(done.status, JobStatus::Cancelled);
+        assert!(done.error.is_some());
+
+        // Finished and unknown jobs cannot be cancelled
+        assert!(matches!(
+            manager.cancel(job_id).await,
+            Err(CoreError::InvalidState { .. })
+        ));
+        assert!(matches!(
+            manager.cancel(JobId::new()).await,
+            Err(CoreError::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_newest_first() {
+        let manager = Arc::new(JobManager::new());
+        let collection_id = CollectionId::new();
+
+        let mut job_ids = Vec::new();
+        for collection in [Some(collection_id), None, Some(collection_id)] {
+            let job = JobDescriptor::new("reindex", collection);
+            job_ids.push(
+                manager
+                    .spawn(job, |_| async { Ok(JsonValue::Null) })
+                    .await
+                    .unwrap(),
+            );
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        for job_id in &job_ids {
+            wait_for_terminal(&manager, *job_id).await;
+        }
+
+        let listed = manager.list(None, None, 10, 0).await.unwrap();
+        let ids: Vec<JobId> = listed.iter().map(|job| job.job_id).collect();
+        assert_eq!(ids, vec![job_ids[2], job_ids[1], job_ids[0]]);
+
+        let listed = manager
+            .list(Some(JobStatus::Succeeded), Some(collection_id), 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].job_id, job_ids[0]);
+        assert!(manager
+            .list(Some(JobStatus::Failed), None, 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
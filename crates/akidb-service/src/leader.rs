@@ -0,0 +1,192 @@
+//! Leader election over leases in the metadata database.
+//!
+//! Several processes may share one metadata database: the REST and gRPC
+//! servers, or replicas of either. Maintenance that must run exactly once
+//! (garbage collection, tiering sweeps, backups) is started with
+//! [`LeaderElection::spawn_singleton`]: every process campaigns for the same
+//! lease and only the current holder runs the work.
+//!
+//! Leadership is judged against the local clock: a leader counts its lease
+//! from before the renewal request was sent and steps down when it cannot
+//! renew in time, so it never acts on a lease another process may already
+//! have taken over.
+
+use crate::config::LeaderElectionConfig;
+use crate::metrics::LEADER;
+use akidb_core::{CoreResult, Lease, LeaseRepository};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Lease shared by all singleton maintenance work.
+pub const MAINTENANCE_LEASE: &str = "maintenance";
+
+/// Campaigns for a named lease and tracks whether this process holds it.
+pub struct LeaderElection {
+    repository: Arc<dyn LeaseRepository>,
+    name: String,
+    holder: String,
+    ttl: Duration,
+    /// Local deadline of the lease this process holds, if any
+    valid_until: Mutex<Option<Instant>>,
+    leader: watch::Sender<bool>,
+}
+
+impl LeaderElection {
+    /// Creates an election for lease `name`, campaigning as `holder`.
+    pub fn new(
+        repository: Arc<dyn LeaseRepository>,
+        name: impl Into<String>,
+        holder: impl Into<String>,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            repository,
+            name: name.into(),
+            holder: holder.into(),
+            ttl,
+            valid_until: Mutex::new(None),
+            leader: watch::channel(false).0,
+        }
+    }
+
+    /// Creates the election for [`MAINTENANCE_LEASE`] from `[leader_election]`.
+    pub fn from_config(
+        repository: Arc<dyn LeaseRepository>,
+        config: &LeaderElectionConfig,
+    ) -> Self {
+        Self::new(
+            repository,
+            MAINTENANCE_LEASE,
+            config.holder_id.clone().unwrap_or_else(default_holder_id),
+            Duration::from_secs(config.lease_ttl_seconds),
+        )
+    }
+
+    /// Name of the lease.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Identifier this process campaigns under.
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Whether this process holds the lease right now.
+    pub fn is_leader(&self) -> bool {
+        let valid_until = *self.valid_until.lock().unwrap();
+        valid_until.is_some_and(|deadline| Instant::now() < deadline)
+    }
+
+    /// Receives `true`/`false` as this process gains and loses the lease.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.leader.subscribe()
+    }
+
+    /// Current lease as stored, whoever holds it.
+    pub async fn current(&self) -> CoreResult<Option<Lease>> {
+        self.repository.get(&self.name).await
+    }
+
+    /// Tries once to acquire or renew the lease. Returns whether this
+    /// process is the leader afterwards.
+    pub async fn campaign(&self) -> CoreResult<bool> {
+        let sent_at = Instant::now();
+        match self
+            .repository
+            .acquire(&self.name, &self.holder, self.ttl)
+            .await
+        {
+            Ok(lease) => {
+                self.set_deadline(lease.map(|_| sent_at + self.ttl));
+                Ok(self.is_leader())
+            }
+            Err(e) => {
+                // Leadership lapses on its own once the lease would have expired
+                if !self.is_leader() {
+                    self.set_deadline(None);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Gives up the lease so another process can take over immediately.
+    pub async fn resign(&self) -> CoreResult<()> {
+        self.set_deadline(None);
+        self.repository.release(&self.name, &self.holder).await?;
+        Ok(())
+    }
+
+    /// Campaigns every third of the lease TTL until the task is aborted.
+    pub fn spawn(self: &Arc<Self>) -> JoinHandle<()> {
+        let election = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(election.ttl / 3);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = election.campaign().await {
+                    tracing::warn!("Failed to renew lease '{}': {}", election.name, e);
+                }
+            }
+        })
+    }
+
+    /// Runs `task` every `interval`, but only while this process is the
+    /// leader. Errors are logged and the next run proceeds as scheduled.
+    pub fn spawn_singleton<F, Fut>(
+        self: &Arc<Self>,
+        job: &'static str,
+        interval: Duration,
+        task: F,
+    ) -> JoinHandle<()>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = CoreResult<()>> + Send,
+    {
+        let election = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if !election.is_leader() {
+                    continue;
+                }
+                if let Err(e) = task().await {
+                    tracing::warn!("Singleton job '{}' failed: {}", job, e);
+                }
+            }
+        })
+    }
+
+    fn set_deadline(&self, deadline: Option<Instant>) {
+        *self.valid_until.lock().unwrap() = deadline;
+        let leader = self.is_leader();
+
+        let changed = self.leader.send_if_modified(|current| {
+            let changed = *current != leader;
+            *current = leader;
+            changed
+        });
+        if changed {
+            if leader {
+                tracing::info!("Acquired lease '{}' as {}", self.name, self.holder);
+            } else {
+                tracing::info!("Lost lease '{}' ({})", self.name, self.holder);
+            }
+        }
+        LEADER
+            .with_label_values(&[&self.name])
+            .set(if leader { 1.0 } else { 0.0 });
+    }
+}
+
+/// Host name, process ID and start time, unique among live processes.
+fn default_holder_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "akidb".to_string());
+    let started = chrono::Utc::now().timestamp_millis();
+    format!("{}-{}-{}", host, std::process::id(), started)
+}
@@ -1,18 +1,89 @@
 //! Service layer for AkiDB 2.0.
 //! Shared business logic for gRPC and REST APIs.
 
+pub mod aliases;
+pub mod audit;
+pub mod backpressure;
+pub mod cdc;
+pub mod collection_acl;
 mod collection_service;
 mod config;
+pub mod debug;
+pub mod drain;
+mod embedding_batcher;
+pub mod embedding_cache;
+pub mod embedding_limits;
 mod embedding_manager;
+pub mod events;
+pub mod feature_flags;
+pub mod filter;
+pub mod health;
+pub mod hybrid;
+pub mod idempotency;
+pub mod jobs;
+pub mod leader;
+pub mod metering;
 pub mod metrics;
+pub mod otlp_metrics;
+pub mod rate_limit;
+pub mod readiness;
+pub mod reload;
+pub mod replication;
+pub mod reranker;
+pub mod snapshot_schedule;
+pub mod tenant_lookup;
+pub mod tls;
+pub mod trace_context;
+pub mod validation;
 
-pub use collection_service::{CollectionService, DLQRetryResult, ServiceMetrics};
+pub use aliases::CollectionAliases;
+pub use audit::AuditTrail;
+pub use backpressure::{Backpressure, Overload, QueueKind};
+pub use cdc::{CdcEvent, CdcOp, CdcPublisher, CdcSink};
+pub use collection_acl::{CollectionAccess, CollectionAcl, CollectionGrants, KeyIdentity};
+pub use collection_service::{
+    BatchDeleteStatus, CollectionOptions, CollectionService, CompactionStatus, DLQRetryResult,
+    ReindexOptions, RerankOptions, SearchOptions, ServiceMetrics,
+};
 pub use config::{
-    Config, ConfigError, DatabaseConfig, FeaturesConfig, HnswConfig, LoggingConfig, ServerConfig,
+    AuditConfig, BackpressureConfig, CdcConfig, CompressionConfig, Config, ConfigError, CorsConfig, DatabaseConfig, DebugConfig, DrainConfig,
+    EmbeddingConfig, EmbeddingModelConfig, FeaturesConfig, HnswConfig, IdempotencyConfig,
+    LeaderElectionConfig, LimitsConfig, LoggingConfig, MeteringConfig, ReloadConfig, ReplicationConfig, ReplicationRule,
+    RerankConfig, ServerConfig, SnapshotSchedule, SnapshotsConfig, TieringConfig, TlsConfig,
+};
+pub use drain::{DrainController, DrainStage, DrainStatus};
+pub use embedding_cache::{EmbeddingCache, EmbeddingCacheStats};
+pub use embedding_limits::{ConcurrencyLimit, TenantRateLimiter};
+pub use embedding_manager::{EmbeddingHealth, EmbeddingManager, WarmupStatus};
+pub use events::{ChangeEvent, ChangeKind, EventBus};
+pub use feature_flags::FeatureFlags;
+pub use filter::FilterTree;
+pub use hybrid::{Fusion, HybridOptions};
+pub use idempotency::{IdempotencyKey, IdempotencyOutcome, IdempotencyStore, StoredResponse};
+pub use jobs::{JobHandle, JobManager};
+pub use leader::{LeaderElection, MAINTENANCE_LEASE};
+pub use metering::UsageMeter;
+pub use otlp_metrics::{OtlpMetricsConfig, OtlpMetricsExporter};
+pub use replication::{
+    CollectionReplication, ReplicaApplier, ReplicaStatus, ReplicatedOp, ReplicationEvent,
+    ReplicationMonitor, ReplicationReport, ReplicationRules, WalShipper,
 };
-pub use embedding_manager::EmbeddingManager;
+pub use rate_limit::{RateLimitDecision, RateLimiter};
+pub use readiness::{BootstrapStage, BootstrapStatus, Readiness};
+pub use reload::{ConfigReloader, LogLevelHandle};
+pub use reranker::{RerankHit, Reranked, Reranker};
+pub use snapshot_schedule::SnapshotScheduler;
+
+// Re-export embedding types used by the API layers
+pub use akidb_embedding::{EmbeddingError, EmbeddingInput, ImageInput, MockRerankProvider, ModelInfo};
+
+// Re-export the tiering manager the servers attach with `[tiering] enabled = true`
+pub use akidb_storage::tiering_manager::TieringManager;
+
+// Re-export the point-in-time restore summary returned by `restore_collection`
+pub use akidb_storage::RestoreReport;
 
-// Re-export ModelInfo from akidb_embedding
-pub use akidb_embedding::ModelInfo;
+// Re-export the storage settings taken by `with_storage_config`
+pub use akidb_storage::StorageConfig;
 
 // TODO: Add TenantService, DatabaseService in rc2
@@ -0,0 +1,169 @@
+//! Per-tenant usage metering for billing.
+//!
+//! Searches, inserted vectors and embedded tokens are counted in memory per
+//! tenant and hour, and added to the hourly rollups in the metadata database
+//! on every flush (see [`spawn_flush`]). Stored bytes are sampled at each
+//! flush as the size of the tenant's vector payloads (dimension × 4 bytes per
+//! vector); the rollup keeps the peak for the hour.
+//!
+//! Several processes sharing the metadata database each add their own counts
+//! to the same rollups.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use akidb_core::{
+    CoreResult, DatabaseId, DatabaseRepository, TenantId, UsageRepository, UsageRollup,
+};
+use chrono::{DateTime, Utc};
+use tokio::task::JoinHandle;
+
+use crate::collection_service::CollectionService;
+use crate::tenant_lookup::TenantLookup;
+
+/// Accumulates usage and persists it as hourly rollups.
+pub struct UsageMeter {
+    repository: Arc<dyn UsageRepository>,
+    tenants: TenantLookup,
+    pending: Mutex<HashMap<(TenantId, DateTime<Utc>), UsageRollup>>,
+}
+
+impl UsageMeter {
+    /// Creates a meter persisting to `repository`, attributing databases to
+    /// tenants through `databases`.
+    pub fn new(
+        repository: Arc<dyn UsageRepository>,
+        databases: Arc<dyn DatabaseRepository>,
+    ) -> Self {
+        Self {
+            repository,
+            tenants: TenantLookup::new(databases),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts `count` queries against the tenant owning `database_id`.
+    pub async fn record_queries(&self, database_id: DatabaseId, count: u64) {
+        if let Some(tenant_id) = self.tenants.tenant_of(database_id).await {
+            self.add(tenant_id, |usage| usage.queries += count);
+        }
+    }
+
+    /// Counts `count` inserted vectors against the tenant owning `database_id`.
+    pub async fn record_inserts(&self, database_id: DatabaseId, count: u64) {
+        if let Some(tenant_id) = self.tenants.tenant_of(database_id).await {
+            self.add(tenant_id, |usage| usage.vectors_inserted += count);
+        }
+    }
+
+    /// Counts `tokens` embedded for `tenant_id`.
+    pub fn record_embedding_tokens(&self, tenant_id: TenantId, tokens: u64) {
+        self.add(tenant_id, |usage| usage.embedding_tokens += tokens);
+    }
+
+    /// Records a sample of the bytes stored per database; the hour keeps the
+    /// largest sample per tenant.
+    pub async fn record_stored_bytes(&self, bytes_by_database: HashMap<DatabaseId, u64>) {
+        let mut bytes_by_tenant: HashMap<TenantId, u64> = HashMap::new();
+        for (database_id, bytes) in bytes_by_database {
+            if let Some(tenant_id) = self.tenants.tenant_of(database_id).await {
+                *bytes_by_tenant.entry(tenant_id).or_default() += bytes;
+            }
+        }
+        for (tenant_id, bytes) in bytes_by_tenant {
+            self.add(tenant_id, |usage| {
+                usage.stored_bytes = usage.stored_bytes.max(bytes)
+            });
+        }
+    }
+
+    /// Tenant owning `database_id`, if known.
+    pub async fn tenant_of(&self, database_id: DatabaseId) -> Option<TenantId> {
+        self.tenants.tenant_of(database_id).await
+    }
+
+    /// Adds the usage counted since the last flush to the stored rollups.
+    ///
+    /// Rollups that cannot be written are kept and retried on the next flush.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first repository error.
+    pub async fn flush(&self) -> CoreResult<()> {
+        let pending: Vec<UsageRollup> = self
+            .pending
+            .lock()
+            .expect("usage meter lock poisoned")
+            .drain()
+            .map(|(_, usage)| usage)
+            .collect();
+
+        let mut result = Ok(());
+        for usage in pending {
+            if result.is_ok() {
+                if let Err(e) = self.repository.record(&usage).await {
+                    result = Err(e);
+                } else {
+                    continue;
+                }
+            }
+            // Put it back for the next flush
+            self.merge(&usage);
+        }
+        result
+    }
+
+    /// Stored rollups with `from <= hour < to`, for one tenant or all.
+    ///
+    /// Usage not flushed yet is not included.
+    ///
+    /// # Errors
+    ///
+    /// Returns the repository's error.
+    pub async fn list(
+        &self,
+        tenant_id: Option<TenantId>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> CoreResult<Vec<UsageRollup>> {
+        self.repository.list(tenant_id, from, to).await
+    }
+
+    fn add(&self, tenant_id: TenantId, update: impl FnOnce(&mut UsageRollup)) {
+        let now = Utc::now();
+        let hour = akidb_core::hour_of(now);
+        let mut pending = self.pending.lock().expect("usage meter lock poisoned");
+        update(
+            pending
+                .entry((tenant_id, hour))
+                .or_insert_with(|| UsageRollup::new(tenant_id, now)),
+        );
+    }
+
+    fn merge(&self, usage: &UsageRollup) {
+        self.pending
+            .lock()
+            .expect("usage meter lock poisoned")
+            .entry((usage.tenant_id, usage.hour))
+            .and_modify(|pending| pending.merge(usage))
+            .or_insert_with(|| usage.clone());
+    }
+}
+
+/// Spawns a task sampling stored bytes and flushing `service`'s usage meter
+/// every `interval`. Does nothing useful if metering is not enabled.
+pub fn spawn_flush(service: Arc<CollectionService>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            if let Err(e) = service.flush_usage().await {
+                tracing::warn!("Failed to flush usage rollups: {}", e);
+            }
+        }
+    })
+}
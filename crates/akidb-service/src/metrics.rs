@@ -3,6 +3,7 @@
 //! Provides comprehensive metrics collection for monitoring, alerting, and observability.
 //! All metrics follow Prometheus naming conventions and best practices.
 
+use crate::collection_service::CollectionService;
 use lazy_static::lazy_static;
 use prometheus::{
     register_counter_vec, register_gauge_vec, register_histogram_vec, CounterVec, Encoder,
@@ -20,11 +21,11 @@ lazy_static! {
     )
     .unwrap();
 
-    /// HTTP request latency distribution (seconds)
+    /// HTTP request latency distribution (seconds) by route template and tenant
     pub static ref HTTP_REQUEST_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
         "akidb_http_request_duration_seconds",
         "HTTP request latency in seconds",
-        &["method", "path"],
+        &["method", "path", "status_code", "tenant"],
         vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
     )
     .unwrap();
@@ -46,7 +47,7 @@ lazy_static! {
     )
     .unwrap();
 
-    // ========== Vector Operation Metrics (3 metrics) ==========
+    // ========== Vector Operation Metrics (5 metrics) ==========
 
     /// Vector search latency by tier (hot/warm/cold) in seconds
     pub static ref VECTOR_SEARCH_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
@@ -66,6 +67,24 @@ lazy_static! {
     )
     .unwrap();
 
+    /// End-to-end search latency (filtering and over-fetch passes included)
+    pub static ref SEARCH_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
+        "akidb_search_duration_seconds",
+        "Search latency in seconds, by whether a metadata filter was applied",
+        &["filtered"],
+        vec![0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0]
+    )
+    .unwrap();
+
+    /// Time spent inside the vector index by operation (insert/delete/search)
+    pub static ref INDEX_OPERATION_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
+        "akidb_index_operation_duration_seconds",
+        "Vector index operation latency in seconds",
+        &["operation"],
+        vec![0.00005, 0.0001, 0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25]
+    )
+    .unwrap();
+
     /// Number of vectors in collection
     pub static ref COLLECTION_SIZE_VECTORS: GaugeVec = register_gauge_vec!(
         "akidb_collection_size_vectors",
@@ -118,6 +137,60 @@ lazy_static! {
         &["worker_type", "status"]
     )
     .unwrap();
+
+    // ========== Embedding Metrics (1 metric) ==========
+
+    /// Embedding cache lookups by result (hit, miss)
+    pub static ref EMBEDDING_CACHE_REQUESTS_TOTAL: CounterVec = register_counter_vec!(
+        "akidb_embedding_cache_requests_total",
+        "Embedding cache lookups by result",
+        &["result"]
+    )
+    .unwrap();
+
+    // ========== Replication Metrics (2 metrics) ==========
+
+    /// Seconds since the last primary heartbeat a standby has applied
+    pub static ref REPLICATION_LAG_SECONDS: GaugeVec = register_gauge_vec!(
+        "akidb_replication_lag_seconds",
+        "Seconds since the last primary heartbeat applied by the standby",
+        &["role"]
+    )
+    .unwrap();
+
+    /// 1 when replication is healthy, 0 otherwise
+    pub static ref REPLICATION_HEALTHY: GaugeVec = register_gauge_vec!(
+        "akidb_replication_healthy",
+        "Whether replication is healthy (1) or not (0)",
+        &["role"]
+    )
+    .unwrap();
+
+    /// 1 while this process holds the lease, 0 otherwise
+    pub static ref LEADER: GaugeVec = register_gauge_vec!(
+        "akidb_leader",
+        "Whether this process is the elected leader (1) or not (0)",
+        &["lease"]
+    )
+    .unwrap();
+
+    // ========== CDC Metrics (2 metrics) ==========
+
+    /// Change events acknowledged by the CDC broker
+    pub static ref CDC_EVENTS_PUBLISHED_TOTAL: CounterVec = register_counter_vec!(
+        "akidb_cdc_events_published_total",
+        "Total change events acknowledged by the CDC broker",
+        &["sink"]
+    )
+    .unwrap();
+
+    /// Failed CDC publishing passes, retried from the checkpoint
+    pub static ref CDC_PUBLISH_ERRORS_TOTAL: CounterVec = register_counter_vec!(
+        "akidb_cdc_publish_errors_total",
+        "Total failed CDC publishing passes",
+        &["sink"]
+    )
+    .unwrap();
 }
 
 /// Initialize all metrics by accessing them once
@@ -142,12 +215,20 @@ pub fn init_metrics() {
     let _ = &*GRPC_REQUEST_DURATION_SECONDS;
     let _ = &*VECTOR_SEARCH_DURATION_SECONDS;
     let _ = &*VECTOR_INSERT_DURATION_SECONDS;
+    let _ = &*SEARCH_DURATION_SECONDS;
+    let _ = &*INDEX_OPERATION_DURATION_SECONDS;
     let _ = &*COLLECTION_SIZE_VECTORS;
     let _ = &*TIER_DISTRIBUTION_COLLECTIONS;
     let _ = &*S3_OPERATIONS_TOTAL;
     let _ = &*S3_OPERATION_DURATION_SECONDS;
     let _ = &*MEMORY_USAGE_BYTES;
     let _ = &*BACKGROUND_WORKER_RUNS_TOTAL;
+    let _ = &*EMBEDDING_CACHE_REQUESTS_TOTAL;
+    let _ = &*REPLICATION_LAG_SECONDS;
+    let _ = &*REPLICATION_HEALTHY;
+    let _ = &*CDC_EVENTS_PUBLISHED_TOTAL;
+    let _ = &*CDC_PUBLISH_ERRORS_TOTAL;
+    let _ = &*LEADER;
 }
 
 /// Exports all metrics in Prometheus text format
@@ -184,6 +265,57 @@ pub fn export_prometheus() -> String {
     })
 }
 
+/// Exports every metric the server has in Prometheus text format
+///
+/// Combines service metrics (collections, vectors), storage metrics (S3, DLQ,
+/// circuit breaker, WAL), the global registry and build info. Served by
+/// `GET /metrics` and pushed by the OTLP exporter.
+pub async fn export_all(service: &CollectionService) -> String {
+    let mut output = String::new();
+
+    // Header
+    output.push_str("# AkiDB 2.0 Metrics\n");
+    output.push_str("# Prometheus Text Format v0.0.4\n\n");
+
+    // Service metrics (collections, vectors, searches)
+    match service.metrics() {
+        Some(metrics) => {
+            output.push_str(&metrics.export_prometheus().await);
+            output.push('\n');
+        }
+        None => {
+            output.push_str("# Service metrics not available\n");
+            output.push_str("# Service was not created with full persistence\n\n");
+        }
+    }
+
+    // Storage metrics (S3, DLQ, circuit breaker, WAL)
+    match service.storage_metrics().await {
+        Some(storage_metrics) => {
+            output.push_str(&storage_metrics.export_prometheus());
+            output.push('\n');
+        }
+        None => {
+            output.push_str("# Storage metrics not available\n");
+            output.push_str("# No storage backends configured\n\n");
+        }
+    }
+
+    // Request latency and index/search histograms (global registry)
+    output.push_str(&export_prometheus());
+    output.push('\n');
+
+    // Build info
+    output.push_str("# HELP akidb_build_info Build information\n");
+    output.push_str("# TYPE akidb_build_info gauge\n");
+    output.push_str(&format!(
+        "akidb_build_info{{version=\"{}\"}} 1\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,7 +337,7 @@ mod tests {
     #[test]
     fn test_http_request_duration() {
         HTTP_REQUEST_DURATION_SECONDS
-            .with_label_values(&["POST", "/search"])
+            .with_label_values(&["POST", "/search", "200", "default"])
             .observe(0.015);
 
         let metrics = prometheus::gather();
@@ -354,7 +486,7 @@ mod tests {
         // Actually USE each metric to force lazy_static initialization
         // (just accessing the reference might not trigger registration)
         HTTP_REQUESTS_TOTAL.with_label_values(&["TEST", "/test", "200"]).inc();
-        HTTP_REQUEST_DURATION_SECONDS.with_label_values(&["TEST", "/test"]).observe(0.001);
+        HTTP_REQUEST_DURATION_SECONDS.with_label_values(&["TEST", "/test", "200", "default"]).observe(0.001);
         GRPC_REQUESTS_TOTAL.with_label_values(&["test_service", "test_method", "ok"]).inc();
         GRPC_REQUEST_DURATION_SECONDS.with_label_values(&["test_service", "test_method"]).observe(0.001);
         VECTOR_SEARCH_DURATION_SECONDS.with_label_values(&["hot"]).observe(0.001);
@@ -365,6 +497,7 @@ mod tests {
         S3_OPERATION_DURATION_SECONDS.with_label_values(&["put"]).observe(0.1);
         MEMORY_USAGE_BYTES.with_label_values(&["test_component"]).set(1024.0);
         BACKGROUND_WORKER_RUNS_TOTAL.with_label_values(&["test_worker", "success"]).inc();
+        EMBEDDING_CACHE_REQUESTS_TOTAL.with_label_values(&["hit"]).inc();
 
         let metrics = prometheus::gather();
         let metric_names: Vec<String> = metrics
@@ -389,6 +522,7 @@ mod tests {
         assert!(metric_names.contains(&"akidb_s3_operation_duration_seconds".to_string()));
         assert!(metric_names.contains(&"akidb_memory_usage_bytes".to_string()));
         assert!(metric_names.contains(&"akidb_background_worker_runs_total".to_string()));
+        assert!(metric_names.contains(&"akidb_embedding_cache_requests_total".to_string()));
 
         // Verify we have at least 12 metrics
         assert!(
@@ -0,0 +1,554 @@
+//! OTLP metrics export
+//!
+//! Pushes the same metrics served by `GET /metrics` (service, storage, query
+//! cache, embedding and request metrics) to an OpenTelemetry collector over
+//! OTLP/HTTP with JSON encoding, for deployments that collect everything
+//! through an OTel collector instead of scraping Prometheus.
+//!
+//! Configured with the standard OpenTelemetry environment variables:
+//! - `OTEL_EXPORTER_OTLP_METRICS_ENDPOINT` - full URL of the metrics endpoint
+//! - `OTEL_EXPORTER_OTLP_ENDPOINT` - collector base URL (`/v1/metrics` is appended)
+//! - `OTEL_METRIC_EXPORT_INTERVAL` - export interval in milliseconds (default 60000)
+//!
+//! Export is disabled when neither endpoint variable is set.
+//!
+//! Prometheus counters become cumulative monotonic sums, gauges stay gauges
+//! and histograms become explicit-bucket histograms.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+
+use crate::collection_service::CollectionService;
+use crate::metrics;
+
+/// Default export interval (OpenTelemetry SDK default)
+const DEFAULT_EXPORT_INTERVAL: Duration = Duration::from_millis(60_000);
+
+/// Timeout for a single export request
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `AGGREGATION_TEMPORALITY_CUMULATIVE` in the OTLP protocol
+const CUMULATIVE: u8 = 2;
+
+/// Where and how often to push metrics
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtlpMetricsConfig {
+    /// OTLP/HTTP metrics endpoint (e.g. "http://otel-collector:4318/v1/metrics")
+    pub endpoint: String,
+    /// Interval between exports
+    pub interval: Duration,
+}
+
+impl OtlpMetricsConfig {
+    /// Reads the configuration from the standard `OTEL_*` environment
+    /// variables, returning `None` if no endpoint is configured.
+    pub fn from_env() -> Option<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let endpoint = var("OTEL_EXPORTER_OTLP_METRICS_ENDPOINT")
+            .filter(|endpoint| !endpoint.is_empty())
+            .or_else(|| {
+                var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                    .filter(|endpoint| !endpoint.is_empty())
+                    .map(|base| format!("{}/v1/metrics", base.trim_end_matches('/')))
+            })?;
+
+        let interval = var("OTEL_METRIC_EXPORT_INTERVAL")
+            .and_then(|ms| ms.parse::<u64>().ok())
+            .filter(|&ms| ms > 0)
+            .map_or(DEFAULT_EXPORT_INTERVAL, Duration::from_millis);
+
+        Some(Self { endpoint, interval })
+    }
+}
+
+/// Periodic OTLP/HTTP metrics exporter
+pub struct OtlpMetricsExporter {
+    client: reqwest::Client,
+    config: OtlpMetricsConfig,
+    service_name: String,
+    start_time_unix_nano: u128,
+}
+
+impl OtlpMetricsExporter {
+    /// Creates an exporter reporting under `service_name` (e.g. "akidb-rest").
+    pub fn new(service_name: impl Into<String>, config: OtlpMetricsConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(EXPORT_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            config,
+            service_name: service_name.into(),
+            start_time_unix_nano: unix_nanos(),
+        }
+    }
+
+    /// Exports the current value of every metric once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the collector cannot be reached or rejects the
+    /// request.
+    pub async fn export(&self, service: &CollectionService) -> Result<(), reqwest::Error> {
+        let text = metrics::export_all(service).await;
+        let request = self.encode(&text, unix_nanos());
+
+        self.client
+            .post(&self.config.endpoint)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Spawns a background task exporting every configured interval.
+    ///
+    /// Failed exports are logged and retried at the next interval. Abort the
+    /// handle and call [`export`](Self::export) once more on shutdown to flush
+    /// the final values.
+    pub fn spawn(self: Arc<Self>, service: Arc<CollectionService>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately; skip it so startup is not
+            // reported as a sample
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.export(&service).await {
+                    tracing::warn!(
+                        "OTLP metrics export to {} failed: {}",
+                        self.config.endpoint,
+                        e
+                    );
+                }
+            }
+        })
+    }
+
+    /// Converts Prometheus text exposition into an OTLP
+    /// `ExportMetricsServiceRequest` (JSON encoding).
+    fn encode(&self, text: &str, time_unix_nano: u128) -> Value {
+        let start = self.start_time_unix_nano.to_string();
+        let time = time_unix_nano.to_string();
+
+        let metrics: Vec<Value> = parse_families(text)
+            .iter()
+            .filter_map(|family| encode_family(family, &start, &time))
+            .collect();
+
+        json!({
+            "resourceMetrics": [{
+                "resource": {
+                    "attributes": [string_attribute("service.name", &self.service_name)]
+                },
+                "scopeMetrics": [{
+                    "scope": {
+                        "name": "akidb",
+                        "version": env!("CARGO_PKG_VERSION")
+                    },
+                    "metrics": metrics
+                }]
+            }]
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FamilyKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+#[derive(Debug)]
+struct Family {
+    name: String,
+    help: String,
+    kind: FamilyKind,
+    samples: Vec<Sample>,
+}
+
+#[derive(Debug)]
+struct Sample {
+    name: String,
+    labels: Vec<(String, String)>,
+    value: f64,
+}
+
+/// Groups the samples of a Prometheus text exposition into metric families.
+///
+/// Samples without a `# TYPE` line are treated as gauges.
+fn parse_families(text: &str) -> Vec<Family> {
+    let mut families: Vec<Family> = Vec::new();
+    let mut help: BTreeMap<String, String> = BTreeMap::new();
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(rest) = line.strip_prefix("# HELP ") {
+            if let Some((name, text)) = rest.split_once(' ') {
+                help.insert(name.to_string(), text.to_string());
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("# TYPE ") {
+            if let Some((name, kind)) = rest.split_once(' ') {
+                let kind = match kind.trim() {
+                    "counter" => FamilyKind::Counter,
+                    "histogram" => FamilyKind::Histogram,
+                    _ => FamilyKind::Gauge,
+                };
+                families.push(Family {
+                    name: name.to_string(),
+                    help: help.remove(name).unwrap_or_default(),
+                    kind,
+                    samples: Vec::new(),
+                });
+            }
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let Some(sample) = parse_sample(line) else {
+            continue;
+        };
+        match families
+            .iter_mut()
+            .rev()
+            .find(|family| family.owns(&sample.name))
+        {
+            Some(family) => family.samples.push(sample),
+            None => families.push(Family {
+                name: sample.name.clone(),
+                help: help.remove(&sample.name).unwrap_or_default(),
+                kind: FamilyKind::Gauge,
+                samples: vec![sample],
+            }),
+        }
+    }
+
+    families
+}
+
+impl Family {
+    fn owns(&self, sample_name: &str) -> bool {
+        match self.kind {
+            FamilyKind::Histogram => sample_name
+                .strip_prefix(self.name.as_str())
+                .is_some_and(|suffix| matches!(suffix, "_bucket" | "_sum" | "_count")),
+            _ => sample_name == self.name,
+        }
+    }
+}
+
+/// Parses `name{label="value",...} value [timestamp]`.
+fn parse_sample(line: &str) -> Option<Sample> {
+    let (name, labels, rest) = match line.find('{') {
+        Some(open) => {
+            let (labels, rest) = parse_labels(&line[open + 1..])?;
+            (&line[..open], labels, rest)
+        }
+        None => {
+            let (name, rest) = line.split_once(' ')?;
+            (name, Vec::new(), rest)
+        }
+    };
+
+    let value = match rest.split_whitespace().next()? {
+        "+Inf" => f64::INFINITY,
+        "-Inf" => f64::NEG_INFINITY,
+        value => value.parse().ok()?,
+    };
+
+    Some(Sample {
+        name: name.trim().to_string(),
+        labels,
+        value,
+    })
+}
+
+/// Parses the label set after the opening brace, returning the labels and
+/// the rest of the line after the closing brace.
+fn parse_labels(mut input: &str) -> Option<(Vec<(String, String)>, &str)> {
+    let mut labels = Vec::new();
+
+    loop {
+        input = input.trim_start_matches([',', ' ']);
+        if let Some(rest) = input.strip_prefix('}') {
+            return Some((labels, rest));
+        }
+
+        let (key, rest) = input.split_once("=\"")?;
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        let end = loop {
+            match chars.next()? {
+                (i, '"') => break i,
+                (_, '\\') => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    c => value.push(c),
+                },
+                (_, c) => value.push(c),
+            }
+        };
+
+        labels.push((key.trim().to_string(), value));
+        input = &rest[end + 1..];
+    }
+}
+
+fn encode_family(family: &Family, start: &str, time: &str) -> Option<Value> {
+    if family.samples.is_empty() {
+        return None;
+    }
+
+    let mut metric = json!({
+        "name": family.name,
+        "description": family.help,
+    });
+    match family.kind {
+        FamilyKind::Counter => {
+            metric["sum"] = json!({
+                "dataPoints": number_points(family, start, time),
+                "aggregationTemporality": CUMULATIVE,
+                "isMonotonic": true
+            });
+        }
+        FamilyKind::Gauge => {
+            metric["gauge"] = json!({ "dataPoints": number_points(family, start, time) });
+        }
+        FamilyKind::Histogram => {
+            metric["histogram"] = json!({
+                "dataPoints": histogram_points(family, start, time),
+                "aggregationTemporality": CUMULATIVE
+            });
+        }
+    }
+    Some(metric)
+}
+
+fn number_points(family: &Family, start: &str, time: &str) -> Vec<Value> {
+    family
+        .samples
+        .iter()
+        .filter(|sample| sample.value.is_finite())
+        .map(|sample| {
+            json!({
+                "attributes": attributes(&sample.labels),
+                "startTimeUnixNano": start,
+                "timeUnixNano": time,
+                "asDouble": sample.value
+            })
+        })
+        .collect()
+}
+
+fn histogram_points(family: &Family, start: &str, time: &str) -> Vec<Value> {
+    #[derive(Default)]
+    struct Point {
+        buckets: Vec<(f64, f64)>,
+        sum: f64,
+        count: f64,
+    }
+
+    // Series are keyed by their labels other than `le`
+    let mut points: BTreeMap<Vec<(String, String)>, Point> = BTreeMap::new();
+    for sample in &family.samples {
+        let suffix = &sample.name[family.name.len()..];
+        let mut le = None;
+        let labels: Vec<(String, String)> = sample
+            .labels
+            .iter()
+            .filter(|(key, value)| {
+                if key == "le" {
+                    le = Some(value.clone());
+                    false
+                } else {
+                    true
+                }
+            })
+            .cloned()
+            .collect();
+
+        let point = points.entry(labels).or_default();
+        match suffix {
+            "_sum" => point.sum = sample.value,
+            "_count" => point.count = sample.value,
+            _ => {
+                let bound = match le.as_deref() {
+                    Some("+Inf") | None => f64::INFINITY,
+                    Some(le) => le.parse().unwrap_or(f64::INFINITY),
+                };
+                point.buckets.push((bound, sample.value));
+            }
+        }
+    }
+
+    points
+        .into_iter()
+        .map(|(labels, mut point)| {
+            point.buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            // Prometheus buckets are cumulative, OTLP bucket counts are not
+            let explicit_bounds: Vec<f64> = point
+                .buckets
+                .iter()
+                .map(|&(bound, _)| bound)
+                .filter(|bound| bound.is_finite())
+                .collect();
+            let mut bucket_counts = Vec::with_capacity(explicit_bounds.len() + 1);
+            let mut previous = 0.0;
+            for &(_, cumulative) in &point.buckets {
+                bucket_counts.push(((cumulative - previous).max(0.0) as u64).to_string());
+                previous = cumulative;
+            }
+            if bucket_counts.len() == explicit_bounds.len() {
+                // No +Inf bucket: everything above the last bound
+                bucket_counts.push(((point.count - previous).max(0.0) as u64).to_string());
+            }
+
+            json!({
+                "attributes": attributes(&labels),
+                "startTimeUnixNano": start,
+                "timeUnixNano": time,
+                "count": (point.count as u64).to_string(),
+                "sum": point.sum,
+                "bucketCounts": bucket_counts,
+                "explicitBounds": explicit_bounds
+            })
+        })
+        .collect()
+}
+
+fn attributes(labels: &[(String, String)]) -> Vec<Value> {
+    labels
+        .iter()
+        .map(|(key, value)| string_attribute(key, value))
+        .collect()
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn unix_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = r#"
+# HELP akidb_search_total Total searches
+# TYPE akidb_search_total counter
+akidb_search_total{collection="docs",tenant="a \"b\""} 42
+# HELP akidb_vectors Vectors stored
+# TYPE akidb_vectors gauge
+akidb_vectors 1000
+# HELP akidb_latency_seconds Search latency
+# TYPE akidb_latency_seconds histogram
+akidb_latency_seconds_bucket{op="search",le="0.1"} 3
+akidb_latency_seconds_bucket{op="search",le="1"} 5
+akidb_latency_seconds_bucket{op="search",le="+Inf"} 6
+akidb_latency_seconds_sum{op="search"} 4.5
+akidb_latency_seconds_count{op="search"} 6
+akidb_build_info{version="2.0.0"} 1
+"#;
+
+    fn exporter() -> OtlpMetricsExporter {
+        OtlpMetricsExporter::new(
+            "akidb-test",
+            OtlpMetricsConfig {
+                endpoint: "http://localhost:4318/v1/metrics".to_string(),
+                interval: DEFAULT_EXPORT_INTERVAL,
+            },
+        )
+    }
+
+    #[test]
+    fn test_encode_prometheus_text() {
+        let request = exporter().encode(TEXT, 1_000);
+        let resource = &request["resourceMetrics"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"],
+            "akidb-test"
+        );
+
+        let metrics = resource["scopeMetrics"][0]["metrics"].as_array().unwrap();
+        assert_eq!(metrics.len(), 4);
+
+        let counter = &metrics[0];
+        assert_eq!(counter["name"], "akidb_search_total");
+        assert_eq!(counter["description"], "Total searches");
+        assert_eq!(counter["sum"]["isMonotonic"], true);
+        let point = &counter["sum"]["dataPoints"][0];
+        assert_eq!(point["asDouble"], 42.0);
+        assert_eq!(point["timeUnixNano"], "1000");
+        assert_eq!(point["attributes"][1]["value"]["stringValue"], "a \"b\"");
+
+        assert_eq!(metrics[1]["gauge"]["dataPoints"][0]["asDouble"], 1000.0);
+
+        let histogram = &metrics[2]["histogram"]["dataPoints"][0];
+        assert_eq!(histogram["attributes"][0]["key"], "op");
+        assert_eq!(histogram["count"], "6");
+        assert_eq!(histogram["sum"], 4.5);
+        assert_eq!(histogram["explicitBounds"], json!([0.1, 1.0]));
+        assert_eq!(histogram["bucketCounts"], json!(["3", "2", "1"]));
+
+        // Untyped samples are exported as gauges
+        assert_eq!(metrics[3]["name"], "akidb_build_info");
+        assert_eq!(
+            metrics[3]["gauge"]["dataPoints"][0]["attributes"][0]["value"]["stringValue"],
+            "2.0.0"
+        );
+    }
+
+    #[test]
+    fn test_config_from_vars() {
+        let vars = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(OtlpMetricsConfig::from_vars(vars(&[])), None);
+
+        let config = OtlpMetricsConfig::from_vars(vars(&[(
+            "OTEL_EXPORTER_OTLP_ENDPOINT",
+            "http://collector:4318/",
+        )]))
+        .unwrap();
+        assert_eq!(config.endpoint, "http://collector:4318/v1/metrics");
+        assert_eq!(config.interval, DEFAULT_EXPORT_INTERVAL);
+
+        let config = OtlpMetricsConfig::from_vars(vars(&[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4318"),
+            (
+                "OTEL_EXPORTER_OTLP_METRICS_ENDPOINT",
+                "http://metrics:4318/m",
+            ),
+            ("OTEL_METRIC_EXPORT_INTERVAL", "5000"),
+        ]))
+        .unwrap();
+        assert_eq!(config.endpoint, "http://metrics:4318/m");
+        assert_eq!(config.interval, Duration::from_secs(5));
+    }
+}
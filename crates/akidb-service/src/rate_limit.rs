@@ -0,0 +1,333 @@
+//! Token-bucket rate limiting shared by the REST and gRPC servers.
+//!
+//! Requests carrying a known API key draw from their tenant's bucket, sized
+//! from `TenantQuota::qps_quota`, so every key owned by a tenant shares one
+//! budget. Requests without a key, and requests whose key cannot be resolved
+//! to a tenant, draw from a bucket per client IP sized at the default QPS, so
+//! omitting or rotating keys does not escape the limit.
+//!
+//! Both the bucket map and the resolved-key cache are bounded; once full,
+//! idle entries are dropped first and then the least recently used ones.
+
+use akidb_core::{hash_api_key, ApiKeyRepository, TenantCatalog, TenantQuota};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a resolved quota is cached before it is looked up again.
+const QUOTA_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Most buckets (and resolved keys) tracked at once.
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Token bucket refilled continuously at `rate` tokens per second.
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(qps: u32, now: Instant) -> Self {
+        // Allow a burst of one second worth of requests
+        let capacity = f64::from(qps);
+        Self {
+            capacity,
+            rate: capacity,
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Whether the bucket would be full by `now`, i.e. carries no state.
+    fn is_idle(&self, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens + elapsed * self.rate >= self.capacity
+    }
+
+    fn try_acquire(&mut self, now: Instant) -> RateLimitDecision {
+        self.refill(now);
+
+        let allowed = self.tokens >= 1.0;
+        if allowed {
+            self.tokens -= 1.0;
+        }
+
+        // Time until one token is available again
+        let reset_after = if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.rate)
+        };
+
+        RateLimitDecision {
+            allowed,
+            limit: self.capacity as u32,
+            remaining: self.tokens.floor() as u32,
+            reset_after,
+        }
+    }
+}
+
+/// Outcome of a rate limit check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    /// Whether the request may proceed.
+    pub allowed: bool,
+    /// Requests permitted per second; 0 when the request is not limited.
+    pub limit: u32,
+    /// Requests left in the current burst.
+    pub remaining: u32,
+    /// Time until the next request will be permitted.
+    pub reset_after: Duration,
+}
+
+impl RateLimitDecision {
+    const UNLIMITED: Self = Self {
+        allowed: true,
+        limit: 0,
+        remaining: 0,
+        reset_after: Duration::ZERO,
+    };
+}
+
+/// Tenant bucket and QPS of a resolved key: `None` for unknown or expired keys.
+type CachedQuota = Option<(String, u32)>;
+
+/// Token-bucket rate limiter keyed by tenant, or by client IP for anonymous
+/// and unresolved keys.
+pub struct RateLimiter {
+    api_keys: Option<Arc<dyn ApiKeyRepository>>,
+    tenants: Option<Arc<dyn TenantCatalog>>,
+    default_qps: u32,
+    /// Cleared to let every request through (`features.rate_limiting_enabled`)
+    enabled: AtomicBool,
+    quotas: Mutex<HashMap<String, (Instant, CachedQuota)>>,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter that applies `default_qps` to every client IP.
+    ///
+    /// Without repositories keys cannot be resolved, so all requests are
+    /// limited by IP. A `default_qps` of 0 disables limiting.
+    pub fn new(default_qps: u32) -> Self {
+        Self {
+            api_keys: None,
+            tenants: None,
+            default_qps,
+            enabled: AtomicBool::new(true),
+            quotas: Mutex::new(HashMap::new()),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Creates a limiter that sizes buckets from each key's tenant quota.
+    ///
+    /// Anonymous requests and keys that are unknown, expired, or whose tenant
+    /// cannot be loaded are limited per client IP at `TenantQuota::DEFAULT_QPS`.
+    pub fn with_repositories(
+        api_keys: Arc<dyn ApiKeyRepository>,
+        tenants: Arc<dyn TenantCatalog>,
+    ) -> Self {
+        Self {
+            api_keys: Some(api_keys),
+            tenants: Some(tenants),
+            ..Self::new(TenantQuota::DEFAULT_QPS)
+        }
+    }
+
+    /// Turns limiting on or off at runtime; buckets are kept while off.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Checks and consumes one request from `api_key` or, failing that,
+    /// `client_ip`.
+    ///
+    /// Requests whose client address is unknown share a single bucket.
+    pub async fn check(
+        &self,
+        api_key: Option<&str>,
+        client_ip: Option<IpAddr>,
+    ) -> RateLimitDecision {
+        let tenant_quota = match api_key.filter(|key| !key.is_empty()) {
+            Some(key) => self.resolve_quota(&hash_api_key(key)).await,
+            None => None,
+        };
+        let (bucket_key, qps) = tenant_quota.unwrap_or_else(|| {
+            let client = client_ip.map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+            (format!("ip:{}", client), self.default_qps)
+        });
+
+        // qps_quota == 0 means unbounded (see TenantQuota::is_qps_unbounded)
+        if qps == 0 {
+            return RateLimitDecision::UNLIMITED;
+        }
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limiter lock poisoned");
+
+        if buckets.len() >= MAX_TRACKED_BUCKETS && !buckets.contains_key(&bucket_key) {
+            // Drop buckets that have fully refilled; they carry no state worth keeping
+            buckets.retain(|_, bucket| !bucket.is_idle(now));
+            if buckets.len() >= MAX_TRACKED_BUCKETS {
+                let oldest = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.last_refill)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    buckets.remove(&oldest);
+                }
+            }
+        }
+
+        let bucket = buckets
+            .entry(bucket_key)
+            .or_insert_with(|| TokenBucket::new(qps, now));
+
+        // Quota changed since the bucket was created
+        if bucket.capacity as u32 != qps {
+            *bucket = TokenBucket::new(qps, now);
+        }
+
+        bucket.try_acquire(now)
+    }
+
+    async fn resolve_quota(&self, key_hash: &str) -> CachedQuota {
+        self.api_keys.as_ref()?;
+
+        {
+            let quotas = self.quotas.lock().expect("rate limiter lock poisoned");
+            if let Some((resolved_at, quota)) = quotas.get(key_hash) {
+                if resolved_at.elapsed() < QUOTA_CACHE_TTL {
+                    return quota.clone();
+                }
+            }
+        }
+
+        let quota = self.lookup_tenant_quota(key_hash).await;
+
+        let mut quotas = self.quotas.lock().expect("rate limiter lock poisoned");
+        if quotas.len() >= MAX_TRACKED_BUCKETS && !quotas.contains_key(key_hash) {
+            quotas.retain(|_, (resolved_at, _)| resolved_at.elapsed() < QUOTA_CACHE_TTL);
+            if quotas.len() >= MAX_TRACKED_BUCKETS {
+                let oldest = quotas
+                    .iter()
+                    .min_by_key(|(_, (resolved_at, _))| *resolved_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    quotas.remove(&oldest);
+                }
+            }
+        }
+        quotas.insert(key_hash.to_string(), (Instant::now(), quota.clone()));
+        quota
+    }
+
+    async fn lookup_tenant_quota(&self, key_hash: &str) -> CachedQuota {
+        let (api_keys, tenants) = (self.api_keys.as_ref()?, self.tenants.as_ref()?);
+
+        let api_key = match api_keys.get_by_hash(key_hash).await {
+            Ok(Some(api_key)) if !api_key.is_expired() => api_key,
+            Ok(_) => return None,
+            Err(e) => {
+                tracing::warn!("Rate limiter failed to look up API key: {}", e);
+                return None;
+            }
+        };
+
+        match tenants.get(api_key.tenant_id).await {
+            Ok(Some(tenant)) => Some((
+                format!("tenant:{}", tenant.tenant_id),
+                tenant.quotas.qps_quota,
+            )),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Rate limiter failed to load tenant quota: {}", e);
+                None
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn tracked_buckets(&self) -> usize {
+        self.buckets
+            .lock()
+            .expect("rate limiter lock poisoned")
+            .len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn ip(last: u8) -> Option<IpAddr> {
+        Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)))
+    }
+
+    #[test]
+    fn test_token_bucket_burst_and_refill() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2, start);
+
+        assert!(bucket.try_acquire(start).allowed);
+        assert!(bucket.try_acquire(start).allowed);
+
+        let denied = bucket.try_acquire(start);
+        assert!(!denied.allowed);
+        assert_eq!(denied.remaining, 0);
+        assert!(denied.reset_after > Duration::ZERO);
+
+        // Half a second refills one token at 2 QPS
+        assert!(
+            bucket
+                .try_acquire(start + Duration::from_millis(500))
+                .allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_limiter_clients_are_independent() {
+        let limiter = RateLimiter::new(1);
+
+        assert!(limiter.check(None, ip(1)).await.allowed);
+        assert!(!limiter.check(None, ip(1)).await.allowed);
+        assert!(limiter.check(None, ip(2)).await.allowed);
+
+        // Keys that cannot be resolved share their client's bucket
+        assert!(!limiter.check(Some("ak_rotated"), ip(1)).await.allowed);
+        assert!(!limiter.check(Some("ak_other"), ip(2)).await.allowed);
+    }
+
+    #[tokio::test]
+    async fn test_limiter_bounds_tracked_buckets() {
+        let limiter = RateLimiter::new(1);
+
+        for n in 0..=MAX_TRACKED_BUCKETS as u32 {
+            let client = IpAddr::V4(Ipv4Addr::from(n));
+            assert!(limiter.check(None, Some(client)).await.allowed);
+        }
+        assert!(limiter.tracked_buckets() <= MAX_TRACKED_BUCKETS);
+    }
+}
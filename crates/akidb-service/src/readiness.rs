@@ -0,0 +1,196 @@
+//! Bootstrap progress behind the readiness probe and `/statusz`.
+//!
+//! A service backed by a repository is not ready until
+//! [`CollectionService::bootstrap`] has loaded every collection, replaying
+//! each one's WAL into its index, and the object store has answered a probe.
+//! Until then [`CollectionService::is_ready`] reports false, so the pod gets
+//! no traffic while indexes are still rebuilding. [`Readiness::status`]
+//! reports how far the bootstrap has got.
+//!
+//! [`CollectionService::bootstrap`]: crate::CollectionService::bootstrap
+//! [`CollectionService::is_ready`]: crate::CollectionService::is_ready
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use akidb_core::CollectionId;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::health::{ProbeResult, ProbeStatus};
+
+/// Delay between object store probes while waiting for it to become reachable.
+pub const OBJECT_STORE_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Step the bootstrap is at, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BootstrapStage {
+    /// Nothing loaded yet
+    Starting,
+    /// Loading collections and replaying their WALs
+    LoadingCollections,
+    /// Collections loaded; waiting for the object store to be reachable
+    WaitingForObjectStore,
+    Ready,
+    /// The collection list could not be read; see `error`
+    Failed,
+}
+
+/// A collection that could not be loaded during bootstrap.
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionLoadFailure {
+    pub collection_id: CollectionId,
+    pub error: String,
+}
+
+/// Snapshot of the bootstrap progress.
+#[derive(Debug, Clone, Serialize)]
+pub struct BootstrapStatus {
+    pub stage: BootstrapStage,
+    pub started_at: DateTime<Utc>,
+    /// When the service became ready
+    pub completed_at: Option<DateTime<Utc>>,
+    pub collections_total: usize,
+    /// Collections whose index was rebuilt, failures included
+    pub collections_loaded: usize,
+    /// Vectors inserted into indexes while loading
+    pub vectors_recovered: usize,
+    /// Whether every collection's WAL has been replayed
+    pub wal_recovered: bool,
+    /// Last object store probe; absent until collections are loaded
+    pub object_store: Option<ProbeResult>,
+    /// Collections skipped because they failed to load
+    pub failures: Vec<CollectionLoadFailure>,
+    pub error: Option<String>,
+}
+
+/// Tracks bootstrap progress for a [`CollectionService`](crate::CollectionService).
+pub struct Readiness {
+    status: Mutex<BootstrapStatus>,
+}
+
+impl Readiness {
+    /// Progress of a bootstrap that has not started.
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(BootstrapStatus {
+                stage: BootstrapStage::Starting,
+                started_at: Utc::now(),
+                completed_at: None,
+                collections_total: 0,
+                collections_loaded: 0,
+                vectors_recovered: 0,
+                wal_recovered: false,
+                object_store: None,
+                failures: Vec::new(),
+                error: None,
+            }),
+        }
+    }
+
+    /// Current progress.
+    pub fn status(&self) -> BootstrapStatus {
+        self.lock().clone()
+    }
+
+    /// Whether the bootstrap has completed.
+    pub fn is_ready(&self) -> bool {
+        self.lock().stage == BootstrapStage::Ready
+    }
+
+    pub(crate) fn loading(&self, collections_total: usize) {
+        let mut status = self.lock();
+        status.stage = BootstrapStage::LoadingCollections;
+        status.collections_total = collections_total;
+        status.collections_loaded = 0;
+        status.vectors_recovered = 0;
+        status.wal_recovered = false;
+        status.failures.clear();
+        status.error = None;
+    }
+
+    pub(crate) fn collection_loaded(&self, vectors: usize) {
+        let mut status = self.lock();
+        status.collections_loaded += 1;
+        status.vectors_recovered += vectors;
+    }
+
+    pub(crate) fn collection_failed(&self, collection_id: CollectionId, error: String) {
+        let mut status = self.lock();
+        status.collections_loaded += 1;
+        status.failures.push(CollectionLoadFailure {
+            collection_id,
+            error,
+        });
+    }
+
+    /// Every collection is loaded; the object store comes next.
+    pub(crate) fn collections_loaded(&self) {
+        let mut status = self.lock();
+        status.stage = BootstrapStage::WaitingForObjectStore;
+        status.wal_recovered = true;
+    }
+
+    /// Records an object store probe, completing the bootstrap unless the
+    /// store is unreachable.
+    pub(crate) fn object_store_probed(&self, probe: ProbeResult) -> bool {
+        let mut status = self.lock();
+        let reachable = probe.status != ProbeStatus::Unhealthy;
+        status.object_store = Some(probe);
+        if reachable {
+            status.stage = BootstrapStage::Ready;
+            status.completed_at = Some(Utc::now());
+        }
+        reachable
+    }
+
+    pub(crate) fn failed(&self, error: String) {
+        let mut status = self.lock();
+        status.stage = BootstrapStage::Failed;
+        status.error = Some(error);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BootstrapStatus> {
+        self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ready_only_once_object_store_is_reachable() {
+        let readiness = Readiness::new();
+        readiness.loading(2);
+        readiness.collection_loaded(10);
+        readiness.collection_failed(CollectionId::new(), "corrupt WAL".to_string());
+        readiness.collections_loaded();
+
+        let status = readiness.status();
+        assert_eq!(status.stage, BootstrapStage::WaitingForObjectStore);
+        assert_eq!(status.collections_loaded, 2);
+        assert_eq!(status.vectors_recovered, 10);
+        assert_eq!(status.failures.len(), 1);
+        assert!(status.wal_recovered);
+
+        let unreachable = ProbeResult {
+            status: ProbeStatus::Unhealthy,
+            latency_ms: Some(1.0),
+            message: Some("connection refused".to_string()),
+        };
+        assert!(!readiness.object_store_probed(unreachable));
+        assert!(!readiness.is_ready());
+
+        assert!(readiness.object_store_probed(ProbeResult::skipped("no bucket")));
+        assert!(readiness.is_ready());
+        assert!(readiness.status().completed_at.is_some());
+    }
+}
@@ -0,0 +1,334 @@
+//! Hot reload of selected config sections (`[reload]`).
+//!
+//! [`ConfigReloader`] re-reads the config file on SIGHUP or when its
+//! modification time changes, validates it, and passes it to the hooks
+//! registered with [`ConfigReloader::on_reload`]. Each hook applies the
+//! settings it owns in place, so nothing restarts and the in-memory indexes
+//! are kept. A file that fails to parse or validate is rejected and the
+//! running settings stay.
+//!
+//! Only the settings in [`RELOADABLE`] are applied; any other change is
+//! logged as needing a restart. The log level is changed through the
+//! [`LogLevelHandle`] returned by [`init_logging`].
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use crate::config::{Config, ConfigError, LoggingConfig};
+
+/// Settings applied without a restart, as dotted paths into [`Config`].
+pub const RELOADABLE: &[&str] = &[
+    "logging.level",
+    "features.rate_limiting_enabled",
+    "embedding.tenant_qps",
+    "tiering.hot_tier_ttl_hours",
+    "tiering.warm_tier_ttl_days",
+    "tiering.hot_promotion_threshold",
+    "tiering.access_window_hours",
+    "tiering.worker_interval_secs",
+];
+
+type Hook = Box<dyn Fn(&Config) + Send + Sync>;
+
+/// Settings that changed in a reload, as dotted paths into [`Config`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadOutcome {
+    /// Changes applied in place
+    pub applied: Vec<String>,
+    /// Changes that take effect only after a restart
+    pub needs_restart: Vec<String>,
+}
+
+/// Re-reads the config file and applies its reloadable settings.
+pub struct ConfigReloader {
+    path: PathBuf,
+    current: Mutex<Config>,
+    hooks: Mutex<Vec<Hook>>,
+}
+
+impl ConfigReloader {
+    /// Creates a reloader for `path`, starting from the running `config`.
+    pub fn new(path: impl Into<PathBuf>, config: Config) -> Self {
+        Self {
+            path: path.into(),
+            current: Mutex::new(config),
+            hooks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Registers a hook applying its settings from a reloaded config.
+    ///
+    /// Hooks run after every reload that changes a reloadable setting, so
+    /// they must be idempotent.
+    pub fn on_reload(&self, hook: impl Fn(&Config) + Send + Sync + 'static) {
+        self.hooks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Box::new(hook));
+    }
+
+    /// The config as of the last successful reload.
+    pub fn current(&self) -> Config {
+        self.current
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Re-reads the file (plus environment overrides) and applies it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, keeping the running settings, if the file cannot be
+    /// read, parsed or validated.
+    pub fn reload(&self) -> Result<ReloadOutcome, ConfigError> {
+        let config = Config::load_from(&self.path)?;
+        config.validate()?;
+
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        let mut changed = Vec::new();
+        diff(
+            "",
+            &serde_json::to_value(&*current).unwrap_or_default(),
+            &serde_json::to_value(&config).unwrap_or_default(),
+            &mut changed,
+        );
+        let (applied, needs_restart): (Vec<String>, Vec<String>) = changed
+            .into_iter()
+            .partition(|path| RELOADABLE.contains(&path.as_str()));
+
+        if !applied.is_empty() {
+            for hook in self.hooks.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+                hook(&config);
+            }
+        }
+        *current = config;
+
+        Ok(ReloadOutcome {
+            applied,
+            needs_restart,
+        })
+    }
+
+    /// Spawns a task reloading on SIGHUP and, unless `watch_interval` is
+    /// zero, whenever the file's modification time changes.
+    pub fn spawn(self: Arc<Self>, watch_interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut hangups = Hangups::new();
+            let mut ticker =
+                (!watch_interval.is_zero()).then(|| tokio::time::interval(watch_interval));
+            let mut last_modified = modified(&self.path);
+
+            loop {
+                let trigger = tokio::select! {
+                    _ = hangups.recv() => "SIGHUP",
+                    _ = tick(&mut ticker) => {
+                        if modified(&self.path) == last_modified {
+                            continue;
+                        }
+                        "file change"
+                    }
+                };
+                last_modified = modified(&self.path);
+
+                match self.reload() {
+                    Ok(outcome) => {
+                        if !outcome.applied.is_empty() {
+                            tracing::info!(
+                                "🔄 Reloaded config ({}): {}",
+                                trigger,
+                                outcome.applied.join(", ")
+                            );
+                        }
+                        if !outcome.needs_restart.is_empty() {
+                            tracing::warn!(
+                                "⚠️  Config changes need a restart to take effect: {}",
+                                outcome.needs_restart.join(", ")
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("⚠️  Rejected config reload ({}): {}", trigger, e);
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Collects the dotted paths of leaves that differ between `old` and `new`.
+fn diff(prefix: &str, old: &Value, new: &Value, changed: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                diff(
+                    &path,
+                    old.get(key).unwrap_or(&Value::Null),
+                    new.get(key).unwrap_or(&Value::Null),
+                    changed,
+                );
+            }
+        }
+        (old, new) if old != new => changed.push(prefix.to_string()),
+        _ => {}
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+async fn tick(ticker: &mut Option<tokio::time::Interval>) {
+    match ticker {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// SIGHUP deliveries; never fires where signals are unavailable.
+struct Hangups {
+    #[cfg(unix)]
+    signal: Option<tokio::signal::unix::Signal>,
+}
+
+impl Hangups {
+    fn new() -> Self {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            let signal = signal(SignalKind::hangup())
+                .map_err(|e| tracing::warn!("⚠️  Cannot listen for SIGHUP: {}", e))
+                .ok();
+            Self { signal }
+        }
+        #[cfg(not(unix))]
+        {
+            Self {}
+        }
+    }
+
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(signal) = &mut self.signal {
+            if signal.recv().await.is_some() {
+                return;
+            }
+        }
+        std::future::pending().await
+    }
+}
+
+/// Changes the level of the subscriber installed by [`init_logging`].
+#[derive(Clone)]
+pub struct LogLevelHandle(reload::Handle<LevelFilter, Registry>);
+
+impl LogLevelHandle {
+    /// Switches to `level` (trace, debug, info, warn or error).
+    pub fn set_level(&self, level: &str) -> Result<(), String> {
+        self.0
+            .modify(|filter| *filter = level_filter(level))
+            .map_err(|e| format!("Failed to change log level: {}", e))
+    }
+}
+
+/// Installs the global fmt subscriber for `config`, with a level that can
+/// be changed later.
+pub fn init_logging(config: &LoggingConfig) -> LogLevelHandle {
+    let (filter, handle) = reload::Layer::new(level_filter(&config.level));
+    let registry = tracing_subscriber::registry().with(filter);
+    if config.format == "json" {
+        registry.with(fmt::layer().json()).init();
+    } else {
+        registry.with(fmt::layer()).init();
+    }
+    LogLevelHandle(handle)
+}
+
+fn level_filter(level: &str) -> LevelFilter {
+    match level {
+        "trace" => LevelFilter::TRACE,
+        "debug" => LevelFilter::DEBUG,
+        "info" => LevelFilter::INFO,
+        "warn" => LevelFilter::WARN,
+        "error" => LevelFilter::ERROR,
+        _ => LevelFilter::INFO,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tempfile::TempDir;
+
+    /// Sections without defaults
+    const REQUIRED: &str = "[database]\n";
+
+    #[test]
+    fn test_reload_applies_reloadable_settings() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            format!("{}[server]\n[embedding]\ntenant_qps = 5\n", REQUIRED),
+        )
+        .unwrap();
+        let reloader = ConfigReloader::new(&path, Config::load_from(&path).unwrap());
+
+        let tenant_qps = Arc::new(AtomicU32::new(5));
+        let hook_qps = Arc::clone(&tenant_qps);
+        reloader.on_reload(move |config| {
+            hook_qps.store(config.embedding.tenant_qps, Ordering::Relaxed)
+        });
+
+        std::fs::write(
+            &path,
+            format!(
+                "{}[server]\nrest_port = 9999\n[embedding]\ntenant_qps = 20\n",
+                REQUIRED
+            ),
+        )
+        .unwrap();
+        let outcome = reloader.reload().unwrap();
+        assert_eq!(outcome.applied, vec!["embedding.tenant_qps"]);
+        assert_eq!(outcome.needs_restart, vec!["server.rest_port"]);
+        assert_eq!(tenant_qps.load(Ordering::Relaxed), 20);
+        assert_eq!(reloader.current().embedding.tenant_qps, 20);
+    }
+
+    #[test]
+    fn test_invalid_reload_keeps_running_config() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        let config =
+            |level: &str| format!("{}[server]\n[logging]\nlevel = \"{}\"\n", REQUIRED, level);
+        std::fs::write(&path, config("info")).unwrap();
+        let reloader = ConfigReloader::new(&path, Config::load_from(&path).unwrap());
+
+        std::fs::write(&path, config("loud")).unwrap();
+        assert!(reloader.reload().is_err());
+
+        std::fs::write(&path, "[logging\n").unwrap();
+        assert!(reloader.reload().is_err());
+
+        assert_eq!(reloader.current().logging.level, "info");
+    }
+}
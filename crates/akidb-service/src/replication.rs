@@ -0,0 +1,746 @@
+//! Node-level logical replication by WAL shipping.
+//!
+//! A primary ([`WalShipper`]) streams every collection's write-ahead log to a
+//! standby: first the entries after the standby's last applied LSN, then new
+//! entries as they are written. The standby ([`ReplicaApplier`]) applies them
+//! through its own write path, so its indexes stay warm and it can take over
+//! without rebuilding them.
+//!
+//! Each collection has its own WAL, so positions are tracked per collection.
+//! When a standby is further behind than the WAL still on disk (files before
+//! a checkpoint are deleted after compaction), the primary resets that
+//! collection on the standby and sends its current documents instead.
+//!
+//! The transport lives in the API layer (`ReplicationService` in akidb-grpc).
+//! [`ReplicationMonitor`] reports lag and health for `GET /admin/replication`
+//! and the `akidb_replication_*` metrics.
+
+use crate::config::{ReplicationConfig, ReplicationRule};
+use crate::metrics::{REPLICATION_HEALTHY, REPLICATION_LAG_SECONDS};
+use crate::{BatchDeleteStatus, CollectionService};
+use akidb_core::{
+    CollectionDescriptor, CollectionId, CoreError, CoreResult, DocumentId, VectorDocument,
+};
+use akidb_storage::LogEntry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Events buffered per standby before the shipper waits for it.
+const SHIP_CHANNEL_CAPACITY: usize = 1024;
+
+/// A write to apply on the standby.
+#[derive(Debug, Clone)]
+pub enum ReplicatedOp {
+    /// Insert or replace a document
+    Upsert(VectorDocument),
+    /// Delete a document
+    Delete(DocumentId),
+}
+
+/// One message of the replication stream.
+#[derive(Debug, Clone)]
+pub enum ReplicationEvent {
+    /// Collection to create on the standby if missing. With `reset`, the
+    /// standby drops its documents and a full copy follows.
+    Collection {
+        descriptor: CollectionDescriptor,
+        reset: bool,
+    },
+    /// Write at `lsn` in the collection's WAL on the primary. Documents of a
+    /// full copy carry LSN 0, as the copy is only complete at the next
+    /// heartbeat.
+    Entry {
+        collection_id: CollectionId,
+        lsn: u64,
+        op: ReplicatedOp,
+    },
+    /// Collection deleted on the primary
+    CollectionDeleted { collection_id: CollectionId },
+    /// Everything up to `positions` has been sent
+    Heartbeat {
+        positions: HashMap<CollectionId, u64>,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+/// The standby went away.
+struct Disconnected;
+
+/// Collections a primary ships, shared by all of its streams.
+///
+/// Replacing the rules takes effect at the next pass of every open stream.
+/// Collections that stop matching keep their data and position on the
+/// standby and resume from there if they match again.
+#[derive(Debug, Default)]
+pub struct ReplicationRules {
+    rules: RwLock<Vec<ReplicationRule>>,
+}
+
+impl ReplicationRules {
+    /// Rules from `[replication]` config; no rules replicate everything.
+    pub fn new(rules: Vec<ReplicationRule>) -> Self {
+        Self {
+            rules: RwLock::new(rules),
+        }
+    }
+
+    /// Current rules.
+    pub fn get(&self) -> Vec<ReplicationRule> {
+        self.rules.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replaces the rules after validating every pattern.
+    pub fn set(&self, rules: Vec<ReplicationRule>) -> CoreResult<()> {
+        for rule in &rules {
+            rule.validate().map_err(CoreError::ValidationError)?;
+        }
+        *self.rules.write().unwrap_or_else(|e| e.into_inner()) = rules;
+        Ok(())
+    }
+
+    /// Priority to ship the collection `name` with, or `None` if it is not
+    /// replicated.
+    pub fn priority(&self, name: &str) -> Option<i32> {
+        let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+        if rules.is_empty() {
+            return Some(0);
+        }
+        // First of the highest-priority matches, so ties go to config order
+        let rule = rules
+            .iter()
+            .filter(|rule| rule.matches(name))
+            .reduce(|best, rule| {
+                if rule.priority > best.priority {
+                    rule
+                } else {
+                    best
+                }
+            })?;
+        rule.enabled.then_some(rule.priority)
+    }
+}
+
+/// Streams collection WALs to standbys (primary side).
+pub struct WalShipper {
+    service: Arc<CollectionService>,
+    poll_interval: Duration,
+    rules: Arc<ReplicationRules>,
+}
+
+impl WalShipper {
+    /// Ships from `service`, scanning for new entries after every write and
+    /// at least every `poll_interval` (which is also the heartbeat interval).
+    pub fn new(service: Arc<CollectionService>, poll_interval: Duration) -> Self {
+        Self {
+            service,
+            poll_interval,
+            rules: Arc::new(ReplicationRules::default()),
+        }
+    }
+
+    /// Ships only the collections selected by `rules`.
+    pub fn with_rules(mut self, rules: Arc<ReplicationRules>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Rules shared by every stream of this shipper.
+    pub fn rules(&self) -> &Arc<ReplicationRules> {
+        &self.rules
+    }
+
+    /// Starts streaming to a standby that has applied everything up to
+    /// `positions`. Collections missing from `positions` are sent in full.
+    ///
+    /// Shipping stops when the receiver is dropped.
+    pub fn stream(
+        &self,
+        positions: HashMap<CollectionId, u64>,
+    ) -> mpsc::Receiver<ReplicationEvent> {
+        let (tx, rx) = mpsc::channel(SHIP_CHANNEL_CAPACITY);
+        let service = self.service.clone();
+        let rules = self.rules.clone();
+        let poll_interval = self.poll_interval;
+
+        tokio::spawn(async move {
+            let mut changes = service.events().subscribe();
+            let mut shipped = positions;
+            loop {
+                if ship_pass(&service, &rules, &mut shipped, &tx)
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+                // Lagging behind the event bus is fine: the next pass reads
+                // the WAL itself
+                tokio::select! {
+                    _ = changes.recv() => {}
+                    _ = tokio::time::sleep(poll_interval) => {}
+                    _ = tx.closed() => break,
+                }
+            }
+        });
+
+        rx
+    }
+}
+
+async fn send(
+    tx: &mpsc::Sender<ReplicationEvent>,
+    event: ReplicationEvent,
+) -> Result<(), Disconnected> {
+    tx.send(event).await.map_err(|_| Disconnected)
+}
+
+/// Sends everything written since `shipped`, then a heartbeat.
+async fn ship_pass(
+    service: &CollectionService,
+    rules: &ReplicationRules,
+    shipped: &mut HashMap<CollectionId, u64>,
+    tx: &mpsc::Sender<ReplicationEvent>,
+) -> Result<(), Disconnected> {
+    let collections = match service.list_collections().await {
+        Ok(collections) => collections,
+        Err(e) => {
+            tracing::warn!("Replication: failed to list collections: {}", e);
+            return Ok(());
+        }
+    };
+
+    let live: HashSet<CollectionId> = collections.iter().map(|c| c.collection_id).collect();
+    let deleted: Vec<CollectionId> = shipped
+        .keys()
+        .filter(|id| !live.contains(id))
+        .copied()
+        .collect();
+    for collection_id in deleted {
+        send(tx, ReplicationEvent::CollectionDeleted { collection_id }).await?;
+        shipped.remove(&collection_id);
+    }
+
+    // Deletions above consider every collection, so excluding one never
+    // deletes it on the standby
+    let mut selected: Vec<(i32, CollectionDescriptor)> = collections
+        .into_iter()
+        .filter_map(|c| rules.priority(&c.name).map(|priority| (priority, c)))
+        .collect();
+    selected.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+
+    for (_, descriptor) in selected {
+        let collection_id = descriptor.collection_id;
+        if let Err(e) = ship_collection(service, descriptor, shipped, tx).await? {
+            // Usually a collection deleted mid-pass; the next pass sorts it out
+            tracing::warn!(
+                "Replication: failed to ship collection {}: {}",
+                collection_id,
+                e
+            );
+        }
+    }
+
+    send(
+        tx,
+        ReplicationEvent::Heartbeat {
+            positions: shipped.clone(),
+            timestamp: Utc::now(),
+        },
+    )
+    .await
+}
+
+async fn ship_collection(
+    service: &CollectionService,
+    descriptor: CollectionDescriptor,
+    shipped: &mut HashMap<CollectionId, u64>,
+    tx: &mpsc::Sender<ReplicationEvent>,
+) -> Result<CoreResult<()>, Disconnected> {
+    let collection_id = descriptor.collection_id;
+    let current = match service.wal_position(collection_id).await {
+        Ok(Some(current)) => current,
+        Ok(None) => return Ok(Ok(())),
+        Err(e) => return Ok(Err(e)),
+    };
+
+    let position = shipped.get(&collection_id).copied();
+    if position == Some(current) {
+        return Ok(Ok(()));
+    }
+    if position.is_none() {
+        send(
+            tx,
+            ReplicationEvent::Collection {
+                descriptor: descriptor.clone(),
+                reset: false,
+            },
+        )
+        .await?;
+        shipped.insert(collection_id, 0);
+    }
+
+    let from = position.unwrap_or(0);
+    let entries = if from < current {
+        match service.read_wal(collection_id, from + 1).await {
+            Ok(entries) => entries,
+            Err(e) => return Ok(Err(e)),
+        }
+    } else {
+        Vec::new()
+    };
+
+    // Entries the standby needs are gone, or the standby is ahead of this
+    // WAL (e.g. it followed another primary): start the collection over
+    let gap = from > current
+        || entries
+            .first()
+            .map_or(from < current, |(lsn, _)| *lsn > from + 1);
+    if gap {
+        return resync_collection(service, descriptor, shipped, tx).await;
+    }
+
+    for (lsn, entry) in entries {
+        if let Some(op) = replicated_op(entry) {
+            send(
+                tx,
+                ReplicationEvent::Entry {
+                    collection_id,
+                    lsn,
+                    op,
+                },
+            )
+            .await?;
+        }
+        shipped.insert(collection_id, lsn);
+    }
+
+    Ok(Ok(()))
+}
+
+/// Resets the collection on the standby and sends all of its documents.
+async fn resync_collection(
+    service: &CollectionService,
+    descriptor: CollectionDescriptor,
+    shipped: &mut HashMap<CollectionId, u64>,
+    tx: &mpsc::Sender<ReplicationEvent>,
+) -> Result<CoreResult<()>, Disconnected> {
+    let collection_id = descriptor.collection_id;
+    let (lsn, docs) = match service.replication_snapshot(collection_id).await {
+        Ok(snapshot) => snapshot,
+        Err(e) => return Ok(Err(e)),
+    };
+    tracing::info!(
+        "Replication: sending full copy of collection {} ({} documents, LSN {})",
+        collection_id,
+        docs.len(),
+        lsn
+    );
+
+    send(
+        tx,
+        ReplicationEvent::Collection {
+            descriptor,
+            reset: true,
+        },
+    )
+    .await?;
+    for doc in docs {
+        send(
+            tx,
+            ReplicationEvent::Entry {
+                collection_id,
+                lsn: 0,
+                op: ReplicatedOp::Upsert(doc),
+            },
+        )
+        .await?;
+    }
+    shipped.insert(collection_id, lsn);
+
+    Ok(Ok(()))
+}
+
+/// Document writes are replicated; collection and checkpoint records are
+/// local to each node's WAL.
+fn replicated_op(entry: LogEntry) -> Option<ReplicatedOp> {
+    match entry {
+        LogEntry::Upsert {
+            doc_id,
+            vector,
+            sparse,
+            external_id,
+            metadata,
+            timestamp,
+            ..
+        } => Some(ReplicatedOp::Upsert(VectorDocument {
+            doc_id,
+            external_id,
+            vector,
+            sparse,
+            metadata,
+            inserted_at: timestamp,
+        })),
+        LogEntry::Delete { doc_id, .. } => Some(ReplicatedOp::Delete(doc_id)),
+        _ => None,
+    }
+}
+
+/// Replication progress of a standby.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplicaStatus {
+    /// Whether the stream from the primary is currently open
+    pub connected: bool,
+    /// Primary clock time of the last applied heartbeat: everything the
+    /// primary wrote before it has been applied
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    /// Entries applied since startup
+    pub entries_applied: u64,
+    /// Last applied primary LSN per collection
+    pub positions: HashMap<CollectionId, u64>,
+}
+
+/// Progress persisted across standby restarts (and read by
+/// [`ReplicationMonitor`]).
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ReplicaState {
+    positions: HashMap<CollectionId, u64>,
+    #[serde(default)]
+    last_heartbeat: Option<DateTime<Utc>>,
+}
+
+impl ReplicaState {
+    /// State stored at `path`, or `None` if there is none yet.
+    fn load(path: &Path) -> CoreResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let bytes = std::fs::read(path)
+            .map_err(|e| CoreError::internal(format!("failed to read {:?}: {}", path, e)))?;
+        serde_json::from_slice(&bytes).map(Some).map_err(|e| {
+            CoreError::internal(format!("invalid replication state {:?}: {}", path, e))
+        })
+    }
+}
+
+/// Applies a replication stream to the local collections (standby side).
+pub struct ReplicaApplier {
+    service: Arc<CollectionService>,
+    state_path: Option<PathBuf>,
+    status: Mutex<ReplicaStatus>,
+}
+
+impl ReplicaApplier {
+    /// Applies to `service`, keeping positions in memory only.
+    pub fn new(service: Arc<CollectionService>) -> Self {
+        Self {
+            service,
+            state_path: None,
+            status: Mutex::new(ReplicaStatus {
+                connected: false,
+                last_heartbeat: None,
+                entries_applied: 0,
+                positions: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Persists positions to `path` at every heartbeat, resuming from the
+    /// positions already stored there.
+    pub fn with_state_path(mut self, path: impl Into<PathBuf>) -> CoreResult<Self> {
+        let path = path.into();
+        if let Some(state) = ReplicaState::load(&path)? {
+            let mut status = self.lock();
+            status.positions = state.positions;
+            status.last_heartbeat = state.last_heartbeat;
+        }
+        self.state_path = Some(path);
+        Ok(self)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ReplicaStatus> {
+        self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Last applied primary LSN per collection, to resume streaming from.
+    pub fn positions(&self) -> HashMap<CollectionId, u64> {
+        self.lock().positions.clone()
+    }
+
+    /// Records whether the stream from the primary is open.
+    pub fn set_connected(&self, connected: bool) {
+        self.lock().connected = connected;
+    }
+
+    /// Current replication progress.
+    pub fn status(&self) -> ReplicaStatus {
+        self.lock().clone()
+    }
+
+    /// Applies one event. On error the position is not advanced, so the
+    /// event is sent again after reconnecting.
+    pub async fn apply(&self, event: ReplicationEvent) -> CoreResult<()> {
+        match event {
+            ReplicationEvent::Collection { descriptor, reset } => {
+                self.apply_collection(descriptor, reset).await
+            }
+            ReplicationEvent::Entry {
+                collection_id,
+                lsn,
+                op,
+            } => {
+                self.apply_op(collection_id, op).await?;
+                let mut status = self.lock();
+                status.entries_applied += 1;
+                if lsn > 0 {
+                    status.positions.insert(collection_id, lsn);
+                }
+                Ok(())
+            }
+            ReplicationEvent::CollectionDeleted { collection_id } => {
+                match self.service.delete_collection(collection_id).await {
+                    Ok(()) | Err(CoreError::NotFound { .. }) => {}
+                    Err(e) => return Err(e),
+                }
+                self.lock().positions.remove(&collection_id);
+                Ok(())
+            }
+            ReplicationEvent::Heartbeat {
+                positions,
+                timestamp,
+            } => {
+                let state = {
+                    let mut status = self.lock();
+                    for (collection_id, lsn) in positions {
+                        if let Some(position) = status.positions.get_mut(&collection_id) {
+                            *position = lsn;
+                        }
+                    }
+                    status.last_heartbeat = Some(timestamp);
+                    ReplicaState {
+                        positions: status.positions.clone(),
+                        last_heartbeat: status.last_heartbeat,
+                    }
+                };
+                self.persist(&state)
+            }
+        }
+    }
+
+    async fn apply_collection(
+        &self,
+        descriptor: CollectionDescriptor,
+        reset: bool,
+    ) -> CoreResult<()> {
+        let collection_id = descriptor.collection_id;
+        if self.service.get_collection(collection_id).await.is_err() {
+            self.service.create_replica_collection(descriptor).await?;
+        } else if reset {
+            let (_, docs) = self.service.replication_snapshot(collection_id).await?;
+            let doc_ids = docs.into_iter().map(|doc| doc.doc_id).collect();
+            self.service.delete_batch(collection_id, doc_ids).await?;
+        }
+
+        let mut status = self.lock();
+        if reset {
+            status.positions.insert(collection_id, 0);
+        } else {
+            status.positions.entry(collection_id).or_insert(0);
+        }
+        Ok(())
+    }
+
+    async fn apply_op(&self, collection_id: CollectionId, op: ReplicatedOp) -> CoreResult<()> {
+        match op {
+            ReplicatedOp::Upsert(doc) => {
+                let mut results = self.service.upsert_batch(collection_id, vec![doc]).await?;
+                results.pop().transpose().map(|_| ())
+            }
+            ReplicatedOp::Delete(doc_id) => {
+                let statuses = self
+                    .service
+                    .delete_batch(collection_id, vec![doc_id])
+                    .await?;
+                match statuses.into_iter().next() {
+                    Some((_, BatchDeleteStatus::Failed(message))) => {
+                        Err(CoreError::internal(message))
+                    }
+                    // Already gone, e.g. an entry sent again after a full copy
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+
+    fn persist(&self, state: &ReplicaState) -> CoreResult<()> {
+        let Some(path) = &self.state_path else {
+            return Ok(());
+        };
+        let bytes = serde_json::to_vec(state).map_err(|e| {
+            CoreError::internal(format!("failed to encode replication state: {}", e))
+        })?;
+        // Write-then-rename so a crash never leaves a truncated file
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)
+            .and_then(|()| std::fs::rename(&tmp, path))
+            .map_err(|e| CoreError::internal(format!("failed to write {:?}: {}", path, e)))
+    }
+}
+
+/// WAL position of one collection in a [`ReplicationReport`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionReplication {
+    pub collection_id: CollectionId,
+    /// Latest LSN on a primary, last applied primary LSN on a standby
+    pub lsn: u64,
+}
+
+/// Replication health of this node.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplicationReport {
+    /// "none", "primary" or "standby"
+    pub role: String,
+    /// "healthy", "unhealthy", or "disabled" when replication is off
+    pub status: String,
+    /// Why the node is unhealthy
+    pub message: Option<String>,
+    /// Primary followed by a standby
+    pub primary_url: Option<String>,
+    /// Primary clock time of the last heartbeat a standby applied
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    /// Seconds since `last_heartbeat`
+    pub lag_seconds: Option<f64>,
+    /// Maximum lag before a standby is unhealthy
+    pub max_lag_seconds: u64,
+    pub collections: Vec<CollectionReplication>,
+}
+
+impl ReplicationReport {
+    pub fn is_healthy(&self) -> bool {
+        self.status != "unhealthy"
+    }
+}
+
+/// Reports replication lag and health from the node's `[replication]` config.
+///
+/// A standby's progress is read from its state file rather than from a
+/// [`ReplicaApplier`], so any process on the node (e.g. the REST server next
+/// to a gRPC standby) can report it.
+pub struct ReplicationMonitor {
+    service: Arc<CollectionService>,
+    config: ReplicationConfig,
+}
+
+impl ReplicationMonitor {
+    pub fn new(service: Arc<CollectionService>, config: ReplicationConfig) -> Self {
+        Self { service, config }
+    }
+
+    /// Current replication health; also updates the `akidb_replication_*`
+    /// gauges.
+    pub async fn report(&self) -> ReplicationReport {
+        let mut report = ReplicationReport {
+            role: self.config.role.clone(),
+            status: "healthy".to_string(),
+            message: None,
+            primary_url: self.config.primary_url.clone(),
+            last_heartbeat: None,
+            lag_seconds: None,
+            max_lag_seconds: self.config.max_lag_seconds,
+            collections: Vec::new(),
+        };
+
+        match self.config.role.as_str() {
+            "primary" => self.report_primary(&mut report).await,
+            "standby" => self.report_standby(&mut report),
+            _ => report.status = "disabled".to_string(),
+        }
+        report
+            .collections
+            .sort_by_key(|c| c.collection_id.to_string());
+
+        if report.status != "disabled" {
+            REPLICATION_HEALTHY
+                .with_label_values(&[&report.role])
+                .set(if report.is_healthy() { 1.0 } else { 0.0 });
+            if let Some(lag) = report.lag_seconds {
+                REPLICATION_LAG_SECONDS
+                    .with_label_values(&[&report.role])
+                    .set(lag);
+            }
+        }
+        report
+    }
+
+    /// Refreshes the gauges every `interval`, so they stay current between
+    /// requests.
+    pub fn spawn_refresh(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.report().await;
+            }
+        })
+    }
+
+    async fn report_primary(&self, report: &mut ReplicationReport) {
+        let collections = match self.service.list_collections().await {
+            Ok(collections) => collections,
+            Err(e) => {
+                report.status = "unhealthy".to_string();
+                report.message = Some(format!("Failed to list collections: {}", e));
+                return;
+            }
+        };
+        for collection in collections {
+            if let Ok(Some(lsn)) = self.service.wal_position(collection.collection_id).await {
+                report.collections.push(CollectionReplication {
+                    collection_id: collection.collection_id,
+                    lsn,
+                });
+            }
+        }
+    }
+
+    fn report_standby(&self, report: &mut ReplicationReport) {
+        let state = match ReplicaState::load(&self.config.state_path) {
+            Ok(Some(state)) => state,
+            Ok(None) => {
+                report.status = "unhealthy".to_string();
+                report.message = Some("No heartbeat received from the primary yet".to_string());
+                return;
+            }
+            Err(e) => {
+                report.status = "unhealthy".to_string();
+                report.message = Some(e.to_string());
+                return;
+            }
+        };
+
+        report.collections = state
+            .positions
+            .into_iter()
+            .map(|(collection_id, lsn)| CollectionReplication { collection_id, lsn })
+            .collect();
+        report.last_heartbeat = state.last_heartbeat;
+
+        let Some(last_heartbeat) = state.last_heartbeat else {
+            report.status = "unhealthy".to_string();
+            report.message = Some("No heartbeat received from the primary yet".to_string());
+            return;
+        };
+        let lag = (Utc::now() - last_heartbeat).num_milliseconds().max(0) as f64 / 1000.0;
+        report.lag_seconds = Some(lag);
+        if lag > self.config.max_lag_seconds as f64 {
+            report.status = "unhealthy".to_string();
+            report.message = Some(format!(
+                "Standby is {:.0}s behind the primary (max {}s)",
+                lag, self.config.max_lag_seconds
+            ));
+        }
+    }
+}
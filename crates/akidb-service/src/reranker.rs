@@ -0,0 +1,290 @@
+//! Cross-encoder reranking.
+//!
+//! A reranker scores each (query, document) pair jointly, which is more
+//! accurate than vector similarity but too slow to run over a whole
+//! collection. Search therefore fetches a short list of candidates from the
+//! index and reorders them here; the same scorer backs `POST /api/v1/rerank`.
+
+use crate::config::RerankConfig;
+use akidb_core::{CoreError, CoreResult, SearchResult};
+use akidb_embedding::{
+    EmbeddingError, EmbeddingResult, MockRerankProvider, RerankProvider, RerankRequest, Usage,
+};
+use std::sync::Arc;
+
+/// A document's position in the request and its relevance score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RerankHit {
+    /// Index into the documents passed to [`Reranker::rerank`]
+    pub index: usize,
+    /// Relevance score; higher is more relevant
+    pub score: f32,
+}
+
+/// Documents ordered by relevance.
+#[derive(Debug, Clone)]
+pub struct Reranked {
+    /// Model that scored the documents
+    pub model: String,
+    /// Every document, most relevant first
+    pub hits: Vec<RerankHit>,
+    /// Usage statistics
+    pub usage: Usage,
+}
+
+/// Scores and reorders documents against a query.
+pub struct Reranker {
+    provider: Arc<dyn RerankProvider>,
+    model: String,
+    max_documents: usize,
+}
+
+impl Reranker {
+    /// Wraps `provider`, accepting at most `max_documents` per request.
+    pub fn new(provider: Arc<dyn RerankProvider>, model: &str, max_documents: usize) -> Self {
+        Self {
+            provider,
+            model: model.to_string(),
+            max_documents,
+        }
+    }
+
+    /// Builds the reranker described by the `[rerank]` section.
+    ///
+    /// Returns `None` when reranking is disabled (`provider = "none"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provider is unknown or fails to initialize.
+    pub fn from_config(config: &RerankConfig) -> Result<Option<Self>, String> {
+        let provider: Arc<dyn RerankProvider> = match config.provider.as_str() {
+            "none" => return Ok(None),
+            "mock" => Arc::new(MockRerankProvider::new()),
+            "http" => {
+                let http = akidb_embedding::HttpRerankConfig {
+                    model: config.model.clone(),
+                    ..config
+                        .http
+                        .clone()
+                        .ok_or("rerank.http must be set for the http provider")?
+                };
+                Arc::new(
+                    akidb_embedding::HttpRerankProvider::new(http)
+                        .map_err(|e| format!("Failed to initialize HTTP reranker: {}", e))?,
+                )
+            }
+            "onnx" => Self::onnx_provider(config)?,
+            other => return Err(format!("Unknown rerank provider: {}", other)),
+        };
+
+        tracing::info!(
+            provider = %config.provider,
+            model = %config.model,
+            "Initialized reranker"
+        );
+        Ok(Some(Self::new(
+            provider,
+            &config.model,
+            config.max_documents,
+        )))
+    }
+
+    #[cfg(feature = "onnx")]
+    fn onnx_provider(config: &RerankConfig) -> Result<Arc<dyn RerankProvider>, String> {
+        let model_path = config
+            .model_path
+            .clone()
+            .ok_or("rerank.model_path must be set for the onnx provider")?;
+        let provider =
+            akidb_embedding::OnnxRerankProvider::with_config(akidb_embedding::OnnxRerankConfig {
+                tokenizer_path: model_path.with_file_name("tokenizer.json"),
+                model_path,
+                model_name: config.model.clone(),
+                ..Default::default()
+            })
+            .map_err(|e| format!("Failed to initialize ONNX reranker: {}", e))?;
+        Ok(Arc::new(provider))
+    }
+
+    #[cfg(not(feature = "onnx"))]
+    fn onnx_provider(_config: &RerankConfig) -> Result<Arc<dyn RerankProvider>, String> {
+        Err("The onnx rerank provider requires building with the `onnx` feature".to_string())
+    }
+
+    /// Model name used for scoring.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Most documents accepted per request.
+    pub fn max_documents(&self) -> usize {
+        self.max_documents
+    }
+
+    /// Scores `documents` against `query` and orders them best first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if there are more than `max_documents`
+    /// documents, or the provider's error if scoring fails.
+    pub async fn rerank(&self, query: &str, documents: Vec<String>) -> EmbeddingResult<Reranked> {
+        if documents.len() > self.max_documents {
+            return Err(EmbeddingError::InvalidInput(format!(
+                "Cannot rerank {} documents (maximum {})",
+                documents.len(),
+                self.max_documents
+            )));
+        }
+
+        let count = documents.len();
+        let response = self
+            .provider
+            .rerank(RerankRequest {
+                model: self.model.clone(),
+                query: query.to_string(),
+                documents,
+            })
+            .await?;
+        if response.scores.len() != count {
+            return Err(EmbeddingError::Internal(format!(
+                "Reranker returned {} scores for {} documents",
+                response.scores.len(),
+                count
+            )));
+        }
+
+        let mut hits: Vec<RerankHit> = response
+            .scores
+            .into_iter()
+            .enumerate()
+            .map(|(index, score)| RerankHit { index, score })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        Ok(Reranked {
+            model: response.model,
+            hits,
+            usage: response.usage,
+        })
+    }
+
+    /// Reorders search results by relevance of their `text_field` metadata
+    /// to `query`, replacing each score with the reranker's.
+    ///
+    /// Results without a string at `text_field` are scored as empty text.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error for invalid input and an internal error if
+    /// scoring fails.
+    pub async fn rerank_results(
+        &self,
+        query: &str,
+        text_field: &str,
+        results: Vec<SearchResult>,
+    ) -> CoreResult<Vec<SearchResult>> {
+        let documents = results
+            .iter()
+            .map(|result| {
+                result
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.get(text_field))
+                    .and_then(|text| text.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect();
+
+        let reranked = self.rerank(query, documents).await.map_err(|e| match e {
+            EmbeddingError::InvalidInput(message) => CoreError::ValidationError(message),
+            other => CoreError::internal(format!("Reranking failed: {}", other)),
+        })?;
+
+        let mut results: Vec<Option<SearchResult>> = results.into_iter().map(Some).collect();
+        Ok(reranked
+            .hits
+            .into_iter()
+            .filter_map(|hit| {
+                let mut result = results[hit.index].take()?;
+                result.score = hit.score;
+                Some(result)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::DocumentId;
+
+    fn result(text: &str) -> SearchResult {
+        SearchResult {
+            doc_id: DocumentId::new(),
+            external_id: Some(text.to_string()),
+            score: 0.5,
+            metadata: Some(serde_json::json!({ "text": text })),
+            vector: None,
+            sparse: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rerank_orders_best_first() {
+        let reranker = Reranker::new(Arc::new(MockRerankProvider::new()), "mock-rerank", 10);
+        let documents = vec![
+            "cats and dogs".to_string(),
+            "vector database search".to_string(),
+            "a database".to_string(),
+        ];
+
+        let reranked = reranker.rerank("vector database", documents).await.unwrap();
+        let order: Vec<usize> = reranked.hits.iter().map(|hit| hit.index).collect();
+        assert_eq!(order, vec![1, 2, 0]);
+        assert_eq!(reranked.hits[0].score, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_rerank_enforces_max_documents() {
+        let reranker = Reranker::new(Arc::new(MockRerankProvider::new()), "mock-rerank", 1);
+        let result = reranker
+            .rerank("query", vec!["a".to_string(), "b".to_string()])
+            .await;
+        assert!(matches!(result, Err(EmbeddingError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rerank_results_replaces_scores() {
+        let reranker = Reranker::new(Arc::new(MockRerankProvider::new()), "mock-rerank", 10);
+        let mut untexted = result("unused");
+        untexted.metadata = None;
+
+        let results = reranker
+            .rerank_results(
+                "rust",
+                "text",
+                vec![untexted, result("python"), result("rust")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].external_id.as_deref(), Some("rust"));
+        assert_eq!(results[0].score, 1.0);
+        assert_eq!(results[2].score, 0.0);
+    }
+
+    #[test]
+    fn test_from_config_disabled_by_default() {
+        assert!(Reranker::from_config(&RerankConfig::default())
+            .unwrap()
+            .is_none());
+
+        let config = RerankConfig {
+            provider: "mock".to_string(),
+            ..RerankConfig::default()
+        };
+        let reranker = Reranker::from_config(&config).unwrap().unwrap();
+        assert_eq!(reranker.max_documents(), 100);
+    }
+}
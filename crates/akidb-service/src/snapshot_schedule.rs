@@ -0,0 +1,213 @@
+//! Scheduled collection snapshots (`[snapshots]`).
+//!
+//! Without a schedule, snapshots are only taken when compaction truncates the
+//! WAL. [`SnapshotScheduler`] checks every collection against the configured
+//! [`SnapshotSchedule`]s and, when one is due, runs a `snapshot` job through
+//! the [`JobManager`](crate::JobManager): the job snapshots the collection
+//! (keeping its WAL) and then deletes all but the newest `keep_last`
+//! snapshots through the Snapshotter.
+//!
+//! A schedule is due `interval_seconds` after the last scheduled snapshot, or
+//! once `every_ops` writes were made since then, counted by WAL position.
+//! After a restart, time is counted from the newest existing snapshot and
+//! writes from startup.
+//!
+//! With `[leader_election]` the servers run the checks through
+//! [`LeaderElection::spawn_singleton`](crate::LeaderElection::spawn_singleton),
+//! so only the leader takes scheduled snapshots. The snapshot and the pruning
+//! each hold the collection's "backup" lock in the object store; a job that
+//! finds it held elsewhere fails and the schedule is retried at the next
+//! check.
+
+use crate::config::SnapshotSchedule;
+use crate::CollectionService;
+use akidb_core::{CollectionId, CoreResult, JobDescriptor, JobId};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// What a collection's schedule is measured against.
+#[derive(Debug, Clone, Copy)]
+struct ScheduleState {
+    /// WAL position at the last scheduled snapshot (or when first seen)
+    lsn: u64,
+    /// When the newest snapshot was taken, if any
+    snapshot_at: Option<DateTime<Utc>>,
+}
+
+/// Runs snapshot jobs for the collections whose schedule is due.
+pub struct SnapshotScheduler {
+    service: Arc<CollectionService>,
+    schedules: Vec<SnapshotSchedule>,
+    state: Arc<Mutex<HashMap<CollectionId, ScheduleState>>>,
+    /// Collections with a snapshot job in progress
+    running: Arc<Mutex<HashSet<CollectionId>>>,
+}
+
+impl SnapshotScheduler {
+    /// Schedules snapshots of `service`'s collections; the first of
+    /// `schedules` matching a collection applies.
+    pub fn new(service: Arc<CollectionService>, schedules: Vec<SnapshotSchedule>) -> Self {
+        Self {
+            service,
+            schedules,
+            state: Arc::new(Mutex::new(HashMap::new())),
+            running: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Schedule applying to the collection `name`, if any.
+    pub fn schedule_for(&self, name: &str) -> Option<&SnapshotSchedule> {
+        self.schedules
+            .iter()
+            .find(|schedule| schedule.matches(name))
+    }
+
+    /// Starts a snapshot job for every collection whose schedule is due,
+    /// returning the job IDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the collections cannot be listed. Collections that
+    /// fail to be checked are logged and retried at the next check.
+    pub async fn run_due(&self) -> CoreResult<Vec<JobId>> {
+        let mut jobs = Vec::new();
+        for descriptor in self.service.list_collections().await? {
+            let collection_id = descriptor.collection_id;
+            let Some(schedule) = self.schedule_for(&descriptor.name).cloned() else {
+                continue;
+            };
+            if lock(&self.running).contains(&collection_id) {
+                continue;
+            }
+            match self.due(collection_id, &schedule).await {
+                Ok(Some(lsn)) => jobs.push(self.start(collection_id, schedule, lsn).await?),
+                Ok(None) => {}
+                Err(e) => tracing::warn!(
+                    "Snapshot schedule: failed to check collection {}: {}",
+                    descriptor.name,
+                    e
+                ),
+            }
+        }
+        Ok(jobs)
+    }
+
+    /// The current WAL position if `schedule` is due for the collection.
+    async fn due(
+        &self,
+        collection_id: CollectionId,
+        schedule: &SnapshotSchedule,
+    ) -> CoreResult<Option<u64>> {
+        // Collections without a WAL (legacy persistence) have no snapshots
+        let Some(lsn) = self.service.wal_position(collection_id).await? else {
+            return Ok(None);
+        };
+        if self.service.get_count(collection_id).await? == 0 {
+            return Ok(None);
+        }
+
+        let known = lock(&self.state).get(&collection_id).copied();
+        let state = match known {
+            Some(state) => state,
+            None => {
+                let newest = self.service.list_snapshots(collection_id).await?;
+                let state = ScheduleState {
+                    lsn,
+                    snapshot_at: newest.first().map(|metadata| metadata.created_at),
+                };
+                lock(&self.state).insert(collection_id, state);
+                state
+            }
+        };
+
+        let ops_due = schedule
+            .every_ops
+            .is_some_and(|every_ops| lsn.saturating_sub(state.lsn) >= every_ops);
+        let interval_due = schedule.interval_seconds.is_some_and(|interval| {
+            state.snapshot_at.map_or(true, |at| {
+                (Utc::now() - at).num_seconds() >= i64::try_from(interval).unwrap_or(i64::MAX)
+            })
+        });
+        Ok((ops_due || interval_due).then_some(lsn))
+    }
+
+    /// Runs the snapshot and pruning of one collection as a job.
+    async fn start(
+        &self,
+        collection_id: CollectionId,
+        schedule: SnapshotSchedule,
+        lsn: u64,
+    ) -> CoreResult<JobId> {
+        lock(&self.running).insert(collection_id);
+        let service = Arc::clone(&self.service);
+        let state = Arc::clone(&self.state);
+        let running = Arc::clone(&self.running);
+
+        let job = JobDescriptor::new("snapshot", Some(collection_id)).with_total(1);
+        let spawned = self
+            .service
+            .jobs()
+            .spawn(job, move |handle| async move {
+                let outcome = async {
+                    let metadata = service.snapshot_collection(collection_id).await?;
+                    lock(&state).insert(
+                        collection_id,
+                        ScheduleState {
+                            lsn,
+                            snapshot_at: Some(metadata.created_at),
+                        },
+                    );
+                    handle.advance(1).await;
+
+                    let pruned = service
+                        .prune_snapshots(collection_id, schedule.keep_last)
+                        .await?;
+                    tracing::info!(
+                        "📸 Scheduled snapshot {} of collection {} ({} vectors, {} pruned)",
+                        metadata.snapshot_id,
+                        collection_id,
+                        metadata.vector_count,
+                        pruned.len()
+                    );
+                    Ok(json!({
+                        "snapshot_id": metadata.snapshot_id,
+                        "vector_count": metadata.vector_count,
+                        "size_bytes": metadata.size_bytes,
+                        "pruned": pruned,
+                    }))
+                }
+                .await;
+                lock(&running).remove(&collection_id);
+                outcome
+            })
+            .await;
+
+        if spawned.is_err() {
+            lock(&self.running).remove(&collection_id);
+        }
+        spawned
+    }
+
+    /// Spawns a task running due snapshots every `interval`.
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run_due().await {
+                    tracing::warn!("Snapshot schedule check failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
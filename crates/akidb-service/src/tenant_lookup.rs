@@ -0,0 +1,55 @@
+//! Tenant ownership of databases, for attributing work done on collections.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use akidb_core::{DatabaseId, DatabaseRepository, TenantId};
+
+/// Cached database → tenant lookup.
+///
+/// Databases never change tenant, so lookups are cached for the life of the
+/// process.
+pub struct TenantLookup {
+    databases: Arc<dyn DatabaseRepository>,
+    tenants: Mutex<HashMap<DatabaseId, TenantId>>,
+}
+
+impl TenantLookup {
+    /// Creates a lookup over `databases`.
+    pub fn new(databases: Arc<dyn DatabaseRepository>) -> Self {
+        Self {
+            databases,
+            tenants: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Tenant owning `database_id`, or `None` (logged) if it cannot be found.
+    pub async fn tenant_of(&self, database_id: DatabaseId) -> Option<TenantId> {
+        if let Some(tenant_id) = self
+            .tenants
+            .lock()
+            .expect("tenant lookup lock poisoned")
+            .get(&database_id)
+        {
+            return Some(*tenant_id);
+        }
+
+        match self.databases.get(database_id).await {
+            Ok(Some(database)) => {
+                self.tenants
+                    .lock()
+                    .expect("tenant lookup lock poisoned")
+                    .insert(database_id, database.tenant_id);
+                Some(database.tenant_id)
+            }
+            Ok(None) => {
+                tracing::warn!("Database {} has no tenant", database_id);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Failed to look up tenant of database {}: {}", database_id, e);
+                None
+            }
+        }
+    }
+}
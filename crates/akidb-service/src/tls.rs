@@ -0,0 +1,343 @@
+//! TLS and mutual TLS (mTLS) support shared by the gRPC and REST servers.
+//!
+//! Certificates are loaded from PEM files configured in `[server.tls]`.
+//! When a client CA bundle is configured, clients must present a certificate
+//! signed by that CA (mTLS).
+//!
+//! The files are polled for changes and the rustls configuration is swapped
+//! in place, so rotated certificates take effect for new connections without
+//! restarting the server.
+
+use crate::config::TlsConfig;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::{self, Certificate, PrivateKey, RootCertStore, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+/// Maximum time a client may take to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// TLS error types
+#[derive(Debug, thiserror::Error)]
+pub enum TlsError {
+    /// I/O error reading a certificate or key file
+    #[error("Failed to read {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// PEM file contained no certificates
+    #[error("No certificates found in {0:?}")]
+    NoCertificates(PathBuf),
+
+    /// PEM file contained no private key
+    #[error("No private key found in {0:?}")]
+    NoPrivateKey(PathBuf),
+
+    /// rustls rejected the certificate material
+    #[error("Invalid TLS configuration: {0}")]
+    Rustls(#[from] rustls::Error),
+}
+
+/// rustls server configuration that can be rebuilt from disk at runtime.
+///
+/// Connections accepted after a reload use the new certificates; established
+/// connections keep the configuration they were negotiated with.
+pub struct ReloadableTlsConfig {
+    config: TlsConfig,
+    alpn_protocols: Vec<Vec<u8>>,
+    current: RwLock<Arc<ServerConfig>>,
+}
+
+impl ReloadableTlsConfig {
+    /// Load certificates from the configured PEM files.
+    ///
+    /// `alpn_protocols` is advertised during the handshake (e.g. `h2` for gRPC).
+    pub fn load(config: &TlsConfig, alpn_protocols: Vec<Vec<u8>>) -> Result<Self, TlsError> {
+        let server_config = build_server_config(config, &alpn_protocols)?;
+
+        Ok(Self {
+            config: config.clone(),
+            alpn_protocols,
+            current: RwLock::new(Arc::new(server_config)),
+        })
+    }
+
+    /// Whether client certificates are required (mTLS).
+    pub fn is_mutual(&self) -> bool {
+        self.config.client_ca_path.is_some()
+    }
+
+    /// Acceptor for the currently active certificates.
+    pub fn acceptor(&self) -> TlsAcceptor {
+        let config = self.current.read().expect("TLS config lock poisoned");
+        TlsAcceptor::from(Arc::clone(&config))
+    }
+
+    /// Re-read certificate files and swap in the new configuration.
+    ///
+    /// On error the previous configuration stays active.
+    pub fn reload(&self) -> Result<(), TlsError> {
+        let server_config = build_server_config(&self.config, &self.alpn_protocols)?;
+        *self.current.write().expect("TLS config lock poisoned") = Arc::new(server_config);
+        Ok(())
+    }
+
+    /// Spawn a background task that reloads certificates when the files change.
+    ///
+    /// Files are polled every `reload_interval_seconds`. A value of 0 disables polling.
+    pub fn spawn_reloader(self: &Arc<Self>) -> Option<JoinHandle<()>> {
+        if self.config.reload_interval_seconds == 0 {
+            return None;
+        }
+
+        let tls = Arc::clone(self);
+        let interval = Duration::from_secs(self.config.reload_interval_seconds);
+
+        Some(tokio::spawn(async move {
+            let mut last_modified = tls.files_modified();
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+
+                let modified = tls.files_modified();
+                if modified == last_modified {
+                    continue;
+                }
+
+                match tls.reload() {
+                    Ok(()) => {
+                        tracing::info!("🔐 Reloaded TLS certificates");
+                        last_modified = modified;
+                    }
+                    Err(e) => {
+                        // Keep serving with the old certificates; retry on next tick
+                        tracing::warn!("⚠️  Failed to reload TLS certificates: {}", e);
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Accept TCP connections and complete TLS handshakes in the background.
+    ///
+    /// Handshakes run concurrently so a slow client cannot stall the accept loop.
+    /// Failed handshakes are logged and dropped. The accept loop stops once the
+    /// returned receiver is dropped.
+    pub fn accept(self: Arc<Self>, listener: TcpListener) -> mpsc::Receiver<TlsStream<TcpStream>> {
+        let (tx, rx) = mpsc::channel(128);
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, peer) = tokio::select! {
+                    _ = tx.closed() => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            tracing::warn!("Failed to accept TCP connection: {}", e);
+                            continue;
+                        }
+                    },
+                };
+
+                let acceptor = self.acceptor();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(tls_stream)) => {
+                            let _ = tx.send(tls_stream).await;
+                        }
+                        Ok(Err(e)) => {
+                            tracing::debug!("TLS handshake with {} failed: {}", peer, e);
+                        }
+                        Err(_) => {
+                            tracing::debug!("TLS handshake with {} timed out", peer);
+                        }
+                    }
+                });
+            }
+        });
+
+        rx
+    }
+
+    fn files_modified(&self) -> Vec<Option<SystemTime>> {
+        let mut paths = vec![&self.config.cert_path, &self.config.key_path];
+        if let Some(ca_path) = &self.config.client_ca_path {
+            paths.push(ca_path);
+        }
+
+        paths
+            .into_iter()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+fn build_server_config(
+    config: &TlsConfig,
+    alpn_protocols: &[Vec<u8>],
+) -> Result<ServerConfig, TlsError> {
+    let certs = load_certs(&config.cert_path)?;
+    let key = load_private_key(&config.key_path)?;
+
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let mut server_config = match &config.client_ca_path {
+        Some(ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca_path)? {
+                roots.add(&cert)?;
+            }
+            builder
+                .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+                .with_single_cert(certs, key)?
+        }
+        None => builder.with_no_client_auth().with_single_cert(certs, key)?,
+    };
+
+    server_config.alpn_protocols = alpn_protocols.to_vec();
+    Ok(server_config)
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, TlsError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| TlsError::Io {
+            path: path.to_path_buf(),
+            source: e,
+        })
+}
+
+fn load_certs(path: &Path) -> Result<Vec<Certificate>, TlsError> {
+    let certs = rustls_pemfile::certs(&mut open(path)?).map_err(|e| TlsError::Io {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    if certs.is_empty() {
+        return Err(TlsError::NoCertificates(path.to_path_buf()));
+    }
+
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKey, TlsError> {
+    let mut reader = open(path)?;
+
+    loop {
+        let item = rustls_pemfile::read_one(&mut reader).map_err(|e| TlsError::Io {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        match item {
+            Some(rustls_pemfile::Item::PKCS8Key(key))
+            | Some(rustls_pemfile::Item::RSAKey(key))
+            | Some(rustls_pemfile::Item::ECKey(key)) => return Ok(PrivateKey(key)),
+            Some(_) => continue,
+            None => return Err(TlsError::NoPrivateKey(path.to_path_buf())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_pem(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn self_signed(dir: &Path, prefix: &str) -> TlsConfig {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        TlsConfig {
+            cert_path: write_pem(
+                dir,
+                &format!("{prefix}.crt"),
+                &cert.serialize_pem().unwrap(),
+            ),
+            key_path: write_pem(
+                dir,
+                &format!("{prefix}.key"),
+                &cert.serialize_private_key_pem(),
+            ),
+            client_ca_path: None,
+            reload_interval_seconds: 60,
+        }
+    }
+
+    #[test]
+    fn test_load_self_signed() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = self_signed(dir.path(), "server");
+
+        let tls = ReloadableTlsConfig::load(&config, vec![b"h2".to_vec()]).unwrap();
+        assert!(!tls.is_mutual());
+        assert_eq!(
+            tls.current.read().unwrap().alpn_protocols,
+            vec![b"h2".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_load_with_client_ca() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = self_signed(dir.path(), "server");
+        config.client_ca_path = Some(self_signed(dir.path(), "ca").cert_path);
+
+        let tls = ReloadableTlsConfig::load(&config, Vec::new()).unwrap();
+        assert!(tls.is_mutual());
+    }
+
+    #[test]
+    fn test_missing_cert_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = self_signed(dir.path(), "server");
+        config.cert_path = dir.path().join("missing.crt");
+
+        let result = ReloadableTlsConfig::load(&config, Vec::new());
+        assert!(matches!(result, Err(TlsError::Io { .. })));
+    }
+
+    #[test]
+    fn test_empty_key_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = self_signed(dir.path(), "server");
+        config.key_path = write_pem(dir.path(), "empty.key", "");
+
+        let result = ReloadableTlsConfig::load(&config, Vec::new());
+        assert!(matches!(result, Err(TlsError::NoPrivateKey(_))));
+    }
+
+    #[test]
+    fn test_reload_keeps_old_config_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = self_signed(dir.path(), "server");
+        let tls = ReloadableTlsConfig::load(&config, Vec::new()).unwrap();
+        let before = Arc::clone(&tls.current.read().unwrap());
+
+        std::fs::write(&config.cert_path, "not a certificate").unwrap();
+        assert!(matches!(tls.reload(), Err(TlsError::NoCertificates(_))));
+        assert!(Arc::ptr_eq(&before, &tls.current.read().unwrap()));
+
+        // Rotate to a fresh certificate
+        let rotated = self_signed(dir.path(), "rotated");
+        std::fs::copy(&rotated.cert_path, &config.cert_path).unwrap();
+        std::fs::copy(&rotated.key_path, &config.key_path).unwrap();
+        tls.reload().unwrap();
+        assert!(!Arc::ptr_eq(&before, &tls.current.read().unwrap()));
+    }
+}
@@ -0,0 +1,118 @@
+//! W3C trace context propagation and Jaeger export.
+//!
+//! Incoming REST and gRPC requests carry `traceparent` / `tracestate` headers
+//! (gRPC metadata is HTTP/2 headers). The API layers call
+//! [`set_parent_from_headers`] on their request span so it joins the caller's
+//! trace; everything below it — search, index and WAL operations, S3 uploads,
+//! embedding calls — runs in child spans and ends up in the same trace.
+//!
+//! Propagation needs the W3C propagator installed ([`install_propagator`],
+//! done by [`init_tracing`]); without it extraction and injection are no-ops.
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TraceError;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{self, Sampler};
+use opentelemetry_sdk::Resource;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry};
+
+/// W3C `traceparent` header.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// W3C `tracestate` header.
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// Installs the W3C trace context propagator globally.
+pub fn install_propagator() {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+}
+
+/// Installs the propagator and a subscriber exporting spans to Jaeger.
+///
+/// # Errors
+///
+/// Returns an error if the Jaeger pipeline cannot be built.
+pub fn init_tracing(service_name: &str, jaeger_endpoint: &str) -> Result<(), TraceError> {
+    install_propagator();
+
+    let tracer = opentelemetry_jaeger::new_agent_pipeline()
+        .with_service_name(service_name)
+        .with_endpoint(jaeger_endpoint)
+        .with_trace_config(
+            trace::config()
+                .with_sampler(Sampler::ParentBased(Box::new(Sampler::AlwaysOn)))
+                .with_resource(Resource::new(vec![
+                    KeyValue::new("service.name", service_name.to_string()),
+                    KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+                ])),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+    let env_filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info,akidb=debug"));
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_target(true)
+        .with_thread_ids(false)
+        .with_line_number(true);
+
+    Registry::default()
+        .with(env_filter)
+        .with(fmt_layer)
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .init();
+
+    Ok(())
+}
+
+/// Flushes pending spans and shuts the tracer provider down.
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// Makes `span` a child of the trace named by the request's `traceparent`.
+///
+/// Requests without (or with a malformed) `traceparent` start a new trace.
+pub fn set_parent_from_headers(span: &Span, headers: &HeaderMap) {
+    let context =
+        global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+    span.set_parent(context);
+}
+
+/// Writes `span`'s trace context as `traceparent` / `tracestate` headers.
+pub fn inject_headers(span: &Span, headers: &mut HeaderMap) {
+    let context = span.context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(headers));
+    });
+}
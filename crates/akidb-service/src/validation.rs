@@ -0,0 +1,195 @@
+//! Request payload validation shared by the gRPC and REST APIs.
+//!
+//! Checks run before documents reach the index so oversized batches or
+//! pathological metadata are rejected cheaply. Errors carry the violated limit
+//! and the observed value so the API layers can return structured responses
+//! (413 for size limits, 422 for malformed payloads).
+
+use crate::config::LimitsConfig;
+use akidb_core::VectorDocument;
+use serde_json::Value as JsonValue;
+
+/// Payload validation error types
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    /// Batch contains more vectors than allowed
+    #[error("batch contains {actual} vectors, limit is {limit}")]
+    TooManyVectors { limit: usize, actual: usize },
+
+    /// Document metadata + external_id exceed the per-document byte limit
+    #[error("document payload is {actual} bytes, limit is {limit}")]
+    DocumentTooLarge { limit: usize, actual: usize },
+
+    /// Document metadata is nested too deeply
+    #[error("metadata nesting depth is {actual}, limit is {limit}")]
+    MetadataTooDeep { limit: usize, actual: usize },
+}
+
+impl ValidationError {
+    /// Stable machine-readable error code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::TooManyVectors { .. } => "too_many_vectors",
+            Self::DocumentTooLarge { .. } => "document_too_large",
+            Self::MetadataTooDeep { .. } => "metadata_too_deep",
+        }
+    }
+
+    /// Whether the error is a size limit (HTTP 413) rather than a malformed payload (HTTP 422).
+    pub fn is_payload_too_large(&self) -> bool {
+        matches!(
+            self,
+            Self::TooManyVectors { .. } | Self::DocumentTooLarge { .. }
+        )
+    }
+
+    /// The limit that was exceeded.
+    pub fn limit(&self) -> usize {
+        match self {
+            Self::TooManyVectors { limit, .. }
+            | Self::DocumentTooLarge { limit, .. }
+            | Self::MetadataTooDeep { limit, .. } => *limit,
+        }
+    }
+
+    /// The observed value.
+    pub fn actual(&self) -> usize {
+        match self {
+            Self::TooManyVectors { actual, .. }
+            | Self::DocumentTooLarge { actual, .. }
+            | Self::MetadataTooDeep { actual, .. } => *actual,
+        }
+    }
+}
+
+/// Validate the number of vectors in a batch request.
+pub fn validate_batch_size(count: usize, limits: &LimitsConfig) -> Result<(), ValidationError> {
+    if count > limits.max_batch_vectors {
+        return Err(ValidationError::TooManyVectors {
+            limit: limits.max_batch_vectors,
+            actual: count,
+        });
+    }
+    Ok(())
+}
+
+/// Validate a single document's payload size and metadata shape.
+pub fn validate_document(
+    doc: &VectorDocument,
+    limits: &LimitsConfig,
+) -> Result<(), ValidationError> {
+    let mut payload_bytes = doc.external_id.as_ref().map_or(0, String::len);
+
+    if let Some(metadata) = &doc.metadata {
+        let depth = metadata_depth(metadata);
+        if depth > limits.max_metadata_depth {
+            return Err(ValidationError::MetadataTooDeep {
+                limit: limits.max_metadata_depth,
+                actual: depth,
+            });
+        }
+
+        // Serializing a Value cannot fail (all keys are strings)
+        payload_bytes += serde_json::to_vec(metadata).map_or(0, |bytes| bytes.len());
+    }
+
+    if payload_bytes > limits.max_document_payload_bytes {
+        return Err(ValidationError::DocumentTooLarge {
+            limit: limits.max_document_payload_bytes,
+            actual: payload_bytes,
+        });
+    }
+
+    Ok(())
+}
+
+/// Nesting depth of a JSON value (scalars are depth 0, `{}`/`[]` are depth 1).
+///
+/// Iterative so hostile input cannot overflow the stack.
+pub fn metadata_depth(value: &JsonValue) -> usize {
+    let mut max_depth = 0;
+    let mut stack = vec![(value, 0usize)];
+
+    while let Some((value, depth)) = stack.pop() {
+        match value {
+            JsonValue::Array(items) => {
+                max_depth = max_depth.max(depth + 1);
+                stack.extend(items.iter().map(|item| (item, depth + 1)));
+            }
+            JsonValue::Object(fields) => {
+                max_depth = max_depth.max(depth + 1);
+                stack.extend(fields.values().map(|field| (field, depth + 1)));
+            }
+            _ => {}
+        }
+    }
+
+    max_depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use akidb_core::DocumentId;
+    use serde_json::json;
+
+    fn doc_with_metadata(metadata: JsonValue) -> VectorDocument {
+        VectorDocument::new(DocumentId::new(), vec![0.1, 0.2]).with_metadata(metadata)
+    }
+
+    #[test]
+    fn test_metadata_depth() {
+        assert_eq!(metadata_depth(&json!(1)), 0);
+        assert_eq!(metadata_depth(&json!({})), 1);
+        assert_eq!(metadata_depth(&json!({"a": [1, 2]})), 2);
+        assert_eq!(metadata_depth(&json!({"a": {"b": {"c": []}}})), 4);
+    }
+
+    #[test]
+    fn test_validate_batch_size() {
+        let limits = LimitsConfig {
+            max_batch_vectors: 2,
+            ..LimitsConfig::default()
+        };
+
+        assert!(validate_batch_size(2, &limits).is_ok());
+        let err = validate_batch_size(3, &limits).unwrap_err();
+        assert_eq!(err.code(), "too_many_vectors");
+        assert!(err.is_payload_too_large());
+    }
+
+    #[test]
+    fn test_validate_document_too_deep() {
+        let limits = LimitsConfig {
+            max_metadata_depth: 2,
+            ..LimitsConfig::default()
+        };
+
+        assert!(validate_document(&doc_with_metadata(json!({"a": [1]})), &limits).is_ok());
+
+        let err =
+            validate_document(&doc_with_metadata(json!({"a": {"b": {}}})), &limits).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::MetadataTooDeep {
+                limit: 2,
+                actual: 3
+            }
+        );
+        assert!(!err.is_payload_too_large());
+    }
+
+    #[test]
+    fn test_validate_document_too_large() {
+        let limits = LimitsConfig {
+            max_document_payload_bytes: 32,
+            ..LimitsConfig::default()
+        };
+
+        let doc = doc_with_metadata(json!({"text": "x".repeat(64)}));
+        let err = validate_document(&doc, &limits).unwrap_err();
+        assert_eq!(err.code(), "document_too_large");
+        assert_eq!(err.limit(), 32);
+        assert!(err.actual() > 64);
+    }
+}
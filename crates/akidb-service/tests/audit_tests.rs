@@ -0,0 +1,153 @@
+//! Audit entries for vector writes and admin actions.
+
+use akidb_core::{
+    Action, AuditLogRepository, DatabaseDescriptor, DatabaseRepository, DistanceMetric, DocumentId,
+    TenantCatalog, TenantDescriptor, TenantId, VectorDocument,
+};
+use akidb_metadata::{
+    create_sqlite_pool, run_migrations, SqliteAuditLogRepository, SqliteDatabaseRepository,
+    SqliteTenantCatalog,
+};
+use akidb_service::{AuditConfig, AuditTrail, CollectionService, StorageConfig};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+struct Fixture {
+    service: CollectionService,
+    audit_logs: Arc<SqliteAuditLogRepository>,
+    tenant_id: TenantId,
+    _dir: TempDir,
+}
+
+async fn fixture(config: AuditConfig) -> Fixture {
+    let dir = TempDir::new().unwrap();
+    let url = format!("sqlite://{}", dir.path().join("metadata.db").display());
+    let pool = create_sqlite_pool(&url).await.unwrap();
+    run_migrations(&pool).await.unwrap();
+
+    let tenant = TenantDescriptor::new("Audit Corp", "audit-corp");
+    SqliteTenantCatalog::new(pool.clone())
+        .create(&tenant)
+        .await
+        .unwrap();
+    let database = DatabaseDescriptor::new(tenant.tenant_id, "default", None);
+    let databases = Arc::new(SqliteDatabaseRepository::new(pool.clone()));
+    databases.create(&database).await.unwrap();
+
+    let audit_logs = Arc::new(SqliteAuditLogRepository::new(pool));
+    let service = CollectionService::new()
+        .with_storage_config(StorageConfig::memory(dir.path().join("akidb.wal")))
+        .with_audit(Arc::new(AuditTrail::new(
+            audit_logs.clone(),
+            databases,
+            &config,
+        )));
+    service.set_default_database_id(database.database_id).await;
+
+    Fixture {
+        service,
+        audit_logs,
+        tenant_id: tenant.tenant_id,
+        _dir: dir,
+    }
+}
+
+fn doc() -> VectorDocument {
+    VectorDocument::new(DocumentId::new(), vec![0.5; 16])
+}
+
+#[tokio::test]
+async fn test_vector_writes_are_audited_when_enabled() {
+    let f = fixture(AuditConfig {
+        data_plane: true,
+        ..AuditConfig::default()
+    })
+    .await;
+    let collection_id = f
+        .service
+        .create_collection("docs".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+
+    let doc_id = f.service.insert(collection_id, doc()).await.unwrap();
+    f.service
+        .upsert_batch(collection_id, vec![doc(), doc()])
+        .await
+        .unwrap();
+    f.service.delete(collection_id, doc_id).await.unwrap();
+
+    let entries = f
+        .audit_logs
+        .list_by_tenant(f.tenant_id, 10, 0)
+        .await
+        .unwrap();
+    let mut actions: Vec<Action> = entries.iter().map(|e| e.action).collect();
+    actions.sort_by_key(|action| action.as_str());
+    assert_eq!(
+        actions,
+        vec![
+            Action::DocumentDelete,
+            Action::DocumentInsert,
+            Action::DocumentUpdate
+        ]
+    );
+
+    let batch = entries
+        .iter()
+        .find(|e| e.action == Action::DocumentUpdate)
+        .unwrap();
+    assert_eq!(batch.resource_id, collection_id.to_string());
+    let metadata = batch.metadata.as_ref().unwrap();
+    assert_eq!(metadata["count"], 2);
+    assert_eq!(metadata["succeeded"], 2);
+    assert_eq!(metadata["sample_rate"], 1.0);
+}
+
+#[tokio::test]
+async fn test_vector_writes_are_sampled() {
+    let f = fixture(AuditConfig {
+        data_plane: true,
+        write_sample_rate: 0.25,
+        ..AuditConfig::default()
+    })
+    .await;
+    let collection_id = f
+        .service
+        .create_collection("docs".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+
+    for _ in 0..20 {
+        f.service.insert(collection_id, doc()).await.unwrap();
+    }
+
+    let entries = f
+        .audit_logs
+        .list_by_tenant(f.tenant_id, 100, 0)
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 5);
+}
+
+#[tokio::test]
+async fn test_admin_actions_are_audited_by_default() {
+    let f = fixture(AuditConfig::default()).await;
+    let collection_id = f
+        .service
+        .create_collection("docs".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+
+    // Vector writes are off by default
+    f.service.insert(collection_id, doc()).await.unwrap();
+    f.service.reset_circuit_breaker().await.unwrap();
+
+    let entries = f
+        .audit_logs
+        .list_by_tenant(f.tenant_id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, Action::CircuitBreakerReset);
+    assert_eq!(entries[0].resource_type, "node");
+}
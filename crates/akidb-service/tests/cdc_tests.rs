@@ -0,0 +1,117 @@
+//! Change data capture tests: a recording sink stands in for the broker.
+
+use akidb_core::{CoreError, CoreResult, DistanceMetric, DocumentId, VectorDocument};
+use akidb_service::{CdcConfig, CdcEvent, CdcOp, CdcPublisher, CdcSink};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+mod common;
+
+/// Records what it acknowledges; fails every publish while `down` is set.
+#[derive(Default)]
+struct RecordingSink {
+    published: Mutex<Vec<(String, CdcEvent)>>,
+    down: AtomicBool,
+}
+
+impl RecordingSink {
+    fn take(&self) -> Vec<(String, CdcEvent)> {
+        std::mem::take(&mut *self.published.lock().unwrap())
+    }
+}
+
+#[async_trait]
+impl CdcSink for RecordingSink {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    async fn publish(&self, topic: &str, events: &[CdcEvent]) -> CoreResult<()> {
+        if self.down.load(Ordering::SeqCst) {
+            return Err(CoreError::internal("broker unavailable"));
+        }
+        let mut published = self.published.lock().unwrap();
+        published.extend(events.iter().map(|e| (topic.to_string(), e.clone())));
+        Ok(())
+    }
+}
+
+fn test_doc() -> VectorDocument {
+    VectorDocument::new(DocumentId::new(), vec![0.1; 16])
+}
+
+#[tokio::test]
+async fn test_publishes_wal_in_order_and_resumes_from_checkpoint() {
+    let dir = TempDir::new().unwrap();
+    let service = common::setup_service("test-cdc", &dir).await.service;
+    let collection_id = service
+        .create_collection("orders".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+    let kept = service.insert(collection_id, test_doc()).await.unwrap();
+    let deleted = service.insert(collection_id, test_doc()).await.unwrap();
+    service.delete(collection_id, deleted).await.unwrap();
+
+    let config = CdcConfig {
+        batch_size: 2,
+        checkpoint_path: dir.path().join("cdc_checkpoint.json"),
+        ..CdcConfig::default()
+    };
+    let sink = Arc::new(RecordingSink::default());
+    let mut publisher = CdcPublisher::new(service.clone(), sink.clone(), &config).unwrap();
+    publisher.publish_pass().await.unwrap();
+
+    let events = sink.take();
+    assert_eq!(events.len(), 3);
+    assert!(events.iter().all(|(topic, _)| topic == "akidb.cdc.orders"));
+    assert!(events.windows(2).all(|w| w[0].1.lsn < w[1].1.lsn));
+    assert!(matches!(events[0].1.op, CdcOp::Upsert { doc_id, .. } if doc_id == kept));
+    assert_eq!(events[2].1.op, CdcOp::Delete { doc_id: deleted });
+    let lsn = service.wal_state(collection_id).await.unwrap().current_lsn;
+    assert_eq!(publisher.positions()[&collection_id], lsn);
+
+    // Unacknowledged writes are published again once the broker is back
+    service.insert(collection_id, test_doc()).await.unwrap();
+    sink.down.store(true, Ordering::SeqCst);
+    assert!(publisher.publish_pass().await.is_err());
+    assert_eq!(publisher.positions()[&collection_id], lsn);
+    sink.down.store(false, Ordering::SeqCst);
+
+    // A restarted publisher resumes after the checkpoint
+    drop(publisher);
+    let mut publisher = CdcPublisher::new(service.clone(), sink.clone(), &config).unwrap();
+    publisher.publish_pass().await.unwrap();
+    let events = sink.take();
+    assert_eq!(events.len(), 1);
+    assert!(events[0].1.lsn > lsn);
+    assert!(!events[0].1.snapshot);
+}
+
+#[tokio::test]
+async fn test_only_selected_collections_are_published() {
+    let dir = TempDir::new().unwrap();
+    let service = common::setup_service("test-cdc", &dir).await.service;
+    for name in ["logs-2024", "users"] {
+        let collection_id = service
+            .create_collection(name.to_string(), 16, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+        service.insert(collection_id, test_doc()).await.unwrap();
+    }
+
+    let config = CdcConfig {
+        collections: vec!["logs-*".to_string()],
+        checkpoint_path: dir.path().join("cdc_checkpoint.json"),
+        ..CdcConfig::default()
+    };
+    let sink = Arc::new(RecordingSink::default());
+    let mut publisher = CdcPublisher::new(service, sink.clone(), &config).unwrap();
+    publisher.publish_pass().await.unwrap();
+
+    let events = sink.take();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, "akidb.cdc.logs-2024");
+    assert_eq!(events[0].1.collection, "logs-2024");
+}
@@ -0,0 +1,84 @@
+//! Fixtures shared by the service integration tests.
+
+#![allow(dead_code)] // Each test crate uses a subset
+
+use akidb_core::{DatabaseId, TenantId};
+use akidb_metadata::{SqliteCollectionRepository, VectorPersistence};
+use akidb_service::CollectionService;
+use akidb_storage::StorageConfig;
+use sqlx::SqlitePool;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Counter making tenant slugs unique
+static TENANT_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Service with its own database and storage directory
+pub struct TestService {
+    pub service: Arc<CollectionService>,
+    pub pool: SqlitePool,
+    pub database_id: DatabaseId,
+}
+
+/// Migrated in-memory database with one tenant and one database
+///
+/// The tenant's slug is `slug_prefix` followed by a unique number.
+pub async fn setup_db(slug_prefix: &str) -> (SqlitePool, DatabaseId) {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::migrate!("../akidb-metadata/migrations")
+        .run(&pool)
+        .await
+        .unwrap();
+
+    let tenant_id = TenantId::new();
+    let slug = format!(
+        "{}-{}",
+        slug_prefix,
+        TENANT_COUNTER.fetch_add(1, Ordering::SeqCst)
+    );
+    sqlx::query(
+        "INSERT INTO tenants (tenant_id, name, slug, status, created_at, updated_at)
+         VALUES (?1, 'test-tenant', ?2, 'active', datetime('now'), datetime('now'))",
+    )
+    .bind(&tenant_id.to_bytes()[..])
+    .bind(&slug)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let database_id = DatabaseId::new();
+    sqlx::query(
+        "INSERT INTO databases (database_id, tenant_id, name, state, created_at, updated_at)
+         VALUES (?1, ?2, 'test-database', 'ready', datetime('now'), datetime('now'))",
+    )
+    .bind(&database_id.to_bytes()[..])
+    .bind(&tenant_id.to_bytes()[..])
+    .execute(&pool)
+    .await
+    .unwrap();
+    (pool, database_id)
+}
+
+/// Service over `pool` keeping its WAL and snapshots in `dir`
+pub fn service(pool: &SqlitePool, dir: &TempDir) -> CollectionService {
+    let mut storage = StorageConfig::memory(dir.path().join("akidb.wal"));
+    storage.snapshot_dir = dir.path().join("snapshots");
+    CollectionService::with_storage(
+        Arc::new(SqliteCollectionRepository::new(pool.clone())),
+        Arc::new(VectorPersistence::new(pool.clone())),
+        storage,
+    )
+}
+
+/// Service with its own database, creating in that database by default
+pub async fn setup_service(slug_prefix: &str, dir: &TempDir) -> TestService {
+    let (pool, database_id) = setup_db(slug_prefix).await;
+    let service = Arc::new(service(&pool, dir));
+    service.set_default_database_id(database_id).await;
+    TestService {
+        service,
+        pool,
+        database_id,
+    }
+}
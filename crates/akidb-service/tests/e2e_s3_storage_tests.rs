@@ -10,9 +10,9 @@
 //! - 2 ignored tests (require mock S3 injection)
 //! - 1 ignored benchmark test
 
-use akidb_core::{DistanceMetric, DocumentId, VectorDocument};
+use akidb_core::{CoreError, DistanceMetric, DocumentId, VectorDocument};
 use akidb_metadata::{SqliteCollectionRepository, VectorPersistence};
-use akidb_service::CollectionService;
+use akidb_service::{CollectionService, FilterTree};
 use akidb_storage::{StorageConfig, TieringPolicy};
 use sqlx::SqlitePool;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -36,6 +36,14 @@ async fn setup_test_db() -> SqlitePool {
 /// Helper to create a collection service with S3 storage backend
 async fn setup_service_with_s3(
     policy: TieringPolicy,
+) -> (Arc<CollectionService>, TempDir, SqlitePool) {
+    setup_service_with_s3_cache(policy, 10_000).await
+}
+
+/// Like `setup_service_with_s3`, with an S3Only cache of `cache_size` vectors
+async fn setup_service_with_s3_cache(
+    policy: TieringPolicy,
+    cache_size: usize,
 ) -> (Arc<CollectionService>, TempDir, SqlitePool) {
     let pool = setup_test_db().await;
     let temp_dir = tempfile::tempdir().unwrap();
@@ -62,7 +70,7 @@ async fn setup_service_with_s3(
         }
         TieringPolicy::S3Only => {
             let s3_bucket = format!("file://{}", s3_path.display());
-            StorageConfig::s3_only(wal_path, snapshot_dir, s3_bucket, 10_000)
+            StorageConfig::s3_only(wal_path, snapshot_dir, s3_bucket, cache_size)
         }
     };
 
@@ -247,6 +255,83 @@ async fn test_e2e_s3only_cache_behavior() {
     println!("✓ S3Only cache behavior test passed");
 }
 
+#[tokio::test]
+async fn test_e2e_s3only_upsert_by_external_id_after_eviction() {
+    let (service, _temp_dir, _pool) = setup_service_with_s3_cache(TieringPolicy::S3Only, 2).await;
+
+    let collection_id = service
+        .create_collection(
+            "s3only-external-ids".to_string(),
+            128,
+            DistanceMetric::Cosine,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let (doc_id, created) = service
+        .upsert_by_external_id(collection_id, "ext-1".to_string(), vec![1.0; 128], None)
+        .await
+        .unwrap();
+    assert!(created);
+
+    // Evict the document from the two-entry cache
+    for i in 0..4 {
+        service
+            .insert(collection_id, create_test_vector(128, i as f32))
+            .await
+            .unwrap();
+    }
+
+    let (upserted_id, created) = service
+        .upsert_by_external_id(collection_id, "ext-1".to_string(), vec![2.0; 128], None)
+        .await
+        .unwrap();
+    assert!(
+        !created,
+        "Evicted document should be updated, not duplicated"
+    );
+    assert_eq!(upserted_id, doc_id);
+    assert_eq!(service.get_count(collection_id).await.unwrap(), 5);
+
+    let resolved = service
+        .resolve_external_ids(collection_id, &["ext-1".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(resolved.get("ext-1"), Some(&doc_id));
+}
+
+#[tokio::test]
+async fn test_e2e_s3only_delete_by_filter_rejected() {
+    let (service, _temp_dir, _pool) = setup_service_with_s3_cache(TieringPolicy::S3Only, 2).await;
+
+    let collection_id = service
+        .create_collection(
+            "s3only-filter-delete".to_string(),
+            128,
+            DistanceMetric::Cosine,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // More matching documents than the cache holds
+    for i in 0..5 {
+        let doc =
+            create_test_vector(128, i as f32).with_metadata(serde_json::json!({ "shard": 1 }));
+        service.insert(collection_id, doc).await.unwrap();
+    }
+
+    let filter = FilterTree::parse(&serde_json::json!({ "shard": 1 })).unwrap();
+    let result = service.delete_by_filter(collection_id, &filter).await;
+    assert!(
+        matches!(result, Err(CoreError::InvalidState { .. })),
+        "Expected InvalidState, got {:?}",
+        result
+    );
+    assert_eq!(service.get_count(collection_id).await.unwrap(), 5);
+}
+
 #[tokio::test]
 async fn test_e2e_background_compaction_non_blocking() {
     let (service, _temp_dir, _pool) = setup_service_with_s3(TieringPolicy::Memory).await;
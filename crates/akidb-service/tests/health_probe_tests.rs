@@ -0,0 +1,52 @@
+//! Active dependency probes behind GET /admin/health.
+
+use akidb_metadata::{SqliteCollectionRepository, VectorPersistence};
+use akidb_service::health::ProbeStatus;
+use akidb_service::CollectionService;
+use akidb_storage::StorageConfig;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_probes_exercise_database_and_wal_dir() {
+    let dir = TempDir::new().unwrap();
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let service = CollectionService::with_storage(
+        Arc::new(SqliteCollectionRepository::new(pool.clone())),
+        Arc::new(VectorPersistence::new(pool.clone())),
+        StorageConfig::memory(dir.path().join("akidb.wal")),
+    );
+
+    let database = service.probe_database().await;
+    assert_eq!(database.status, ProbeStatus::Healthy);
+    assert!(database.latency_ms.is_some());
+
+    let wal = service.probe_wal_dir().await;
+    assert_eq!(wal.status, ProbeStatus::Healthy, "{:?}", wal.message);
+    // The probe file is cleaned up
+    let leftovers = std::fs::read_dir(dir.path().join("collections"))
+        .unwrap()
+        .count();
+    assert_eq!(leftovers, 0);
+
+    // No collection storage is open yet
+    assert_eq!(
+        service.probe_object_store().await.status,
+        ProbeStatus::Skipped
+    );
+
+    pool.close().await;
+    assert_eq!(
+        service.probe_database().await.status,
+        ProbeStatus::Unhealthy
+    );
+}
+
+#[tokio::test]
+async fn test_probes_skip_in_memory_mode() {
+    let service = CollectionService::new();
+
+    assert_eq!(service.probe_database().await.status, ProbeStatus::Skipped);
+    assert_eq!(service.probe_wal_dir().await.status, ProbeStatus::Skipped);
+}
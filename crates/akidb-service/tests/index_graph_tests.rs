@@ -0,0 +1,96 @@
+//! HNSW graph persistence: graphs saved on snapshot are restored at load.
+
+use akidb_core::{DistanceMetric, DocumentId, VectorDocument};
+use akidb_service::{CollectionService, Fusion, HnswConfig, HybridOptions, SearchOptions};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+mod common;
+
+/// Service persisting index graphs, sharing `pool` and `dir` across restarts
+fn setup_service(pool: &SqlitePool, dir: &TempDir) -> Arc<CollectionService> {
+    let hnsw = HnswConfig {
+        persist_graph: true,
+        ..HnswConfig::default()
+    };
+    Arc::new(common::service(pool, dir).with_index_graph_persistence(&hnsw))
+}
+
+fn vector(i: usize) -> Vec<f32> {
+    let mut vector = vec![0.0; 16];
+    vector[i % 16] = 1.0;
+    vector[(i / 16) % 16] += 0.5;
+    vector
+}
+
+#[tokio::test]
+async fn test_graph_restored_with_later_writes() {
+    let dir = TempDir::new().unwrap();
+    let (pool, database_id) = common::setup_db("test-graphs").await;
+    let service = setup_service(&pool, &dir);
+    service.set_default_database_id(database_id).await;
+
+    let collection_id = service
+        .create_collection("graphs".to_string(), 16, DistanceMetric::L2, None)
+        .await
+        .unwrap();
+    let mut doc_ids = Vec::new();
+    for i in 0..40 {
+        let doc = VectorDocument::new(DocumentId::new(), vector(i))
+            .with_metadata(serde_json::json!({ "text": format!("item{}", i) }));
+        doc_ids.push(service.insert(collection_id, doc).await.unwrap());
+    }
+    service.snapshot_collection(collection_id).await.unwrap();
+    let graph = dir
+        .path()
+        .join(format!(
+            "collections/{}/snapshots/index-graphs",
+            collection_id
+        ))
+        .join(format!("{}.bin", collection_id));
+    assert!(graph.exists());
+
+    // Writes after the graph was saved are replayed from the WAL
+    service.delete(collection_id, doc_ids[3]).await.unwrap();
+    let late = VectorDocument::new(DocumentId::new(), vector(41))
+        .with_metadata(serde_json::json!({"text": "late arrival"}));
+    let late_id = service.insert(collection_id, late).await.unwrap();
+    drop(service);
+
+    let service = setup_service(&pool, &dir);
+    service.set_default_database_id(database_id).await;
+    service.load_all_collections().await.unwrap();
+
+    assert_eq!(service.get_count(collection_id).await.unwrap(), 40);
+    assert!(service
+        .get(collection_id, doc_ids[3])
+        .await
+        .unwrap()
+        .is_none());
+    let results = service.query(collection_id, vector(41), 1).await.unwrap();
+    assert_eq!(results[0].doc_id, late_id);
+    let results = service.query(collection_id, vector(7), 1).await.unwrap();
+    assert_eq!(results[0].doc_id, doc_ids[7]);
+
+    // The keyword index is rebuilt alongside the restored graph
+    for (keywords, expected) in [("item7", doc_ids[7]), ("late", late_id)] {
+        let options = SearchOptions {
+            hybrid: Some(HybridOptions {
+                query: keywords.to_string(),
+                fields: None,
+                fusion: Fusion::Weighted { alpha: 0.0 },
+                candidates: 10,
+            }),
+            ..SearchOptions::default()
+        };
+        let results = service
+            .search(collection_id, vector(0), 1, &options)
+            .await
+            .unwrap();
+        assert_eq!(results[0].doc_id, expected);
+    }
+
+    service.delete_collection(collection_id).await.unwrap();
+    assert!(!graph.exists());
+}
@@ -3,9 +3,9 @@
 //! These tests validate complete workflows from collection creation through
 //! vector operations, persistence, and cleanup.
 
-use akidb_core::{CollectionId, DistanceMetric, DocumentId, VectorDocument};
+use akidb_core::{CollectionId, DistanceMetric, DocumentId, Quantization, VectorDocument};
 use akidb_metadata::{SqliteCollectionRepository, VectorPersistence};
-use akidb_service::CollectionService;
+use akidb_service::{BatchDeleteStatus, CollectionOptions, CollectionService, FilterTree};
 use akidb_storage::StorageConfig;
 use sqlx::SqlitePool;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -1092,3 +1092,193 @@ async fn test_concurrent_collection_recovery() {
         );
     }
 }
+
+#[tokio::test]
+async fn test_normalized_collection() {
+    let pool = setup_test_db().await;
+    let service1 = setup_service(&pool).await;
+    let database_id = service1.default_database_id().await;
+
+    let collection_id = service1
+        .create_collection_in(
+            database_id,
+            "normalized".to_string(),
+            16,
+            DistanceMetric::L2,
+            None,
+            CollectionOptions {
+                normalize: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let mut vector = vec![0.0; 16];
+    vector[0] = 3.0;
+    vector[1] = 4.0;
+    let doc_id = service1
+        .insert(
+            collection_id,
+            VectorDocument::new(DocumentId::new(), vector),
+        )
+        .await
+        .unwrap();
+    let stored = service1.get(collection_id, doc_id).await.unwrap().unwrap();
+    assert!((stored.vector[0] - 0.6).abs() < 1e-6);
+    assert!((stored.vector[1] - 0.8).abs() < 1e-6);
+
+    // Zero vectors have no direction to keep
+    let results = service1
+        .upsert_batch(
+            collection_id,
+            vec![VectorDocument::new(DocumentId::new(), vec![0.0; 16])],
+        )
+        .await
+        .unwrap();
+    assert!(results[0].is_err());
+
+    // The setting survives a restart
+    drop(service1);
+    let service2 = setup_service(&pool).await;
+    service2.load_all_collections().await.unwrap();
+    assert!(
+        service2
+            .get_collection(collection_id)
+            .await
+            .unwrap()
+            .normalize
+    );
+
+    // Queries are normalized too, so a scaled copy is an exact match
+
+    let mut query = vec![0.0; 16];
+    query[0] = 30.0;
+    query[1] = 40.0;
+    let results = service2.query(collection_id, query, 1).await.unwrap();
+    assert_eq!(results[0].doc_id, doc_id);
+    assert!(results[0].score.abs() < 1e-5);
+}
+
+#[tokio::test]
+async fn test_quantized_collection() {
+    let pool = setup_test_db().await;
+    let service1 = setup_service(&pool).await;
+    let database_id = service1.default_database_id().await;
+
+    let collection_id = service1
+        .create_collection_in(
+            database_id,
+            "quantized".to_string(),
+            16,
+            DistanceMetric::Cosine,
+            None,
+            CollectionOptions {
+                quantization: Quantization::Int8,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let vectors: Vec<Vec<f32>> = (0..20)
+        .map(|i| (0..16).map(|j| ((i * 16 + j) as f32).sin()).collect())
+        .collect();
+    let mut doc_ids = Vec::new();
+    for vector in &vectors {
+        let doc_id = service1
+            .insert(
+                collection_id,
+                VectorDocument::new(DocumentId::new(), vector.clone()),
+            )
+            .await
+            .unwrap();
+        doc_ids.push(doc_id);
+    }
+
+    // The setting survives a restart, and the index is rebuilt quantized
+    drop(service1);
+    let service2 = setup_service(&pool).await;
+    service2.load_all_collections().await.unwrap();
+    assert_eq!(
+        service2
+            .get_collection(collection_id)
+            .await
+            .unwrap()
+            .quantization,
+        Quantization::Int8
+    );
+
+    let results = service2
+        .query(collection_id, vectors[5].clone(), 3)
+        .await
+        .unwrap();
+    assert_eq!(results[0].doc_id, doc_ids[5]);
+    assert!((results[0].score - 1.0).abs() < 1e-3);
+
+    // Index reads return the dequantized vector
+    let stored = service2
+        .get(collection_id, doc_ids[5])
+        .await
+        .unwrap()
+        .unwrap();
+    for (original, restored) in vectors[5].iter().zip(&stored.vector) {
+        assert!((original - restored).abs() < 0.01);
+    }
+}
+
+#[tokio::test]
+async fn test_delete_by_filter_survives_restart() {
+    let pool = setup_test_db().await;
+    let service1 = setup_service(&pool).await;
+
+    let collection_id = service1
+        .create_collection(
+            "filter-delete".to_string(),
+            16,
+            DistanceMetric::Cosine,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let mut doc_ids = Vec::new();
+    for i in 0..10 {
+        let doc = VectorDocument::new(DocumentId::new(), vec![0.1 * (i + 1) as f32; 16])
+            .with_metadata(serde_json::json!({"shard": i % 2}));
+        doc_ids.push(service1.insert(collection_id, doc).await.unwrap());
+    }
+
+    let filter = FilterTree::parse(&serde_json::json!({"shard": 1})).unwrap();
+    let statuses = service1
+        .delete_by_filter(collection_id, &filter)
+        .await
+        .unwrap();
+    assert_eq!(statuses.len(), 5);
+    assert!(statuses
+        .iter()
+        .all(|(_, status)| matches!(status, BatchDeleteStatus::Deleted)));
+    assert_eq!(service1.get_count(collection_id).await.unwrap(), 5);
+
+    // Batch delete reports already-deleted IDs as not found
+    let statuses = service1
+        .delete_batch(collection_id, vec![doc_ids[0], doc_ids[1]])
+        .await
+        .unwrap();
+    assert!(matches!(statuses[0].1, BatchDeleteStatus::Deleted));
+    assert!(matches!(statuses[1].1, BatchDeleteStatus::NotFound));
+
+    drop(service1);
+    let service2 = setup_service(&pool).await;
+    service2.load_all_collections().await.unwrap();
+
+    assert_eq!(service2.get_count(collection_id).await.unwrap(), 4);
+    for (i, doc_id) in doc_ids.iter().enumerate() {
+        let present = service2
+            .get(collection_id, *doc_id)
+            .await
+            .unwrap()
+            .is_some();
+        assert_eq!(present, i != 0 && i % 2 == 0, "document {}", i);
+    }
+}
@@ -0,0 +1,107 @@
+//! Leader election between processes sharing a metadata database, simulated
+//! by several elections over one SQLite file.
+
+use akidb_core::LeaseRepository;
+use akidb_metadata::{create_sqlite_pool, run_migrations, SqliteLeaseRepository};
+use akidb_service::LeaderElection;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+async fn lease_repository(dir: &TempDir) -> Arc<dyn LeaseRepository> {
+    let url = format!("sqlite://{}", dir.path().join("metadata.db").display());
+    let pool = create_sqlite_pool(&url).await.unwrap();
+    run_migrations(&pool).await.unwrap();
+    Arc::new(SqliteLeaseRepository::new(pool))
+}
+
+#[tokio::test]
+async fn test_one_leader_until_it_resigns() {
+    let dir = TempDir::new().unwrap();
+    let repository = lease_repository(&dir).await;
+    let ttl = Duration::from_secs(30);
+    let a = LeaderElection::new(repository.clone(), "gc", "node-a", ttl);
+    let b = LeaderElection::new(repository, "gc", "node-b", ttl);
+    let mut a_changes = a.subscribe();
+
+    assert!(a.campaign().await.unwrap());
+    assert!(!b.campaign().await.unwrap());
+    assert!(a.is_leader());
+    assert!(!b.is_leader());
+    assert!(*a_changes.borrow_and_update());
+
+    // Renewing keeps the role
+    assert!(a.campaign().await.unwrap());
+
+    a.resign().await.unwrap();
+    assert!(!a.is_leader());
+    assert!(!*a_changes.borrow_and_update());
+    assert!(b.campaign().await.unwrap());
+    assert!(!a.campaign().await.unwrap());
+
+    let lease = b.current().await.unwrap().unwrap();
+    assert_eq!(lease.holder, "node-b");
+    assert_eq!(lease.token, 2);
+}
+
+#[tokio::test]
+async fn test_leadership_lapses_without_renewal() {
+    let dir = TempDir::new().unwrap();
+    let repository = lease_repository(&dir).await;
+    let a = LeaderElection::new(
+        repository.clone(),
+        "tiering",
+        "node-a",
+        Duration::from_millis(100),
+    );
+    let b = LeaderElection::new(repository, "tiering", "node-b", Duration::from_secs(30));
+
+    assert!(a.campaign().await.unwrap());
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    // A crashed or partitioned leader stops counting itself as leader
+    assert!(!a.is_leader());
+    assert!(b.campaign().await.unwrap());
+    assert!(!a.campaign().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_singleton_runs_on_leader_only() {
+    let dir = TempDir::new().unwrap();
+    let repository = lease_repository(&dir).await;
+    let ttl = Duration::from_millis(300);
+    let a = Arc::new(LeaderElection::new(
+        repository.clone(),
+        "backup",
+        "node-a",
+        ttl,
+    ));
+    let b = Arc::new(LeaderElection::new(repository, "backup", "node-b", ttl));
+
+    // node-a campaigns first, so it wins
+    assert!(a.campaign().await.unwrap());
+    let mut tasks = vec![a.spawn(), b.spawn()];
+
+    let runs: Vec<Arc<AtomicUsize>> = (0..2).map(|_| Arc::new(AtomicUsize::new(0))).collect();
+    for (election, counter) in [&a, &b].into_iter().zip(&runs) {
+        let counter = Arc::clone(counter);
+        tasks.push(
+            election.spawn_singleton("backup", Duration::from_millis(20), move || {
+                let counter = Arc::clone(&counter);
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            }),
+        );
+    }
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(runs[0].load(Ordering::SeqCst) > 0);
+    assert_eq!(runs[1].load(Ordering::SeqCst), 0);
+
+    for task in tasks {
+        task.abort();
+    }
+}
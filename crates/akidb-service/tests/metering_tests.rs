@@ -0,0 +1,104 @@
+//! Per-tenant usage metering and hourly rollups.
+
+use akidb_core::{
+    DatabaseDescriptor, DatabaseRepository, DistanceMetric, DocumentId, TenantCatalog,
+    TenantDescriptor, TenantId, VectorDocument,
+};
+use akidb_metadata::{
+    create_sqlite_pool, run_migrations, SqliteDatabaseRepository, SqliteTenantCatalog,
+    SqliteUsageRepository,
+};
+use akidb_service::{CollectionService, StorageConfig, UsageMeter};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+struct Fixture {
+    service: CollectionService,
+    tenant_id: TenantId,
+    _dir: TempDir,
+}
+
+async fn fixture() -> Fixture {
+    let dir = TempDir::new().unwrap();
+    let url = format!("sqlite://{}", dir.path().join("metadata.db").display());
+    let pool = create_sqlite_pool(&url).await.unwrap();
+    run_migrations(&pool).await.unwrap();
+
+    let tenant = TenantDescriptor::new("Metered Corp", "metered-corp");
+    SqliteTenantCatalog::new(pool.clone())
+        .create(&tenant)
+        .await
+        .unwrap();
+    let database = DatabaseDescriptor::new(tenant.tenant_id, "default", None);
+    let databases = Arc::new(SqliteDatabaseRepository::new(pool.clone()));
+    databases.create(&database).await.unwrap();
+
+    let service = CollectionService::new()
+        .with_storage_config(StorageConfig::memory(dir.path().join("akidb.wal")))
+        .with_metering(Arc::new(UsageMeter::new(
+            Arc::new(SqliteUsageRepository::new(pool)),
+            databases,
+        )));
+    service.set_default_database_id(database.database_id).await;
+
+    Fixture {
+        service,
+        tenant_id: tenant.tenant_id,
+        _dir: dir,
+    }
+}
+
+fn doc() -> VectorDocument {
+    VectorDocument::new(DocumentId::new(), vec![0.5; 16])
+}
+
+#[tokio::test]
+async fn test_usage_is_rolled_up_per_tenant() {
+    let f = fixture().await;
+    let collection_id = f
+        .service
+        .create_collection("docs".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+
+    f.service.insert(collection_id, doc()).await.unwrap();
+    f.service
+        .upsert_batch(collection_id, vec![doc(), doc()])
+        .await
+        .unwrap();
+    for _ in 0..4 {
+        f.service
+            .query(collection_id, vec![0.5; 16], 2)
+            .await
+            .unwrap();
+    }
+    f.service.record_embedding_usage(None, 100).await;
+    f.service.flush_usage().await.unwrap();
+
+    // A second flush adds only what was counted since the first
+    f.service
+        .query(collection_id, vec![0.5; 16], 2)
+        .await
+        .unwrap();
+    f.service.flush_usage().await.unwrap();
+
+    let now = Utc::now();
+    let usage = f
+        .service
+        .usage_meter()
+        .unwrap()
+        .list(
+            Some(f.tenant_id),
+            now - Duration::hours(2),
+            now + Duration::hours(1),
+        )
+        .await
+        .unwrap();
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].tenant_id, f.tenant_id);
+    assert_eq!(usage[0].queries, 5);
+    assert_eq!(usage[0].vectors_inserted, 3);
+    assert_eq!(usage[0].stored_bytes, 3 * 16 * 4);
+    assert_eq!(usage[0].embedding_tokens, 100);
+}
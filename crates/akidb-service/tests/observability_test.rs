@@ -7,7 +7,7 @@
 //! - Metrics accuracy
 
 use akidb_core::{DistanceMetric, DocumentId, VectorDocument};
-use akidb_service::{CollectionService, ServiceMetrics};
+use akidb_service::{CollectionService, ServiceMetrics, StorageConfig};
 
 #[tokio::test]
 async fn test_service_metrics_structure() {
@@ -52,9 +52,9 @@ async fn test_metrics_export_prometheus_format() {
 
 #[tokio::test]
 async fn test_vector_operations_record_metrics() {
-    
-
-    let service = CollectionService::new();
+    let dir = tempfile::tempdir().unwrap();
+    let service = CollectionService::new()
+        .with_storage_config(StorageConfig::memory(dir.path().join("akidb.wal")));
 
     // Create collection
     let collection_id = service
@@ -116,7 +116,7 @@ async fn test_all_core_metrics_registered() {
     // Actually USE each metric to force lazy_static initialization
     use akidb_service::metrics::*;
     HTTP_REQUESTS_TOTAL.with_label_values(&["TEST", "/test", "200"]).inc();
-    HTTP_REQUEST_DURATION_SECONDS.with_label_values(&["TEST", "/test"]).observe(0.001);
+    HTTP_REQUEST_DURATION_SECONDS.with_label_values(&["TEST", "/test", "200", "default"]).observe(0.001);
     GRPC_REQUESTS_TOTAL.with_label_values(&["test_service", "test_method", "ok"]).inc();
     GRPC_REQUEST_DURATION_SECONDS.with_label_values(&["test_service", "test_method"]).observe(0.001);
     VECTOR_SEARCH_DURATION_SECONDS.with_label_values(&["hot"]).observe(0.001);
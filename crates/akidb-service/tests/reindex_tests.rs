@@ -0,0 +1,152 @@
+//! Reindex jobs: the rebuilt index is swapped in without losing writes.
+
+use akidb_core::{
+    CollectionId, CollectionRepository, DistanceMetric, DocumentId, JobDescriptor, JobId,
+    JobStatus, VectorDocument,
+};
+use akidb_metadata::SqliteCollectionRepository;
+use akidb_service::{CollectionService, ReindexOptions, SearchOptions};
+use std::time::Duration;
+use tempfile::TempDir;
+
+mod common;
+
+fn doc(i: usize) -> VectorDocument {
+    let mut vector = vec![0.1; 16];
+    vector[i % 16] += 1.0 + i as f32 / 100.0;
+    VectorDocument::new(DocumentId::new(), vector)
+}
+
+async fn insert_docs(
+    service: &CollectionService,
+    collection_id: CollectionId,
+    range: std::ops::Range<usize>,
+) -> Vec<DocumentId> {
+    let mut doc_ids = Vec::new();
+    for i in range {
+        doc_ids.push(service.insert(collection_id, doc(i)).await.unwrap());
+    }
+    doc_ids
+}
+
+/// Waits for the job to finish.
+async fn wait_for(service: &CollectionService, job_id: JobId) -> JobDescriptor {
+    tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            let job = service.jobs().get(job_id).await.unwrap().unwrap();
+            if job.status.is_terminal() {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("reindex job did not finish")
+}
+
+#[tokio::test]
+async fn test_reindex_keeps_concurrent_writes() {
+    let dir = TempDir::new().unwrap();
+    let common::TestService { service, pool, .. } =
+        common::setup_service("test-reindex", &dir).await;
+    let repository = SqliteCollectionRepository::new(pool);
+    let collection_id = service
+        .create_collection("docs".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+    let mut doc_ids = insert_docs(&service, collection_id, 0..200).await;
+
+    // Small enough for brute-force search, with new HNSW settings
+    let options = ReindexOptions {
+        hnsw_m: Some(16),
+        hnsw_ef_construction: Some(100),
+        max_doc_count: Some(5_000),
+    };
+    let job_id = service
+        .reindex_collection(collection_id, options)
+        .await
+        .unwrap();
+
+    // Writes keep going to the current index during the rebuild
+    doc_ids.extend(insert_docs(&service, collection_id, 200..300).await);
+    let deleted = doc_ids.remove(0);
+    service.delete(collection_id, deleted).await.unwrap();
+
+    let job = wait_for(&service, job_id).await;
+    assert_eq!(job.status, JobStatus::Succeeded, "{:?}", job.error);
+    assert_eq!(job.kind, "reindex");
+    assert_eq!(job.progress, job.total.unwrap());
+
+    // Writes after the swap go to the new index
+    doc_ids.extend(insert_docs(&service, collection_id, 300..310).await);
+
+    assert_eq!(service.get_count(collection_id).await.unwrap(), 309);
+    let results = service
+        .search(
+            collection_id,
+            doc(305).vector,
+            400,
+            &SearchOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 309);
+    assert!(results.iter().all(|r| r.doc_id != deleted));
+    assert_eq!(results[0].doc_id, doc_ids[304]);
+
+    // The new settings are persisted
+    let stored = repository.get(collection_id).await.unwrap().unwrap();
+    assert_eq!(
+        (
+            stored.hnsw_m,
+            stored.hnsw_ef_construction,
+            stored.max_doc_count
+        ),
+        (16, 100, 5_000)
+    );
+}
+
+#[tokio::test]
+async fn test_reindex_rejects_invalid_requests() {
+    let dir = TempDir::new().unwrap();
+    let service = common::setup_service("test-reindex", &dir).await.service;
+    let collection_id = service
+        .create_collection("docs".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+    insert_docs(&service, collection_id, 0..10).await;
+
+    let out_of_range = ReindexOptions {
+        hnsw_m: Some(1),
+        ..ReindexOptions::default()
+    };
+    assert!(service
+        .reindex_collection(collection_id, out_of_range)
+        .await
+        .is_err());
+    assert!(service
+        .reindex_collection(CollectionId::new(), ReindexOptions::default())
+        .await
+        .is_err());
+
+    // One reindex per collection at a time
+    let job_id = service
+        .reindex_collection(collection_id, ReindexOptions::default())
+        .await
+        .unwrap();
+    let second = service
+        .reindex_collection(collection_id, ReindexOptions::default())
+        .await;
+    let job = wait_for(&service, job_id).await;
+    assert_eq!(job.status, JobStatus::Succeeded, "{:?}", job.error);
+    assert!(second.is_err());
+
+    let job_id = service
+        .reindex_collection(collection_id, ReindexOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        wait_for(&service, job_id).await.status,
+        JobStatus::Succeeded
+    );
+}
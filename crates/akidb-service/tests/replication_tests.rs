@@ -0,0 +1,326 @@
+//! WAL shipping tests: a standby service follows a primary service in-process.
+
+use akidb_core::{DistanceMetric, DocumentId, VectorDocument};
+use akidb_metadata::{SqliteCollectionRepository, VectorPersistence};
+use akidb_service::{
+    CollectionService, ReplicaApplier, ReplicationConfig, ReplicationEvent, ReplicationMonitor,
+    ReplicationRule, ReplicationRules, WalShipper,
+};
+use akidb_storage::StorageConfig;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+/// Service with its own database and WAL directory
+async fn setup_node(dir: &TempDir) -> Arc<CollectionService> {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::migrate!("../akidb-metadata/migrations")
+        .run(&pool)
+        .await
+        .unwrap();
+
+    let service = Arc::new(CollectionService::with_storage(
+        Arc::new(SqliteCollectionRepository::new(pool.clone())),
+        Arc::new(VectorPersistence::new(pool.clone())),
+        StorageConfig::memory(dir.path().join("akidb.wal")),
+    ));
+
+    let tenant_id = akidb_core::TenantId::new();
+    sqlx::query(
+        "INSERT INTO tenants (tenant_id, name, slug, status, created_at, updated_at)
+         VALUES (?1, 'test-tenant', 'test-replication', 'active', datetime('now'), datetime('now'))",
+    )
+    .bind(&tenant_id.to_bytes()[..])
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let database_id = akidb_core::DatabaseId::new();
+    sqlx::query(
+        "INSERT INTO databases (database_id, tenant_id, name, state, created_at, updated_at)
+         VALUES (?1, ?2, 'test-database', 'ready', datetime('now'), datetime('now'))",
+    )
+    .bind(&database_id.to_bytes()[..])
+    .bind(&tenant_id.to_bytes()[..])
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    service.set_default_database_id(database_id).await;
+    service
+}
+
+fn test_doc(id: &str) -> VectorDocument {
+    let mut vector = vec![0.1; 32];
+    vector[0] = id.len() as f32;
+    VectorDocument::new(DocumentId::new(), vector)
+        .with_external_id(id.to_string())
+        .with_metadata(serde_json::json!({ "id": id }))
+}
+
+/// Applies events until a heartbeat, returning how many entries were applied.
+async fn apply_until_heartbeat(
+    rx: &mut mpsc::Receiver<ReplicationEvent>,
+    applier: &ReplicaApplier,
+) -> usize {
+    let mut entries = 0;
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("no heartbeat from the primary")
+            .expect("stream closed");
+        let heartbeat = matches!(event, ReplicationEvent::Heartbeat { .. });
+        if matches!(event, ReplicationEvent::Entry { .. }) {
+            entries += 1;
+        }
+        applier.apply(event).await.unwrap();
+        if heartbeat {
+            return entries;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_standby_follows_primary() {
+    let (primary_dir, standby_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let primary = setup_node(&primary_dir).await;
+    let standby = setup_node(&standby_dir).await;
+
+    let collection_id = primary
+        .create_collection("replicated".to_string(), 32, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+    let kept = primary.insert(collection_id, test_doc("a")).await.unwrap();
+    let deleted = primary.insert(collection_id, test_doc("bb")).await.unwrap();
+    primary.delete(collection_id, deleted).await.unwrap();
+
+    let shipper = WalShipper::new(primary.clone(), Duration::from_millis(50));
+    let applier = ReplicaApplier::new(standby.clone());
+    let mut rx = shipper.stream(applier.positions());
+
+    assert_eq!(apply_until_heartbeat(&mut rx, &applier).await, 3);
+    let replica = standby.get_collection(collection_id).await.unwrap();
+    assert_eq!(replica.name, "replicated");
+    assert_eq!(standby.get_count(collection_id).await.unwrap(), 1);
+    let doc = standby.get(collection_id, kept).await.unwrap().unwrap();
+    assert_eq!(doc.external_id.as_deref(), Some("a"));
+    assert_eq!(doc.metadata, Some(serde_json::json!({ "id": "a" })));
+    assert!(standby.get(collection_id, deleted).await.unwrap().is_none());
+
+    let primary_lsn = primary.wal_state(collection_id).await.unwrap().current_lsn;
+    assert_eq!(applier.positions()[&collection_id], primary_lsn);
+    assert!(applier.status().last_heartbeat.is_some());
+
+    // New writes are shipped as they happen
+    let later = primary
+        .insert(collection_id, test_doc("ccc"))
+        .await
+        .unwrap();
+    assert_eq!(apply_until_heartbeat(&mut rx, &applier).await, 1);
+    assert!(standby.get(collection_id, later).await.unwrap().is_some());
+
+    primary.delete_collection(collection_id).await.unwrap();
+    apply_until_heartbeat(&mut rx, &applier).await;
+    assert!(standby.get_collection(collection_id).await.is_err());
+    assert!(applier.positions().is_empty());
+}
+
+#[tokio::test]
+async fn test_standby_resumes_from_saved_positions() {
+    let (primary_dir, standby_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let primary = setup_node(&primary_dir).await;
+    let standby = setup_node(&standby_dir).await;
+    let state_path = standby_dir.path().join("replication_state.json");
+
+    let collection_id = primary
+        .create_collection("resumed".to_string(), 32, DistanceMetric::L2, None)
+        .await
+        .unwrap();
+    for id in ["a", "bb"] {
+        primary.insert(collection_id, test_doc(id)).await.unwrap();
+    }
+
+    let shipper = WalShipper::new(primary.clone(), Duration::from_millis(50));
+    {
+        let applier = ReplicaApplier::new(standby.clone())
+            .with_state_path(&state_path)
+            .unwrap();
+        let mut rx = shipper.stream(applier.positions());
+        assert_eq!(apply_until_heartbeat(&mut rx, &applier).await, 2);
+    }
+
+    // Written while the standby is disconnected
+    primary
+        .insert(collection_id, test_doc("ccc"))
+        .await
+        .unwrap();
+
+    // Only the missed entry is shipped after reconnecting
+    let applier = ReplicaApplier::new(standby.clone())
+        .with_state_path(&state_path)
+        .unwrap();
+    assert_eq!(applier.positions()[&collection_id], 2);
+    let mut rx = shipper.stream(applier.positions());
+    assert_eq!(apply_until_heartbeat(&mut rx, &applier).await, 1);
+    assert_eq!(standby.get_count(collection_id).await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn test_standby_ahead_of_primary_gets_full_copy() {
+    let (primary_dir, standby_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let primary = setup_node(&primary_dir).await;
+    let standby = setup_node(&standby_dir).await;
+
+    let collection_id = primary
+        .create_collection("copied".to_string(), 32, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+    for id in ["a", "bb", "ccc"] {
+        primary.insert(collection_id, test_doc(id)).await.unwrap();
+    }
+
+    // A position the primary's WAL never reached cannot be resumed from
+    let shipper = WalShipper::new(primary.clone(), Duration::from_millis(50));
+    let applier = ReplicaApplier::new(standby.clone());
+    let mut rx = shipper.stream([(collection_id, 999)].into_iter().collect());
+
+    let first = rx.recv().await.unwrap();
+    assert!(matches!(
+        first,
+        ReplicationEvent::Collection { reset: true, .. }
+    ));
+    applier.apply(first).await.unwrap();
+    assert_eq!(apply_until_heartbeat(&mut rx, &applier).await, 3);
+    assert_eq!(standby.get_count(collection_id).await.unwrap(), 3);
+    assert_eq!(applier.positions()[&collection_id], 3);
+}
+
+#[tokio::test]
+async fn test_monitor_reports_lag_from_state_file() {
+    let (primary_dir, standby_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let primary = setup_node(&primary_dir).await;
+    let standby = setup_node(&standby_dir).await;
+
+    let collection_id = primary
+        .create_collection("monitored".to_string(), 32, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+    primary.insert(collection_id, test_doc("a")).await.unwrap();
+
+    let standby_config = ReplicationConfig {
+        role: "standby".to_string(),
+        primary_url: Some("http://primary:9090".to_string()),
+        state_path: standby_dir.path().join("replication_state.json"),
+        ..Default::default()
+    };
+    let monitor = ReplicationMonitor::new(standby.clone(), standby_config.clone());
+
+    // Nothing applied yet
+    let report = monitor.report().await;
+    assert_eq!(report.status, "unhealthy");
+    assert!(!report.is_healthy());
+
+    let applier = ReplicaApplier::new(standby.clone())
+        .with_state_path(&standby_config.state_path)
+        .unwrap();
+    let mut rx =
+        WalShipper::new(primary.clone(), Duration::from_millis(50)).stream(applier.positions());
+    apply_until_heartbeat(&mut rx, &applier).await;
+
+    let report = monitor.report().await;
+    assert_eq!(report.status, "healthy");
+    assert!(report.lag_seconds.unwrap() < 5.0);
+    assert_eq!(report.collections.len(), 1);
+    assert_eq!(
+        report.collections[0].lsn,
+        applier.positions()[&collection_id]
+    );
+
+    // Lag beyond the limit
+    let strict = ReplicationMonitor::new(
+        standby.clone(),
+        ReplicationConfig {
+            max_lag_seconds: 0,
+            ..standby_config
+        },
+    );
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(strict.report().await.status, "unhealthy");
+
+    // The primary reports its own WAL positions
+    let primary_config = ReplicationConfig {
+        role: "primary".to_string(),
+        ..Default::default()
+    };
+    let report = ReplicationMonitor::new(primary.clone(), primary_config)
+        .report()
+        .await;
+    assert_eq!(report.status, "healthy");
+    assert_eq!(report.collections[0].collection_id, collection_id);
+    assert_eq!(
+        report.collections[0].lsn,
+        primary.wal_state(collection_id).await.unwrap().current_lsn
+    );
+}
+
+#[tokio::test]
+async fn test_rules_select_and_order_collections() {
+    let (primary_dir, standby_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let primary = setup_node(&primary_dir).await;
+    let standby = setup_node(&standby_dir).await;
+
+    let mut ids = HashMap::new();
+    for name in ["archive", "products", "scratch-1"] {
+        let id = primary
+            .create_collection(name.to_string(), 32, DistanceMetric::Cosine, None)
+            .await
+            .unwrap();
+        primary.insert(id, test_doc(name)).await.unwrap();
+        ids.insert(name, id);
+    }
+
+    let rule = |collections: &str, enabled: bool, priority: i32| ReplicationRule {
+        collections: collections.to_string(),
+        enabled,
+        priority,
+    };
+    let rules = Arc::new(ReplicationRules::new(vec![
+        rule("scratch-*", false, 10),
+        rule("prod*", true, 5),
+        rule("*", true, 0),
+    ]));
+    let shipper =
+        WalShipper::new(primary.clone(), Duration::from_millis(50)).with_rules(rules.clone());
+    let applier = ReplicaApplier::new(standby.clone());
+    let mut rx = shipper.stream(applier.positions());
+
+    // Higher priority collections are shipped first
+    let first = rx.recv().await.unwrap();
+    match &first {
+        ReplicationEvent::Collection { descriptor, .. } => {
+            assert_eq!(descriptor.name, "products")
+        }
+        other => panic!("expected a collection, got {:?}", other),
+    }
+    applier.apply(first).await.unwrap();
+    apply_until_heartbeat(&mut rx, &applier).await;
+
+    assert!(standby.get_collection(ids["products"]).await.is_ok());
+    assert!(standby.get_collection(ids["archive"]).await.is_ok());
+    assert!(standby.get_collection(ids["scratch-1"]).await.is_err());
+
+    // Rule changes apply to the open stream
+    rules.set(Vec::new()).unwrap();
+    apply_until_heartbeat(&mut rx, &applier).await;
+    assert_eq!(standby.get_count(ids["scratch-1"]).await.unwrap(), 1);
+
+    // Excluding a collection again leaves the standby copy alone
+    rules.set(vec![rule("products", true, 0)]).unwrap();
+    apply_until_heartbeat(&mut rx, &applier).await;
+    assert!(standby.get_collection(ids["archive"]).await.is_ok());
+
+    assert!(rules.set(vec![rule("a*b", true, 0)]).is_err());
+}
@@ -0,0 +1,117 @@
+//! Point-in-time restore tests: storage and index are rolled back together.
+
+use akidb_core::{DistanceMetric, DocumentId, VectorDocument};
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+mod common;
+
+#[tokio::test]
+async fn test_restore_collection_to_point_in_time() {
+    let dir = TempDir::new().unwrap();
+    let service = common::setup_service("test-restore", &dir).await.service;
+    let collection_id = service
+        .create_collection("events".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+
+    let kept = VectorDocument::new(DocumentId::new(), vec![0.1; 16]);
+    let removed = VectorDocument::new(DocumentId::new(), vec![0.2; 16]);
+    service.insert(collection_id, kept.clone()).await.unwrap();
+    service
+        .insert(collection_id, removed.clone())
+        .await
+        .unwrap();
+    service.snapshot_collection(collection_id).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let target = Utc::now();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+
+    // The mistake to undo
+    service.delete(collection_id, removed.doc_id).await.unwrap();
+    let added = VectorDocument::new(DocumentId::new(), vec![0.3; 16]);
+    service.insert(collection_id, added.clone()).await.unwrap();
+
+    let report = service
+        .restore_collection(collection_id, target)
+        .await
+        .unwrap();
+    assert_eq!(report.documents, 2);
+    assert_eq!(report.upserted, 1);
+    assert_eq!(report.deleted, 1);
+
+    assert_eq!(service.get_count(collection_id).await.unwrap(), 2);
+    for doc_id in [kept.doc_id, removed.doc_id] {
+        assert!(service.get(collection_id, doc_id).await.unwrap().is_some());
+    }
+    assert!(service
+        .get(collection_id, added.doc_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Restoring the same point again changes nothing
+    let report = service
+        .restore_collection(collection_id, target)
+        .await
+        .unwrap();
+    assert_eq!((report.upserted, report.deleted), (0, 0));
+}
+
+#[tokio::test]
+async fn test_restore_keeps_writes_made_while_it_runs() {
+    let dir = TempDir::new().unwrap();
+    let service = common::setup_service("test-restore", &dir).await.service;
+    let collection_id = service
+        .create_collection("events".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+
+    for i in 0..200 {
+        let doc = VectorDocument::new(DocumentId::new(), vec![i as f32 / 200.0 + 0.01; 16]);
+        service.insert(collection_id, doc).await.unwrap();
+    }
+    service.snapshot_collection(collection_id).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let target = Utc::now();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let rolled_back = VectorDocument::new(DocumentId::new(), vec![0.5; 16]);
+    service
+        .insert(collection_id, rolled_back.clone())
+        .await
+        .unwrap();
+
+    let restore = tokio::spawn({
+        let service = Arc::clone(&service);
+        async move { service.restore_collection(collection_id, target).await }
+    });
+    // Let the restore start, then write while it runs
+    tokio::task::yield_now().await;
+    assert!(!restore.is_finished());
+    let concurrent = VectorDocument::new(DocumentId::new(), vec![0.7; 16]);
+    service
+        .insert(collection_id, concurrent.clone())
+        .await
+        .unwrap();
+    // The write waited for the restore to finish rolling back the index
+    assert!(service
+        .get(collection_id, rolled_back.doc_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    let report = restore.await.unwrap().unwrap();
+    assert_eq!(report.deleted, 1);
+
+    // It lands after the restore and is kept
+    assert_eq!(service.get_count(collection_id).await.unwrap(), 201);
+    assert!(service
+        .get(collection_id, concurrent.doc_id)
+        .await
+        .unwrap()
+        .is_some());
+}
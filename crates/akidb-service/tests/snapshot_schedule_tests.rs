@@ -0,0 +1,122 @@
+//! Scheduled snapshot tests: schedules run as jobs and prune old snapshots.
+
+use akidb_core::{CollectionId, DistanceMetric, DocumentId, JobId, JobStatus, VectorDocument};
+use akidb_service::{CollectionService, SnapshotSchedule, SnapshotScheduler};
+use std::time::Duration;
+use tempfile::TempDir;
+
+mod common;
+
+async fn insert_docs(service: &CollectionService, collection_id: CollectionId, count: usize) {
+    for _ in 0..count {
+        let doc = VectorDocument::new(DocumentId::new(), vec![0.1; 16]);
+        service.insert(collection_id, doc).await.unwrap();
+    }
+}
+
+/// Waits for the jobs to finish, asserting they succeeded.
+async fn wait_for(service: &CollectionService, job_ids: &[JobId]) {
+    for job_id in job_ids {
+        let job = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let job = service.jobs().get(*job_id).await.unwrap().unwrap();
+                if matches!(job.status, JobStatus::Succeeded | JobStatus::Failed) {
+                    return job;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("snapshot job did not finish");
+        assert_eq!(job.status, JobStatus::Succeeded, "{:?}", job.error);
+        assert_eq!(job.kind, "snapshot");
+    }
+}
+
+#[tokio::test]
+async fn test_snapshot_every_n_ops_keeps_last_k() {
+    let dir = TempDir::new().unwrap();
+    let service = common::setup_service("test-snapshots", &dir).await.service;
+    let collection_id = service
+        .create_collection("events".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+    let unscheduled = service
+        .create_collection("scratch".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+    insert_docs(&service, collection_id, 1).await;
+    insert_docs(&service, unscheduled, 5).await;
+
+    let scheduler = SnapshotScheduler::new(
+        service.clone(),
+        vec![SnapshotSchedule {
+            collections: "events".to_string(),
+            interval_seconds: None,
+            every_ops: Some(3),
+            keep_last: 2,
+        }],
+    );
+    // Writes are counted from the first check
+    assert!(scheduler.run_due().await.unwrap().is_empty());
+
+    for round in 1..=3 {
+        insert_docs(&service, collection_id, 2).await;
+        assert!(scheduler.run_due().await.unwrap().is_empty());
+        insert_docs(&service, collection_id, 1).await;
+        let jobs = scheduler.run_due().await.unwrap();
+        assert_eq!(jobs.len(), 1, "round {}", round);
+        wait_for(&service, &jobs).await;
+    }
+
+    let snapshots = service.list_snapshots(collection_id).await.unwrap();
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(snapshots[0].vector_count, 10);
+    assert!(service
+        .list_snapshots(unscheduled)
+        .await
+        .unwrap()
+        .is_empty());
+    // Unlike compaction, scheduled snapshots keep the WAL
+    let wal = service.wal_state(collection_id).await.unwrap();
+    assert_eq!(wal.checkpoint_lsn, 0);
+}
+
+#[tokio::test]
+async fn test_snapshot_interval() {
+    let dir = TempDir::new().unwrap();
+    let service = common::setup_service("test-snapshots", &dir).await.service;
+    let collection_id = service
+        .create_collection("hourly-1".to_string(), 16, DistanceMetric::Cosine, None)
+        .await
+        .unwrap();
+
+    let scheduler = SnapshotScheduler::new(
+        service.clone(),
+        vec![SnapshotSchedule {
+            collections: "hourly-*".to_string(),
+            interval_seconds: Some(3600),
+            every_ops: None,
+            keep_last: 1,
+        }],
+    );
+    // Empty collections are skipped
+    assert!(scheduler.run_due().await.unwrap().is_empty());
+
+    insert_docs(&service, collection_id, 3).await;
+    let jobs = scheduler.run_due().await.unwrap();
+    assert_eq!(jobs.len(), 1);
+    wait_for(&service, &jobs).await;
+
+    // Not due again within the interval, even after a restart
+    assert!(scheduler.run_due().await.unwrap().is_empty());
+    let restarted = SnapshotScheduler::new(
+        service.clone(),
+        scheduler
+            .schedule_for("hourly-1")
+            .cloned()
+            .into_iter()
+            .collect(),
+    );
+    assert!(restarted.run_due().await.unwrap().is_empty());
+}
@@ -0,0 +1,165 @@
+//! Sparse and dense+sparse collections: insert, search and validation.
+
+use akidb_core::{DistanceMetric, DocumentId, SparseVector, VectorDocument, VectorType};
+use akidb_service::{CollectionOptions, SearchOptions};
+use tempfile::TempDir;
+
+mod common;
+
+fn sparse(pairs: &[(u32, f32)]) -> SparseVector {
+    let (indices, values) = pairs.iter().copied().unzip();
+    SparseVector::new(indices, values).unwrap()
+}
+
+#[tokio::test]
+async fn test_sparse_collection_search() {
+    let dir = TempDir::new().unwrap();
+    let common::TestService {
+        service,
+        database_id,
+        ..
+    } = common::setup_service("test-sparse", &dir).await;
+    let options = CollectionOptions {
+        vector_type: VectorType::Sparse,
+        ..CollectionOptions::default()
+    };
+
+    // Sparse collections have no dense dimension
+    assert!(service
+        .create_collection_in(
+            database_id,
+            "bad".to_string(),
+            16,
+            DistanceMetric::Dot,
+            None,
+            options,
+        )
+        .await
+        .is_err());
+    let collection_id = service
+        .create_collection_in(
+            database_id,
+            "splade".to_string(),
+            0,
+            DistanceMetric::Dot,
+            None,
+            options,
+        )
+        .await
+        .unwrap();
+
+    let strong = VectorDocument::new(DocumentId::new(), Vec::new())
+        .with_sparse(sparse(&[(10, 2.0), (42, 1.0)]));
+    let weak = VectorDocument::new(DocumentId::new(), Vec::new())
+        .with_sparse(sparse(&[(42, 0.5), (7, 3.0)]));
+    let unrelated =
+        VectorDocument::new(DocumentId::new(), Vec::new()).with_sparse(sparse(&[(99, 1.0)]));
+    for doc in [&strong, &weak, &unrelated] {
+        service.insert(collection_id, doc.clone()).await.unwrap();
+    }
+
+    // Documents without a sparse vector, or with a dense one, are rejected
+    assert!(service
+        .insert(
+            collection_id,
+            VectorDocument::new(DocumentId::new(), Vec::new())
+        )
+        .await
+        .is_err());
+    assert!(service
+        .insert(
+            collection_id,
+            VectorDocument::new(DocumentId::new(), vec![0.1; 16]).with_sparse(sparse(&[(1, 1.0)]))
+        )
+        .await
+        .is_err());
+
+    let options = SearchOptions {
+        sparse: Some(sparse(&[(10, 1.0), (42, 2.0)])),
+        include_vector: true,
+        ..SearchOptions::default()
+    };
+    let results = service
+        .search(collection_id, Vec::new(), 10, &options)
+        .await
+        .unwrap();
+    let hits: Vec<(DocumentId, f32)> = results.iter().map(|r| (r.doc_id, r.score)).collect();
+    assert_eq!(hits, vec![(strong.doc_id, 4.0), (weak.doc_id, 1.0)]);
+    assert_eq!(results[0].sparse, strong.sparse);
+
+    // A dense query has nothing to search in a sparse collection
+    assert!(service
+        .search(collection_id, vec![0.1; 16], 10, &SearchOptions::default())
+        .await
+        .is_err());
+
+    let stored = service
+        .get(collection_id, strong.doc_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.sparse, strong.sparse);
+
+    service.delete(collection_id, strong.doc_id).await.unwrap();
+    let results = service
+        .search(collection_id, Vec::new(), 10, &options)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].doc_id, weak.doc_id);
+}
+
+#[tokio::test]
+async fn test_dense_and_sparse_collection() {
+    let dir = TempDir::new().unwrap();
+    let common::TestService {
+        service,
+        database_id,
+        ..
+    } = common::setup_service("test-sparse", &dir).await;
+    let collection_id = service
+        .create_collection_in(
+            database_id,
+            "hybrid".to_string(),
+            16,
+            DistanceMetric::Cosine,
+            None,
+            CollectionOptions {
+                vector_type: VectorType::Both,
+                ..CollectionOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    // The sparse vector is optional next to the dense one
+    let with_sparse =
+        VectorDocument::new(DocumentId::new(), vec![0.1; 16]).with_sparse(sparse(&[(3, 1.0)]));
+    let dense_only = VectorDocument::new(DocumentId::new(), vec![0.2; 16]);
+    service
+        .insert(collection_id, with_sparse.clone())
+        .await
+        .unwrap();
+    service
+        .insert(collection_id, dense_only.clone())
+        .await
+        .unwrap();
+
+    let dense = service
+        .search(collection_id, vec![0.1; 16], 10, &SearchOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(dense.len(), 2);
+
+    let options = SearchOptions {
+        sparse: Some(sparse(&[(3, 2.0)])),
+        ..SearchOptions::default()
+    };
+    let results = service
+        .search(collection_id, Vec::new(), 10, &options)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].doc_id, with_sparse.doc_id);
+    assert!((results[0].score - 2.0).abs() < 1e-6);
+}
@@ -168,6 +168,7 @@ mod tests {
             doc_id: DocumentId::new(),
             external_id: None,
             vector,
+            sparse: None,
             metadata: None,
             inserted_at: Utc::now(),
         }
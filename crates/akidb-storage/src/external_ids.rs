@@ -0,0 +1,115 @@
+//! Per-collection index of external IDs
+//!
+//! Maps each document's external ID to its document ID, so upserts by
+//! external ID do not have to scan the collection. Under the S3Only policy
+//! the backend only keeps a cache of the documents, so the index is the only
+//! complete view of the external IDs: it is saved next to the snapshots on
+//! compaction (before the WAL is truncated) and loaded before WAL replay.
+//! The other policies keep every document in memory and rebuild the index
+//! from WAL replay.
+
+use crate::object_store::ObjectStore;
+use akidb_core::{CoreError, CoreResult, DocumentId, VectorDocument};
+use bytes::Bytes;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Default)]
+struct Entries {
+    by_external: HashMap<String, DocumentId>,
+    by_doc: HashMap<DocumentId, String>,
+}
+
+/// External ID to document ID index of one collection
+pub(crate) struct ExternalIdIndex {
+    entries: RwLock<Entries>,
+    // Where the index is saved (S3Only policy only)
+    store: Option<(Arc<dyn ObjectStore>, String)>,
+}
+
+impl ExternalIdIndex {
+    /// Index kept in memory only
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: RwLock::new(Entries::default()),
+            store: None,
+        }
+    }
+
+    /// Index saved to `store` under `key`, starting from the saved copy
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the saved index cannot be read or decoded
+    pub(crate) async fn load(store: Arc<dyn ObjectStore>, key: String) -> CoreResult<Self> {
+        let mut entries = Entries::default();
+        if store.exists(&key).await? {
+            let data = store.get(&key).await?;
+            entries.by_external = serde_json::from_slice(&data).map_err(|e| {
+                CoreError::StorageError(format!("Failed to decode external ID index: {}", e))
+            })?;
+            entries.by_doc = entries
+                .by_external
+                .iter()
+                .map(|(external_id, doc_id)| (*doc_id, external_id.clone()))
+                .collect();
+        }
+        Ok(Self {
+            entries: RwLock::new(entries),
+            store: Some((store, key)),
+        })
+    }
+
+    /// Record `doc` as stored, replacing its previous external ID
+    pub(crate) fn insert(&self, doc: &VectorDocument) {
+        let mut entries = self.entries.write();
+        Self::remove_entry(&mut entries, &doc.doc_id);
+        if let Some(external_id) = &doc.external_id {
+            // An external ID moved to another document no longer names the old one
+            if let Some(previous) = entries.by_external.insert(external_id.clone(), doc.doc_id) {
+                entries.by_doc.remove(&previous);
+            }
+            entries.by_doc.insert(doc.doc_id, external_id.clone());
+        }
+    }
+
+    /// Forget the external ID of a deleted document
+    pub(crate) fn remove(&self, doc_id: &DocumentId) {
+        Self::remove_entry(&mut self.entries.write(), doc_id);
+    }
+
+    fn remove_entry(entries: &mut Entries, doc_id: &DocumentId) {
+        if let Some(external_id) = entries.by_doc.remove(doc_id) {
+            entries.by_external.remove(&external_id);
+        }
+    }
+
+    /// Document IDs of the known `external_ids`
+    pub(crate) fn resolve(&self, external_ids: &[String]) -> HashMap<String, DocumentId> {
+        let entries = self.entries.read();
+        external_ids
+            .iter()
+            .filter_map(|external_id| {
+                entries
+                    .by_external
+                    .get(external_id)
+                    .map(|doc_id| (external_id.clone(), *doc_id))
+            })
+            .collect()
+    }
+
+    /// Save the index, if it is persisted
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the upload fails
+    pub(crate) async fn save(&self) -> CoreResult<()> {
+        let Some((store, key)) = &self.store else {
+            return Ok(());
+        };
+        let data = serde_json::to_vec(&self.entries.read().by_external)
+            .map_err(|e| CoreError::StorageError(e.to_string()))?;
+        store.put(key, Bytes::from(data)).await
+    }
+}
@@ -0,0 +1,235 @@
+//! Garbage collection of per-vector S3 objects
+//!
+//! With the MemoryS3 policy every inserted document is uploaded to
+//! `vectors/{collection_id}/{doc_id}`. Deletes and compactions never remove
+//! those objects, so they accumulate. Garbage collection lists the
+//! collection's prefix and deletes objects whose document is neither live
+//! nor part of a retained snapshot.
+//!
+//! Objects newer than the retention window are always kept: their upload
+//! may have raced with the scan, and they give a grace period before data
+//! becomes unrecoverable from the bucket.
+
+use crate::lock::CollectionLock;
+use crate::object_store::ObjectStore;
+use akidb_core::{CollectionId, CoreResult, DocumentId};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// Garbage collection configuration
+#[derive(Debug, Clone)]
+pub struct GcConfig {
+    /// Collect this backend's objects when the embedding service runs
+    /// garbage collection, which it does only on the elected leader
+    /// (default: false; MemoryS3 policy only)
+    pub enabled: bool,
+    /// Interval between GC runs in seconds (default: 3,600 = 1 hour)
+    pub interval_seconds: u64,
+    /// Minimum age in seconds of an object before it can be deleted
+    /// (default: 86,400 = 1 day)
+    pub retention_seconds: i64,
+}
+
+impl Default for GcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_seconds: 3600,    // 1 hour
+            retention_seconds: 86_400, // 1 day
+        }
+    }
+}
+
+impl GcConfig {
+    /// Objects last modified before this time may be deleted
+    #[must_use]
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::seconds(self.retention_seconds)
+    }
+}
+
+/// Result of one garbage collection pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct GcReport {
+    /// Objects listed under the collection's prefix
+    pub objects_scanned: u64,
+    /// Unreferenced objects deleted
+    pub objects_deleted: u64,
+    /// Bytes freed by the deleted objects
+    pub bytes_deleted: u64,
+}
+
+/// Prefix of a collection's per-vector objects
+#[must_use]
+pub fn vector_prefix(collection_id: CollectionId) -> String {
+    format!("vectors/{}/", collection_id)
+}
+
+/// Document an object under `prefix` belongs to, if its key names one
+fn doc_id_of(key: &str, prefix: &str) -> Option<DocumentId> {
+    let name = key.strip_prefix(prefix)?;
+    let name = name.strip_suffix(".json").unwrap_or(name);
+    DocumentId::from_str(name).ok()
+}
+
+/// Delete the objects under `prefix` whose document is not in `referenced`
+/// and that were last modified before `cutoff`
+///
+/// Keys that do not name a document are left alone. Failed deletes are
+/// logged and retried on the next pass. Each delete first checks that
+/// `locks` are still held.
+///
+/// # Errors
+///
+/// Returns error if the prefix cannot be listed or one of `locks` is lost
+pub async fn collect_garbage(
+    store: &dyn ObjectStore,
+    prefix: &str,
+    referenced: &HashSet<DocumentId>,
+    cutoff: DateTime<Utc>,
+    locks: &[CollectionLock],
+) -> CoreResult<GcReport> {
+    let objects = store.list(prefix).await?;
+    let mut report = GcReport {
+        objects_scanned: objects.len() as u64,
+        ..GcReport::default()
+    };
+
+    for object in objects {
+        let Some(doc_id) = doc_id_of(&object.key, prefix) else {
+            continue;
+        };
+        if referenced.contains(&doc_id) || object.last_modified >= cutoff {
+            continue;
+        }
+        for lock in locks {
+            lock.ensure_held()?;
+        }
+
+        match store.delete(&object.key).await {
+            Ok(()) => {
+                report.objects_deleted += 1;
+                report.bytes_deleted += object.size_bytes;
+            }
+            Err(e) => tracing::warn!("GC failed to delete {}: {}", object.key, e),
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object_store::{LocalObjectStore, MockS3Config, MockS3ObjectStore};
+    use bytes::Bytes;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_doc_id_of() {
+        let collection_id = CollectionId::new();
+        let prefix = vector_prefix(collection_id);
+        let doc_id = DocumentId::new();
+
+        assert_eq!(
+            doc_id_of(&format!("{}{}", prefix, doc_id), &prefix),
+            Some(doc_id)
+        );
+        assert_eq!(
+            doc_id_of(&format!("{}{}.json", prefix, doc_id), &prefix),
+            Some(doc_id)
+        );
+        assert_eq!(doc_id_of(&format!("{}index.bin", prefix), &prefix), None);
+        assert_eq!(doc_id_of(&format!("vectors/{}", doc_id), &prefix), None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = LocalObjectStore::new(temp_dir.path()).await.unwrap();
+        let prefix = vector_prefix(CollectionId::new());
+
+        let (live, orphan) = (DocumentId::new(), DocumentId::new());
+        for key in [
+            format!("{}{}", prefix, live),
+            format!("{}{}", prefix, orphan),
+            format!("{}README", prefix),
+        ] {
+            store.put(&key, Bytes::from_static(b"{}")).await.unwrap();
+        }
+        let referenced = HashSet::from([live]);
+
+        // Everything is within the retention window
+        let report = collect_garbage(
+            &store,
+            &prefix,
+            &referenced,
+            Utc::now() - Duration::hours(1),
+            &[],
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.objects_scanned, 3);
+        assert_eq!(report.objects_deleted, 0);
+
+        let report = collect_garbage(
+            &store,
+            &prefix,
+            &referenced,
+            Utc::now() + Duration::hours(1),
+            &[],
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.objects_scanned, 3);
+        assert_eq!(report.objects_deleted, 1);
+        assert_eq!(report.bytes_deleted, 2);
+        assert!(!store
+            .exists(&format!("{}{}", prefix, orphan))
+            .await
+            .unwrap());
+        assert!(store.exists(&format!("{}{}", prefix, live)).await.unwrap());
+        assert!(store.exists(&format!("{}README", prefix)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_past_first_list_page() {
+        let store = MockS3ObjectStore::new_with_config(MockS3Config {
+            latency: std::time::Duration::ZERO,
+            track_history: true,
+        });
+        let prefix = vector_prefix(CollectionId::new());
+
+        let doc_ids: Vec<DocumentId> = (0..2_500).map(|_| DocumentId::new()).collect();
+        for doc_id in &doc_ids {
+            store
+                .put(&format!("{}{}", prefix, doc_id), Bytes::from_static(b"{}"))
+                .await
+                .unwrap();
+        }
+        let referenced: HashSet<DocumentId> = doc_ids.iter().step_by(2).copied().collect();
+
+        let report = collect_garbage(
+            &store,
+            &prefix,
+            &referenced,
+            Utc::now() + Duration::hours(1),
+            &[],
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.objects_scanned, 2_500);
+        assert_eq!(report.objects_deleted, 1_250);
+        assert_eq!(store.storage_size(), 1_250);
+
+        // Three pages of at most 1,000 keys
+        let pages = store
+            .get_call_history()
+            .iter()
+            .filter(|call| call.operation == "list")
+            .count();
+        assert_eq!(pages, 3);
+    }
+}
@@ -81,9 +81,13 @@ pub mod batch_uploader;
 pub mod circuit_breaker;
 pub mod compression;
 pub mod dlq;
+mod external_ids;
+pub mod gc;
+pub mod lock;
 pub mod object_store;
 pub mod parallel_uploader;
 pub mod parquet_encoder;
+pub mod restore;
 pub mod snapshotter;
 pub mod storage_backend;
 pub mod tiering;
@@ -93,10 +97,13 @@ pub mod wal;
 // Re-export commonly used types
 pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerState};
 pub use dlq::{DLQConfig, DLQEntry, DLQMetrics, DeadLetterQueue};
+pub use gc::{GcConfig, GcReport};
+pub use lock::{CollectionLock, ObjectStoreLeaseRepository};
 pub use object_store::{
     CallHistoryEntry, MockFailure, MockS3Config, MockS3ObjectStore, ObjectStore,
 };
-pub use storage_backend::{CacheStats, RetryConfig, StorageBackend, StorageMetrics};
+pub use restore::{RestoreOutcome, RestoreReport};
+pub use storage_backend::{CacheStats, RetryConfig, StorageBackend, StorageMetrics, WalState};
 pub use tiering::{CompactionConfig, CompressionType, StorageConfig, TieringPolicy};
 pub use wal::{FileWAL, FileWALConfig, LogEntry, LogSequenceNumber, WriteAheadLog};
 
@@ -0,0 +1,503 @@
+//! Leases and locks kept in the object store.
+//!
+//! Nodes sharing a bucket do not necessarily share a metadata database, so
+//! storage maintenance (compaction, backup, garbage collection) coordinates
+//! through the bucket itself. Each lease is a small JSON object under
+//! `locks/`, replaced only with conditional writes
+//! ([`ObjectStore::put_if_match`]): of two nodes racing for the same lease,
+//! exactly one write succeeds.
+//!
+//! Expiry is judged against each node's wall clock, so clocks must be kept
+//! reasonably in sync (well within the lease TTL).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use akidb_core::{CollectionId, CoreError, CoreResult, Lease, LeaseRepository};
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::object_store::ObjectStore;
+
+/// Key prefix for lease objects
+const LOCK_PREFIX: &str = "locks/";
+
+/// How long a collection lock lasts if its holder dies mid-operation
+pub const COLLECTION_LOCK_TTL: Duration = Duration::from_secs(600);
+
+/// [`LeaseRepository`] backed by conditional writes to an object store.
+pub struct ObjectStoreLeaseRepository {
+    store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectStoreLeaseRepository {
+    /// Creates a lease repository storing leases in `store`.
+    #[must_use]
+    pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+        Self { store }
+    }
+
+    /// Reads the lease and the entity tag to replace it with, if it exists.
+    async fn read(&self, key: &str) -> CoreResult<Option<(Lease, String)>> {
+        let etag = match self.store.head(key).await {
+            Ok(metadata) => metadata.etag.ok_or_else(|| {
+                CoreError::StorageError(format!("object store reports no ETag for '{}'", key))
+            })?,
+            Err(CoreError::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        // A write between head and get changes the ETag, so the conditional
+        // write below fails rather than act on a stale lease
+        let data = match self.store.get(key).await {
+            Ok(data) => data,
+            Err(CoreError::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let lease = serde_json::from_slice(&data)
+            .map_err(|e| CoreError::DeserializationError(format!("lease '{}': {}", key, e)))?;
+
+        Ok(Some((lease, etag)))
+    }
+
+    async fn write(&self, key: &str, lease: &Lease, etag: Option<&str>) -> CoreResult<bool> {
+        let data =
+            serde_json::to_vec(lease).map_err(|e| CoreError::SerializationError(e.to_string()))?;
+        self.store.put_if_match(key, Bytes::from(data), etag).await
+    }
+}
+
+#[async_trait]
+impl LeaseRepository for ObjectStoreLeaseRepository {
+    async fn acquire(&self, name: &str, holder: &str, ttl: Duration) -> CoreResult<Option<Lease>> {
+        let key = lease_key(name);
+        let now = Utc::now();
+        let expires_at = now
+            + chrono::Duration::from_std(ttl)
+                .map_err(|e| CoreError::ValidationError(format!("lease TTL: {}", e)))?;
+
+        let current = self.read(&key).await?;
+        let lease = match &current {
+            None => Lease {
+                name: name.to_string(),
+                holder: holder.to_string(),
+                token: 1,
+                acquired_at: now,
+                expires_at,
+            },
+            // Renewal keeps the token
+            Some((lease, _)) if lease.holder == holder => Lease {
+                expires_at,
+                ..lease.clone()
+            },
+            Some((lease, _)) if lease.is_expired(now) => Lease {
+                name: name.to_string(),
+                holder: holder.to_string(),
+                token: lease.token + 1,
+                acquired_at: now,
+                expires_at,
+            },
+            Some(_) => return Ok(None),
+        };
+
+        let etag = current.as_ref().map(|(_, etag)| etag.as_str());
+        Ok(self.write(&key, &lease, etag).await?.then_some(lease))
+    }
+
+    async fn release(&self, name: &str, holder: &str) -> CoreResult<bool> {
+        let key = lease_key(name);
+        let now = Utc::now();
+
+        let Some((lease, etag)) = self.read(&key).await? else {
+            return Ok(false);
+        };
+        if lease.holder != holder || lease.is_expired(now) {
+            return Ok(false);
+        }
+
+        // Expire rather than delete, so the next holder gets a higher token
+        let released = Lease {
+            expires_at: now,
+            ..lease
+        };
+        self.write(&key, &released, Some(&etag)).await
+    }
+
+    async fn get(&self, name: &str) -> CoreResult<Option<Lease>> {
+        Ok(self.read(&lease_key(name)).await?.map(|(lease, _)| lease))
+    }
+}
+
+/// Exclusive hold on one maintenance operation for one collection.
+///
+/// Two holders cannot have the same operation's lock for the same collection
+/// at once, whether on different nodes or in the same process: every
+/// acquisition campaigns under its own holder ID. The lease is renewed in
+/// the background every third of its TTL while the lock is alive; call
+/// [`ensure_held`](Self::ensure_held) before committing work done under it.
+///
+/// Call [`release`](Self::release) when done. Dropping the lock releases it
+/// in the background; a lock whose process dies lapses after its TTL.
+#[must_use = "the lock is held until released or expired"]
+pub struct CollectionLock {
+    leases: Arc<dyn LeaseRepository>,
+    lease: Lease,
+    /// When the lease lapses by the local clock, or None once renewal lost it
+    valid_until: Arc<Mutex<Option<DateTime<Utc>>>>,
+    renewal: JoinHandle<()>,
+    released: bool,
+}
+
+impl CollectionLock {
+    /// Takes the `operation` lock (e.g. "compaction") for `collection_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CoreError::InvalidState` if another holder has it, or the
+    /// repository's error if the lease cannot be read or written.
+    pub async fn acquire(
+        leases: Arc<dyn LeaseRepository>,
+        operation: &str,
+        collection_id: CollectionId,
+    ) -> CoreResult<Self> {
+        Self::acquire_with_ttl(leases, operation, collection_id, COLLECTION_LOCK_TTL).await
+    }
+
+    /// Like [`acquire`](Self::acquire), with a lease lasting `ttl`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`acquire`](Self::acquire).
+    pub async fn acquire_with_ttl(
+        leases: Arc<dyn LeaseRepository>,
+        operation: &str,
+        collection_id: CollectionId,
+        ttl: Duration,
+    ) -> CoreResult<Self> {
+        static NEXT_ACQUISITION: AtomicU64 = AtomicU64::new(1);
+
+        let name = format!("{}/{}", operation, collection_id);
+        let holder = format!(
+            "{}#{}",
+            node_id(),
+            NEXT_ACQUISITION.fetch_add(1, Ordering::Relaxed)
+        );
+        if let Some(lease) = leases.acquire(&name, &holder, ttl).await? {
+            let valid_until = Arc::new(Mutex::new(Some(lease.expires_at)));
+            let renewal = tokio::spawn(renew(
+                Arc::clone(&leases),
+                name,
+                holder,
+                ttl,
+                Arc::clone(&valid_until),
+            ));
+            return Ok(Self {
+                leases,
+                lease,
+                valid_until,
+                renewal,
+                released: false,
+            });
+        }
+
+        let holder = leases
+            .get(&name)
+            .await?
+            .map_or_else(|| "another node".to_string(), |lease| lease.holder);
+        Err(CoreError::invalid_state(format!(
+            "{} of collection {} is in progress on {}",
+            operation, collection_id, holder
+        )))
+    }
+
+    /// Fencing token of the lease, higher than any earlier holder's.
+    #[must_use]
+    pub fn token(&self) -> u64 {
+        self.lease.token
+    }
+
+    /// Holder ID of this acquisition.
+    #[must_use]
+    pub fn holder(&self) -> &str {
+        &self.lease.holder
+    }
+
+    /// Whether the lease is still ours: renewal has not failed and it has
+    /// not lapsed by the local clock.
+    #[must_use]
+    pub fn is_held(&self) -> bool {
+        let valid_until = *self.valid_until.lock();
+        valid_until.is_some_and(|deadline| Utc::now() < deadline)
+    }
+
+    /// Fails if the lease was lost, so work done under it is not committed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CoreError::InvalidState` if renewal lost the lease or it
+    /// lapsed.
+    pub fn ensure_held(&self) -> CoreResult<()> {
+        if self.is_held() {
+            Ok(())
+        } else {
+            Err(CoreError::invalid_state(format!(
+                "lost the lock '{}' before the operation finished",
+                self.lease.name
+            )))
+        }
+    }
+
+    /// Releases the lock so other holders can take it immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns the repository's error if the lease cannot be written.
+    pub async fn release(mut self) -> CoreResult<()> {
+        self.renewal.abort();
+        self.released = true;
+        self.leases
+            .release(&self.lease.name, &self.lease.holder)
+            .await?;
+        Ok(())
+    }
+}
+
+impl Drop for CollectionLock {
+    fn drop(&mut self) {
+        self.renewal.abort();
+        if self.released {
+            return;
+        }
+        // E.g. the operation returned early with `?`
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let leases = Arc::clone(&self.leases);
+            let name = self.lease.name.clone();
+            let holder = self.lease.holder.clone();
+            runtime.spawn(async move {
+                if let Err(e) = leases.release(&name, &holder).await {
+                    tracing::warn!("Failed to release lock '{}': {}", name, e);
+                }
+            });
+        }
+    }
+}
+
+/// Renews a collection lock every third of `ttl` until aborted or the lease
+/// is lost.
+async fn renew(
+    leases: Arc<dyn LeaseRepository>,
+    name: String,
+    holder: String,
+    ttl: Duration,
+    valid_until: Arc<Mutex<Option<DateTime<Utc>>>>,
+) {
+    let mut ticker = tokio::time::interval(ttl / 3);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        match leases.acquire(&name, &holder, ttl).await {
+            Ok(Some(lease)) => *valid_until.lock() = Some(lease.expires_at),
+            Ok(None) => {
+                tracing::warn!("Lock '{}' was taken over by another holder", name);
+                break;
+            }
+            Err(e) => {
+                let lapsed = valid_until
+                    .lock()
+                    .map_or(true, |deadline| Utc::now() >= deadline);
+                tracing::warn!("Failed to renew lock '{}': {}", name, e);
+                if lapsed {
+                    break;
+                }
+            }
+        }
+    }
+    *valid_until.lock() = None;
+}
+
+/// Identifier of this process: host name, process ID and start time.
+/// Collection locks are held under it plus a per-acquisition counter.
+pub fn node_id() -> &'static str {
+    static NODE_ID: OnceLock<String> = OnceLock::new();
+    NODE_ID.get_or_init(|| {
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "akidb".to_string());
+        format!(
+            "{}-{}-{}",
+            host,
+            std::process::id(),
+            Utc::now().timestamp_millis()
+        )
+    })
+}
+
+fn lease_key(name: &str) -> String {
+    format!("{}{}.json", LOCK_PREFIX, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object_store::MockS3ObjectStore;
+
+    fn leases() -> Arc<dyn LeaseRepository> {
+        Arc::new(ObjectStoreLeaseRepository::new(Arc::new(
+            MockS3ObjectStore::new(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_lease_is_exclusive_until_released() {
+        let leases = leases();
+        let ttl = Duration::from_secs(30);
+
+        let lease = leases.acquire("gc", "node-a", ttl).await.unwrap().unwrap();
+        assert_eq!(lease.token, 1);
+        assert!(leases.acquire("gc", "node-b", ttl).await.unwrap().is_none());
+
+        // Renewal keeps the token
+        let renewed = leases.acquire("gc", "node-a", ttl).await.unwrap().unwrap();
+        assert_eq!(renewed.token, 1);
+        assert_eq!(renewed.acquired_at, lease.acquired_at);
+
+        assert!(!leases.release("gc", "node-b").await.unwrap());
+        assert!(leases.release("gc", "node-a").await.unwrap());
+
+        let taken = leases.acquire("gc", "node-b", ttl).await.unwrap().unwrap();
+        assert_eq!(taken.holder, "node-b");
+        assert_eq!(taken.token, 2);
+        assert_eq!(leases.get("gc").await.unwrap(), Some(taken));
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_can_be_taken_over() {
+        let leases = leases();
+
+        leases
+            .acquire("backup", "node-a", Duration::from_millis(100))
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        let lease = leases
+            .acquire("backup", "node-b", Duration::from_secs(30))
+            .await
+            .unwrap()
+            .expect("expired lease is free");
+        assert_eq!(lease.holder, "node-b");
+        assert_eq!(lease.token, 2);
+    }
+
+    #[tokio::test]
+    async fn test_collection_lock_excludes_same_operation() {
+        let leases = leases();
+        let collection_id = CollectionId::new();
+
+        let lock = CollectionLock::acquire(leases.clone(), "compaction", collection_id)
+            .await
+            .unwrap();
+
+        // Other operations on the collection are not blocked
+        leases
+            .acquire(
+                &format!("gc/{}", collection_id),
+                "node-b",
+                COLLECTION_LOCK_TTL,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(leases
+            .acquire(
+                &format!("compaction/{}", collection_id),
+                "node-b",
+                COLLECTION_LOCK_TTL,
+            )
+            .await
+            .unwrap()
+            .is_none());
+
+        lock.release().await.unwrap();
+        assert!(leases
+            .acquire(
+                &format!("compaction/{}", collection_id),
+                "node-b",
+                COLLECTION_LOCK_TTL,
+            )
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_collection_lock_is_per_acquisition() {
+        let leases = leases();
+        let collection_id = CollectionId::new();
+
+        let first = CollectionLock::acquire(leases.clone(), "backup", collection_id)
+            .await
+            .unwrap();
+        // The same process does not count as renewing its own lock
+        let second = CollectionLock::acquire(leases.clone(), "backup", collection_id).await;
+        assert!(matches!(second, Err(CoreError::InvalidState { .. })));
+
+        // Dropping the lock releases it
+        drop(first);
+        for _ in 0..50 {
+            if let Ok(lock) = CollectionLock::acquire(leases.clone(), "backup", collection_id).await
+            {
+                assert!(lock.is_held());
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("dropped lock was not released");
+    }
+
+    #[tokio::test]
+    async fn test_collection_lock_renews_until_lost() {
+        let store = Arc::new(MockS3ObjectStore::new());
+        let leases: Arc<dyn LeaseRepository> =
+            Arc::new(ObjectStoreLeaseRepository::new(store.clone()));
+        let collection_id = CollectionId::new();
+        let name = format!("gc/{}", collection_id);
+        let ttl = Duration::from_millis(300);
+
+        let lock = CollectionLock::acquire_with_ttl(leases.clone(), "gc", collection_id, ttl)
+            .await
+            .unwrap();
+
+        // Held past the TTL while the guard lives
+        tokio::time::sleep(ttl * 2).await;
+        lock.ensure_held().unwrap();
+        assert!(leases
+            .acquire(&name, "node-b", ttl)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Another node overwrites the lease, e.g. after a long pause of ours
+        let taken_over = Lease {
+            holder: "node-b".to_string(),
+            token: lock.token() + 1,
+            ..leases.get(&name).await.unwrap().unwrap()
+        };
+        store
+            .put(
+                &lease_key(&name),
+                Bytes::from(serde_json::to_vec(&taken_over).unwrap()),
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(ttl / 2).await;
+        assert!(!lock.is_held());
+        assert!(matches!(
+            lock.ensure_held(),
+            Err(CoreError::InvalidState { .. })
+        ));
+    }
+}
@@ -39,6 +39,8 @@ use std::time::UNIX_EPOCH;
 /// ```
 pub struct LocalObjectStore {
     base_dir: PathBuf,
+    /// Serializes conditional puts (within this process only)
+    cas_lock: tokio::sync::Mutex<()>,
 }
 
 impl LocalObjectStore {
@@ -52,7 +54,10 @@ impl LocalObjectStore {
     pub async fn new(base_dir: impl AsRef<Path>) -> CoreResult<Self> {
         let base_dir = base_dir.as_ref().to_path_buf();
         tokio::fs::create_dir_all(&base_dir).await?;
-        Ok(Self { base_dir })
+        Ok(Self {
+            base_dir,
+            cas_lock: tokio::sync::Mutex::new(()),
+        })
     }
 
     /// Convert key to full filesystem path
@@ -60,6 +65,12 @@ impl LocalObjectStore {
         self.base_dir.join(key)
     }
 
+    /// Entity tag of an object: MD5 hash of its contents, as S3 reports for single-part uploads
+    async fn etag(path: &Path) -> std::io::Result<String> {
+        let data = tokio::fs::read(path).await?;
+        Ok(format!("{:x}", md5::compute(&data)))
+    }
+
     /// Strip base directory from path to get key
     fn path_to_key(&self, path: &Path) -> Option<String> {
         path.strip_prefix(&self.base_dir)
@@ -216,7 +227,7 @@ impl ObjectStore for LocalObjectStore {
                         .and_then(|d| DateTime::from_timestamp(d.as_secs() as i64, 0))
                 })
                 .unwrap_or_else(Utc::now),
-            etag: None,
+            etag: Some(Self::etag(&path).await?),
         })
     }
 
@@ -251,6 +262,22 @@ impl ObjectStore for LocalObjectStore {
 
         self.put(key, Bytes::from(combined)).await
     }
+
+    async fn put_if_match(&self, key: &str, data: Bytes, etag: Option<&str>) -> CoreResult<bool> {
+        let _guard = self.cas_lock.lock().await;
+
+        let current = match Self::etag(&self.full_path(key)).await {
+            Ok(current) => Some(current),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        if current.as_deref() != etag {
+            return Ok(false);
+        }
+
+        self.put(key, data).await?;
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -345,7 +372,33 @@ mod tests {
         let metadata = store.head("head.txt").await.unwrap();
         assert_eq!(metadata.key, "head.txt");
         assert_eq!(metadata.size_bytes, data.len() as u64);
-        assert!(metadata.etag.is_none());
+        assert!(metadata.etag.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_local_store_put_if_match() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = LocalObjectStore::new(temp_dir.path()).await.unwrap();
+
+        assert!(store
+            .put_if_match("lock.json", Bytes::from("v1"), None)
+            .await
+            .unwrap());
+        assert!(!store
+            .put_if_match("lock.json", Bytes::from("v2"), None)
+            .await
+            .unwrap());
+
+        let etag = store.head("lock.json").await.unwrap().etag.unwrap();
+        assert!(!store
+            .put_if_match("lock.json", Bytes::from("v2"), Some("stale"))
+            .await
+            .unwrap());
+        assert!(store
+            .put_if_match("lock.json", Bytes::from("v2"), Some(&etag))
+            .await
+            .unwrap());
+        assert_eq!(store.get("lock.json").await.unwrap(), Bytes::from("v2"));
     }
 
     #[tokio::test]
@@ -51,7 +51,7 @@ use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use super::{ObjectMetadata, ObjectStore};
+use super::{list_all_pages, ListPage, ObjectMetadata, ObjectStore, MAX_KEYS_PER_LIST};
 use akidb_core::{CoreError, CoreResult};
 
 /// Mock S3 failure pattern.
@@ -295,6 +295,50 @@ impl MockS3ObjectStore {
         }
     }
 
+    /// One S3-style listing page: up to [`MAX_KEYS_PER_LIST`] keys in key
+    /// order, after the key named by `token`.
+    async fn list_page(&self, prefix: &str, token: Option<String>) -> CoreResult<ListPage> {
+        // Simulate network latency
+        tokio::time::sleep(self.config.latency).await;
+
+        // Check for simulated failure
+        if let Some(error) = self.check_failure() {
+            self.record_call("list", prefix, false);
+            return Err(error);
+        }
+
+        let storage = self.storage.read();
+        let mut keys: Vec<&String> = storage
+            .keys()
+            .filter(|k| k.starts_with(prefix))
+            .filter(|k| token.as_ref().map_or(true, |after| *k > after))
+            .collect();
+        keys.sort();
+
+        let next_token =
+            (keys.len() > MAX_KEYS_PER_LIST).then(|| keys[MAX_KEYS_PER_LIST - 1].clone());
+        let objects = keys
+            .into_iter()
+            .take(MAX_KEYS_PER_LIST)
+            .map(|k| {
+                let v = &storage[k];
+                ObjectMetadata {
+                    key: k.clone(),
+                    size_bytes: v.len() as u64,
+                    last_modified: Utc::now(),
+                    etag: Some(format!("{:x}", md5::compute(v.as_ref()))),
+                }
+            })
+            .collect();
+        drop(storage);
+
+        self.record_call("list", prefix, true);
+        Ok(ListPage {
+            objects,
+            next_token,
+        })
+    }
+
     /// Record call in history.
     fn record_call(&self, operation: &str, key: &str, success: bool) {
         if self.config.track_history {
@@ -389,28 +433,7 @@ impl ObjectStore for MockS3ObjectStore {
     }
 
     async fn list(&self, prefix: &str) -> CoreResult<Vec<ObjectMetadata>> {
-        // Simulate network latency
-        tokio::time::sleep(self.config.latency).await;
-
-        // Check for simulated failure
-        if let Some(error) = self.check_failure() {
-            return Err(error);
-        }
-
-        // Success: list keys with prefix
-        let storage = self.storage.read();
-        let objects: Vec<ObjectMetadata> = storage
-            .iter()
-            .filter(|(k, _)| k.starts_with(prefix))
-            .map(|(k, v)| ObjectMetadata {
-                key: k.clone(),
-                size_bytes: v.len() as u64,
-                last_modified: Utc::now(),
-                etag: Some(format!("{:x}", md5::compute(v.as_ref()))),
-            })
-            .collect();
-
-        Ok(objects)
+        list_all_pages(|token| self.list_page(prefix, token)).await
     }
 
     async fn head(&self, key: &str) -> CoreResult<ObjectMetadata> {
@@ -495,6 +518,31 @@ impl ObjectStore for MockS3ObjectStore {
 
         Ok(())
     }
+
+    async fn put_if_match(&self, key: &str, data: Bytes, etag: Option<&str>) -> CoreResult<bool> {
+        // Simulate network latency
+        tokio::time::sleep(self.config.latency).await;
+
+        // Check for simulated failure
+        if let Some(error) = self.check_failure() {
+            self.record_call("put_if_match", key, false);
+            return Err(error);
+        }
+
+        // Compare and write under one lock, as S3 does atomically
+        let mut storage = self.storage.write();
+        let current = storage
+            .get(key)
+            .map(|v| format!("{:x}", md5::compute(v.as_ref())));
+        let matches = current.as_deref() == etag;
+        if matches {
+            storage.insert(key.to_string(), data);
+        }
+        drop(storage);
+        self.record_call("put_if_match", key, matches);
+
+        Ok(matches)
+    }
 }
 
 #[cfg(test)]
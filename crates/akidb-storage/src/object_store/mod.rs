@@ -13,11 +13,12 @@ pub use local::LocalObjectStore;
 pub use mock::{CallHistoryEntry, MockFailure, MockS3Config, MockS3ObjectStore};
 pub use s3::{S3Config, S3ObjectStore};
 
-use akidb_core::CoreResult;
+use akidb_core::{CoreError, CoreResult};
 use async_trait::async_trait;
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 
 /// Object metadata returned by list/head operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,10 +29,45 @@ pub struct ObjectMetadata {
     pub size_bytes: u64,
     /// Last modification timestamp
     pub last_modified: DateTime<Utc>,
-    /// ETag (S3 entity tag, None for local storage)
+    /// ETag (S3 entity tag; local storage only reports one from `head`)
     pub etag: Option<String>,
 }
 
+/// Most keys S3 returns from one `ListObjectsV2` call
+pub const MAX_KEYS_PER_LIST: usize = 1000;
+
+/// One page of a paginated listing
+#[derive(Debug, Clone, Default)]
+pub struct ListPage {
+    /// Objects on this page
+    pub objects: Vec<ObjectMetadata>,
+    /// Token for the next page; `None` on the last page
+    pub next_token: Option<String>,
+}
+
+/// Collects every page of a listing, passing each page's token to `fetch`
+/// until a page without one comes back
+///
+/// # Errors
+///
+/// Returns the first error from `fetch`
+pub async fn list_all_pages<F, Fut>(mut fetch: F) -> CoreResult<Vec<ObjectMetadata>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = CoreResult<ListPage>>,
+{
+    let mut objects = Vec::new();
+    let mut token = None;
+    loop {
+        let page = fetch(token).await?;
+        objects.extend(page.objects);
+        match page.next_token {
+            Some(next) => token = Some(next),
+            None => return Ok(objects),
+        }
+    }
+}
+
 /// Object Store trait - S3-like interface for cloud/local storage
 ///
 /// All implementations must be thread-safe (Send + Sync) and support
@@ -151,6 +187,36 @@ pub trait ObjectStore: Send + Sync {
     /// - `CoreError::StorageError` if the operation fails
     /// - `CoreError::ValidationError` if total size exceeds backend limits
     async fn put_multipart(&self, key: &str, parts: Vec<Bytes>) -> CoreResult<()>;
+
+    /// Conditional put (compare-and-swap)
+    ///
+    /// Stores the data only if the object's current ETag equals `etag`, or,
+    /// when `etag` is `None`, only if no object exists at that key. Returns
+    /// `false` without writing when the condition does not hold.
+    ///
+    /// # Errors
+    ///
+    /// - `CoreError::StorageError` if the operation fails or the backend does
+    ///   not support conditional writes (the default)
+    async fn put_if_match(&self, key: &str, data: Bytes, etag: Option<&str>) -> CoreResult<bool> {
+        let _ = (data, etag);
+        Err(CoreError::StorageError(format!(
+            "conditional put of '{}' is not supported by this object store",
+            key
+        )))
+    }
+
+    /// Verify the backend is reachable and the bucket exists
+    ///
+    /// The default looks up a key that is never written, so it only proves
+    /// the store answers requests.
+    ///
+    /// # Errors
+    ///
+    /// - `CoreError::StorageError` if the store is unreachable
+    async fn check(&self) -> CoreResult<()> {
+        self.exists(".akidb-health-check").await.map(|_| ())
+    }
 }
 
 #[cfg(test)]
@@ -3,7 +3,7 @@
 //! Provides production-ready S3 integration with MinIO compatibility.
 //! Supports standard AWS S3 and S3-compatible endpoints (MinIO, Wasabi, etc.).
 
-use super::{ObjectMetadata, ObjectStore};
+use super::{list_all_pages, ListPage, ObjectMetadata, ObjectStore};
 use akidb_core::{CoreError, CoreResult};
 use async_trait::async_trait;
 use aws_config::BehaviorVersion;
@@ -181,6 +181,59 @@ impl S3ObjectStore {
             key.to_string()
         }
     }
+
+    /// One `ListObjectsV2` page under `full_prefix`, starting at `token`
+    async fn list_page(&self, full_prefix: &str, token: Option<String>) -> CoreResult<ListPage> {
+        let resp = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(full_prefix)
+            .set_continuation_token(token)
+            .send()
+            .await
+            .map_err(|e| CoreError::StorageError(format!("S3 list failed: {}", e)))?;
+
+        let objects = resp
+            .contents()
+            .iter()
+            .filter_map(|obj| {
+                let key = obj.key()?;
+                let stripped_key = self.strip_prefix(key);
+                let size = obj.size().unwrap_or(0);
+                let modified = obj.last_modified()?;
+                let etag = obj.e_tag().map(|s| s.to_string());
+
+                // Convert AWS DateTime to chrono DateTime
+                let last_modified =
+                    chrono::DateTime::from_timestamp(modified.secs(), modified.subsec_nanos())
+                        .unwrap_or_else(Utc::now);
+
+                Some(ObjectMetadata {
+                    key: stripped_key,
+                    size_bytes: size as u64,
+                    last_modified,
+                    etag,
+                })
+            })
+            .collect();
+
+        let next_token = if resp.is_truncated() == Some(true) {
+            let token = resp.next_continuation_token().ok_or_else(|| {
+                CoreError::StorageError(
+                    "S3 list truncated without a continuation token".to_string(),
+                )
+            })?;
+            Some(token.to_string())
+        } else {
+            None
+        };
+
+        Ok(ListPage {
+            objects,
+            next_token,
+        })
+    }
 }
 
 #[async_trait]
@@ -260,41 +313,7 @@ impl ObjectStore for S3ObjectStore {
 
     async fn list(&self, prefix: &str) -> CoreResult<Vec<ObjectMetadata>> {
         let full_prefix = self.full_key(prefix);
-
-        let resp = self
-            .client
-            .list_objects_v2()
-            .bucket(&self.bucket)
-            .prefix(&full_prefix)
-            .send()
-            .await
-            .map_err(|e| CoreError::StorageError(format!("S3 list failed: {}", e)))?;
-
-        let objects = resp
-            .contents()
-            .iter()
-            .filter_map(|obj| {
-                let key = obj.key()?;
-                let stripped_key = self.strip_prefix(key);
-                let size = obj.size().unwrap_or(0);
-                let modified = obj.last_modified()?;
-                let etag = obj.e_tag().map(|s| s.to_string());
-
-                // Convert AWS DateTime to chrono DateTime
-                let last_modified =
-                    chrono::DateTime::from_timestamp(modified.secs(), modified.subsec_nanos())
-                        .unwrap_or_else(Utc::now);
-
-                Some(ObjectMetadata {
-                    key: stripped_key,
-                    size_bytes: size as u64,
-                    last_modified,
-                    etag,
-                })
-            })
-            .collect();
-
-        Ok(objects)
+        list_all_pages(|token| self.list_page(&full_prefix, token)).await
     }
 
     async fn head(&self, key: &str) -> CoreResult<ObjectMetadata> {
@@ -369,6 +388,52 @@ impl ObjectStore for S3ObjectStore {
             ))
         }
     }
+
+    async fn put_if_match(&self, key: &str, data: Bytes, etag: Option<&str>) -> CoreResult<bool> {
+        if key.is_empty() {
+            return Err(CoreError::ValidationError(
+                "Key cannot be empty".to_string(),
+            ));
+        }
+
+        let full_key = self.full_key(key);
+
+        let request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&full_key)
+            .body(ByteStream::from(data));
+        let request = match etag {
+            Some(etag) => request.if_match(etag),
+            None => request.if_none_match("*"),
+        };
+
+        match request.send().await {
+            Ok(_) => Ok(true),
+            // 412: condition failed; 409: a concurrent conditional write won
+            Err(e)
+                if e.raw_response()
+                    .is_some_and(|r| matches!(r.status().as_u16(), 409 | 412)) =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(CoreError::StorageError(format!(
+                "S3 conditional put failed: {}",
+                e
+            ))),
+        }
+    }
+
+    async fn check(&self) -> CoreResult<()> {
+        self.client
+            .head_bucket()
+            .bucket(&self.bucket)
+            .send()
+            .await
+            .map_err(|e| CoreError::StorageError(format!("S3 head bucket failed: {}", e)))?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -74,6 +74,7 @@ impl ParquetEncoder {
                 DataType::Timestamp(TimeUnit::Millisecond, None),
                 false,
             ),
+            Field::new("sparse_json", DataType::Utf8, true),
         ];
 
         Arc::new(Schema::new(fields))
@@ -133,6 +134,12 @@ impl ParquetEncoder {
             .map(|d| d.inserted_at.timestamp_millis())
             .collect();
 
+        let sparse_jsons: Vec<Option<String>> = documents
+            .iter()
+            .map(|d| d.sparse.as_ref().map(serde_json::to_string).transpose())
+            .collect::<Result<_, _>>()
+            .map_err(|e| CoreError::SerializationError(e.to_string()))?;
+
         // Create Arrow arrays
         let document_id_array: ArrayRef = Arc::new(BinaryArray::from(document_id_refs));
         let external_id_array: ArrayRef = Arc::new(StringArray::from(external_ids));
@@ -153,6 +160,7 @@ impl ParquetEncoder {
 
         let metadata_array: ArrayRef = Arc::new(StringArray::from(metadata_jsons));
         let inserted_at_array: ArrayRef = Arc::new(TimestampMillisecondArray::from(inserted_ats));
+        let sparse_array: ArrayRef = Arc::new(StringArray::from(sparse_jsons));
 
         let schema = Self::schema(dimension);
 
@@ -165,6 +173,7 @@ impl ParquetEncoder {
                 vector_array,
                 metadata_array,
                 inserted_at_array,
+                sparse_array,
             ],
         )
         .map_err(|e| CoreError::SerializationError(e.to_string()))?;
@@ -263,6 +272,14 @@ impl ParquetEncoder {
                     CoreError::DeserializationError("Invalid inserted_at column".to_string())
                 })?;
 
+            // Files written before sparse vectors existed lack this column
+            let sparse_jsons = match batch.columns().get(6) {
+                Some(column) => Some(column.as_any().downcast_ref::<StringArray>().ok_or_else(
+                    || CoreError::DeserializationError("Invalid sparse_json column".to_string()),
+                )?),
+                None => None,
+            };
+
             // Build VectorDocuments
             for i in 0..batch.num_rows() {
                 // Document ID
@@ -322,10 +339,24 @@ impl ParquetEncoder {
                         CoreError::DeserializationError("Invalid timestamp".to_string())
                     })?;
 
+                // Sparse vector (optional)
+                let sparse = match sparse_jsons {
+                    Some(sparse_jsons) if !sparse_jsons.is_null(i) => {
+                        Some(serde_json::from_str(sparse_jsons.value(i)).map_err(|e| {
+                            CoreError::DeserializationError(format!(
+                                "Failed to parse sparse vector JSON: {}",
+                                e
+                            ))
+                        })?)
+                    }
+                    _ => None,
+                };
+
                 documents.push(VectorDocument {
                     doc_id,
                     external_id,
                     vector,
+                    sparse,
                     metadata,
                     inserted_at,
                 });
@@ -351,6 +382,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: Some("doc1".to_string()),
                 vector: vec![1.0, 2.0, 3.0],
+                sparse: None,
                 metadata: None,
                 inserted_at: Utc::now(),
             },
@@ -358,6 +390,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: Some("doc2".to_string()),
                 vector: vec![4.0, 5.0, 6.0],
+                sparse: None,
                 metadata: Some(serde_json::json!({"tag": "test"})),
                 inserted_at: Utc::now(),
             },
@@ -383,6 +416,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: Some(format!("doc{}", i)),
                 vector: vec![i as f32; 512], // 512-dim
+                sparse: None,
                 metadata: None,
                 inserted_at: Utc::now(),
             })
@@ -417,6 +451,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: None,
                 vector: vec![1.0, 2.0, 3.0],
+                sparse: None,
                 metadata: None,
                 inserted_at: Utc::now(),
             },
@@ -424,6 +459,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: None,
                 vector: vec![1.0, 2.0], // Wrong dimension!
+                sparse: None,
                 metadata: None,
                 inserted_at: Utc::now(),
             },
@@ -452,6 +488,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: Some("doc1".to_string()),
                 vector: vec![1.0, 2.0, 3.0],
+                sparse: None,
                 metadata: Some(serde_json::json!({"tag": "test"})),
                 inserted_at: Utc::now(),
             },
@@ -459,6 +496,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: None,
                 vector: vec![4.0, 5.0, 6.0],
+                sparse: None,
                 metadata: None,
                 inserted_at: Utc::now(),
             },
@@ -494,6 +532,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: Some(format!("doc{}", i)),
                 vector: vec![i as f32; 128],
+                sparse: None,
                 metadata: Some(serde_json::json!({"index": i})),
                 inserted_at: Utc::now(),
             })
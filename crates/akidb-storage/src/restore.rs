@@ -0,0 +1,292 @@
+//! Point-in-time restore from a snapshot and the WAL
+//!
+//! A collection's documents as of a timestamp `T` are rebuilt from the
+//! latest snapshot taken at or before `T` that records its WAL position,
+//! followed by the WAL entries after that position, in LSN order, up to the
+//! first entry written after `T`. Without such a snapshot the WAL is replayed
+//! from the start, which requires that none of it was discarded.
+//!
+//! [`StorageBackend::restore_to`](crate::StorageBackend::restore_to) writes
+//! the difference from the current documents back to the WAL, so a restore
+//! is durable, reaches replicas, and can itself be undone by restoring to a
+//! time before it.
+
+use crate::snapshotter::{SnapshotId, SnapshotMetadata};
+use crate::wal::{LogEntry, LogSequenceNumber};
+use akidb_core::{CoreError, CoreResult, DocumentId, VectorDocument};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Summary of a point-in-time restore
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestoreReport {
+    /// Point in time restored to
+    pub target: DateTime<Utc>,
+    /// Snapshot the restore started from (`None` if the WAL was replayed
+    /// from the start)
+    pub snapshot_id: Option<SnapshotId>,
+    /// WAL entries replayed on top of the snapshot
+    pub replayed_entries: usize,
+    /// Last WAL entry reflected in the restored documents
+    pub restored_lsn: LogSequenceNumber,
+    /// Documents in the collection after the restore
+    pub documents: usize,
+    /// Documents written back (re-created or reverted)
+    pub upserted: usize,
+    /// Documents removed
+    pub deleted: usize,
+}
+
+/// Result of [`StorageBackend::restore_to`](crate::StorageBackend::restore_to)
+///
+/// Carries the applied changes so callers keeping their own copy of the
+/// documents (such as a search index) can apply the same changes.
+#[derive(Debug, Clone)]
+pub struct RestoreOutcome {
+    /// Summary of the restore
+    pub report: RestoreReport,
+    /// Documents written back, as stored after the restore
+    pub upserted: Vec<VectorDocument>,
+    /// Documents removed
+    pub deleted: Vec<DocumentId>,
+}
+
+/// The latest snapshot taken at or before `target` that records the WAL
+/// position it covers
+#[must_use]
+pub fn select_snapshot(
+    snapshots: &[SnapshotMetadata],
+    target: DateTime<Utc>,
+) -> Option<&SnapshotMetadata> {
+    snapshots
+        .iter()
+        .filter(|snapshot| snapshot.created_at <= target && snapshot.wal_lsn.is_some())
+        .max_by_key(|snapshot| snapshot.created_at)
+}
+
+/// Applies WAL `entries` (in LSN order, starting right after `from`) to
+/// `documents`, stopping at the first entry written after `target`
+///
+/// Returns the number of entries applied and the last LSN applied (`from`
+/// if none were).
+///
+/// # Errors
+///
+/// Returns `CoreError::InvalidState` if the entries do not start right
+/// after `from`, i.e. the WAL history needed was already discarded.
+pub fn replay_until(
+    documents: &mut HashMap<DocumentId, VectorDocument>,
+    from: LogSequenceNumber,
+    entries: Vec<(LogSequenceNumber, LogEntry)>,
+    target: DateTime<Utc>,
+) -> CoreResult<(usize, LogSequenceNumber)> {
+    if let Some((first, _)) = entries.first() {
+        if *first != from.next() {
+            return Err(CoreError::invalid_state(format!(
+                "WAL entries after {} are no longer available (oldest is {}); \
+                 no snapshot taken at or before {} covers them",
+                from, first, target
+            )));
+        }
+    }
+
+    let mut applied = 0;
+    let mut restored_lsn = from;
+    for (lsn, entry) in entries {
+        if entry.timestamp() > target {
+            break;
+        }
+
+        match entry {
+            LogEntry::Upsert {
+                doc_id,
+                vector,
+                sparse,
+                external_id,
+                metadata,
+                timestamp,
+                ..
+            } => {
+                let mut doc = VectorDocument::new(doc_id, vector);
+                doc.sparse = sparse;
+                doc.external_id = external_id;
+                doc.metadata = metadata;
+                doc.inserted_at = timestamp;
+                documents.insert(doc_id, doc);
+            }
+            LogEntry::Delete { doc_id, .. } => {
+                documents.remove(&doc_id);
+            }
+            LogEntry::CreateCollection { .. }
+            | LogEntry::DeleteCollection { .. }
+            | LogEntry::Checkpoint { .. } => {}
+        }
+        applied += 1;
+        restored_lsn = lsn;
+    }
+
+    Ok((applied, restored_lsn))
+}
+
+/// Documents to write and to remove to turn `current` into `target`
+///
+/// Documents whose vector, external ID and metadata are unchanged are left
+/// out.
+#[must_use]
+pub fn diff(
+    current: &HashMap<DocumentId, VectorDocument>,
+    target: HashMap<DocumentId, VectorDocument>,
+) -> (Vec<VectorDocument>, Vec<DocumentId>) {
+    let deleted = current
+        .keys()
+        .filter(|doc_id| !target.contains_key(doc_id))
+        .copied()
+        .collect();
+    let upserted = target
+        .into_values()
+        .filter(|doc| {
+            current.get(&doc.doc_id).map_or(true, |existing| {
+                existing.vector != doc.vector
+                    || existing.external_id != doc.external_id
+                    || existing.metadata != doc.metadata
+            })
+        })
+        .collect();
+    (upserted, deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshotter::{CompressionCodec, SnapshotFormat};
+    use akidb_core::CollectionId;
+    use chrono::Duration;
+
+    fn upsert(doc_id: DocumentId, value: f32, timestamp: DateTime<Utc>) -> LogEntry {
+        LogEntry::Upsert {
+            collection_id: CollectionId::new(),
+            doc_id,
+            vector: vec![value; 4],
+            sparse: None,
+            external_id: None,
+            metadata: None,
+            timestamp,
+        }
+    }
+
+    fn snapshot(created_at: DateTime<Utc>, wal_lsn: Option<u64>) -> SnapshotMetadata {
+        SnapshotMetadata {
+            snapshot_id: SnapshotId::new(),
+            collection_id: CollectionId::new(),
+            vector_count: 1,
+            dimension: 4,
+            created_at,
+            size_bytes: 1,
+            compression: CompressionCodec::None,
+            format: SnapshotFormat::Json,
+            wal_lsn: wal_lsn.map(LogSequenceNumber::new),
+        }
+    }
+
+    #[test]
+    fn test_select_snapshot() {
+        let now = Utc::now();
+        let snapshots = vec![
+            snapshot(now - Duration::hours(3), Some(10)),
+            snapshot(now - Duration::hours(2), Some(20)),
+            snapshot(now - Duration::hours(1), None),
+            snapshot(now + Duration::hours(1), Some(30)),
+        ];
+
+        let selected = select_snapshot(&snapshots, now).unwrap();
+        assert_eq!(selected.wal_lsn, Some(LogSequenceNumber::new(20)));
+        assert!(select_snapshot(&snapshots, now - Duration::hours(4)).is_none());
+    }
+
+    #[test]
+    fn test_replay_until_stops_after_target() {
+        let now = Utc::now();
+        let (a, b) = (DocumentId::new(), DocumentId::new());
+        let entries = vec![
+            (
+                LogSequenceNumber::new(6),
+                upsert(a, 1.0, now - Duration::minutes(3)),
+            ),
+            (
+                LogSequenceNumber::new(7),
+                upsert(b, 1.0, now - Duration::minutes(2)),
+            ),
+            (
+                LogSequenceNumber::new(8),
+                LogEntry::Delete {
+                    collection_id: CollectionId::new(),
+                    doc_id: b,
+                    timestamp: now - Duration::minutes(1),
+                },
+            ),
+            (
+                LogSequenceNumber::new(9),
+                upsert(a, 2.0, now + Duration::minutes(1)),
+            ),
+        ];
+
+        let mut documents = HashMap::new();
+        let (applied, lsn) =
+            replay_until(&mut documents, LogSequenceNumber::new(5), entries, now).unwrap();
+
+        assert_eq!(applied, 3);
+        assert_eq!(lsn, LogSequenceNumber::new(8));
+        assert_eq!(documents.len(), 1);
+        assert_eq!(documents[&a].vector, vec![1.0; 4]);
+    }
+
+    #[test]
+    fn test_replay_until_detects_missing_history() {
+        let entries = vec![(
+            LogSequenceNumber::new(9),
+            upsert(DocumentId::new(), 1.0, Utc::now()),
+        )];
+
+        let result = replay_until(
+            &mut HashMap::new(),
+            LogSequenceNumber::ZERO,
+            entries,
+            Utc::now(),
+        );
+        assert!(matches!(result, Err(CoreError::InvalidState { .. })));
+    }
+
+    #[test]
+    fn test_diff() {
+        let (kept, changed, removed, added) = (
+            DocumentId::new(),
+            DocumentId::new(),
+            DocumentId::new(),
+            DocumentId::new(),
+        );
+        let current: HashMap<_, _> = [
+            VectorDocument::new(kept, vec![1.0; 4]),
+            VectorDocument::new(changed, vec![1.0; 4]),
+            VectorDocument::new(removed, vec![1.0; 4]),
+        ]
+        .into_iter()
+        .map(|doc| (doc.doc_id, doc))
+        .collect();
+        let target: HashMap<_, _> = [
+            VectorDocument::new(kept, vec![1.0; 4]),
+            VectorDocument::new(changed, vec![2.0; 4]),
+            VectorDocument::new(added, vec![1.0; 4]),
+        ]
+        .into_iter()
+        .map(|doc| (doc.doc_id, doc))
+        .collect();
+
+        let (mut upserted, deleted) = diff(&current, target);
+        upserted.sort_by_key(|doc| doc.doc_id == added);
+        assert_eq!(upserted.len(), 2);
+        assert_eq!(upserted[0].doc_id, changed);
+        assert_eq!(upserted[1].doc_id, added);
+        assert_eq!(deleted, vec![removed]);
+    }
+}
@@ -0,0 +1,232 @@
+//! Stream encoders and decoders for [`CompressionCodec`]
+//!
+//! All codecs use their framed (streaming) format: the Snappy framing
+//! format, a standard Zstd frame, and the LZ4 frame format. Snapshots can
+//! therefore be compressed while they are being serialized and inspected
+//! with the usual command-line tools.
+
+use super::CompressionCodec;
+use akidb_core::{CoreError, CoreResult};
+use std::io::{self, BufRead, Read, Write};
+
+/// Zstd level used for snapshots (the library default, a good
+/// speed/ratio balance)
+const ZSTD_LEVEL: i32 = 3;
+
+impl CompressionCodec {
+    /// Compress a buffer
+    ///
+    /// # Errors
+    ///
+    /// - `CoreError::SerializationError` if compression fails
+    pub fn compress(self, data: &[u8]) -> CoreResult<Vec<u8>> {
+        let mut encoder = self.encoder(Vec::new())?;
+        encoder
+            .write_all(data)
+            .map_err(|e| compress_error(self, &e))?;
+        encoder.finish()
+    }
+
+    /// Decompress a buffer produced by [`CompressionCodec::compress`]
+    ///
+    /// # Errors
+    ///
+    /// - `CoreError::DeserializationError` if the data is corrupted
+    pub fn decompress(self, data: &[u8]) -> CoreResult<Vec<u8>> {
+        let mut decoder = self.decoder(data)?;
+        let mut decompressed = Vec::new();
+        decoder
+            .read_to_end(&mut decompressed)
+            .map_err(|e| decompress_error(self, &e))?;
+        Ok(decompressed)
+    }
+
+    /// Wrap `writer` in a stream encoder
+    ///
+    /// Data written to the encoder is compressed into `writer`;
+    /// [`SnapshotEncoder::finish`] must be called to write the end of the
+    /// stream.
+    ///
+    /// # Errors
+    ///
+    /// - `CoreError::SerializationError` if the encoder cannot be created
+    pub fn encoder<W: Write>(self, writer: W) -> CoreResult<SnapshotEncoder<W>> {
+        let inner = match self {
+            CompressionCodec::None => EncoderInner::None(writer),
+            CompressionCodec::Snappy => {
+                EncoderInner::Snappy(Box::new(snap::write::FrameEncoder::new(writer)))
+            }
+            CompressionCodec::Zstd => EncoderInner::Zstd(
+                zstd::Encoder::new(writer, ZSTD_LEVEL).map_err(|e| compress_error(self, &e))?,
+            ),
+            CompressionCodec::Lz4 => EncoderInner::Lz4(lz4_flex::frame::FrameEncoder::new(writer)),
+        };
+        Ok(SnapshotEncoder { codec: self, inner })
+    }
+
+    /// Wrap `reader` in a stream decoder
+    ///
+    /// # Errors
+    ///
+    /// - `CoreError::DeserializationError` if the decoder cannot be created
+    pub fn decoder<R: BufRead>(self, reader: R) -> CoreResult<SnapshotDecoder<R>> {
+        let inner = match self {
+            CompressionCodec::None => DecoderInner::None(reader),
+            CompressionCodec::Snappy => {
+                DecoderInner::Snappy(Box::new(snap::read::FrameDecoder::new(reader)))
+            }
+            CompressionCodec::Zstd => DecoderInner::Zstd(
+                zstd::Decoder::with_buffer(reader).map_err(|e| decompress_error(self, &e))?,
+            ),
+            CompressionCodec::Lz4 => DecoderInner::Lz4(lz4_flex::frame::FrameDecoder::new(reader)),
+        };
+        Ok(SnapshotDecoder { inner })
+    }
+}
+
+/// Streaming compressor returned by [`CompressionCodec::encoder`]
+pub struct SnapshotEncoder<W: Write> {
+    codec: CompressionCodec,
+    inner: EncoderInner<W>,
+}
+
+enum EncoderInner<W: Write> {
+    None(W),
+    // The Snappy encoder keeps its hash table inline
+    Snappy(Box<snap::write::FrameEncoder<W>>),
+    Zstd(zstd::Encoder<'static, W>),
+    Lz4(lz4_flex::frame::FrameEncoder<W>),
+}
+
+impl<W: Write> SnapshotEncoder<W> {
+    /// Flush buffered data, write the end of the stream and return the
+    /// underlying writer
+    ///
+    /// # Errors
+    ///
+    /// - `CoreError::SerializationError` if writing fails
+    pub fn finish(self) -> CoreResult<W> {
+        let codec = self.codec;
+        let result = match self.inner {
+            EncoderInner::None(mut writer) => writer.flush().map(|()| writer),
+            EncoderInner::Snappy(encoder) => encoder
+                .into_inner()
+                .map_err(snap::write::IntoInnerError::into_error),
+            EncoderInner::Zstd(encoder) => encoder.finish(),
+            EncoderInner::Lz4(encoder) => encoder.finish().map_err(io::Error::from),
+        };
+        result.map_err(|e| compress_error(codec, &e))
+    }
+}
+
+impl<W: Write> Write for SnapshotEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            EncoderInner::None(writer) => writer.write(buf),
+            EncoderInner::Snappy(encoder) => encoder.write(buf),
+            EncoderInner::Zstd(encoder) => encoder.write(buf),
+            EncoderInner::Lz4(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.inner {
+            EncoderInner::None(writer) => writer.flush(),
+            EncoderInner::Snappy(encoder) => encoder.flush(),
+            EncoderInner::Zstd(encoder) => encoder.flush(),
+            EncoderInner::Lz4(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Streaming decompressor returned by [`CompressionCodec::decoder`]
+pub struct SnapshotDecoder<R: BufRead> {
+    inner: DecoderInner<R>,
+}
+
+enum DecoderInner<R: BufRead> {
+    None(R),
+    Snappy(Box<snap::read::FrameDecoder<R>>),
+    Zstd(zstd::Decoder<'static, R>),
+    Lz4(lz4_flex::frame::FrameDecoder<R>),
+}
+
+impl<R: BufRead> Read for SnapshotDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            DecoderInner::None(reader) => reader.read(buf),
+            DecoderInner::Snappy(decoder) => decoder.read(buf),
+            DecoderInner::Zstd(decoder) => decoder.read(buf),
+            DecoderInner::Lz4(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+fn compress_error(codec: CompressionCodec, error: &io::Error) -> CoreError {
+    CoreError::SerializationError(format!("{} compression failed: {}", codec, error))
+}
+
+pub(super) fn decompress_error(codec: CompressionCodec, error: &io::Error) -> CoreError {
+    CoreError::DeserializationError(format!("{} decompression failed: {}", codec, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODECS: [CompressionCodec; 4] = [
+        CompressionCodec::None,
+        CompressionCodec::Snappy,
+        CompressionCodec::Zstd,
+        CompressionCodec::Lz4,
+    ];
+
+    #[test]
+    fn test_compress_decompress_round_trip() {
+        let data = b"vector snapshot data ".repeat(1000);
+
+        for codec in CODECS {
+            let compressed = codec.compress(&data).unwrap();
+            if codec != CompressionCodec::None {
+                assert!(
+                    compressed.len() < data.len() / 4,
+                    "{codec} did not compress"
+                );
+            }
+            assert_eq!(codec.decompress(&compressed).unwrap(), data, "{codec}");
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_buffer_api() {
+        // Written in small pieces, as serde_json does
+        let data = b"0123456789abcdef".repeat(20_000);
+
+        for codec in CODECS {
+            let mut encoder = codec.encoder(Vec::new()).unwrap();
+            for chunk in data.chunks(7) {
+                encoder.write_all(chunk).unwrap();
+            }
+            let compressed = encoder.finish().unwrap();
+
+            assert_eq!(codec.decompress(&compressed).unwrap(), data, "{codec}");
+        }
+    }
+
+    #[test]
+    fn test_decompress_corrupted_data() {
+        let garbage = b"definitely not a compressed stream";
+
+        for codec in [
+            CompressionCodec::Snappy,
+            CompressionCodec::Zstd,
+            CompressionCodec::Lz4,
+        ] {
+            let result = codec.decompress(garbage);
+            assert!(
+                matches!(result, Err(CoreError::DeserializationError(_))),
+                "{codec}"
+            );
+        }
+    }
+}
@@ -41,13 +41,16 @@
 //! }
 //! ```
 
+mod codec;
 pub mod parquet;
 
 use super::object_store::ObjectStore;
+use super::wal::LogSequenceNumber;
 use akidb_core::{CollectionId, CoreError, CoreResult, VectorDocument};
 use async_trait::async_trait;
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
+pub use codec::{SnapshotDecoder, SnapshotEncoder};
 pub use parquet::{ParquetSnapshotConfig, ParquetSnapshotter};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -120,6 +123,10 @@ pub struct SnapshotMetadata {
     pub compression: CompressionCodec,
     /// Snapshot format (JSON or Parquet)
     pub format: SnapshotFormat,
+    /// WAL position the snapshot covers: every entry up to it is included
+    /// (later entries may be too). `None` for snapshots taken without one.
+    #[serde(default)]
+    pub wal_lsn: Option<LogSequenceNumber>,
 }
 
 /// Compression codec for snapshot storage
@@ -164,6 +171,22 @@ pub trait Snapshotter: Send + Sync {
         &self,
         collection_id: CollectionId,
         vectors: Vec<VectorDocument>,
+    ) -> CoreResult<SnapshotId> {
+        self.create_snapshot_at(collection_id, vectors, None).await
+    }
+
+    /// Create snapshot from in-memory vectors, recording the WAL position
+    /// they reflect in the metadata (see [`SnapshotMetadata::wal_lsn`])
+    ///
+    /// # Errors
+    ///
+    /// - `CoreError::ValidationError` if vectors is empty
+    /// - `CoreError::StorageError` if upload fails
+    async fn create_snapshot_at(
+        &self,
+        collection_id: CollectionId,
+        vectors: Vec<VectorDocument>,
+        wal_lsn: Option<LogSequenceNumber>,
     ) -> CoreResult<SnapshotId>;
 
     /// Restore vectors from snapshot
@@ -215,9 +238,13 @@ pub trait Snapshotter: Send + Sync {
 /// Stores vectors as JSON with metadata sidecar file.
 /// Suitable for datasets up to 100GB (target scale for AkiDB 2.0).
 ///
+/// The JSON is streamed through the compressor while it is serialized (and
+/// parsed straight out of the decompressor on restore), so the uncompressed
+/// snapshot is never held in memory.
+///
 /// # File Format
 ///
-/// - Snapshot: `snapshots/{snapshot_id}.json[.gz|.zst|.lz4]`
+/// - Snapshot: `snapshots/{snapshot_id}.json[.snappy|.zst|.lz4]`
 /// - Metadata: `snapshots/{snapshot_id}.meta.json`
 ///
 /// # Future: Parquet Enhancement
@@ -255,40 +282,15 @@ impl JsonSnapshotter {
     fn metadata_key(&self, snapshot_id: SnapshotId) -> String {
         format!("snapshots/{}.meta.json", snapshot_id)
     }
-
-    /// Compress data according to compression codec
-    fn compress(&self, data: Vec<u8>) -> CoreResult<Vec<u8>> {
-        match self.compression {
-            CompressionCodec::None => Ok(data),
-            // Note: For Week 3, we implement None compression
-            // Snappy/Zstd/Lz4 can be added in Week 5 polish phase
-            _ => Err(CoreError::ValidationError(format!(
-                "Compression codec {:?} not yet implemented",
-                self.compression
-            ))),
-        }
-    }
-
-    /// Decompress data according to compression codec
-    fn decompress(&self, data: Vec<u8>) -> CoreResult<Vec<u8>> {
-        match self.compression {
-            CompressionCodec::None => Ok(data),
-            // Note: For Week 3, we implement None compression
-            // Snappy/Zstd/Lz4 can be added in Week 5 polish phase
-            _ => Err(CoreError::ValidationError(format!(
-                "Compression codec {:?} not yet implemented",
-                self.compression
-            ))),
-        }
-    }
 }
 
 #[async_trait]
 impl Snapshotter for JsonSnapshotter {
-    async fn create_snapshot(
+    async fn create_snapshot_at(
         &self,
         collection_id: CollectionId,
         vectors: Vec<VectorDocument>,
+        wal_lsn: Option<LogSequenceNumber>,
     ) -> CoreResult<SnapshotId> {
         if vectors.is_empty() {
             return Err(CoreError::ValidationError(
@@ -299,11 +301,10 @@ impl Snapshotter for JsonSnapshotter {
         let snapshot_id = SnapshotId::new();
         let dimension = vectors[0].vector.len() as u32;
 
-        // Serialize to JSON
-        let json_data = serde_json::to_vec(&vectors)?;
-
-        // Compress
-        let compressed_data = self.compress(json_data)?;
+        // Serialize to JSON, compressing as we go
+        let mut encoder = self.compression.encoder(Vec::new())?;
+        serde_json::to_writer(&mut encoder, &vectors)?;
+        let compressed_data = encoder.finish()?;
         let size_bytes = compressed_data.len() as u64;
 
         // Upload to object store
@@ -322,6 +323,7 @@ impl Snapshotter for JsonSnapshotter {
             size_bytes,
             compression: self.compression,
             format: SnapshotFormat::Json,
+            wal_lsn,
         };
 
         let metadata_json = serde_json::to_vec(&metadata)?;
@@ -338,11 +340,17 @@ impl Snapshotter for JsonSnapshotter {
         let snapshot_key = self.snapshot_key(snapshot_id);
         let compressed_data = self.object_store.get(&snapshot_key).await?;
 
-        // Decompress
-        let json_data = self.decompress(compressed_data.to_vec())?;
-
-        // Deserialize
-        let vectors: Vec<VectorDocument> = serde_json::from_slice(&json_data)?;
+        // Decompress and deserialize in one pass
+        let decoder = self.compression.decoder(compressed_data.as_ref())?;
+        let vectors: Vec<VectorDocument> = serde_json::from_reader(std::io::BufReader::new(decoder))
+            .map_err(|e| {
+                // Read errors come from the decompressor (corrupted data)
+                if e.is_io() {
+                    codec::decompress_error(self.compression, &std::io::Error::from(e))
+                } else {
+                    CoreError::from(e)
+                }
+            })?;
 
         Ok(vectors)
     }
@@ -414,6 +422,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: Some(format!("doc-{}", i)),
                 vector: vec![i as f32; dimension],
+                sparse: None,
                 metadata: Some(serde_json::json!({"index": i})),
                 inserted_at: Utc::now(),
             })
@@ -461,6 +470,71 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_compressed_snapshots() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = Arc::new(LocalObjectStore::new(temp_dir.path()).await.unwrap());
+        let vectors = create_test_vectors(100, 64);
+        let collection_id = CollectionId::new();
+
+        let uncompressed = JsonSnapshotter::new(store.clone(), CompressionCodec::None);
+        let snapshot_id = uncompressed
+            .create_snapshot(collection_id, vectors.clone())
+            .await
+            .unwrap();
+        let json_size = uncompressed
+            .get_metadata(snapshot_id)
+            .await
+            .unwrap()
+            .size_bytes;
+
+        for codec in [
+            CompressionCodec::Snappy,
+            CompressionCodec::Zstd,
+            CompressionCodec::Lz4,
+        ] {
+            let snapshotter = JsonSnapshotter::new(store.clone(), codec);
+            let snapshot_id = snapshotter
+                .create_snapshot(collection_id, vectors.clone())
+                .await
+                .unwrap();
+
+            let metadata = snapshotter.get_metadata(snapshot_id).await.unwrap();
+            assert_eq!(metadata.compression, codec);
+            assert!(metadata.size_bytes < json_size, "{codec} did not compress");
+
+            let restored = snapshotter.restore_snapshot(snapshot_id).await.unwrap();
+            assert_eq!(restored.len(), vectors.len());
+            for (original, restored) in vectors.iter().zip(restored.iter()) {
+                assert_eq!(original.doc_id, restored.doc_id);
+                assert_eq!(original.vector, restored.vector);
+                assert_eq!(original.metadata, restored.metadata);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restore_corrupted_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = Arc::new(LocalObjectStore::new(temp_dir.path()).await.unwrap());
+        let snapshotter = JsonSnapshotter::new(store.clone(), CompressionCodec::Zstd);
+
+        let snapshot_id = snapshotter
+            .create_snapshot(CollectionId::new(), create_test_vectors(10, 16))
+            .await
+            .unwrap();
+        store
+            .put(
+                &snapshotter.snapshot_key(snapshot_id),
+                Bytes::from_static(b"not a zstd frame"),
+            )
+            .await
+            .unwrap();
+
+        let result = snapshotter.restore_snapshot(snapshot_id).await;
+        assert!(matches!(result, Err(CoreError::DeserializationError(_))));
+    }
+
     #[tokio::test]
     async fn test_snapshot_metadata() {
         let temp_dir = TempDir::new().unwrap();
@@ -5,6 +5,7 @@
 use super::{CompressionCodec, SnapshotFormat, SnapshotId, SnapshotMetadata, Snapshotter};
 use crate::object_store::ObjectStore;
 use crate::parquet_encoder::{ParquetConfig, ParquetEncoder};
+use crate::wal::LogSequenceNumber;
 use akidb_core::{CollectionId, CoreError, CoreResult, VectorDocument};
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -93,10 +94,11 @@ impl ParquetSnapshotter {
 
 #[async_trait]
 impl Snapshotter for ParquetSnapshotter {
-    async fn create_snapshot(
+    async fn create_snapshot_at(
         &self,
         collection_id: CollectionId,
         vectors: Vec<VectorDocument>,
+        wal_lsn: Option<LogSequenceNumber>,
     ) -> CoreResult<SnapshotId> {
         // Validate input
         if vectors.is_empty() {
@@ -137,6 +139,7 @@ impl Snapshotter for ParquetSnapshotter {
             size_bytes: parquet_bytes.len() as u64,
             compression: self.config.to_compression_codec(),
             format: SnapshotFormat::Parquet,
+            wal_lsn,
         };
 
         let metadata_json = serde_json::to_vec(&metadata)?;
@@ -259,6 +262,7 @@ mod tests {
                 doc_id: DocumentId::new(),
                 external_id: Some(format!("doc-{}", i)),
                 vector: vec![i as f32; dimension],
+                sparse: None,
                 metadata: Some(serde_json::json!({"index": i})),
                 inserted_at: Utc::now(),
             })
@@ -316,6 +320,7 @@ mod tests {
             doc_id: DocumentId::new(),
             external_id: None,
             vector: vec![0.0; 32], // Wrong dimension!
+            sparse: None,
             metadata: None,
             inserted_at: Utc::now(),
         });
@@ -3,25 +3,32 @@
 //! Provides three tiering policies for different performance/cost trade-offs.
 
 use crate::dlq::DeadLetterQueue;
+use crate::external_ids::ExternalIdIndex;
+use crate::gc::{self, GcReport};
+use crate::lock::{CollectionLock, ObjectStoreLeaseRepository};
 use crate::object_store::{LocalObjectStore, ObjectStore, S3Config, S3ObjectStore};
-use crate::snapshotter::{JsonSnapshotter, Snapshotter};
+use crate::restore::{self, RestoreOutcome, RestoreReport};
+use crate::snapshotter::{JsonSnapshotter, SnapshotId, SnapshotMetadata, Snapshotter};
 use crate::tiering::{StorageConfig, TieringPolicy};
 use crate::wal::{FileWAL, FileWALConfig, LogEntry, LogSequenceNumber, WriteAheadLog};
-use akidb_core::{CollectionId, CoreResult, DocumentId, VectorDocument};
+use akidb_core::{CollectionId, CoreResult, DocumentId, LeaseRepository, VectorDocument};
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use tokio::sync::Notify;
 use tokio::task::JoinHandle;
+use tracing::Instrument;
 
 /// Task for background S3 upload
 #[derive(Clone, Debug)]
 struct S3UploadTask {
     collection_id: CollectionId,
     doc: VectorDocument,
+    /// Span of the write that queued the upload, so the upload joins its trace
+    span: tracing::Span,
 }
 
 /// Metadata for a failed S3 upload awaiting retry.
@@ -73,6 +80,25 @@ enum ErrorClass {
     Permanent, // Move to DLQ
 }
 
+/// Durability state of a collection's WAL and upload pipeline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalState {
+    /// LSN of the most recent WAL entry
+    pub current_lsn: u64,
+    /// LSN of the last checkpoint (entries up to it are in a snapshot)
+    pub checkpoint_lsn: u64,
+    /// WAL files on disk
+    pub file_count: usize,
+    /// Total size of WAL files on disk
+    pub disk_bytes: u64,
+    /// Documents waiting for their first S3 upload (`MemoryS3` policy)
+    pub pending_uploads: usize,
+    /// Failed uploads waiting to be retried
+    pub pending_retries: usize,
+    /// Uploads that failed permanently
+    pub dlq_size: usize,
+}
+
 /// Storage metrics for monitoring and debugging
 #[derive(Debug, Clone, Default)]
 pub struct StorageMetrics {
@@ -108,6 +134,10 @@ pub struct StorageMetrics {
     pub circuit_breaker_state: u8,
     /// Circuit breaker error rate (0.0-1.0)
     pub circuit_breaker_error_rate: f64,
+    /// Objects listed by S3 garbage collection
+    pub gc_objects_scanned: u64,
+    /// Unreferenced objects deleted by S3 garbage collection
+    pub gc_objects_deleted: u64,
 }
 
 impl StorageMetrics {
@@ -209,6 +239,21 @@ impl StorageMetrics {
         output.push_str("# TYPE akidb_compactions_total counter\n");
         output.push_str(&format!("akidb_compactions_total {}\n", self.compactions));
 
+        // === Garbage Collection Metrics ===
+        output.push_str("# HELP akidb_gc_objects_scanned_total Objects listed by S3 garbage collection\n");
+        output.push_str("# TYPE akidb_gc_objects_scanned_total counter\n");
+        output.push_str(&format!(
+            "akidb_gc_objects_scanned_total {}\n",
+            self.gc_objects_scanned
+        ));
+
+        output.push_str("# HELP akidb_gc_objects_deleted_total Unreferenced objects deleted by S3 garbage collection\n");
+        output.push_str("# TYPE akidb_gc_objects_deleted_total counter\n");
+        output.push_str(&format!(
+            "akidb_gc_objects_deleted_total {}\n",
+            self.gc_objects_deleted
+        ));
+
         output
     }
 }
@@ -279,12 +324,24 @@ pub struct StorageBackend {
     snapshotter: Arc<JsonSnapshotter>,
     object_store: Option<Arc<dyn ObjectStore>>,
 
+    // Store holding snapshots and the serialized index graph
+    snapshot_store: Arc<dyn ObjectStore>,
+
+    // Locks in the object store, shared with other nodes using the bucket
+    leases: Option<Arc<dyn LeaseRepository>>,
+
     // In-memory vector storage (Memory and MemoryS3 policies)
     vector_store: Arc<RwLock<HashMap<DocumentId, VectorDocument>>>,
 
     // For S3Only policy: LRU cache of recently accessed vectors
     pub(crate) vector_cache: Option<Arc<RwLock<lru::LruCache<DocumentId, VectorDocument>>>>,
 
+    // External ID of every stored document (all policies)
+    external_ids: Arc<ExternalIdIndex>,
+
+    // Serializes upserts by external ID
+    external_id_upserts: tokio::sync::Mutex<()>,
+
     // Metrics
     metrics: Arc<RwLock<StorageMetrics>>,
 
@@ -311,6 +368,10 @@ pub struct StorageBackend {
 
     // Phase 7 Week 1 Days 3-4: DLQ cleanup worker
     dlq_cleanup_handle: Option<JoinHandle<()>>,
+
+    // Documents referenced by each snapshot, as of the last GC pass
+    // (snapshots are immutable, so each is read once)
+    gc_snapshot_refs: RwLock<HashMap<SnapshotId, Vec<DocumentId>>>,
 }
 
 /// Classify S3 error as transient (retry) or permanent (DLQ).
@@ -491,7 +552,7 @@ impl StorageBackend {
             crate::tiering::CompressionType::Lz4 => crate::snapshotter::CompressionCodec::Lz4,
         };
 
-        let snapshotter = Arc::new(JsonSnapshotter::new(snapshotter_store, compression));
+        let snapshotter = Arc::new(JsonSnapshotter::new(snapshotter_store.clone(), compression));
 
         // Create vector cache (for S3Only policy)
         let vector_cache = if config.tiering_policy == TieringPolicy::S3Only {
@@ -540,14 +601,24 @@ impl StorageBackend {
         // FIX BUG #16: Extract collection_id from config BEFORE consuming it
         let collection_id = config.collection_id;
 
+        let leases = object_store.clone().map(|store| {
+            Arc::new(ObjectStoreLeaseRepository::new(store)) as Arc<dyn LeaseRepository>
+        });
+        let external_ids =
+            Arc::new(Self::external_id_index(&config, collection_id, &snapshotter_store).await?);
+
         let mut backend = Self {
             collection_id, // Store the real collection_id instead of generating random ones
             config: config.clone(),
             wal,
             snapshotter,
             object_store: object_store.clone(),
+            snapshot_store: snapshotter_store,
+            leases: leases.clone(),
             vector_store: vector_store_ref.clone(),
             vector_cache,
+            external_ids: external_ids.clone(),
+            external_id_upserts: tokio::sync::Mutex::new(()),
             metrics: metrics_ref.clone(),
             s3_upload_queue: s3_upload_queue.clone(),
             s3_upload_notify: s3_upload_notify.clone(),
@@ -561,6 +632,7 @@ impl StorageBackend {
             retry_config: retry_config.clone(),
             circuit_breaker: circuit_breaker.clone(),
             dlq_cleanup_handle: None,
+            gc_snapshot_refs: RwLock::new(HashMap::new()),
         };
 
         // Recover from WAL on startup
@@ -606,21 +678,25 @@ impl StorageBackend {
             let wal_clone = wal_ref;
             let snapshotter_clone = snapshotter_ref;
             let vector_store_clone = vector_store_ref;
+            let external_ids_clone = external_ids;
             let notify_clone = compaction_notify;
             let metrics_clone = metrics_ref;
             let compaction_config = config.compaction_config.clone();
             // FIX BUG #16: Clone collection_id for background worker
             let coll_id = collection_id;
+            let leases_clone = leases;
 
             backend.compaction_handle = Some(tokio::spawn(async move {
                 Self::compaction_worker(
                     wal_clone,
                     snapshotter_clone,
                     vector_store_clone,
+                    external_ids_clone,
                     notify_clone,
                     metrics_clone,
                     compaction_config,
                     coll_id, // FIX BUG #16: Pass collection_id
+                    leases_clone,
                 )
                 .await;
             }));
@@ -706,6 +782,7 @@ impl StorageBackend {
         };
 
         let snapshotter = Arc::new(JsonSnapshotter::new(mock_s3.clone(), compression));
+        let snapshotter_store: Arc<dyn ObjectStore> = mock_s3.clone();
 
         // Create vector cache (for S3Only policy)
         let vector_cache = if config.tiering_policy == TieringPolicy::S3Only {
@@ -747,14 +824,24 @@ impl StorageBackend {
         // FIX BUG #16: Extract collection_id from config BEFORE consuming it
         let collection_id = config.collection_id;
 
+        let leases = object_store.clone().map(|store| {
+            Arc::new(ObjectStoreLeaseRepository::new(store)) as Arc<dyn LeaseRepository>
+        });
+        let external_ids =
+            Arc::new(Self::external_id_index(&config, collection_id, &snapshotter_store).await?);
+
         let mut backend = Self {
             collection_id, // Store the real collection_id instead of generating random ones
             config: config.clone(),
             wal,
             snapshotter,
             object_store: object_store.clone(),
+            snapshot_store: snapshotter_store,
+            leases: leases.clone(),
             vector_store: vector_store_ref.clone(),
             vector_cache,
+            external_ids: external_ids.clone(),
+            external_id_upserts: tokio::sync::Mutex::new(()),
             metrics: metrics_ref.clone(),
             s3_upload_queue: s3_upload_queue.clone(),
             s3_upload_notify: s3_upload_notify.clone(),
@@ -768,6 +855,7 @@ impl StorageBackend {
             retry_config: retry_config.clone(),
             circuit_breaker: circuit_breaker.clone(),
             dlq_cleanup_handle: None,
+            gc_snapshot_refs: RwLock::new(HashMap::new()),
         };
 
         // Recover from WAL on startup
@@ -813,21 +901,25 @@ impl StorageBackend {
             let wal_clone = wal_ref;
             let snapshotter_clone = snapshotter_ref;
             let vector_store_clone = vector_store_ref;
+            let external_ids_clone = external_ids;
             let notify_clone = compaction_notify;
             let metrics_clone = metrics_ref;
             let compaction_config = config.compaction_config.clone();
             // FIX BUG #16: Clone collection_id for background worker
             let coll_id = collection_id;
+            let leases_clone = leases;
 
             backend.compaction_handle = Some(tokio::spawn(async move {
                 Self::compaction_worker(
                     wal_clone,
                     snapshotter_clone,
                     vector_store_clone,
+                    external_ids_clone,
                     notify_clone,
                     metrics_clone,
                     compaction_config,
                     coll_id, // FIX BUG #16: Pass collection_id
+                    leases_clone,
                 )
                 .await;
             }));
@@ -947,7 +1039,9 @@ impl StorageBackend {
                 };
 
                 // Attempt S3 upload
-                match object_store.put(&key, Bytes::from(data)).await {
+                let span = &task.task.span;
+                let retry_span = tracing::info_span!(parent: span, "storage.s3_upload_retry", key = %key);
+                match object_store.put(&key, Bytes::from(data)).instrument(retry_span).await {
                     Ok(_) => {
                         tracing::info!(
                             "Retry successful after {} attempts: {}",
@@ -1063,7 +1157,13 @@ impl StorageBackend {
 
                 match serde_json::to_vec(&task.doc) {
                     Ok(data) => {
-                        match object_store.put(&key, Bytes::from(data)).await {
+                        let upload_span =
+                            tracing::info_span!(parent: &task.span, "storage.s3_upload", key = %key);
+                        match object_store
+                            .put(&key, Bytes::from(data))
+                            .instrument(upload_span)
+                            .await
+                        {
                             Ok(()) => {
                                 tracing::trace!("S3 upload succeeded: {}", key);
                                 metrics.write().s3_uploads += 1;
@@ -1107,10 +1207,12 @@ impl StorageBackend {
         wal: Arc<FileWAL>,
         snapshotter: Arc<JsonSnapshotter>,
         vector_store: Arc<RwLock<HashMap<DocumentId, VectorDocument>>>,
+        external_ids: Arc<ExternalIdIndex>,
         notify: Arc<Notify>,
         metrics: Arc<RwLock<StorageMetrics>>,
         compaction_config: crate::tiering::CompactionConfig,
         collection_id: CollectionId, // FIX BUG #16: Pass collection_id for snapshots
+        leases: Option<Arc<dyn LeaseRepository>>,
     ) {
         use std::time::Duration;
 
@@ -1139,12 +1241,39 @@ impl StorageBackend {
                 continue;
             }
 
+            // Another node sharing the bucket may be compacting this collection
+            let lock = match &leases {
+                Some(leases) => {
+                    match CollectionLock::acquire(Arc::clone(leases), "compaction", collection_id)
+                        .await
+                    {
+                        Ok(lock) => Some(lock),
+                        Err(e) => {
+                            tracing::debug!("Compaction skipped: {}", e);
+                            continue;
+                        }
+                    }
+                }
+                None => None,
+            };
+
             // Perform compaction (non-blocking to insert path)
             tracing::info!("Starting background compaction");
             let start = std::time::Instant::now();
 
             // FIX BUG #16: Pass collection_id to perform_compaction
-            match Self::perform_compaction(&wal, &snapshotter, &vector_store, collection_id).await {
+            let result = Self::perform_compaction(
+                &wal,
+                &snapshotter,
+                &vector_store,
+                &external_ids,
+                collection_id,
+                lock.as_ref(),
+            )
+            .await;
+            Self::release_lock(lock, "compaction").await;
+
+            match result {
                 Ok(()) => {
                     let elapsed = start.elapsed();
                     tracing::info!("Compaction complete in {:?}", elapsed);
@@ -1169,17 +1298,30 @@ impl StorageBackend {
         wal: &Arc<FileWAL>,
         snapshotter: &Arc<JsonSnapshotter>,
         vector_store: &Arc<RwLock<HashMap<DocumentId, VectorDocument>>>,
+        external_ids: &ExternalIdIndex,
         collection_id: CollectionId, // FIX BUG #16: Use real collection_id
+        lock: Option<&CollectionLock>,
     ) -> CoreResult<()> {
-        // 1. Collect current vector state
+        // 1. Collect current vector state (the WAL position first, so the
+        // snapshot covers at least every entry up to it)
+        let wal_lsn = wal.current_lsn().await?;
         let vectors: Vec<VectorDocument> = vector_store.read().values().cloned().collect();
 
         // 2. Create snapshot
         // FIX BUG #16: Use the real collection_id passed as parameter
-        snapshotter.create_snapshot(collection_id, vectors).await?;
+        snapshotter
+            .create_snapshot_at(collection_id, vectors, Some(wal_lsn))
+            .await?;
 
-        // 3. Create checkpoint in WAL
+        // Another node may have taken over; leave the WAL to it
+        if let Some(lock) = lock {
+            lock.ensure_held()?;
+        }
+
+        // 3. Create checkpoint in WAL (saving the external IDs the
+        // truncated entries may be the only record of)
         let current_lsn = wal.current_lsn().await?;
+        external_ids.save().await?;
         let checkpoint_entry = LogEntry::Checkpoint {
             lsn: current_lsn,
             timestamp: Utc::now(),
@@ -1206,31 +1348,94 @@ impl StorageBackend {
     /// Returns error if:
     /// - WAL append fails
     /// - S3 upload fails (S3Only policy only, MemoryS3 fails silently)
+    #[tracing::instrument(name = "storage.insert", skip_all, fields(collection_id = %self.collection_id))]
     pub async fn insert(&self, doc: VectorDocument) -> CoreResult<()> {
         // 1. Append to WAL (all policies)
+        let (log_entry, entry_size_bytes) = self.upsert_entry(&doc);
+
+        self.wal.append(log_entry).await?;
+        self.wal.flush().await?;
+
+        // Track WAL size
+        self.metrics.write().wal_size_bytes += entry_size_bytes as u64;
+
+        // 2. Handle tiering policy
+        self.store(doc).await?;
+
+        // Update metrics
+        self.metrics.write().inserts += 1;
+
+        Ok(())
+    }
+
+    /// Insert multiple vector documents
+    ///
+    /// All WAL entries are appended with one batch append and a single
+    /// flush, instead of one flush per document as with [`Self::insert`].
+    /// Tiering is then applied per document as in `insert`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the WAL append fails (nothing is stored) or an S3
+    /// upload fails (S3Only policy only; earlier documents stay stored)
+    #[tracing::instrument(name = "storage.insert_batch", skip_all, fields(collection_id = %self.collection_id, count = docs.len()))]
+    pub async fn insert_batch(&self, docs: Vec<VectorDocument>) -> CoreResult<()> {
+        if docs.is_empty() {
+            return Ok(());
+        }
+
+        // 1. Append to WAL (all policies)
+        let (log_entries, sizes): (Vec<LogEntry>, Vec<usize>) =
+            docs.iter().map(|doc| self.upsert_entry(doc)).unzip();
+
+        self.wal.append_batch(log_entries).await?;
+        self.wal.flush().await?;
+
+        // Track WAL size
+        self.metrics.write().wal_size_bytes += sizes.iter().sum::<usize>() as u64;
+
+        // 2. Handle tiering policy
+        let count = docs.len() as u64;
+        for doc in docs {
+            self.store(doc).await?;
+        }
+
+        // Update metrics
+        self.metrics.write().inserts += count;
+
+        Ok(())
+    }
+
+    /// WAL entry for inserting `doc`, with its estimated size in bytes.
+    fn upsert_entry(&self, doc: &VectorDocument) -> (LogEntry, usize) {
         // FIX BUG #16: Use real collection_id instead of generating random ones
         let log_entry = LogEntry::Upsert {
             collection_id: self.collection_id, // Now using the real collection_id!
             doc_id: doc.doc_id.clone(),
             vector: doc.vector.clone(),
+            sparse: doc.sparse.clone(),
             external_id: doc.external_id.clone(),
             metadata: doc.metadata.clone(),
             timestamp: doc.inserted_at,
         };
 
         // FIX BUG #6: Track WAL size for compaction threshold
-        // Estimate entry size: UUID (16) + vector (dim * 4) + metadata overhead (~100)
-        let entry_size_bytes = 16 + (doc.vector.len() * 4) + 100
+        // Estimate entry size: UUID (16) + vector (dim * 4) + sparse pairs (8 each)
+        // + metadata overhead (~100)
+        let entry_size_bytes = 16
+            + (doc.vector.len() * 4)
+            + doc.sparse.as_ref().map_or(0, |sparse| sparse.len() * 8)
+            + 100
             + doc.external_id.as_ref().map_or(0, |s| s.len())
             + doc.metadata.as_ref().map_or(0, |_| 200); // JSON metadata estimate
 
-        self.wal.append(log_entry).await?;
-        self.wal.flush().await?;
+        (log_entry, entry_size_bytes)
+    }
 
-        // Track WAL size
-        self.metrics.write().wal_size_bytes += entry_size_bytes as u64;
+    /// Stores an inserted document according to the tiering policy.
+    async fn store(&self, doc: VectorDocument) -> CoreResult<()> {
+        self.external_ids.insert(&doc);
 
-        // 2. Handle tiering policy
         match self.config.tiering_policy {
             TieringPolicy::Memory => {
                 // Store in HashMap
@@ -1248,7 +1453,11 @@ impl StorageBackend {
                 let collection_id = self.collection_id;
                 self.s3_upload_queue
                     .write()
-                    .push_back(S3UploadTask { collection_id, doc });
+                    .push_back(S3UploadTask {
+                        collection_id,
+                        doc,
+                        span: tracing::Span::current(),
+                    });
                 self.s3_upload_notify.notify_one();
 
                 tracing::trace!("Enqueued S3 upload task");
@@ -1278,9 +1487,6 @@ impl StorageBackend {
             }
         }
 
-        // Update metrics
-        self.metrics.write().inserts += 1;
-
         Ok(())
     }
 
@@ -1289,6 +1495,7 @@ impl StorageBackend {
     /// # Errors
     ///
     /// Returns error if document not found or S3 download fails
+    #[tracing::instrument(name = "storage.get", skip_all, fields(collection_id = %self.collection_id))]
     pub async fn get(&self, doc_id: &DocumentId) -> CoreResult<Option<VectorDocument>> {
         // FIX BUG #19: Increment queries counter for monitoring/dashboards
         // Without this, Prometheus/Grafana dashboards show 0 queries forever
@@ -1372,6 +1579,7 @@ impl StorageBackend {
     /// # Errors
     ///
     /// Returns error if WAL append fails or S3 delete fails
+    #[tracing::instrument(name = "storage.delete", skip_all, fields(collection_id = %self.collection_id))]
     pub async fn delete(&self, doc_id: &DocumentId) -> CoreResult<()> {
         // 1. Append to WAL
         // FIX BUG #16: Use real collection_id
@@ -1385,6 +1593,7 @@ impl StorageBackend {
         self.wal.flush().await?;
 
         // 2. Delete from storage
+        self.external_ids.remove(doc_id);
         match self.config.tiering_policy {
             TieringPolicy::Memory | TieringPolicy::MemoryS3 => {
                 self.vector_store.write().remove(doc_id);
@@ -1467,12 +1676,85 @@ impl StorageBackend {
         metrics
     }
 
+    /// Get WAL position, on-disk footprint, and upload queue depths
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the WAL directory cannot be read
+    pub async fn wal_state(&self) -> CoreResult<WalState> {
+        let current_lsn = self.wal.current_lsn().await?;
+        let (file_count, disk_bytes) = self.wal.disk_usage().await?;
+
+        Ok(WalState {
+            current_lsn: current_lsn.value(),
+            checkpoint_lsn: self.wal.checkpoint_lsn().value(),
+            file_count,
+            disk_bytes,
+            pending_uploads: self.pending_uploads(),
+            pending_retries: self.pending_retries(),
+            dlq_size: self.dead_letter_queue.size(),
+        })
+    }
+
+    /// Highest LSN assigned by the WAL
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the WAL state cannot be read
+    pub async fn current_lsn(&self) -> CoreResult<LogSequenceNumber> {
+        self.wal.current_lsn().await
+    }
+
+    /// WAL entries with LSN >= `from_lsn` that are still on disk, in order
+    ///
+    /// Used to ship the log to standby nodes. Files older than the last
+    /// checkpoint are eventually deleted, so the first entry returned can be
+    /// past `from_lsn`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the WAL files cannot be read
+    pub async fn read_wal(
+        &self,
+        from_lsn: LogSequenceNumber,
+    ) -> CoreResult<Vec<(LogSequenceNumber, LogEntry)>> {
+        self.wal.replay(from_lsn).await
+    }
+
+    /// Number of documents waiting for their first S3 upload
+    #[must_use]
+    pub fn pending_uploads(&self) -> usize {
+        self.s3_upload_queue.read().len()
+    }
+
+    /// Number of failed S3 uploads waiting to be retried
+    #[must_use]
+    pub fn pending_retries(&self) -> usize {
+        self.retry_queue.read().len()
+    }
+
     /// Get storage configuration
     #[must_use]
     pub fn config(&self) -> &StorageConfig {
         &self.config
     }
 
+    /// Leases in this backend's bucket (None without an object store)
+    ///
+    /// Take a [`CollectionLock`] from these before maintenance that must not
+    /// overlap with other nodes sharing the bucket, such as backup or garbage
+    /// collection.
+    #[must_use]
+    pub fn leases(&self) -> Option<Arc<dyn LeaseRepository>> {
+        self.leases.clone()
+    }
+
+    /// Object store for S3 tiering (None without one)
+    #[must_use]
+    pub fn object_store(&self) -> Option<Arc<dyn ObjectStore>> {
+        self.object_store.clone()
+    }
+
     /// Check if compaction is needed
     ///
     /// Returns true if either:
@@ -1508,6 +1790,7 @@ impl StorageBackend {
                 LogEntry::Upsert {
                     doc_id,
                     vector,
+                    sparse,
                     external_id,
                     metadata,
                     timestamp,
@@ -1515,6 +1798,7 @@ impl StorageBackend {
                 } => {
                     // Reconstruct VectorDocument
                     let mut doc = VectorDocument::new(doc_id.clone(), vector);
+                    doc.sparse = sparse;
                     if let Some(ext_id) = external_id {
                         doc = doc.with_external_id(ext_id);
                     }
@@ -1525,6 +1809,7 @@ impl StorageBackend {
                     doc.inserted_at = timestamp;
 
                     // Apply to storage
+                    self.external_ids.insert(&doc);
                     match self.config.tiering_policy {
                         TieringPolicy::Memory | TieringPolicy::MemoryS3 => {
                             self.vector_store.write().insert(doc_id, doc);
@@ -1540,6 +1825,7 @@ impl StorageBackend {
 
                 LogEntry::Delete { doc_id, .. } => {
                     // Apply deletion
+                    self.external_ids.remove(&doc_id);
                     match self.config.tiering_policy {
                         TieringPolicy::Memory | TieringPolicy::MemoryS3 => {
                             self.vector_store.write().remove(&doc_id);
@@ -1575,34 +1861,42 @@ impl StorageBackend {
     /// # Errors
     ///
     /// Returns error if:
+    /// - Another node sharing the bucket is compacting this collection
+    ///   (`CoreError::InvalidState`)
     /// - Snapshot creation fails
     /// - S3 upload fails (MemoryS3/S3Only policies)
     /// - WAL checkpoint fails
+    #[tracing::instrument(name = "storage.compact", skip_all, fields(collection_id = %self.collection_id))]
     pub async fn compact(&self) -> CoreResult<()> {
-        // 1. Collect current vector state
-        let vectors: Vec<VectorDocument> = match self.config.tiering_policy {
-            TieringPolicy::Memory | TieringPolicy::MemoryS3 => {
-                self.vector_store.read().values().cloned().collect()
-            }
-            TieringPolicy::S3Only => {
-                // For S3Only, snapshot the cache (not full S3 state)
-                if let Some(cache) = &self.vector_cache {
-                    cache.read().iter().map(|(_, v)| v.clone()).collect()
-                } else {
-                    Vec::new()
-                }
-            }
-        };
+        let lock = self.lock("compaction").await?;
+        let result = self.compact_unlocked(lock.as_ref()).await;
+        Self::release_lock(lock, "compaction").await;
+        result
+    }
+
+    /// Compaction proper, once the compaction lock (if any) is held.
+    async fn compact_unlocked(&self, lock: Option<&CollectionLock>) -> CoreResult<()> {
+        // 1. Collect current vector state (the WAL position first, so the
+        // snapshot covers at least every entry up to it)
+        let wal_lsn = self.wal.current_lsn().await?;
+        let vectors = self.snapshot_vectors();
 
         // 2. Create snapshot
         // FIX BUG #4: Use real collection_id instead of random UUID
         // This ensures snapshots are saved under correct S3 prefix for backup/restore
         self.snapshotter
-            .create_snapshot(self.collection_id, vectors)
+            .create_snapshot_at(self.collection_id, vectors, Some(wal_lsn))
             .await?;
 
-        // 3. Create checkpoint in WAL
+        // Another node may have taken over; leave the WAL to it
+        if let Some(lock) = lock {
+            lock.ensure_held()?;
+        }
+
+        // 3. Create checkpoint in WAL (saving the external IDs the
+        // truncated entries may be the only record of)
         let current_lsn = self.wal.current_lsn().await?;
+        self.external_ids.save().await?;
         let checkpoint_entry = LogEntry::Checkpoint {
             lsn: current_lsn,
             timestamp: Utc::now(),
@@ -1629,6 +1923,417 @@ impl StorageBackend {
         Ok(())
     }
 
+    /// Take this collection's `operation` lock, if the backend has leases
+    ///
+    /// # Errors
+    ///
+    /// Returns `CoreError::InvalidState` if another holder has it
+    async fn lock(&self, operation: &str) -> CoreResult<Option<CollectionLock>> {
+        match &self.leases {
+            Some(leases) => Ok(Some(
+                CollectionLock::acquire(Arc::clone(leases), operation, self.collection_id)
+                    .await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Release a lock taken with [`Self::lock`], logging failures
+    async fn release_lock(lock: Option<CollectionLock>, operation: &str) {
+        if let Some(lock) = lock {
+            if let Err(e) = lock.release().await {
+                tracing::warn!("Failed to release {} lock: {}", operation, e);
+            }
+        }
+    }
+
+    /// Documents a snapshot captures
+    fn snapshot_vectors(&self) -> Vec<VectorDocument> {
+        match self.config.tiering_policy {
+            TieringPolicy::Memory | TieringPolicy::MemoryS3 => {
+                self.vector_store.read().values().cloned().collect()
+            }
+            TieringPolicy::S3Only => {
+                // For S3Only, snapshot the cache (not full S3 state)
+                if let Some(cache) = &self.vector_cache {
+                    cache.read().iter().map(|(_, v)| v.clone()).collect()
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    }
+
+    /// Snapshot the current vector state without touching the WAL
+    ///
+    /// Unlike [`compact`](Self::compact), the WAL is kept, so this can run on
+    /// a schedule for backups independently of compaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if:
+    /// - Another holder is backing up this collection, or the backup lock
+    ///   was lost while the snapshot was written (`CoreError::InvalidState`)
+    /// - The collection is empty (`CoreError::ValidationError`)
+    /// - Snapshot upload fails
+    #[tracing::instrument(name = "storage.snapshot", skip_all, fields(collection_id = %self.collection_id))]
+    pub async fn snapshot(&self) -> CoreResult<SnapshotMetadata> {
+        let lock = self.lock("backup").await?;
+        let result = async {
+            let wal_lsn = self.wal.current_lsn().await?;
+            let snapshot_id = self
+                .snapshotter
+                .create_snapshot_at(self.collection_id, self.snapshot_vectors(), Some(wal_lsn))
+                .await?;
+            if let Some(lock) = &lock {
+                lock.ensure_held()?;
+            }
+            self.snapshotter.get_metadata(snapshot_id).await
+        }
+        .await;
+        Self::release_lock(lock, "backup").await;
+        result
+    }
+
+    /// Snapshots of this collection, newest first
+    ///
+    /// Includes the snapshots taken by compaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the snapshot store cannot be listed
+    pub async fn list_snapshots(&self) -> CoreResult<Vec<SnapshotMetadata>> {
+        self.snapshotter.list_snapshots(self.collection_id).await
+    }
+
+    /// Run one S3 garbage collection pass now (see [`crate::gc`])
+    ///
+    /// Deletes per-vector objects whose document is neither live nor in a
+    /// retained snapshot, once they are older than the configured retention
+    /// window. Does nothing without an object store.
+    ///
+    /// The pass holds the collection's "gc" lock, and its "compaction" and
+    /// "backup" locks so no snapshot is written while it runs. Nothing runs
+    /// it on a schedule; run it from one process only (see
+    /// [`GcConfig::enabled`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if:
+    /// - The collection uses the S3Only policy, whose live documents are not
+    ///   all in memory (`CoreError::InvalidState`)
+    /// - One of the locks is held elsewhere or lost during the pass
+    ///   (`CoreError::InvalidState`)
+    /// - Listing snapshots or objects fails
+    #[tracing::instrument(name = "storage.collect_garbage", skip_all, fields(collection_id = %self.collection_id))]
+    pub async fn collect_garbage(&self) -> CoreResult<GcReport> {
+        if self.config.tiering_policy == TieringPolicy::S3Only {
+            return Err(akidb_core::CoreError::invalid_state(
+                "S3 garbage collection is not supported with the S3Only tiering policy",
+            ));
+        }
+        let Some(store) = &self.object_store else {
+            return Ok(GcReport::default());
+        };
+
+        let mut locks = Vec::new();
+        let mut result = Ok(GcReport::default());
+        for operation in ["gc", "compaction", "backup"] {
+            match self.lock(operation).await {
+                Ok(lock) => locks.extend(lock),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        if result.is_ok() {
+            result = self.gc_pass(store.as_ref(), &locks).await;
+        }
+        for lock in locks {
+            Self::release_lock(Some(lock), "gc").await;
+        }
+        result
+    }
+
+    /// One garbage collection pass over the collection's per-vector objects
+    ///
+    /// Documents in the vector store and in any retained snapshot are
+    /// referenced. Snapshots never change, so each is read once and the
+    /// documents it references are kept for later passes.
+    async fn gc_pass(
+        &self,
+        store: &dyn ObjectStore,
+        locks: &[CollectionLock],
+    ) -> CoreResult<GcReport> {
+        // Taken before listing: objects uploaded since are within the retention window
+        let cutoff = self.config.gc_config.cutoff(Utc::now());
+
+        let snapshots = self.snapshotter.list_snapshots(self.collection_id).await?;
+        for snapshot in &snapshots {
+            if self.gc_snapshot_refs.read().contains_key(&snapshot.snapshot_id) {
+                continue;
+            }
+            let docs = self
+                .snapshotter
+                .restore_snapshot(snapshot.snapshot_id)
+                .await?;
+            self.gc_snapshot_refs.write().insert(
+                snapshot.snapshot_id,
+                docs.into_iter().map(|doc| doc.doc_id).collect(),
+            );
+        }
+
+        let mut referenced: HashSet<DocumentId> =
+            self.vector_store.read().keys().copied().collect();
+        {
+            let mut refs = self.gc_snapshot_refs.write();
+            // Deleted snapshots no longer protect their documents
+            refs.retain(|snapshot_id, _| {
+                snapshots
+                    .iter()
+                    .any(|snapshot| snapshot.snapshot_id == *snapshot_id)
+            });
+            for docs in refs.values() {
+                referenced.extend(docs.iter().copied());
+            }
+        }
+
+        let report = gc::collect_garbage(
+            store,
+            &gc::vector_prefix(self.collection_id),
+            &referenced,
+            cutoff,
+            locks,
+        )
+        .await?;
+
+        let mut metrics = self.metrics.write();
+        metrics.gc_objects_scanned += report.objects_scanned;
+        metrics.gc_objects_deleted += report.objects_deleted;
+
+        Ok(report)
+    }
+
+    /// Restore the collection's documents to their state at `target`
+    ///
+    /// The state is rebuilt from the latest snapshot taken at or before
+    /// `target` plus the WAL entries after it (see [`crate::restore`]).
+    /// Changes from the current state are then written to the WAL as
+    /// ordinary upserts and deletes (timestamped now) before being applied,
+    /// so the restore survives restarts, reaches replicas, and can be undone
+    /// by restoring to a time before it.
+    ///
+    /// Writes made while the restore runs may be overwritten; quiesce the
+    /// collection first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if:
+    /// - The collection uses the S3Only policy (`CoreError::InvalidState`)
+    /// - The WAL history needed was discarded and no snapshot taken at or
+    ///   before `target` covers it (`CoreError::InvalidState`)
+    /// - Reading the snapshot or WAL, or appending to the WAL, fails
+    #[tracing::instrument(name = "storage.restore_to", skip_all, fields(collection_id = %self.collection_id, %target))]
+    pub async fn restore_to(&self, target: DateTime<Utc>) -> CoreResult<RestoreOutcome> {
+        if self.config.tiering_policy == TieringPolicy::S3Only {
+            return Err(akidb_core::CoreError::invalid_state(
+                "point-in-time restore is not supported with the S3Only tiering policy",
+            ));
+        }
+
+        // 1. Start from the latest usable snapshot, or from nothing
+        let snapshots = self.list_snapshots().await?;
+        let snapshot = restore::select_snapshot(&snapshots, target);
+        let mut documents = HashMap::new();
+        let from = match snapshot {
+            Some(snapshot) => {
+                for doc in self
+                    .snapshotter
+                    .restore_snapshot(snapshot.snapshot_id)
+                    .await?
+                {
+                    documents.insert(doc.doc_id, doc);
+                }
+                snapshot.wal_lsn.unwrap_or(LogSequenceNumber::ZERO)
+            }
+            None => LogSequenceNumber::ZERO,
+        };
+
+        // 2. Replay the WAL up to the target time
+        let entries = self.wal.replay(from.next()).await?;
+        let (replayed_entries, restored_lsn) =
+            restore::replay_until(&mut documents, from, entries, target)?;
+
+        // 3. Write the difference from the current state through the WAL
+        let document_count = documents.len();
+        let (mut upserted, deleted) = {
+            let current = self.vector_store.read();
+            restore::diff(&current, documents)
+        };
+
+        let now = Utc::now();
+        let mut log_entries: Vec<LogEntry> = deleted
+            .iter()
+            .map(|doc_id| LogEntry::Delete {
+                collection_id: self.collection_id,
+                doc_id: *doc_id,
+                timestamp: now,
+            })
+            .collect();
+        for doc in &mut upserted {
+            doc.inserted_at = now;
+            log_entries.push(self.upsert_entry(doc).0);
+        }
+        if !log_entries.is_empty() {
+            self.wal.append_batch(log_entries).await?;
+            self.wal.flush().await?;
+        }
+
+        {
+            let mut vector_store = self.vector_store.write();
+            for doc_id in &deleted {
+                vector_store.remove(doc_id);
+                self.external_ids.remove(doc_id);
+            }
+        }
+        for doc in &upserted {
+            self.store(doc.clone()).await?;
+        }
+
+        tracing::info!(
+            snapshot_id = ?snapshot.map(|s| s.snapshot_id),
+            replayed_entries,
+            upserted = upserted.len(),
+            deleted = deleted.len(),
+            "Restored collection to {}",
+            target
+        );
+
+        Ok(RestoreOutcome {
+            report: RestoreReport {
+                target,
+                snapshot_id: snapshot.map(|s| s.snapshot_id),
+                replayed_entries,
+                restored_lsn,
+                documents: document_count,
+                upserted: upserted.len(),
+                deleted: deleted.len(),
+            },
+            upserted,
+            deleted,
+        })
+    }
+
+    /// Delete all but the newest `keep` snapshots, returning the deleted ones
+    ///
+    /// # Errors
+    ///
+    /// Returns error if listing or deleting fails; snapshots deleted before
+    /// the failure stay deleted
+    pub async fn prune_snapshots(&self, keep: usize) -> CoreResult<Vec<SnapshotId>> {
+        let lock = self.lock("backup").await?;
+        let result = async {
+            let mut deleted = Vec::new();
+            for metadata in self.list_snapshots().await?.into_iter().skip(keep) {
+                if let Some(lock) = &lock {
+                    lock.ensure_held()?;
+                }
+                self.snapshotter
+                    .delete_snapshot(metadata.snapshot_id)
+                    .await?;
+                deleted.push(metadata.snapshot_id);
+            }
+            Ok(deleted)
+        }
+        .await;
+        Self::release_lock(lock, "backup").await;
+        result
+    }
+
+    /// External ID index for a new backend
+    ///
+    /// Under the S3Only policy the index is stored next to the snapshots,
+    /// as the WAL alone does not cover compacted documents.
+    async fn external_id_index(
+        config: &StorageConfig,
+        collection_id: CollectionId,
+        snapshot_store: &Arc<dyn ObjectStore>,
+    ) -> CoreResult<ExternalIdIndex> {
+        if config.tiering_policy == TieringPolicy::S3Only {
+            ExternalIdIndex::load(
+                Arc::clone(snapshot_store),
+                format!("external-ids/{}.json", collection_id),
+            )
+            .await
+        } else {
+            Ok(ExternalIdIndex::new())
+        }
+    }
+
+    /// Document IDs of the stored documents with the given external IDs
+    ///
+    /// Covers every stored document, including ones the S3Only cache has
+    /// evicted. Unknown external IDs are omitted from the result.
+    #[must_use]
+    pub fn resolve_external_ids(&self, external_ids: &[String]) -> HashMap<String, DocumentId> {
+        self.external_ids.resolve(external_ids)
+    }
+
+    /// Serialize upserts by external ID in this collection
+    ///
+    /// Hold the guard from resolving an external ID until the document is
+    /// stored, so concurrent upserts of an unknown ID create one document.
+    pub async fn lock_external_ids(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.external_id_upserts.lock().await
+    }
+
+    /// Object key of the collection's serialized index graph
+    fn index_graph_key(&self) -> String {
+        format!("index-graphs/{}.bin", self.collection_id)
+    }
+
+    /// Store the collection's serialized index graph next to its snapshots
+    ///
+    /// The graph goes to the object store when one is configured (the
+    /// snapshot directory otherwise), replacing the previously stored graph.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the upload fails
+    #[tracing::instrument(name = "storage.save_index_graph", skip_all, fields(collection_id = %self.collection_id, bytes = graph.len()))]
+    pub async fn save_index_graph(&self, graph: Vec<u8>) -> CoreResult<()> {
+        self.snapshot_store
+            .put(&self.index_graph_key(), Bytes::from(graph))
+            .await
+    }
+
+    /// The collection's stored index graph, if one was saved
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the store cannot be read
+    pub async fn load_index_graph(&self) -> CoreResult<Option<Bytes>> {
+        let key = self.index_graph_key();
+        if !self.snapshot_store.exists(&key).await? {
+            return Ok(None);
+        }
+        self.snapshot_store.get(&key).await.map(Some)
+    }
+
+    /// Delete the collection's stored index graph, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the delete fails
+    pub async fn delete_index_graph(&self) -> CoreResult<()> {
+        let key = self.index_graph_key();
+        if self.snapshot_store.exists(&key).await? {
+            self.snapshot_store.delete(&key).await?;
+        }
+        Ok(())
+    }
+
     /// Auto-compact if thresholds exceeded
     ///
     /// Checks `should_compact()` and automatically compacts if needed.
@@ -1665,6 +2370,28 @@ impl StorageBackend {
         Ok(())
     }
 
+    /// Batch insert with auto-compaction
+    ///
+    /// Like [`Self::insert_with_auto_compact`], for [`Self::insert_batch`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the batch insert fails
+    pub async fn insert_batch_with_auto_compact(
+        &self,
+        docs: Vec<VectorDocument>,
+    ) -> CoreResult<()> {
+        self.insert_batch(docs).await?;
+
+        // Check if compaction needed (non-blocking)
+        if self.should_compact() {
+            tracing::debug!("Compaction threshold reached, notifying worker");
+            self.compaction_notify.notify_one(); // Signal worker (returns immediately)
+        }
+
+        Ok(())
+    }
+
     /// Get current cache statistics (S3Only policy only)
     pub fn get_cache_stats(&self) -> Option<CacheStats> {
         if let Some(cache) = &self.vector_cache {
@@ -1886,7 +2613,7 @@ mod tests {
     async fn test_storage_backend_config_validation() {
         let temp_dir = TempDir::new().unwrap();
         let wal_path = temp_dir.path().join("test.wal");
-        let snapshot_dir = temp_dir.path().join("snapshots");
+        let snapshot_dir = temp_dir.path().join("s3-snapshots");
 
         // Don't create snapshot directory
         // std::fs::create_dir_all(&snapshot_dir).unwrap();
@@ -2076,6 +2803,39 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_insert_batch_recovery_from_wal() {
+        let temp_dir = TempDir::new().unwrap();
+        let wal_path = temp_dir.path().join("test.wal");
+        let snapshot_dir = temp_dir.path().join("snapshots");
+        std::fs::create_dir_all(&snapshot_dir).unwrap();
+
+        let config = StorageConfig::memory(&wal_path);
+        let mut config = config;
+        config.snapshot_dir = snapshot_dir.clone();
+
+        // Create backend and insert 5 documents in one batch
+        {
+            let backend = StorageBackend::new(config.clone()).await.unwrap();
+
+            let docs = (0..5)
+                .map(|i| VectorDocument::new(DocumentId::new(), vec![i as f32; 128]))
+                .collect();
+            backend.insert_batch(docs).await.unwrap();
+
+            assert_eq!(backend.count(), 5);
+            assert_eq!(backend.metrics().inserts, 5);
+        }
+
+        // Create new backend with same WAL path (simulates restart)
+        {
+            let backend = StorageBackend::new(config).await.unwrap();
+
+            // Should have recovered the whole batch
+            assert_eq!(backend.count(), 5);
+        }
+    }
+
     #[tokio::test]
     async fn test_recovery_with_deletes() {
         let temp_dir = TempDir::new().unwrap();
@@ -2155,6 +2915,268 @@ mod tests {
         assert_eq!(backend.count(), 10);
     }
 
+    #[tokio::test]
+    async fn test_snapshot_and_prune() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = StorageConfig::memory(temp_dir.path().join("test.wal"));
+        config.snapshot_dir = temp_dir.path().join("snapshots");
+        std::fs::create_dir_all(&config.snapshot_dir).unwrap();
+        let backend = StorageBackend::new(config).await.unwrap();
+
+        // Nothing to snapshot yet
+        assert!(backend.snapshot().await.is_err());
+
+        let mut snapshots = Vec::new();
+        for i in 0..3 {
+            let doc = VectorDocument::new(DocumentId::new(), vec![i as f32; 16]);
+            backend.insert(doc).await.unwrap();
+            let metadata = backend.snapshot().await.unwrap();
+            assert_eq!(metadata.vector_count, i + 1);
+            snapshots.push(metadata.snapshot_id);
+        }
+        // The WAL is kept, unlike compaction
+        assert_eq!(backend.metrics().compactions, 0);
+        assert_eq!(backend.list_snapshots().await.unwrap().len(), 3);
+
+        let deleted = backend.prune_snapshots(2).await.unwrap();
+        assert_eq!(deleted, vec![snapshots[0]]);
+        let kept: Vec<SnapshotId> = backend
+            .list_snapshots()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|metadata| metadata.snapshot_id)
+            .collect();
+        assert_eq!(kept, vec![snapshots[2], snapshots[1]]);
+    }
+
+    #[tokio::test]
+    async fn test_restore_to_point_in_time() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = StorageConfig::memory(temp_dir.path().join("test.wal"));
+        config.snapshot_dir = temp_dir.path().join("snapshots");
+        std::fs::create_dir_all(&config.snapshot_dir).unwrap();
+        let pause = || tokio::time::sleep(std::time::Duration::from_millis(5));
+
+        let (a, b, c, d) = (
+            DocumentId::new(),
+            DocumentId::new(),
+            DocumentId::new(),
+            DocumentId::new(),
+        );
+        let target;
+        {
+            let backend = StorageBackend::new(config.clone()).await.unwrap();
+            backend.insert(VectorDocument::new(a, vec![1.0; 16])).await.unwrap();
+            backend.insert(VectorDocument::new(b, vec![1.0; 16])).await.unwrap();
+            let snapshot = backend.snapshot().await.unwrap();
+            assert!(snapshot.wal_lsn.is_some());
+
+            backend.insert(VectorDocument::new(c, vec![1.0; 16])).await.unwrap();
+            backend.insert(VectorDocument::new(a, vec![2.0; 16])).await.unwrap();
+            pause().await;
+            target = Utc::now();
+            pause().await;
+
+            backend.delete(&b).await.unwrap();
+            backend.insert(VectorDocument::new(d, vec![1.0; 16])).await.unwrap();
+            backend.insert(VectorDocument::new(a, vec![3.0; 16])).await.unwrap();
+
+            let outcome = backend.restore_to(target).await.unwrap();
+            assert_eq!(outcome.report.snapshot_id, Some(snapshot.snapshot_id));
+            assert_eq!(outcome.report.replayed_entries, 2);
+            assert_eq!(outcome.report.documents, 3);
+            assert_eq!(outcome.report.upserted, 2); // a reverted, b re-created
+            assert_eq!(outcome.deleted, vec![d]);
+        }
+
+        // The restore went through the WAL, so it survives a restart
+        let backend = StorageBackend::new(config).await.unwrap();
+        assert_eq!(backend.count(), 3);
+        assert_eq!(backend.get(&a).await.unwrap().unwrap().vector, vec![2.0; 16]);
+        assert!(backend.get(&b).await.unwrap().is_some());
+        assert!(backend.get(&d).await.unwrap().is_none());
+
+        // Before the first write there was nothing
+        let outcome = backend
+            .restore_to(target - chrono::Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(outcome.report.snapshot_id, None);
+        assert_eq!(outcome.report.documents, 0);
+        assert_eq!(backend.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_index_graph_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = StorageConfig::memory(temp_dir.path().join("test.wal"));
+        config.snapshot_dir = temp_dir.path().join("snapshots");
+        std::fs::create_dir_all(&config.snapshot_dir).unwrap();
+        let backend = StorageBackend::new(config).await.unwrap();
+
+        assert!(backend.load_index_graph().await.unwrap().is_none());
+
+        backend.save_index_graph(vec![1, 2, 3]).await.unwrap();
+        backend.save_index_graph(vec![4, 5]).await.unwrap();
+        let graph = backend.load_index_graph().await.unwrap().unwrap();
+        assert_eq!(&graph[..], &[4, 5]);
+        // Graphs are not mistaken for snapshots
+        assert!(backend.list_snapshots().await.unwrap().is_empty());
+
+        backend.delete_index_graph().await.unwrap();
+        backend.delete_index_graph().await.unwrap();
+        assert!(backend.load_index_graph().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_compaction_excluded_across_nodes() {
+        let temp_dir = TempDir::new().unwrap();
+        let snapshot_dir = temp_dir.path().join("snapshots");
+        std::fs::create_dir_all(&snapshot_dir).unwrap();
+        let bucket: Arc<dyn ObjectStore> = Arc::new(crate::object_store::MockS3ObjectStore::new());
+
+        let mut config = StorageConfig::memory_s3(
+            temp_dir.path().join("test.wal"),
+            snapshot_dir,
+            "mock://bucket".to_string(),
+        );
+        config.enable_background_compaction = false;
+        let backend = StorageBackend::new_with_mock_s3(config, bucket)
+            .await
+            .unwrap();
+        let leases = backend.leases().unwrap();
+        backend
+            .insert(VectorDocument::new(DocumentId::new(), vec![1.0; 128]))
+            .await
+            .unwrap();
+
+        // Another node sharing the bucket is compacting this collection
+        let name = format!("compaction/{}", backend.collection_id);
+        leases
+            .acquire(&name, "other-node", std::time::Duration::from_secs(60))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let err = backend.compact().await.unwrap_err();
+        assert!(matches!(err, akidb_core::CoreError::InvalidState { .. }));
+        assert_eq!(backend.metrics().compactions, 0);
+
+        assert!(leases.release(&name, "other-node").await.unwrap());
+        backend.compact().await.unwrap();
+        assert_eq!(backend.metrics().compactions, 1);
+
+        // The lock is released after compacting
+        assert!(leases
+            .acquire(&name, "other-node", std::time::Duration::from_secs(60))
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_keeps_snapshotted_documents() {
+        let temp_dir = TempDir::new().unwrap();
+        let snapshot_dir = temp_dir.path().join("snapshots");
+        std::fs::create_dir_all(&snapshot_dir).unwrap();
+        let bucket = Arc::new(
+            LocalObjectStore::new(temp_dir.path().join("bucket"))
+                .await
+                .unwrap(),
+        );
+
+        let mut config = StorageConfig::memory_s3(
+            temp_dir.path().join("test.wal"),
+            snapshot_dir,
+            "local://bucket".to_string(),
+        );
+        config.enable_background_compaction = false;
+        config.gc_config.enabled = false;
+        config.gc_config.retention_seconds = 0;
+        let backend = StorageBackend::new_with_mock_s3(config, bucket.clone())
+            .await
+            .unwrap();
+
+        let docs: Vec<_> = (0..3)
+            .map(|i| VectorDocument::new(DocumentId::new(), vec![i as f32; 16]))
+            .collect();
+        for doc in &docs {
+            backend.insert(doc.clone()).await.unwrap();
+        }
+        let prefix = gc::vector_prefix(backend.collection_id);
+        for _ in 0..100 {
+            if bucket.list(&prefix).await.unwrap().len() == docs.len() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        backend.snapshot().await.unwrap();
+        backend.delete(&docs[0].doc_id).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        // Still in a snapshot
+        let report = backend.collect_garbage().await.unwrap();
+        assert_eq!(report.objects_scanned, 3);
+        assert_eq!(report.objects_deleted, 0);
+
+        // Not while another holder may be writing a snapshot
+        let lock = CollectionLock::acquire(
+            backend.leases().unwrap(),
+            "compaction",
+            backend.collection_id,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            backend.collect_garbage().await,
+            Err(akidb_core::CoreError::InvalidState { .. })
+        ));
+        lock.release().await.unwrap();
+
+        backend.prune_snapshots(0).await.unwrap();
+        let report = backend.collect_garbage().await.unwrap();
+        assert_eq!(report.objects_scanned, 3);
+        assert_eq!(report.objects_deleted, 1);
+        assert!(!bucket
+            .exists(&format!("{}{}", prefix, docs[0].doc_id))
+            .await
+            .unwrap());
+
+        let metrics = backend.metrics();
+        assert_eq!(metrics.gc_objects_scanned, 6);
+        assert_eq!(metrics.gc_objects_deleted, 1);
+        assert!(metrics
+            .export_prometheus()
+            .contains("akidb_gc_objects_deleted_total 1"));
+    }
+
+    #[tokio::test]
+    async fn test_wal_state_tracks_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = StorageConfig::memory(temp_dir.path().join("test.wal"));
+        config.snapshot_dir = temp_dir.path().join("snapshots");
+        std::fs::create_dir_all(&config.snapshot_dir).unwrap();
+
+        let backend = StorageBackend::new(config).await.unwrap();
+        for i in 0..5u8 {
+            let doc = VectorDocument::new(DocumentId::new(), vec![f32::from(i); 16]);
+            backend.insert(doc).await.unwrap();
+        }
+
+        let before = backend.wal_state().await.unwrap();
+        assert!(before.current_lsn >= 5);
+        assert_eq!(before.checkpoint_lsn, 0);
+        assert!(before.file_count >= 1);
+        assert!(before.disk_bytes > 0);
+        assert_eq!(before.pending_uploads, 0);
+
+        backend.compact().await.unwrap();
+        let after = backend.wal_state().await.unwrap();
+        assert!(after.checkpoint_lsn >= before.current_lsn);
+    }
+
     #[tokio::test]
     async fn test_compaction_threshold() {
         let temp_dir = TempDir::new().unwrap();
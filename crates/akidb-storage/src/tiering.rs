@@ -171,6 +171,9 @@ pub struct StorageConfig {
 
     /// DLQ configuration (Phase 7 Week 1 Days 3-4)
     pub dlq_config: crate::dlq::DLQConfig,
+
+    /// Garbage collection of per-vector S3 objects (MemoryS3 policy)
+    pub gc_config: crate::gc::GcConfig,
 }
 
 impl Default for StorageConfig {
@@ -198,6 +201,7 @@ impl Default for StorageConfig {
             circuit_breaker_enabled: true,
             circuit_breaker_config: Some(crate::circuit_breaker::CircuitBreakerConfig::default()),
             dlq_config: crate::dlq::DLQConfig::default(),
+            gc_config: crate::gc::GcConfig::default(),
         }
     }
 }
@@ -231,10 +235,18 @@ impl StorageConfig {
     }
 
     /// Create config for memory policy
+    ///
+    /// Local snapshots go to `snapshots/` next to the WAL, as with the defaults.
     pub fn memory(wal_path: impl AsRef<Path>) -> Self {
+        let wal_path = wal_path.as_ref().to_path_buf();
+        let snapshot_dir = wal_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("snapshots");
         Self {
             tiering_policy: TieringPolicy::Memory,
-            wal_path: wal_path.as_ref().to_path_buf(),
+            wal_path,
+            snapshot_dir,
             ..Default::default()
         }
     }
@@ -2,9 +2,13 @@ use super::{AccessTracker, Tier, TieringPolicyConfig};
 use akidb_core::{CollectionId, CoreError, CoreResult};
 use akidb_metadata::{TierState, TierStateRepository};
 use chrono::{Duration, Utc};
+use parking_lot::RwLock;
 use std::sync::Arc;
 use tokio::task::JoinHandle;
 
+/// Hook called after a collection moves between tiers
+type TierChangeHook = Box<dyn Fn(CollectionId, Tier) + Send + Sync>;
+
 /// Tiering manager for hot/warm/cold tier transitions
 ///
 /// Automatically moves collections between tiers based on access patterns:
@@ -37,8 +41,11 @@ use tokio::task::JoinHandle;
 /// ```
 pub struct TieringManager {
     access_tracker: Arc<AccessTracker>,
-    policy: TieringPolicyConfig,
+    /// Shared with the worker so [`set_policy`](Self::set_policy) reaches it
+    policy: Arc<RwLock<TieringPolicyConfig>>,
     metadata: Arc<TierStateRepository>,
+    /// Shared with the worker so hooks installed after it starts reach it
+    on_tier_change: Arc<RwLock<Option<TierChangeHook>>>,
     worker: Option<JoinHandle<()>>,
 }
 
@@ -56,12 +63,41 @@ impl TieringManager {
 
         Ok(Self {
             access_tracker: Arc::new(AccessTracker::new()),
-            policy,
+            policy: Arc::new(RwLock::new(policy)),
             metadata,
+            on_tier_change: Arc::new(RwLock::new(None)),
             worker: None,
         })
     }
 
+    /// Current tiering policy
+    #[must_use]
+    pub fn policy(&self) -> TieringPolicyConfig {
+        self.policy.read().clone()
+    }
+
+    /// Replace the tiering policy without restarting the worker
+    ///
+    /// Thresholds apply from the next tiering cycle; a new
+    /// `worker_interval_secs` applies after the pending tick.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if policy validation fails; the current policy is kept
+    pub fn set_policy(&self, policy: TieringPolicyConfig) -> CoreResult<()> {
+        policy.validate().map_err(CoreError::invalid_state)?;
+        *self.policy.write() = policy;
+        Ok(())
+    }
+
+    /// Call `hook` after every tier transition, whether made by the
+    /// background worker or requested manually
+    ///
+    /// Replaces any previously installed hook.
+    pub fn on_tier_change(&self, hook: impl Fn(CollectionId, Tier) + Send + Sync + 'static) {
+        *self.on_tier_change.write() = Some(Box::new(hook));
+    }
+
     /// Record collection access
     ///
     /// This should be called on every search/insert operation.
@@ -101,8 +137,7 @@ impl TieringManager {
         // This will be implemented when we integrate with StorageBackend
 
         let warm_path = format!("warm/{}.parquet", collection_id);
-        self.metadata
-            .update_tier_state(collection_id, Tier::Warm, Some(warm_path), None)
+        self.set_tier(collection_id, Tier::Warm, Some(warm_path), None)
             .await
     }
 
@@ -124,9 +159,7 @@ impl TieringManager {
         // TODO: Load from warm tier into RAM
         // This will be implemented when we integrate with StorageBackend
 
-        self.metadata
-            .update_tier_state(collection_id, Tier::Hot, None, None)
-            .await
+        self.set_tier(collection_id, Tier::Hot, None, None).await
     }
 
     /// Demote collection from hot to warm
@@ -153,8 +186,7 @@ impl TieringManager {
         // This requires integration with StorageBackend
 
         let warm_path = format!("warm/{}.parquet", collection_id);
-        self.metadata
-            .update_tier_state(collection_id, Tier::Warm, Some(warm_path), None)
+        self.set_tier(collection_id, Tier::Warm, Some(warm_path), None)
             .await
     }
 
@@ -187,11 +219,27 @@ impl TieringManager {
         // This requires integration with ParquetSnapshotter from Week 1
 
         let snapshot_id = uuid::Uuid::new_v4(); // Placeholder
-        self.metadata
-            .update_tier_state(collection_id, Tier::Cold, None, Some(snapshot_id))
+        self.set_tier(collection_id, Tier::Cold, None, Some(snapshot_id))
             .await
     }
 
+    /// Persist a tier transition and report it to the tier change hook
+    async fn set_tier(
+        &self,
+        collection_id: CollectionId,
+        tier: Tier,
+        warm_file_path: Option<String>,
+        snapshot_id: Option<uuid::Uuid>,
+    ) -> CoreResult<()> {
+        self.metadata
+            .update_tier_state(collection_id, tier, warm_file_path, snapshot_id)
+            .await?;
+        if let Some(hook) = self.on_tier_change.read().as_ref() {
+            hook(collection_id, tier);
+        }
+        Ok(())
+    }
+
     /// Pin collection to hot tier (prevent demotion)
     pub async fn pin_collection(&self, collection_id: CollectionId) -> CoreResult<()> {
         self.metadata.pin_collection(collection_id).await
@@ -247,9 +295,10 @@ impl TieringManager {
         }
 
         let manager = self.clone_for_worker();
-        let interval = self.policy.worker_interval();
+        let interval = self.policy.read().worker_interval();
 
         let handle = tokio::spawn(async move {
+            let mut interval = interval;
             let mut ticker = tokio::time::interval(interval);
 
             loop {
@@ -258,6 +307,14 @@ impl TieringManager {
                 if let Err(e) = manager.run_tiering_cycle().await {
                     tracing::error!(error = %e, "Tiering cycle failed");
                 }
+
+                // Pick up a reloaded worker interval
+                let current = manager.policy.read().worker_interval();
+                if current != interval {
+                    interval = current;
+                    ticker =
+                        tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+                }
             }
         });
 
@@ -272,9 +329,10 @@ impl TieringManager {
     pub async fn run_tiering_cycle(&self) -> CoreResult<()> {
         tracing::info!("Starting tiering cycle");
         let start = std::time::Instant::now();
+        let policy = self.policy();
 
         // Demote hot → warm (no access for hot_tier_ttl_hours)
-        let hot_cutoff = Utc::now() - Duration::hours(self.policy.hot_tier_ttl_hours);
+        let hot_cutoff = Utc::now() - Duration::hours(policy.hot_tier_ttl_hours);
         let hot_candidates = self
             .metadata
             .find_hot_collections_idle_since(hot_cutoff)
@@ -292,7 +350,7 @@ impl TieringManager {
         }
 
         // Demote warm → cold (no access for warm_tier_ttl_days)
-        let warm_cutoff = Utc::now() - Duration::days(self.policy.warm_tier_ttl_days);
+        let warm_cutoff = Utc::now() - Duration::days(policy.warm_tier_ttl_days);
         let warm_candidates = self
             .metadata
             .find_warm_collections_idle_since(warm_cutoff)
@@ -310,12 +368,12 @@ impl TieringManager {
         }
 
         // Promote warm → hot (high access frequency)
-        let access_window_start = Utc::now() - Duration::hours(self.policy.access_window_hours);
+        let access_window_start = Utc::now() - Duration::hours(policy.access_window_hours);
         let warm_hot_candidates = self
             .metadata
             .find_warm_collections_with_high_access(
                 access_window_start,
-                self.policy.hot_promotion_threshold,
+                policy.hot_promotion_threshold,
             )
             .await?;
 
@@ -351,8 +409,9 @@ impl TieringManager {
     fn clone_for_worker(&self) -> Self {
         Self {
             access_tracker: Arc::clone(&self.access_tracker),
-            policy: self.policy.clone(),
+            policy: Arc::clone(&self.policy),
             metadata: Arc::clone(&self.metadata),
+            on_tier_change: Arc::clone(&self.on_tier_change),
             worker: None,
         }
     }
@@ -490,4 +549,53 @@ mod tests {
         assert_eq!(state.tier, Tier::Warm);
         assert!(state.warm_file_path.is_some());
     }
+
+    #[tokio::test]
+    async fn test_tier_change_hook() {
+        let (manager, pool) = setup().await;
+        let collection_id = create_test_collection(&pool).await;
+        manager
+            .metadata
+            .init_tier_state(collection_id)
+            .await
+            .unwrap();
+
+        let changes = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&changes);
+        manager.on_tier_change(move |collection_id, tier| {
+            recorded.lock().push((collection_id, tier));
+        });
+
+        manager.force_demote_to_cold(collection_id).await.unwrap();
+        manager.force_promote_to_hot(collection_id).await.unwrap();
+
+        assert_eq!(
+            *changes.lock(),
+            vec![
+                (collection_id, Tier::Warm),
+                (collection_id, Tier::Cold),
+                (collection_id, Tier::Warm),
+                (collection_id, Tier::Hot),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_policy_validates() {
+        let (manager, _pool) = setup().await;
+
+        let policy = TieringPolicyConfig {
+            hot_tier_ttl_hours: 2,
+            ..TieringPolicyConfig::default()
+        };
+        manager.set_policy(policy.clone()).unwrap();
+        assert_eq!(manager.policy(), policy);
+
+        let invalid = TieringPolicyConfig {
+            worker_interval_secs: 1,
+            ..TieringPolicyConfig::default()
+        };
+        assert!(manager.set_policy(invalid).is_err());
+        assert_eq!(manager.policy(), policy);
+    }
 }
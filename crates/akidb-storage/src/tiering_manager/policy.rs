@@ -14,7 +14,8 @@ use std::time::Duration;
 /// assert_eq!(policy.hot_tier_ttl_hours, 6);
 /// assert_eq!(policy.warm_tier_ttl_days, 7);
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TieringPolicyConfig {
     /// Hours without access before demoting hot → warm (default: 6)
     pub hot_tier_ttl_hours: i64,
@@ -235,6 +235,26 @@ impl FileWAL {
         Ok(())
     }
 
+    /// Last checkpoint LSN (entries before it are covered by a snapshot)
+    #[must_use]
+    pub fn checkpoint_lsn(&self) -> LogSequenceNumber {
+        *self.checkpoint_lsn.read()
+    }
+
+    /// Number of WAL files on disk and their total size in bytes
+    ///
+    /// # Errors
+    /// - `CoreError::IoError` if the WAL directory cannot be read
+    pub async fn disk_usage(&self) -> CoreResult<(usize, u64)> {
+        let wal_files = self.get_wal_files(LogSequenceNumber::ZERO).await?;
+        let bytes = wal_files
+            .iter()
+            .filter_map(|(_, path)| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        Ok((wal_files.len(), bytes))
+    }
+
     /// Get list of all WAL files >= from_lsn
     async fn get_wal_files(
         &self,
@@ -323,12 +343,17 @@ impl WriteAheadLog for FileWAL {
     ) -> CoreResult<Vec<(LogSequenceNumber, LogEntry)>> {
         let mut entries = Vec::new();
 
-        // Get all WAL files >= from_lsn
-        let wal_files = self.get_wal_files(from_lsn).await?;
+        // A file holds entries from its starting LSN up to the next file's, so
+        // the file containing from_lsn may start before it
+        let wal_files = self.get_wal_files(LogSequenceNumber::ZERO).await?;
+        let first = wal_files
+            .iter()
+            .rposition(|(start, _)| *start <= from_lsn)
+            .unwrap_or(0);
 
         // Read entries from each file
-        for (_, path) in wal_files {
-            let file = File::open(&path)?;
+        for (_, path) in &wal_files[first..] {
+            let file = File::open(path)?;
             let reader = BufReader::new(file);
 
             for line in reader.lines() {
@@ -458,6 +483,7 @@ mod tests {
                 collection_id: CollectionId::new(),
                 doc_id: DocumentId::new(),
                 vector: vec![1.0, 2.0, 3.0],
+                sparse: None,
                 external_id: None,
                 metadata: None,
                 timestamp: chrono::Utc::now(),
@@ -480,6 +506,7 @@ mod tests {
                 collection_id: CollectionId::new(),
                 doc_id: DocumentId::new(),
                 vector: vec![i as f32],
+                sparse: None,
                 external_id: Some(format!("doc-{}", i)),
                 metadata: None,
                 timestamp: chrono::Utc::now(),
@@ -497,6 +524,34 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_file_wal_replay_from_middle_of_rotated_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = FileWALConfig {
+            // Rotate after every few entries
+            max_file_size_bytes: 512,
+            ..FileWALConfig::default()
+        };
+        let wal = FileWAL::new(temp_dir.path(), config).await.unwrap();
+
+        for i in 0..20 {
+            let entry = LogEntry::Delete {
+                collection_id: CollectionId::new(),
+                doc_id: DocumentId::new(),
+                timestamp: chrono::Utc::now(),
+            };
+            let lsn = wal.append(entry).await.unwrap();
+            assert_eq!(lsn.value(), i + 1);
+        }
+        assert!(wal.disk_usage().await.unwrap().0 > 2);
+
+        for from in 1..=20 {
+            let entries = wal.replay(LogSequenceNumber::new(from)).await.unwrap();
+            let lsns: Vec<u64> = entries.iter().map(|(lsn, _)| lsn.value()).collect();
+            assert_eq!(lsns, (from..=20).collect::<Vec<_>>());
+        }
+    }
+
     #[tokio::test]
     async fn test_file_wal_crash_recovery() {
         let temp_dir = TempDir::new().unwrap();
@@ -513,6 +568,7 @@ mod tests {
                     collection_id: CollectionId::new(),
                     doc_id: DocumentId::new(),
                     vector: vec![i as f32],
+                    sparse: None,
                     external_id: None,
                     metadata: None,
                     timestamp: chrono::Utc::now(),
@@ -547,6 +603,7 @@ mod tests {
                 collection_id: CollectionId::new(),
                 doc_id: DocumentId::new(),
                 vector: vec![i as f32],
+                sparse: None,
                 external_id: None,
                 metadata: None,
                 timestamp: chrono::Utc::now(),
@@ -8,7 +8,7 @@ mod file_wal;
 
 pub use file_wal::{FileWAL, FileWALConfig};
 
-use akidb_core::{CollectionId, CoreResult, DocumentId};
+use akidb_core::{CollectionId, CoreResult, DocumentId, SparseVector};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -83,6 +83,9 @@ pub enum LogEntry {
         doc_id: DocumentId,
         /// Dense vector embedding
         vector: Vec<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        /// Optional sparse vector
+        sparse: Option<SparseVector>,
         #[serde(skip_serializing_if = "Option::is_none")]
         /// Optional user-provided external ID
         external_id: Option<String>,
@@ -262,6 +265,7 @@ mod tests {
             collection_id: CollectionId::new(),
             doc_id: DocumentId::new(),
             vector: vec![1.0, 2.0, 3.0],
+            sparse: None,
             external_id: None,
             metadata: None,
             timestamp: now,
@@ -21,6 +21,7 @@ fn create_test_vectors(count: usize, dimension: usize) -> Vec<VectorDocument> {
             doc_id: DocumentId::new(),
             external_id: Some(format!("doc-{}", i)),
             vector: vec![i as f32; dimension],
+            sparse: None,
             metadata: Some(serde_json::json!({"index": i, "category": "test"})),
             inserted_at: Utc::now(),
         })
@@ -278,6 +278,53 @@ async fn test_s3only_lru_eviction() {
     assert!(metrics.s3_downloads >= 1, "Should download from S3");
 }
 
+#[tokio::test]
+async fn test_s3only_external_ids_survive_compaction_and_restart() {
+    let temp_dir = TempDir::new().unwrap();
+    let local_s3_dir = temp_dir.path().join("s3");
+    std::fs::create_dir_all(&local_s3_dir).unwrap();
+    std::fs::create_dir_all(temp_dir.path().join("snapshots")).unwrap();
+
+    let config = StorageConfig::s3_only(
+        temp_dir.path().join("wal"),
+        temp_dir.path().join("snapshots"),
+        format!("file://{}", local_s3_dir.display()),
+        1,
+    );
+
+    let backend = StorageBackend::new(config.clone()).await.unwrap();
+    let doc = VectorDocument::new(DocumentId::new(), vec![1.0]).with_external_id("ext-1".into());
+    let doc_id = doc.doc_id;
+    backend.insert(doc).await.unwrap();
+    // Evicts the first document from the one-entry cache
+    backend
+        .insert(VectorDocument::new(DocumentId::new(), vec![2.0]))
+        .await
+        .unwrap();
+
+    let ids = ["ext-1".to_string()];
+    assert_eq!(
+        backend.resolve_external_ids(&ids).get("ext-1"),
+        Some(&doc_id)
+    );
+
+    backend.compact().await.unwrap();
+    backend.shutdown().await.unwrap();
+    drop(backend);
+
+    // Compaction lets the WAL files it covers be deleted
+    std::fs::remove_dir_all(temp_dir.path().join("wal")).unwrap();
+
+    let backend = StorageBackend::new(config).await.unwrap();
+    assert_eq!(
+        backend.resolve_external_ids(&ids).get("ext-1"),
+        Some(&doc_id)
+    );
+
+    backend.delete(&doc_id).await.unwrap();
+    assert!(backend.resolve_external_ids(&ids).is_empty());
+}
+
 // ==================== Day 4 Tests: S3 Retry Logic + DLQ ====================
 
 use akidb_storage::object_store::{ObjectMetadata, ObjectStore};
@@ -23,7 +23,7 @@ TOTAL=8
 echo "========================================================================"
 echo "Scenario 1: Baseline Performance (5 minutes)"
 echo "========================================================================"
-if cargo test --release -p akidb-storage scenario_1_baseline_quick -- --nocapture; then
+if cargo test --release -p akidb-loadtest scenario_1_baseline_quick -- --nocapture; then
     echo "✅ Scenario 1: PASSED"
     PASSED=$((PASSED + 1))
 else
@@ -35,7 +35,7 @@ echo ""
 echo "========================================================================"
 echo "Scenario 2: Sustained High Load (10 minutes)"
 echo "========================================================================"
-if cargo test --release -p akidb-storage scenario_2_sustained_load_quick -- --nocapture; then
+if cargo test --release -p akidb-loadtest scenario_2_sustained_load_quick -- --nocapture; then
     echo "✅ Scenario 2: PASSED"
     PASSED=$((PASSED + 1))
 else
@@ -47,7 +47,7 @@ echo ""
 echo "========================================================================"
 echo "Scenario 3: Spike Load (5 minutes)"
 echo "========================================================================"
-if cargo test --release -p akidb-storage scenario_3_spike_load_quick -- --nocapture; then
+if cargo test --release -p akidb-loadtest scenario_3_spike_load_quick -- --nocapture; then
     echo "✅ Scenario 3: PASSED"
     PASSED=$((PASSED + 1))
 else
@@ -59,7 +59,7 @@ echo ""
 echo "========================================================================"
 echo "Scenario 4: Tiered Storage (10 minutes)"
 echo "========================================================================"
-if cargo test --release -p akidb-storage scenario_4_tiered_storage_quick -- --nocapture; then
+if cargo test --release -p akidb-loadtest scenario_4_tiered_storage_quick -- --nocapture; then
     echo "✅ Scenario 4: PASSED"
     PASSED=$((PASSED + 1))
 else
@@ -71,7 +71,7 @@ echo ""
 echo "========================================================================"
 echo "Scenario 5: Multi-Tenant Load (10 minutes)"
 echo "========================================================================"
-if cargo test --release -p akidb-storage scenario_5_multi_tenant_quick -- --nocapture; then
+if cargo test --release -p akidb-loadtest scenario_5_multi_tenant_quick -- --nocapture; then
     echo "✅ Scenario 5: PASSED"
     PASSED=$((PASSED + 1))
 else
@@ -83,7 +83,7 @@ echo ""
 echo "========================================================================"
 echo "Scenario 6: Large Dataset (15 minutes)"
 echo "========================================================================"
-if cargo test --release -p akidb-storage scenario_6_large_dataset_quick -- --nocapture; then
+if cargo test --release -p akidb-loadtest scenario_6_large_dataset_quick -- --nocapture; then
     echo "✅ Scenario 6: PASSED"
     PASSED=$((PASSED + 1))
 else
@@ -95,7 +95,7 @@ echo ""
 echo "========================================================================"
 echo "Scenario 7: Failure Injection (5 minutes)"
 echo "========================================================================"
-if cargo test --release -p akidb-storage scenario_7_failure_injection_quick -- --nocapture; then
+if cargo test --release -p akidb-loadtest scenario_7_failure_injection_quick -- --nocapture; then
     echo "✅ Scenario 7: PASSED"
     PASSED=$((PASSED + 1))
 else
@@ -107,7 +107,7 @@ echo ""
 echo "========================================================================"
 echo "Scenario 8: Mixed Workload Chaos (10 minutes)"
 echo "========================================================================"
-if cargo test --release -p akidb-storage scenario_8_mixed_chaos_quick -- --nocapture; then
+if cargo test --release -p akidb-loadtest scenario_8_mixed_chaos_quick -- --nocapture; then
     echo "✅ Scenario 8: PASSED"
     PASSED=$((PASSED + 1))
 else